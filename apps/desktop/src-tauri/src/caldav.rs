@@ -0,0 +1,1361 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use keyring::{Entry as KeyringEntry, Error as KeyringError};
+use reqwest::blocking::Client as HttpClient;
+use reqwest::{Method, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CALDAV_CACHE_FILE: &str = "caldav-cache.json";
+const CALDAV_KEYRING_SERVICE: &str = "com.johnjeong.philo.caldav";
+const CALDAV_KEYRING_SERVICE_DEV: &str = "com.johnjeong.philo.dev.caldav";
+const CALDAV_KEYRING_ACCOUNT: &str = "passwords";
+const CALDAV_MULTIGET_BATCH_SIZE: usize = 50;
+const DAV_NS: &str = "DAV:";
+const CALDAV_NS: &str = "urn:ietf:params:xml:ns:caldav";
+const CALENDARSERVER_NS: &str = "http://calendarserver.org/ns/";
+const APPLE_ICAL_NS: &str = "http://apple.com/ns/ical/";
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectCaldavAccountInput {
+    pub server_url: String,
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncCaldavEventsInput {
+    pub account_id: String,
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub calendar_hrefs: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaldavCalendar {
+    pub href: String,
+    pub display_name: String,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub ctag: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaldavAccount {
+    pub id: String,
+    pub server_url: String,
+    pub username: String,
+    pub calendars: Vec<CaldavCalendar>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaldavEventParticipant {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+/// Mirrors the shape of `db-user`'s `Event`: `tracking_id` is the stable key a
+/// calendar platform gives an occurrence (the iCalendar UID, suffixed with the
+/// RECURRENCE-ID for expanded instances).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaldavEvent {
+    pub id: String,
+    pub tracking_id: String,
+    pub calendar_id: String,
+    pub uid: String,
+    pub name: String,
+    pub note: String,
+    pub location: Option<String>,
+    pub start_date: String,
+    pub end_date: String,
+    pub all_day: bool,
+    pub event_url: Option<String>,
+    pub participants: Vec<CaldavEventParticipant>,
+    pub is_recurring: bool,
+    pub cancelled: bool,
+    pub etag: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaldavSyncResult {
+    pub events: Vec<CaldavEvent>,
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+#[derive(Clone, Debug)]
+struct CaldavCredentials {
+    server_url: String,
+    username: String,
+    password: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CaldavCache {
+    #[serde(default)]
+    accounts: BTreeMap<String, CaldavAccountCache>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CaldavAccountCache {
+    server_url: String,
+    username: String,
+    #[serde(default)]
+    calendars: BTreeMap<String, CaldavCalendarCache>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CaldavCalendarCache {
+    calendar: CaldavCalendar,
+    #[serde(default)]
+    synced_range: Option<(String, String)>,
+    #[serde(default)]
+    synced_ctag: Option<String>,
+    #[serde(default)]
+    objects: BTreeMap<String, CachedCalendarObject>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedCalendarObject {
+    etag: String,
+    events: Vec<CaldavEvent>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct SyncCounts {
+    added: usize,
+    updated: usize,
+    removed: usize,
+    unchanged: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+struct DavProp {
+    namespace: String,
+    name: String,
+    text: String,
+    hrefs: Vec<String>,
+    children: Vec<(String, String)>,
+    components: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct DavResponse {
+    href: String,
+    props: Vec<DavProp>,
+}
+
+impl DavResponse {
+    fn prop(&self, namespace: &str, name: &str) -> Option<&DavProp> {
+        self.props
+            .iter()
+            .find(|prop| prop.namespace == namespace && prop.name == name)
+    }
+
+    fn prop_text(&self, namespace: &str, name: &str) -> Option<String> {
+        self.prop(namespace, name)
+            .map(|prop| prop.text.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+}
+
+pub fn connect_caldav_account(
+    app_data_dir: &Path,
+    input: ConnectCaldavAccountInput,
+) -> Result<CaldavAccount, String> {
+    let credentials = CaldavCredentials {
+        server_url: normalize_server_url(&input.server_url)?,
+        username: input.username.trim().to_string(),
+        password: input.password,
+    };
+    if credentials.username.is_empty() {
+        return Err("Enter the CalDAV username.".to_string());
+    }
+    if credentials.password.is_empty() {
+        return Err("Enter the CalDAV password or app password.".to_string());
+    }
+
+    let client = caldav_http_client()?;
+    let calendars = discover_calendars(&client, &credentials)?;
+    let account_id = build_account_id(&credentials.server_url, &credentials.username)?;
+
+    let mut passwords = load_caldav_passwords()?;
+    passwords.insert(account_id.clone(), credentials.password.clone());
+    store_caldav_passwords(&passwords)?;
+
+    let mut cache = load_cache(app_data_dir)?;
+    let account_cache = cache.accounts.entry(account_id.clone()).or_default();
+    account_cache.server_url = credentials.server_url.clone();
+    account_cache.username = credentials.username.clone();
+    merge_discovered_calendars(account_cache, &calendars);
+    save_cache(app_data_dir, &cache)?;
+
+    Ok(CaldavAccount {
+        id: account_id,
+        server_url: credentials.server_url,
+        username: credentials.username,
+        calendars,
+    })
+}
+
+pub fn list_caldav_accounts(app_data_dir: &Path) -> Result<Vec<CaldavAccount>, String> {
+    let cache = load_cache(app_data_dir)?;
+    Ok(cache
+        .accounts
+        .iter()
+        .map(|(id, account)| CaldavAccount {
+            id: id.clone(),
+            server_url: account.server_url.clone(),
+            username: account.username.clone(),
+            calendars: account
+                .calendars
+                .values()
+                .map(|calendar| calendar.calendar.clone())
+                .collect(),
+        })
+        .collect())
+}
+
+pub fn disconnect_caldav_account(app_data_dir: &Path, account_id: &str) -> Result<(), String> {
+    let mut cache = load_cache(app_data_dir)?;
+    cache.accounts.remove(account_id);
+    save_cache(app_data_dir, &cache)?;
+
+    let mut passwords = load_caldav_passwords()?;
+    if passwords.remove(account_id).is_some() {
+        if passwords.is_empty() {
+            return clear_caldav_passwords();
+        }
+        return store_caldav_passwords(&passwords);
+    }
+    Ok(())
+}
+
+pub fn sync_caldav_events(
+    app_data_dir: &Path,
+    input: SyncCaldavEventsInput,
+) -> Result<CaldavSyncResult, String> {
+    let mut cache = load_cache(app_data_dir)?;
+    let account = cache
+        .accounts
+        .get_mut(&input.account_id)
+        .ok_or("Connect the CalDAV account before syncing.".to_string())?;
+    let password = load_caldav_passwords()?
+        .remove(&input.account_id)
+        .ok_or("Reconnect the CalDAV account to sync events.".to_string())?;
+    let credentials = CaldavCredentials {
+        server_url: account.server_url.clone(),
+        username: account.username.clone(),
+        password,
+    };
+    let range = parse_sync_range(&input.from, &input.to)?;
+
+    let client = caldav_http_client()?;
+    let result = sync_account(
+        &client,
+        &credentials,
+        account,
+        &range,
+        &input.calendar_hrefs,
+    )?;
+    save_cache(app_data_dir, &cache)?;
+    Ok(result)
+}
+
+fn sync_account(
+    client: &HttpClient,
+    credentials: &CaldavCredentials,
+    account: &mut CaldavAccountCache,
+    range: &(String, String),
+    calendar_hrefs: &[String],
+) -> Result<CaldavSyncResult, String> {
+    let calendars = discover_calendars(client, credentials)?;
+    merge_discovered_calendars(account, &calendars);
+
+    let selected = calendar_hrefs
+        .iter()
+        .map(|href| href.trim().to_string())
+        .filter(|href| !href.is_empty())
+        .collect::<HashSet<_>>();
+    let mut result = CaldavSyncResult::default();
+    for (href, calendar_cache) in account.calendars.iter_mut() {
+        if !selected.is_empty() && !selected.contains(href) {
+            continue;
+        }
+
+        let counts = sync_calendar(client, credentials, calendar_cache, range)?;
+        result.added += counts.added;
+        result.updated += counts.updated;
+        result.removed += counts.removed;
+        result.unchanged += counts.unchanged;
+        result.events.extend(
+            calendar_cache
+                .objects
+                .values()
+                .flat_map(|object| object.events.iter().cloned()),
+        );
+    }
+
+    result.events.sort_by(|left, right| {
+        left.start_date
+            .cmp(&right.start_date)
+            .then_with(|| left.tracking_id.cmp(&right.tracking_id))
+    });
+    Ok(result)
+}
+
+fn merge_discovered_calendars(account: &mut CaldavAccountCache, calendars: &[CaldavCalendar]) {
+    let discovered = calendars
+        .iter()
+        .map(|calendar| calendar.href.clone())
+        .collect::<HashSet<_>>();
+    account
+        .calendars
+        .retain(|href, _| discovered.contains(href));
+    for calendar in calendars {
+        account
+            .calendars
+            .entry(calendar.href.clone())
+            .or_default()
+            .calendar = calendar.clone();
+    }
+}
+
+fn sync_calendar(
+    client: &HttpClient,
+    credentials: &CaldavCredentials,
+    cache: &mut CaldavCalendarCache,
+    range: &(String, String),
+) -> Result<SyncCounts, String> {
+    let same_range = cache.synced_range.as_ref() == Some(range);
+    if same_range && cache.calendar.ctag.is_some() && cache.synced_ctag == cache.calendar.ctag {
+        return Ok(SyncCounts {
+            unchanged: cache.objects.len(),
+            ..SyncCounts::default()
+        });
+    }
+
+    let calendar_url = resolve_href(&credentials.server_url, &cache.calendar.href)?;
+    let remote_etags = fetch_calendar_etags(client, credentials, &calendar_url, range)?;
+    let mut counts = SyncCounts::default();
+
+    let remote_hrefs = remote_etags.keys().cloned().collect::<HashSet<_>>();
+    let stale = cache
+        .objects
+        .keys()
+        .filter(|href| !remote_hrefs.contains(*href))
+        .cloned()
+        .collect::<Vec<_>>();
+    for href in stale {
+        cache.objects.remove(&href);
+        counts.removed += 1;
+    }
+
+    let changed = remote_etags
+        .iter()
+        .filter(|(href, etag)| {
+            // Expanded occurrences depend on the requested window, so a new
+            // window refetches everything even when the ETag did not move.
+            !same_range
+                || cache
+                    .objects
+                    .get(*href)
+                    .map(|object| &object.etag != *etag)
+                    .unwrap_or(true)
+        })
+        .map(|(href, _)| href.clone())
+        .collect::<Vec<_>>();
+    counts.unchanged = remote_etags.len() - changed.len();
+
+    for batch in changed.chunks(CALDAV_MULTIGET_BATCH_SIZE) {
+        for (href, etag, ics) in
+            fetch_calendar_objects(client, credentials, &calendar_url, batch, range)?
+        {
+            let etag = if etag.is_empty() {
+                remote_etags.get(&href).cloned().unwrap_or_default()
+            } else {
+                etag
+            };
+            let events = parse_ical_events(&ics, &cache.calendar.href, &etag);
+            let previous = cache
+                .objects
+                .insert(href, CachedCalendarObject { etag, events });
+            if previous.is_some() {
+                counts.updated += 1;
+            } else {
+                counts.added += 1;
+            }
+        }
+    }
+
+    cache.synced_range = Some(range.clone());
+    cache.synced_ctag = cache.calendar.ctag.clone();
+    Ok(counts)
+}
+
+fn caldav_http_client() -> Result<HttpClient, String> {
+    HttpClient::builder()
+        .build()
+        .map_err(|e| format!("Could not start CalDAV client: {e}"))
+}
+
+fn normalize_server_url(raw: &str) -> Result<String, String> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Err("Enter the CalDAV server URL.".to_string());
+    }
+
+    let with_scheme = if trimmed.contains("://") {
+        trimmed.to_string()
+    } else {
+        format!("https://{trimmed}")
+    };
+    let url = Url::parse(&with_scheme).map_err(|_| "Invalid CalDAV server URL.".to_string())?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err("CalDAV server URL must use http or https.".to_string());
+    }
+    Ok(url.to_string())
+}
+
+fn build_account_id(server_url: &str, username: &str) -> Result<String, String> {
+    let url = Url::parse(server_url).map_err(|_| "Invalid CalDAV server URL.".to_string())?;
+    let host = url
+        .host_str()
+        .ok_or("CalDAV server URL has no host.".to_string())?;
+    let host = match url.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    };
+    Ok(format!(
+        "{}@{}",
+        username.trim().to_lowercase(),
+        host.to_lowercase()
+    ))
+}
+
+fn resolve_href(base: &str, href: &str) -> Result<Url, String> {
+    let base = Url::parse(base).map_err(|_| "Invalid CalDAV server URL.".to_string())?;
+    base.join(href.trim())
+        .map_err(|_| format!("Invalid CalDAV href: {href}"))
+}
+
+fn parse_sync_range(from: &str, to: &str) -> Result<(String, String), String> {
+    let from_date = NaiveDate::parse_from_str(from.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date: {}", from))?;
+    let to_date = NaiveDate::parse_from_str(to.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date: {}", to))?;
+    if from_date > to_date {
+        return Err("--from must be on or before --to.".to_string());
+    }
+
+    let format = |date: NaiveDate| date.format("%Y%m%dT000000Z").to_string();
+    Ok((format(from_date), format(to_date + Duration::days(1))))
+}
+
+fn dav_request(
+    client: &HttpClient,
+    credentials: &CaldavCredentials,
+    method: &str,
+    url: &Url,
+    depth: &str,
+    body: String,
+) -> Result<String, String> {
+    let method = Method::from_bytes(method.as_bytes()).map_err(|e| e.to_string())?;
+    let response = client
+        .request(method.clone(), url.clone())
+        .basic_auth(&credentials.username, Some(&credentials.password))
+        .header("Content-Type", "application/xml; charset=utf-8")
+        .header("Depth", depth)
+        .body(body)
+        .send()
+        .map_err(|e| format!("CalDAV {method} request failed: {e}"))?;
+
+    match response.status() {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            Err("CalDAV server rejected the username or password.".to_string())
+        }
+        status if status.is_success() => response
+            .text()
+            .map_err(|e| format!("Could not read CalDAV response: {e}")),
+        status => Err(format!(
+            "CalDAV {method} {} failed with {status}.",
+            url.path()
+        )),
+    }
+}
+
+fn propfind(
+    client: &HttpClient,
+    credentials: &CaldavCredentials,
+    url: &Url,
+    depth: &str,
+    props: &str,
+) -> Result<Vec<DavResponse>, String> {
+    let body = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:" xmlns:c="{CALDAV_NS}" xmlns:cs="{CALENDARSERVER_NS}" xmlns:ic="{APPLE_ICAL_NS}">
+  <d:prop>{props}</d:prop>
+</d:propfind>"#
+    );
+    let xml = dav_request(client, credentials, "PROPFIND", url, depth, body)?;
+    parse_multistatus(&xml)
+}
+
+fn find_principal_href(
+    client: &HttpClient,
+    credentials: &CaldavCredentials,
+    url: &Url,
+) -> Result<Option<String>, String> {
+    let responses = propfind(client, credentials, url, "0", "<d:current-user-principal/>")?;
+    Ok(responses
+        .iter()
+        .filter_map(|response| response.prop(DAV_NS, "current-user-principal"))
+        .find_map(|prop| prop.hrefs.first().cloned()))
+}
+
+fn discover_calendars(
+    client: &HttpClient,
+    credentials: &CaldavCredentials,
+) -> Result<Vec<CaldavCalendar>, String> {
+    let base_url = resolve_href(&credentials.server_url, "")?;
+    let principal_href = match find_principal_href(client, credentials, &base_url) {
+        Ok(Some(href)) => href,
+        Ok(None) | Err(_) => {
+            let well_known = resolve_href(&credentials.server_url, "/.well-known/caldav")?;
+            find_principal_href(client, credentials, &well_known)?
+                .ok_or("CalDAV server did not report a principal for this user.".to_string())?
+        }
+    };
+    let principal_url = resolve_href(&credentials.server_url, &principal_href)?;
+
+    let home_href = propfind(
+        client,
+        credentials,
+        &principal_url,
+        "0",
+        "<c:calendar-home-set/>",
+    )?
+    .iter()
+    .filter_map(|response| response.prop(CALDAV_NS, "calendar-home-set"))
+    .find_map(|prop| prop.hrefs.first().cloned())
+    .unwrap_or(principal_href);
+    let home_url = resolve_href(&credentials.server_url, &home_href)?;
+
+    let responses = propfind(
+        client,
+        credentials,
+        &home_url,
+        "1",
+        "<d:resourcetype/><d:displayname/><cs:getctag/><ic:calendar-color/><c:supported-calendar-component-set/>",
+    )?;
+
+    let mut calendars = Vec::new();
+    for response in responses {
+        let is_calendar = response
+            .prop(DAV_NS, "resourcetype")
+            .map(|prop| {
+                prop.children
+                    .iter()
+                    .any(|(namespace, name)| namespace == CALDAV_NS && name == "calendar")
+            })
+            .unwrap_or(false);
+        if !is_calendar {
+            continue;
+        }
+
+        let supports_events = response
+            .prop(CALDAV_NS, "supported-calendar-component-set")
+            .map(|prop| {
+                prop.components.is_empty()
+                    || prop
+                        .components
+                        .iter()
+                        .any(|component| component.eq_ignore_ascii_case("VEVENT"))
+            })
+            .unwrap_or(true);
+        if !supports_events {
+            continue;
+        }
+
+        let display_name = response
+            .prop_text(DAV_NS, "displayname")
+            .unwrap_or_else(|| {
+                response
+                    .href
+                    .trim_end_matches('/')
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or("Calendar")
+                    .to_string()
+            });
+        calendars.push(CaldavCalendar {
+            href: response.href.clone(),
+            display_name,
+            color: response.prop_text(APPLE_ICAL_NS, "calendar-color"),
+            ctag: response.prop_text(CALENDARSERVER_NS, "getctag"),
+        });
+    }
+
+    if calendars.is_empty() {
+        return Err("No CalDAV calendars with events were found for this account.".to_string());
+    }
+    Ok(calendars)
+}
+
+fn fetch_calendar_etags(
+    client: &HttpClient,
+    credentials: &CaldavCredentials,
+    calendar_url: &Url,
+    range: &(String, String),
+) -> Result<HashMap<String, String>, String> {
+    let (start, end) = range;
+    let body = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="{CALDAV_NS}">
+  <d:prop><d:getetag/></d:prop>
+  <c:filter>
+    <c:comp-filter name="VCALENDAR">
+      <c:comp-filter name="VEVENT">
+        <c:time-range start="{start}" end="{end}"/>
+      </c:comp-filter>
+    </c:comp-filter>
+  </c:filter>
+</c:calendar-query>"#
+    );
+    let xml = dav_request(client, credentials, "REPORT", calendar_url, "1", body)?;
+    Ok(parse_multistatus(&xml)?
+        .into_iter()
+        .filter(|response| {
+            response.href.trim_end_matches('/') != calendar_url.path().trim_end_matches('/')
+        })
+        .map(|response| {
+            let etag = response.prop_text(DAV_NS, "getetag").unwrap_or_default();
+            (response.href, etag)
+        })
+        .collect())
+}
+
+fn fetch_calendar_objects(
+    client: &HttpClient,
+    credentials: &CaldavCredentials,
+    calendar_url: &Url,
+    hrefs: &[String],
+    range: &(String, String),
+) -> Result<Vec<(String, String, String)>, String> {
+    if hrefs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let (start, end) = range;
+    let href_elements = hrefs
+        .iter()
+        .map(|href| format!("<d:href>{}</d:href>", escape_xml(href)))
+        .collect::<Vec<_>>()
+        .join("\n  ");
+    let body = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-multiget xmlns:d="DAV:" xmlns:c="{CALDAV_NS}">
+  <d:prop>
+    <d:getetag/>
+    <c:calendar-data><c:expand start="{start}" end="{end}"/></c:calendar-data>
+  </d:prop>
+  {href_elements}
+</c:calendar-multiget>"#
+    );
+    let xml = dav_request(client, credentials, "REPORT", calendar_url, "1", body)?;
+    Ok(parse_multistatus(&xml)?
+        .into_iter()
+        .filter_map(|response| {
+            let ics = response.prop_text(CALDAV_NS, "calendar-data")?;
+            let etag = response.prop_text(DAV_NS, "getetag").unwrap_or_default();
+            Some((response.href, etag, ics))
+        })
+        .collect())
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn parse_multistatus(xml: &str) -> Result<Vec<DavResponse>, String> {
+    let document = roxmltree::Document::parse(xml)
+        .map_err(|e| format!("Could not parse CalDAV response: {e}"))?;
+    let is_dav = |node: &roxmltree::Node<'_, '_>, name: &str| {
+        node.is_element()
+            && node.tag_name().namespace() == Some(DAV_NS)
+            && node.tag_name().name() == name
+    };
+
+    let mut responses = Vec::new();
+    for response in document
+        .root_element()
+        .children()
+        .filter(|node| is_dav(node, "response"))
+    {
+        let Some(href) = response
+            .children()
+            .find(|node| is_dav(node, "href"))
+            .and_then(|node| node.text())
+            .map(|value| value.trim().to_string())
+        else {
+            continue;
+        };
+
+        let mut props = Vec::new();
+        for propstat in response.children().filter(|node| is_dav(node, "propstat")) {
+            let ok = propstat
+                .children()
+                .find(|node| is_dav(node, "status"))
+                .and_then(|node| node.text())
+                .map(|status| status.contains(" 200"))
+                .unwrap_or(true);
+            if !ok {
+                continue;
+            }
+
+            for prop in propstat
+                .children()
+                .filter(|node| is_dav(node, "prop"))
+                .flat_map(|node| node.children())
+                .filter(|node| node.is_element())
+            {
+                props.push(DavProp {
+                    namespace: prop.tag_name().namespace().unwrap_or_default().to_string(),
+                    name: prop.tag_name().name().to_string(),
+                    text: prop
+                        .descendants()
+                        .filter(|node| node.is_text())
+                        .filter_map(|node| node.text())
+                        .collect::<String>(),
+                    hrefs: prop
+                        .descendants()
+                        .filter(|node| is_dav(node, "href"))
+                        .filter_map(|node| node.text())
+                        .map(|value| value.trim().to_string())
+                        .filter(|value| !value.is_empty())
+                        .collect(),
+                    children: prop
+                        .children()
+                        .filter(|node| node.is_element())
+                        .map(|node| {
+                            (
+                                node.tag_name().namespace().unwrap_or_default().to_string(),
+                                node.tag_name().name().to_string(),
+                            )
+                        })
+                        .collect(),
+                    components: prop
+                        .descendants()
+                        .filter(|node| node.is_element() && node.tag_name().name() == "comp")
+                        .filter_map(|node| node.attribute("name"))
+                        .map(ToOwned::to_owned)
+                        .collect(),
+                });
+            }
+        }
+
+        responses.push(DavResponse { href, props });
+    }
+
+    Ok(responses)
+}
+
+struct IcalProperty {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl IcalProperty {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn unfold_ical_lines(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in ics.split('\n') {
+        let line = raw.trim_end_matches('\r');
+        if let Some(continuation) = line.strip_prefix([' ', '\t']) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(continuation);
+                continue;
+            }
+        }
+        if !line.is_empty() {
+            lines.push(line.to_string());
+        }
+    }
+    lines
+}
+
+fn parse_ical_property(line: &str) -> Option<IcalProperty> {
+    let mut in_quotes = false;
+    let split_at = line.char_indices().find_map(|(index, ch)| match ch {
+        '"' => {
+            in_quotes = !in_quotes;
+            None
+        }
+        ':' if !in_quotes => Some(index),
+        _ => None,
+    })?;
+
+    let (head, value) = (&line[..split_at], &line[split_at + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    let params = parts
+        .filter_map(|part| {
+            let (key, value) = part.split_once('=')?;
+            Some((
+                key.trim().to_ascii_uppercase(),
+                value.trim().trim_matches('"').to_string(),
+            ))
+        })
+        .collect();
+
+    Some(IcalProperty {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+fn unescape_ical_text(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// Returns the instant as RFC 3339 plus whether it was a date-only value.
+/// Floating times (no `Z`, no `TZID`) are treated as UTC.
+fn parse_ical_datetime(property: &IcalProperty) -> Option<(DateTime<Utc>, bool)> {
+    let value = property.value.trim();
+    let date_only = property
+        .param("VALUE")
+        .map(|kind| kind.eq_ignore_ascii_case("DATE"))
+        .unwrap_or(false)
+        || (value.len() == 8 && value.chars().all(|ch| ch.is_ascii_digit()));
+    if date_only {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some((Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?), true));
+    }
+
+    if let Some(utc_value) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc_value, "%Y%m%dT%H%M%S").ok()?;
+        return Some((Utc.from_utc_datetime(&naive), false));
+    }
+
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    if let Some(tz) = property
+        .param("TZID")
+        .and_then(|tzid| tzid.trim_start_matches('/').parse::<chrono_tz::Tz>().ok())
+    {
+        let local = tz.from_local_datetime(&naive).earliest()?;
+        return Some((local.with_timezone(&Utc), false));
+    }
+
+    Some((Utc.from_utc_datetime(&naive), false))
+}
+
+fn parse_ical_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.trim_start_matches('+')),
+    };
+    let value = value.strip_prefix('P')?;
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    for ch in value.chars() {
+        match ch {
+            '0'..='9' => number.push(ch),
+            'T' => {}
+            unit => {
+                let amount = number.parse::<i64>().ok()?;
+                number.clear();
+                total += match unit {
+                    'W' => Duration::weeks(amount),
+                    'D' => Duration::days(amount),
+                    'H' => Duration::hours(amount),
+                    'M' => Duration::minutes(amount),
+                    'S' => Duration::seconds(amount),
+                    _ => return None,
+                };
+            }
+        }
+    }
+
+    Some(if negative { -total } else { total })
+}
+
+fn parse_ical_participant(property: &IcalProperty) -> CaldavEventParticipant {
+    let value = property.value.trim();
+    let email = value
+        .get(..7)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("mailto:"))
+        .map(|_| value[7..].trim().to_string())
+        .filter(|email| !email.is_empty());
+    CaldavEventParticipant {
+        name: property
+            .param("CN")
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty()),
+        email,
+    }
+}
+
+fn parse_ical_events(ics: &str, calendar_id: &str, etag: &str) -> Vec<CaldavEvent> {
+    let mut events = Vec::new();
+    let mut current: Option<Vec<IcalProperty>> = None;
+    let mut nested_depth = 0usize;
+
+    for line in unfold_ical_lines(ics) {
+        let Some(property) = parse_ical_property(&line) else {
+            continue;
+        };
+        match (property.name.as_str(), property.value.trim()) {
+            ("BEGIN", "VEVENT") if current.is_none() => {
+                current = Some(Vec::new());
+                nested_depth = 0;
+            }
+            ("BEGIN", _) if current.is_some() => nested_depth += 1,
+            ("END", "VEVENT") if nested_depth == 0 => {
+                if let Some(event) = current
+                    .take()
+                    .and_then(|properties| build_event(&properties, calendar_id, etag))
+                {
+                    events.push(event);
+                }
+            }
+            ("END", _) if current.is_some() => nested_depth = nested_depth.saturating_sub(1),
+            _ => {
+                if nested_depth == 0 {
+                    if let Some(properties) = current.as_mut() {
+                        properties.push(property);
+                    }
+                }
+            }
+        }
+    }
+
+    events
+}
+
+fn build_event(properties: &[IcalProperty], calendar_id: &str, etag: &str) -> Option<CaldavEvent> {
+    let find = |name: &str| properties.iter().find(|property| property.name == name);
+    let text = |name: &str| {
+        find(name)
+            .map(|property| unescape_ical_text(property.value.trim()))
+            .filter(|value| !value.trim().is_empty())
+    };
+
+    let uid = text("UID")?;
+    let (start, all_day) = parse_ical_datetime(find("DTSTART")?)?;
+    let end = find("DTEND")
+        .and_then(parse_ical_datetime)
+        .map(|(end, _)| end)
+        .or_else(|| {
+            find("DURATION")
+                .and_then(|property| parse_ical_duration(&property.value))
+                .map(|duration| start + duration)
+        })
+        .unwrap_or_else(|| {
+            if all_day {
+                start + Duration::days(1)
+            } else {
+                start
+            }
+        });
+
+    let recurrence_id = find("RECURRENCE-ID").map(|property| property.value.trim().to_string());
+    let tracking_id = match &recurrence_id {
+        Some(recurrence_id) => format!("{uid}::{recurrence_id}"),
+        None => uid.clone(),
+    };
+    let participants = properties
+        .iter()
+        .filter(|property| property.name == "ATTENDEE" || property.name == "ORGANIZER")
+        .map(parse_ical_participant)
+        .filter(|participant| participant.name.is_some() || participant.email.is_some())
+        .fold(
+            Vec::<CaldavEventParticipant>::new(),
+            |mut acc, participant| {
+                if !acc.contains(&participant) {
+                    acc.push(participant);
+                }
+                acc
+            },
+        );
+
+    Some(CaldavEvent {
+        id: format!("{calendar_id}#{tracking_id}"),
+        tracking_id,
+        calendar_id: calendar_id.to_string(),
+        uid,
+        name: text("SUMMARY").unwrap_or_default(),
+        note: text("DESCRIPTION").unwrap_or_default(),
+        location: text("LOCATION"),
+        start_date: start.to_rfc3339(),
+        end_date: end.to_rfc3339(),
+        all_day,
+        event_url: text("URL"),
+        participants,
+        is_recurring: recurrence_id.is_some() || find("RRULE").is_some(),
+        cancelled: text("STATUS")
+            .map(|status| status.eq_ignore_ascii_case("CANCELLED"))
+            .unwrap_or(false),
+        etag: etag.to_string(),
+    })
+}
+
+fn cache_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(CALDAV_CACHE_FILE)
+}
+
+fn load_cache(app_data_dir: &Path) -> Result<CaldavCache, String> {
+    match fs::read_to_string(cache_path(app_data_dir)) {
+        Ok(raw) => Ok(serde_json::from_str(&raw).unwrap_or_default()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(CaldavCache::default()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+fn save_cache(app_data_dir: &Path, cache: &CaldavCache) -> Result<(), String> {
+    fs::create_dir_all(app_data_dir).map_err(|e| e.to_string())?;
+    let payload = serde_json::to_string_pretty(cache).map_err(|e| e.to_string())?;
+    fs::write(cache_path(app_data_dir), payload).map_err(|e| e.to_string())
+}
+
+fn caldav_keyring_entry() -> Result<KeyringEntry, String> {
+    let service = if cfg!(debug_assertions) {
+        CALDAV_KEYRING_SERVICE_DEV
+    } else {
+        CALDAV_KEYRING_SERVICE
+    };
+    KeyringEntry::new(service, CALDAV_KEYRING_ACCOUNT)
+        .map_err(|e| format!("Could not access secure CalDAV storage: {e}"))
+}
+
+fn load_caldav_passwords() -> Result<HashMap<String, String>, String> {
+    match caldav_keyring_entry()?.get_password() {
+        Ok(raw) if raw.trim().is_empty() => Ok(HashMap::new()),
+        Ok(raw) => serde_json::from_str(&raw)
+            .map_err(|_| "Could not read secure CalDAV storage.".to_string()),
+        Err(KeyringError::NoEntry) => Ok(HashMap::new()),
+        Err(e) => Err(format!("Could not access secure CalDAV storage: {e}")),
+    }
+}
+
+fn store_caldav_passwords(passwords: &HashMap<String, String>) -> Result<(), String> {
+    let payload = serde_json::to_string(passwords)
+        .map_err(|e| format!("Could not serialize CalDAV credentials: {e}"))?;
+    caldav_keyring_entry()?
+        .set_password(&payload)
+        .map_err(|e| format!("Could not store CalDAV credentials securely: {e}"))
+}
+
+fn clear_caldav_passwords() -> Result<(), String> {
+    match caldav_keyring_entry()?.delete_credential() {
+        Ok(()) | Err(KeyringError::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Could not clear secure CalDAV storage: {e}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    type StubRoute = (&'static str, &'static str, String);
+
+    /// Minimal stand-in CalDAV server: answers each request by method + path
+    /// with a canned 207 multistatus body and records what it was asked.
+    fn spawn_caldav_stub(routes: Vec<StubRoute>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+                let mut raw = Vec::new();
+                let mut buffer = [0_u8; 4096];
+                loop {
+                    let size = stream.read(&mut buffer).unwrap_or(0);
+                    raw.extend_from_slice(&buffer[..size]);
+                    let text = String::from_utf8_lossy(&raw).to_string();
+                    if let Some(header_end) = text.find("\r\n\r\n") {
+                        let content_length = text[..header_end]
+                            .lines()
+                            .find_map(|line| {
+                                let (name, value) = line.split_once(':')?;
+                                name.eq_ignore_ascii_case("content-length")
+                                    .then(|| value.trim().parse::<usize>().ok())?
+                            })
+                            .unwrap_or(0);
+                        if raw.len() >= header_end + 4 + content_length || size == 0 {
+                            break;
+                        }
+                    } else if size == 0 {
+                        break;
+                    }
+                }
+
+                let request = String::from_utf8_lossy(&raw).to_string();
+                let mut request_line = request.lines().next().unwrap_or("").split_whitespace();
+                let method = request_line.next().unwrap_or("").to_string();
+                let path = request_line.next().unwrap_or("").to_string();
+                recorded.lock().unwrap().push(request.clone());
+
+                let body = routes
+                    .iter()
+                    .find(|(route_method, route_path, _)| {
+                        *route_method == method && *route_path == path
+                    })
+                    .map(|(_, _, body)| body.clone());
+                let response = match body {
+                    Some(body) => format!(
+                        "HTTP/1.1 207 Multi-Status\r\nContent-Type: application/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    ),
+                    None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                        .to_string(),
+                };
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        (base_url, requests)
+    }
+
+    fn multistatus(responses: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav" xmlns:cs="http://calendarserver.org/ns/">{responses}</d:multistatus>"#
+        )
+    }
+
+    fn discovery_routes(ctag: &str) -> Vec<StubRoute> {
+        vec![
+            (
+                "PROPFIND",
+                "/",
+                multistatus(
+                    r#"<d:response><d:href>/</d:href><d:propstat><d:prop>
+                    <d:current-user-principal><d:href>/principals/alex/</d:href></d:current-user-principal>
+                    </d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>"#,
+                ),
+            ),
+            (
+                "PROPFIND",
+                "/principals/alex/",
+                multistatus(
+                    r#"<d:response><d:href>/principals/alex/</d:href><d:propstat><d:prop>
+                    <c:calendar-home-set><d:href>/calendars/alex/</d:href></c:calendar-home-set>
+                    </d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>"#,
+                ),
+            ),
+            (
+                "PROPFIND",
+                "/calendars/alex/",
+                multistatus(&format!(
+                    r#"<d:response><d:href>/calendars/alex/</d:href><d:propstat><d:prop>
+                    <d:resourcetype><d:collection/></d:resourcetype>
+                    </d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+                    <d:response><d:href>/calendars/alex/work/</d:href><d:propstat><d:prop>
+                    <d:resourcetype><d:collection/><c:calendar/></d:resourcetype>
+                    <d:displayname>Work</d:displayname>
+                    <cs:getctag>{ctag}</cs:getctag>
+                    <c:supported-calendar-component-set><c:comp name="VEVENT"/></c:supported-calendar-component-set>
+                    </d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+                    <d:response><d:href>/calendars/alex/tasks/</d:href><d:propstat><d:prop>
+                    <d:resourcetype><d:collection/><c:calendar/></d:resourcetype>
+                    <d:displayname>Tasks</d:displayname>
+                    <c:supported-calendar-component-set><c:comp name="VTODO"/></c:supported-calendar-component-set>
+                    </d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>"#
+                )),
+            ),
+        ]
+    }
+
+    fn credentials(base_url: &str) -> CaldavCredentials {
+        CaldavCredentials {
+            server_url: base_url.to_string(),
+            username: "alex".to_string(),
+            password: "app-password".to_string(),
+        }
+    }
+
+    const STANDUP_ICS: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:standup-1\r\nSUMMARY:Daily standup\r\nDESCRIPTION:Bring blockers\\, questions\\nand notes\r\nDTSTART;TZID=Europe/Berlin:20261019T093000\r\nDTEND;TZID=Europe/Berlin:20261019T094500\r\nLOCATION:Room 4\r\nATTENDEE;CN=\"Sam Lee\";PARTSTAT=ACCEPTED:mailto:sam@example.com\r\nORGANIZER;CN=Alex:mailto:alex@example.com\r\nBEGIN:VALARM\r\nTRIGGER:-PT10M\r\nDESCRIPTION:Reminder\r\nEND:VALARM\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+
+    #[test]
+    fn parses_vevent_with_timezone_attendees_and_nested_alarm() {
+        let events = parse_ical_events(STANDUP_ICS, "/calendars/alex/work/", "\"1\"");
+
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.tracking_id, "standup-1");
+        assert_eq!(event.name, "Daily standup");
+        assert_eq!(event.note, "Bring blockers, questions\nand notes");
+        assert_eq!(event.start_date, "2026-10-19T07:30:00+00:00");
+        assert_eq!(event.end_date, "2026-10-19T07:45:00+00:00");
+        assert_eq!(event.location.as_deref(), Some("Room 4"));
+        assert_eq!(event.participants.len(), 2);
+        assert_eq!(event.participants[0].name.as_deref(), Some("Sam Lee"));
+        assert_eq!(
+            event.participants[0].email.as_deref(),
+            Some("sam@example.com")
+        );
+        assert!(!event.is_recurring);
+    }
+
+    #[test]
+    fn parses_all_day_and_expanded_recurring_instances() {
+        let ics = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nUID:offsite\nSUMMARY:Team\n offsite\nDTSTART;VALUE=DATE:20261020\nEND:VEVENT\nBEGIN:VEVENT\nUID:weekly\nSUMMARY:1:1\nRECURRENCE-ID:20261021T150000Z\nDTSTART:20261021T150000Z\nDURATION:PT30M\nEND:VEVENT\nEND:VCALENDAR\n";
+        let events = parse_ical_events(ics, "cal", "");
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].name, "Teamoffsite");
+        assert!(events[0].all_day);
+        assert_eq!(events[0].end_date, "2026-10-21T00:00:00+00:00");
+        assert_eq!(events[1].tracking_id, "weekly::20261021T150000Z");
+        assert_eq!(events[1].end_date, "2026-10-21T15:30:00+00:00");
+        assert!(events[1].is_recurring);
+    }
+
+    #[test]
+    fn discovers_event_calendars_through_principal_and_home_set() {
+        let (base_url, _) = spawn_caldav_stub(discovery_routes("ctag-1"));
+        let client = caldav_http_client().unwrap();
+
+        let calendars = discover_calendars(&client, &credentials(&base_url)).unwrap();
+
+        assert_eq!(
+            calendars,
+            vec![CaldavCalendar {
+                href: "/calendars/alex/work/".to_string(),
+                display_name: "Work".to_string(),
+                color: None,
+                ctag: Some("ctag-1".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn syncs_incrementally_by_etag() {
+        let mut routes = discovery_routes("ctag-2");
+        routes.push((
+            "REPORT",
+            "/calendars/alex/work/",
+            multistatus(&format!(
+                r#"<d:response><d:href>/calendars/alex/work/standup.ics</d:href><d:propstat><d:prop>
+                <d:getetag>"etag-standup"</d:getetag>
+                <c:calendar-data>{}</c:calendar-data>
+                </d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>"#,
+                STANDUP_ICS
+            )),
+        ));
+        let (base_url, requests) = spawn_caldav_stub(routes);
+        let client = caldav_http_client().unwrap();
+        let credentials = credentials(&base_url);
+        let range = parse_sync_range("2026-10-18", "2026-10-24").unwrap();
+        let mut account = CaldavAccountCache {
+            server_url: base_url.clone(),
+            username: "alex".to_string(),
+            ..CaldavAccountCache::default()
+        };
+        account.calendars.insert(
+            "/calendars/alex/work/".to_string(),
+            CaldavCalendarCache {
+                synced_range: Some(range.clone()),
+                objects: BTreeMap::from([(
+                    "/calendars/alex/work/removed.ics".to_string(),
+                    CachedCalendarObject {
+                        etag: "\"old\"".to_string(),
+                        events: Vec::new(),
+                    },
+                )]),
+                ..CaldavCalendarCache::default()
+            },
+        );
+
+        let first = sync_account(&client, &credentials, &mut account, &range, &[]).unwrap();
+        assert_eq!((first.added, first.removed, first.unchanged), (1, 1, 0));
+        assert_eq!(first.events.len(), 1);
+        assert_eq!(first.events[0].etag, "\"etag-standup\"");
+
+        account
+            .calendars
+            .get_mut("/calendars/alex/work/")
+            .unwrap()
+            .synced_ctag = None;
+        let report_count = |requests: &Arc<Mutex<Vec<String>>>| {
+            requests
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|request| request.starts_with("REPORT"))
+                .count()
+        };
+        let reports_before = report_count(&requests);
+        let second = sync_account(&client, &credentials, &mut account, &range, &[]).unwrap();
+        assert_eq!((second.added, second.updated, second.unchanged), (0, 0, 1));
+        assert_eq!(report_count(&requests), reports_before + 1);
+
+        let third = sync_account(&client, &credentials, &mut account, &range, &[]).unwrap();
+        assert_eq!(third.unchanged, 1);
+        assert_eq!(report_count(&requests), reports_before + 1);
+    }
+
+    #[test]
+    fn builds_stable_account_ids() {
+        assert_eq!(
+            build_account_id(
+                &normalize_server_url("caldav.fastmail.com/dav/").unwrap(),
+                " Alex@Example.com "
+            )
+            .unwrap(),
+            "alex@example.com@caldav.fastmail.com"
+        );
+    }
+}
@@ -2,26 +2,38 @@
 #[macro_use]
 extern crate objc;
 
+pub mod caldav;
 #[cfg(target_os = "macos")]
 mod macos_location;
+pub mod note_templates;
+pub mod oauth;
+pub mod obsidian;
+pub mod page_schema;
+pub mod periodic_notes;
 pub mod philo_tools;
 pub mod settings_paths;
+pub mod site_export;
+pub mod vault_git;
 pub mod widget_git;
 
 use crate::settings_paths::normalize_folder;
-use keyring::{Entry as KeyringEntry, Error as KeyringError};
-use reqwest::blocking::{Client as HttpClient, Response as HttpResponse};
+use caldav::{CaldavAccount, CaldavSyncResult, ConnectCaldavAccountInput, SyncCaldavEventsInput};
+use oauth::{
+    DisconnectOAuthAccountInput, EnsureOAuthAccessTokenInput, FinishOAuthAuthorizationInput,
+    OAuthAccessTokenResult, OAuthAccountManager, OAuthAuthorizationSession, OAuthConnectionResult,
+    OAuthProviderInput, OAuthState, StartOAuthAuthorizationInput,
+};
+use obsidian::ObsidianCompatibilityReport;
 use reqwest::{Client as AsyncHttpClient, Method};
 use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::{HashMap, HashSet};
-use std::io::{ErrorKind, Read, Write};
-use std::net::TcpListener;
+use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{mpsc, Arc, Mutex};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, UNIX_EPOCH};
 use std::{env, fs, process::Command};
 use tauri::ipc::Channel;
 use tauri::menu::{MenuBuilder, MenuItemBuilder, PredefinedMenuItem, SubmenuBuilder};
@@ -29,9 +41,16 @@ use tauri::{AppHandle, Emitter, Manager, State};
 use tauri_plugin_deep_link::DeepLinkExt;
 use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
 use tauri_plugin_fs::FsExt;
+use tauri_plugin_notify::{FileChanged, NotifyPluginExt, OwnFileChanged};
 use tauri_plugin_permissions::{Permission, PermissionStatus, PermissionsPluginExt};
 #[cfg(desktop)]
 use tauri_plugin_updater::UpdaterExt;
+use tauri_specta::Event as _;
+use vault_git::{
+    RestoreVaultGitRevisionInput, SetVaultGitEnabledInput, VaultGitDiff, VaultGitDiffInput,
+    VaultGitHistoryEntry, VaultGitHistoryInput, VaultGitInput, VaultGitRestoreResult,
+    VaultGitState, VaultGitStatus,
+};
 use widget_git::{
     EnsureWidgetGitBaselineInput, RecordWidgetGitRevisionInput, RestoreWidgetGitRevisionInput,
     WidgetGitDiff, WidgetGitDiffInput, WidgetGitHistoryEntry, WidgetGitHistoryInput,
@@ -59,123 +78,14 @@ struct NSRect {
     size: NSSize,
 }
 
-const GOOGLE_OAUTH_DEFAULT_TIMEOUT_MS: u64 = 180_000;
-const GOOGLE_OAUTH_ACCESS_TOKEN_BUFFER_MS: u64 = 60_000;
-const GOOGLE_OAUTH_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
-const GOOGLE_OAUTH_USERINFO_URL: &str = "https://openidconnect.googleapis.com/v1/userinfo";
-const GOOGLE_OAUTH_KEYRING_SERVICE: &str = "com.johnjeong.philo.google-oauth";
-const GOOGLE_OAUTH_KEYRING_SERVICE_DEV: &str = "com.johnjeong.philo.dev.google-oauth";
-const GOOGLE_OAUTH_KEYRING_ACCOUNT: &str = "session";
 const MICROPHONE_PERMISSION_SETTLE_ATTEMPTS: usize = 4;
 const MICROPHONE_PERMISSION_SETTLE_DELAY_MS: u64 = 150;
 
-#[derive(Default)]
-struct GoogleOAuthState {
-    sessions: Mutex<HashMap<String, Arc<GoogleOAuthPendingSession>>>,
-}
-
-struct GoogleOAuthPendingSession {
-    cancelled: AtomicBool,
-    receiver: Mutex<mpsc::Receiver<GoogleOAuthCallbackPayload>>,
-}
-
 #[derive(Default)]
 struct HttpStreamState {
     requests: Mutex<HashMap<String, Arc<AtomicBool>>>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct GoogleOAuthSession {
-    session_id: String,
-    redirect_uri: String,
-}
-
-#[derive(Clone, Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct GoogleOAuthCallbackPayload {
-    code: Option<String>,
-    state: Option<String>,
-    error: Option<String>,
-}
-
-#[derive(Clone, Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct GoogleOAuthLegacySessionInput {
-    #[serde(default)]
-    access_token: String,
-    access_token_expires_at_ms: u64,
-    refresh_token: String,
-}
-
-#[derive(Clone, Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct GoogleCompleteOAuthInput {
-    client_id: String,
-    code: String,
-    code_verifier: String,
-    #[serde(default)]
-    expected_account_email: Option<String>,
-    #[serde(default)]
-    legacy_account_email: Option<String>,
-    #[serde(default)]
-    legacy_session: Option<GoogleOAuthLegacySessionInput>,
-    redirect_uri: String,
-}
-
-#[derive(Clone, Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct GoogleEnsureAccessTokenInput {
-    client_id: String,
-    account_email: String,
-    #[serde(default)]
-    legacy_account_email: Option<String>,
-    #[serde(default)]
-    granted_scopes: Vec<String>,
-    #[serde(default)]
-    legacy_session: Option<GoogleOAuthLegacySessionInput>,
-}
-
-type GoogleStoredSessions = HashMap<String, GoogleStoredSession>;
-
-#[derive(Clone, Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct GoogleStoredSession {
-    access_token: String,
-    access_token_expires_at_ms: u64,
-    refresh_token: String,
-}
-
-#[derive(Clone, Debug, Deserialize)]
-struct GoogleTokenResponse {
-    access_token: Option<String>,
-    expires_in: Option<u64>,
-    refresh_token: Option<String>,
-    scope: Option<String>,
-}
-
-#[derive(Clone, Debug, Deserialize)]
-struct GoogleUserInfoResponse {
-    email: Option<String>,
-}
-
-#[derive(Clone, Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-struct GoogleOAuthConnectionResult {
-    access_token_expires_at_ms: u64,
-    account_email: String,
-    granted_scopes: Vec<String>,
-}
-
-#[derive(Clone, Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-struct GoogleAccessTokenResult {
-    account_email: String,
-    access_token: String,
-    access_token_expires_at_ms: u64,
-    granted_scopes: Vec<String>,
-}
-
 #[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct HttpJsonResponse {
@@ -345,7 +255,12 @@ fn read_markdown_file(path: String) -> Result<Option<String>, String> {
 }
 
 #[tauri::command]
-fn write_markdown_file(path: String, content: String) -> Result<(), String> {
+fn write_markdown_file(
+    app: AppHandle,
+    state: State<'_, VaultGitState>,
+    path: String,
+    content: String,
+) -> Result<(), String> {
     let normalized_path = path.trim();
     if normalized_path.is_empty() {
         return Ok(());
@@ -356,85 +271,36 @@ fn write_markdown_file(path: String, content: String) -> Result<(), String> {
         fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
 
+    mark_vault_own_writes(&app, &state, std::slice::from_ref(&target));
     fs::write(target, content).map_err(|e| e.to_string())
 }
 
-fn decode_url_component(value: &str) -> String {
-    let bytes = value.as_bytes();
-    let mut decoded = Vec::with_capacity(bytes.len());
-    let mut index = 0;
-
-    while index < bytes.len() {
-        match bytes[index] {
-            b'+' => {
-                decoded.push(b' ');
-                index += 1;
-            }
-            b'%' if index + 2 < bytes.len() => {
-                let hi = bytes[index + 1] as char;
-                let lo = bytes[index + 2] as char;
-                if let (Some(hi), Some(lo)) = (hi.to_digit(16), lo.to_digit(16)) {
-                    decoded.push(((hi * 16) + lo) as u8);
-                    index += 3;
-                } else {
-                    decoded.push(bytes[index]);
-                    index += 1;
-                }
-            }
-            value => {
-                decoded.push(value);
-                index += 1;
-            }
-        }
-    }
-
-    String::from_utf8_lossy(&decoded).to_string()
-}
-
-fn parse_google_oauth_callback(path: &str) -> GoogleOAuthCallbackPayload {
-    let query = path
-        .split_once('?')
-        .map(|(_, query)| query)
-        .unwrap_or_default();
-    let mut params = HashMap::new();
-
-    for pair in query.split('&') {
-        if pair.is_empty() {
-            continue;
-        }
-        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
-        params.insert(decode_url_component(key), decode_url_component(value));
-    }
-
-    let error = params.get("error").cloned();
-    let code = params.get("code").cloned();
-    let state = params.get("state").cloned();
-
-    GoogleOAuthCallbackPayload {
-        code,
-        state,
-        error: error.or_else(|| {
-            if params.contains_key("code") {
-                None
-            } else {
-                Some("Google did not return an authorization code.".to_string())
-            }
-        }),
+/// Tells the watcher these writes came from Philo, so vault history attributes them to the app.
+/// Paths outside the armed vault are ignored.
+fn mark_vault_own_writes(app: &AppHandle, state: &VaultGitState, targets: &[PathBuf]) {
+    let Some(vault_root) = state.vault_root() else {
+        return;
+    };
+    let relative = targets
+        .iter()
+        .filter_map(|target| {
+            let resolved = match (target.parent(), target.file_name()) {
+                (Some(parent), Some(name)) => fs::canonicalize(parent)
+                    .map(|parent| parent.join(name))
+                    .unwrap_or_else(|_| target.to_path_buf()),
+                _ => target.to_path_buf(),
+            };
+            resolved
+                .strip_prefix(&vault_root)
+                .ok()
+                .map(|relative| relative.to_string_lossy().to_string())
+        })
+        .collect::<Vec<_>>();
+    if relative.is_empty() {
+        return;
     }
-}
-
-fn write_google_oauth_response(
-    stream: &mut std::net::TcpStream,
-    status: &str,
-    body: &str,
-) -> Result<(), String> {
-    let response = format!(
-        "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
-        body.len()
-    );
-    stream
-        .write_all(response.as_bytes())
-        .map_err(|e| e.to_string())
+    app.notify().mark_own_writes(&relative);
+    state.claim_own_writes(&relative);
 }
 
 fn focus_main_window<R: tauri::Runtime>(app: &AppHandle<R>, _source: &'static str) {
@@ -445,579 +311,6 @@ fn focus_main_window<R: tauri::Runtime>(app: &AppHandle<R>, _source: &'static st
     }
 }
 
-fn google_oauth_callback_page(success: bool) -> String {
-    let offer_deep_link = success && !cfg!(debug_assertions);
-    let eyebrow = if success {
-        "Google connected"
-    } else {
-        "Google sign-in failed"
-    };
-    let title = if success {
-        "Account connected."
-    } else {
-        "Connection didn't go through."
-    };
-    let description = if success {
-        "Your Google account is now connected to Philo."
-    } else {
-        "Philo could not finish connecting your Google account."
-    };
-    let detail = if offer_deep_link {
-        "Philo should reopen automatically. If it stays in the background, open it below."
-    } else if success {
-        "Philo should come forward automatically. If it stays in the background, switch back to the app."
-    } else {
-        "Close this window and try again from Philo."
-    };
-    let panel_class = if success {
-        "panel panel-success"
-    } else {
-        "panel panel-error"
-    };
-    let action_markup = if offer_deep_link {
-        r#"<div class="actions">
-          <a class="open-button" href="philo://google-connected">Open Philo</a>
-        </div>"#
-            .to_string()
-    } else {
-        String::new()
-    };
-    let auto_open_script = if offer_deep_link {
-        r#"<script>
-      window.setTimeout(() => {
-        window.location.href = "philo://google-connected";
-      }, 300);
-    </script>"#
-            .to_string()
-    } else {
-        String::new()
-    };
-
-    format!(
-        r#"<!DOCTYPE html>
-<html lang="en">
-  <head>
-    <meta charset="utf-8" />
-    <meta name="viewport" content="width=device-width, initial-scale=1" />
-    <title>{title} | Philo</title>
-    <link rel="preconnect" href="https://fonts.googleapis.com" />
-    <link rel="preconnect" href="https://fonts.gstatic.com" crossorigin />
-    <link
-      href="https://fonts.googleapis.com/css2?family=IBM+Plex+Mono:wght@400;500&display=swap"
-      rel="stylesheet"
-    />
-    <style>
-      :root {{
-        color-scheme: light;
-        --bg: #f4f4f1;
-        --bg-soft: rgba(255, 255, 255, 0.72);
-        --line: #e2e2db;
-        --text: #252525;
-        --muted: #646460;
-        --accent: #5f70d8;
-        --success: #2f7a58;
-        --success-bg: rgba(47, 122, 88, 0.08);
-        --error: #a14e42;
-        --error-bg: rgba(161, 78, 66, 0.08);
-      }}
-
-      * {{
-        box-sizing: border-box;
-      }}
-
-      body {{
-        margin: 0;
-        min-height: 100vh;
-        font-family: "IBM Plex Mono", "SFMono-Regular", Menlo, monospace;
-        background: linear-gradient(150deg, #f6f6f4 0%, #efefeb 100%);
-        color: var(--text);
-        position: relative;
-        overflow: hidden;
-      }}
-
-      .ambient {{
-        position: fixed;
-        inset: 0;
-        pointer-events: none;
-        background:
-          radial-gradient(circle at 10% 12%, rgba(255, 255, 255, 0.95) 0%, transparent 32%),
-          radial-gradient(circle at 92% 88%, rgba(95, 112, 216, 0.16) 0%, transparent 34%);
-        opacity: 0.95;
-      }}
-
-      .shell {{
-        position: relative;
-        min-height: 100vh;
-        display: grid;
-        place-items: center;
-        padding: 32px 20px;
-      }}
-
-      .frame {{
-        width: min(100%, 560px);
-      }}
-
-      .brand {{
-        margin: 0 0 14px;
-        font-size: 12px;
-        font-weight: 500;
-        letter-spacing: 0.08em;
-        text-transform: uppercase;
-        color: #3f3f3d;
-      }}
-
-      .panel {{
-        border: 1px solid var(--line);
-        border-radius: 24px;
-        padding: 28px;
-        background: var(--bg-soft);
-        backdrop-filter: blur(18px);
-        box-shadow: 0 20px 60px rgba(32, 32, 29, 0.08);
-      }}
-
-      .panel-success {{
-        box-shadow:
-          0 20px 60px rgba(32, 32, 29, 0.08),
-          inset 0 0 0 1px rgba(47, 122, 88, 0.08);
-      }}
-
-      .panel-error {{
-        box-shadow:
-          0 20px 60px rgba(32, 32, 29, 0.08),
-          inset 0 0 0 1px rgba(161, 78, 66, 0.08);
-      }}
-
-      .eyebrow {{
-        display: inline-flex;
-        align-items: center;
-        gap: 8px;
-        margin: 0;
-        padding: 7px 10px;
-        border-radius: 999px;
-        font-size: 12px;
-        font-weight: 500;
-        letter-spacing: 0.04em;
-        text-transform: uppercase;
-      }}
-
-      .panel-success .eyebrow {{
-        color: var(--success);
-        background: var(--success-bg);
-      }}
-
-      .panel-error .eyebrow {{
-        color: var(--error);
-        background: var(--error-bg);
-      }}
-
-      h1 {{
-        margin: 18px 0 10px;
-        font-size: clamp(28px, 4vw, 40px);
-        line-height: 1.1;
-        font-weight: 500;
-        letter-spacing: -0.04em;
-      }}
-
-      .description {{
-        margin: 0;
-        font-size: 14px;
-        line-height: 1.7;
-        color: #3f3f3d;
-      }}
-
-      .detail {{
-        margin: 20px 0 0;
-        padding-top: 16px;
-        border-top: 1px solid var(--line);
-        font-size: 12px;
-        line-height: 1.7;
-        color: var(--muted);
-      }}
-
-      .actions {{
-        display: flex;
-        margin-top: 22px;
-      }}
-
-      .open-button {{
-        display: inline-flex;
-        align-items: center;
-        justify-content: center;
-        min-height: 44px;
-        padding: 0 18px;
-        border-radius: 999px;
-        border: 1px solid var(--line);
-        background: #ffffff;
-        color: var(--text);
-        font-size: 14px;
-        font-weight: 500;
-        text-decoration: none;
-        transition: transform 160ms ease, border-color 160ms ease, box-shadow 160ms ease;
-      }}
-
-      .open-button:hover {{
-        border-color: rgba(95, 112, 216, 0.35);
-        box-shadow: 0 16px 28px rgba(95, 112, 216, 0.14);
-        transform: translateY(-1px);
-      }}
-
-      .open-button:active {{
-        transform: translateY(0);
-        box-shadow: none;
-      }}
-
-      @media (max-width: 640px) {{
-        .shell {{
-          padding: 18px;
-        }}
-
-        .panel {{
-          padding: 22px;
-          border-radius: 20px;
-        }}
-      }}
-    </style>
-  </head>
-  <body>
-    <div class="ambient"></div>
-    <main class="shell">
-      <div class="frame">
-        <p class="brand">Philo</p>
-        <section class="{panel_class}">
-          <p class="eyebrow">{eyebrow}</p>
-          <h1>{title}</h1>
-          <p class="description">{description}</p>
-          <p class="detail">{detail}</p>
-          {action_markup}
-        </section>
-      </div>
-    </main>
-    {auto_open_script}
-  </body>
-</html>"#
-    )
-}
-
-fn receive_google_oauth_callback(listener: TcpListener) -> GoogleOAuthCallbackPayload {
-    let Ok((mut stream, _addr)) = listener.accept() else {
-        return GoogleOAuthCallbackPayload {
-            code: None,
-            state: None,
-            error: Some("Could not receive Google authorization callback.".to_string()),
-        };
-    };
-
-    let mut buffer = [0_u8; 8192];
-    let request_size = match stream.read(&mut buffer) {
-        Ok(size) if size > 0 => size,
-        _ => {
-            let _ = write_google_oauth_response(
-                &mut stream,
-                "400 Bad Request",
-                &google_oauth_callback_page(false),
-            );
-            return GoogleOAuthCallbackPayload {
-                code: None,
-                state: None,
-                error: Some("Google callback request was empty.".to_string()),
-            };
-        }
-    };
-
-    let request = String::from_utf8_lossy(&buffer[..request_size]);
-    let path = request
-        .lines()
-        .next()
-        .and_then(|line| line.split_whitespace().nth(1))
-        .unwrap_or("/");
-
-    let payload = parse_google_oauth_callback(path);
-    let body = if payload.error.is_some() {
-        google_oauth_callback_page(false)
-    } else {
-        google_oauth_callback_page(true)
-    };
-    let status = if payload.error.is_some() {
-        "400 Bad Request"
-    } else {
-        "200 OK"
-    };
-
-    let _ = write_google_oauth_response(&mut stream, status, &body);
-    payload
-}
-
-#[tauri::command]
-fn start_google_oauth_callback(
-    app: AppHandle,
-    state: State<GoogleOAuthState>,
-) -> Result<GoogleOAuthSession, String> {
-    let listener = TcpListener::bind("127.0.0.1:0").map_err(|e| e.to_string())?;
-    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
-    let session_id = format!(
-        "google-oauth-{}-{}",
-        std::process::id(),
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(|e| e.to_string())?
-            .as_nanos()
-    );
-
-    let (sender, receiver) = mpsc::channel();
-    state
-        .sessions
-        .lock()
-        .map_err(|_| "Google OAuth state is unavailable.".to_string())?
-        .insert(
-            session_id.clone(),
-            Arc::new(GoogleOAuthPendingSession {
-                cancelled: AtomicBool::new(false),
-                receiver: Mutex::new(receiver),
-            }),
-        );
-
-    std::thread::spawn(move || {
-        let payload = receive_google_oauth_callback(listener);
-        let _ = sender.send(payload);
-        focus_main_window(&app, "google_oauth");
-    });
-
-    Ok(GoogleOAuthSession {
-        session_id,
-        redirect_uri: format!("http://127.0.0.1:{port}/"),
-    })
-}
-
-#[tauri::command]
-fn wait_for_google_oauth_callback(
-    session_id: String,
-    timeout_ms: Option<u64>,
-    state: State<GoogleOAuthState>,
-) -> Result<GoogleOAuthCallbackPayload, String> {
-    let session = state
-        .sessions
-        .lock()
-        .map_err(|_| "Google OAuth state is unavailable.".to_string())?
-        .get(&session_id)
-        .cloned()
-        .ok_or("Google OAuth session not found.".to_string())?;
-
-    let started_at = SystemTime::now();
-    let timeout = Duration::from_millis(timeout_ms.unwrap_or(GOOGLE_OAUTH_DEFAULT_TIMEOUT_MS));
-
-    loop {
-        if session.cancelled.load(Ordering::Relaxed) {
-            let _ = state
-                .sessions
-                .lock()
-                .map_err(|_| "Google OAuth state is unavailable.".to_string())?
-                .remove(&session_id);
-            return Err("Google authorization cancelled.".to_string());
-        }
-
-        if started_at.elapsed().map_err(|e| e.to_string())? >= timeout {
-            let _ = state
-                .sessions
-                .lock()
-                .map_err(|_| "Google OAuth state is unavailable.".to_string())?
-                .remove(&session_id);
-            return Err("Timed out waiting for Google authorization.".to_string());
-        }
-
-        let callback = {
-            let receiver = session
-                .receiver
-                .lock()
-                .map_err(|_| "Google OAuth state is unavailable.".to_string())?;
-            receiver.recv_timeout(Duration::from_millis(250))
-        };
-
-        match callback {
-            Ok(payload) => {
-                let _ = state
-                    .sessions
-                    .lock()
-                    .map_err(|_| "Google OAuth state is unavailable.".to_string())?
-                    .remove(&session_id);
-                return Ok(payload);
-            }
-            Err(mpsc::RecvTimeoutError::Timeout) => continue,
-            Err(mpsc::RecvTimeoutError::Disconnected) => {
-                let _ = state
-                    .sessions
-                    .lock()
-                    .map_err(|_| "Google OAuth state is unavailable.".to_string())?
-                    .remove(&session_id);
-                return Err(
-                    "Google OAuth session ended before authorization completed.".to_string()
-                );
-            }
-        }
-    }
-}
-
-#[tauri::command]
-fn cancel_google_oauth_callback(
-    session_id: String,
-    state: State<GoogleOAuthState>,
-) -> Result<(), String> {
-    let session = state
-        .sessions
-        .lock()
-        .map_err(|_| "Google OAuth state is unavailable.".to_string())?
-        .remove(&session_id);
-
-    if let Some(session) = session {
-        session.cancelled.store(true, Ordering::Relaxed);
-    }
-
-    Ok(())
-}
-
-fn google_oauth_client_secret() -> Option<String> {
-    env::var("GOOGLE_OAUTH_CLIENT_SECRET")
-        .ok()
-        .or_else(|| option_env!("GOOGLE_OAUTH_CLIENT_SECRET").map(ToOwned::to_owned))
-        .map(|value| value.trim().to_string())
-        .filter(|value| !value.is_empty())
-}
-
-fn google_oauth_keyring_service() -> &'static str {
-    if cfg!(debug_assertions) {
-        GOOGLE_OAUTH_KEYRING_SERVICE_DEV
-    } else {
-        GOOGLE_OAUTH_KEYRING_SERVICE
-    }
-}
-
-fn google_oauth_keyring_entry() -> Result<KeyringEntry, String> {
-    KeyringEntry::new(google_oauth_keyring_service(), GOOGLE_OAUTH_KEYRING_ACCOUNT)
-        .map_err(|e| format!("Could not access secure Google session storage: {e}"))
-}
-
-fn normalize_google_account_email(email: &str) -> Option<String> {
-    let normalized = email.trim().to_lowercase();
-    if normalized.is_empty() {
-        None
-    } else {
-        Some(normalized)
-    }
-}
-
-fn build_google_stored_session(
-    legacy_session: Option<GoogleOAuthLegacySessionInput>,
-) -> Option<GoogleStoredSession> {
-    let legacy = legacy_session?;
-    let refresh_token = legacy.refresh_token.trim().to_string();
-    if refresh_token.is_empty() {
-        return None;
-    }
-
-    Some(GoogleStoredSession {
-        access_token: legacy.access_token.trim().to_string(),
-        access_token_expires_at_ms: legacy.access_token_expires_at_ms,
-        refresh_token,
-    })
-}
-
-fn load_google_stored_sessions(
-) -> Result<(GoogleStoredSessions, Option<GoogleStoredSession>), String> {
-    let entry = google_oauth_keyring_entry()?;
-    match entry.get_password() {
-        Ok(raw) => {
-            if raw.trim().is_empty() {
-                return Ok((HashMap::new(), None));
-            }
-
-            if let Ok(sessions) = serde_json::from_str::<GoogleStoredSessions>(&raw) {
-                return Ok((sessions, None));
-            }
-
-            if let Ok(session) = serde_json::from_str::<GoogleStoredSession>(&raw) {
-                return Ok((HashMap::new(), Some(session)));
-            }
-
-            Err("Could not read secure Google session storage.".to_string())
-        }
-        Err(KeyringError::NoEntry) => Ok((HashMap::new(), None)),
-        Err(e) => Err(format!(
-            "Could not access secure Google session storage: {e}"
-        )),
-    }
-}
-
-fn store_google_stored_sessions(sessions: &GoogleStoredSessions) -> Result<(), String> {
-    let entry = google_oauth_keyring_entry()?;
-    let payload = serde_json::to_string(sessions)
-        .map_err(|e| format!("Could not serialize Google session: {e}"))?;
-    entry
-        .set_password(&payload)
-        .map_err(|e| format!("Could not store Google session securely: {e}"))
-}
-
-fn clear_google_stored_session() -> Result<(), String> {
-    let entry = google_oauth_keyring_entry()?;
-    match entry.delete_credential() {
-        Ok(()) | Err(KeyringError::NoEntry) => Ok(()),
-        Err(e) => Err(format!(
-            "Could not clear secure Google session storage: {e}"
-        )),
-    }
-}
-
-fn migrate_legacy_google_stored_session(
-    sessions: &mut GoogleStoredSessions,
-    legacy_keyring_session: Option<GoogleStoredSession>,
-    legacy_account_email: Option<&str>,
-    legacy_session: Option<GoogleOAuthLegacySessionInput>,
-) -> bool {
-    let Some(legacy_account_email) = legacy_account_email.and_then(normalize_google_account_email)
-    else {
-        return false;
-    };
-    if sessions.contains_key(&legacy_account_email) {
-        return false;
-    }
-
-    let legacy_session =
-        legacy_keyring_session.or_else(|| build_google_stored_session(legacy_session));
-    let Some(legacy_session) = legacy_session else {
-        return false;
-    };
-    sessions.insert(legacy_account_email, legacy_session);
-    true
-}
-
-fn resolve_google_stored_session(
-    sessions: &GoogleStoredSessions,
-    account_email: &str,
-    legacy_session: Option<GoogleOAuthLegacySessionInput>,
-    legacy_account_email: Option<&str>,
-) -> Result<Option<GoogleStoredSession>, String> {
-    let Some(normalized_account_email) = normalize_google_account_email(account_email) else {
-        return Ok(None);
-    };
-
-    if let Some(stored) = sessions.get(&normalized_account_email) {
-        return Ok(Some(stored.clone()));
-    }
-
-    let legacy_account_email = legacy_account_email
-        .and_then(normalize_google_account_email)
-        .unwrap_or_default();
-    if legacy_account_email == normalized_account_email {
-        return Ok(build_google_stored_session(legacy_session));
-    }
-
-    Ok(None)
-}
-
-fn google_http_client() -> Result<HttpClient, String> {
-    HttpClient::builder()
-        .build()
-        .map_err(|e| format!("Could not start Google OAuth client: {e}"))
-}
-
 fn async_http_client() -> Result<AsyncHttpClient, String> {
     AsyncHttpClient::builder()
         .build()
@@ -1170,293 +463,77 @@ fn cancel_http_stream(request_id: String, state: State<'_, HttpStreamState>) ->
     Ok(())
 }
 
-fn parse_google_error(response: HttpResponse) -> String {
-    let payload = response.json::<Value>().unwrap_or(Value::Null);
-    let error = payload
-        .get("error")
-        .and_then(|value| value.as_str())
-        .unwrap_or("request_failed");
-    let description = payload
-        .get("error_description")
-        .and_then(|value| value.as_str())
-        .or_else(|| {
-            payload
-                .get("error")
-                .and_then(|value| value.get("message"))
-                .and_then(|value| value.as_str())
-        })
-        .unwrap_or_default();
-    if description.is_empty() {
-        format!("Google {error}.")
-    } else {
-        format!("Google {error}: {description}")
-    }
+#[tauri::command]
+fn start_oauth_authorization(
+    app: AppHandle,
+    input: StartOAuthAuthorizationInput,
+    state: State<OAuthState>,
+) -> Result<OAuthAuthorizationSession, String> {
+    let provider = input.provider.resolve()?;
+    state.start(provider, &input.client_id, &input.options, move || {
+        focus_main_window(&app, "oauth")
+    })
 }
 
-fn exchange_google_token(
-    client: &HttpClient,
-    body: Vec<(String, String)>,
-) -> Result<GoogleTokenResponse, String> {
-    let response = client
-        .post(GOOGLE_OAUTH_TOKEN_URL)
-        .form(&body)
-        .send()
-        .map_err(|e| format!("Google token request failed: {e}"))?;
-
-    if !response.status().is_success() {
-        return Err(parse_google_error(response));
-    }
-
-    response
-        .json::<GoogleTokenResponse>()
-        .map_err(|e| format!("Could not parse Google token response: {e}"))
+#[tauri::command]
+fn finish_oauth_authorization(
+    input: FinishOAuthAuthorizationInput,
+    state: State<OAuthState>,
+) -> Result<OAuthConnectionResult, String> {
+    state.finish(input)
 }
 
-fn fetch_google_user_email(client: &HttpClient, access_token: &str) -> Result<String, String> {
-    let response = client
-        .get(GOOGLE_OAUTH_USERINFO_URL)
-        .bearer_auth(access_token)
-        .send()
-        .map_err(|e| format!("Google user info request failed: {e}"))?;
-
-    if !response.status().is_success() {
-        return Err(parse_google_error(response));
-    }
-
-    let payload = response
-        .json::<GoogleUserInfoResponse>()
-        .map_err(|e| format!("Could not parse Google user info response: {e}"))?;
-    payload
-        .email
-        .filter(|email| !email.trim().is_empty())
-        .ok_or("Google did not return an email address.".to_string())
-}
-
-fn build_google_granted_scopes(
-    scope_value: Option<String>,
-    fallback_scopes: &[String],
-) -> Vec<String> {
-    let Some(scope_value) = scope_value else {
-        return fallback_scopes.to_vec();
-    };
-    let normalized = scope_value
-        .split(' ')
-        .map(str::trim)
-        .filter(|scope| !scope.is_empty())
-        .map(ToOwned::to_owned)
-        .collect::<HashSet<_>>();
-
-    if normalized.is_empty() {
-        fallback_scopes.to_vec()
-    } else {
-        normalized.into_iter().collect()
-    }
+#[tauri::command]
+fn cancel_oauth_authorization(session_id: String, state: State<OAuthState>) -> Result<(), String> {
+    state.cancel(&session_id)
 }
 
-fn current_time_ms() -> Result<u64, String> {
-    Ok(SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map_err(|e| e.to_string())?
-        .as_millis() as u64)
+#[tauri::command]
+fn ensure_oauth_access_token(
+    input: EnsureOAuthAccessTokenInput,
+) -> Result<OAuthAccessTokenResult, String> {
+    OAuthAccountManager::new(input.provider.resolve()?, &input.client_id)?
+        .access_token(input.request)
 }
 
-fn access_token_expires_at_ms(expires_in: u64) -> Result<u64, String> {
-    Ok(current_time_ms()? + (expires_in * 1000))
+#[tauri::command]
+fn disconnect_oauth_account(input: DisconnectOAuthAccountInput) -> Result<(), String> {
+    OAuthAccountManager::new(input.provider.resolve()?, "")?.disconnect(&input.account)
 }
 
 #[tauri::command]
-fn complete_google_oauth(
-    input: GoogleCompleteOAuthInput,
-) -> Result<GoogleOAuthConnectionResult, String> {
-    let client_id = input.client_id.trim();
-    if client_id.is_empty() {
-        return Err("Google sign-in is not configured yet.".to_string());
-    }
-
-    let mut body = vec![
-        ("client_id".to_string(), client_id.to_string()),
-        ("code".to_string(), input.code),
-        ("code_verifier".to_string(), input.code_verifier),
-        ("grant_type".to_string(), "authorization_code".to_string()),
-        ("redirect_uri".to_string(), input.redirect_uri),
-    ];
-    if let Some(client_secret) = google_oauth_client_secret() {
-        body.push(("client_secret".to_string(), client_secret.to_string()));
-    }
-
-    let client = google_http_client()?;
-    let token = exchange_google_token(&client, body)?;
-    let access_token = token
-        .access_token
-        .filter(|value| !value.trim().is_empty())
-        .ok_or("Google did not return an access token.".to_string())?;
-    let expires_in = token
-        .expires_in
-        .ok_or("Google did not return an access token expiry.".to_string())?;
-    let granted_scopes = build_google_granted_scopes(token.scope, &[]);
-    let account_email = fetch_google_user_email(&client, &access_token)?;
-    if let Some(expected_account_email) = input
-        .expected_account_email
-        .as_deref()
-        .and_then(normalize_google_account_email)
-    {
-        let normalized_account_email = normalize_google_account_email(&account_email)
-            .ok_or("Google did not return an email address.".to_string())?;
-        if normalized_account_email != expected_account_email {
-            return Err("Google connected a different account than expected.".to_string());
-        }
-    }
-
-    let (mut sessions, legacy_keyring_session) = load_google_stored_sessions()?;
-    let migrated_legacy = migrate_legacy_google_stored_session(
-        &mut sessions,
-        legacy_keyring_session,
-        input.legacy_account_email.as_deref(),
-        input.legacy_session,
-    );
-    let existing_session = resolve_google_stored_session(
-        &sessions,
-        &account_email,
-        None,
-        input.legacy_account_email.as_deref(),
-    )?;
-    let refresh_token = token
-        .refresh_token
-        .filter(|value| !value.trim().is_empty())
-        .or_else(|| {
-            existing_session
-                .as_ref()
-                .map(|session| session.refresh_token.clone())
-        })
-        .ok_or(
-            "Google did not return a refresh token. Reconnect Google and try again.".to_string(),
-        )?;
-    let access_token_expires_at_ms = access_token_expires_at_ms(expires_in)?;
-
-    let Some(normalized_account_email) = normalize_google_account_email(&account_email) else {
-        return Err("Google did not return an email address.".to_string());
-    };
-    sessions.insert(
-        normalized_account_email,
-        GoogleStoredSession {
-            access_token,
-            access_token_expires_at_ms,
-            refresh_token,
-        },
-    );
-    if migrated_legacy || !sessions.is_empty() {
-        store_google_stored_sessions(&sessions)?;
-    }
-
-    Ok(GoogleOAuthConnectionResult {
-        access_token_expires_at_ms,
-        account_email,
-        granted_scopes,
-    })
+fn list_oauth_accounts(input: OAuthProviderInput) -> Result<Vec<String>, String> {
+    OAuthAccountManager::new(input.resolve()?, "")?.accounts()
 }
 
 #[tauri::command]
-fn ensure_google_access_token(
-    input: GoogleEnsureAccessTokenInput,
-) -> Result<GoogleAccessTokenResult, String> {
-    let client_id = input.client_id.trim();
-    if client_id.is_empty() {
-        return Err("Reconnect Google to refresh access.".to_string());
-    }
-    let account_email = input.account_email.trim();
-    if account_email.is_empty() {
-        return Err("Select a Google account to refresh access.".to_string());
-    }
-
-    let (mut sessions, legacy_keyring_session) = load_google_stored_sessions()?;
-    let migrated_legacy = migrate_legacy_google_stored_session(
-        &mut sessions,
-        legacy_keyring_session,
-        input.legacy_account_email.as_deref(),
-        input.legacy_session,
-    );
-    if migrated_legacy {
-        store_google_stored_sessions(&sessions)?;
-    }
-
-    let mut session = resolve_google_stored_session(
-        &sessions,
-        account_email,
-        None,
-        input.legacy_account_email.as_deref(),
-    )?
-    .ok_or("Reconnect Google to refresh access.".to_string())?;
-    let now_ms = current_time_ms()?;
-    if !session.access_token.trim().is_empty()
-        && session.access_token_expires_at_ms > (now_ms + GOOGLE_OAUTH_ACCESS_TOKEN_BUFFER_MS)
-    {
-        return Ok(GoogleAccessTokenResult {
-            account_email: account_email.to_string(),
-            access_token: session.access_token,
-            access_token_expires_at_ms: session.access_token_expires_at_ms,
-            granted_scopes: input.granted_scopes,
-        });
-    }
-
-    let mut body = vec![
-        ("client_id".to_string(), client_id.to_string()),
-        ("grant_type".to_string(), "refresh_token".to_string()),
-        ("refresh_token".to_string(), session.refresh_token.clone()),
-    ];
-    if let Some(client_secret) = google_oauth_client_secret() {
-        body.push(("client_secret".to_string(), client_secret.to_string()));
-    }
+fn connect_caldav_account(
+    app: AppHandle,
+    input: ConnectCaldavAccountInput,
+) -> Result<CaldavAccount, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    caldav::connect_caldav_account(&app_data_dir, input)
+}
 
-    let client = google_http_client()?;
-    let token = exchange_google_token(&client, body)?;
-    session.access_token = token
-        .access_token
-        .filter(|value| !value.trim().is_empty())
-        .ok_or("Google did not return an access token.".to_string())?;
-    session.access_token_expires_at_ms = access_token_expires_at_ms(
-        token
-            .expires_in
-            .ok_or("Google did not return an access token expiry.".to_string())?,
-    )?;
-    if let Some(refresh_token) = token.refresh_token.filter(|value| !value.trim().is_empty()) {
-        session.refresh_token = refresh_token;
-    }
-    let normalized_account_email = normalize_google_account_email(account_email)
-        .ok_or("Select a Google account to refresh access.".to_string())?;
-    sessions.insert(normalized_account_email, session.clone());
-    store_google_stored_sessions(&sessions)?;
-
-    Ok(GoogleAccessTokenResult {
-        account_email: account_email.to_string(),
-        access_token: session.access_token,
-        access_token_expires_at_ms: session.access_token_expires_at_ms,
-        granted_scopes: build_google_granted_scopes(token.scope, &input.granted_scopes),
-    })
+#[tauri::command]
+fn list_caldav_accounts(app: AppHandle) -> Result<Vec<CaldavAccount>, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    caldav::list_caldav_accounts(&app_data_dir)
 }
 
 #[tauri::command]
-fn clear_google_oauth_session(account_email: String) -> Result<(), String> {
-    let (mut sessions, legacy_keyring_session) = load_google_stored_sessions()?;
-    let normalized_account_email = normalize_google_account_email(&account_email)
-        .ok_or("Select a Google account to disconnect.".to_string())?;
-    if sessions.remove(&normalized_account_email).is_some() {
-        if sessions.is_empty() {
-            return clear_google_stored_session();
-        }
-        return store_google_stored_sessions(&sessions);
-    }
-    if legacy_keyring_session.is_some() {
-        return clear_google_stored_session();
-    }
-    Ok(())
+fn disconnect_caldav_account(app: AppHandle, account_id: String) -> Result<(), String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    caldav::disconnect_caldav_account(&app_data_dir, &account_id)
 }
 
 #[tauri::command]
-fn list_google_oauth_session_accounts() -> Result<Vec<String>, String> {
-    let (sessions, _) = load_google_stored_sessions()?;
-    let mut accounts = sessions.keys().cloned().collect::<Vec<_>>();
-    accounts.sort();
-    Ok(accounts)
+fn sync_caldav_events(
+    app: AppHandle,
+    input: SyncCaldavEventsInput,
+) -> Result<CaldavSyncResult, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    caldav::sync_caldav_events(&app_data_dir, input)
 }
 
 #[cfg(target_os = "macos")]
@@ -1626,12 +703,19 @@ fn show_path_in_folder(path: String) -> Result<(), String> {
 
 #[tauri::command]
 fn run_ai_tool(
+    app: AppHandle,
+    state: State<'_, VaultGitState>,
     command: String,
     argv: Vec<String>,
     stdin: Option<String>,
 ) -> Result<philo_tools::ToolCommandOutput, String> {
     if command == "philo" {
-        philo_tools::run_sidecar_philo(&argv, stdin)
+        let output = philo_tools::run_sidecar_philo(&argv, stdin)?;
+        if output.code == 0 {
+            let written = philo_tools::written_paths(&argv, &output.stdout);
+            mark_vault_own_writes(&app, &state, &written);
+        }
+        Ok(output)
     } else {
         philo_tools::run_tool_command(philo_tools::ToolCommand::SafeShell {
             command,
@@ -1670,6 +754,8 @@ struct ObsidianSettingsDetection {
     excalidraw_folder: String,
     assets_folder: String,
     filename_pattern: String,
+    templates_folder: String,
+    daily_note_template: String,
 }
 
 fn read_json_file(path: &PathBuf) -> Option<Value> {
@@ -3351,10 +2437,13 @@ fn detect_obsidian_settings(vault_dir: String) -> ObsidianSettingsDetection {
             excalidraw_folder: String::new(),
             assets_folder: String::new(),
             filename_pattern: String::new(),
+            templates_folder: String::new(),
+            daily_note_template: String::new(),
         };
     }
 
-    let obsidian_dir = PathBuf::from(vault_dir).join(".obsidian");
+    let vault_path = PathBuf::from(vault_dir);
+    let obsidian_dir = vault_path.join(".obsidian");
     let daily_notes = read_json_file(&obsidian_dir.join("daily-notes.json"));
     let periodic_notes = read_json_file(&obsidian_dir.join("plugins/periodic-notes/data.json"));
     let app = read_json_file(&obsidian_dir.join("app.json"));
@@ -3435,11 +2524,34 @@ fn detect_obsidian_settings(vault_dir: String) -> ObsidianSettingsDetection {
         excalidraw_folder,
         assets_folder,
         filename_pattern,
+        templates_folder: obsidian::templates_folder(&vault_path).unwrap_or_default(),
+        daily_note_template: obsidian::daily_note_template(&vault_path)
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_default(),
     }
 }
 
+#[tauri::command]
+fn obsidian_compatibility_report(vault_dir: String) -> Result<ObsidianCompatibilityReport, String> {
+    obsidian::compatibility_report(Path::new(vault_dir.trim()))
+}
+
+#[tauri::command]
+fn render_note_template(input: philo_tools::NoteTemplateRequest) -> Result<Option<String>, String> {
+    let context = philo_tools::resolve_note_context()?;
+    philo_tools::render_note_template_file(&context, &input)
+}
+
+#[tauri::command]
+fn list_note_templates() -> Result<Vec<String>, String> {
+    let context = philo_tools::resolve_note_context()?;
+    Ok(note_templates::list_templates(&context.templates_dir))
+}
+
 #[tauri::command]
 fn bootstrap_obsidian_vault(
+    app: AppHandle,
+    state: State<'_, VaultGitState>,
     vault_dir: String,
     daily_logs_folder: String,
     excalidraw_folder: String,
@@ -3466,37 +2578,46 @@ fn bootstrap_obsidian_vault(
     ensure_folder_in_vault(&vault_path, &normalized_excalidraw)?;
     ensure_folder_in_vault(&vault_path, &normalized_assets)?;
 
+    let mut written = Vec::new();
+
     if !normalized_daily.is_empty() && normalized_daily != "." {
+        let path = obsidian_dir.join("daily-notes.json");
         write_json_file(
-            &obsidian_dir.join("daily-notes.json"),
+            &path,
             json!({
                 "format": "YYYY-MM-DD",
                 "folder": normalized_daily,
                 "template": ""
             }),
         )?;
+        written.push(path);
     }
 
     if !normalized_assets.is_empty() && normalized_assets != "." {
+        let path = obsidian_dir.join("app.json");
         write_json_file(
-            &obsidian_dir.join("app.json"),
+            &path,
             json!({
                 "attachmentFolderPath": normalized_assets
             }),
         )?;
+        written.push(path);
     }
 
     if !normalized_excalidraw.is_empty() && normalized_excalidraw != "." {
         let plugin_dir = obsidian_dir.join("plugins/obsidian-excalidraw-plugin");
         fs::create_dir_all(&plugin_dir).map_err(|e| e.to_string())?;
+        let path = plugin_dir.join("data.json");
         write_json_file(
-            &plugin_dir.join("data.json"),
+            &path,
             json!({
                 "folder": normalized_excalidraw
             }),
         )?;
+        written.push(path);
     }
 
+    mark_vault_own_writes(&app, &state, &written);
     Ok(())
 }
 
@@ -3709,6 +2830,103 @@ fn restore_widget_git_revision(
     widget_git::restore_widget_git_revision(&app_data_dir, input)
 }
 
+#[tauri::command]
+fn open_vault_git(
+    app: AppHandle,
+    state: State<'_, VaultGitState>,
+    input: VaultGitInput,
+) -> Result<VaultGitStatus, String> {
+    let status = vault_git::vault_git_status(&input.vault_root)?;
+    arm_vault_auto_commit(&app, &state, &input.vault_root, &status)?;
+    Ok(status)
+}
+
+#[tauri::command]
+fn set_vault_git_enabled(
+    app: AppHandle,
+    state: State<'_, VaultGitState>,
+    input: SetVaultGitEnabledInput,
+) -> Result<VaultGitStatus, String> {
+    let vault_root = input.vault_root.clone();
+    let status = vault_git::set_vault_git_enabled(input)?;
+    arm_vault_auto_commit(&app, &state, &vault_root, &status)?;
+    Ok(status)
+}
+
+#[tauri::command]
+fn list_vault_git_history(
+    input: VaultGitHistoryInput,
+) -> Result<Vec<VaultGitHistoryEntry>, String> {
+    vault_git::list_vault_git_history(input)
+}
+
+#[tauri::command]
+fn get_vault_git_diff(input: VaultGitDiffInput) -> Result<VaultGitDiff, String> {
+    vault_git::get_vault_git_diff(input)
+}
+
+#[tauri::command]
+fn restore_vault_git_revision(
+    input: RestoreVaultGitRevisionInput,
+) -> Result<VaultGitRestoreResult, String> {
+    vault_git::restore_vault_git_revision(input)
+}
+
+/// Points the file watcher at the vault while auto-commits are on, and stops it otherwise.
+fn arm_vault_auto_commit(
+    app: &AppHandle,
+    state: &VaultGitState,
+    vault_root: &str,
+    status: &VaultGitStatus,
+) -> Result<(), String> {
+    let vault_root = status
+        .auto_commit
+        .then(|| fs::canonicalize(vault_root.trim()).map_err(|e| e.to_string()))
+        .transpose()?;
+    let previous = state.vault_root();
+    if previous == vault_root {
+        return Ok(());
+    }
+
+    if let Some(previous) = previous {
+        state.set_vault_root(None);
+        app.notify().stop_at(&previous).map_err(|e| e.to_string())?;
+    }
+    if let Some(vault_root) = vault_root {
+        app.notify()
+            .start_at(vault_root.clone())
+            .map_err(|e| e.to_string())?;
+        state.set_vault_root(Some(vault_root));
+    }
+    Ok(())
+}
+
+/// The one task that turns watcher events into auto-commits, so commits never overlap.
+async fn run_vault_auto_commits(app: AppHandle) {
+    let state = app.state::<VaultGitState>();
+    loop {
+        state.settled().await;
+        let Some((vault_root, changes)) = state.take_pending() else {
+            continue;
+        };
+        let committed = tauri::async_runtime::spawn_blocking(move || {
+            vault_git::commit_vault_changes(&vault_root, &changes)
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|result| result);
+        match committed {
+            Ok(Some(commit_id)) => {
+                let _ = app.emit("philo:vault-git-committed", commit_id);
+            }
+            Ok(None) => {}
+            Err(err) => {
+                let _ = app.emit("philo:vault-git-commit-failed", err);
+            }
+        }
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -3725,11 +2943,17 @@ pub fn run() {
         .plugin(tauri_plugin_store2::init())
         .plugin(tauri_plugin_settings::init())
         .plugin(tauri_plugin_listener::init())
+        .plugin(tauri_plugin_notify::init_with(
+            tauri_plugin_notify::InitOptions {
+                watch_vault_base: false,
+            },
+        ))
         .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
             focus_main_window(app, "single_instance");
         }))
-        .manage(GoogleOAuthState::default())
+        .manage(OAuthState::default())
         .manage(HttpStreamState::default())
+        .manage(VaultGitState::default())
         .invoke_handler(tauri::generate_handler![
             extend_fs_scope,
             ensure_widget_git_history_baseline,
@@ -3737,21 +2961,32 @@ pub fn run() {
             list_widget_git_history,
             get_widget_git_diff,
             restore_widget_git_revision,
+            open_vault_git,
+            set_vault_git_enabled,
+            list_vault_git_history,
+            get_vault_git_diff,
+            restore_vault_git_revision,
             find_obsidian_vaults,
             detect_obsidian_settings,
+            obsidian_compatibility_report,
+            render_note_template,
+            list_note_templates,
             bootstrap_obsidian_vault,
             post_json,
             stream_http,
             cancel_http_stream,
             read_markdown_file,
             write_markdown_file,
-            start_google_oauth_callback,
-            wait_for_google_oauth_callback,
-            cancel_google_oauth_callback,
-            complete_google_oauth,
-            ensure_google_access_token,
-            clear_google_oauth_session,
-            list_google_oauth_session_accounts,
+            start_oauth_authorization,
+            finish_oauth_authorization,
+            cancel_oauth_authorization,
+            ensure_oauth_access_token,
+            disconnect_oauth_account,
+            list_oauth_accounts,
+            connect_caldav_account,
+            list_caldav_accounts,
+            disconnect_caldav_account,
+            sync_caldav_events,
             open_in_apple_mail,
             open_in_apple_calendar,
             show_path_in_folder,
@@ -3776,6 +3011,20 @@ pub fn run() {
         .setup(|app| {
             let app_handle = app.handle().clone();
 
+            let changed_handle = app.handle().clone();
+            FileChanged::listen(app.handle(), move |event| {
+                changed_handle
+                    .state::<VaultGitState>()
+                    .record(&event.payload.path, false);
+            });
+            let own_handle = app.handle().clone();
+            OwnFileChanged::listen(app.handle(), move |event| {
+                own_handle
+                    .state::<VaultGitState>()
+                    .record(&event.payload.path, true);
+            });
+            tauri::async_runtime::spawn(run_vault_auto_commits(app.handle().clone()));
+
             app.deep_link().on_open_url(move |event| {
                 let payload: Vec<String> = event
                     .urls()
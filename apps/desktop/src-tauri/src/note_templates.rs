@@ -0,0 +1,556 @@
+use crate::obsidian::format_moment;
+use crate::periodic_notes::PeriodKind;
+use chrono::{NaiveDate, NaiveDateTime};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+const CALENDAR_STATE_FILE: &str = "google-import-state.json";
+const DEFAULT_DATE_FORMAT: &str = "YYYY-MM-DD";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteTemplateKind {
+    Daily,
+    Page,
+    Weekly,
+    Monthly,
+    Quarterly,
+}
+
+impl From<PeriodKind> for NoteTemplateKind {
+    fn from(kind: PeriodKind) -> Self {
+        match kind {
+            PeriodKind::Week => NoteTemplateKind::Weekly,
+            PeriodKind::Month => NoteTemplateKind::Monthly,
+            PeriodKind::Quarter => NoteTemplateKind::Quarterly,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TemplateValue {
+    Text(String),
+    /// Rendered as `YYYY-MM-DD` unless the template asks for a format, e.g. `{{ date:dddd }}`.
+    Date(NaiveDateTime),
+    List(Vec<TemplateValue>),
+    /// Rendered through its `markdown` field, so `{{ event }}` prints a ready-made list item.
+    Map(BTreeMap<String, TemplateValue>),
+}
+
+impl TemplateValue {
+    pub fn text(value: impl Into<String>) -> Self {
+        TemplateValue::Text(value.into())
+    }
+
+    pub fn map<const N: usize>(fields: [(&str, TemplateValue); N]) -> Self {
+        TemplateValue::Map(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    fn render(&self, format: Option<&str>) -> String {
+        match self {
+            TemplateValue::Text(value) => value.clone(),
+            TemplateValue::Date(value) => {
+                format_moment(*value, format.unwrap_or(DEFAULT_DATE_FORMAT))
+            }
+            TemplateValue::List(items) => items
+                .iter()
+                .map(|item| item.render(format))
+                .filter(|item| !item.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            TemplateValue::Map(fields) => fields
+                .get("markdown")
+                .map(|value| value.render(format))
+                .unwrap_or_default(),
+        }
+    }
+
+    fn is_truthy(&self) -> bool {
+        match self {
+            TemplateValue::Text(value) => !value.trim().is_empty(),
+            TemplateValue::List(items) => !items.is_empty(),
+            TemplateValue::Date(_) | TemplateValue::Map(_) => true,
+        }
+    }
+}
+
+pub type TemplateScope = BTreeMap<String, TemplateValue>;
+
+enum Token {
+    Text(String),
+    Var(String),
+    Tag(String),
+}
+
+enum Node {
+    Text(String),
+    Var {
+        path: String,
+        format: Option<String>,
+    },
+    If {
+        path: String,
+        negate: bool,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    For {
+        name: String,
+        path: String,
+        body: Vec<Node>,
+    },
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut rest = source;
+    let mut last_was_var = false;
+
+    loop {
+        let Some(start) = [rest.find("{{"), rest.find("{%")]
+            .into_iter()
+            .flatten()
+            .min()
+        else {
+            text.push_str(rest);
+            break;
+        };
+        text.push_str(&rest[..start]);
+        let is_tag = rest[start..].starts_with("{%");
+        let close = if is_tag { "%}" } else { "}}" };
+        let end = rest[start + 2..]
+            .find(close)
+            .map(|offset| start + 2 + offset)
+            .ok_or_else(|| format!("Unclosed {} in template.", &rest[start..start + 2]))?;
+        let inner = rest[start + 2..end].trim().to_string();
+        rest = &rest[end + 2..];
+
+        if !is_tag {
+            if !text.is_empty() {
+                tokens.push(Token::Text(std::mem::take(&mut text)));
+            }
+            tokens.push(Token::Var(inner));
+            last_was_var = true;
+            continue;
+        }
+
+        // A tag alone on its line disappears with its line, so blocks don't leave blank lines.
+        let line_start = text.rfind('\n').map(|index| index + 1).unwrap_or(0);
+        let shares_line_with_var = last_was_var && !text.contains('\n');
+        let line_end = rest.find('\n');
+        if text[line_start..].trim().is_empty()
+            && !shares_line_with_var
+            && rest[..line_end.unwrap_or(rest.len())].trim().is_empty()
+        {
+            text.truncate(line_start);
+            rest = &rest[line_end.map(|index| index + 1).unwrap_or(rest.len())..];
+        }
+        if !text.is_empty() {
+            tokens.push(Token::Text(std::mem::take(&mut text)));
+        }
+        tokens.push(Token::Tag(inner));
+        last_was_var = false;
+    }
+
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+    Ok(tokens)
+}
+
+fn validate_path(path: &str) -> Result<String, String> {
+    let valid = !path.is_empty()
+        && path.split('.').all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+        });
+    if valid {
+        Ok(path.to_string())
+    } else {
+        Err(format!("Invalid template variable: {path}"))
+    }
+}
+
+fn parse_nodes(
+    tokens: &[Token],
+    index: &mut usize,
+    until: &[&str],
+) -> Result<(Vec<Node>, Option<String>), String> {
+    let mut nodes = Vec::new();
+    while *index < tokens.len() {
+        let token = &tokens[*index];
+        *index += 1;
+        match token {
+            Token::Text(text) => nodes.push(Node::Text(text.clone())),
+            Token::Var(inner) => {
+                let (path, format) = match inner.split_once(':') {
+                    Some((path, format)) => (path.trim(), Some(format.trim().to_string())),
+                    None => (inner.as_str(), None),
+                };
+                nodes.push(Node::Var {
+                    path: validate_path(path)?,
+                    format,
+                });
+            }
+            Token::Tag(inner) => {
+                let mut words = inner.split_whitespace();
+                let keyword = words.next().unwrap_or_default();
+                if until.contains(&keyword) {
+                    return Ok((nodes, Some(keyword.to_string())));
+                }
+                match keyword {
+                    "if" => {
+                        let (negate, path) = match (words.next(), words.next()) {
+                            (Some("not"), Some(path)) => (true, path),
+                            (Some(path), None) => (false, path),
+                            _ => return Err(format!("Invalid template tag: {{% {inner} %}}")),
+                        };
+                        let (then, end) = parse_nodes(tokens, index, &["else", "endif"])?;
+                        let otherwise = match end.as_deref() {
+                            Some("else") => parse_nodes(tokens, index, &["endif"])?.0,
+                            Some(_) => Vec::new(),
+                            None => return Err("Missing {% endif %} in template.".to_string()),
+                        };
+                        nodes.push(Node::If {
+                            path: validate_path(path)?,
+                            negate,
+                            then,
+                            otherwise,
+                        });
+                    }
+                    "for" => {
+                        let (Some(name), Some("in"), Some(path), None) =
+                            (words.next(), words.next(), words.next(), words.next())
+                        else {
+                            return Err(format!("Invalid template tag: {{% {inner} %}}"));
+                        };
+                        let (body, end) = parse_nodes(tokens, index, &["endfor"])?;
+                        if end.is_none() {
+                            return Err("Missing {% endfor %} in template.".to_string());
+                        }
+                        nodes.push(Node::For {
+                            name: validate_path(name)?,
+                            path: validate_path(path)?,
+                            body,
+                        });
+                    }
+                    _ => return Err(format!("Unexpected template tag: {{% {inner} %}}")),
+                }
+            }
+        }
+    }
+    Ok((nodes, None))
+}
+
+fn lookup<'a>(
+    path: &str,
+    scope: &'a TemplateScope,
+    locals: &'a [(String, TemplateValue)],
+) -> Result<&'a TemplateValue, String> {
+    let mut parts = path.split('.');
+    let root = parts.next().unwrap_or_default();
+    let mut value = locals
+        .iter()
+        .rev()
+        .find(|(name, _)| name == root)
+        .map(|(_, value)| value)
+        .or_else(|| scope.get(root))
+        .ok_or_else(|| format!("Unknown template variable: {root}"))?;
+    for part in parts {
+        value = match value {
+            TemplateValue::Map(fields) => fields
+                .get(part)
+                .ok_or_else(|| format!("Unknown template variable: {path}"))?,
+            _ => return Err(format!("Unknown template variable: {path}")),
+        };
+    }
+    Ok(value)
+}
+
+fn render_nodes(
+    nodes: &[Node],
+    scope: &TemplateScope,
+    locals: &mut Vec<(String, TemplateValue)>,
+    output: &mut String,
+) -> Result<(), String> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Var { path, format } => {
+                output.push_str(&lookup(path, scope, locals)?.render(format.as_deref()))
+            }
+            Node::If {
+                path,
+                negate,
+                then,
+                otherwise,
+            } => {
+                let truthy = lookup(path, scope, locals)?.is_truthy();
+                let branch = if truthy != *negate { then } else { otherwise };
+                render_nodes(branch, scope, locals, output)?;
+            }
+            Node::For { name, path, body } => {
+                let items = match lookup(path, scope, locals)? {
+                    TemplateValue::List(items) => items.clone(),
+                    _ => return Err(format!("{path} is not a list.")),
+                };
+                for item in items {
+                    locals.push((name.clone(), item));
+                    let result = render_nodes(body, scope, locals, output);
+                    locals.pop();
+                    result?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Renders a note template: `{{ var }}`, `{{ var.field }}`, `{{ date:FORMAT }}` with moment.js
+/// tokens, `{% if var %}`/`{% if not var %}`/`{% else %}`/`{% endif %}` and
+/// `{% for item in list %}`/`{% endfor %}`. Unknown variables are errors rather than blanks.
+pub fn render_note_template(source: &str, scope: &TemplateScope) -> Result<String, String> {
+    let tokens = tokenize(source)?;
+    let mut index = 0;
+    let (nodes, end) = parse_nodes(&tokens, &mut index, &[])?;
+    if let Some(end) = end {
+        return Err(format!("Unexpected {{% {end} %}} in template."));
+    }
+    let mut output = String::new();
+    render_nodes(&nodes, scope, &mut Vec::new(), &mut output)?;
+    Ok(output)
+}
+
+/// Template names in `dir`, i.e. markdown file stems, sorted.
+pub fn list_templates(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+        })
+        .filter_map(|path| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+        })
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+pub fn load_template(dir: &Path, name: &str) -> Result<String, String> {
+    let name = name.trim();
+    let name = name
+        .strip_suffix(".md")
+        .or_else(|| name.strip_suffix(".MD"))
+        .unwrap_or(name);
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(format!("Invalid template name: {name}"));
+    }
+    let path = dir.join(format!("{name}.md"));
+    fs::read_to_string(&path).map_err(|err| {
+        if err.kind() == std::io::ErrorKind::NotFound {
+            format!("Template {name} does not exist in {}.", dir.display())
+        } else {
+            err.to_string()
+        }
+    })
+}
+
+/// Unchecked task texts in a note, for carrying them into the next one.
+pub fn open_tasks(markdown: &str) -> Vec<String> {
+    let task_re = Regex::new(r"^\s*[-*+]\s+\[ \]\s+(.+?)\s*$").expect("valid open task regex");
+    markdown
+        .lines()
+        .filter_map(|line| {
+            task_re
+                .captures(line)
+                .map(|captures| captures[1].to_string())
+        })
+        .collect()
+}
+
+pub fn open_task_values(tasks: Vec<String>) -> TemplateValue {
+    TemplateValue::List(
+        tasks
+            .into_iter()
+            .map(|task| {
+                TemplateValue::map([
+                    ("markdown", TemplateValue::text(format!("- [ ] {task}"))),
+                    ("text", TemplateValue::text(task)),
+                ])
+            })
+            .collect(),
+    )
+}
+
+/// Events for `date` from the calendar records the Google and CalDAV imports keep next to the
+/// settings file, ordered by start time.
+pub fn load_calendar_events(base_dir: &Path, date: NaiveDate) -> TemplateValue {
+    let date = date.format("%Y-%m-%d").to_string();
+    let state = fs::read_to_string(base_dir.join(CALENDAR_STATE_FILE))
+        .ok()
+        .and_then(|raw| serde_json::from_str::<JsonValue>(&raw).ok());
+    let Some(records) = state
+        .as_ref()
+        .and_then(|state| state.get("records"))
+        .and_then(JsonValue::as_object)
+    else {
+        return TemplateValue::List(Vec::new());
+    };
+
+    let mut events = records
+        .values()
+        .filter(|record| {
+            record.get("kind").and_then(JsonValue::as_str) == Some("google_calendar")
+                && record.get("active").and_then(JsonValue::as_bool) == Some(true)
+                && record
+                    .get("sourceId")
+                    .and_then(JsonValue::as_str)
+                    .is_some_and(|id| id.ends_with(":overview"))
+        })
+        .filter_map(|record| record.get("current"))
+        .filter(|current| current.get("dueDate").and_then(JsonValue::as_str) == Some(date.as_str()))
+        .map(|current| {
+            let text = |key: &str| {
+                current
+                    .get(key)
+                    .and_then(JsonValue::as_str)
+                    .map(str::trim)
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            let time = Some(text("timeText"))
+                .filter(|time| !time.is_empty())
+                .unwrap_or_else(|| "All day".to_string());
+            // The overview text reads "Mon, Oct 5 · 09:00 Standup"; keep only the title.
+            let overview = text("fallbackText");
+            let title = overview
+                .split_once(" · ")
+                .map(|(_, rest)| rest)
+                .unwrap_or(&overview)
+                .trim_start_matches(time.as_str())
+                .trim()
+                .to_string();
+            let link = text("href");
+            let markdown = if link.is_empty() {
+                format!("- {time} {title}")
+            } else {
+                format!("- {time} [{title}]({link})")
+            };
+            (
+                text("sortKey"),
+                TemplateValue::map([
+                    ("title", TemplateValue::text(title)),
+                    ("time", TemplateValue::text(time)),
+                    ("link", TemplateValue::text(link)),
+                    ("markdown", TemplateValue::text(markdown)),
+                ]),
+            )
+        })
+        .collect::<Vec<_>>();
+    events.sort_by(|left, right| left.0.cmp(&right.0));
+    TemplateValue::List(events.into_iter().map(|(_, event)| event).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn scope() -> TemplateScope {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 5)
+            .unwrap()
+            .and_hms_opt(8, 30, 0)
+            .unwrap();
+        TemplateScope::from([
+            ("date".to_string(), TemplateValue::Date(date)),
+            ("city".to_string(), TemplateValue::text("")),
+            (
+                "previous_open_tasks".to_string(),
+                open_task_values(open_tasks(
+                    "- [ ] Email vendor\n- [x] Done\n  * [ ] Book venue\n",
+                )),
+            ),
+            (
+                "events".to_string(),
+                TemplateValue::List(vec![TemplateValue::map([
+                    ("title", TemplateValue::text("Standup")),
+                    ("time", TemplateValue::text("09:00")),
+                    ("markdown", TemplateValue::text("- 09:00 Standup")),
+                ])]),
+            ),
+        ])
+    }
+
+    #[test]
+    fn renders_variables_blocks_and_loops() {
+        let template = "# {{ date:dddd, MMMM D }}\n{% if city %}\nWeather in {{ city }}\n{% else %}\nNo city yet\n{% endif %}\n\n## Carried over\n{{ previous_open_tasks }}\n\n## Calendar\n{% for event in events %}\n- **{{ event.time }}** {{ event.title }}\n{% endfor %}\n{% if not events %}\nFree day\n{% endif %}\n";
+
+        assert_eq!(
+            render_note_template(template, &scope()).unwrap(),
+            "# Monday, October 5\nNo city yet\n\n## Carried over\n- [ ] Email vendor\n- [ ] Book venue\n\n## Calendar\n- **09:00** Standup\n"
+        );
+    }
+
+    #[test]
+    fn reports_template_errors() {
+        assert_eq!(
+            render_note_template("{{ weather }}", &scope()).unwrap_err(),
+            "Unknown template variable: weather"
+        );
+        assert_eq!(
+            render_note_template("{% if city %}x", &scope()).unwrap_err(),
+            "Missing {% endif %} in template."
+        );
+        assert!(render_note_template("{{ date", &scope()).is_err());
+        assert!(render_note_template("{% for e in city %}{% endfor %}", &scope()).is_err());
+    }
+
+    #[test]
+    fn loads_calendar_events_for_a_day() {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let base = std::env::temp_dir().join(format!("philo-note-templates-test-{unique}"));
+        fs::create_dir_all(&base).unwrap();
+        fs::write(
+            base.join(CALENDAR_STATE_FILE),
+            r#"{"records":{
+                "a":{"kind":"google_calendar","active":true,"sourceId":"e2:overview","current":{"dueDate":"2026-10-05","timeText":"14:00","fallbackText":"Mon, Oct 5 · 14:00 Review","href":"https://cal/e2","sortKey":"2026-10-05:14:00:e2:overview"}},
+                "b":{"kind":"google_calendar","active":true,"sourceId":"e1:overview","current":{"dueDate":"2026-10-05","timeText":null,"fallbackText":"Mon, Oct 5 · All day Offsite","href":"","sortKey":"2026-10-05:all-day:e1:overview"}},
+                "c":{"kind":"google_calendar","active":true,"sourceId":"e1:action","current":{"dueDate":"2026-10-05","fallbackText":"Attend Offsite","sortKey":"x"}},
+                "d":{"kind":"google_calendar","active":false,"sourceId":"e3:overview","current":{"dueDate":"2026-10-05","fallbackText":"Mon, Oct 5 · 10:00 Gone","sortKey":"y"}},
+                "e":{"kind":"gmail","active":true,"sourceId":"m:overview","current":{"dueDate":"2026-10-05","fallbackText":"Email","sortKey":"z"}}
+            }}"#,
+        )
+        .unwrap();
+
+        let events = load_calendar_events(&base, NaiveDate::from_ymd_opt(2026, 10, 5).unwrap());
+
+        assert_eq!(
+            events.render(None),
+            "- 14:00 [Review](https://cal/e2)\n- All day Offsite"
+        );
+    }
+}
@@ -0,0 +1,1935 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use keyring::{Entry as KeyringEntry, Error as KeyringError};
+use reqwest::blocking::{Client as HttpClient, Response as HttpResponse};
+use reqwest::header::ACCEPT;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::env;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const OAUTH_DEFAULT_TIMEOUT_MS: u64 = 180_000;
+const OAUTH_ACCESS_TOKEN_BUFFER_MS: u64 = 60_000;
+// Providers such as GitHub hand out access tokens without an expiry. Treat those as valid for a
+// year so the frontend still gets a representable timestamp.
+const OAUTH_NON_EXPIRING_TOKEN_TTL_SECS: u64 = 365 * 24 * 60 * 60;
+const OAUTH_KEYRING_SERVICE_PREFIX: &str = "com.johnjeong.philo";
+const OAUTH_KEYRING_SERVICE_PREFIX_DEV: &str = "com.johnjeong.philo.dev";
+const OAUTH_KEYRING_ACCOUNT: &str = "session";
+const OAUTH_USER_AGENT: &str = "Philo";
+
+/// Endpoints and conventions for one OAuth2 identity provider.
+///
+/// Google, Microsoft 365, and GitHub ship as [`OAuthProviderConfig::builtin`] entries. Any other
+/// authorization server, such as a self-hosted IdP, can be described by the frontend with the
+/// same shape.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthProviderConfig {
+    pub id: String,
+    pub display_name: String,
+    pub authorization_url: String,
+    pub token_url: String,
+    pub userinfo_url: String,
+    #[serde(default)]
+    pub revocation_url: Option<String>,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// Extra query parameters appended to the authorization URL, e.g. `access_type=offline`.
+    #[serde(default)]
+    pub authorization_params: BTreeMap<String, String>,
+    /// Userinfo field that identifies the account. OIDC providers use `email`.
+    #[serde(default = "default_account_claim")]
+    pub account_claim: String,
+    /// Environment variable holding the client secret, for providers that require one. Only
+    /// built-in providers set this; a frontend-supplied config must not pick which variable is
+    /// sent to its token endpoint.
+    #[serde(skip)]
+    pub client_secret_env: Option<String>,
+    #[serde(skip)]
+    bundled_client_secret: Option<String>,
+}
+
+fn default_account_claim() -> String {
+    "email".to_string()
+}
+
+impl OAuthProviderConfig {
+    pub fn google() -> Self {
+        Self {
+            id: "google".to_string(),
+            display_name: "Google".to_string(),
+            authorization_url: "https://accounts.google.com/o/oauth2/v2/auth".to_string(),
+            token_url: "https://oauth2.googleapis.com/token".to_string(),
+            userinfo_url: "https://openidconnect.googleapis.com/v1/userinfo".to_string(),
+            revocation_url: Some("https://oauth2.googleapis.com/revoke".to_string()),
+            scopes: [
+                "openid",
+                "email",
+                "profile",
+                "https://www.googleapis.com/auth/calendar.events.readonly",
+                "https://www.googleapis.com/auth/gmail.readonly",
+            ]
+            .into_iter()
+            .map(ToOwned::to_owned)
+            .collect(),
+            authorization_params: BTreeMap::from([
+                ("access_type".to_string(), "offline".to_string()),
+                ("include_granted_scopes".to_string(), "true".to_string()),
+                ("prompt".to_string(), "consent".to_string()),
+            ]),
+            account_claim: default_account_claim(),
+            client_secret_env: Some("GOOGLE_OAUTH_CLIENT_SECRET".to_string()),
+            bundled_client_secret: option_env!("GOOGLE_OAUTH_CLIENT_SECRET").map(ToOwned::to_owned),
+        }
+    }
+
+    pub fn microsoft() -> Self {
+        Self {
+            id: "microsoft".to_string(),
+            display_name: "Microsoft".to_string(),
+            authorization_url: "https://login.microsoftonline.com/common/oauth2/v2.0/authorize"
+                .to_string(),
+            token_url: "https://login.microsoftonline.com/common/oauth2/v2.0/token".to_string(),
+            userinfo_url: "https://graph.microsoft.com/oidc/userinfo".to_string(),
+            revocation_url: None,
+            scopes: [
+                "openid",
+                "email",
+                "profile",
+                "offline_access",
+                "https://graph.microsoft.com/Calendars.Read",
+            ]
+            .into_iter()
+            .map(ToOwned::to_owned)
+            .collect(),
+            authorization_params: BTreeMap::from([(
+                "prompt".to_string(),
+                "select_account".to_string(),
+            )]),
+            account_claim: default_account_claim(),
+            client_secret_env: None,
+            bundled_client_secret: None,
+        }
+    }
+
+    pub fn github() -> Self {
+        Self {
+            id: "github".to_string(),
+            display_name: "GitHub".to_string(),
+            authorization_url: "https://github.com/login/oauth/authorize".to_string(),
+            token_url: "https://github.com/login/oauth/access_token".to_string(),
+            userinfo_url: "https://api.github.com/user".to_string(),
+            revocation_url: None,
+            scopes: vec!["read:user".to_string()],
+            authorization_params: BTreeMap::new(),
+            account_claim: "login".to_string(),
+            client_secret_env: Some("GITHUB_OAUTH_CLIENT_SECRET".to_string()),
+            bundled_client_secret: None,
+        }
+    }
+
+    pub fn builtin(id: &str) -> Option<Self> {
+        match id {
+            "google" => Some(Self::google()),
+            "microsoft" => Some(Self::microsoft()),
+            "github" => Some(Self::github()),
+            _ => None,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let valid_id = !self.id.is_empty()
+            && self
+                .id
+                .chars()
+                .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '-');
+        if !valid_id {
+            return Err(format!("Invalid OAuth provider id: {}", self.id));
+        }
+        for url in [&self.authorization_url, &self.token_url, &self.userinfo_url]
+            .into_iter()
+            .chain(self.revocation_url.as_ref())
+        {
+            Url::parse(url)
+                .map_err(|e| format!("Invalid {} OAuth URL {url}: {e}", self.display_name))?;
+        }
+        Ok(())
+    }
+
+    fn keyring_service(&self) -> String {
+        let prefix = if cfg!(debug_assertions) {
+            OAUTH_KEYRING_SERVICE_PREFIX_DEV
+        } else {
+            OAUTH_KEYRING_SERVICE_PREFIX
+        };
+        format!("{prefix}.{}-oauth", self.id)
+    }
+
+    fn client_secret(&self) -> Option<String> {
+        self.client_secret_env
+            .as_deref()
+            .and_then(|name| env::var(name).ok())
+            .or_else(|| self.bundled_client_secret.clone())
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Builds the authorization-code + PKCE request the user opens in their browser.
+    pub fn authorization_request(
+        &self,
+        client_id: &str,
+        redirect_uri: &str,
+        options: &OAuthAuthorizeOptions,
+    ) -> Result<OAuthAuthorizationRequest, String> {
+        let client_id = client_id.trim();
+        if client_id.is_empty() {
+            return Err(format!(
+                "{} sign-in is not configured yet.",
+                self.display_name
+            ));
+        }
+
+        let code_verifier = random_url_token(64)?;
+        let state = random_url_token(32)?;
+        let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+        let scopes = options.scopes.as_ref().unwrap_or(&self.scopes);
+
+        let mut params = self.authorization_params.clone();
+        params.insert("client_id".to_string(), client_id.to_string());
+        params.insert("code_challenge".to_string(), code_challenge);
+        params.insert("code_challenge_method".to_string(), "S256".to_string());
+        params.insert("redirect_uri".to_string(), redirect_uri.to_string());
+        params.insert("response_type".to_string(), "code".to_string());
+        params.insert("state".to_string(), state.clone());
+        if !scopes.is_empty() {
+            params.insert("scope".to_string(), scopes.join(" "));
+        }
+        if let Some(login_hint) = options.login_hint.as_deref().map(str::trim) {
+            if !login_hint.is_empty() {
+                params.insert("login_hint".to_string(), login_hint.to_string());
+            }
+        }
+        if let Some(prompt) = options.prompt.as_deref().map(str::trim) {
+            if !prompt.is_empty() {
+                params.insert("prompt".to_string(), prompt.to_string());
+            }
+        }
+
+        let authorization_url = Url::parse_with_params(&self.authorization_url, &params)
+            .map_err(|e| format!("Invalid {} authorization URL: {e}", self.display_name))?;
+
+        Ok(OAuthAuthorizationRequest {
+            authorization_url: authorization_url.to_string(),
+            state,
+            code_verifier,
+        })
+    }
+}
+
+/// Looks up a built-in provider by id unless the caller supplied its own configuration.
+pub fn resolve_oauth_provider(
+    provider_id: &str,
+    custom: Option<OAuthProviderConfig>,
+) -> Result<OAuthProviderConfig, String> {
+    let provider = match custom {
+        // A custom provider reusing a built-in id would share its keyring entry.
+        Some(provider) if OAuthProviderConfig::builtin(&provider.id).is_some() => {
+            return Err(format!(
+                "OAuth provider id {} is reserved for the built-in provider.",
+                provider.id
+            ));
+        }
+        Some(provider) => provider,
+        None => OAuthProviderConfig::builtin(provider_id.trim())
+            .ok_or(format!("Unknown OAuth provider: {provider_id}"))?,
+    };
+    provider.validate()?;
+    Ok(provider)
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthProviderInput {
+    pub provider_id: String,
+    #[serde(default)]
+    pub provider: Option<OAuthProviderConfig>,
+}
+
+impl OAuthProviderInput {
+    pub fn resolve(self) -> Result<OAuthProviderConfig, String> {
+        resolve_oauth_provider(&self.provider_id, self.provider)
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthAuthorizeOptions {
+    #[serde(default)]
+    pub login_hint: Option<String>,
+    #[serde(default)]
+    pub prompt: Option<String>,
+    #[serde(default)]
+    pub scopes: Option<Vec<String>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct OAuthAuthorizationRequest {
+    pub authorization_url: String,
+    pub state: String,
+    pub code_verifier: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthLegacySessionInput {
+    #[serde(default)]
+    pub access_token: String,
+    pub access_token_expires_at_ms: u64,
+    pub refresh_token: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct OAuthCodeExchange {
+    pub code: String,
+    pub code_verifier: String,
+    pub redirect_uri: String,
+    pub expected_account: Option<String>,
+    pub legacy_account: Option<String>,
+    pub legacy_session: Option<OAuthLegacySessionInput>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthAccessTokenRequest {
+    pub account: String,
+    #[serde(default)]
+    pub granted_scopes: Vec<String>,
+    #[serde(default)]
+    pub legacy_account: Option<String>,
+    #[serde(default)]
+    pub legacy_session: Option<OAuthLegacySessionInput>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthConnectionResult {
+    pub access_token_expires_at_ms: u64,
+    pub account: String,
+    pub granted_scopes: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthAccessTokenResult {
+    pub account: String,
+    pub access_token: String,
+    pub access_token_expires_at_ms: u64,
+    pub granted_scopes: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthStoredSession {
+    pub access_token: String,
+    pub access_token_expires_at_ms: u64,
+    pub refresh_token: String,
+}
+
+pub type OAuthStoredSessions = HashMap<String, OAuthStoredSession>;
+
+#[derive(Clone, Debug, Deserialize)]
+struct OAuthTokenResponse {
+    access_token: Option<String>,
+    expires_in: Option<u64>,
+    refresh_token: Option<String>,
+    scope: Option<String>,
+}
+
+/// Persistence for the per-account sessions of one provider.
+pub trait OAuthSessionStore {
+    /// Returns the sessions keyed by account, plus a single-account session if the store still
+    /// holds the format used before multiple accounts were supported.
+    fn load(&self) -> Result<(OAuthStoredSessions, Option<OAuthStoredSession>), String>;
+    fn save(&self, sessions: &OAuthStoredSessions) -> Result<(), String>;
+    fn clear(&self) -> Result<(), String>;
+}
+
+fn parse_stored_sessions(
+    raw: &str,
+    provider_name: &str,
+) -> Result<(OAuthStoredSessions, Option<OAuthStoredSession>), String> {
+    if raw.trim().is_empty() {
+        return Ok((HashMap::new(), None));
+    }
+    if let Ok(sessions) = serde_json::from_str::<OAuthStoredSessions>(raw) {
+        return Ok((sessions, None));
+    }
+    if let Ok(session) = serde_json::from_str::<OAuthStoredSession>(raw) {
+        return Ok((HashMap::new(), Some(session)));
+    }
+    Err(format!(
+        "Could not read secure {provider_name} session storage."
+    ))
+}
+
+pub struct KeyringSessionStore {
+    service: String,
+    provider_name: String,
+}
+
+impl KeyringSessionStore {
+    pub fn for_provider(provider: &OAuthProviderConfig) -> Self {
+        Self {
+            service: provider.keyring_service(),
+            provider_name: provider.display_name.clone(),
+        }
+    }
+
+    fn entry(&self) -> Result<KeyringEntry, String> {
+        KeyringEntry::new(&self.service, OAUTH_KEYRING_ACCOUNT).map_err(|e| {
+            format!(
+                "Could not access secure {} session storage: {e}",
+                self.provider_name
+            )
+        })
+    }
+}
+
+impl OAuthSessionStore for KeyringSessionStore {
+    fn load(&self) -> Result<(OAuthStoredSessions, Option<OAuthStoredSession>), String> {
+        match self.entry()?.get_password() {
+            Ok(raw) => parse_stored_sessions(&raw, &self.provider_name),
+            Err(KeyringError::NoEntry) => Ok((HashMap::new(), None)),
+            Err(e) => Err(format!(
+                "Could not access secure {} session storage: {e}",
+                self.provider_name
+            )),
+        }
+    }
+
+    fn save(&self, sessions: &OAuthStoredSessions) -> Result<(), String> {
+        let payload = serde_json::to_string(sessions)
+            .map_err(|e| format!("Could not serialize {} session: {e}", self.provider_name))?;
+        self.entry()?.set_password(&payload).map_err(|e| {
+            format!(
+                "Could not store {} session securely: {e}",
+                self.provider_name
+            )
+        })
+    }
+
+    fn clear(&self) -> Result<(), String> {
+        match self.entry()?.delete_credential() {
+            Ok(()) | Err(KeyringError::NoEntry) => Ok(()),
+            Err(e) => Err(format!(
+                "Could not clear secure {} session storage: {e}",
+                self.provider_name
+            )),
+        }
+    }
+}
+
+/// Authorization-code exchange, refresh, revocation, and multi-account bookkeeping for a single
+/// provider.
+pub struct OAuthAccountManager<S = KeyringSessionStore> {
+    provider: OAuthProviderConfig,
+    client_id: String,
+    store: S,
+    http: HttpClient,
+}
+
+impl OAuthAccountManager<KeyringSessionStore> {
+    pub fn new(provider: OAuthProviderConfig, client_id: &str) -> Result<Self, String> {
+        let store = KeyringSessionStore::for_provider(&provider);
+        Self::with_store(provider, client_id, store)
+    }
+}
+
+impl<S: OAuthSessionStore> OAuthAccountManager<S> {
+    pub fn with_store(
+        provider: OAuthProviderConfig,
+        client_id: &str,
+        store: S,
+    ) -> Result<Self, String> {
+        provider.validate()?;
+        let http = HttpClient::builder()
+            .user_agent(OAUTH_USER_AGENT)
+            .build()
+            .map_err(|e| {
+                format!(
+                    "Could not start {} OAuth client: {e}",
+                    provider.display_name
+                )
+            })?;
+        Ok(Self {
+            provider,
+            client_id: client_id.trim().to_string(),
+            store,
+            http,
+        })
+    }
+
+    pub fn provider(&self) -> &OAuthProviderConfig {
+        &self.provider
+    }
+
+    pub fn complete_authorization(
+        &self,
+        input: OAuthCodeExchange,
+    ) -> Result<OAuthConnectionResult, String> {
+        let name = &self.provider.display_name;
+        if self.client_id.is_empty() {
+            return Err(format!("{name} sign-in is not configured yet."));
+        }
+
+        let token = self.request_token(vec![
+            ("client_id".to_string(), self.client_id.clone()),
+            ("code".to_string(), input.code),
+            ("code_verifier".to_string(), input.code_verifier),
+            ("grant_type".to_string(), "authorization_code".to_string()),
+            ("redirect_uri".to_string(), input.redirect_uri),
+        ])?;
+        let access_token = token
+            .access_token
+            .filter(|value| !value.trim().is_empty())
+            .ok_or(format!("{name} did not return an access token."))?;
+        let access_token_expires_at_ms = access_token_expires_at_ms(token.expires_in)?;
+        let granted_scopes = build_granted_scopes(token.scope, &self.provider.scopes);
+        let account = self.fetch_account(&access_token)?;
+        if let Some(expected_account) = input
+            .expected_account
+            .as_deref()
+            .and_then(normalize_account)
+        {
+            if account != expected_account {
+                return Err(format!(
+                    "{name} connected a different account than expected."
+                ));
+            }
+        }
+
+        let mut sessions =
+            self.load_sessions(input.legacy_account.as_deref(), input.legacy_session)?;
+        let refresh_token = token
+            .refresh_token
+            .filter(|value| !value.trim().is_empty())
+            .or_else(|| {
+                sessions
+                    .get(&account)
+                    .map(|session| session.refresh_token.clone())
+                    .filter(|value| !value.is_empty())
+            });
+        let refresh_token = match (refresh_token, token.expires_in) {
+            (Some(refresh_token), _) => refresh_token,
+            (None, None) => String::new(),
+            (None, Some(_)) => {
+                return Err(format!(
+                    "{name} did not return a refresh token. Reconnect {name} and try again."
+                ))
+            }
+        };
+
+        sessions.insert(
+            account.clone(),
+            OAuthStoredSession {
+                access_token,
+                access_token_expires_at_ms,
+                refresh_token,
+            },
+        );
+        self.store.save(&sessions)?;
+
+        Ok(OAuthConnectionResult {
+            access_token_expires_at_ms,
+            account,
+            granted_scopes,
+        })
+    }
+
+    /// Returns a cached access token for the account, refreshing it when it is about to expire.
+    pub fn access_token(
+        &self,
+        input: OAuthAccessTokenRequest,
+    ) -> Result<OAuthAccessTokenResult, String> {
+        let name = &self.provider.display_name;
+        let reconnect = || format!("Reconnect {name} to refresh access.");
+        if self.client_id.is_empty() {
+            return Err(reconnect());
+        }
+        let account = normalize_account(&input.account)
+            .ok_or(format!("Select a {name} account to refresh access."))?;
+
+        let mut sessions =
+            self.load_sessions(input.legacy_account.as_deref(), input.legacy_session)?;
+        let mut session = sessions.get(&account).cloned().ok_or_else(reconnect)?;
+        if !session.access_token.trim().is_empty()
+            && session.access_token_expires_at_ms
+                > current_time_ms()?.saturating_add(OAUTH_ACCESS_TOKEN_BUFFER_MS)
+        {
+            return Ok(OAuthAccessTokenResult {
+                account: input.account.trim().to_string(),
+                access_token: session.access_token,
+                access_token_expires_at_ms: session.access_token_expires_at_ms,
+                granted_scopes: input.granted_scopes,
+            });
+        }
+        if session.refresh_token.is_empty() {
+            return Err(reconnect());
+        }
+
+        let token = self.request_token(vec![
+            ("client_id".to_string(), self.client_id.clone()),
+            ("grant_type".to_string(), "refresh_token".to_string()),
+            ("refresh_token".to_string(), session.refresh_token.clone()),
+        ])?;
+        session.access_token = token
+            .access_token
+            .filter(|value| !value.trim().is_empty())
+            .ok_or(format!("{name} did not return an access token."))?;
+        session.access_token_expires_at_ms = access_token_expires_at_ms(token.expires_in)?;
+        if let Some(refresh_token) = token.refresh_token.filter(|value| !value.trim().is_empty()) {
+            session.refresh_token = refresh_token;
+        }
+        sessions.insert(account, session.clone());
+        self.store.save(&sessions)?;
+
+        Ok(OAuthAccessTokenResult {
+            account: input.account.trim().to_string(),
+            access_token: session.access_token,
+            access_token_expires_at_ms: session.access_token_expires_at_ms,
+            granted_scopes: build_granted_scopes(token.scope, &input.granted_scopes),
+        })
+    }
+
+    /// Forgets the account locally and, when the provider supports it, revokes its grant.
+    /// Revocation is best effort so a dead network never leaves an account stuck connected.
+    pub fn disconnect(&self, account: &str) -> Result<(), String> {
+        let name = &self.provider.display_name;
+        let account =
+            normalize_account(account).ok_or(format!("Select a {name} account to disconnect."))?;
+        let (mut sessions, legacy_session) = self.store.load()?;
+        let Some(session) = sessions.remove(&account).or(legacy_session) else {
+            return Ok(());
+        };
+
+        self.revoke(&session);
+        if sessions.is_empty() {
+            self.store.clear()
+        } else {
+            self.store.save(&sessions)
+        }
+    }
+
+    pub fn accounts(&self) -> Result<Vec<String>, String> {
+        let (sessions, _) = self.store.load()?;
+        let mut accounts = sessions.into_keys().collect::<Vec<_>>();
+        accounts.sort();
+        Ok(accounts)
+    }
+
+    fn revoke(&self, session: &OAuthStoredSession) {
+        let Some(revocation_url) = self.provider.revocation_url.as_deref() else {
+            return;
+        };
+        let token = if session.refresh_token.is_empty() {
+            &session.access_token
+        } else {
+            &session.refresh_token
+        };
+        if token.trim().is_empty() {
+            return;
+        }
+        let _ = self
+            .http
+            .post(revocation_url)
+            .form(&[("token", token.as_str())])
+            .send();
+    }
+
+    /// Loads stored sessions, folding in a session saved before multi-account support under the
+    /// account it belonged to.
+    fn load_sessions(
+        &self,
+        legacy_account: Option<&str>,
+        legacy_session: Option<OAuthLegacySessionInput>,
+    ) -> Result<OAuthStoredSessions, String> {
+        let (mut sessions, legacy_keyring_session) = self.store.load()?;
+        let Some(legacy_account) = legacy_account.and_then(normalize_account) else {
+            return Ok(sessions);
+        };
+        if sessions.contains_key(&legacy_account) {
+            return Ok(sessions);
+        }
+        let Some(legacy) = legacy_keyring_session.or_else(|| build_legacy_session(legacy_session))
+        else {
+            return Ok(sessions);
+        };
+
+        sessions.insert(legacy_account, legacy);
+        self.store.save(&sessions)?;
+        Ok(sessions)
+    }
+
+    fn request_token(&self, mut body: Vec<(String, String)>) -> Result<OAuthTokenResponse, String> {
+        let name = &self.provider.display_name;
+        if let Some(client_secret) = self.provider.client_secret() {
+            body.push(("client_secret".to_string(), client_secret));
+        }
+        let response = self
+            .http
+            .post(&self.provider.token_url)
+            .header(ACCEPT, "application/json")
+            .form(&body)
+            .send()
+            .map_err(|e| format!("{name} token request failed: {e}"))?;
+        if !response.status().is_success() {
+            return Err(parse_provider_error(name, response));
+        }
+
+        let payload = response
+            .json::<Value>()
+            .map_err(|e| format!("Could not parse {name} token response: {e}"))?;
+        // GitHub reports token errors with a 200 status.
+        if payload.get("access_token").is_none() && payload.get("error").is_some() {
+            return Err(describe_provider_error(name, &payload));
+        }
+        serde_json::from_value(payload)
+            .map_err(|e| format!("Could not parse {name} token response: {e}"))
+    }
+
+    fn fetch_account(&self, access_token: &str) -> Result<String, String> {
+        let name = &self.provider.display_name;
+        let response = self
+            .http
+            .get(&self.provider.userinfo_url)
+            .header(ACCEPT, "application/json")
+            .bearer_auth(access_token)
+            .send()
+            .map_err(|e| format!("{name} user info request failed: {e}"))?;
+        if !response.status().is_success() {
+            return Err(parse_provider_error(name, response));
+        }
+
+        let payload = response
+            .json::<Value>()
+            .map_err(|e| format!("Could not parse {name} user info response: {e}"))?;
+        payload
+            .get(&self.provider.account_claim)
+            .and_then(Value::as_str)
+            .and_then(normalize_account)
+            .ok_or(format!(
+                "{name} did not return an account {}.",
+                self.provider.account_claim
+            ))
+    }
+}
+
+fn normalize_account(account: &str) -> Option<String> {
+    let normalized = account.trim().to_lowercase();
+    if normalized.is_empty() {
+        None
+    } else {
+        Some(normalized)
+    }
+}
+
+fn build_legacy_session(
+    legacy_session: Option<OAuthLegacySessionInput>,
+) -> Option<OAuthStoredSession> {
+    let legacy = legacy_session?;
+    let refresh_token = legacy.refresh_token.trim().to_string();
+    if refresh_token.is_empty() {
+        return None;
+    }
+
+    Some(OAuthStoredSession {
+        access_token: legacy.access_token.trim().to_string(),
+        access_token_expires_at_ms: legacy.access_token_expires_at_ms,
+        refresh_token,
+    })
+}
+
+fn parse_provider_error(provider_name: &str, response: HttpResponse) -> String {
+    let payload = response.json::<Value>().unwrap_or(Value::Null);
+    describe_provider_error(provider_name, &payload)
+}
+
+fn describe_provider_error(provider_name: &str, payload: &Value) -> String {
+    let error = payload
+        .get("error")
+        .and_then(|value| value.as_str())
+        .unwrap_or("request_failed");
+    let description = payload
+        .get("error_description")
+        .and_then(|value| value.as_str())
+        .or_else(|| {
+            payload
+                .get("error")
+                .and_then(|value| value.get("message"))
+                .and_then(|value| value.as_str())
+        })
+        .unwrap_or_default();
+    if description.is_empty() {
+        format!("{provider_name} {error}.")
+    } else {
+        format!("{provider_name} {error}: {description}")
+    }
+}
+
+fn build_granted_scopes(scope_value: Option<String>, fallback_scopes: &[String]) -> Vec<String> {
+    let Some(scope_value) = scope_value else {
+        return fallback_scopes.to_vec();
+    };
+    let normalized = scope_value
+        .split([' ', ','])
+        .map(str::trim)
+        .filter(|scope| !scope.is_empty())
+        .map(ToOwned::to_owned)
+        .collect::<HashSet<_>>();
+
+    if normalized.is_empty() {
+        fallback_scopes.to_vec()
+    } else {
+        let mut scopes = normalized.into_iter().collect::<Vec<_>>();
+        scopes.sort();
+        scopes
+    }
+}
+
+fn current_time_ms() -> Result<u64, String> {
+    Ok(SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| e.to_string())?
+        .as_millis() as u64)
+}
+
+fn access_token_expires_at_ms(expires_in: Option<u64>) -> Result<u64, String> {
+    let expires_in = expires_in.unwrap_or(OAUTH_NON_EXPIRING_TOKEN_TTL_SECS);
+    expires_in
+        .checked_mul(1000)
+        .and_then(|expires_in_ms| current_time_ms().ok()?.checked_add(expires_in_ms))
+        .ok_or_else(|| format!("OAuth token expiry is out of range: {expires_in}s"))
+}
+
+fn random_url_token(byte_length: usize) -> Result<String, String> {
+    let mut bytes = vec![0_u8; byte_length];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("Could not generate OAuth state: {e}"))?;
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthCallbackPayload {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthAuthorizationSession {
+    pub session_id: String,
+    pub authorization_url: String,
+    pub redirect_uri: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartOAuthAuthorizationInput {
+    #[serde(flatten)]
+    pub provider: OAuthProviderInput,
+    pub client_id: String,
+    #[serde(flatten)]
+    pub options: OAuthAuthorizeOptions,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinishOAuthAuthorizationInput {
+    pub session_id: String,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub expected_account: Option<String>,
+    #[serde(default)]
+    pub legacy_account: Option<String>,
+    #[serde(default)]
+    pub legacy_session: Option<OAuthLegacySessionInput>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnsureOAuthAccessTokenInput {
+    #[serde(flatten)]
+    pub provider: OAuthProviderInput,
+    pub client_id: String,
+    #[serde(flatten)]
+    pub request: OAuthAccessTokenRequest,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisconnectOAuthAccountInput {
+    #[serde(flatten)]
+    pub provider: OAuthProviderInput,
+    pub account: String,
+}
+
+/// An authorization code received on the loopback redirect, with everything needed to redeem
+/// it.
+pub struct OAuthAuthorizedCode {
+    pub provider: OAuthProviderConfig,
+    pub client_id: String,
+    pub exchange: OAuthCodeExchange,
+}
+
+struct OAuthPendingSession {
+    cancelled: AtomicBool,
+    receiver: Mutex<mpsc::Receiver<OAuthCallbackPayload>>,
+    provider: OAuthProviderConfig,
+    client_id: String,
+    redirect_uri: String,
+    request: OAuthAuthorizationRequest,
+}
+
+/// In-flight authorizations, each waiting on its own loopback redirect listener.
+#[derive(Default)]
+pub struct OAuthState {
+    sessions: Mutex<HashMap<String, Arc<OAuthPendingSession>>>,
+}
+
+impl OAuthState {
+    /// Binds a loopback redirect listener and prepares the authorization URL for it.
+    /// `on_callback` runs on the listener thread once the browser has been redirected back.
+    pub fn start(
+        &self,
+        provider: OAuthProviderConfig,
+        client_id: &str,
+        options: &OAuthAuthorizeOptions,
+        on_callback: impl FnOnce() + Send + 'static,
+    ) -> Result<OAuthAuthorizationSession, String> {
+        let listener = TcpListener::bind("127.0.0.1:0").map_err(|e| e.to_string())?;
+        let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+        let redirect_uri = format!("http://127.0.0.1:{port}/");
+        let request = provider.authorization_request(client_id, &redirect_uri, options)?;
+        let session_id = format!(
+            "{}-oauth-{}-{}",
+            provider.id,
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_err(|e| e.to_string())?
+                .as_nanos()
+        );
+
+        let (sender, receiver) = mpsc::channel();
+        let provider_name = provider.display_name.clone();
+        let authorization_url = request.authorization_url.clone();
+        self.lock()?.insert(
+            session_id.clone(),
+            Arc::new(OAuthPendingSession {
+                cancelled: AtomicBool::new(false),
+                receiver: Mutex::new(receiver),
+                provider,
+                client_id: client_id.trim().to_string(),
+                redirect_uri: redirect_uri.clone(),
+                request,
+            }),
+        );
+
+        std::thread::spawn(move || {
+            let payload = receive_oauth_callback(listener, &provider_name);
+            let _ = sender.send(payload);
+            on_callback();
+        });
+
+        Ok(OAuthAuthorizationSession {
+            session_id,
+            authorization_url,
+            redirect_uri,
+        })
+    }
+
+    /// Blocks until the redirect arrives, then checks its state against the one we issued.
+    pub fn wait_for_code(
+        &self,
+        session_id: &str,
+        timeout_ms: Option<u64>,
+    ) -> Result<OAuthAuthorizedCode, String> {
+        let session = self
+            .lock()?
+            .get(session_id)
+            .cloned()
+            .ok_or("OAuth session not found.".to_string())?;
+        let name = session.provider.display_name.clone();
+        let payload = self.receive(&session, timeout_ms);
+        let _ = self.lock()?.remove(session_id);
+        let payload = payload?;
+
+        if let Some(error) = payload.error {
+            return Err(error);
+        }
+        let code = payload
+            .code
+            .filter(|code| !code.is_empty())
+            .ok_or(format!("{name} did not return an authorization code."))?;
+        if payload.state.as_deref() != Some(session.request.state.as_str()) {
+            return Err(format!("{name} OAuth state mismatch. Please try again."));
+        }
+
+        Ok(OAuthAuthorizedCode {
+            provider: session.provider.clone(),
+            client_id: session.client_id.clone(),
+            exchange: OAuthCodeExchange {
+                code,
+                code_verifier: session.request.code_verifier.clone(),
+                redirect_uri: session.redirect_uri.clone(),
+                ..OAuthCodeExchange::default()
+            },
+        })
+    }
+
+    /// Waits for the redirect and redeems the code, persisting the session in the keyring.
+    pub fn finish(
+        &self,
+        input: FinishOAuthAuthorizationInput,
+    ) -> Result<OAuthConnectionResult, String> {
+        let authorized = self.wait_for_code(&input.session_id, input.timeout_ms)?;
+        let manager = OAuthAccountManager::new(authorized.provider, &authorized.client_id)?;
+        manager.complete_authorization(OAuthCodeExchange {
+            expected_account: input.expected_account,
+            legacy_account: input.legacy_account,
+            legacy_session: input.legacy_session,
+            ..authorized.exchange
+        })
+    }
+
+    pub fn cancel(&self, session_id: &str) -> Result<(), String> {
+        if let Some(session) = self.lock()?.remove(session_id) {
+            session.cancelled.store(true, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    fn receive(
+        &self,
+        session: &OAuthPendingSession,
+        timeout_ms: Option<u64>,
+    ) -> Result<OAuthCallbackPayload, String> {
+        let name = &session.provider.display_name;
+        let started_at = SystemTime::now();
+        let timeout = Duration::from_millis(timeout_ms.unwrap_or(OAUTH_DEFAULT_TIMEOUT_MS));
+
+        loop {
+            if session.cancelled.load(Ordering::Relaxed) {
+                return Err(format!("{name} authorization cancelled."));
+            }
+            if started_at.elapsed().map_err(|e| e.to_string())? >= timeout {
+                return Err(format!("Timed out waiting for {name} authorization."));
+            }
+
+            let callback = {
+                let receiver = session
+                    .receiver
+                    .lock()
+                    .map_err(|_| "OAuth state is unavailable.".to_string())?;
+                receiver.recv_timeout(Duration::from_millis(250))
+            };
+            match callback {
+                Ok(payload) => return Ok(payload),
+                Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    return Err(format!(
+                        "{name} OAuth session ended before authorization completed."
+                    ));
+                }
+            }
+        }
+    }
+
+    fn lock(
+        &self,
+    ) -> Result<std::sync::MutexGuard<'_, HashMap<String, Arc<OAuthPendingSession>>>, String> {
+        self.sessions
+            .lock()
+            .map_err(|_| "OAuth state is unavailable.".to_string())
+    }
+}
+
+fn decode_url_component(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'+' => {
+                decoded.push(b' ');
+                index += 1;
+            }
+            b'%' if index + 2 < bytes.len() => {
+                let hi = bytes[index + 1] as char;
+                let lo = bytes[index + 2] as char;
+                if let (Some(hi), Some(lo)) = (hi.to_digit(16), lo.to_digit(16)) {
+                    decoded.push(((hi * 16) + lo) as u8);
+                    index += 3;
+                } else {
+                    decoded.push(bytes[index]);
+                    index += 1;
+                }
+            }
+            value => {
+                decoded.push(value);
+                index += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn parse_oauth_callback(provider_name: &str, path: &str) -> OAuthCallbackPayload {
+    let query = path
+        .split_once('?')
+        .map(|(_, query)| query)
+        .unwrap_or_default();
+    let mut params = HashMap::new();
+
+    for pair in query.split('&') {
+        if pair.is_empty() {
+            continue;
+        }
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        params.insert(decode_url_component(key), decode_url_component(value));
+    }
+
+    let error = params.get("error").cloned();
+    let code = params.get("code").cloned();
+    let state = params.get("state").cloned();
+
+    OAuthCallbackPayload {
+        code,
+        state,
+        error: error.or_else(|| {
+            if params.contains_key("code") {
+                None
+            } else {
+                Some(format!(
+                    "{provider_name} did not return an authorization code."
+                ))
+            }
+        }),
+    }
+}
+
+fn write_oauth_response(
+    stream: &mut std::net::TcpStream,
+    status: &str,
+    body: &str,
+) -> Result<(), String> {
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream
+        .write_all(response.as_bytes())
+        .map_err(|e| e.to_string())
+}
+
+fn oauth_callback_page(provider_name: &str, success: bool) -> String {
+    let offer_deep_link = success && !cfg!(debug_assertions);
+    let eyebrow = if success {
+        format!("{provider_name} connected")
+    } else {
+        format!("{provider_name} sign-in failed")
+    };
+    let title = if success {
+        "Account connected."
+    } else {
+        "Connection didn't go through."
+    };
+    let description = if success {
+        format!("Your {provider_name} account is now connected to Philo.")
+    } else {
+        format!("Philo could not finish connecting your {provider_name} account.")
+    };
+    let detail = if offer_deep_link {
+        "Philo should reopen automatically. If it stays in the background, open it below."
+    } else if success {
+        "Philo should come forward automatically. If it stays in the background, switch back to the app."
+    } else {
+        "Close this window and try again from Philo."
+    };
+    let panel_class = if success {
+        "panel panel-success"
+    } else {
+        "panel panel-error"
+    };
+    let action_markup = if offer_deep_link {
+        r#"<div class="actions">
+          <a class="open-button" href="philo://oauth-connected">Open Philo</a>
+        </div>"#
+            .to_string()
+    } else {
+        String::new()
+    };
+    let auto_open_script = if offer_deep_link {
+        r#"<script>
+      window.setTimeout(() => {
+        window.location.href = "philo://oauth-connected";
+      }, 300);
+    </script>"#
+            .to_string()
+    } else {
+        String::new()
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+  <head>
+    <meta charset="utf-8" />
+    <meta name="viewport" content="width=device-width, initial-scale=1" />
+    <title>{title} | Philo</title>
+    <link rel="preconnect" href="https://fonts.googleapis.com" />
+    <link rel="preconnect" href="https://fonts.gstatic.com" crossorigin />
+    <link
+      href="https://fonts.googleapis.com/css2?family=IBM+Plex+Mono:wght@400;500&display=swap"
+      rel="stylesheet"
+    />
+    <style>
+      :root {{
+        color-scheme: light;
+        --bg: #f4f4f1;
+        --bg-soft: rgba(255, 255, 255, 0.72);
+        --line: #e2e2db;
+        --text: #252525;
+        --muted: #646460;
+        --accent: #5f70d8;
+        --success: #2f7a58;
+        --success-bg: rgba(47, 122, 88, 0.08);
+        --error: #a14e42;
+        --error-bg: rgba(161, 78, 66, 0.08);
+      }}
+
+      * {{
+        box-sizing: border-box;
+      }}
+
+      body {{
+        margin: 0;
+        min-height: 100vh;
+        font-family: "IBM Plex Mono", "SFMono-Regular", Menlo, monospace;
+        background: linear-gradient(150deg, #f6f6f4 0%, #efefeb 100%);
+        color: var(--text);
+        position: relative;
+        overflow: hidden;
+      }}
+
+      .ambient {{
+        position: fixed;
+        inset: 0;
+        pointer-events: none;
+        background:
+          radial-gradient(circle at 10% 12%, rgba(255, 255, 255, 0.95) 0%, transparent 32%),
+          radial-gradient(circle at 92% 88%, rgba(95, 112, 216, 0.16) 0%, transparent 34%);
+        opacity: 0.95;
+      }}
+
+      .shell {{
+        position: relative;
+        min-height: 100vh;
+        display: grid;
+        place-items: center;
+        padding: 32px 20px;
+      }}
+
+      .frame {{
+        width: min(100%, 560px);
+      }}
+
+      .brand {{
+        margin: 0 0 14px;
+        font-size: 12px;
+        font-weight: 500;
+        letter-spacing: 0.08em;
+        text-transform: uppercase;
+        color: #3f3f3d;
+      }}
+
+      .panel {{
+        border: 1px solid var(--line);
+        border-radius: 24px;
+        padding: 28px;
+        background: var(--bg-soft);
+        backdrop-filter: blur(18px);
+        box-shadow: 0 20px 60px rgba(32, 32, 29, 0.08);
+      }}
+
+      .panel-success {{
+        box-shadow:
+          0 20px 60px rgba(32, 32, 29, 0.08),
+          inset 0 0 0 1px rgba(47, 122, 88, 0.08);
+      }}
+
+      .panel-error {{
+        box-shadow:
+          0 20px 60px rgba(32, 32, 29, 0.08),
+          inset 0 0 0 1px rgba(161, 78, 66, 0.08);
+      }}
+
+      .eyebrow {{
+        display: inline-flex;
+        align-items: center;
+        gap: 8px;
+        margin: 0;
+        padding: 7px 10px;
+        border-radius: 999px;
+        font-size: 12px;
+        font-weight: 500;
+        letter-spacing: 0.04em;
+        text-transform: uppercase;
+      }}
+
+      .panel-success .eyebrow {{
+        color: var(--success);
+        background: var(--success-bg);
+      }}
+
+      .panel-error .eyebrow {{
+        color: var(--error);
+        background: var(--error-bg);
+      }}
+
+      h1 {{
+        margin: 18px 0 10px;
+        font-size: clamp(28px, 4vw, 40px);
+        line-height: 1.1;
+        font-weight: 500;
+        letter-spacing: -0.04em;
+      }}
+
+      .description {{
+        margin: 0;
+        font-size: 14px;
+        line-height: 1.7;
+        color: #3f3f3d;
+      }}
+
+      .detail {{
+        margin: 20px 0 0;
+        padding-top: 16px;
+        border-top: 1px solid var(--line);
+        font-size: 12px;
+        line-height: 1.7;
+        color: var(--muted);
+      }}
+
+      .actions {{
+        display: flex;
+        margin-top: 22px;
+      }}
+
+      .open-button {{
+        display: inline-flex;
+        align-items: center;
+        justify-content: center;
+        min-height: 44px;
+        padding: 0 18px;
+        border-radius: 999px;
+        border: 1px solid var(--line);
+        background: #ffffff;
+        color: var(--text);
+        font-size: 14px;
+        font-weight: 500;
+        text-decoration: none;
+        transition: transform 160ms ease, border-color 160ms ease, box-shadow 160ms ease;
+      }}
+
+      .open-button:hover {{
+        border-color: rgba(95, 112, 216, 0.35);
+        box-shadow: 0 16px 28px rgba(95, 112, 216, 0.14);
+        transform: translateY(-1px);
+      }}
+
+      .open-button:active {{
+        transform: translateY(0);
+        box-shadow: none;
+      }}
+
+      @media (max-width: 640px) {{
+        .shell {{
+          padding: 18px;
+        }}
+
+        .panel {{
+          padding: 22px;
+          border-radius: 20px;
+        }}
+      }}
+    </style>
+  </head>
+  <body>
+    <div class="ambient"></div>
+    <main class="shell">
+      <div class="frame">
+        <p class="brand">Philo</p>
+        <section class="{panel_class}">
+          <p class="eyebrow">{eyebrow}</p>
+          <h1>{title}</h1>
+          <p class="description">{description}</p>
+          <p class="detail">{detail}</p>
+          {action_markup}
+        </section>
+      </div>
+    </main>
+    {auto_open_script}
+  </body>
+</html>"#
+    )
+}
+
+fn receive_oauth_callback(listener: TcpListener, provider_name: &str) -> OAuthCallbackPayload {
+    let Ok((mut stream, _addr)) = listener.accept() else {
+        return OAuthCallbackPayload {
+            code: None,
+            state: None,
+            error: Some(format!(
+                "Could not receive {provider_name} authorization callback."
+            )),
+        };
+    };
+
+    let mut buffer = [0_u8; 8192];
+    let request_size = match stream.read(&mut buffer) {
+        Ok(size) if size > 0 => size,
+        _ => {
+            let _ = write_oauth_response(
+                &mut stream,
+                "400 Bad Request",
+                &oauth_callback_page(provider_name, false),
+            );
+            return OAuthCallbackPayload {
+                code: None,
+                state: None,
+                error: Some(format!("{provider_name} callback request was empty.")),
+            };
+        }
+    };
+
+    let request = String::from_utf8_lossy(&buffer[..request_size]);
+    let path = request
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .unwrap_or("/");
+
+    let payload = parse_oauth_callback(provider_name, path);
+    let body = if payload.error.is_some() {
+        oauth_callback_page(provider_name, false)
+    } else {
+        oauth_callback_page(provider_name, true)
+    };
+    let status = if payload.error.is_some() {
+        "400 Bad Request"
+    } else {
+        "200 OK"
+    };
+
+    let _ = write_oauth_response(&mut stream, status, &body);
+    payload
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpStream;
+
+    struct MockRequest {
+        method: String,
+        path: String,
+        headers: String,
+        body: String,
+    }
+
+    impl MockRequest {
+        fn form(&self) -> HashMap<String, String> {
+            self.body
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .map(|(key, value)| (decode_url_component(key), decode_url_component(value)))
+                .collect()
+        }
+    }
+
+    type RequestLog = Arc<Mutex<Vec<String>>>;
+    type MockHandler = Box<dyn Fn(&MockRequest) -> (u16, String) + Send>;
+
+    /// A tiny authorization server: every request is recorded and answered by `handler`.
+    fn spawn_authorization_server(handler: MockHandler) -> (String, RequestLog) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+                let mut raw = Vec::new();
+                let mut buffer = [0_u8; 4096];
+                loop {
+                    let size = stream.read(&mut buffer).unwrap_or(0);
+                    raw.extend_from_slice(&buffer[..size]);
+                    let text = String::from_utf8_lossy(&raw).to_string();
+                    if let Some(header_end) = text.find("\r\n\r\n") {
+                        let content_length = text[..header_end]
+                            .lines()
+                            .find_map(|line| {
+                                let (name, value) = line.split_once(':')?;
+                                name.eq_ignore_ascii_case("content-length")
+                                    .then(|| value.trim().parse::<usize>().ok())?
+                            })
+                            .unwrap_or(0);
+                        if raw.len() >= header_end + 4 + content_length || size == 0 {
+                            break;
+                        }
+                    } else if size == 0 {
+                        break;
+                    }
+                }
+
+                let text = String::from_utf8_lossy(&raw).to_string();
+                let (head, body) = text.split_once("\r\n\r\n").unwrap_or((&text, ""));
+                let mut request_line = head.lines().next().unwrap_or("").split_whitespace();
+                let request = MockRequest {
+                    method: request_line.next().unwrap_or("").to_string(),
+                    path: request_line.next().unwrap_or("").to_string(),
+                    headers: head.to_lowercase(),
+                    body: body.to_string(),
+                };
+                recorded.lock().unwrap().push(format!(
+                    "{} {} {}",
+                    request.method, request.path, request.body
+                ));
+
+                let (status, body) = handler(&request);
+                let response = format!(
+                    "HTTP/1.1 {status} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        (base_url, requests)
+    }
+
+    #[derive(Default)]
+    struct MemorySessionStore {
+        raw: Mutex<String>,
+    }
+
+    impl MemorySessionStore {
+        fn with_raw(raw: &str) -> Self {
+            Self {
+                raw: Mutex::new(raw.to_string()),
+            }
+        }
+
+        fn sessions(&self) -> OAuthStoredSessions {
+            self.load().unwrap().0
+        }
+    }
+
+    impl OAuthSessionStore for MemorySessionStore {
+        fn load(&self) -> Result<(OAuthStoredSessions, Option<OAuthStoredSession>), String> {
+            parse_stored_sessions(&self.raw.lock().unwrap(), "Mock")
+        }
+
+        fn save(&self, sessions: &OAuthStoredSessions) -> Result<(), String> {
+            *self.raw.lock().unwrap() = serde_json::to_string(sessions).unwrap();
+            Ok(())
+        }
+
+        fn clear(&self) -> Result<(), String> {
+            self.raw.lock().unwrap().clear();
+            Ok(())
+        }
+    }
+
+    fn mock_provider(base_url: &str) -> OAuthProviderConfig {
+        OAuthProviderConfig {
+            id: "mock-idp".to_string(),
+            display_name: "Mock".to_string(),
+            authorization_url: format!("{base_url}/authorize"),
+            token_url: format!("{base_url}/token"),
+            userinfo_url: format!("{base_url}/userinfo"),
+            revocation_url: Some(format!("{base_url}/revoke")),
+            scopes: vec!["openid".to_string(), "calendar".to_string()],
+            authorization_params: BTreeMap::from([(
+                "access_type".to_string(),
+                "offline".to_string(),
+            )]),
+            account_claim: default_account_claim(),
+            client_secret_env: None,
+            bundled_client_secret: None,
+        }
+    }
+
+    /// Issues `code-1` for any PKCE challenge and only redeems it with the matching verifier.
+    fn pkce_authorization_server() -> (String, RequestLog, Arc<Mutex<String>>) {
+        let challenge = Arc::new(Mutex::new(String::new()));
+        let expected_challenge = challenge.clone();
+        let (base_url, requests) = spawn_authorization_server(Box::new(move |request| {
+            match (request.method.as_str(), request.path.as_str()) {
+                ("POST", "/token") => {
+                    let form = request.form();
+                    match form.get("grant_type").map(String::as_str) {
+                        Some("authorization_code") => {
+                            let verifier = form.get("code_verifier").cloned().unwrap_or_default();
+                            let challenge =
+                                URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+                            if form.get("code").map(String::as_str) != Some("code-1")
+                                || challenge != *expected_challenge.lock().unwrap()
+                            {
+                                return (
+                                    400,
+                                    r#"{"error":"invalid_grant","error_description":"PKCE verification failed"}"#
+                                        .to_string(),
+                                );
+                            }
+                            (
+                                200,
+                                r#"{"access_token":"access-1","expires_in":3600,"refresh_token":"refresh-1","scope":"openid calendar"}"#
+                                    .to_string(),
+                            )
+                        }
+                        Some("refresh_token") => (
+                            200,
+                            r#"{"access_token":"access-2","expires_in":3600}"#.to_string(),
+                        ),
+                        _ => (400, r#"{"error":"unsupported_grant_type"}"#.to_string()),
+                    }
+                }
+                ("GET", "/userinfo") => {
+                    if request.headers.contains("authorization: bearer access-1") {
+                        (200, r#"{"email":"Alex@Example.com"}"#.to_string())
+                    } else {
+                        (401, r#"{"error":"invalid_token"}"#.to_string())
+                    }
+                }
+                ("POST", "/revoke") => (200, "{}".to_string()),
+                _ => (404, "{}".to_string()),
+            }
+        }));
+        (base_url, requests, challenge)
+    }
+
+    fn query_param(url: &str, name: &str) -> Option<String> {
+        Url::parse(url)
+            .unwrap()
+            .query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    }
+
+    #[test]
+    fn completes_pkce_flow_through_loopback_redirect() {
+        let (base_url, _, challenge) = pkce_authorization_server();
+        let state = OAuthState::default();
+        let session = state
+            .start(
+                mock_provider(&base_url),
+                "client-1",
+                &OAuthAuthorizeOptions {
+                    login_hint: Some("alex@example.com".to_string()),
+                    ..OAuthAuthorizeOptions::default()
+                },
+                || {},
+            )
+            .unwrap();
+
+        let url = &session.authorization_url;
+        assert!(url.starts_with(&format!("{base_url}/authorize?")));
+        assert_eq!(query_param(url, "client_id").as_deref(), Some("client-1"));
+        assert_eq!(query_param(url, "access_type").as_deref(), Some("offline"));
+        assert_eq!(
+            query_param(url, "code_challenge_method").as_deref(),
+            Some("S256")
+        );
+        assert_eq!(
+            query_param(url, "scope").as_deref(),
+            Some("openid calendar")
+        );
+        assert_eq!(
+            query_param(url, "login_hint").as_deref(),
+            Some("alex@example.com")
+        );
+        assert_eq!(
+            query_param(url, "redirect_uri").as_deref(),
+            Some(session.redirect_uri.as_str())
+        );
+        *challenge.lock().unwrap() = query_param(url, "code_challenge").unwrap();
+
+        // Play the browser: follow the redirect back to the loopback listener.
+        let issued_state = query_param(url, "state").unwrap();
+        let address = session
+            .redirect_uri
+            .trim_start_matches("http://")
+            .trim_end_matches('/');
+        let mut browser = TcpStream::connect(address).unwrap();
+        let redirect =
+            format!("GET /?code=code-1&state={issued_state} HTTP/1.1\r\nHost: {address}\r\n\r\n");
+        browser.write_all(redirect.as_bytes()).unwrap();
+        let mut page = String::new();
+        browser.read_to_string(&mut page).unwrap();
+        assert!(page.starts_with("HTTP/1.1 200 OK"));
+        assert!(page.contains("Mock connected"));
+
+        let authorized = state
+            .wait_for_code(&session.session_id, Some(5_000))
+            .unwrap();
+        let manager = OAuthAccountManager::with_store(
+            authorized.provider,
+            &authorized.client_id,
+            MemorySessionStore::default(),
+        )
+        .unwrap();
+        let connection = manager
+            .complete_authorization(OAuthCodeExchange {
+                expected_account: Some("alex@example.com".to_string()),
+                ..authorized.exchange
+            })
+            .unwrap();
+
+        assert_eq!(connection.account, "alex@example.com");
+        assert_eq!(connection.granted_scopes, vec!["calendar", "openid"]);
+        assert_eq!(manager.accounts().unwrap(), vec!["alex@example.com"]);
+        assert_eq!(
+            manager.store.sessions()["alex@example.com"].refresh_token,
+            "refresh-1"
+        );
+    }
+
+    #[test]
+    fn rejects_redirects_with_a_foreign_state() {
+        let state = OAuthState::default();
+        let session = state
+            .start(
+                mock_provider("http://127.0.0.1:9"),
+                "client-1",
+                &OAuthAuthorizeOptions::default(),
+                || {},
+            )
+            .unwrap();
+        let address = session
+            .redirect_uri
+            .trim_start_matches("http://")
+            .trim_end_matches('/');
+        let mut browser = TcpStream::connect(address).unwrap();
+        browser
+            .write_all(b"GET /?code=code-1&state=forged HTTP/1.1\r\n\r\n")
+            .unwrap();
+        let mut page = String::new();
+        browser.read_to_string(&mut page).unwrap();
+
+        let error = state
+            .wait_for_code(&session.session_id, Some(5_000))
+            .err()
+            .unwrap();
+        assert_eq!(error, "Mock OAuth state mismatch. Please try again.");
+    }
+
+    #[test]
+    fn rejects_code_exchange_with_the_wrong_verifier() {
+        let (base_url, _, challenge) = pkce_authorization_server();
+        *challenge.lock().unwrap() = "not-the-challenge".to_string();
+        let manager = OAuthAccountManager::with_store(
+            mock_provider(&base_url),
+            "client-1",
+            MemorySessionStore::default(),
+        )
+        .unwrap();
+
+        let error = manager
+            .complete_authorization(OAuthCodeExchange {
+                code: "code-1".to_string(),
+                code_verifier: "verifier".to_string(),
+                redirect_uri: "http://127.0.0.1:1/".to_string(),
+                ..OAuthCodeExchange::default()
+            })
+            .err()
+            .unwrap();
+        assert_eq!(error, "Mock invalid_grant: PKCE verification failed");
+        assert!(manager.accounts().unwrap().is_empty());
+    }
+
+    #[test]
+    fn refreshes_expired_tokens_and_keeps_accounts_separate() {
+        let (base_url, requests, _) = pkce_authorization_server();
+        let store = MemorySessionStore::default();
+        store
+            .save(&HashMap::from([
+                (
+                    "alex@example.com".to_string(),
+                    OAuthStoredSession {
+                        access_token: "stale".to_string(),
+                        access_token_expires_at_ms: 0,
+                        refresh_token: "refresh-1".to_string(),
+                    },
+                ),
+                (
+                    "sam@example.com".to_string(),
+                    OAuthStoredSession {
+                        access_token: "fresh".to_string(),
+                        access_token_expires_at_ms: u64::MAX,
+                        refresh_token: "refresh-sam".to_string(),
+                    },
+                ),
+            ]))
+            .unwrap();
+        let manager =
+            OAuthAccountManager::with_store(mock_provider(&base_url), "client-1", store).unwrap();
+
+        let refreshed = manager
+            .access_token(OAuthAccessTokenRequest {
+                account: "Alex@example.com".to_string(),
+                granted_scopes: vec!["calendar".to_string()],
+                ..OAuthAccessTokenRequest::default()
+            })
+            .unwrap();
+        assert_eq!(refreshed.access_token, "access-2");
+        assert_eq!(refreshed.granted_scopes, vec!["calendar"]);
+
+        let cached = manager
+            .access_token(OAuthAccessTokenRequest {
+                account: "sam@example.com".to_string(),
+                ..OAuthAccessTokenRequest::default()
+            })
+            .unwrap();
+        assert_eq!(cached.access_token, "fresh");
+
+        let sessions = manager.store.sessions();
+        assert_eq!(sessions["alex@example.com"].access_token, "access-2");
+        assert_eq!(sessions["alex@example.com"].refresh_token, "refresh-1");
+        let token_requests = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|request| request.starts_with("POST /token"))
+            .count();
+        assert_eq!(token_requests, 1);
+    }
+
+    #[test]
+    fn disconnect_revokes_the_grant_and_forgets_the_account() {
+        let (base_url, requests, _) = pkce_authorization_server();
+        let store = MemorySessionStore::default();
+        store
+            .save(&HashMap::from([(
+                "alex@example.com".to_string(),
+                OAuthStoredSession {
+                    access_token: "access-1".to_string(),
+                    access_token_expires_at_ms: 0,
+                    refresh_token: "refresh-1".to_string(),
+                },
+            )]))
+            .unwrap();
+        let manager =
+            OAuthAccountManager::with_store(mock_provider(&base_url), "client-1", store).unwrap();
+
+        manager.disconnect("alex@example.com").unwrap();
+
+        assert!(manager.accounts().unwrap().is_empty());
+        assert!(requests
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|request| request == "POST /revoke token=refresh-1"));
+    }
+
+    #[test]
+    fn migrates_single_account_sessions_on_first_use() {
+        let store = MemorySessionStore::with_raw(
+            r#"{"accessToken":"legacy","accessTokenExpiresAtMs":18446744073709551615,"refreshToken":"legacy-refresh"}"#,
+        );
+        let manager =
+            OAuthAccountManager::with_store(mock_provider("http://127.0.0.1:9"), "client-1", store)
+                .unwrap();
+
+        let token = manager
+            .access_token(OAuthAccessTokenRequest {
+                account: "alex@example.com".to_string(),
+                legacy_account: Some("Alex@example.com".to_string()),
+                ..OAuthAccessTokenRequest::default()
+            })
+            .unwrap();
+
+        assert_eq!(token.access_token, "legacy");
+        assert_eq!(manager.accounts().unwrap(), vec!["alex@example.com"]);
+    }
+
+    #[test]
+    fn resolves_builtin_and_custom_providers() {
+        let google = resolve_oauth_provider("google", None).unwrap();
+        assert_eq!(
+            google.keyring_service(),
+            if cfg!(debug_assertions) {
+                "com.johnjeong.philo.dev.google-oauth"
+            } else {
+                "com.johnjeong.philo.google-oauth"
+            }
+        );
+        assert_eq!(
+            resolve_oauth_provider("github", None)
+                .unwrap()
+                .account_claim,
+            "login"
+        );
+        assert!(resolve_oauth_provider("myspace", None).is_err());
+
+        let custom =
+            resolve_oauth_provider("ignored", Some(mock_provider("https://id.example.org")))
+                .unwrap();
+        assert_eq!(custom.id, "mock-idp");
+
+        let mut invalid = mock_provider("https://id.example.org");
+        invalid.id = "Mock IdP".to_string();
+        assert!(resolve_oauth_provider("mock-idp", Some(invalid)).is_err());
+
+        let mut impostor = mock_provider("https://id.example.org");
+        impostor.id = "google".to_string();
+        assert!(resolve_oauth_provider("google", Some(impostor)).is_err());
+    }
+
+    #[test]
+    fn custom_providers_cannot_choose_a_client_secret_env() {
+        let provider: OAuthProviderConfig = serde_json::from_value(serde_json::json!({
+            "id": "mock-idp",
+            "displayName": "Mock IdP",
+            "authorizationUrl": "https://id.example.org/authorize",
+            "tokenUrl": "https://id.example.org/token",
+            "userinfoUrl": "https://id.example.org/userinfo",
+            "clientSecretEnv": "PATH",
+        }))
+        .unwrap();
+        assert_eq!(provider.client_secret_env, None);
+        assert_eq!(provider.client_secret(), None);
+    }
+
+    #[test]
+    fn rejects_overflowing_token_expiry() {
+        assert!(access_token_expires_at_ms(Some(u64::MAX)).is_err());
+        assert!(access_token_expires_at_ms(Some(3600)).is_ok());
+    }
+}
@@ -0,0 +1,1108 @@
+use crate::periodic_notes::PeriodKind;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+const DEFAULT_TEMPLATE_DATE_FORMAT: &str = "YYYY-MM-DD";
+const DEFAULT_TEMPLATE_TIME_FORMAT: &str = "HH:mm";
+
+fn read_json(path: &Path) -> Option<JsonValue> {
+    let raw = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn json_str<'a>(value: &'a JsonValue, key: &str) -> Option<&'a str> {
+    value
+        .get(key)
+        .and_then(JsonValue::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn normalize_vault_relative(value: &str) -> Option<PathBuf> {
+    let trimmed = value.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        return None;
+    }
+    let path = PathBuf::from(trimmed);
+    if path
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return None;
+    }
+    Some(path)
+}
+
+/// Where the vault keeps its templates, preferring the core Templates plugin over Templater.
+pub fn templates_folder(vault_dir: &Path) -> Option<String> {
+    let obsidian_dir = vault_dir.join(".obsidian");
+    read_json(&obsidian_dir.join("templates.json"))
+        .as_ref()
+        .and_then(|value| json_str(value, "folder").map(ToOwned::to_owned))
+        .or_else(|| {
+            read_json(&obsidian_dir.join("plugins/templater-obsidian/data.json"))
+                .as_ref()
+                .and_then(|value| json_str(value, "templates_folder").map(ToOwned::to_owned))
+        })
+        .map(|folder| folder.trim_matches('/').to_string())
+}
+
+fn daily_template_setting(vault_dir: &Path) -> Option<String> {
+    let obsidian_dir = vault_dir.join(".obsidian");
+    read_json(&obsidian_dir.join("daily-notes.json"))
+        .as_ref()
+        .and_then(|value| json_str(value, "template").map(ToOwned::to_owned))
+        .or_else(|| {
+            read_json(&obsidian_dir.join("plugins/periodic-notes/data.json"))
+                .as_ref()
+                .and_then(|value| value.get("daily"))
+                .and_then(|value| json_str(value, "template").map(ToOwned::to_owned))
+        })
+}
+
+/// The vault-relative template configured for new daily notes, if it exists on disk. Bare names
+/// are looked up inside the templates folder the way Obsidian's template picker does.
+pub fn daily_note_template(vault_dir: &Path) -> Option<PathBuf> {
+    let setting = daily_template_setting(vault_dir)?;
+    let relative = normalize_vault_relative(&setting)?;
+    let with_extension = if relative
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+    {
+        relative
+    } else {
+        PathBuf::from(format!("{}.md", relative.to_string_lossy()))
+    };
+
+    let mut candidates = vec![with_extension.clone()];
+    if let Some(folder) = templates_folder(vault_dir).and_then(|f| normalize_vault_relative(&f)) {
+        candidates.push(folder.join(&with_extension));
+    }
+    candidates
+        .into_iter()
+        .find(|candidate| vault_dir.join(candidate).is_file())
+}
+
+/// Folder and filename pattern the Periodic Notes plugin uses for `kind`, when it is enabled.
+/// The moment format is translated to Philo's `{TOKEN}` pattern; formats Philo cannot round-trip
+/// leave the pattern unset.
+pub fn periodic_note_settings(
+    vault_dir: &Path,
+    kind: PeriodKind,
+) -> Option<(String, Option<String>)> {
+    let data = read_json(&vault_dir.join(".obsidian/plugins/periodic-notes/data.json"))?;
+    let config = data.get(kind.obsidian_key())?;
+    if config.get("enabled").and_then(JsonValue::as_bool) != Some(true) {
+        return None;
+    }
+    let folder = json_str(config, "folder")
+        .unwrap_or_default()
+        .trim_matches('/')
+        .to_string();
+    let pattern = json_str(config, "format").and_then(map_periodic_format);
+    Some((folder, pattern))
+}
+
+/// Translates a moment format such as `gggg-[W]ww` into `{GGGG}-W{WW}`. Returns `None` for
+/// tokens Philo's filename patterns can't express, e.g. `MMMM` or `DD`.
+///
+/// Moment's locale week tokens `gggg`/`ww` are read as ISO weeks. They only agree for locales
+/// whose weeks start on Monday with the first week containing Jan 4th; elsewhere a note near
+/// the turn of the year can land one week off.
+pub fn map_periodic_format(format: &str) -> Option<String> {
+    let mut output = String::new();
+    let mut rest = format;
+    while let Some(ch) = rest.chars().next() {
+        if ch == '[' {
+            let end = rest.find(']')?;
+            output.push_str(&rest[1..end]);
+            rest = &rest[end + 1..];
+            continue;
+        }
+        if !ch.is_ascii_alphabetic() {
+            output.push(ch);
+            rest = &rest[ch.len_utf8()..];
+            continue;
+        }
+        // Tokens are runs of one letter, so `MMMM` never splits into two `MM`s.
+        let token_len = rest.find(|next| next != ch).unwrap_or(rest.len());
+        let replacement = match &rest[..token_len] {
+            "GGGG" | "gggg" => "{GGGG}",
+            "YYYY" => "{YYYY}",
+            "WW" | "ww" => "{WW}",
+            "MM" => "{MM}",
+            "Q" => "{Q}",
+            _ => return None,
+        };
+        output.push_str(replacement);
+        rest = &rest[token_len..];
+    }
+    Some(output)
+}
+
+#[derive(Clone, Debug)]
+pub struct TemplateContext {
+    /// The note's filename without extension, i.e. Obsidian's `{{title}}`.
+    pub title: String,
+    /// The day the note is for. `{{date}}` renders this rather than today.
+    pub date: NaiveDate,
+    pub now: NaiveDateTime,
+    pub date_format: String,
+    pub time_format: String,
+}
+
+impl TemplateContext {
+    pub fn for_vault(vault_dir: &Path, title: &str, date: NaiveDate, now: NaiveDateTime) -> Self {
+        let config = read_json(&vault_dir.join(".obsidian/templates.json"));
+        let config = config.as_ref();
+        Self {
+            title: title.to_string(),
+            date,
+            now,
+            date_format: config
+                .and_then(|value| json_str(value, "dateFormat"))
+                .unwrap_or(DEFAULT_TEMPLATE_DATE_FORMAT)
+                .to_string(),
+            time_format: config
+                .and_then(|value| json_str(value, "timeFormat"))
+                .unwrap_or(DEFAULT_TEMPLATE_TIME_FORMAT)
+                .to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedTemplate {
+    pub markdown: String,
+    /// Templater commands that were left untouched because Philo cannot evaluate them.
+    pub unsupported: Vec<String>,
+}
+
+/// Expands core Templates variables and the date/title subset of Templater.
+pub fn render_template(raw: &str, context: &TemplateContext) -> RenderedTemplate {
+    let core_re = Regex::new(r"\{\{\s*(title|date|time)\s*(?::([^}]*))?\}\}")
+        .expect("valid template variable regex");
+    let note_start = context.date.and_time(context.now.time());
+    let markdown = core_re
+        .replace_all(raw, |captures: &regex::Captures| {
+            let format = captures.get(2).map(|value| value.as_str().trim());
+            match &captures[1] {
+                "title" => context.title.clone(),
+                "date" => format_moment(
+                    note_start,
+                    format
+                        .filter(|f| !f.is_empty())
+                        .unwrap_or(&context.date_format),
+                ),
+                _ => format_moment(
+                    context.now,
+                    format
+                        .filter(|f| !f.is_empty())
+                        .unwrap_or(&context.time_format),
+                ),
+            }
+        })
+        .into_owned();
+
+    let templater_re = Regex::new(r"(?s)<%([-_*]?)(.*?)([-_]?)%>").expect("valid templater regex");
+    let date_call_re = Regex::new(
+        r#"^tp\.date\.(now|today|tomorrow|yesterday)\(\s*(?:["']([^"']*)["'])?\s*(?:,\s*(-?\d+)\s*)?\)$"#,
+    )
+    .expect("valid templater date regex");
+    let mut unsupported = Vec::new();
+    let markdown = templater_re
+        .replace_all(&markdown, |captures: &regex::Captures| {
+            let full = captures[0].to_string();
+            if &captures[1] == "*" {
+                unsupported.push(full.clone());
+                return full;
+            }
+            let command = captures[2].trim();
+            if command == "tp.file.title" {
+                return context.title.clone();
+            }
+            if let Some(call) = date_call_re.captures(command) {
+                let offset = call
+                    .get(3)
+                    .and_then(|value| value.as_str().parse::<i64>().ok())
+                    .unwrap_or(0);
+                let base = match &call[1] {
+                    "tomorrow" => context.now + Duration::days(1),
+                    "yesterday" => context.now - Duration::days(1),
+                    _ => context.now,
+                };
+                let format = call
+                    .get(2)
+                    .map(|value| value.as_str())
+                    .filter(|value| !value.is_empty())
+                    .unwrap_or(DEFAULT_TEMPLATE_DATE_FORMAT);
+                return format_moment(base + Duration::days(offset), format);
+            }
+            unsupported.push(full.clone());
+            full
+        })
+        .into_owned();
+
+    RenderedTemplate {
+        markdown,
+        unsupported,
+    }
+}
+
+/// Renders the vault's daily-note template for `date`, or `None` when no template is set up.
+pub fn render_daily_note_template(
+    vault_dir: &Path,
+    title: &str,
+    date: NaiveDate,
+    now: NaiveDateTime,
+) -> Result<Option<RenderedTemplate>, String> {
+    let Some(template) = daily_note_template(vault_dir) else {
+        return Ok(None);
+    };
+    let raw = fs::read_to_string(vault_dir.join(&template)).map_err(|e| {
+        format!(
+            "Could not read daily note template {}: {e}",
+            template.display()
+        )
+    })?;
+    let context = TemplateContext::for_vault(vault_dir, title, date, now);
+    Ok(Some(render_template(&raw, &context)))
+}
+
+const MOMENT_TOKENS: [&str; 31] = [
+    "YYYY", "GGGG", "gggg", "MMMM", "MMM", "dddd", "DDDD", "YY", "MM", "Do", "DD", "ddd", "dd",
+    "WW", "ww", "HH", "hh", "mm", "ss", "M", "D", "d", "E", "W", "w", "H", "h", "m", "s", "A", "a",
+];
+
+fn ordinal_suffix(day: u32) -> &'static str {
+    match (day % 10, day % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+/// Formats a datetime with the moment.js tokens Obsidian uses in its date settings. Text in
+/// square brackets is copied verbatim.
+pub fn format_moment(datetime: NaiveDateTime, format: &str) -> String {
+    let mut output = String::new();
+    let mut rest = format;
+    while let Some(ch) = rest.chars().next() {
+        if ch == '[' {
+            if let Some(end) = rest.find(']') {
+                output.push_str(&rest[1..end]);
+                rest = &rest[end + 1..];
+                continue;
+            }
+        }
+
+        let Some(token) = MOMENT_TOKENS.iter().find(|token| rest.starts_with(**token)) else {
+            output.push(ch);
+            rest = &rest[ch.len_utf8()..];
+            continue;
+        };
+
+        let week = datetime.iso_week();
+        let hour12 = match datetime.hour() % 12 {
+            0 => 12,
+            hour => hour,
+        };
+        let rendered = match *token {
+            "YYYY" => format!("{:04}", datetime.year()),
+            "YY" => format!("{:02}", datetime.year().rem_euclid(100)),
+            "GGGG" | "gggg" => format!("{:04}", week.year()),
+            "MMMM" => datetime.format("%B").to_string(),
+            "MMM" => datetime.format("%b").to_string(),
+            "MM" => format!("{:02}", datetime.month()),
+            "M" => datetime.month().to_string(),
+            "DDDD" => format!("{:03}", datetime.ordinal()),
+            "Do" => format!("{}{}", datetime.day(), ordinal_suffix(datetime.day())),
+            "DD" => format!("{:02}", datetime.day()),
+            "D" => datetime.day().to_string(),
+            "dddd" => datetime.format("%A").to_string(),
+            "ddd" => datetime.format("%a").to_string(),
+            "dd" => datetime.format("%a").to_string()[..2].to_string(),
+            "d" => datetime.weekday().num_days_from_sunday().to_string(),
+            "E" => datetime.weekday().number_from_monday().to_string(),
+            "WW" | "ww" => format!("{:02}", week.week()),
+            "W" | "w" => week.week().to_string(),
+            "HH" => format!("{:02}", datetime.hour()),
+            "H" => datetime.hour().to_string(),
+            "hh" => format!("{hour12:02}"),
+            "h" => hour12.to_string(),
+            "mm" => format!("{:02}", datetime.minute()),
+            "m" => datetime.minute().to_string(),
+            "ss" => format!("{:02}", datetime.second()),
+            "s" => datetime.second().to_string(),
+            "A" => (if datetime.hour() < 12 { "AM" } else { "PM" }).to_string(),
+            _ => (if datetime.hour() < 12 { "am" } else { "pm" }).to_string(),
+        };
+        output.push_str(&rendered);
+        rest = &rest[token.len()..];
+    }
+    output
+}
+
+fn frontmatter_strings(frontmatter: &JsonMap<String, JsonValue>, keys: &[&str]) -> Vec<String> {
+    let mut values = Vec::new();
+    for key in keys {
+        match frontmatter.get(*key) {
+            Some(JsonValue::Array(items)) => values.extend(
+                items
+                    .iter()
+                    .filter_map(JsonValue::as_str)
+                    .map(ToOwned::to_owned),
+            ),
+            Some(JsonValue::String(value)) => {
+                values.extend(value.split(',').map(ToOwned::to_owned))
+            }
+            _ => {}
+        }
+    }
+    values
+        .into_iter()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+/// Reads `aliases:` (or the older `alias:`) as Obsidian does: a list or a comma-separated string.
+pub fn frontmatter_aliases(frontmatter: &JsonMap<String, JsonValue>) -> Vec<String> {
+    frontmatter_strings(frontmatter, &["aliases", "alias"])
+}
+
+/// Case-insensitive lookup from a page title or one of its aliases to the page's title.
+#[derive(Clone, Debug, Default)]
+pub struct PageAliases {
+    titles: HashMap<String, String>,
+    aliases: HashMap<String, String>,
+}
+
+impl PageAliases {
+    pub fn insert_page(&mut self, title: &str, frontmatter: &JsonMap<String, JsonValue>) {
+        self.titles.insert(title.to_lowercase(), title.to_string());
+        for alias in frontmatter_aliases(frontmatter) {
+            self.aliases
+                .entry(alias.to_lowercase())
+                .or_insert_with(|| title.to_string());
+        }
+    }
+
+    /// Returns the page a title refers to. A real page always wins over an alias of another page.
+    pub fn resolve(&self, title: &str) -> String {
+        let key = title.to_lowercase();
+        self.titles
+            .get(&key)
+            .or_else(|| self.aliases.get(&key))
+            .cloned()
+            .unwrap_or_else(|| title.to_string())
+    }
+}
+
+fn is_valid_tag(tag: &str) -> bool {
+    !tag.is_empty() && !tag.chars().all(|ch| ch.is_ascii_digit() || ch == '/')
+}
+
+fn normalize_tag(tag: &str) -> Option<String> {
+    let normalized = tag
+        .trim()
+        .trim_start_matches('#')
+        .trim_matches('/')
+        .to_lowercase();
+    is_valid_tag(&normalized).then_some(normalized)
+}
+
+/// Lines outside fenced code blocks, with inline code spans blanked out.
+fn prose_lines(markdown: &str) -> Vec<String> {
+    let inline_code_re = Regex::new(r"`[^`\n]*`").expect("valid inline code regex");
+    let mut lines = Vec::new();
+    let mut fence: Option<&str> = None;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        let marker = if trimmed.starts_with("```") {
+            Some("```")
+        } else if trimmed.starts_with("~~~") {
+            Some("~~~")
+        } else {
+            None
+        };
+        match (fence, marker) {
+            (None, Some(marker)) => {
+                fence = Some(marker);
+                continue;
+            }
+            (Some(open), Some(marker)) if open == marker => {
+                fence = None;
+                continue;
+            }
+            (Some(_), _) => continue,
+            _ => {}
+        }
+        lines.push(inline_code_re.replace_all(line, "").into_owned());
+    }
+    lines
+}
+
+/// Inline `#tags` in a markdown body, including nested `#area/project` tags.
+pub fn extract_inline_tags(markdown: &str) -> BTreeSet<String> {
+    let tag_re = Regex::new(r"(?:^|\s)#([\p{L}\p{N}_/\-]+)").expect("valid tag regex");
+    prose_lines(markdown)
+        .iter()
+        .flat_map(|line| {
+            tag_re
+                .captures_iter(line)
+                .filter_map(|captures| normalize_tag(&captures[1]))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Frontmatter `tags:` plus inline tags, lowercased and without the leading `#`.
+pub fn collect_tags(frontmatter: &JsonMap<String, JsonValue>, body: &str) -> BTreeSet<String> {
+    let mut tags = extract_inline_tags(body);
+    for value in frontmatter_strings(frontmatter, &["tags", "tag"]) {
+        tags.extend(value.split_whitespace().filter_map(normalize_tag));
+    }
+    tags
+}
+
+/// True when `tags` contains `query` itself or a tag nested below it, so `#work` finds
+/// `#work/launch`.
+pub fn tags_match(tags: &BTreeSet<String>, query: &str) -> bool {
+    let Some(query) = normalize_tag(query) else {
+        return false;
+    };
+    let nested_prefix = format!("{query}/");
+    tags.iter()
+        .any(|tag| *tag == query || tag.starts_with(&nested_prefix))
+}
+
+/// Splits a search query into `#tag` filters and the remaining free-text terms.
+pub fn split_tag_query(query: &str) -> (Vec<String>, String) {
+    let mut tags = Vec::new();
+    let mut text = Vec::new();
+    for part in query.split_whitespace() {
+        match part.strip_prefix('#').and_then(normalize_tag) {
+            Some(tag) => tags.push(tag),
+            None => text.push(part),
+        }
+    }
+    (tags, text.join(" "))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LinkFragment {
+    Heading(String),
+    Block(String),
+}
+
+/// Splits `Page#Heading`, `Page#^block` and `Page^block` link targets into the page part and
+/// the referenced section.
+pub fn split_link_fragment(target: &str) -> (&str, Option<LinkFragment>) {
+    let Some(index) = target.find(['#', '^']) else {
+        return (target, None);
+    };
+    let page = &target[..index];
+    let rest = &target[index..];
+    let fragment = if let Some(block) = rest.strip_prefix("#^").or(rest.strip_prefix('^')) {
+        Some(LinkFragment::Block(block.trim().to_string()))
+    } else {
+        // Obsidian allows `Page#Parent#Child`; the last heading is the one that is linked.
+        rest.rsplit('#')
+            .map(str::trim)
+            .find(|heading| !heading.is_empty())
+            .map(|heading| LinkFragment::Heading(heading.to_string()))
+    };
+    (
+        page,
+        fragment.filter(|fragment| !fragment_is_empty(fragment)),
+    )
+}
+
+fn fragment_is_empty(fragment: &LinkFragment) -> bool {
+    match fragment {
+        LinkFragment::Heading(value) | LinkFragment::Block(value) => value.is_empty(),
+    }
+}
+
+fn heading_level(line: &str) -> Option<(usize, &str)> {
+    let trimmed = line.trim_start();
+    let level = trimmed.chars().take_while(|ch| *ch == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let text = trimmed[level..].strip_prefix([' ', '\t'])?;
+    Some((level, text.trim().trim_end_matches('#').trim()))
+}
+
+fn normalize_heading(value: &str) -> String {
+    value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn is_list_item(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("- ")
+        || trimmed.starts_with("* ")
+        || trimmed.starts_with("+ ")
+        || trimmed.starts_with("> ")
+    {
+        return true;
+    }
+    let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+    digits > 0 && trimmed[digits..].starts_with(". ")
+}
+
+/// Returns the markdown a heading or block reference points at: a heading with everything up to
+/// the next heading of the same or higher level, or the paragraph/list item carrying `^id`.
+pub fn extract_link_fragment(markdown: &str, fragment: &LinkFragment) -> Option<String> {
+    let lines = markdown.lines().collect::<Vec<_>>();
+    match fragment {
+        LinkFragment::Heading(heading) => {
+            let wanted = normalize_heading(heading);
+            let start = lines.iter().position(|line| {
+                heading_level(line).is_some_and(|(_, text)| normalize_heading(text) == wanted)
+            })?;
+            let (level, _) = heading_level(lines[start])?;
+            let end = lines[start + 1..]
+                .iter()
+                .position(|line| heading_level(line).is_some_and(|(next, _)| next <= level))
+                .map(|offset| start + 1 + offset)
+                .unwrap_or(lines.len());
+            Some(lines[start..end].join("\n").trim_end().to_string())
+        }
+        LinkFragment::Block(id) => {
+            let marker_re = Regex::new(&format!(r"(?:^|\s)\^{}\s*$", regex::escape(id)))
+                .expect("valid block marker regex");
+            let index = lines.iter().position(|line| marker_re.is_match(line))?;
+            let line = marker_re.replace(lines[index], "").trim_end().to_string();
+
+            // A marker on its own line labels the block above it, as Obsidian does for tables
+            // and quotes.
+            let (end, first_line) = if line.trim().is_empty() {
+                let end = lines[..index]
+                    .iter()
+                    .rposition(|line| !line.trim().is_empty())?;
+                (end, lines[end].to_string())
+            } else {
+                (index, line)
+            };
+            if is_list_item(&first_line) {
+                return Some(first_line.trim_end().to_string());
+            }
+            let start = lines[..end]
+                .iter()
+                .rposition(|line| line.trim().is_empty() || heading_level(line).is_some())
+                .map(|position| position + 1)
+                .unwrap_or(0);
+            let mut block = lines[start..end]
+                .iter()
+                .map(|line| line.to_string())
+                .collect::<Vec<_>>();
+            block.push(first_line);
+            Some(block.join("\n").trim_end().to_string())
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObsidianSupport {
+    Supported,
+    Partial,
+    Unsupported,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObsidianPluginReport {
+    pub id: String,
+    pub kind: String,
+    pub support: ObsidianSupport,
+    pub detail: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObsidianSettingReport {
+    pub file: String,
+    pub key: String,
+    pub value: JsonValue,
+    pub detail: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObsidianCompatibilityReport {
+    pub vault_dir: String,
+    pub plugins: Vec<ObsidianPluginReport>,
+    pub unsupported_settings: Vec<ObsidianSettingReport>,
+}
+
+fn core_plugin_support(id: &str) -> (ObsidianSupport, &'static str) {
+    use ObsidianSupport::*;
+    match id {
+        "daily-notes" => (
+            Supported,
+            "Daily note folder, filename format and template are used.",
+        ),
+        "templates" => (
+            Supported,
+            "{{title}}, {{date}} and {{time}} are expanded in new daily notes.",
+        ),
+        "global-search" | "switcher" => (Supported, "Philo search covers notes, pages and #tags."),
+        "backlink" | "outgoing-link" => (
+            Supported,
+            "Wiki links, aliases, headings and block references resolve.",
+        ),
+        "tag-pane" => (Partial, "Tags are searchable but there is no tag browser."),
+        "properties" => (
+            Partial,
+            "Frontmatter is kept but only Philo's own fields are editable.",
+        ),
+        "file-explorer" | "command-palette" | "editor-status" | "word-count" | "page-preview"
+        | "outline" => (Supported, "Philo has its own equivalent."),
+        "canvas" => (Unsupported, ".canvas files are ignored."),
+        "bookmarks" | "starred" => (Unsupported, "Bookmarks are not shown."),
+        "graph" => (Unsupported, "There is no graph view."),
+        "sync" | "publish" => (
+            Unsupported,
+            "Obsidian Sync and Publish keep working but Philo does not talk to them.",
+        ),
+        "workspaces" => (Unsupported, "Workspace layouts are not restored."),
+        _ => (Unsupported, "Philo has no equivalent for this core plugin."),
+    }
+}
+
+fn community_plugin_support(id: &str) -> (ObsidianSupport, &'static str) {
+    use ObsidianSupport::*;
+    match id {
+        "obsidian-excalidraw-plugin" => (Supported, "Drawings are embedded from the Excalidraw folder."),
+        "periodic-notes" => (Partial, "Daily, weekly, monthly and quarterly notes are used; yearly notes are not."),
+        "templater-obsidian" => (
+            Partial,
+            "tp.file.title and tp.date.* are expanded; other commands and <%* %> blocks are left as written.",
+        ),
+        "obsidian-tasks-plugin" => (
+            Partial,
+            "Checkbox tasks work; task queries and emoji metadata are not evaluated.",
+        ),
+        "dataview" => (Unsupported, "Dataview queries show up as plain code blocks."),
+        _ => (Unsupported, "Philo does not run Obsidian plugins."),
+    }
+}
+
+fn enabled_core_plugins(value: &JsonValue) -> Vec<String> {
+    match value {
+        JsonValue::Array(items) => items
+            .iter()
+            .filter_map(JsonValue::as_str)
+            .map(ToOwned::to_owned)
+            .collect(),
+        JsonValue::Object(map) => map
+            .iter()
+            .filter(|(_, enabled)| enabled.as_bool().unwrap_or(false))
+            .map(|(id, _)| id.clone())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn unsupported_date_tokens(format: &str) -> bool {
+    let mut rest = format;
+    while let Some(ch) = rest.chars().next() {
+        if ch == '[' {
+            match rest.find(']') {
+                Some(end) => {
+                    rest = &rest[end + 1..];
+                    continue;
+                }
+                None => return true,
+            }
+        }
+        if let Some(token) = ["YYYY", "MM", "DD"]
+            .iter()
+            .find(|token| rest.starts_with(**token))
+        {
+            rest = &rest[token.len()..];
+            continue;
+        }
+        if ch.is_alphabetic() {
+            return true;
+        }
+        rest = &rest[ch.len_utf8()..];
+    }
+    false
+}
+
+/// Lists the enabled plugins with how well Philo understands what they write, plus vault settings
+/// Philo does not honor.
+pub fn compatibility_report(vault_dir: &Path) -> Result<ObsidianCompatibilityReport, String> {
+    let obsidian_dir = vault_dir.join(".obsidian");
+    if !obsidian_dir.is_dir() {
+        return Err(format!("{} is not an Obsidian vault.", vault_dir.display()));
+    }
+
+    let mut plugins = Vec::new();
+    if let Some(core) = read_json(&obsidian_dir.join("core-plugins.json")) {
+        for id in enabled_core_plugins(&core) {
+            let (support, detail) = core_plugin_support(&id);
+            plugins.push(ObsidianPluginReport {
+                id,
+                kind: "core".to_string(),
+                support,
+                detail: detail.to_string(),
+            });
+        }
+    }
+    if let Some(community) = read_json(&obsidian_dir.join("community-plugins.json")) {
+        for id in enabled_core_plugins(&community) {
+            let (support, detail) = community_plugin_support(&id);
+            plugins.push(ObsidianPluginReport {
+                id,
+                kind: "community".to_string(),
+                support,
+                detail: detail.to_string(),
+            });
+        }
+    }
+
+    let mut unsupported_settings = Vec::new();
+    let mut flag = |file: &str, key: &str, value: &JsonValue, detail: &str| {
+        unsupported_settings.push(ObsidianSettingReport {
+            file: file.to_string(),
+            key: key.to_string(),
+            value: value.clone(),
+            detail: detail.to_string(),
+        });
+    };
+
+    if let Some(app) = read_json(&obsidian_dir.join("app.json")) {
+        if let Some(value) = app.get("newLinkFormat") {
+            if matches!(value.as_str(), Some("relative" | "absolute")) {
+                flag(
+                    "app.json",
+                    "newLinkFormat",
+                    value,
+                    "Philo writes shortest-path links to pages.",
+                );
+            }
+        }
+        if let Some(value) = app.get("attachmentFolderPath") {
+            if value.as_str().is_some_and(|path| path.starts_with("./")) {
+                flag(
+                    "app.json",
+                    "attachmentFolderPath",
+                    value,
+                    "Attachments are stored in one vault folder, not next to each note.",
+                );
+            }
+        }
+    }
+
+    if let Some(daily) = read_json(&obsidian_dir.join("daily-notes.json")) {
+        if let Some(value) = daily.get("format") {
+            if value.as_str().is_some_and(unsupported_date_tokens) {
+                flag(
+                    "daily-notes.json",
+                    "format",
+                    value,
+                    "Only YYYY, MM and DD are understood in daily note filenames.",
+                );
+            }
+        }
+        if let Some(value) = daily.get("template") {
+            if value
+                .as_str()
+                .is_some_and(|template| !template.trim().is_empty())
+                && daily_note_template(vault_dir).is_none()
+            {
+                flag(
+                    "daily-notes.json",
+                    "template",
+                    value,
+                    "The daily note template could not be found.",
+                );
+            }
+        }
+    }
+
+    if let Some(periodic) = read_json(&obsidian_dir.join("plugins/periodic-notes/data.json")) {
+        for kind in PeriodKind::ALL {
+            if let Some(value) = periodic.get(kind.obsidian_key()) {
+                let format = value.get("format").and_then(JsonValue::as_str);
+                if value.get("enabled").and_then(JsonValue::as_bool) == Some(true)
+                    && format.is_some_and(|format| map_periodic_format(format).is_none())
+                {
+                    flag(
+                        "plugins/periodic-notes/data.json",
+                        kind.obsidian_key(),
+                        value,
+                        "The filename format uses tokens Philo cannot read; the default is used.",
+                    );
+                }
+            }
+        }
+        if let Some(value) = periodic.get("yearly") {
+            if value.get("enabled").and_then(JsonValue::as_bool) == Some(true) {
+                flag(
+                    "plugins/periodic-notes/data.json",
+                    "yearly",
+                    value,
+                    "Yearly notes are not supported.",
+                );
+            }
+        }
+    }
+
+    if let Some(templater) = read_json(&obsidian_dir.join("plugins/templater-obsidian/data.json")) {
+        if let Some(value) = templater.get("enable_system_commands") {
+            if value.as_bool() == Some(true) {
+                flag(
+                    "plugins/templater-obsidian/data.json",
+                    "enable_system_commands",
+                    value,
+                    "Templater system commands are never run.",
+                );
+            }
+        }
+    }
+
+    Ok(ObsidianCompatibilityReport {
+        vault_dir: vault_dir.to_string_lossy().to_string(),
+        plugins,
+        unsupported_settings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn make_vault() -> PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let vault = std::env::temp_dir().join(format!("philo-obsidian-test-{unique}"));
+        fs::create_dir_all(vault.join(".obsidian/plugins/templater-obsidian")).unwrap();
+        vault
+    }
+
+    fn write(path: PathBuf, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{date} {time}"), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn formats_moment_tokens() {
+        let datetime = at("2026-10-02", "09:05");
+        assert_eq!(format_moment(datetime, "YYYY-MM-DD"), "2026-10-02");
+        assert_eq!(
+            format_moment(datetime, "dddd, MMMM Do [at] h:mm A"),
+            "Friday, October 2nd at 9:05 AM"
+        );
+        assert_eq!(format_moment(datetime, "gggg-[W]WW"), "2026-W40");
+    }
+
+    #[test]
+    fn maps_periodic_formats_and_rejects_unsupported_tokens() {
+        assert_eq!(
+            map_periodic_format("gggg-[W]ww").as_deref(),
+            Some("{GGGG}-W{WW}")
+        );
+        assert_eq!(
+            map_periodic_format("YYYY-MM").as_deref(),
+            Some("{YYYY}-{MM}")
+        );
+        assert_eq!(
+            map_periodic_format("YYYY-[Q]Q").as_deref(),
+            Some("{YYYY}-Q{Q}")
+        );
+        assert_eq!(map_periodic_format("YYYY-MMMM"), None);
+        assert_eq!(map_periodic_format("YYYY-MMM"), None);
+        assert_eq!(map_periodic_format("YYYY-MM-DD"), None);
+        assert_eq!(map_periodic_format("YY-MM"), None);
+    }
+
+    #[test]
+    fn renders_daily_template_from_templates_folder() {
+        let vault = make_vault();
+        write(
+            vault.join(".obsidian/daily-notes.json"),
+            r#"{"folder":"Daily","template":"Daily"}"#,
+        );
+        write(
+            vault.join(".obsidian/templates.json"),
+            r#"{"folder":"Templates","dateFormat":"ddd D MMM"}"#,
+        );
+        write(
+            vault.join("Templates/Daily.md"),
+            "# {{title}}\nDate: {{date}} ({{date:YYYY-MM-DD}}) at {{time}}\nTomorrow: <% tp.date.now(\"YYYY-MM-DD\", 1) %>\n<%* tR += 'js' %>\n",
+        );
+
+        let rendered = render_daily_note_template(
+            &vault,
+            "2026-10-18",
+            NaiveDate::from_ymd_opt(2026, 10, 18).unwrap(),
+            at("2026-10-17", "21:30"),
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(
+            rendered.markdown,
+            "# 2026-10-18\nDate: Sun 18 Oct (2026-10-18) at 21:30\nTomorrow: 2026-10-18\n<%* tR += 'js' %>\n"
+        );
+        assert_eq!(rendered.unsupported, vec!["<%* tR += 'js' %>"]);
+    }
+
+    #[test]
+    fn resolves_aliases_without_shadowing_real_pages() {
+        let mut aliases = PageAliases::default();
+        aliases.insert_page(
+            "Launch plan",
+            json!({"aliases": ["Launch", "Q4 launch"]})
+                .as_object()
+                .unwrap(),
+        );
+        aliases.insert_page(
+            "Retro",
+            json!({"alias": "Launch plan, Review"}).as_object().unwrap(),
+        );
+
+        assert_eq!(aliases.resolve("q4 LAUNCH"), "Launch plan");
+        assert_eq!(aliases.resolve("Launch plan"), "Launch plan");
+        assert_eq!(aliases.resolve("review"), "Retro");
+        assert_eq!(aliases.resolve("Unknown"), "Unknown");
+    }
+
+    #[test]
+    fn collects_inline_nested_and_frontmatter_tags() {
+        let frontmatter = json!({"tags": ["Work", "#reading"]});
+        let tags = collect_tags(
+            frontmatter.as_object().unwrap(),
+            "# Heading\nShipped #project/alpha and #2026 not a tag\n`#code` stays\n```\n#fenced\n```\nmail me at a#b\n",
+        );
+
+        assert_eq!(
+            tags.into_iter().collect::<Vec<_>>(),
+            vec!["project/alpha", "reading", "work"]
+        );
+        let tags = BTreeSet::from(["project/alpha".to_string()]);
+        assert!(tags_match(&tags, "#project"));
+        assert!(tags_match(&tags, "Project/Alpha"));
+        assert!(!tags_match(&tags, "proj"));
+        assert_eq!(
+            split_tag_query("#project launch #Work/"),
+            (
+                vec!["project".to_string(), "work".to_string()],
+                "launch".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn resolves_heading_and_block_references() {
+        let markdown = "# Plan\nIntro\n## Goals\n- ship\n### Detail\nmore\n## Risks\nA risk\nspanning lines ^risk-1\n\n- item one ^item\n\n| a |\n|---|\n\n^table\n";
+
+        assert_eq!(
+            split_link_fragment("Plan#Goals"),
+            ("Plan", Some(LinkFragment::Heading("Goals".to_string())))
+        );
+        assert_eq!(
+            split_link_fragment("Plan#^risk-1"),
+            ("Plan", Some(LinkFragment::Block("risk-1".to_string())))
+        );
+        assert_eq!(
+            split_link_fragment("Plan^item"),
+            ("Plan", Some(LinkFragment::Block("item".to_string())))
+        );
+        assert_eq!(
+            extract_link_fragment(markdown, &LinkFragment::Heading("goals".to_string())),
+            Some("## Goals\n- ship\n### Detail\nmore".to_string())
+        );
+        assert_eq!(
+            extract_link_fragment(markdown, &LinkFragment::Block("risk-1".to_string())),
+            Some("A risk\nspanning lines".to_string())
+        );
+        assert_eq!(
+            extract_link_fragment(markdown, &LinkFragment::Block("item".to_string())),
+            Some("- item one".to_string())
+        );
+        assert_eq!(
+            extract_link_fragment(markdown, &LinkFragment::Block("table".to_string())),
+            Some("| a |\n|---|".to_string())
+        );
+    }
+
+    #[test]
+    fn reports_unsupported_plugins_and_settings() {
+        let vault = make_vault();
+        write(
+            vault.join(".obsidian/core-plugins.json"),
+            r#"{"daily-notes":true,"canvas":true,"graph":false}"#,
+        );
+        write(
+            vault.join(".obsidian/community-plugins.json"),
+            r#"["dataview","templater-obsidian"]"#,
+        );
+        write(
+            vault.join(".obsidian/app.json"),
+            r#"{"newLinkFormat":"relative","attachmentFolderPath":"./assets"}"#,
+        );
+        write(
+            vault.join(".obsidian/daily-notes.json"),
+            r#"{"format":"dddd YYYY-MM-DD","template":"Missing"}"#,
+        );
+
+        let report = compatibility_report(&vault).unwrap();
+        let plugins = report
+            .plugins
+            .iter()
+            .map(|plugin| (plugin.id.as_str(), plugin.support))
+            .collect::<Vec<_>>();
+        let settings = report
+            .unsupported_settings
+            .iter()
+            .map(|setting| setting.key.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            plugins,
+            vec![
+                ("canvas", ObsidianSupport::Unsupported),
+                ("daily-notes", ObsidianSupport::Supported),
+                ("dataview", ObsidianSupport::Unsupported),
+                ("templater-obsidian", ObsidianSupport::Partial),
+            ]
+        );
+        assert_eq!(
+            settings,
+            vec![
+                "newLinkFormat",
+                "attachmentFolderPath",
+                "format",
+                "template"
+            ]
+        );
+    }
+}
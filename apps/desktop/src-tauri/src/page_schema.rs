@@ -0,0 +1,597 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Fields every page may carry, whatever its type.
+const COMMON_TYPE: &str = "page";
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FieldType {
+    String,
+    StringList,
+    /// `YYYY-MM-DD`, optionally written as a `[[YYYY-MM-DD]]` link.
+    Date,
+    /// RFC 3339, or a local `YYYY-MM-DDTHH:MM[:SS]`.
+    DateTime,
+    Number {
+        #[serde(default)]
+        min: Option<f64>,
+        #[serde(default)]
+        max: Option<f64>,
+    },
+    Boolean,
+    Object,
+    Enum {
+        values: Vec<String>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct FieldSchema {
+    #[serde(flatten)]
+    pub kind: FieldType,
+    #[serde(default)]
+    pub required: bool,
+    /// Written into new pages of this type and assumed when the field is missing.
+    #[serde(default)]
+    pub default: Option<JsonValue>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct PageTypeSchema {
+    #[serde(default)]
+    pub fields: BTreeMap<String, FieldSchema>,
+}
+
+/// `page-types.json` next to the settings file. Types named like a built-in one extend it.
+#[derive(Clone, Debug, Default, Deserialize)]
+struct PageTypesConfig {
+    #[serde(default)]
+    types: BTreeMap<String, PageTypeSchema>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueCode {
+    MissingRequired,
+    WrongType,
+    InvalidValue,
+    UnknownType,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaIssue {
+    pub field: String,
+    pub severity: IssueSeverity,
+    pub code: IssueCode,
+    pub message: String,
+}
+
+impl SchemaIssue {
+    fn error(field: &str, code: IssueCode, message: String) -> Self {
+        Self {
+            field: field.to_string(),
+            severity: IssueSeverity::Error,
+            code,
+            message,
+        }
+    }
+
+    fn warning(field: &str, code: IssueCode, message: String) -> Self {
+        Self {
+            field: field.to_string(),
+            severity: IssueSeverity::Warning,
+            code,
+            message,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == IssueSeverity::Error
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageSchemas {
+    types: BTreeMap<String, PageTypeSchema>,
+}
+
+impl Default for PageSchemas {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl PageSchemas {
+    pub fn builtin() -> Self {
+        let string = || field(FieldType::String);
+        let strings = || field(FieldType::StringList);
+        let date = || field(FieldType::Date);
+        let datetime = || field(FieldType::DateTime);
+        let one_of = |values: &[&str]| {
+            field(FieldType::Enum {
+                values: values.iter().map(|value| value.to_string()).collect(),
+            })
+        };
+
+        let mut types = BTreeMap::new();
+        types.insert(
+            COMMON_TYPE.to_string(),
+            schema([
+                ("type", string()),
+                ("aliases", strings()),
+                ("tags", strings()),
+            ]),
+        );
+        types.insert(
+            "meeting".to_string(),
+            schema([
+                ("attached_to", date()),
+                ("event_id", string()),
+                ("started_at", datetime()),
+                ("ended_at", datetime()),
+                ("participants", strings()),
+                ("location", string()),
+                ("executive_summary", string()),
+                ("session_kind", one_of(&["decision_making", "informative"])),
+                ("agenda", strings()),
+                ("action_items", strings()),
+                ("source", string()),
+            ]),
+        );
+        types.insert(
+            "person".to_string(),
+            schema([
+                ("email", string()),
+                ("company", string()),
+                ("role", string()),
+                ("birthday", date()),
+            ]),
+        );
+        types.insert(
+            "project".to_string(),
+            schema([
+                (
+                    "status",
+                    FieldSchema {
+                        default: Some(JsonValue::String("active".to_string())),
+                        ..one_of(&["active", "paused", "done", "archived"])
+                    },
+                ),
+                ("owner", string()),
+                ("members", strings()),
+                ("started_at", date()),
+                ("due", date()),
+            ]),
+        );
+        types.insert(
+            "link".to_string(),
+            schema([
+                (
+                    "source",
+                    FieldSchema {
+                        required: true,
+                        ..string()
+                    },
+                ),
+                (
+                    "link_kind",
+                    one_of(&["generic", "github_pr", "github_issue", "github_commit"]),
+                ),
+                ("link_title", string()),
+                ("summary_updated_at", datetime()),
+                ("follow_up_questions", strings()),
+                ("link_data", field(FieldType::Object)),
+            ]),
+        );
+        Self { types }
+    }
+
+    /// Built-in types plus the user's `page-types.json`, when there is one.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let mut schemas = Self::builtin();
+        let raw = match fs::read_to_string(path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(schemas),
+            Err(err) => return Err(err.to_string()),
+        };
+        let config: PageTypesConfig = serde_json::from_str(&raw)
+            .map_err(|e| format!("Could not parse {}: {}", path.display(), e))?;
+        for (name, user) in config.types {
+            schemas
+                .types
+                .entry(name)
+                .or_default()
+                .fields
+                .extend(user.fields);
+        }
+        Ok(schemas)
+    }
+
+    /// The schema a page is checked against: its `type` when that names a schema, `link` for the
+    /// untyped pages link summaries write, and the common fields otherwise.
+    pub fn page_type(&self, frontmatter: &JsonMap<String, JsonValue>) -> String {
+        if let Some(name) = frontmatter.get("type").and_then(JsonValue::as_str) {
+            let name = name.trim();
+            if name != COMMON_TYPE && self.types.contains_key(name) {
+                return name.to_string();
+            }
+        }
+        if ["link_kind", "link_title", "summary_updated_at"]
+            .iter()
+            .any(|key| frontmatter.contains_key(*key))
+        {
+            return "link".to_string();
+        }
+        COMMON_TYPE.to_string()
+    }
+
+    /// Defaults of `page_type` that `frontmatter` does not set yet.
+    pub fn missing_defaults(
+        &self,
+        page_type: &str,
+        frontmatter: &JsonMap<String, JsonValue>,
+    ) -> Vec<(String, JsonValue)> {
+        self.fields(page_type)
+            .filter(|(name, _)| is_missing(frontmatter.get(*name)))
+            .filter_map(|(name, field)| Some((name.to_string(), field.default.clone()?)))
+            .collect()
+    }
+
+    pub fn validate(&self, frontmatter: &JsonMap<String, JsonValue>) -> Vec<SchemaIssue> {
+        let page_type = self.page_type(frontmatter);
+        let mut issues = Vec::new();
+
+        let declared = frontmatter
+            .get("type")
+            .and_then(JsonValue::as_str)
+            .map(str::trim);
+        if let Some(declared) = declared.filter(|name| !self.types.contains_key(*name)) {
+            issues.push(SchemaIssue::warning(
+                "type",
+                IssueCode::UnknownType,
+                format!("Unknown page type \"{declared}\"; only the common fields are checked."),
+            ));
+        }
+
+        for (name, field) in self.fields(&page_type) {
+            let value = frontmatter.get(name);
+            if is_missing(value) {
+                if field.required {
+                    issues.push(match &field.default {
+                        Some(default) => SchemaIssue::warning(
+                            name,
+                            IssueCode::MissingRequired,
+                            format!("Missing {name}; {default} is assumed."),
+                        ),
+                        None => SchemaIssue::error(
+                            name,
+                            IssueCode::MissingRequired,
+                            format!("{page_type} pages need {name}."),
+                        ),
+                    });
+                }
+                continue;
+            }
+            if let Some(value) = value {
+                check_value(name, &field.kind, value, &mut issues);
+            }
+        }
+        issues
+    }
+
+    fn fields(&self, page_type: &str) -> impl Iterator<Item = (&str, &FieldSchema)> {
+        let common = self.types.get(COMMON_TYPE);
+        let specific = (page_type != COMMON_TYPE)
+            .then(|| self.types.get(page_type))
+            .flatten();
+        common
+            .into_iter()
+            .chain(specific)
+            .flat_map(|schema| schema.fields.iter())
+            .map(|(name, field)| (name.as_str(), field))
+    }
+}
+
+fn field(kind: FieldType) -> FieldSchema {
+    FieldSchema {
+        kind,
+        required: false,
+        default: None,
+    }
+}
+
+fn schema<const N: usize>(fields: [(&str, FieldSchema); N]) -> PageTypeSchema {
+    PageTypeSchema {
+        fields: fields
+            .into_iter()
+            .map(|(name, field)| (name.to_string(), field))
+            .collect(),
+    }
+}
+
+fn is_missing(value: Option<&JsonValue>) -> bool {
+    match value {
+        None | Some(JsonValue::Null) => true,
+        Some(JsonValue::String(s)) => s.trim().is_empty(),
+        Some(JsonValue::Array(items)) => items.is_empty(),
+        Some(_) => false,
+    }
+}
+
+fn check_value(name: &str, kind: &FieldType, value: &JsonValue, issues: &mut Vec<SchemaIssue>) {
+    let wrong_type = |expected: &str| {
+        SchemaIssue::error(
+            name,
+            IssueCode::WrongType,
+            format!("{name} should be {expected}, found {}.", describe(value)),
+        )
+    };
+
+    match kind {
+        FieldType::String => match value {
+            JsonValue::String(_) => {}
+            JsonValue::Number(_) | JsonValue::Bool(_) => issues.push(SchemaIssue::warning(
+                name,
+                IssueCode::WrongType,
+                format!("{name} should be text; quote {value} to keep it as written."),
+            )),
+            _ => issues.push(wrong_type("text")),
+        },
+        FieldType::StringList => match value {
+            JsonValue::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    if !item.is_string() {
+                        issues.push(SchemaIssue::error(
+                            &format!("{name}[{index}]"),
+                            IssueCode::WrongType,
+                            format!("{name} items should be text, found {}.", describe(item)),
+                        ));
+                    }
+                }
+            }
+            JsonValue::String(_) => issues.push(SchemaIssue::warning(
+                name,
+                IssueCode::WrongType,
+                format!("{name} should be a list; the single value is read as one item."),
+            )),
+            _ => issues.push(wrong_type("a list of text")),
+        },
+        FieldType::Date => match value.as_str() {
+            Some(text) if parse_date(text).is_some() => {}
+            Some(text) => issues.push(SchemaIssue::error(
+                name,
+                IssueCode::InvalidValue,
+                format!("{name} should be a YYYY-MM-DD date, found \"{text}\"."),
+            )),
+            None => issues.push(wrong_type("a YYYY-MM-DD date")),
+        },
+        FieldType::DateTime => match value.as_str() {
+            Some(text) if parse_datetime(text) => {}
+            Some(text) if parse_date(text).is_some() => issues.push(SchemaIssue::warning(
+                name,
+                IssueCode::InvalidValue,
+                format!("{name} has a date but no time."),
+            )),
+            Some(text) => issues.push(SchemaIssue::error(
+                name,
+                IssueCode::InvalidValue,
+                format!("{name} should be a date and time, found \"{text}\"."),
+            )),
+            None => issues.push(wrong_type("a date and time")),
+        },
+        FieldType::Number { min, max } => {
+            let number = match value {
+                JsonValue::Number(number) => number.as_f64(),
+                JsonValue::String(text) => {
+                    let parsed = text.trim().parse::<f64>().ok();
+                    if parsed.is_some() {
+                        issues.push(SchemaIssue::warning(
+                            name,
+                            IssueCode::WrongType,
+                            format!("{name} is a number written as text."),
+                        ));
+                    }
+                    parsed
+                }
+                _ => None,
+            };
+            let Some(number) = number else {
+                issues.push(wrong_type("a number"));
+                return;
+            };
+            if min.is_some_and(|min| number < min) || max.is_some_and(|max| number > max) {
+                issues.push(SchemaIssue::error(
+                    name,
+                    IssueCode::InvalidValue,
+                    format!(
+                        "{name} should be between {} and {}, found {number}.",
+                        min.map_or("-∞".to_string(), |min| min.to_string()),
+                        max.map_or("∞".to_string(), |max| max.to_string()),
+                    ),
+                ));
+            }
+        }
+        FieldType::Boolean => match value {
+            JsonValue::Bool(_) => {}
+            JsonValue::String(text) if matches!(text.trim(), "true" | "false") => {
+                issues.push(SchemaIssue::warning(
+                    name,
+                    IssueCode::WrongType,
+                    format!("{name} is a boolean written as text."),
+                ))
+            }
+            _ => issues.push(wrong_type("true or false")),
+        },
+        FieldType::Object => {
+            if !value.is_object() {
+                issues.push(wrong_type("a mapping"));
+            }
+        }
+        FieldType::Enum { values } => match value.as_str() {
+            Some(text) if values.iter().any(|allowed| allowed == text.trim()) => {}
+            Some(text) => issues.push(SchemaIssue::error(
+                name,
+                IssueCode::InvalidValue,
+                format!(
+                    "{name} should be one of {}, found \"{text}\".",
+                    values.join(", ")
+                ),
+            )),
+            None => issues.push(wrong_type(&format!("one of {}", values.join(", ")))),
+        },
+    }
+}
+
+fn describe(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "nothing",
+        JsonValue::Bool(_) => "a boolean",
+        JsonValue::Number(_) => "a number",
+        JsonValue::String(_) => "text",
+        JsonValue::Array(_) => "a list",
+        JsonValue::Object(_) => "a mapping",
+    }
+}
+
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    let text = text.trim();
+    let text = text
+        .strip_prefix("[[")
+        .and_then(|rest| rest.strip_suffix("]]"))
+        .unwrap_or(text);
+    NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()
+}
+
+fn parse_datetime(text: &str) -> bool {
+    let text = text.trim();
+    DateTime::parse_from_rfc3339(text).is_ok()
+        || [
+            "%Y-%m-%dT%H:%M:%S",
+            "%Y-%m-%dT%H:%M",
+            "%Y-%m-%d %H:%M:%S",
+            "%Y-%m-%d %H:%M",
+        ]
+        .iter()
+        .any(|format| NaiveDateTime::parse_from_str(text, format).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IssueCode, IssueSeverity, PageSchemas};
+    use serde_json::{json, Map as JsonMap, Value as JsonValue};
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn frontmatter(value: JsonValue) -> JsonMap<String, JsonValue> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn reports_malformed_meeting_fields() {
+        let schemas = PageSchemas::builtin();
+        let issues = schemas.validate(&frontmatter(json!({
+            "type": "meeting",
+            "started_at": "tomorrow",
+            "ended_at": "2026-03-16",
+            "participants": ["Alex", 3],
+            "location": 42,
+            "session_kind": "brainstorm",
+        })));
+
+        let summary: Vec<(&str, IssueSeverity, IssueCode)> = issues
+            .iter()
+            .map(|issue| (issue.field.as_str(), issue.severity, issue.code))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("ended_at", IssueSeverity::Warning, IssueCode::InvalidValue),
+                ("location", IssueSeverity::Warning, IssueCode::WrongType),
+                (
+                    "participants[1]",
+                    IssueSeverity::Error,
+                    IssueCode::WrongType
+                ),
+                (
+                    "session_kind",
+                    IssueSeverity::Error,
+                    IssueCode::InvalidValue
+                ),
+                ("started_at", IssueSeverity::Error, IssueCode::InvalidValue),
+            ]
+        );
+    }
+
+    #[test]
+    fn infers_link_pages_and_requires_source() {
+        let schemas = PageSchemas::builtin();
+        let page = frontmatter(json!({ "type": "page", "link_kind": "generic" }));
+        assert_eq!(schemas.page_type(&page), "link");
+
+        let issues = schemas.validate(&page);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].field, "source");
+        assert!(issues[0].is_error());
+    }
+
+    #[test]
+    fn loads_user_defined_types() {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("philo-page-types-{unique}.json"));
+        fs::write(
+            &path,
+            r#"{
+              "types": {
+                "book": {
+                  "fields": {
+                    "author": { "type": "string", "required": true },
+                    "rating": { "type": "number", "min": 1, "max": 5 },
+                    "status": { "type": "enum", "values": ["queued", "reading", "done"], "default": "queued" }
+                  }
+                },
+                "project": {
+                  "fields": { "budget": { "type": "number" } }
+                }
+              }
+            }"#,
+        )
+        .unwrap();
+
+        let schemas = PageSchemas::load(&path).unwrap();
+        let book = frontmatter(json!({ "type": "book", "rating": 7 }));
+        let issues = schemas.validate(&book);
+        assert_eq!(
+            issues
+                .iter()
+                .map(|issue| issue.field.as_str())
+                .collect::<Vec<_>>(),
+            vec!["author", "rating"]
+        );
+        assert_eq!(
+            schemas.missing_defaults("book", &book),
+            vec![("status".to_string(), json!("queued"))]
+        );
+
+        let project = frontmatter(json!({ "type": "project", "budget": "lots", "status": "done" }));
+        assert_eq!(schemas.validate(&project)[0].field, "budget");
+
+        let unknown = frontmatter(json!({ "type": "recipe" }));
+        assert_eq!(schemas.validate(&unknown)[0].code, IssueCode::UnknownType);
+    }
+}
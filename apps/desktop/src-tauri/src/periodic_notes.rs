@@ -0,0 +1,421 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const ROLLUP_START_MARKER: &str = "<!-- philo:rollup:start -->";
+const ROLLUP_END_MARKER: &str = "<!-- philo:rollup:end -->";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PeriodKind {
+    Week,
+    Month,
+    Quarter,
+}
+
+impl PeriodKind {
+    pub const ALL: [PeriodKind; 3] = [PeriodKind::Week, PeriodKind::Month, PeriodKind::Quarter];
+
+    pub fn from_flag(flag: &str) -> Option<Self> {
+        match flag {
+            "--week" => Some(PeriodKind::Week),
+            "--month" => Some(PeriodKind::Month),
+            "--quarter" => Some(PeriodKind::Quarter),
+            _ => None,
+        }
+    }
+
+    pub fn default_filename_pattern(self) -> &'static str {
+        match self {
+            PeriodKind::Week => "{GGGG}-W{WW}",
+            PeriodKind::Month => "{YYYY}-{MM}",
+            PeriodKind::Quarter => "{YYYY}-Q{Q}",
+        }
+    }
+
+    /// The key Obsidian's Periodic Notes plugin stores this kind under.
+    pub fn obsidian_key(self) -> &'static str {
+        match self {
+            PeriodKind::Week => "weekly",
+            PeriodKind::Month => "monthly",
+            PeriodKind::Quarter => "quarterly",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Period {
+    pub kind: PeriodKind,
+    /// `2026-W41`, `2026-10` or `2026-Q4`.
+    pub key: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+fn month_end(year: i32, month: u32) -> Option<NaiveDate> {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1).map(|date| date - Duration::days(1))
+}
+
+impl Period {
+    /// Parses ISO week (`2026-W41`), month (`2026-10`) and quarter (`2026-Q4`) keys.
+    pub fn parse(kind: PeriodKind, value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        let invalid = || match kind {
+            PeriodKind::Week => format!("Invalid week: {value}. Use YYYY-Www, e.g. 2026-W41."),
+            PeriodKind::Month => format!("Invalid month: {value}. Use YYYY-MM, e.g. 2026-10."),
+            PeriodKind::Quarter => format!("Invalid quarter: {value}. Use YYYY-Qn, e.g. 2026-Q4."),
+        };
+        let (year, rest) = value.split_once('-').ok_or_else(invalid)?;
+        let year = year.parse::<i32>().map_err(|_| invalid())?;
+
+        match kind {
+            PeriodKind::Week => {
+                let week = rest
+                    .strip_prefix(['W', 'w'])
+                    .and_then(|week| week.parse::<u32>().ok())
+                    .ok_or_else(invalid)?;
+                let start =
+                    NaiveDate::from_isoywd_opt(year, week, Weekday::Mon).ok_or_else(invalid)?;
+                Ok(Self::containing(kind, start))
+            }
+            PeriodKind::Month => {
+                let month = rest.parse::<u32>().map_err(|_| invalid())?;
+                let start = NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(invalid)?;
+                Ok(Self::containing(kind, start))
+            }
+            PeriodKind::Quarter => {
+                let quarter = rest
+                    .strip_prefix(['Q', 'q'])
+                    .and_then(|quarter| quarter.parse::<u32>().ok())
+                    .filter(|quarter| (1..=4).contains(quarter))
+                    .ok_or_else(invalid)?;
+                let start =
+                    NaiveDate::from_ymd_opt(year, (quarter - 1) * 3 + 1, 1).ok_or_else(invalid)?;
+                Ok(Self::containing(kind, start))
+            }
+        }
+    }
+
+    pub fn containing(kind: PeriodKind, date: NaiveDate) -> Self {
+        match kind {
+            PeriodKind::Week => {
+                let week = date.iso_week();
+                let start = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+                Self {
+                    kind,
+                    key: format!("{:04}-W{:02}", week.year(), week.week()),
+                    start,
+                    end: start + Duration::days(6),
+                }
+            }
+            PeriodKind::Month => {
+                let start = date.with_day(1).unwrap_or(date);
+                Self {
+                    kind,
+                    key: format!("{:04}-{:02}", date.year(), date.month()),
+                    start,
+                    end: month_end(date.year(), date.month()).unwrap_or(start),
+                }
+            }
+            PeriodKind::Quarter => {
+                let quarter = (date.month() - 1) / 3 + 1;
+                let first_month = (quarter - 1) * 3 + 1;
+                let start = NaiveDate::from_ymd_opt(date.year(), first_month, 1).unwrap_or(date);
+                Self {
+                    kind,
+                    key: format!("{:04}-Q{quarter}", date.year()),
+                    start,
+                    end: month_end(date.year(), first_month + 2).unwrap_or(start),
+                }
+            }
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match self.kind {
+            PeriodKind::Week => {
+                let week = self.start.iso_week();
+                format!("Week {}, {}", week.week(), week.year())
+            }
+            PeriodKind::Month => self.start.format("%B %Y").to_string(),
+            PeriodKind::Quarter => format!(
+                "Q{} {}",
+                (self.start.month() - 1) / 3 + 1,
+                self.start.year()
+            ),
+        }
+    }
+
+    pub fn days(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        self.start
+            .iter_days()
+            .take_while(move |date| *date <= self.end)
+    }
+}
+
+/// Fills `{GGGG}`, `{WW}`, `{YYYY}`, `{MM}` and `{Q}` in a periodic filename pattern. Weekly
+/// notes use the ISO week-year for `{YYYY}` so the last days of December land in week 1.
+pub fn apply_period_pattern(pattern: &str, period: &Period) -> String {
+    let week = period.start.iso_week();
+    let year = match period.kind {
+        PeriodKind::Week => week.year(),
+        _ => period.start.year(),
+    };
+    pattern
+        .replace("{GGGG}", &format!("{:04}", week.year()))
+        .replace("{WW}", &format!("{:02}", week.week()))
+        .replace("{YYYY}", &format!("{year:04}"))
+        .replace("{MM}", &format!("{:02}", period.start.month()))
+        .replace("{Q}", &((period.start.month() - 1) / 3 + 1).to_string())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PeriodicNoteConfig {
+    pub dir: PathBuf,
+    pub filename_pattern: String,
+}
+
+impl PeriodicNoteConfig {
+    pub fn path(&self, period: &Period) -> PathBuf {
+        self.dir.join(format!(
+            "{}.md",
+            apply_period_pattern(&self.filename_pattern, period)
+        ))
+    }
+}
+
+/// Periodic notes default to living next to the daily notes with their default patterns.
+pub fn default_periodic_notes(journal_dir: &Path) -> HashMap<PeriodKind, PeriodicNoteConfig> {
+    PeriodKind::ALL
+        .into_iter()
+        .map(|kind| {
+            (
+                kind,
+                PeriodicNoteConfig {
+                    dir: journal_dir.to_path_buf(),
+                    filename_pattern: kind.default_filename_pattern().to_string(),
+                },
+            )
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RollupItem {
+    pub text: String,
+    pub date: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rollup {
+    pub completed: Vec<RollupItem>,
+    pub meetings: Vec<RollupItem>,
+    pub open: Vec<RollupItem>,
+}
+
+/// Gathers checked tasks, meetings and still-open tasks from `(date, markdown)` daily notes in
+/// date order. A task that was carried over and later checked off only shows up as completed.
+pub fn collect_rollup(notes: &[(String, String)], meetings: Vec<RollupItem>) -> Rollup {
+    let task_re = Regex::new(r"^\s*[-*+]\s+\[([ xX])\]\s+(.+?)\s*$").expect("valid task regex");
+    let mut completed: Vec<RollupItem> = Vec::new();
+    let mut open: Vec<RollupItem> = Vec::new();
+
+    for (date, markdown) in notes {
+        for line in markdown.lines() {
+            let Some(captures) = task_re.captures(line) else {
+                continue;
+            };
+            let text = captures[2].to_string();
+            if &captures[1] == " " {
+                open.retain(|item| item.text != text);
+                open.push(RollupItem {
+                    text,
+                    date: date.clone(),
+                });
+            } else if !completed.iter().any(|item| item.text == text) {
+                completed.push(RollupItem {
+                    text,
+                    date: date.clone(),
+                });
+            }
+        }
+    }
+    open.retain(|item| !completed.iter().any(|done| done.text == item.text));
+
+    Rollup {
+        completed,
+        meetings,
+        open,
+    }
+}
+
+/// Renders the rollup as a marked block so regenerating it replaces only Philo's section.
+pub fn render_rollup(rollup: &Rollup) -> String {
+    let mut sections = Vec::new();
+    if !rollup.completed.is_empty() {
+        sections.push(format!(
+            "## Completed\n{}",
+            rollup
+                .completed
+                .iter()
+                .map(|item| format!("- [x] {} ({})", item.text, item.date))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+    }
+    if !rollup.meetings.is_empty() {
+        sections.push(format!(
+            "## Meetings\n{}",
+            rollup
+                .meetings
+                .iter()
+                .map(|item| format!("- [[{}]] ({})", item.text, item.date))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+    }
+    if !rollup.open.is_empty() {
+        sections.push(format!(
+            "## Open\n{}",
+            rollup
+                .open
+                .iter()
+                .map(|item| format!("- [ ] {} ({})", item.text, item.date))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+    }
+    if sections.is_empty() {
+        sections.push("_Nothing to roll up from this period's daily notes._".to_string());
+    }
+
+    format!(
+        "{ROLLUP_START_MARKER}\n{}\n{ROLLUP_END_MARKER}",
+        sections.join("\n\n")
+    )
+}
+
+/// Replaces an earlier rollup block in `existing`, or appends one. An empty note gets a title.
+pub fn merge_rollup(existing: &str, period: &Period, block: &str) -> String {
+    if let (Some(start), Some(end)) = (
+        existing.find(ROLLUP_START_MARKER),
+        existing.find(ROLLUP_END_MARKER),
+    ) {
+        if start < end {
+            return format!(
+                "{}{block}{}",
+                &existing[..start],
+                &existing[end + ROLLUP_END_MARKER.len()..]
+            );
+        }
+    }
+
+    if existing.trim().is_empty() {
+        return format!("# {}\n\n{block}\n", period.title());
+    }
+    format!("{}\n\n{block}\n", existing.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_period_keys() {
+        let week = Period::parse(PeriodKind::Week, "2026-W41").unwrap();
+        assert_eq!(week.start, NaiveDate::from_ymd_opt(2026, 10, 5).unwrap());
+        assert_eq!(week.end, NaiveDate::from_ymd_opt(2026, 10, 11).unwrap());
+        assert_eq!(week.title(), "Week 41, 2026");
+
+        let new_year = Period::containing(
+            PeriodKind::Week,
+            NaiveDate::from_ymd_opt(2026, 12, 31).unwrap(),
+        );
+        assert_eq!(new_year.key, "2026-W53");
+
+        let month = Period::parse(PeriodKind::Month, "2026-02").unwrap();
+        assert_eq!(month.end, NaiveDate::from_ymd_opt(2026, 2, 28).unwrap());
+        assert_eq!(month.days().count(), 28);
+
+        let quarter = Period::parse(PeriodKind::Quarter, "2026-q4").unwrap();
+        assert_eq!(quarter.key, "2026-Q4");
+        assert_eq!(quarter.end, NaiveDate::from_ymd_opt(2026, 12, 31).unwrap());
+        assert_eq!(quarter.title(), "Q4 2026");
+
+        assert!(Period::parse(PeriodKind::Week, "2026-41").is_err());
+        assert!(Period::parse(PeriodKind::Quarter, "2026-Q5").is_err());
+    }
+
+    #[test]
+    fn applies_periodic_filename_patterns() {
+        let week = Period::containing(
+            PeriodKind::Week,
+            NaiveDate::from_ymd_opt(2025, 12, 30).unwrap(),
+        );
+        assert_eq!(
+            apply_period_pattern("{YYYY}/Weekly/{GGGG}-W{WW}", &week),
+            "2026/Weekly/2026-W01"
+        );
+
+        let quarter = Period::parse(PeriodKind::Quarter, "2026-Q3").unwrap();
+        assert_eq!(apply_period_pattern("{YYYY}-Q{Q}", &quarter), "2026-Q3");
+    }
+
+    #[test]
+    fn rolls_up_tasks_and_replaces_previous_block() {
+        let notes = vec![
+            (
+                "2026-10-05".to_string(),
+                "- [x] Ship beta\n- [ ] Email vendor\n- [ ] Book venue\n".to_string(),
+            ),
+            (
+                "2026-10-06".to_string(),
+                "  - [X] Email vendor\n- [ ] Book venue\n".to_string(),
+            ),
+        ];
+        let rollup = collect_rollup(
+            &notes,
+            vec![RollupItem {
+                text: "Design sync".to_string(),
+                date: "2026-10-06".to_string(),
+            }],
+        );
+
+        assert_eq!(
+            rollup
+                .completed
+                .iter()
+                .map(|item| item.text.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Ship beta", "Email vendor"]
+        );
+        assert_eq!(
+            rollup.open,
+            vec![RollupItem {
+                text: "Book venue".to_string(),
+                date: "2026-10-06".to_string(),
+            }]
+        );
+
+        let period = Period::parse(PeriodKind::Week, "2026-W41").unwrap();
+        let first = merge_rollup("", &period, &render_rollup(&rollup));
+        assert!(first.starts_with("# Week 41, 2026\n\n<!-- philo:rollup:start -->\n## Completed\n- [x] Ship beta (2026-10-05)"));
+        assert!(first.contains("## Meetings\n- [[Design sync]] (2026-10-06)"));
+
+        let edited = first.replace("# Week 41, 2026", "# Week 41, 2026\nMy reflection");
+        let regenerated = merge_rollup(&edited, &period, &render_rollup(&Rollup::default()));
+        assert!(regenerated.starts_with(
+            "# Week 41, 2026\nMy reflection\n\n<!-- philo:rollup:start -->\n_Nothing"
+        ));
+        assert!(!regenerated.contains("Ship beta"));
+    }
+}
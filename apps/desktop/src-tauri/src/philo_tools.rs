@@ -1,11 +1,31 @@
+use crate::note_templates::{
+    list_templates, load_calendar_events, load_template, open_task_values, open_tasks,
+    render_note_template, NoteTemplateKind, TemplateScope, TemplateValue,
+};
+use crate::obsidian::{
+    collect_tags, compatibility_report, extract_link_fragment, frontmatter_aliases,
+    periodic_note_settings, render_daily_note_template, split_link_fragment, split_tag_query,
+    tags_match, templates_folder, LinkFragment, ObsidianCompatibilityReport, PageAliases,
+};
+use crate::page_schema::{PageSchemas, SchemaIssue};
+use crate::periodic_notes::{
+    collect_rollup, default_periodic_notes, merge_rollup, render_rollup, Period, PeriodKind,
+    PeriodicNoteConfig, Rollup, RollupItem,
+};
 use crate::settings_paths::{
-    default_settings_path, normalize_filename_pattern, resolve_journal_dir,
+    default_settings_path, normalize_filename_pattern, normalize_folder, resolve_journal_dir,
+};
+use crate::site_export::{
+    build_site, write_site, SiteConfig, SiteNote, SiteNoteKind, SiteOptions, SiteSelection,
 };
-use chrono::{Duration, NaiveDate};
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
+use hypr_buffer::{fix_markdown, lint_markdown, Diagnostic, LintConfig, LintRule};
+use hypr_frontmatter::Frontmatter;
+use hypr_importer_notes::{ImportPlan, Source as ImportSource, VaultLayout};
 use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map as JsonMap, Value as JsonValue};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -23,6 +43,32 @@ pub struct PhiloSettings {
     pub vault_dir: String,
     #[serde(default)]
     pub daily_logs_folder: String,
+    #[serde(default)]
+    pub weekly_notes_folder: String,
+    #[serde(default)]
+    pub weekly_filename_pattern: String,
+    #[serde(default)]
+    pub monthly_notes_folder: String,
+    #[serde(default)]
+    pub monthly_filename_pattern: String,
+    #[serde(default)]
+    pub quarterly_notes_folder: String,
+    #[serde(default)]
+    pub quarterly_filename_pattern: String,
+    #[serde(default)]
+    pub templates_folder: String,
+    #[serde(default)]
+    pub assets_folder: String,
+    #[serde(default)]
+    pub daily_note_template: String,
+    #[serde(default)]
+    pub page_template: String,
+    #[serde(default)]
+    pub weekly_note_template: String,
+    #[serde(default)]
+    pub monthly_note_template: String,
+    #[serde(default)]
+    pub quarterly_note_template: String,
 }
 
 #[derive(Clone, Debug)]
@@ -31,6 +77,27 @@ pub struct NoteContext {
     pub journal_dir: PathBuf,
     pub pages_dir: PathBuf,
     pub filename_pattern: String,
+    /// The Obsidian vault root, when Philo is pointed at one.
+    pub vault_dir: Option<PathBuf>,
+    pub periodic_notes: HashMap<PeriodKind, PeriodicNoteConfig>,
+    pub templates_dir: PathBuf,
+    pub assets_dir: PathBuf,
+    /// The folder notes link attachments through, e.g. `assets` in `![](assets/image.png)`.
+    pub assets_link_root: String,
+    /// The template picked in settings for each kind of note, by name.
+    pub note_templates: HashMap<NoteTemplateKind, String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteTemplateRequest {
+    pub kind: Option<NoteTemplateKind>,
+    /// Overrides the template selected in settings for this kind.
+    pub template: Option<String>,
+    pub date: Option<String>,
+    pub title: Option<String>,
+    pub period: Option<String>,
+    pub city: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -59,6 +126,37 @@ struct PageEnvelope {
     page: PageRecord,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PeriodicNoteEnvelope {
+    note: PeriodicNoteRecord,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RollupEnvelope {
+    change: PeriodicNoteChange,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PeriodicAppliedEnvelope {
+    applied: Vec<AppliedPeriodicNote>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TemplateListEnvelope {
+    dir: String,
+    templates: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ObsidianReportEnvelope {
+    report: ObsidianCompatibilityReport,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 struct PageSearchEnvelope {
@@ -95,6 +193,69 @@ struct PageAppliedEnvelope {
     applied: Vec<AppliedPage>,
 }
 
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PageLintEnvelope {
+    pages: Vec<PageLintResult>,
+    checked: usize,
+    errors: usize,
+    warnings: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LintEnvelope {
+    files: Vec<LintFileResult>,
+    checked: usize,
+    problems: usize,
+    fixed: usize,
+    applied: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportEnvelope {
+    summary: String,
+    #[serde(flatten)]
+    plan: ImportPlan,
+    written: usize,
+    applied: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SiteExportEnvelope {
+    out_dir: String,
+    selection: SiteSelection,
+    pages: usize,
+    daily_notes: usize,
+    attachments: usize,
+    excluded_private: usize,
+    files: Vec<String>,
+    warnings: Vec<String>,
+    written: usize,
+    applied: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LintFileResult {
+    path: String,
+    diagnostics: Vec<Diagnostic>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unified_diff: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PageLintResult {
+    title: String,
+    path: String,
+    #[serde(rename = "type")]
+    r#type: String,
+    issues: Vec<SchemaIssue>,
+}
+
 #[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NoteRecord {
@@ -104,6 +265,36 @@ pub struct NoteRecord {
     pub path: String,
 }
 
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodicNoteRecord {
+    pub kind: PeriodKind,
+    pub period: String,
+    pub title: String,
+    pub from: String,
+    pub to: String,
+    pub markdown: String,
+    pub path: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodicNoteChange {
+    pub kind: PeriodKind,
+    pub period: String,
+    pub before_markdown: String,
+    pub after_markdown: String,
+    pub unified_diff: String,
+    pub rollup: Rollup,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedPeriodicNote {
+    pub period: String,
+    pub path: String,
+}
+
 #[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchHit {
@@ -139,6 +330,13 @@ pub struct PageRecord {
     pub link_data: Option<JsonValue>,
     pub frontmatter: JsonValue,
     pub has_frontmatter: bool,
+    pub aliases: Vec<String>,
+    /// The heading section or block a `Page#Heading` / `Page#^block` lookup pointed at.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fragment: Option<String>,
+    /// Frontmatter that does not match the page type's schema.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub issues: Vec<SchemaIssue>,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -199,17 +397,81 @@ pub enum ToolCommand {
 
 #[derive(Clone, Debug)]
 enum ParsedCommand {
-    NoteSearch { query: String, limit: usize },
-    NoteRead { date: String },
-    NoteReadRange { from: String, to: String },
-    NoteCreate { date: String },
-    NoteUpdate { date: String, apply: bool },
-    NoteDelete { date: String },
-    PageSearch { query: String, limit: usize },
-    PageRead { title: String },
-    PageCreate { title: String },
-    PageUpdate { title: String, apply: bool },
-    PageDelete { title: String },
+    NoteSearch {
+        query: String,
+        limit: usize,
+    },
+    NoteRead {
+        date: String,
+    },
+    NoteReadRange {
+        from: String,
+        to: String,
+    },
+    NoteCreate {
+        date: String,
+        template: Option<String>,
+        city: Option<String>,
+    },
+    NoteUpdate {
+        date: String,
+        apply: bool,
+    },
+    NoteDelete {
+        date: String,
+    },
+    PageSearch {
+        query: String,
+        limit: usize,
+    },
+    PageRead {
+        title: String,
+    },
+    PageCreate {
+        title: String,
+        template: Option<String>,
+    },
+    PageUpdate {
+        title: String,
+        replace_frontmatter: bool,
+        apply: bool,
+    },
+    PageDelete {
+        title: String,
+    },
+    PageLint {
+        page_type: Option<String>,
+    },
+    PeriodicNoteRead {
+        period: Period,
+    },
+    PeriodicNoteCreate {
+        period: Period,
+        template: Option<String>,
+    },
+    NoteRollup {
+        period: Period,
+        apply: bool,
+    },
+    TemplateList,
+    ObsidianReport,
+    Lint {
+        rules: Vec<LintRule>,
+        fix: bool,
+        apply: bool,
+    },
+    Import {
+        source: ImportSource,
+        path: PathBuf,
+        apply: bool,
+    },
+    SiteExport {
+        out_dir: PathBuf,
+        selection: SiteSelection,
+        private_fields: Vec<String>,
+        title: Option<String>,
+        apply: bool,
+    },
 }
 
 pub fn resolve_note_context() -> Result<NoteContext, String> {
@@ -243,14 +505,144 @@ pub fn resolve_note_context() -> Result<NoteContext, String> {
         PathBuf::from(settings.vault_dir.trim()).join("pages")
     };
 
+    let periodic_notes = resolve_periodic_notes(&settings, &journal_dir);
+    let templates_dir = resolve_templates_dir(&settings, &journal_dir, &default_base_dir);
+    let assets_dir = resolve_assets_dir(&settings, &journal_dir);
+    let assets_link_root = Some(normalize_folder(&settings.assets_folder))
+        .filter(|folder| !folder.is_empty() && folder != ".")
+        .unwrap_or_else(|| "assets".to_string());
+    let note_templates = [
+        (NoteTemplateKind::Daily, &settings.daily_note_template),
+        (NoteTemplateKind::Page, &settings.page_template),
+        (NoteTemplateKind::Weekly, &settings.weekly_note_template),
+        (NoteTemplateKind::Monthly, &settings.monthly_note_template),
+        (
+            NoteTemplateKind::Quarterly,
+            &settings.quarterly_note_template,
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, name)| !name.trim().is_empty())
+    .map(|(kind, name)| (kind, name.trim().to_string()))
+    .collect();
+
     Ok(NoteContext {
         settings_path,
         journal_dir,
         pages_dir,
         filename_pattern: normalize_filename_pattern(&settings.filename_pattern),
+        vault_dir: Some(settings.vault_dir.trim())
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from),
+        periodic_notes,
+        templates_dir,
+        assets_dir,
+        assets_link_root,
+        note_templates,
     })
 }
 
+/// Mirrors the frontend: `assetsFolder` when absolute, else under the vault, else under the
+/// journal; unset means `assets` in the journal.
+fn resolve_assets_dir(settings: &PhiloSettings, journal_dir: &Path) -> PathBuf {
+    let folder = normalize_folder(&settings.assets_folder);
+    if folder.is_empty() {
+        return journal_dir.join("assets");
+    }
+    let raw = settings.assets_folder.trim();
+    if Path::new(raw).is_absolute() {
+        return PathBuf::from(raw);
+    }
+    match Some(settings.vault_dir.trim()).filter(|value| !value.is_empty()) {
+        Some(vault_dir) => Path::new(vault_dir).join(folder),
+        None => journal_dir.join(folder),
+    }
+}
+
+/// Templates live in `templatesFolder`, else the vault's Obsidian templates folder, else a
+/// `templates` folder next to the pages.
+fn resolve_templates_dir(
+    settings: &PhiloSettings,
+    journal_dir: &Path,
+    default_base_dir: &Path,
+) -> PathBuf {
+    let vault_dir = Some(settings.vault_dir.trim())
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from);
+    let folder = Some(normalize_folder(&settings.templates_folder))
+        .filter(|value| !value.is_empty())
+        .or_else(|| vault_dir.as_deref().and_then(templates_folder));
+    let base_dir = vault_dir
+        .or_else(|| journal_dir.parent().map(Path::to_path_buf))
+        .unwrap_or_else(|| default_base_dir.to_path_buf());
+    match folder.as_deref() {
+        Some(".") => base_dir,
+        Some(folder) => base_dir.join(folder),
+        None => base_dir.join("templates"),
+    }
+}
+
+/// Philo's own periodic note settings win; in a vault, unset ones fall back to the Periodic
+/// Notes plugin and then to the daily notes folder with the default patterns.
+fn resolve_periodic_notes(
+    settings: &PhiloSettings,
+    journal_dir: &Path,
+) -> HashMap<PeriodKind, PeriodicNoteConfig> {
+    let vault_dir = Some(settings.vault_dir.trim())
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from);
+    let mut configs = default_periodic_notes(journal_dir);
+
+    for kind in PeriodKind::ALL {
+        let (folder, pattern) = match kind {
+            PeriodKind::Week => (
+                &settings.weekly_notes_folder,
+                &settings.weekly_filename_pattern,
+            ),
+            PeriodKind::Month => (
+                &settings.monthly_notes_folder,
+                &settings.monthly_filename_pattern,
+            ),
+            PeriodKind::Quarter => (
+                &settings.quarterly_notes_folder,
+                &settings.quarterly_filename_pattern,
+            ),
+        };
+        let detected = vault_dir
+            .as_deref()
+            .and_then(|vault_dir| periodic_note_settings(vault_dir, kind));
+        let folder = Some(normalize_folder(folder))
+            .filter(|value| !value.is_empty())
+            .or_else(|| {
+                detected
+                    .as_ref()
+                    .map(|(folder, _)| normalize_folder(folder))
+                    .filter(|value| !value.is_empty())
+            });
+        let base_dir = vault_dir
+            .clone()
+            .or_else(|| journal_dir.parent().map(Path::to_path_buf))
+            .unwrap_or_else(|| journal_dir.to_path_buf());
+        let Some(config) = configs.get_mut(&kind) else {
+            continue;
+        };
+        match folder.as_deref() {
+            Some(".") => config.dir = base_dir,
+            Some(folder) => config.dir = base_dir.join(folder),
+            None => {}
+        }
+        if let Some(pattern) = Some(pattern.trim())
+            .filter(|value| !value.is_empty())
+            .map(ToOwned::to_owned)
+            .or_else(|| detected.and_then(|(_, pattern)| pattern))
+        {
+            config.filename_pattern = pattern;
+        }
+    }
+
+    configs
+}
+
 fn apply_filename_pattern(pattern: &str, date: &str) -> Result<String, String> {
     let [yyyy, mm, dd] = date
         .split('-')
@@ -411,12 +803,71 @@ fn parse_frontmatter(raw: &str) -> (Option<String>, String) {
     (city, parsed.body)
 }
 
-fn build_frontmatter(city: Option<&str>, body: &str) -> String {
+/// Sets `city` in a daily note's frontmatter, leaving everything else in the block as written.
+/// When `city` already matches, the block is kept byte for byte, even in layouts
+/// [`Frontmatter`] can't edit.
+fn build_frontmatter(
+    raw_block: Option<&str>,
+    city: Option<&str>,
+    body: &str,
+) -> Result<String, String> {
+    let city = city.map(str::trim).filter(|value| !value.is_empty());
+    if raw_block
+        .and_then(|block| parse_frontmatter(block).0)
+        .as_deref()
+        == city
+    {
+        return Ok(format!("{}{body}", raw_block.unwrap_or_default()));
+    }
+
+    let mut frontmatter = parse_frontmatter_block(raw_block)?;
     match city {
-        Some(value) if !value.trim().is_empty() => {
-            format!("---\ncity: {}\n---\n{}", value.trim(), body)
+        Some(city) => {
+            frontmatter
+                .set("city", city)
+                .map_err(|e| format!("Could not update frontmatter: {e}"))?;
+        }
+        None => {
+            frontmatter
+                .remove("city")
+                .map_err(|e| format!("Could not update frontmatter: {e}"))?;
         }
-        _ => body.to_string(),
+    }
+    Ok(render_frontmatter_block(&frontmatter, body))
+}
+
+/// The editable YAML inside a `---` block captured by `parse_markdown_frontmatter`.
+fn parse_frontmatter_block(raw_block: Option<&str>) -> Result<Frontmatter, String> {
+    let Some(block) = raw_block else {
+        return Ok(Frontmatter::default());
+    };
+    Frontmatter::parse(frontmatter_block_yaml(block))
+        .map_err(|e| format!("Could not edit frontmatter: {e}"))
+}
+
+fn frontmatter_block_yaml(block: &str) -> &str {
+    block
+        .strip_prefix("---\n")
+        .map(|rest| rest.strip_suffix('\n').unwrap_or(rest))
+        .and_then(|rest| rest.strip_suffix("---"))
+        .unwrap_or_default()
+}
+
+/// The keys of a `---` block, read as plain YAML whatever its layout.
+fn frontmatter_block_mapping(block: &str) -> Result<serde_yaml::Mapping, String> {
+    match serde_yaml::from_str(frontmatter_block_yaml(block)) {
+        Ok(serde_yaml::Value::Mapping(mapping)) => Ok(mapping),
+        Ok(serde_yaml::Value::Null) => Ok(serde_yaml::Mapping::new()),
+        Ok(_) => Err("Could not parse frontmatter: expected a mapping".to_string()),
+        Err(e) => Err(format!("Could not parse frontmatter: {e}")),
+    }
+}
+
+fn render_frontmatter_block(frontmatter: &Frontmatter, body: &str) -> String {
+    if frontmatter.is_empty() {
+        body.to_string()
+    } else {
+        format!("---\n{}---\n{}", frontmatter.render(), body)
     }
 }
 
@@ -508,7 +959,7 @@ fn parse_page_title_from_link_target(target: &str) -> Option<String> {
         return None;
     }
 
-    let path_only = trimmed.split(['?', '#']).next().unwrap_or(trimmed);
+    let path_only = trimmed.split(['?', '#', '^']).next().unwrap_or(trimmed);
     let decoded = decode_url_component(path_only);
     let decoded = decoded
         .trim_start_matches('/')
@@ -699,11 +1150,32 @@ fn page_link_kind_from_frontmatter(frontmatter: &JsonMap<String, JsonValue>) ->
     }
 }
 
+fn load_page_aliases(context: &NoteContext) -> PageAliases {
+    let mut aliases = PageAliases::default();
+    for path in collect_markdown_files(&context.pages_dir) {
+        let Some(title) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .map(normalize_page_title_input)
+            .filter(|value| !value.is_empty())
+        else {
+            continue;
+        };
+        let Ok(raw) = fs::read_to_string(&path) else {
+            continue;
+        };
+        aliases.insert_page(&title, &parse_markdown_frontmatter(&raw).frontmatter);
+    }
+    aliases
+}
+
 fn find_date_linking_to_page(context: &NoteContext, title: &str) -> Result<Option<String>, String> {
     let normalized_title = normalize_page_title_input(title);
     if normalized_title.is_empty() {
         return Ok(None);
     }
+    let aliases = load_page_aliases(context);
+    let target = aliases.resolve(&normalized_title);
 
     let root = match fs::canonicalize(&context.journal_dir) {
         Ok(path) => path,
@@ -726,7 +1198,10 @@ fn find_date_linking_to_page(context: &NoteContext, title: &str) -> Result<Optio
             continue;
         };
         let (_, body) = parse_frontmatter(&raw);
-        if !extract_linked_page_titles(&body).contains(&normalized_title) {
+        if !extract_linked_page_titles(&body)
+            .iter()
+            .any(|linked| aliases.resolve(linked) == target)
+        {
             continue;
         }
 
@@ -748,6 +1223,7 @@ fn page_frontmatter_to_value(frontmatter: &JsonMap<String, JsonValue>) -> JsonVa
 
 fn build_page_record(
     context: &NoteContext,
+    schemas: &PageSchemas,
     title: &str,
     path: &Path,
     raw: &str,
@@ -792,104 +1268,832 @@ fn build_page_record(
         },
         frontmatter: page_frontmatter_to_value(&frontmatter),
         has_frontmatter: parsed.has_frontmatter,
+        aliases: frontmatter_aliases(&frontmatter),
+        fragment: None,
+        issues: schemas.validate(&frontmatter),
     })
 }
 
-fn read_page(context: &NoteContext, title: &str) -> Result<Option<PageRecord>, String> {
-    let (normalized_title, path) = page_path(context, title)?;
-    let raw = match fs::read_to_string(&path) {
-        Ok(value) => value,
-        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
-        Err(err) => return Err(err.to_string()),
-    };
-
-    Ok(Some(build_page_record(
-        context,
-        &normalized_title,
-        &path,
-        &raw,
-        true,
-    )?))
+fn lint_config_path(context: &NoteContext) -> PathBuf {
+    context.settings_path.with_file_name("lint.json")
 }
 
-fn serialize_page_markdown(raw_block: Option<&str>, body: &str) -> String {
-    match raw_block {
-        Some(block) => format!("{block}{body}"),
-        None => body.to_string(),
+fn load_lint_config(context: &NoteContext) -> Result<LintConfig, String> {
+    let path = lint_config_path(context);
+    match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw)
+            .map_err(|e| format!("Could not parse {}: {}", path.display(), e)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(LintConfig::default()),
+        Err(err) => Err(err.to_string()),
     }
 }
 
-fn read_note(context: &NoteContext, date: &str) -> Result<Option<NoteRecord>, String> {
-    let path = note_path(context, date)?;
-    let raw = match fs::read_to_string(&path) {
-        Ok(value) => value,
-        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
-        Err(err) => return Err(err.to_string()),
-    };
-    let (city, markdown) = parse_frontmatter(&raw);
-    Ok(Some(NoteRecord {
-        date: date.to_string(),
-        city,
-        markdown,
-        path: path.to_string_lossy().to_string(),
-    }))
-}
-
-fn parse_iso_date(date: &str) -> Result<NaiveDate, String> {
-    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| format!("Invalid date: {}", date))
+/// Lowercased names a `[[wiki link]]` can resolve to: page titles and aliases, and daily notes by
+/// date as well as by file name.
+fn known_link_targets(context: &NoteContext) -> HashSet<String> {
+    let mut targets = HashSet::new();
+    for path in collect_markdown_files(&context.pages_dir) {
+        let Some(title) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        targets.insert(normalize_page_title_input(title).to_lowercase());
+        if let Ok(raw) = fs::read_to_string(&path) {
+            let frontmatter = parse_markdown_frontmatter(&raw).frontmatter;
+            targets.extend(
+                frontmatter_aliases(&frontmatter)
+                    .into_iter()
+                    .map(|alias| alias.to_lowercase()),
+            );
+        }
+    }
+    for path in collect_markdown_files(&context.journal_dir) {
+        if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+            targets.insert(stem.to_lowercase());
+        }
+        let relative = path
+            .strip_prefix(&context.journal_dir)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        if let Some(date) = parse_date_from_relative_path(&relative, &context.filename_pattern) {
+            targets.insert(date);
+        }
+    }
+    targets
 }
 
-fn read_notes_in_range(
+/// Lints every note and page. With `fix`, each file's fixes are collected into a diff and only
+/// written when `apply` is set; the diagnostics are then the ones the fixes could not resolve.
+fn lint_vault(
     context: &NoteContext,
-    from: &str,
-    to: &str,
-) -> Result<Vec<NoteRecord>, String> {
-    let from_date = parse_iso_date(from)?;
-    let to_date = parse_iso_date(to)?;
-    if from_date > to_date {
-        return Err("--from must be on or before --to.".to_string());
+    rules: &[LintRule],
+    fix: bool,
+    apply: bool,
+) -> Result<LintEnvelope, String> {
+    let mut config = load_lint_config(context)?;
+    if !rules.is_empty() {
+        config.rules = rules.iter().copied().collect();
     }
+    config.known_pages = Some(known_link_targets(context));
 
-    let span_days = (to_date - from_date).num_days();
-    if span_days > 31 {
-        return Err("Date range cannot exceed 31 days.".to_string());
-    }
+    let files = collect_markdown_files(&context.journal_dir)
+        .into_iter()
+        .chain(collect_markdown_files(&context.pages_dir))
+        .collect::<BTreeSet<_>>();
+    let mut envelope = LintEnvelope {
+        files: Vec::new(),
+        checked: 0,
+        problems: 0,
+        fixed: 0,
+        applied: apply,
+    };
+    for path in files {
+        let Ok(raw) = fs::read_to_string(&path) else {
+            continue;
+        };
+        envelope.checked += 1;
 
-    let mut notes = Vec::new();
-    let mut cursor = from_date;
-    while cursor <= to_date {
-        let date = cursor.format("%Y-%m-%d").to_string();
-        if let Some(note) = read_note(context, &date)? {
-            notes.push(note);
+        let (diagnostics, unified_diff) = if fix {
+            let (fixed, remaining) = fix_markdown(&raw, &config).map_err(|e| e.to_string())?;
+            if fixed == raw {
+                (remaining, None)
+            } else {
+                if apply {
+                    write_note(&path, &fixed)?;
+                }
+                envelope.fixed += 1;
+                (remaining, Some(build_unified_diff(&raw, &fixed)))
+            }
+        } else {
+            (
+                lint_markdown(&raw, &config).map_err(|e| e.to_string())?,
+                None,
+            )
+        };
+
+        envelope.problems += diagnostics.len();
+        if diagnostics.is_empty() && unified_diff.is_none() {
+            continue;
         }
-        cursor += Duration::days(1);
+        envelope.files.push(LintFileResult {
+            path: path.to_string_lossy().to_string(),
+            diagnostics,
+            unified_diff,
+        });
     }
+    Ok(envelope)
+}
 
-    Ok(notes)
+/// Plans an import from another app into the journal, pages and assets folders, and writes it
+/// only with `apply`. Existing files are never replaced.
+fn import_notes(
+    context: &NoteContext,
+    source: ImportSource,
+    path: &Path,
+    apply: bool,
+) -> Result<ImportEnvelope, String> {
+    let import = hypr_importer_notes::parse(source, path).map_err(|e| e.to_string())?;
+    let layout = VaultLayout {
+        journal_dir: context.journal_dir.clone(),
+        pages_dir: context.pages_dir.clone(),
+        assets_dir: context.assets_dir.clone(),
+        assets_link_root: context.assets_link_root.clone(),
+        filename_pattern: context.filename_pattern.clone(),
+    };
+    let plan = hypr_importer_notes::plan_import(&import, &layout).map_err(|e| e.to_string())?;
+    let written = if apply {
+        hypr_importer_notes::apply_import(&plan).map_err(|e| e.to_string())?
+    } else {
+        0
+    };
+
+    Ok(ImportEnvelope {
+        summary: plan.to_string(),
+        plan,
+        written,
+        applied: apply,
+    })
 }
 
-fn write_note(path: &Path, content: &str) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+fn site_config_path(context: &NoteContext) -> PathBuf {
+    context.settings_path.with_file_name("site.json")
+}
+
+fn load_site_config(context: &NoteContext) -> Result<SiteConfig, String> {
+    let path = site_config_path(context);
+    if !path.exists() {
+        return Ok(SiteConfig::default());
     }
+    let raw = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&raw).map_err(|e| format!("Could not parse {}: {}", path.display(), e))
+}
 
-    fs::write(path, content).map_err(|e| e.to_string())
+/// The vault, or the folder holding the journal when Philo isn't pointed at one.
+fn site_vault_root(context: &NoteContext) -> PathBuf {
+    context
+        .vault_dir
+        .clone()
+        .or_else(|| context.journal_dir.parent().map(Path::to_path_buf))
+        .unwrap_or_else(|| context.journal_dir.clone())
 }
 
-fn create_note(context: &NoteContext, date: &str) -> Result<NoteRecord, String> {
-    let path = note_path(context, date)?;
-    if path.exists() {
+/// Daily notes and pages as the site exporter sees them. Drawings and widgets are left out.
+fn collect_site_notes(context: &NoteContext) -> Result<Vec<SiteNote>, String> {
+    let schemas = load_page_schemas(context)?;
+    let vault_root = site_vault_root(context);
+    let folder_of = |path: &Path| {
+        path.parent()
+            .and_then(|dir| dir.strip_prefix(&vault_root).ok())
+            .map(|dir| dir.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default()
+    };
+
+    let mut notes = Vec::new();
+    let mut seen = HashSet::new();
+    let journal = collect_markdown_files(&context.journal_dir)
+        .into_iter()
+        .map(|path| (path, SiteNoteKind::Daily));
+    let pages = collect_markdown_files(&context.pages_dir)
+        .into_iter()
+        .map(|path| (path, SiteNoteKind::Page));
+    for (path, kind) in journal.chain(pages) {
+        let lower = path.to_string_lossy().to_lowercase();
+        if lower.ends_with(".excalidraw.md") || lower.ends_with(".widget.md") {
+            continue;
+        }
+        if !seen.insert(path.clone()) {
+            continue;
+        }
+
+        let (title, date) = match kind {
+            SiteNoteKind::Daily => {
+                let Some(relative) = path
+                    .strip_prefix(&context.journal_dir)
+                    .ok()
+                    .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+                else {
+                    continue;
+                };
+                let Some(date) =
+                    parse_date_from_relative_path(&relative, &context.filename_pattern)
+                else {
+                    continue;
+                };
+                (date.clone(), Some(date))
+            }
+            SiteNoteKind::Page => {
+                let title = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .map(normalize_page_title_input)
+                    .unwrap_or_default();
+                if title.is_empty() {
+                    continue;
+                }
+                (title, None)
+            }
+        };
+        let Ok(raw) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let parsed = parse_markdown_frontmatter(&raw);
+        notes.push(SiteNote {
+            kind,
+            title,
+            date,
+            folder: folder_of(&path),
+            page_type: schemas.page_type(&parsed.frontmatter),
+            frontmatter: parsed.frontmatter,
+            body: parsed.body,
+            path,
+        });
+    }
+    Ok(notes)
+}
+
+/// Renders the selected notes to a static site and writes it to `out_dir` only with `apply`.
+fn export_site(
+    context: &NoteContext,
+    out_dir: &Path,
+    selection: SiteSelection,
+    private_fields: &[String],
+    title: Option<&str>,
+    apply: bool,
+) -> Result<SiteExportEnvelope, String> {
+    let config = load_site_config(context)?;
+    let title = title
+        .or(Some(config.title.as_str()))
+        .map(str::trim)
+        .filter(|title| !title.is_empty())
+        .unwrap_or("Notes")
+        .to_string();
+    let options = SiteOptions {
+        title,
+        selection,
+        private_fields: config
+            .private_fields
+            .iter()
+            .chain(private_fields)
+            .cloned()
+            .collect(),
+        vault_dir: site_vault_root(context),
+        assets_dir: context.assets_dir.clone(),
+        assets_link_root: context.assets_link_root.clone(),
+    };
+
+    let site = build_site(&collect_site_notes(context)?, &options)?;
+    let written = if apply {
+        write_site(&site, out_dir)?
+    } else {
+        0
+    };
+
+    Ok(SiteExportEnvelope {
+        out_dir: out_dir.to_string_lossy().to_string(),
+        selection: options.selection,
+        pages: site.pages,
+        daily_notes: site.daily_notes,
+        attachments: site.attachments,
+        excluded_private: site.excluded_private,
+        files: site.files.iter().map(|file| file.path.clone()).collect(),
+        warnings: site.warnings,
+        written,
+        applied: apply,
+    })
+}
+
+fn page_types_path(context: &NoteContext) -> PathBuf {
+    context.settings_path.with_file_name("page-types.json")
+}
+
+fn load_page_schemas(context: &NoteContext) -> Result<PageSchemas, String> {
+    PageSchemas::load(&page_types_path(context))
+}
+
+/// Checks every page in the vault, optionally only those of one type, and keeps the ones with
+/// issues.
+fn lint_pages(context: &NoteContext, page_type: Option<&str>) -> Result<PageLintEnvelope, String> {
+    let schemas = load_page_schemas(context)?;
+    let mut envelope = PageLintEnvelope {
+        pages: Vec::new(),
+        checked: 0,
+        errors: 0,
+        warnings: 0,
+    };
+    let mut files = collect_markdown_files(&context.pages_dir);
+    files.sort();
+    for path in files {
+        let Ok(raw) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let parsed = parse_markdown_frontmatter(&raw);
+        let r#type = schemas.page_type(&parsed.frontmatter);
+        if page_type.is_some_and(|wanted| wanted != r#type) {
+            continue;
+        }
+        envelope.checked += 1;
+
+        let issues = schemas.validate(&parsed.frontmatter);
+        if issues.is_empty() {
+            continue;
+        }
+        let errors = issues.iter().filter(|issue| issue.is_error()).count();
+        envelope.errors += errors;
+        envelope.warnings += issues.len() - errors;
+        let title = path
+            .strip_prefix(&context.pages_dir)
+            .unwrap_or(&path)
+            .with_extension("")
+            .to_string_lossy()
+            .replace('\\', "/");
+        envelope.pages.push(PageLintResult {
+            title,
+            path: path.to_string_lossy().to_string(),
+            r#type,
+            issues,
+        });
+    }
+    Ok(envelope)
+}
+
+fn read_page(context: &NoteContext, title: &str) -> Result<Option<PageRecord>, String> {
+    let (mut normalized_title, mut path) = page_path(context, title)?;
+    if !path.exists() {
+        let resolved = load_page_aliases(context).resolve(&normalized_title);
+        if resolved != normalized_title {
+            (normalized_title, path) = page_path(context, &resolved)?;
+        }
+    }
+    let raw = match fs::read_to_string(&path) {
+        Ok(value) => value,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let schemas = load_page_schemas(context)?;
+    Ok(Some(build_page_record(
+        context,
+        &schemas,
+        &normalized_title,
+        &path,
+        &raw,
+        true,
+    )?))
+}
+
+/// Reads a page through an Obsidian link target, so `Page#Heading` and `Page#^block` return the
+/// referenced section alongside the page.
+fn read_page_link(context: &NoteContext, target: &str) -> Result<Option<PageRecord>, String> {
+    let (title, fragment) = split_link_fragment(target);
+    let Some(fragment) = fragment else {
+        return read_page(context, target);
+    };
+    let Some(mut page) = read_page(context, title)? else {
+        return Ok(None);
+    };
+    page.fragment = Some(
+        extract_link_fragment(&page.markdown, &fragment).ok_or_else(|| {
+            format!(
+                "{} has no {}.",
+                page.title,
+                match fragment {
+                    LinkFragment::Heading(heading) => format!("heading {heading}"),
+                    LinkFragment::Block(id) => format!("block ^{id}"),
+                }
+            )
+        })?,
+    );
+    Ok(Some(page))
+}
+
+/// Writes `body` under the page's existing frontmatter. A frontmatter block at the top of `body`
+/// is merged into the existing one key by key, so untouched keys keep their comments and order.
+/// Keys it leaves out are kept unless `replace_frontmatter` is set, and if no key changes the
+/// existing block is written back as is.
+fn serialize_page_markdown(
+    raw_block: Option<&str>,
+    body: &str,
+    replace_frontmatter: bool,
+) -> Result<String, String> {
+    let incoming = parse_markdown_frontmatter(body);
+    let (Some(block), Some(incoming_block)) = (raw_block, incoming.raw_block.as_deref()) else {
+        return Ok(match raw_block {
+            Some(block) => format!("{block}{body}"),
+            None => body.to_string(),
+        });
+    };
+
+    let target = frontmatter_block_mapping(incoming_block)?;
+    let unchanged = frontmatter_block_mapping(block).is_ok_and(|current| {
+        if replace_frontmatter {
+            current == target
+        } else {
+            target
+                .iter()
+                .all(|(key, value)| current.get(key) == Some(value))
+        }
+    });
+    if unchanged {
+        return Ok(format!("{block}{}", incoming.body));
+    }
+
+    let mut frontmatter = parse_frontmatter_block(Some(block))?;
+    let merged = if replace_frontmatter {
+        frontmatter.merge_exact(&target)
+    } else {
+        frontmatter.merge(&target)
+    };
+    merged.map_err(|e| format!("Could not update frontmatter: {e}"))?;
+    Ok(render_frontmatter_block(&frontmatter, &incoming.body))
+}
+
+fn read_note(context: &NoteContext, date: &str) -> Result<Option<NoteRecord>, String> {
+    let path = note_path(context, date)?;
+    let raw = match fs::read_to_string(&path) {
+        Ok(value) => value,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.to_string()),
+    };
+    let (city, markdown) = parse_frontmatter(&raw);
+    Ok(Some(NoteRecord {
+        date: date.to_string(),
+        city,
+        markdown,
+        path: path.to_string_lossy().to_string(),
+    }))
+}
+
+fn parse_iso_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| format!("Invalid date: {}", date))
+}
+
+fn read_notes_in_range(
+    context: &NoteContext,
+    from: &str,
+    to: &str,
+) -> Result<Vec<NoteRecord>, String> {
+    let from_date = parse_iso_date(from)?;
+    let to_date = parse_iso_date(to)?;
+    if from_date > to_date {
+        return Err("--from must be on or before --to.".to_string());
+    }
+
+    let span_days = (to_date - from_date).num_days();
+    if span_days > 31 {
+        return Err("Date range cannot exceed 31 days.".to_string());
+    }
+
+    let mut notes = Vec::new();
+    let mut cursor = from_date;
+    while cursor <= to_date {
+        let date = cursor.format("%Y-%m-%d").to_string();
+        if let Some(note) = read_note(context, &date)? {
+            notes.push(note);
+        }
+        cursor += Duration::days(1);
+    }
+
+    Ok(notes)
+}
+
+fn write_note(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    fs::write(path, content).map_err(|e| e.to_string())
+}
+
+fn create_note(
+    context: &NoteContext,
+    date: &str,
+    template: Option<&str>,
+    city: Option<&str>,
+) -> Result<NoteRecord, String> {
+    let path = note_path(context, date)?;
+    if path.exists() {
         return read_note(context, date)?
             .ok_or_else(|| "Could not load existing note.".to_string());
     }
 
-    write_note(&path, "")?;
-    Ok(NoteRecord {
-        date: date.to_string(),
-        city: None,
-        markdown: String::new(),
+    let content = match render_note_template_file(
+        context,
+        &NoteTemplateRequest {
+            kind: Some(NoteTemplateKind::Daily),
+            template: template.map(ToOwned::to_owned),
+            date: Some(date.to_string()),
+            city: city.map(ToOwned::to_owned),
+            ..NoteTemplateRequest::default()
+        },
+    )? {
+        Some(content) => content,
+        None => build_frontmatter(None, city, "")?,
+    };
+    write_note(&path, &content)?;
+    read_note(context, date)?.ok_or_else(|| "Could not load created note.".to_string())
+}
+
+/// The newest daily note before `date`, if any.
+fn previous_daily_note(
+    context: &NoteContext,
+    date: NaiveDate,
+) -> Result<Option<NoteRecord>, String> {
+    let root = match fs::canonicalize(&context.journal_dir) {
+        Ok(path) => path,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.to_string()),
+    };
+    let current = date.format("%Y-%m-%d").to_string();
+    let previous = collect_markdown_files(&root)
+        .into_iter()
+        .filter_map(|path| {
+            let relative_path = path.strip_prefix(&root).ok()?.to_string_lossy().to_string();
+            parse_date_from_relative_path(&relative_path, &context.filename_pattern)
+        })
+        .filter(|candidate| *candidate < current)
+        .max();
+    match previous {
+        Some(previous) => read_note(context, &previous),
+        None => Ok(None),
+    }
+}
+
+fn note_template_scope(
+    context: &NoteContext,
+    title: &str,
+    date: NaiveDate,
+    city: Option<&str>,
+    now: NaiveDateTime,
+) -> Result<TemplateScope, String> {
+    let previous = previous_daily_note(context, date)?;
+    let city = city
+        .map(ToOwned::to_owned)
+        .or_else(|| previous.as_ref().and_then(|note| note.city.clone()))
+        .unwrap_or_default();
+    let base_dir = context
+        .settings_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    Ok(TemplateScope::from([
+        ("title".to_string(), TemplateValue::text(title)),
+        (
+            "date".to_string(),
+            TemplateValue::Date(date.and_time(now.time())),
+        ),
+        ("now".to_string(), TemplateValue::Date(now)),
+        (
+            "time".to_string(),
+            TemplateValue::text(now.format("%H:%M").to_string()),
+        ),
+        (
+            "weekday".to_string(),
+            TemplateValue::text(date.format("%A").to_string()),
+        ),
+        ("city".to_string(), TemplateValue::text(city)),
+        (
+            "previous_note_date".to_string(),
+            TemplateValue::text(
+                previous
+                    .as_ref()
+                    .map(|note| note.date.clone())
+                    .unwrap_or_default(),
+            ),
+        ),
+        (
+            "previous_open_tasks".to_string(),
+            open_task_values(
+                previous
+                    .map(|note| open_tasks(&note.markdown))
+                    .unwrap_or_default(),
+            ),
+        ),
+        ("events".to_string(), load_calendar_events(&base_dir, date)),
+    ]))
+}
+
+/// Renders the file contents for a new note from the requested or configured template. Daily
+/// notes without one fall back to the vault's Obsidian daily template. Returns `None` when no
+/// template applies.
+pub fn render_note_template_file(
+    context: &NoteContext,
+    request: &NoteTemplateRequest,
+) -> Result<Option<String>, String> {
+    let kind = request.kind.unwrap_or(NoteTemplateKind::Daily);
+    let now = Local::now().naive_local();
+    let city = request
+        .city
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    let (title, date, period) = match kind {
+        NoteTemplateKind::Daily => {
+            let date = request
+                .date
+                .as_deref()
+                .ok_or_else(|| "Daily note templates need a date.".to_string())?;
+            let title = note_path(context, date)?
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| date.to_string());
+            (title, parse_iso_date(date)?, None)
+        }
+        NoteTemplateKind::Page => {
+            let title = normalize_page_title_input(request.title.as_deref().unwrap_or_default());
+            if title.is_empty() {
+                return Err("Page title is required.".to_string());
+            }
+            (title, now.date(), None)
+        }
+        NoteTemplateKind::Weekly | NoteTemplateKind::Monthly | NoteTemplateKind::Quarterly => {
+            let period_kind = match kind {
+                NoteTemplateKind::Weekly => PeriodKind::Week,
+                NoteTemplateKind::Monthly => PeriodKind::Month,
+                _ => PeriodKind::Quarter,
+            };
+            let period = Period::parse(
+                period_kind,
+                request
+                    .period
+                    .as_deref()
+                    .ok_or_else(|| "Periodic note templates need a period.".to_string())?,
+            )?;
+            (period.title(), period.start, Some(period))
+        }
+    };
+
+    let selected = request
+        .template
+        .as_deref()
+        .or_else(|| context.note_templates.get(&kind).map(String::as_str))
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    let Some(name) = selected else {
+        return match (kind, &context.vault_dir) {
+            (NoteTemplateKind::Daily, Some(vault_dir)) => {
+                Ok(render_daily_note_template(vault_dir, &title, date, now)?
+                    .map(|rendered| rendered.markdown))
+            }
+            _ => Ok(None),
+        };
+    };
+
+    let mut scope = note_template_scope(context, &title, date, city, now)?;
+    if let Some(period) = &period {
+        scope.insert(
+            "period".to_string(),
+            TemplateValue::text(period.key.clone()),
+        );
+        scope.insert(
+            "from".to_string(),
+            TemplateValue::Date(period.start.and_time(now.time())),
+        );
+        scope.insert(
+            "to".to_string(),
+            TemplateValue::Date(period.end.and_time(now.time())),
+        );
+    }
+    let source = load_template(&context.templates_dir, name)?;
+    let rendered =
+        render_note_template(&source, &scope).map_err(|err| format!("Template {name}: {err}"))?;
+    let has_frontmatter = rendered.starts_with("---\n");
+
+    Ok(Some(match kind {
+        NoteTemplateKind::Daily if !has_frontmatter => build_frontmatter(None, city, &rendered)?,
+        NoteTemplateKind::Page if !has_frontmatter => {
+            format!("---\ntype: \"page\"\n---\n{rendered}")
+        }
+        _ => rendered,
+    }))
+}
+
+fn periodic_note_path(context: &NoteContext, period: &Period) -> Result<PathBuf, String> {
+    context
+        .periodic_notes
+        .get(&period.kind)
+        .map(|config| config.path(period))
+        .ok_or_else(|| {
+            format!(
+                "No folder is configured for {} notes.",
+                period.kind.obsidian_key()
+            )
+        })
+}
+
+fn read_periodic_note(
+    context: &NoteContext,
+    period: &Period,
+) -> Result<Option<PeriodicNoteRecord>, String> {
+    let path = periodic_note_path(context, period)?;
+    let raw = match fs::read_to_string(&path) {
+        Ok(value) => value,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.to_string()),
+    };
+
+    Ok(Some(PeriodicNoteRecord {
+        kind: period.kind,
+        period: period.key.clone(),
+        title: period.title(),
+        from: period.start.format("%Y-%m-%d").to_string(),
+        to: period.end.format("%Y-%m-%d").to_string(),
+        markdown: parse_markdown_frontmatter(&raw).body,
         path: path.to_string_lossy().to_string(),
-    })
+    }))
+}
+
+fn create_periodic_note(
+    context: &NoteContext,
+    period: &Period,
+    template: Option<&str>,
+) -> Result<PeriodicNoteRecord, String> {
+    let path = periodic_note_path(context, period)?;
+    if !path.exists() {
+        let content = render_note_template_file(
+            context,
+            &NoteTemplateRequest {
+                kind: Some(period.kind.into()),
+                template: template.map(ToOwned::to_owned),
+                period: Some(period.key.clone()),
+                ..NoteTemplateRequest::default()
+            },
+        )?
+        .unwrap_or_else(|| format!("# {}\n", period.title()));
+        write_note(&path, &content)?;
+    }
+    read_periodic_note(context, period)?
+        .ok_or_else(|| format!("Could not load {} note.", period.key))
+}
+
+/// Collects the rollup for a period from its daily notes and the meeting pages they link to.
+fn build_rollup(context: &NoteContext, period: &Period) -> Result<Rollup, String> {
+    let mut notes = Vec::new();
+    let mut meetings: Vec<RollupItem> = Vec::new();
+    for day in period.days() {
+        let date = day.format("%Y-%m-%d").to_string();
+        let Some(note) = read_note(context, &date)? else {
+            continue;
+        };
+
+        let mut titles = extract_linked_page_titles(&note.markdown)
+            .into_iter()
+            .collect::<Vec<_>>();
+        titles.sort();
+        for title in titles {
+            if meetings.iter().any(|meeting| meeting.text == title) {
+                continue;
+            }
+            let (_, path) = page_path(context, &title)?;
+            let Ok(raw) = fs::read_to_string(&path) else {
+                continue;
+            };
+            if page_type_from_frontmatter(&parse_markdown_frontmatter(&raw).frontmatter)
+                == "meeting"
+            {
+                meetings.push(RollupItem {
+                    text: title,
+                    date: date.clone(),
+                });
+            }
+        }
+        notes.push((date, note.markdown));
+    }
+
+    Ok(collect_rollup(&notes, meetings))
+}
+
+fn rollup_periodic_note(
+    context: &NoteContext,
+    period: &Period,
+    apply: bool,
+) -> Result<PeriodicNoteChange, String> {
+    let path = periodic_note_path(context, period)?;
+    let raw = match fs::read_to_string(&path) {
+        Ok(value) => value,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err.to_string()),
+    };
+    let parsed = parse_markdown_frontmatter(&raw);
+    let rollup = build_rollup(context, period)?;
+    let after_markdown = merge_rollup(&parsed.body, period, &render_rollup(&rollup));
+    let change = PeriodicNoteChange {
+        kind: period.kind,
+        period: period.key.clone(),
+        unified_diff: build_unified_diff(&parsed.body, &after_markdown),
+        before_markdown: parsed.body.clone(),
+        after_markdown,
+        rollup,
+    };
+
+    if apply {
+        write_note(
+            &path,
+            &serialize_page_markdown(parsed.raw_block.as_deref(), &change.after_markdown, false)?,
+        )?;
+    }
+
+    Ok(change)
 }
 
 pub fn build_unified_diff(before: &str, after: &str) -> String {
@@ -921,7 +2125,13 @@ fn update_note(
 
     if apply {
         let path = note_path(context, date)?;
-        let serialized = build_frontmatter(before.city.as_deref(), markdown);
+        let raw = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        let parsed = parse_markdown_frontmatter(&raw);
+        let serialized = build_frontmatter(
+            parsed.raw_block.as_deref(),
+            before.city.as_deref(),
+            markdown,
+        )?;
         write_note(&path, &serialized)?;
     }
 
@@ -999,21 +2209,91 @@ fn build_search_snippet(markdown: &str, terms: &[String]) -> String {
     truncate_chars(&condense_whitespace(markdown), 180)
 }
 
-fn create_page(context: &NoteContext, title: &str) -> Result<PageRecord, String> {
+fn create_page(
+    context: &NoteContext,
+    title: &str,
+    template: Option<&str>,
+) -> Result<PageRecord, String> {
     let (normalized_title, path) = page_path(context, title)?;
     if path.exists() {
         return read_page(context, &normalized_title)?
             .ok_or_else(|| "Could not load existing page.".to_string());
     }
 
-    write_note(&path, "---\ntype: \"page\"\n---\n")?;
+    let content = render_note_template_file(
+        context,
+        &NoteTemplateRequest {
+            kind: Some(NoteTemplateKind::Page),
+            template: template.map(ToOwned::to_owned),
+            title: Some(normalized_title.clone()),
+            ..NoteTemplateRequest::default()
+        },
+    )?
+    .unwrap_or_else(|| "---\ntype: \"page\"\n---\n".to_string());
+    write_note(&path, &with_schema_defaults(context, &content)?)?;
     read_page(context, &normalized_title)?.ok_or_else(|| "Could not load created page.".to_string())
 }
 
+/// Fills in the defaults the page's type declares for fields the template left out.
+fn with_schema_defaults(context: &NoteContext, content: &str) -> Result<String, String> {
+    let schemas = load_page_schemas(context)?;
+    let parsed = parse_markdown_frontmatter(content);
+    let defaults =
+        schemas.missing_defaults(&schemas.page_type(&parsed.frontmatter), &parsed.frontmatter);
+    if defaults.is_empty() {
+        return Ok(content.to_string());
+    }
+
+    let mut frontmatter = parse_frontmatter_block(parsed.raw_block.as_deref())?;
+    for (key, value) in defaults {
+        frontmatter
+            .set(&key, &value)
+            .map_err(|e| format!("Could not update frontmatter: {e}"))?;
+    }
+    Ok(render_frontmatter_block(&frontmatter, &parsed.body))
+}
+
+/// Rejects frontmatter edits that introduce schema errors. Errors the page already had are left
+/// for `page lint` so unrelated body edits still go through.
+fn check_page_schema(
+    context: &NoteContext,
+    before: &JsonMap<String, JsonValue>,
+    after: &str,
+) -> Result<(), String> {
+    let schemas = load_page_schemas(context)?;
+    let existing = schemas.validate(before);
+    let mut next = parse_markdown_frontmatter(after).frontmatter;
+    let mut introduced = vec![];
+    // Dropping `type` would otherwise only check the common fields, so the update is still
+    // validated against the type the page had.
+    if let Some(previous_type) = before.get("type") {
+        if !next.contains_key("type") {
+            introduced.push("type: Updates can't remove the page type.".to_string());
+            next.insert("type".to_string(), previous_type.clone());
+        }
+    }
+    introduced.extend(
+        schemas
+            .validate(&next)
+            .into_iter()
+            .filter(|issue| issue.is_error() && !existing.contains(issue))
+            .map(|issue| format!("{}: {}", issue.field, issue.message)),
+    );
+    if introduced.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Frontmatter does not match the page schema: {}",
+            introduced.join(" ")
+        ))
+    }
+}
+
 fn update_page(
     context: &NoteContext,
     title: &str,
     markdown: &str,
+    replace_frontmatter: bool,
     apply: bool,
 ) -> Result<PageChange, String> {
     let (normalized_title, path) = page_path(context, title)?;
@@ -1032,11 +2312,12 @@ fn update_page(
         unified_diff: build_unified_diff(&parsed.body, markdown),
     };
 
+    let serialized =
+        serialize_page_markdown(parsed.raw_block.as_deref(), markdown, replace_frontmatter)?;
+    check_page_schema(context, &parsed.frontmatter, &serialized)?;
+
     if apply {
-        write_note(
-            &path,
-            &serialize_page_markdown(parsed.raw_block.as_deref(), markdown),
-        )?;
+        write_note(&path, &serialized)?;
     }
 
     Ok(change)
@@ -1060,8 +2341,9 @@ fn search_pages(
     query: &str,
     limit: usize,
 ) -> Result<Vec<PageSearchHit>, String> {
-    let terms = normalize_search_terms(query);
-    if terms.is_empty() {
+    let (tags, text) = split_tag_query(query);
+    let terms = normalize_search_terms(&text);
+    if terms.is_empty() && tags.is_empty() {
         return Ok(Vec::new());
     }
 
@@ -1083,6 +2365,12 @@ fn search_pages(
             .filter(|value| !value.is_empty())
             .unwrap_or_else(|| "Untitled".to_string());
         let parsed = parse_markdown_frontmatter(&raw);
+        if !tags.is_empty() {
+            let page_tags = collect_tags(&parsed.frontmatter, &parsed.body);
+            if !tags.iter().all(|tag| tags_match(&page_tags, tag)) {
+                continue;
+            }
+        }
         let frontmatter_json = serde_json::to_string(&parsed.frontmatter).unwrap_or_default();
         let haystack = format!("{title}\n{}\n{frontmatter_json}", parsed.body);
         let normalized_haystack = haystack.to_ascii_lowercase();
@@ -1328,14 +2616,22 @@ fn search_notes(
     if normalized_query.is_empty() {
         return Ok(Vec::new());
     }
-    let Some(fts_query) = build_fts_query(normalized_query) else {
+    let (tags, text) = split_tag_query(normalized_query);
+    let fts_query = build_fts_query(&text);
+    if fts_query.is_none() && tags.is_empty() {
         return Ok(Vec::new());
-    };
+    }
 
     let search_db = ensure_search_db_path(&context.settings_path)?;
     let mut conn = Connection::open(search_db).map_err(|e| e.to_string())?;
     ensure_search_schema(&conn)?;
     refresh_markdown_index(&mut conn, context)?;
+    if !tags.is_empty() {
+        return search_notes_by_tags(&conn, context, &tags, &text, limit);
+    }
+    let Some(fts_query) = fts_query else {
+        return Ok(Vec::new());
+    };
 
     let mut stmt = conn
         .prepare(
@@ -1378,6 +2674,73 @@ fn search_notes(
     Ok(hits)
 }
 
+/// Tag queries can't go through FTS since the tokenizer splits `#area/project`, so matching notes
+/// are filtered by their parsed tags instead.
+fn search_notes_by_tags(
+    conn: &Connection,
+    context: &NoteContext,
+    tags: &[String],
+    text: &str,
+    limit: usize,
+) -> Result<Vec<SearchHit>, String> {
+    let root_key = fs::canonicalize(&context.journal_dir)
+        .map_err(|e| e.to_string())?
+        .to_string_lossy()
+        .to_string();
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT title, content, path, note_date
+            FROM search_docs
+            WHERE root_dir = ?1
+              AND note_date IS NOT NULL
+            ORDER BY note_date DESC
+            "#,
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![root_key], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let terms = normalize_search_terms(text);
+    let mut hits = Vec::new();
+    for row in rows {
+        let (title, content, path, date) = row.map_err(|e| e.to_string())?;
+        let parsed = parse_markdown_frontmatter(&content);
+        let note_tags = collect_tags(&parsed.frontmatter, &parsed.body);
+        if !tags.iter().all(|tag| tags_match(&note_tags, tag)) {
+            continue;
+        }
+        let body_lower = parsed.body.to_ascii_lowercase();
+        if !terms.iter().all(|term| body_lower.contains(term)) {
+            continue;
+        }
+
+        let snippet_terms = if terms.is_empty() {
+            tags.iter().map(|tag| format!("#{tag}")).collect()
+        } else {
+            terms.clone()
+        };
+        hits.push(SearchHit {
+            title,
+            snippet: build_search_snippet(&parsed.body, &snippet_terms),
+            path,
+            date,
+        });
+        if hits.len() >= limit {
+            break;
+        }
+    }
+    Ok(hits)
+}
+
 fn parse_limit(args: &[String], index: usize) -> Result<usize, String> {
     args.get(index)
         .ok_or_else(|| "Missing limit value.".to_string())?
@@ -1385,6 +2748,15 @@ fn parse_limit(args: &[String], index: usize) -> Result<usize, String> {
         .map_err(|_| "Invalid limit value.".to_string())
 }
 
+fn parse_period_arg(args: &[String], index: usize) -> Result<Period, String> {
+    let flag = args[index].as_str();
+    let kind = PeriodKind::from_flag(flag).ok_or_else(|| format!("Unsupported period: {flag}"))?;
+    let value = args
+        .get(index + 1)
+        .ok_or_else(|| format!("Missing value for {flag}."))?;
+    Period::parse(kind, value)
+}
+
 fn parse_note_command(argv: &[String]) -> Result<ParsedCommand, String> {
     if argv.is_empty() {
         return Err("Missing note action.".to_string());
@@ -1416,6 +2788,7 @@ fn parse_note_command(argv: &[String]) -> Result<ParsedCommand, String> {
         }
         "read" => {
             let mut date = None;
+            let mut period = None;
             let mut index = 1usize;
             while index < argv.len() {
                 match argv[index].as_str() {
@@ -1423,13 +2796,22 @@ fn parse_note_command(argv: &[String]) -> Result<ParsedCommand, String> {
                         date = argv.get(index + 1).cloned();
                         index += 2;
                     }
+                    "--week" | "--month" | "--quarter" => {
+                        period = Some(parse_period_arg(argv, index)?);
+                        index += 2;
+                    }
                     "--json" => index += 1,
                     other => return Err(format!("Unsupported flag for note read: {}", other)),
                 }
             }
-            Ok(ParsedCommand::NoteRead {
-                date: date.ok_or_else(|| "Missing --date.".to_string())?,
-            })
+            match (date, period) {
+                (None, Some(period)) => Ok(ParsedCommand::PeriodicNoteRead { period }),
+                (Some(date), None) => Ok(ParsedCommand::NoteRead { date }),
+                (Some(_), Some(_)) => {
+                    Err("Use either --date or one of --week, --month, --quarter.".to_string())
+                }
+                (None, None) => Err("Missing --date.".to_string()),
+            }
         }
         "read-range" => {
             let mut from = None;
@@ -1458,6 +2840,9 @@ fn parse_note_command(argv: &[String]) -> Result<ParsedCommand, String> {
         }
         "create" => {
             let mut date = None;
+            let mut period = None;
+            let mut template = None;
+            let mut city = None;
             let mut index = 1usize;
             while index < argv.len() {
                 match argv[index].as_str() {
@@ -1465,12 +2850,67 @@ fn parse_note_command(argv: &[String]) -> Result<ParsedCommand, String> {
                         date = argv.get(index + 1).cloned();
                         index += 2;
                     }
+                    "--template" => {
+                        template = argv.get(index + 1).cloned();
+                        index += 2;
+                    }
+                    "--city" => {
+                        city = argv.get(index + 1).cloned();
+                        index += 2;
+                    }
+                    "--week" | "--month" | "--quarter" => {
+                        period = Some(parse_period_arg(argv, index)?);
+                        index += 2;
+                    }
                     "--json" => index += 1,
                     other => return Err(format!("Unsupported flag for note create: {}", other)),
                 }
             }
-            Ok(ParsedCommand::NoteCreate {
-                date: date.ok_or_else(|| "Missing --date.".to_string())?,
+            match (date, period) {
+                (None, Some(period)) => Ok(ParsedCommand::PeriodicNoteCreate { period, template }),
+                (Some(date), None) => Ok(ParsedCommand::NoteCreate {
+                    date,
+                    template,
+                    city,
+                }),
+                (Some(_), Some(_)) => {
+                    Err("Use either --date or one of --week, --month, --quarter.".to_string())
+                }
+                (None, None) => Err("Missing --date.".to_string()),
+            }
+        }
+        "rollup" => {
+            let mut period = None;
+            let mut apply = false;
+            let mut dry_run = false;
+            let mut index = 1usize;
+            while index < argv.len() {
+                match argv[index].as_str() {
+                    "--week" | "--month" | "--quarter" => {
+                        period = Some(parse_period_arg(argv, index)?);
+                        index += 2;
+                    }
+                    "--apply" => {
+                        apply = true;
+                        index += 1;
+                    }
+                    "--dry-run" => {
+                        dry_run = true;
+                        index += 1;
+                    }
+                    "--json" => index += 1,
+                    other => return Err(format!("Unsupported flag for note rollup: {}", other)),
+                }
+            }
+
+            if apply == dry_run {
+                return Err("Use exactly one of --dry-run or --apply.".to_string());
+            }
+
+            Ok(ParsedCommand::NoteRollup {
+                period: period
+                    .ok_or_else(|| "Missing --week, --month or --quarter.".to_string())?,
+                apply,
             })
         }
         "update" => {
@@ -1575,6 +3015,7 @@ fn parse_page_command(argv: &[String]) -> Result<ParsedCommand, String> {
         }
         "create" => {
             let mut title = None;
+            let mut template = None;
             let mut index = 1usize;
             while index < argv.len() {
                 match argv[index].as_str() {
@@ -1582,16 +3023,22 @@ fn parse_page_command(argv: &[String]) -> Result<ParsedCommand, String> {
                         title = argv.get(index + 1).cloned();
                         index += 2;
                     }
+                    "--template" => {
+                        template = argv.get(index + 1).cloned();
+                        index += 2;
+                    }
                     "--json" => index += 1,
                     other => return Err(format!("Unsupported flag for page create: {}", other)),
                 }
             }
             Ok(ParsedCommand::PageCreate {
                 title: title.ok_or_else(|| "Missing --title.".to_string())?,
+                template,
             })
         }
         "update" => {
             let mut title = None;
+            let mut replace_frontmatter = false;
             let mut apply = false;
             let mut dry_run = false;
             let mut index = 1usize;
@@ -1601,6 +3048,10 @@ fn parse_page_command(argv: &[String]) -> Result<ParsedCommand, String> {
                         title = argv.get(index + 1).cloned();
                         index += 2;
                     }
+                    "--replace-frontmatter" => {
+                        replace_frontmatter = true;
+                        index += 1;
+                    }
                     "--apply" => {
                         apply = true;
                         index += 1;
@@ -1614,34 +3065,229 @@ fn parse_page_command(argv: &[String]) -> Result<ParsedCommand, String> {
                 }
             }
 
-            if apply == dry_run {
-                return Err("Use exactly one of --dry-run or --apply.".to_string());
-            }
+            if apply == dry_run {
+                return Err("Use exactly one of --dry-run or --apply.".to_string());
+            }
+
+            Ok(ParsedCommand::PageUpdate {
+                title: title.ok_or_else(|| "Missing --title.".to_string())?,
+                replace_frontmatter,
+                apply,
+            })
+        }
+        "delete" => {
+            let mut title = None;
+            let mut index = 1usize;
+            while index < argv.len() {
+                match argv[index].as_str() {
+                    "--title" => {
+                        title = argv.get(index + 1).cloned();
+                        index += 2;
+                    }
+                    "--json" => index += 1,
+                    other => return Err(format!("Unsupported flag for page delete: {}", other)),
+                }
+            }
+            Ok(ParsedCommand::PageDelete {
+                title: title.ok_or_else(|| "Missing --title.".to_string())?,
+            })
+        }
+        "lint" => {
+            let mut page_type = None;
+            let mut index = 1usize;
+            while index < argv.len() {
+                match argv[index].as_str() {
+                    "--type" => {
+                        page_type = argv.get(index + 1).cloned();
+                        index += 2;
+                    }
+                    "--json" => index += 1,
+                    other => return Err(format!("Unsupported flag for page lint: {}", other)),
+                }
+            }
+            Ok(ParsedCommand::PageLint { page_type })
+        }
+        other => Err(format!("Unsupported page action: {}", other)),
+    }
+}
+
+fn parse_template_command(argv: &[String]) -> Result<ParsedCommand, String> {
+    if argv.is_empty() {
+        return Err("Missing template action.".to_string());
+    }
+
+    match argv[0].as_str() {
+        "list" => {
+            for flag in &argv[1..] {
+                if flag != "--json" {
+                    return Err(format!("Unsupported flag for template list: {}", flag));
+                }
+            }
+            Ok(ParsedCommand::TemplateList)
+        }
+        other => Err(format!("Unsupported template action: {}", other)),
+    }
+}
+
+fn parse_obsidian_command(argv: &[String]) -> Result<ParsedCommand, String> {
+    if argv.is_empty() {
+        return Err("Missing obsidian action.".to_string());
+    }
+
+    match argv[0].as_str() {
+        "report" => {
+            for flag in &argv[1..] {
+                if flag != "--json" {
+                    return Err(format!("Unsupported flag for obsidian report: {}", flag));
+                }
+            }
+            Ok(ParsedCommand::ObsidianReport)
+        }
+        other => Err(format!("Unsupported obsidian action: {}", other)),
+    }
+}
+
+fn parse_lint_command(argv: &[String]) -> Result<ParsedCommand, String> {
+    let mut rules = Vec::new();
+    let mut fix = false;
+    let mut apply = false;
+    let mut dry_run = false;
+    let mut index = 0usize;
+    while index < argv.len() {
+        match argv[index].as_str() {
+            "--rule" => {
+                let name = argv
+                    .get(index + 1)
+                    .ok_or_else(|| "Missing value for --rule.".to_string())?;
+                rules.push(name.parse::<LintRule>().map_err(|e| e.to_string())?);
+                index += 2;
+            }
+            "--fix" => {
+                fix = true;
+                index += 1;
+            }
+            "--apply" => {
+                apply = true;
+                index += 1;
+            }
+            "--dry-run" => {
+                dry_run = true;
+                index += 1;
+            }
+            "--json" => index += 1,
+            other => return Err(format!("Unsupported flag for lint: {}", other)),
+        }
+    }
+
+    if fix && apply == dry_run {
+        return Err("Use exactly one of --dry-run or --apply with --fix.".to_string());
+    }
+    if !fix && (apply || dry_run) {
+        return Err("--dry-run and --apply need --fix.".to_string());
+    }
+
+    Ok(ParsedCommand::Lint { rules, fix, apply })
+}
+
+fn parse_import_command(argv: &[String]) -> Result<ParsedCommand, String> {
+    let mut source = None;
+    let mut path = None;
+    let mut apply = false;
+    let mut dry_run = false;
+    let mut index = 0usize;
+    while index < argv.len() {
+        match argv[index].as_str() {
+            "--from" => {
+                let name = argv
+                    .get(index + 1)
+                    .ok_or_else(|| "Missing value for --from.".to_string())?;
+                source = Some(name.parse::<ImportSource>().map_err(|e| e.to_string())?);
+                index += 2;
+            }
+            "--path" => {
+                path = argv.get(index + 1).map(PathBuf::from);
+                index += 2;
+            }
+            "--apply" => {
+                apply = true;
+                index += 1;
+            }
+            "--dry-run" => {
+                dry_run = true;
+                index += 1;
+            }
+            "--json" => index += 1,
+            other => return Err(format!("Unsupported flag for import: {}", other)),
+        }
+    }
+
+    if apply == dry_run {
+        return Err("Use exactly one of --dry-run or --apply.".to_string());
+    }
+
+    Ok(ParsedCommand::Import {
+        source: source.ok_or_else(|| "Missing --from.".to_string())?,
+        path: path.ok_or_else(|| "Missing --path.".to_string())?,
+        apply,
+    })
+}
+
+fn parse_site_command(argv: &[String]) -> Result<ParsedCommand, String> {
+    if argv.is_empty() {
+        return Err("Missing site action.".to_string());
+    }
+    if argv[0] != "export" {
+        return Err(format!("Unsupported site action: {}", argv[0]));
+    }
 
-            Ok(ParsedCommand::PageUpdate {
-                title: title.ok_or_else(|| "Missing --title.".to_string())?,
-                apply,
-            })
-        }
-        "delete" => {
-            let mut title = None;
-            let mut index = 1usize;
-            while index < argv.len() {
-                match argv[index].as_str() {
-                    "--title" => {
-                        title = argv.get(index + 1).cloned();
-                        index += 2;
-                    }
-                    "--json" => index += 1,
-                    other => return Err(format!("Unsupported flag for page delete: {}", other)),
-                }
+    let mut out_dir = None;
+    let mut selection = SiteSelection::default();
+    let mut private_fields = Vec::new();
+    let mut title = None;
+    let mut apply = false;
+    let mut dry_run = false;
+    let mut index = 1usize;
+    while index < argv.len() {
+        let flag = argv[index].as_str();
+        if matches!(
+            flag,
+            "--out" | "--folder" | "--tag" | "--type" | "--private-field" | "--title"
+        ) {
+            let value = argv
+                .get(index + 1)
+                .cloned()
+                .ok_or_else(|| format!("Missing value for {}.", flag))?;
+            match flag {
+                "--out" => out_dir = Some(PathBuf::from(value)),
+                "--folder" => selection.folders.push(value),
+                "--tag" => selection.tags.push(value),
+                "--type" => selection.page_types.push(value),
+                "--private-field" => private_fields.push(value),
+                _ => title = Some(value),
             }
-            Ok(ParsedCommand::PageDelete {
-                title: title.ok_or_else(|| "Missing --title.".to_string())?,
-            })
+            index += 2;
+            continue;
         }
-        other => Err(format!("Unsupported page action: {}", other)),
+        match flag {
+            "--apply" => apply = true,
+            "--dry-run" => dry_run = true,
+            "--json" => {}
+            other => return Err(format!("Unsupported flag for site export: {}", other)),
+        }
+        index += 1;
     }
+
+    if apply == dry_run {
+        return Err("Use exactly one of --dry-run or --apply.".to_string());
+    }
+
+    Ok(ParsedCommand::SiteExport {
+        out_dir: out_dir.ok_or_else(|| "Missing --out.".to_string())?,
+        selection,
+        private_fields,
+        title,
+        apply,
+    })
 }
 
 fn parse_command(argv: &[String]) -> Result<ParsedCommand, String> {
@@ -1652,6 +3298,11 @@ fn parse_command(argv: &[String]) -> Result<ParsedCommand, String> {
     match argv[0].as_str() {
         "note" => parse_note_command(&argv[1..]),
         "page" => parse_page_command(&argv[1..]),
+        "template" => parse_template_command(&argv[1..]),
+        "obsidian" => parse_obsidian_command(&argv[1..]),
+        "lint" => parse_lint_command(&argv[1..]),
+        "import" => parse_import_command(&argv[1..]),
+        "site" => parse_site_command(&argv[1..]),
         other => Err(format!("Unsupported subcommand: {}", other)),
     }
 }
@@ -1671,8 +3322,12 @@ pub fn run_philo_command(argv: &[String], stdin: Option<String>) -> Result<Strin
         ParsedCommand::NoteReadRange { from, to } => serde_json::to_string(&ReadRangeEnvelope {
             notes: read_notes_in_range(&context, &from, &to)?,
         }),
-        ParsedCommand::NoteCreate { date } => serde_json::to_string(&NoteEnvelope {
-            note: create_note(&context, &date)?,
+        ParsedCommand::NoteCreate {
+            date,
+            template,
+            city,
+        } => serde_json::to_string(&NoteEnvelope {
+            note: create_note(&context, &date, template.as_deref(), city.as_deref())?,
         }),
         ParsedCommand::NoteUpdate { date, apply } => {
             let markdown =
@@ -1700,7 +3355,7 @@ pub fn run_philo_command(argv: &[String], stdin: Option<String>) -> Result<Strin
             hits: search_pages(&context, &query, limit.min(20))?,
         }),
         ParsedCommand::PageRead { title } => {
-            let page = read_page(&context, &title)?.ok_or_else(|| {
+            let page = read_page_link(&context, &title)?.ok_or_else(|| {
                 format!(
                     "Page {} does not exist.",
                     normalize_page_title_input(&title)
@@ -1708,14 +3363,18 @@ pub fn run_philo_command(argv: &[String], stdin: Option<String>) -> Result<Strin
             })?;
             serde_json::to_string(&PageEnvelope { page })
         }
-        ParsedCommand::PageCreate { title } => serde_json::to_string(&PageEnvelope {
-            page: create_page(&context, &title)?,
+        ParsedCommand::PageCreate { title, template } => serde_json::to_string(&PageEnvelope {
+            page: create_page(&context, &title, template.as_deref())?,
         }),
-        ParsedCommand::PageUpdate { title, apply } => {
+        ParsedCommand::PageUpdate {
+            title,
+            replace_frontmatter,
+            apply,
+        } => {
             let markdown =
                 stdin.ok_or_else(|| "Page update requires stdin markdown.".to_string())?;
             if apply {
-                let change = update_page(&context, &title, &markdown, true)?;
+                let change = update_page(&context, &title, &markdown, replace_frontmatter, true)?;
                 let (_, path) = page_path(&context, &change.title)?;
                 serde_json::to_string(&PageAppliedEnvelope {
                     applied: vec![AppliedPage {
@@ -1725,19 +3384,142 @@ pub fn run_philo_command(argv: &[String], stdin: Option<String>) -> Result<Strin
                 })
             } else {
                 serde_json::to_string(&PageUpdateEnvelope {
-                    change: update_page(&context, &title, &markdown, false)?,
+                    change: update_page(&context, &title, &markdown, replace_frontmatter, false)?,
                 })
             }
         }
         ParsedCommand::PageDelete { title } => serde_json::to_string(&PageAppliedEnvelope {
             applied: vec![delete_page(&context, &title)?],
         }),
+        ParsedCommand::PageLint { page_type } => {
+            serde_json::to_string(&lint_pages(&context, page_type.as_deref())?)
+        }
+        ParsedCommand::PeriodicNoteRead { period } => {
+            let note = read_periodic_note(&context, &period)?
+                .ok_or_else(|| format!("Note {} does not exist.", period.key))?;
+            serde_json::to_string(&PeriodicNoteEnvelope { note })
+        }
+        ParsedCommand::PeriodicNoteCreate { period, template } => {
+            serde_json::to_string(&PeriodicNoteEnvelope {
+                note: create_periodic_note(&context, &period, template.as_deref())?,
+            })
+        }
+        ParsedCommand::NoteRollup { period, apply } => {
+            let change = rollup_periodic_note(&context, &period, apply)?;
+            if apply {
+                serde_json::to_string(&PeriodicAppliedEnvelope {
+                    applied: vec![AppliedPeriodicNote {
+                        period: change.period,
+                        path: periodic_note_path(&context, &period)?
+                            .to_string_lossy()
+                            .to_string(),
+                    }],
+                })
+            } else {
+                serde_json::to_string(&RollupEnvelope { change })
+            }
+        }
+        ParsedCommand::TemplateList => serde_json::to_string(&TemplateListEnvelope {
+            dir: context.templates_dir.to_string_lossy().to_string(),
+            templates: list_templates(&context.templates_dir),
+        }),
+        ParsedCommand::ObsidianReport => {
+            let vault_dir = context
+                .vault_dir
+                .as_ref()
+                .ok_or_else(|| "No Obsidian vault is configured.".to_string())?;
+            serde_json::to_string(&ObsidianReportEnvelope {
+                report: compatibility_report(vault_dir)?,
+            })
+        }
+        ParsedCommand::Lint { rules, fix, apply } => {
+            serde_json::to_string(&lint_vault(&context, &rules, fix, apply)?)
+        }
+        ParsedCommand::Import {
+            source,
+            path,
+            apply,
+        } => serde_json::to_string(&import_notes(&context, source, &path, apply)?),
+        ParsedCommand::SiteExport {
+            out_dir,
+            selection,
+            private_fields,
+            title,
+            apply,
+        } => serde_json::to_string(&export_site(
+            &context,
+            &out_dir,
+            selection,
+            &private_fields,
+            title.as_deref(),
+            apply,
+        )?),
     }
     .map_err(|e| e.to_string())?;
 
     Ok(value)
 }
 
+/// Files a `philo` command wrote or deleted, read back from its JSON output so the sidecar
+/// binary is covered too. Previews and read-only commands report nothing.
+pub fn written_paths(argv: &[String], stdout: &str) -> Vec<PathBuf> {
+    let (Ok(command), Ok(output)) = (
+        parse_command(argv),
+        serde_json::from_str::<JsonValue>(stdout),
+    ) else {
+        return Vec::new();
+    };
+    let path_of = |value: &JsonValue| {
+        value
+            .get("path")
+            .and_then(JsonValue::as_str)
+            .map(PathBuf::from)
+    };
+    let list = |key: &str| {
+        output
+            .get(key)
+            .and_then(JsonValue::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    };
+
+    match command {
+        ParsedCommand::NoteCreate { .. } | ParsedCommand::PeriodicNoteCreate { .. } => {
+            output.get("note").and_then(path_of).into_iter().collect()
+        }
+        ParsedCommand::PageCreate { .. } => {
+            output.get("page").and_then(path_of).into_iter().collect()
+        }
+        ParsedCommand::NoteUpdate { apply: true, .. }
+        | ParsedCommand::PageUpdate { apply: true, .. }
+        | ParsedCommand::NoteRollup { apply: true, .. }
+        | ParsedCommand::NoteDelete { .. }
+        | ParsedCommand::PageDelete { .. } => list("applied").iter().filter_map(path_of).collect(),
+        ParsedCommand::Lint {
+            fix: true,
+            apply: true,
+            ..
+        } => list("files")
+            .iter()
+            .filter(|file| file.get("unifiedDiff").is_some())
+            .filter_map(path_of)
+            .collect(),
+        ParsedCommand::Import { apply: true, .. } => {
+            list("files").iter().filter_map(path_of).collect()
+        }
+        ParsedCommand::SiteExport {
+            out_dir,
+            apply: true,
+            ..
+        } => list("files")
+            .iter()
+            .filter_map(JsonValue::as_str)
+            .map(|file| out_dir.join(file))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
 fn reject_find_args(args: &[String]) -> Result<(), String> {
     for arg in args {
         if matches!(
@@ -1855,9 +3637,15 @@ pub fn run_sidecar_philo(
 #[cfg(test)]
 mod tests {
     use super::{
-        apply_filename_pattern, build_unified_diff, parse_date_from_relative_path,
-        read_notes_in_range, read_page, search_pages, update_page, NoteContext,
+        apply_filename_pattern, build_unified_diff, create_note, create_page, export_site,
+        import_notes, lint_pages, lint_vault, parse_command, parse_date_from_relative_path,
+        read_notes_in_range, read_page, read_page_link, read_periodic_note, rollup_periodic_note,
+        search_notes, search_pages, update_note, update_page, written_paths, ImportSource,
+        NoteContext, ParsedCommand,
     };
+    use crate::note_templates::NoteTemplateKind;
+    use crate::periodic_notes::{default_periodic_notes, Period, PeriodKind};
+    use std::collections::HashMap;
     use std::fs;
     use std::path::PathBuf;
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -1874,6 +3662,12 @@ mod tests {
             journal_dir: base.join("notes"),
             pages_dir: base.join("pages"),
             filename_pattern: "{YYYY}-{MM}-{DD}".to_string(),
+            vault_dir: None,
+            periodic_notes: default_periodic_notes(&base.join("notes")),
+            templates_dir: base.join("templates"),
+            assets_dir: base.join("notes").join("assets"),
+            assets_link_root: "assets".to_string(),
+            note_templates: HashMap::new(),
         }
     }
 
@@ -1988,7 +3782,8 @@ mod tests {
             "---\ntype: meeting\nlocation: HQ\n---\n# Launch\nOld body\n",
         );
 
-        let change = update_page(&context, "Launch plan", "# Launch\nNew body\n", true).unwrap();
+        let change =
+            update_page(&context, "Launch plan", "# Launch\nNew body\n", false, true).unwrap();
         let raw = fs::read_to_string(context.pages_dir.join("Launch plan.md")).unwrap();
 
         assert_eq!(change.title, "Launch plan");
@@ -1997,6 +3792,297 @@ mod tests {
         assert!(raw.ends_with("# Launch\nNew body\n"));
     }
 
+    #[test]
+    fn merges_page_frontmatter_edits_without_reformatting() {
+        let context = make_test_context();
+        write_test_page(
+            &context.pages_dir,
+            "Launch plan",
+            "---\n# review weekly\ntype: meeting\nlocation: \"HQ\" # room 4\nstatus: draft\n---\n# Launch\n",
+        );
+
+        update_page(
+            &context,
+            "Launch plan",
+            "---\ntype: meeting\nlocation: Annex\nowner: Alex\n---\n# Launch\nNew body\n",
+            false,
+            true,
+        )
+        .unwrap();
+        let raw = fs::read_to_string(context.pages_dir.join("Launch plan.md")).unwrap();
+        assert_eq!(
+            raw,
+            "---\n# review weekly\ntype: meeting\nlocation: \"Annex\" # room 4\nstatus: draft\nowner: Alex\n---\n# Launch\nNew body\n"
+        );
+
+        update_page(
+            &context,
+            "Launch plan",
+            "---\ntype: meeting\nlocation: Annex\nowner: Alex\n---\n# Launch\nNew body\n",
+            true,
+            true,
+        )
+        .unwrap();
+        let raw = fs::read_to_string(context.pages_dir.join("Launch plan.md")).unwrap();
+        assert_eq!(
+            raw,
+            "---\n# review weekly\ntype: meeting\nlocation: \"Annex\" # room 4\nowner: Alex\n---\n# Launch\nNew body\n"
+        );
+    }
+
+    #[test]
+    fn keeps_unchanged_frontmatter_verbatim_in_any_layout() {
+        let context = make_test_context();
+        let block = "---\n{type: meeting, location: HQ}\n---\n";
+        write_test_page(
+            &context.pages_dir,
+            "Launch plan",
+            &format!("{block}# Launch\n"),
+        );
+
+        update_page(&context, "Launch plan", "# Launch\nNew body\n", false, true).unwrap();
+        update_page(
+            &context,
+            "Launch plan",
+            "---\nlocation: HQ\n---\n# Launch\nNewer body\n",
+            false,
+            true,
+        )
+        .unwrap();
+        let raw = fs::read_to_string(context.pages_dir.join("Launch plan.md")).unwrap();
+        assert_eq!(raw, format!("{block}# Launch\nNewer body\n"));
+
+        write_test_note(
+            &context.journal_dir,
+            "2026-03-12",
+            "---\n{city: Seoul, mood: 7}\n---\nOld\n",
+        );
+        update_note(&context, "2026-03-12", "New\n", true).unwrap();
+        let raw = fs::read_to_string(context.journal_dir.join("2026-03-12.md")).unwrap();
+        assert_eq!(raw, "---\n{city: Seoul, mood: 7}\n---\nNew\n");
+    }
+
+    #[test]
+    fn lints_pages_and_rejects_schema_breaking_updates() {
+        let context = make_test_context();
+        write_test_page(
+            &context.pages_dir,
+            "Standup",
+            "---\ntype: meeting\nstarted_at: soon\nparticipants: Alex\n---\n# Standup\n",
+        );
+        write_test_page(
+            &context.pages_dir,
+            "Roadmap",
+            "---\ntype: project\nstatus: active\n---\n# Roadmap\n",
+        );
+
+        let report = lint_pages(&context, None).unwrap();
+        assert_eq!(report.checked, 2);
+        assert_eq!((report.errors, report.warnings), (1, 1));
+        assert_eq!(report.pages.len(), 1);
+        assert_eq!(report.pages[0].title, "Standup");
+        assert_eq!(report.pages[0].r#type, "meeting");
+        assert_eq!(lint_pages(&context, Some("project")).unwrap().checked, 1);
+
+        let page = read_page(&context, "Standup").unwrap().unwrap();
+        assert_eq!(page.issues.len(), 2);
+
+        let page_types = context.settings_path.with_file_name("page-types.json");
+        fs::write(&page_types, "{ not json").unwrap();
+        let lint_err = lint_pages(&context, None).unwrap_err();
+        assert_eq!(read_page(&context, "Standup").unwrap_err(), lint_err);
+        fs::remove_file(&page_types).unwrap();
+
+        update_page(&context, "Standup", "# Standup\nNotes\n", false, true).unwrap();
+        let err = update_page(
+            &context,
+            "Roadmap",
+            "---\nstatus: someday\n---\n# Roadmap\n",
+            true,
+            true,
+        )
+        .unwrap_err();
+        assert!(err.contains("status"));
+        assert!(err.contains("type"));
+        let raw = fs::read_to_string(context.pages_dir.join("Roadmap.md")).unwrap();
+        assert!(raw.contains("status: active"));
+        assert!(raw.contains("type: project"));
+    }
+
+    #[test]
+    fn lints_vault_and_previews_fixes() {
+        let context = make_test_context();
+        write_test_page(&context.pages_dir, "Launch plan", "# Launch\n");
+        write_test_note(
+            &context.journal_dir,
+            "2026-03-12",
+            "# Thu\n\n### Todo  \n\n- [ ] Prep [[Launch plan]]\n- [ ] Ask [[Nobody]]\n- [ ] prep [[launch plan]]\n",
+        );
+        let note_path = context.journal_dir.join("2026-03-12.md");
+        let before = fs::read_to_string(&note_path).unwrap();
+
+        assert!(matches!(
+            parse_command(&[
+                "lint".to_string(),
+                "--fix".to_string(),
+                "--dry-run".to_string(),
+                "--json".to_string(),
+            ])
+            .unwrap(),
+            ParsedCommand::Lint {
+                fix: true,
+                apply: false,
+                ..
+            }
+        ));
+        assert!(parse_command(&["lint".to_string(), "--apply".to_string()]).is_err());
+
+        let report = lint_vault(&context, &[], false, false).unwrap();
+        assert_eq!(report.checked, 2);
+        assert_eq!(report.problems, 4);
+
+        let preview = lint_vault(&context, &[], true, false).unwrap();
+        assert_eq!(preview.fixed, 1);
+        assert_eq!(preview.problems, 1);
+        let file = &preview.files[0];
+        assert!(file.unified_diff.as_deref().unwrap().contains("+## Todo"));
+        assert_eq!(fs::read_to_string(&note_path).unwrap(), before);
+
+        lint_vault(&context, &[], true, true).unwrap();
+        assert_eq!(
+            fs::read_to_string(&note_path).unwrap(),
+            "# Thu\n\n## Todo\n\n- [ ] Prep [[Launch plan]]\n- [ ] Ask [[Nobody]]\n"
+        );
+    }
+
+    #[test]
+    fn exports_tagged_notes_as_site() {
+        let context = make_test_context();
+        write_test_page(
+            &context.pages_dir,
+            "Launch plan",
+            "---\ntags: [launch]\nbudget: 10k\n---\n# Launch plan\n\nShip it.\n",
+        );
+        write_test_page(&context.pages_dir, "Diary", "Private thoughts #personal\n");
+        write_test_note(
+            &context.journal_dir,
+            "2026-03-12",
+            "- Reviewed [[Launch plan]] #launch\n- Wrote in [[Diary]]\n",
+        );
+        fs::write(
+            context.settings_path.with_file_name("site.json"),
+            r#"{"title": "Launch", "privateFields": ["budget"]}"#,
+        )
+        .unwrap();
+        let out_dir = context.settings_path.with_file_name("site");
+
+        let ParsedCommand::SiteExport { selection, .. } = parse_command(&[
+            "site".to_string(),
+            "export".to_string(),
+            "--out".to_string(),
+            out_dir.to_string_lossy().to_string(),
+            "--tag".to_string(),
+            "launch".to_string(),
+            "--dry-run".to_string(),
+        ])
+        .unwrap() else {
+            panic!("expected a site export");
+        };
+        assert!(parse_command(&[
+            "site".to_string(),
+            "export".to_string(),
+            "--apply".to_string()
+        ])
+        .is_err());
+
+        let preview = export_site(&context, &out_dir, selection.clone(), &[], None, false).unwrap();
+        assert_eq!((preview.pages, preview.daily_notes), (1, 1));
+        assert!(preview
+            .files
+            .contains(&"pages/launch-plan.html".to_string()));
+        assert!(!out_dir.exists());
+
+        export_site(&context, &out_dir, selection, &[], None, true).unwrap();
+        let page = fs::read_to_string(out_dir.join("pages/launch-plan.html")).unwrap();
+        assert!(page.contains("<title>Launch plan · Launch</title>"));
+        assert!(!page.contains("10k"));
+        assert!(page.contains("href=\"../daily/2026-03-12.html\""));
+        let daily = fs::read_to_string(out_dir.join("daily/2026-03-12.html")).unwrap();
+        assert!(daily.contains("Wrote in Diary"));
+        assert!(!out_dir.join("pages/diary.html").exists());
+    }
+
+    #[test]
+    fn imports_logseq_graph_after_preview() {
+        let context = make_test_context();
+        let graph = context.settings_path.with_file_name("graph");
+        write_test_note(
+            &graph.join("journals"),
+            "2026_03_12",
+            "- TODO Review [[Launch plan]]\n- ![mock](../assets/mock.png)\n",
+        );
+        write_test_page(&graph.join("pages"), "Launch plan", "- Draft\n");
+        fs::create_dir_all(graph.join("assets")).unwrap();
+        fs::write(graph.join("assets").join("mock.png"), b"png").unwrap();
+        write_test_page(&context.pages_dir, "Launch plan", "# Mine\n");
+
+        assert!(matches!(
+            parse_command(&[
+                "import".to_string(),
+                "--from".to_string(),
+                "logseq".to_string(),
+                "--path".to_string(),
+                graph.to_string_lossy().to_string(),
+                "--dry-run".to_string(),
+            ])
+            .unwrap(),
+            ParsedCommand::Import { apply: false, .. }
+        ));
+        assert!(parse_command(&[
+            "import".to_string(),
+            "--from".to_string(),
+            "logseq".to_string(),
+            "--apply".to_string()
+        ])
+        .is_err());
+
+        let preview = import_notes(&context, ImportSource::Logseq, &graph, false).unwrap();
+        assert!(preview
+            .summary
+            .starts_with("daily_notes: 1\npages: 0\nattachments: 1\nconflicts: 1\n"));
+        assert!(!context.journal_dir.join("2026-03-12.md").exists());
+
+        let applied = import_notes(&context, ImportSource::Logseq, &graph, true).unwrap();
+        assert_eq!(applied.written, 2);
+        assert_eq!(
+            fs::read_to_string(context.journal_dir.join("2026-03-12.md")).unwrap(),
+            "- [ ] Review [[Launch plan]]\n- ![mock](assets/mock.png)\n"
+        );
+        assert!(context.assets_dir.join("mock.png").exists());
+        assert_eq!(
+            fs::read_to_string(context.pages_dir.join("Launch plan.md")).unwrap(),
+            "# Mine\n"
+        );
+    }
+
+    #[test]
+    fn updates_note_body_without_dropping_frontmatter() {
+        let context = make_test_context();
+        write_test_note(
+            &context.journal_dir,
+            "2026-03-12",
+            "---\nmood: 7 # out of 10\ncity: Seoul\ntags: [travel]\n---\nOld\n",
+        );
+
+        update_note(&context, "2026-03-12", "New\n", true).unwrap();
+        let raw = fs::read_to_string(context.journal_dir.join("2026-03-12.md")).unwrap();
+
+        assert_eq!(
+            raw,
+            "---\nmood: 7 # out of 10\ncity: Seoul\ntags: [travel]\n---\nNew\n"
+        );
+    }
+
     #[test]
     fn searches_pages_by_title_and_body() {
         let context = make_test_context();
@@ -2016,4 +4102,230 @@ mod tests {
         assert_eq!(hits.len(), 1);
         assert_eq!(hits[0].title, "Launch plan");
     }
+
+    #[test]
+    fn resolves_aliases_and_fragments_when_reading_pages() {
+        let context = make_test_context();
+        write_test_page(
+            &context.pages_dir,
+            "Launch plan",
+            "---\naliases: [Launch]\n---\n# Launch plan\n## Risks\nLate vendor ^vendor\n",
+        );
+        write_test_note(
+            &context.journal_dir,
+            "2026-03-12",
+            "Reviewed [[Launch#Risks|the risks]]\n",
+        );
+
+        let page = read_page(&context, "launch").unwrap().unwrap();
+        assert_eq!(page.title, "Launch plan");
+        assert_eq!(page.aliases, vec!["Launch"]);
+        assert_eq!(page.attached_to.as_deref(), Some("2026-03-12"));
+
+        let section = read_page_link(&context, "Launch#Risks").unwrap().unwrap();
+        assert_eq!(
+            section.fragment.as_deref(),
+            Some("## Risks\nLate vendor ^vendor")
+        );
+        let block = read_page_link(&context, "Launch plan#^vendor")
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.fragment.as_deref(), Some("Late vendor"));
+        assert!(read_page_link(&context, "Launch#Missing").is_err());
+    }
+
+    #[test]
+    fn searches_notes_and_pages_by_nested_tags() {
+        let context = make_test_context();
+        write_test_note(
+            &context.journal_dir,
+            "2026-03-10",
+            "Kickoff for #project/alpha\n",
+        );
+        write_test_note(&context.journal_dir, "2026-03-11", "Unrelated #projects\n");
+        write_test_page(
+            &context.pages_dir,
+            "Alpha",
+            "---\ntags: [project/alpha]\n---\nScope\n",
+        );
+
+        let hits = search_notes(&context, "#project", 10).unwrap();
+        assert_eq!(
+            hits.iter().map(|hit| hit.date.as_str()).collect::<Vec<_>>(),
+            vec!["2026-03-10"]
+        );
+        assert!(search_notes(&context, "#project kickoff", 10)
+            .unwrap()
+            .iter()
+            .any(|hit| hit.date == "2026-03-10"));
+        assert_eq!(
+            search_pages(&context, "#project/alpha scope", 10).unwrap()[0].title,
+            "Alpha"
+        );
+        assert!(search_pages(&context, "#projects", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn creates_daily_note_from_obsidian_template() {
+        let mut context = make_test_context();
+        let vault = context.pages_dir.parent().unwrap().to_path_buf();
+        fs::create_dir_all(vault.join(".obsidian")).unwrap();
+        fs::create_dir_all(vault.join("Templates")).unwrap();
+        fs::write(
+            vault.join(".obsidian/daily-notes.json"),
+            r#"{"template":"Templates/Daily"}"#,
+        )
+        .unwrap();
+        fs::write(
+            vault.join("Templates/Daily.md"),
+            "---\ncity: Seoul\n---\n# {{date:dddd D MMMM}}\n",
+        )
+        .unwrap();
+        context.vault_dir = Some(vault);
+
+        let note = create_note(&context, "2026-03-14", None, None).unwrap();
+
+        assert_eq!(note.city.as_deref(), Some("Seoul"));
+        assert_eq!(note.markdown, "# Saturday 14 March\n");
+    }
+
+    #[test]
+    fn parses_periodic_note_addresses() {
+        let argv = ["note", "read", "--week", "2026-W41"].map(String::from);
+        let Ok(ParsedCommand::PeriodicNoteRead { period }) = parse_command(&argv) else {
+            panic!("expected a periodic read");
+        };
+        assert_eq!(period.key, "2026-W41");
+
+        let argv = ["note", "read", "--date", "2026-10-05", "--month", "2026-10"].map(String::from);
+        assert!(parse_command(&argv).is_err());
+        let argv = ["note", "rollup", "--quarter", "2026-Q4"].map(String::from);
+        assert!(parse_command(&argv).is_err());
+    }
+
+    #[test]
+    fn rolls_up_week_with_dry_run_before_apply() {
+        let context = make_test_context();
+        write_test_note(
+            &context.journal_dir,
+            "2026-10-05",
+            "- [x] Ship beta\n- [ ] Email vendor\nMet in [[Design sync]]\n",
+        );
+        write_test_note(&context.journal_dir, "2026-10-12", "- [x] Next week\n");
+        write_test_page(
+            &context.pages_dir,
+            "Design sync",
+            "---\ntype: meeting\n---\nNotes\n",
+        );
+        let period = Period::parse(PeriodKind::Week, "2026-W41").unwrap();
+
+        let preview = rollup_periodic_note(&context, &period, false).unwrap();
+        assert!(read_periodic_note(&context, &period).unwrap().is_none());
+        assert!(preview
+            .unified_diff
+            .contains("+- [x] Ship beta (2026-10-05)"));
+        assert!(preview
+            .after_markdown
+            .contains("- [[Design sync]] (2026-10-05)"));
+        assert!(preview
+            .after_markdown
+            .contains("- [ ] Email vendor (2026-10-05)"));
+        assert!(!preview.after_markdown.contains("Next week"));
+
+        rollup_periodic_note(&context, &period, true).unwrap();
+        let note = read_periodic_note(&context, &period).unwrap().unwrap();
+        assert!(note.path.ends_with("2026-W41.md"));
+        assert_eq!(note.markdown, preview.after_markdown);
+        assert!(rollup_periodic_note(&context, &period, false)
+            .unwrap()
+            .unified_diff
+            .is_empty());
+    }
+
+    #[test]
+    fn creates_notes_and_pages_from_selected_templates() {
+        let mut context = make_test_context();
+        fs::create_dir_all(&context.templates_dir).unwrap();
+        fs::write(
+            context.templates_dir.join("Daily.md"),
+            "# {{ weekday }} {{ date:MMM D }}\n{% if previous_open_tasks %}\n## From {{ previous_note_date }}\n{{ previous_open_tasks }}\n{% endif %}\n{% if city %}\nWeather: {{ city }}\n{% endif %}\n",
+        )
+        .unwrap();
+        fs::write(
+            context.templates_dir.join("Meeting.md"),
+            "## Attendees\n\n## Notes for {{ title }}\n",
+        )
+        .unwrap();
+        context
+            .note_templates
+            .insert(NoteTemplateKind::Daily, "Daily".to_string());
+        write_test_note(
+            &context.journal_dir,
+            "2026-03-12",
+            "---\ncity: Seoul\n---\n- [ ] Email vendor\n- [x] Done\n",
+        );
+
+        let note = create_note(&context, "2026-03-14", None, None).unwrap();
+        assert_eq!(note.city, None);
+        assert_eq!(
+            note.markdown,
+            "# Saturday Mar 14\n## From 2026-03-12\n- [ ] Email vendor\nWeather: Seoul\n"
+        );
+
+        let note = create_note(&context, "2026-03-15", None, Some("Busan")).unwrap();
+        assert_eq!(note.city.as_deref(), Some("Busan"));
+        assert!(note.markdown.ends_with("Weather: Busan\n"));
+
+        let page = create_page(&context, "Design sync", Some("Meeting")).unwrap();
+        assert_eq!(page.markdown, "## Attendees\n\n## Notes for Design sync\n");
+
+        assert!(create_note(&context, "2026-03-20", Some("Missing"), None)
+            .unwrap_err()
+            .contains("Template Missing does not exist"));
+    }
+
+    #[test]
+    fn written_paths_reports_only_applied_writes() {
+        let argv = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let applied =
+            r#"{"applied":[{"date":"2026-03-20","path":"/vault/journal/2026-03-20.md"}]}"#;
+
+        assert_eq!(
+            written_paths(
+                &argv(&["note", "update", "--date", "2026-03-20", "--apply"]),
+                applied
+            ),
+            vec![PathBuf::from("/vault/journal/2026-03-20.md")]
+        );
+        assert!(written_paths(
+            &argv(&["note", "update", "--date", "2026-03-20", "--dry-run"]),
+            r#"{"change":{"path":"/vault/journal/2026-03-20.md"}}"#
+        )
+        .is_empty());
+        assert!(written_paths(
+            &argv(&["note", "read", "--date", "2026-03-20"]),
+            r#"{"note":{"path":"/vault/journal/2026-03-20.md"}}"#
+        )
+        .is_empty());
+
+        let lint = r#"{"files":[
+            {"path":"/vault/a.md","diagnostics":[],"unifiedDiff":"-a\n+b"},
+            {"path":"/vault/b.md","diagnostics":[{"rule":"x"}]}
+        ],"checked":2,"problems":1,"fixed":1,"applied":true}"#;
+        assert_eq!(
+            written_paths(&argv(&["lint", "--fix", "--apply"]), lint),
+            vec![PathBuf::from("/vault/a.md")]
+        );
+
+        assert_eq!(
+            written_paths(
+                &argv(&["site", "export", "--out", "/vault/site", "--apply"]),
+                r#"{"outDir":"/vault/site","files":["index.html","pages/a.html"]}"#
+            ),
+            vec![
+                PathBuf::from("/vault/site/index.html"),
+                PathBuf::from("/vault/site/pages/a.html")
+            ]
+        );
+    }
 }
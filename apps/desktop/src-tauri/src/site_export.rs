@@ -0,0 +1,1125 @@
+//! Renders a chosen part of the vault to a static HTML site: one page per note with wiki links
+//! resolved and backlinks listed, a timeline of daily notes on the index, the attachments the
+//! notes use, and a search index the browser can query without a server.
+
+use crate::obsidian::{
+    collect_tags, extract_link_fragment, split_link_fragment, tags_match, PageAliases,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Written at the root of every export so a later export knows the folder is safe to replace.
+const SITE_MARKER: &str = ".philo-site";
+
+/// Keys that only control publishing and never appear on the site.
+const PUBLISH_KEYS: [&str; 2] = ["private", "publish"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SiteNoteKind {
+    Daily,
+    Page,
+}
+
+/// A note as read from the vault, with its frontmatter already split off.
+#[derive(Clone, Debug)]
+pub struct SiteNote {
+    pub kind: SiteNoteKind,
+    pub title: String,
+    /// `YYYY-MM-DD`, for daily notes.
+    pub date: Option<String>,
+    pub path: PathBuf,
+    /// The note's folder relative to the vault root, `/`-separated.
+    pub folder: String,
+    pub page_type: String,
+    pub frontmatter: JsonMap<String, JsonValue>,
+    pub body: String,
+}
+
+/// `site.json`, next to the settings file.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SiteConfig {
+    pub title: String,
+    /// Frontmatter fields that are never published, on top of `_`-prefixed ones.
+    pub private_fields: Vec<String>,
+}
+
+/// Which notes to publish. A note is picked when it matches any folder, tag or page type; with
+/// no filters at all, every note is.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteSelection {
+    pub folders: Vec<String>,
+    pub tags: Vec<String>,
+    pub page_types: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SiteOptions {
+    pub title: String,
+    pub selection: SiteSelection,
+    pub private_fields: Vec<String>,
+    /// Attachments are only published from under this folder or `assets_dir`.
+    pub vault_dir: PathBuf,
+    pub assets_dir: PathBuf,
+    /// The folder notes link attachments through, e.g. `assets`.
+    pub assets_link_root: String,
+}
+
+#[derive(Clone, Debug)]
+pub enum SiteContent {
+    Generated(Vec<u8>),
+    Copied(PathBuf),
+}
+
+#[derive(Clone, Debug)]
+pub struct SiteFile {
+    /// Relative to the output folder, `/`-separated.
+    pub path: String,
+    pub content: SiteContent,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Site {
+    pub files: Vec<SiteFile>,
+    pub pages: usize,
+    pub daily_notes: usize,
+    pub attachments: usize,
+    /// Notes the selection picked but that are marked `private: true` or `publish: false`.
+    pub excluded_private: usize,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct SearchEntry<'a> {
+    title: &'a str,
+    url: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<&'a str>,
+    tags: Vec<&'a str>,
+    text: String,
+}
+
+/// Published notes and how to reach them.
+struct Catalog<'a> {
+    notes: Vec<&'a SiteNote>,
+    urls: Vec<String>,
+    aliases: PageAliases,
+    by_title: HashMap<String, usize>,
+    by_date: HashMap<String, usize>,
+}
+
+impl Catalog<'_> {
+    /// The published note a wiki link target (without its fragment) names.
+    fn resolve(&self, target: &str) -> Option<usize> {
+        let target = target.trim();
+        if let Some(&index) = self.by_date.get(target) {
+            return Some(index);
+        }
+        let title = self.aliases.resolve(target);
+        self.by_title.get(&title.to_lowercase()).copied()
+    }
+}
+
+/// Attachments copied into the site, named uniquely under `assets/`.
+#[derive(Default)]
+struct SiteAssets {
+    by_source: HashMap<PathBuf, String>,
+    names: HashSet<String>,
+    files: Vec<SiteFile>,
+}
+
+impl SiteAssets {
+    fn add(&mut self, source: &Path) -> String {
+        if let Some(path) = self.by_source.get(source) {
+            return path.clone();
+        }
+
+        let name = source
+            .file_name()
+            .map(|name| name.to_string_lossy().replace(' ', "-"))
+            .unwrap_or_else(|| "attachment".to_string());
+        let (stem, extension) = match name.rsplit_once('.') {
+            Some((stem, extension)) if !stem.is_empty() => (stem.to_string(), Some(extension)),
+            _ => (name.clone(), None),
+        };
+        let mut candidate = name.clone();
+        let mut counter = 1;
+        while !self.names.insert(candidate.to_lowercase()) {
+            counter += 1;
+            candidate = match extension {
+                Some(extension) => format!("{stem}-{counter}.{extension}"),
+                None => format!("{stem}-{counter}"),
+            };
+        }
+
+        let path = format!("assets/{candidate}");
+        self.by_source.insert(source.to_path_buf(), path.clone());
+        self.files.push(SiteFile {
+            path: path.clone(),
+            content: SiteContent::Copied(source.to_path_buf()),
+        });
+        path
+    }
+}
+
+pub fn is_private(frontmatter: &JsonMap<String, JsonValue>) -> bool {
+    frontmatter.get("private") == Some(&JsonValue::Bool(true))
+        || frontmatter.get("publish") == Some(&JsonValue::Bool(false))
+}
+
+fn is_selected(note: &SiteNote, selection: &SiteSelection) -> bool {
+    if selection.folders.is_empty() && selection.tags.is_empty() && selection.page_types.is_empty()
+    {
+        return true;
+    }
+
+    let in_folder = selection.folders.iter().any(|folder| {
+        let folder = folder.trim().trim_matches('/');
+        folder.is_empty() || note.folder == folder || note.folder.starts_with(&format!("{folder}/"))
+    });
+    let tags = collect_tags(&note.frontmatter, &note.body);
+    let has_tag = selection.tags.iter().any(|tag| tags_match(&tags, tag));
+    let has_type = selection
+        .page_types
+        .iter()
+        .any(|page_type| page_type.eq_ignore_ascii_case(&note.page_type));
+    in_folder || has_tag || has_type
+}
+
+fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for ch in title.to_lowercase().chars() {
+        if ch.is_alphanumeric() {
+            slug.push(ch);
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        "page".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn display_date(date: &str) -> String {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|parsed| parsed.format("%A, %B %-d, %Y").to_string())
+        .unwrap_or_else(|_| date.to_string())
+}
+
+/// Calls `rewrite` for every line outside fenced code blocks.
+fn map_prose_lines(markdown: &str, mut rewrite: impl FnMut(&str) -> String) -> String {
+    let mut out = Vec::new();
+    let mut fence: Option<&str> = None;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        let marker = ["```", "~~~"]
+            .into_iter()
+            .find(|marker| trimmed.starts_with(marker));
+        match (fence, marker) {
+            (None, Some(marker)) => fence = Some(marker),
+            (Some(open), Some(marker)) if open == marker => fence = None,
+            _ => {}
+        }
+        if fence.is_some() || marker.is_some() {
+            out.push(line.to_string());
+        } else {
+            out.push(rewrite(line));
+        }
+    }
+    let mut joined = out.join("\n");
+    if markdown.ends_with('\n') {
+        joined.push('\n');
+    }
+    joined
+}
+
+/// Calls `rewrite` for the parts of a line outside inline code spans.
+fn map_outside_inline_code(line: &str, mut rewrite: impl FnMut(&str) -> String) -> String {
+    line.split('`')
+        .enumerate()
+        .map(|(index, part)| {
+            if index % 2 == 0 {
+                rewrite(part)
+            } else {
+                part.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("`")
+}
+
+fn is_external(target: &str) -> bool {
+    target.contains("://")
+        || target.starts_with('#')
+        || target.starts_with("mailto:")
+        || target.starts_with("data:")
+}
+
+/// The body without a leading `# Title` that repeats the note's title.
+fn strip_title_heading(body: &str, title: &str) -> String {
+    let trimmed = body.trim_start();
+    match trimmed.split_once('\n') {
+        Some((first, rest)) if first.trim() == format!("# {title}") => {
+            rest.trim_start().to_string()
+        }
+        None if trimmed.trim() == format!("# {title}") => String::new(),
+        _ => trimmed.to_string(),
+    }
+}
+
+struct Renderer<'a> {
+    catalog: &'a Catalog<'a>,
+    options: &'a SiteOptions,
+    assets: SiteAssets,
+    links: BTreeMap<usize, BTreeSet<usize>>,
+    warnings: Vec<String>,
+    /// The canonical vault and assets folders attachments may come from.
+    attachment_roots: Vec<PathBuf>,
+    wiki_link_re: regex::Regex,
+    embed_line_re: regex::Regex,
+    markdown_link_re: regex::Regex,
+    block_anchor_re: regex::Regex,
+}
+
+impl<'a> Renderer<'a> {
+    fn new(catalog: &'a Catalog<'a>, options: &'a SiteOptions) -> Self {
+        Self {
+            catalog,
+            options,
+            assets: SiteAssets::default(),
+            links: BTreeMap::new(),
+            warnings: Vec::new(),
+            attachment_roots: [&options.vault_dir, &options.assets_dir]
+                .into_iter()
+                .filter_map(|dir| dir.canonicalize().ok())
+                .collect(),
+            wiki_link_re: regex::Regex::new(r"(!?)\[\[([^\]|\n]+)(?:\|([^\]\n]+))?\]\]")
+                .expect("valid wiki link regex"),
+            embed_line_re: regex::Regex::new(r"^\s*!\[\[([^\]|\n]+)(?:\|[^\]\n]*)?\]\]\s*$")
+                .expect("valid embed regex"),
+            markdown_link_re: regex::Regex::new(
+                r"(!?)\[([^\]\n]*)\]\(([^)\s]+)(\s+\x22[^\x22]*\x22)?\)",
+            )
+            .expect("valid markdown link regex"),
+            block_anchor_re: regex::Regex::new(r"\s\^[A-Za-z0-9-]+\s*$")
+                .expect("valid block anchor regex"),
+        }
+    }
+
+    /// The markdown to render for the note at `index`, with links pointing into the site.
+    fn rewrite_body(&mut self, index: usize) -> String {
+        let note = self.catalog.notes[index];
+        let body = strip_title_heading(&note.body, &note.title);
+        let rewritten = map_prose_lines(&body, |line| {
+            let expanded = match self.embed_line_re.captures(line) {
+                Some(captures) => self.expand_embed(index, &captures[1]),
+                None => None,
+            };
+            match expanded {
+                Some(quote) => quote
+                    .lines()
+                    .map(|line| self.rewrite_line(index, line))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                None => self.rewrite_line(index, line),
+            }
+        });
+        map_prose_lines(&rewritten, |line| {
+            self.block_anchor_re.replace(line, "").into_owned()
+        })
+    }
+
+    /// A note embedded on a line of its own becomes a quote of its content, or of the section a
+    /// `#heading` or `#^block` fragment names.
+    fn expand_embed(&mut self, from: usize, target: &str) -> Option<String> {
+        let (page, fragment) = split_link_fragment(target);
+        let embedded = self.catalog.resolve(page)?;
+        let note = self.catalog.notes[embedded];
+        let body = strip_title_heading(&note.body, &note.title);
+        let content = match fragment {
+            Some(fragment) => extract_link_fragment(&body, &fragment)?,
+            None => body,
+        };
+        self.links.entry(embedded).or_default().insert(from);
+
+        let mut quote = content
+            .trim_end()
+            .lines()
+            .map(|line| format!("> {line}").trim_end().to_string())
+            .collect::<Vec<_>>();
+        quote.push(">".to_string());
+        quote.push(format!("> [{}]({})", note.title, self.link_to(embedded)));
+        Some(quote.join("\n"))
+    }
+
+    fn link_to(&self, index: usize) -> String {
+        format!("../{}", self.catalog.urls[index])
+    }
+
+    fn rewrite_line(&mut self, from: usize, line: &str) -> String {
+        map_outside_inline_code(line, |part| {
+            let wiki_link_re = self.wiki_link_re.clone();
+            let part = wiki_link_re.replace_all(part, |captures: &regex::Captures| {
+                self.rewrite_wiki_link(
+                    from,
+                    !captures[1].is_empty(),
+                    &captures[2],
+                    captures.get(3).map(|value| value.as_str()),
+                )
+            });
+            let markdown_link_re = self.markdown_link_re.clone();
+            markdown_link_re
+                .replace_all(&part, |captures: &regex::Captures| {
+                    self.rewrite_markdown_link(from, &captures[1], &captures[2], &captures[3])
+                        .unwrap_or_else(|| captures[0].to_string())
+                })
+                .into_owned()
+        })
+    }
+
+    fn rewrite_wiki_link(
+        &mut self,
+        from: usize,
+        is_embed: bool,
+        target: &str,
+        label: Option<&str>,
+    ) -> String {
+        let (page, fragment) = split_link_fragment(target);
+        let text = label.map(str::trim).unwrap_or_else(|| {
+            if fragment.is_some() && !target.contains("#^") && !target.contains('^') {
+                target.trim()
+            } else {
+                page.trim()
+            }
+        });
+        let text = text.replace('#', " > ");
+
+        if let Some(index) = self.catalog.resolve(page) {
+            self.links.entry(index).or_default().insert(from);
+            return format!("[{}]({})", text, self.link_to(index));
+        }
+
+        // `![[photo.png]]` and `[[report.pdf]]` name attachments.
+        let file_name = page.trim();
+        if file_name.contains('.') && !file_name.to_lowercase().ends_with(".md") {
+            let source = self.options.assets_dir.join(file_name);
+            if let Ok(source) = self.contained_attachment(&source) {
+                let path = self.assets.add(&source);
+                let bang = if is_embed { "!" } else { "" };
+                return format!("{bang}[{}](../{})", label.unwrap_or(""), path);
+            }
+        }
+        text
+    }
+
+    fn rewrite_markdown_link(
+        &mut self,
+        from: usize,
+        bang: &str,
+        text: &str,
+        target: &str,
+    ) -> Option<String> {
+        if is_external(target) {
+            return None;
+        }
+        let target = target
+            .strip_prefix('<')
+            .and_then(|value| value.strip_suffix('>'))
+            .unwrap_or(target);
+        let decoded = decode_percent(target.split(['?', '#']).next().unwrap_or(target));
+
+        if decoded.to_lowercase().ends_with(".md") {
+            let stem = Path::new(&decoded)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            return Some(match self.catalog.resolve(&stem) {
+                Some(index) => {
+                    self.links.entry(index).or_default().insert(from);
+                    format!("{bang}[{text}]({})", self.link_to(index))
+                }
+                None => text.to_string(),
+            });
+        }
+
+        let source = match self.contained_attachment(&self.attachment_path(from, &decoded)) {
+            Ok(source) => source,
+            Err(problem) => {
+                self.warnings.push(format!(
+                    "{}: attachment {} {}",
+                    self.catalog.notes[from].path.display(),
+                    decoded,
+                    problem
+                ));
+                return None;
+            }
+        };
+        let path = self.assets.add(&source);
+        Some(format!("{bang}[{text}](../{path})"))
+    }
+
+    /// Resolves an attachment link the way the editor does: `assets/…` and bare file names are
+    /// in the assets folder, anything else is relative to the note.
+    fn attachment_path(&self, from: usize, target: &str) -> PathBuf {
+        let relative = target.trim_start_matches("./").trim_start_matches("../");
+        let root = self.options.assets_link_root.trim_matches('/');
+        if let Some(suffix) = relative.strip_prefix(&format!("{root}/")) {
+            return self.options.assets_dir.join(suffix);
+        }
+        if !relative.contains('/') {
+            return self.options.assets_dir.join(relative);
+        }
+        self.catalog.notes[from]
+            .path
+            .parent()
+            .map(|dir| dir.join(target))
+            .unwrap_or_else(|| PathBuf::from(target))
+    }
+
+    /// The canonical path of an attachment, as long as it is a file under the vault or the
+    /// assets folder. Absolute paths and `..` escapes elsewhere are refused.
+    fn contained_attachment(&self, source: &Path) -> Result<PathBuf, &'static str> {
+        let source = source
+            .canonicalize()
+            .ok()
+            .filter(|source| source.is_file())
+            .ok_or("was not found")?;
+        if self
+            .attachment_roots
+            .iter()
+            .any(|root| source.starts_with(root))
+        {
+            Ok(source)
+        } else {
+            Err("is outside the vault")
+        }
+    }
+}
+
+fn decode_percent(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' && index + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[index + 1..index + 3]).unwrap_or("");
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                decoded.push(byte);
+                index += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[index]);
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn public_frontmatter(
+    frontmatter: &JsonMap<String, JsonValue>,
+    private_fields: &[String],
+) -> Vec<(String, String)> {
+    frontmatter
+        .iter()
+        .filter(|(key, _)| {
+            !key.starts_with('_')
+                && !PUBLISH_KEYS.contains(&key.as_str())
+                && !matches!(key.as_str(), "tags" | "tag" | "aliases" | "alias")
+                && !private_fields
+                    .iter()
+                    .any(|field| field.eq_ignore_ascii_case(key))
+        })
+        .map(|(key, value)| {
+            let value = match value {
+                JsonValue::String(value) => value.clone(),
+                JsonValue::Array(items) => items
+                    .iter()
+                    .map(|item| match item {
+                        JsonValue::String(value) => value.clone(),
+                        other => other.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                JsonValue::Null => String::new(),
+                other => other.to_string(),
+            };
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+fn html_to_text(html: &str) -> String {
+    let tag_re = regex::Regex::new(r"<[^>]*>").expect("valid tag regex");
+    let text = tag_re
+        .replace_all(html, " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&");
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(5000)
+        .collect()
+}
+
+fn render_document(site_title: &str, title: &str, root: &str, content: &str) -> String {
+    let head_title = if title == site_title {
+        escape_html(site_title)
+    } else {
+        format!("{} · {}", escape_html(title), escape_html(site_title))
+    };
+    format!(
+        r#"<!doctype html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{head_title}</title>
+<link rel="stylesheet" href="{root}style.css">
+</head>
+<body data-root="{root}">
+<header>
+<a class="site-title" href="{root}index.html">{site_title}</a>
+<input id="search" type="search" placeholder="Search" autocomplete="off">
+<ul id="search-results"></ul>
+</header>
+<main>
+{content}
+</main>
+<script src="{root}search-index.js"></script>
+<script src="{root}search.js"></script>
+</body>
+</html>
+"#,
+        site_title = escape_html(site_title),
+    )
+}
+
+/// Renders the selected notes. Nothing is written until `write_site`.
+pub fn build_site(notes: &[SiteNote], options: &SiteOptions) -> Result<Site, String> {
+    let mut site = Site::default();
+
+    let mut published = Vec::new();
+    for note in notes {
+        if !is_selected(note, &options.selection) {
+            continue;
+        }
+        if is_private(&note.frontmatter) {
+            site.excluded_private += 1;
+            continue;
+        }
+        published.push(note);
+    }
+    published.sort_by(|a, b| {
+        (a.kind == SiteNoteKind::Page, &a.date, &a.title).cmp(&(
+            b.kind == SiteNoteKind::Page,
+            &b.date,
+            &b.title,
+        ))
+    });
+
+    let mut catalog = Catalog {
+        notes: published,
+        urls: Vec::new(),
+        aliases: PageAliases::default(),
+        by_title: HashMap::new(),
+        by_date: HashMap::new(),
+    };
+    let mut slugs = HashSet::new();
+    for (index, note) in catalog.notes.iter().enumerate() {
+        let url = match (note.kind, note.date.as_deref()) {
+            (SiteNoteKind::Daily, Some(date)) => {
+                catalog.by_date.insert(date.to_string(), index);
+                format!("daily/{date}.html")
+            }
+            _ => {
+                let base = slugify(&note.title);
+                let mut slug = base.clone();
+                let mut counter = 1;
+                while !slugs.insert(slug.clone()) {
+                    counter += 1;
+                    slug = format!("{base}-{counter}");
+                }
+                catalog.aliases.insert_page(&note.title, &note.frontmatter);
+                catalog.by_title.insert(note.title.to_lowercase(), index);
+                format!("pages/{slug}.html")
+            }
+        };
+        catalog.urls.push(url);
+    }
+
+    let mut renderer = Renderer::new(&catalog, options);
+    let bodies = (0..catalog.notes.len())
+        .map(|index| renderer.rewrite_body(index))
+        .collect::<Vec<_>>();
+
+    let dailies = (0..catalog.notes.len())
+        .filter(|&index| catalog.notes[index].kind == SiteNoteKind::Daily)
+        .collect::<Vec<_>>();
+    let mut search = Vec::new();
+    for (index, body) in bodies.iter().enumerate() {
+        let note = catalog.notes[index];
+        let html = hypr_buffer::opinionated_md_to_html(body)
+            .map_err(|e| format!("Could not render {}: {}", note.path.display(), e))?;
+        let title = match note.kind {
+            SiteNoteKind::Daily => display_date(note.date.as_deref().unwrap_or_default()),
+            SiteNoteKind::Page => note.title.clone(),
+        };
+        let tags = collect_tags(&note.frontmatter, &note.body);
+
+        let mut content = format!("<article>\n<h1>{}</h1>\n", escape_html(&title));
+        let properties = public_frontmatter(&note.frontmatter, &options.private_fields);
+        if !properties.is_empty() {
+            content.push_str("<dl class=\"properties\">\n");
+            for (key, value) in &properties {
+                content.push_str(&format!(
+                    "<dt>{}</dt><dd>{}</dd>\n",
+                    escape_html(key),
+                    escape_html(value)
+                ));
+            }
+            content.push_str("</dl>\n");
+        }
+        if !tags.is_empty() {
+            let chips = tags
+                .iter()
+                .map(|tag| format!("<li>#{}</li>", escape_html(tag)))
+                .collect::<String>();
+            content.push_str(&format!("<ul class=\"tags\">{chips}</ul>\n"));
+        }
+        content.push_str(&html);
+        content.push_str("\n</article>\n");
+
+        if let Some(sources) = renderer.links.get(&index) {
+            let mut backlinks = sources
+                .iter()
+                .filter(|&&source| source != index)
+                .map(|&source| {
+                    let linked = catalog.notes[source];
+                    let label = match linked.kind {
+                        SiteNoteKind::Daily => {
+                            display_date(linked.date.as_deref().unwrap_or_default())
+                        }
+                        SiteNoteKind::Page => linked.title.clone(),
+                    };
+                    (linked.date.clone(), label, source)
+                })
+                .collect::<Vec<_>>();
+            backlinks.sort();
+            if !backlinks.is_empty() {
+                content.push_str("<section class=\"backlinks\">\n<h2>Linked from</h2>\n<ul>\n");
+                for (_, label, source) in backlinks {
+                    content.push_str(&format!(
+                        "<li><a href=\"../{}\">{}</a></li>\n",
+                        catalog.urls[source],
+                        escape_html(&label)
+                    ));
+                }
+                content.push_str("</ul>\n</section>\n");
+            }
+        }
+
+        if note.kind == SiteNoteKind::Daily {
+            let position = dailies.iter().position(|&daily| daily == index);
+            let previous = position
+                .and_then(|position| position.checked_sub(1))
+                .map(|position| dailies[position]);
+            let next = position.and_then(|position| dailies.get(position + 1).copied());
+            content.push_str("<nav class=\"timeline\">\n");
+            if let Some(previous) = previous {
+                content.push_str(&format!(
+                    "<a rel=\"prev\" href=\"../{}\">← {}</a>\n",
+                    catalog.urls[previous],
+                    catalog.notes[previous].date.as_deref().unwrap_or_default()
+                ));
+            }
+            if let Some(next) = next {
+                content.push_str(&format!(
+                    "<a rel=\"next\" href=\"../{}\">{} →</a>\n",
+                    catalog.urls[next],
+                    catalog.notes[next].date.as_deref().unwrap_or_default()
+                ));
+            }
+            content.push_str("</nav>\n");
+        }
+
+        site.files.push(SiteFile {
+            path: catalog.urls[index].clone(),
+            content: SiteContent::Generated(
+                render_document(&options.title, &title, "../", &content).into_bytes(),
+            ),
+        });
+        search.push((index, title, tags, html_to_text(&html)));
+        match note.kind {
+            SiteNoteKind::Daily => site.daily_notes += 1,
+            SiteNoteKind::Page => site.pages += 1,
+        }
+    }
+
+    site.files.push(SiteFile {
+        path: "index.html".to_string(),
+        content: SiteContent::Generated(render_index(&catalog, options).into_bytes()),
+    });
+
+    let entries = search
+        .iter()
+        .map(|(index, title, tags, text)| SearchEntry {
+            title,
+            url: &catalog.urls[*index],
+            date: catalog.notes[*index].date.as_deref(),
+            tags: tags.iter().map(String::as_str).collect(),
+            text: text.clone(),
+        })
+        .collect::<Vec<_>>();
+    let index_json = serde_json::to_string(&entries).map_err(|e| e.to_string())?;
+    site.files.push(SiteFile {
+        path: "search-index.js".to_string(),
+        content: SiteContent::Generated(
+            format!(
+                "window.PHILO_SEARCH_INDEX = {};\n",
+                index_json.replace("</", "<\\/")
+            )
+            .into_bytes(),
+        ),
+    });
+    for (path, contents) in [
+        ("search.js", SEARCH_JS),
+        ("style.css", STYLE_CSS),
+        (SITE_MARKER, ""),
+    ] {
+        site.files.push(SiteFile {
+            path: path.to_string(),
+            content: SiteContent::Generated(contents.as_bytes().to_vec()),
+        });
+    }
+
+    site.attachments = renderer.assets.files.len();
+    site.files.extend(renderer.assets.files);
+    site.warnings = renderer.warnings;
+    Ok(site)
+}
+
+fn render_index(catalog: &Catalog<'_>, options: &SiteOptions) -> String {
+    let mut content = String::new();
+    let mut months: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    let mut pages = Vec::new();
+    for (index, note) in catalog.notes.iter().enumerate() {
+        match (note.kind, note.date.as_deref()) {
+            (SiteNoteKind::Daily, Some(date)) => months
+                .entry(date.get(..7).unwrap_or(date).to_string())
+                .or_default()
+                .push(index),
+            _ => pages.push(index),
+        }
+    }
+
+    if !months.is_empty() {
+        content.push_str("<section class=\"timeline\">\n<h2>Timeline</h2>\n");
+        for (month, days) in months.iter().rev() {
+            let heading = NaiveDate::parse_from_str(&format!("{month}-01"), "%Y-%m-%d")
+                .map(|date| date.format("%B %Y").to_string())
+                .unwrap_or_else(|_| month.clone());
+            content.push_str(&format!("<h3>{}</h3>\n<ul>\n", escape_html(&heading)));
+            for &index in days.iter().rev() {
+                content.push_str(&format!(
+                    "<li><a href=\"{}\">{}</a></li>\n",
+                    catalog.urls[index],
+                    escape_html(&display_date(
+                        catalog.notes[index].date.as_deref().unwrap_or_default()
+                    ))
+                ));
+            }
+            content.push_str("</ul>\n");
+        }
+        content.push_str("</section>\n");
+    }
+
+    if !pages.is_empty() {
+        pages.sort_by_key(|&index| catalog.notes[index].title.to_lowercase());
+        content.push_str("<section class=\"pages\">\n<h2>Pages</h2>\n<ul>\n");
+        for index in pages {
+            content.push_str(&format!(
+                "<li><a href=\"{}\">{}</a></li>\n",
+                catalog.urls[index],
+                escape_html(&catalog.notes[index].title)
+            ));
+        }
+        content.push_str("</ul>\n</section>\n");
+    }
+
+    render_document(&options.title, &options.title, "", &content)
+}
+
+/// Replaces `out_dir` with the site. A non-empty folder is only replaced when an earlier export
+/// created it, so a typo can't wipe unrelated files.
+pub fn write_site(site: &Site, out_dir: &Path) -> Result<usize, String> {
+    if out_dir.exists() {
+        let is_empty = fs::read_dir(out_dir)
+            .map_err(|e| e.to_string())?
+            .next()
+            .is_none();
+        if !is_empty {
+            if !out_dir.join(SITE_MARKER).exists() {
+                return Err(format!(
+                    "{} is not empty and was not created by a site export.",
+                    out_dir.display()
+                ));
+            }
+            fs::remove_dir_all(out_dir).map_err(|e| e.to_string())?;
+        }
+    }
+
+    for file in &site.files {
+        let path = out_dir.join(&file.path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        match &file.content {
+            SiteContent::Generated(contents) => fs::write(&path, contents),
+            SiteContent::Copied(source) => fs::copy(source, &path).map(|_| ()),
+        }
+        .map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+    }
+    Ok(site.files.len())
+}
+
+const SEARCH_JS: &str = r#"(function () {
+  var input = document.getElementById("search");
+  var results = document.getElementById("search-results");
+  var root = document.body.getAttribute("data-root") || "";
+  var index = window.PHILO_SEARCH_INDEX || [];
+  input.addEventListener("input", function () {
+    var terms = input.value.toLowerCase().split(/\s+/).filter(Boolean);
+    results.innerHTML = "";
+    if (!terms.length) return;
+    index
+      .filter(function (entry) {
+        var haystack = (entry.title + " " + entry.tags.join(" ") + " " + entry.text).toLowerCase();
+        return terms.every(function (term) {
+          return haystack.indexOf(term) !== -1;
+        });
+      })
+      .slice(0, 20)
+      .forEach(function (entry) {
+        var item = document.createElement("li");
+        var link = document.createElement("a");
+        link.href = root + entry.url;
+        link.textContent = entry.title;
+        item.appendChild(link);
+        results.appendChild(item);
+      });
+  });
+})();
+"#;
+
+const STYLE_CSS: &str = r#"body { margin: 0 auto; max-width: 44rem; padding: 1.5rem; font: 16px/1.6 -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; color: #1f2328; }
+header { display: flex; flex-wrap: wrap; gap: 0.75rem; align-items: center; margin-bottom: 2rem; position: relative; }
+header .site-title { font-weight: 600; color: inherit; text-decoration: none; }
+#search { flex: 1; min-width: 10rem; padding: 0.35rem 0.6rem; border: 1px solid #d0d7de; border-radius: 6px; }
+#search-results { position: absolute; top: 100%; right: 0; left: 0; margin: 0; padding: 0; list-style: none; background: #fff; box-shadow: 0 4px 12px rgba(0, 0, 0, 0.08); }
+#search-results li a { display: block; padding: 0.4rem 0.6rem; }
+a { color: #0969da; }
+img { max-width: 100%; }
+blockquote { margin: 1rem 0; padding-left: 1rem; border-left: 3px solid #d0d7de; color: #57606a; }
+.properties { display: grid; grid-template-columns: max-content 1fr; gap: 0.2rem 1rem; color: #57606a; font-size: 0.9rem; }
+.properties dd { margin: 0; }
+.tags { display: flex; gap: 0.5rem; padding: 0; list-style: none; color: #57606a; font-size: 0.9rem; }
+.backlinks, nav.timeline { margin-top: 2.5rem; padding-top: 1rem; border-top: 1px solid #d0d7de; }
+nav.timeline { display: flex; justify-content: space-between; }
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn make_test_dir() -> PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("philo-site-export-test-{unique}"));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn note(kind: SiteNoteKind, title: &str, folder: &str, markdown: &str) -> SiteNote {
+        let (frontmatter, body) = match markdown.strip_prefix("---\n") {
+            Some(rest) => {
+                let (yaml, body) = rest.split_once("\n---\n").unwrap();
+                (
+                    serde_yaml::from_str::<JsonMap<String, JsonValue>>(yaml).unwrap(),
+                    body.to_string(),
+                )
+            }
+            None => (JsonMap::new(), markdown.to_string()),
+        };
+        let date = (kind == SiteNoteKind::Daily).then(|| title.to_string());
+        SiteNote {
+            kind,
+            title: title.to_string(),
+            date,
+            path: PathBuf::from(format!("/vault/{folder}/{title}.md")),
+            folder: folder.to_string(),
+            page_type: frontmatter
+                .get("type")
+                .and_then(JsonValue::as_str)
+                .unwrap_or("page")
+                .to_string(),
+            frontmatter,
+            body,
+        }
+    }
+
+    fn generated<'a>(site: &'a Site, path: &str) -> &'a str {
+        site.files
+            .iter()
+            .find_map(|file| match &file.content {
+                SiteContent::Generated(contents) if file.path == path => {
+                    Some(std::str::from_utf8(contents).unwrap())
+                }
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("{path} was not generated"))
+    }
+
+    #[test]
+    fn exports_selected_notes_with_links_and_backlinks() {
+        let base = make_test_dir();
+        let assets = base.join("assets");
+        fs::create_dir_all(&assets).unwrap();
+        fs::write(assets.join("chart.png"), b"png").unwrap();
+        let notes = vec![
+            note(
+                SiteNoteKind::Page,
+                "Launch",
+                "pages",
+                "---\ntype: project\nowner: Sam\n_reviewer: Kim\nsalary: 1\naliases: [Go live]\n---\n# Launch\n\nGoals ^goals\n\n![](assets/chart.png)\n\nSee [[Secret]] and `[[Launch]]`.\n",
+            ),
+            note(
+                SiteNoteKind::Daily,
+                "2026-03-12",
+                "journal",
+                "- Prep [[go live|the launch]]\n\n![[Launch#^goals]]\n",
+            ),
+            note(
+                SiteNoteKind::Page,
+                "Secret",
+                "pages",
+                "---\ntype: project\nprivate: true\n---\nHidden\n",
+            ),
+            note(SiteNoteKind::Page, "Recipes", "pages", "Soup\n"),
+        ];
+        let options = SiteOptions {
+            title: "Launch notes".to_string(),
+            selection: SiteSelection {
+                folders: vec!["journal".to_string()],
+                page_types: vec!["project".to_string()],
+                ..Default::default()
+            },
+            private_fields: vec!["salary".to_string()],
+            vault_dir: base.clone(),
+            assets_dir: assets.clone(),
+            assets_link_root: "assets".to_string(),
+        };
+
+        let site = build_site(&notes, &options).unwrap();
+        assert_eq!((site.pages, site.daily_notes, site.attachments), (1, 1, 1));
+        assert_eq!(site.excluded_private, 1);
+
+        let launch = generated(&site, "pages/launch.html");
+        assert!(launch.contains("<dt>owner</dt><dd>Sam</dd>"));
+        assert!(!launch.contains("Kim") && !launch.contains("salary"));
+        assert!(launch.contains("src=\"../assets/chart.png\""));
+        assert!(launch.contains("See Secret and <code>[[Launch]]</code>"));
+        assert!(!launch.contains("^goals"));
+        assert!(
+            launch.contains("<a href=\"../daily/2026-03-12.html\">Thursday, March 12, 2026</a>")
+        );
+
+        let daily = generated(&site, "daily/2026-03-12.html");
+        assert!(daily.contains("<a href=\"../pages/launch.html\">the launch</a>"));
+        assert!(daily.contains("<blockquote>\n<p>Goals</p>"));
+
+        let index = generated(&site, "index.html");
+        assert!(index.contains("<h3>March 2026</h3>"));
+        assert!(index.contains("<a href=\"pages/launch.html\">Launch</a>"));
+        assert!(!index.contains("Recipes"));
+        let search = generated(&site, "search-index.js");
+        assert!(search.contains("\"url\":\"pages/launch.html\""));
+        assert!(!search.contains("Hidden"));
+
+        let target = base.join("site");
+        write_site(&site, &target).unwrap();
+        assert_eq!(fs::read(target.join("assets/chart.png")).unwrap(), b"png");
+        write_site(&site, &target).unwrap();
+
+        assert!(write_site(&site, &base).is_err());
+        assert!(assets.join("chart.png").exists());
+    }
+
+    #[test]
+    fn refuses_attachments_outside_the_vault() {
+        let base = make_test_dir();
+        let vault = base.join("vault");
+        let assets = vault.join("assets");
+        fs::create_dir_all(&assets).unwrap();
+        fs::create_dir_all(vault.join("pages/media")).unwrap();
+        fs::write(vault.join("pages/media/map.png"), b"png").unwrap();
+        fs::create_dir_all(base.join("outside")).unwrap();
+        fs::write(base.join("outside/secret.txt"), b"secret").unwrap();
+
+        let mut launch = note(
+            SiteNoteKind::Page,
+            "Launch",
+            "pages",
+            "![](media/map.png)\n\n[passwd](/etc/passwd)\n\n[up](../../outside/secret.txt)\n\n[via assets](assets/../../outside/secret.txt)\n\n![[../../outside/secret.txt]]\n",
+        );
+        launch.path = vault.join("pages/Launch.md");
+        let options = SiteOptions {
+            title: "Notes".to_string(),
+            selection: SiteSelection::default(),
+            private_fields: Vec::new(),
+            vault_dir: vault.clone(),
+            assets_dir: assets,
+            assets_link_root: "assets".to_string(),
+        };
+
+        let site = build_site(&[launch], &options).unwrap();
+        assert_eq!(site.attachments, 1);
+        assert!(site.files.iter().all(|file| match &file.content {
+            SiteContent::Copied(source) => source.ends_with("pages/media/map.png"),
+            SiteContent::Generated(_) => true,
+        }));
+        let page = generated(&site, "pages/launch.html");
+        assert!(page.contains("src=\"../assets/map.png\""));
+        assert!(page.contains("href=\"/etc/passwd\""));
+        assert!(page.contains("href=\"../../outside/secret.txt\""));
+        assert!(page.contains("href=\"assets/../../outside/secret.txt\""));
+        assert_eq!(
+            site.warnings
+                .iter()
+                .filter(|warning| warning.ends_with("is outside the vault"))
+                .count(),
+            3
+        );
+    }
+}
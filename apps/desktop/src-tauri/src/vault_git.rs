@@ -0,0 +1,683 @@
+use git2::{ConfigLevel, ErrorCode, IndexAddOption, Oid, Repository, Signature};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::widget_git::{
+    blob_content_at_commit, build_unified_diff, commit_timestamp, commit_touches_path,
+};
+
+const AUTO_COMMIT_DEBOUNCE: Duration = Duration::from_secs(5);
+
+const MANAGED_CONFIG_KEY: &str = "philo.managed";
+const AUTO_COMMIT_CONFIG_KEY: &str = "philo.autocommit";
+const HISTORY_AUTHOR_NAME: &str = "Philo";
+const EXTERNAL_AUTHOR_NAME: &str = "External edit";
+const HISTORY_AUTHOR_EMAIL: &str = "vault-history@local";
+const DEFAULT_EXCLUDES: &str = ".DS_Store\n.tmp*\n*.tmp\n";
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultGitInput {
+    pub vault_root: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetVaultGitEnabledInput {
+    pub vault_root: String,
+    pub enabled: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultGitHistoryInput {
+    pub vault_root: String,
+    pub relative_note_path: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultGitDiffInput {
+    pub vault_root: String,
+    pub relative_note_path: String,
+    pub commit_id: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreVaultGitRevisionInput {
+    pub vault_root: String,
+    pub relative_note_path: String,
+    pub commit_id: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VaultGitMode {
+    /// The vault is not inside any git repository.
+    None,
+    /// Philo created the repository and may commit to it.
+    Managed,
+    /// The vault lives in a repository the user manages. Philo only reads its history.
+    User,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultGitStatus {
+    pub mode: VaultGitMode,
+    pub auto_commit: bool,
+    pub repo_path: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultGitHistoryEntry {
+    pub commit_id: String,
+    pub reason: String,
+    pub summary: String,
+    pub author: String,
+    pub created_at: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultGitDiff {
+    pub commit_id: String,
+    pub parent_commit_id: Option<String>,
+    pub unified_diff: String,
+    pub can_restore: bool,
+    pub blocked_reason: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultGitRestoreResult {
+    pub commit_id: String,
+    pub snapshot: String,
+}
+
+/// Paths reported by the file watcher since the last auto-commit. Own writes only decide how a
+/// commit is attributed; the commit itself always stages the whole vault.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PendingVaultChanges {
+    pub own: BTreeSet<String>,
+    pub external: BTreeSet<String>,
+}
+
+#[derive(Default)]
+struct AutoCommitQueue {
+    vault_root: Option<PathBuf>,
+    changes: PendingVaultChanges,
+}
+
+/// Collects watcher events for the auto-commit task, which commits once
+/// `AUTO_COMMIT_DEBOUNCE` passes without a newer event.
+#[derive(Default)]
+pub struct VaultGitState {
+    queue: Mutex<AutoCommitQueue>,
+    changed: Notify,
+}
+
+impl VaultGitState {
+    /// Arms auto-commits for `vault_root`, or disarms them with `None`. Only called for a vault
+    /// whose status has auto-commits on, so an armed root is what "on" means below.
+    pub fn set_vault_root(&self, vault_root: Option<PathBuf>) {
+        let mut queue = self.queue.lock().unwrap();
+        if queue.vault_root != vault_root {
+            queue.changes = PendingVaultChanges::default();
+        }
+        queue.vault_root = vault_root;
+    }
+
+    pub fn vault_root(&self) -> Option<PathBuf> {
+        self.queue.lock().unwrap().vault_root.clone()
+    }
+
+    /// Queues a changed path and wakes the auto-commit task. Returns `false` when no vault is
+    /// armed or the path belongs to the repository itself.
+    pub fn record(&self, relative_path: &str, own_write: bool) -> bool {
+        let normalized = relative_path.replace('\\', "/");
+        let mut queue = self.queue.lock().unwrap();
+        if queue.vault_root.is_none() || is_git_internal_path(&normalized) {
+            return false;
+        }
+        if own_write {
+            queue.changes.own.insert(normalized);
+        } else {
+            queue.changes.external.insert(normalized);
+        }
+        self.changed.notify_one();
+        true
+    }
+
+    /// Moves paths the app wrote itself from external to own. The watcher may report the first
+    /// files of a long write before the caller knows everything it touched.
+    pub fn claim_own_writes(&self, relative_paths: &[String]) {
+        let mut queue = self.queue.lock().unwrap();
+        for path in relative_paths {
+            let normalized = path.replace('\\', "/");
+            if queue.changes.external.remove(&normalized) {
+                queue.changes.own.insert(normalized);
+            }
+        }
+    }
+
+    /// Takes everything queued so far, or `None` when nothing is or no vault is armed.
+    pub fn take_pending(&self) -> Option<(PathBuf, PendingVaultChanges)> {
+        let mut queue = self.queue.lock().unwrap();
+        let vault_root = queue.vault_root.clone()?;
+        if queue.changes == PendingVaultChanges::default() {
+            return None;
+        }
+        Some((vault_root, std::mem::take(&mut queue.changes)))
+    }
+
+    /// Waits for a change, then until `AUTO_COMMIT_DEBOUNCE` passes without another one.
+    pub async fn settled(&self) {
+        self.changed.notified().await;
+        while tokio::time::timeout(AUTO_COMMIT_DEBOUNCE, self.changed.notified())
+            .await
+            .is_ok()
+        {}
+    }
+}
+
+pub fn vault_git_status(vault_root: &str) -> Result<VaultGitStatus, String> {
+    let vault_path = vault_path(vault_root)?;
+    let repo = match Repository::discover(&vault_path) {
+        Ok(repo) => repo,
+        Err(err) if err.code() == ErrorCode::NotFound => {
+            return Ok(VaultGitStatus {
+                mode: VaultGitMode::None,
+                auto_commit: false,
+                repo_path: None,
+            })
+        }
+        Err(err) => return Err(err.to_string()),
+    };
+    let mode = repo_mode(&repo, &vault_path)?;
+
+    Ok(VaultGitStatus {
+        mode,
+        auto_commit: mode == VaultGitMode::Managed && config_flag(&repo, AUTO_COMMIT_CONFIG_KEY)?,
+        repo_path: repo
+            .workdir()
+            .map(|workdir| workdir.to_string_lossy().trim_end_matches('/').to_string()),
+    })
+}
+
+/// Turns auto-commits on or off. Enabling creates a Philo-managed repository with a baseline
+/// commit when the vault has none; a repository the user manages is never written to.
+pub fn set_vault_git_enabled(input: SetVaultGitEnabledInput) -> Result<VaultGitStatus, String> {
+    let status = vault_git_status(&input.vault_root)?;
+    match (status.mode, input.enabled) {
+        (VaultGitMode::User, true) => {
+            return Err("This vault is already in a git repository you manage.".to_string())
+        }
+        (VaultGitMode::User, false) | (VaultGitMode::None, false) => return Ok(status),
+        (VaultGitMode::None, true) => {
+            let vault_path = vault_path(&input.vault_root)?;
+            let repo = Repository::init(&vault_path).map_err(|e| e.to_string())?;
+            let info_dir = repo.path().join("info");
+            fs::create_dir_all(&info_dir).map_err(|e| e.to_string())?;
+            fs::write(info_dir.join("exclude"), DEFAULT_EXCLUDES).map_err(|e| e.to_string())?;
+            set_config_flag(&repo, MANAGED_CONFIG_KEY, true)?;
+            set_config_flag(&repo, AUTO_COMMIT_CONFIG_KEY, true)?;
+            commit_vault(&repo, "import", "Vault", HISTORY_AUTHOR_NAME, true)?;
+        }
+        (VaultGitMode::Managed, enabled) => {
+            let repo =
+                Repository::open(vault_path(&input.vault_root)?).map_err(|e| e.to_string())?;
+            set_config_flag(&repo, AUTO_COMMIT_CONFIG_KEY, enabled)?;
+        }
+    }
+
+    vault_git_status(&input.vault_root)
+}
+
+/// Commits everything that changed in a managed vault. Returns the new commit id, or `None`
+/// when auto-commits are off or the tree is unchanged.
+pub fn commit_vault_changes(
+    vault_root: &Path,
+    changes: &PendingVaultChanges,
+) -> Result<Option<String>, String> {
+    let status = vault_git_status(&vault_root.to_string_lossy())?;
+    if !status.auto_commit {
+        return Ok(None);
+    }
+
+    let repo = Repository::open(vault_root).map_err(|e| e.to_string())?;
+    let (reason, author) = if changes.external.is_empty() && !changes.own.is_empty() {
+        ("edit", HISTORY_AUTHOR_NAME)
+    } else {
+        ("external", EXTERNAL_AUTHOR_NAME)
+    };
+    let changed: BTreeSet<&String> = changes.own.iter().chain(&changes.external).collect();
+    let summary = match changed.len() {
+        0 => "Vault".to_string(),
+        1 => changed.iter().next().unwrap().to_string(),
+        count => format!("{count} files"),
+    };
+
+    commit_vault(&repo, reason, &summary, author, false)
+}
+
+pub fn list_vault_git_history(
+    input: VaultGitHistoryInput,
+) -> Result<Vec<VaultGitHistoryEntry>, String> {
+    let Some((repo, relative_path)) = open_note_repo(&input.vault_root, &input.relative_note_path)?
+    else {
+        return Ok(Vec::new());
+    };
+    let Some(head_oid) = head_oid(&repo)? else {
+        return Ok(Vec::new());
+    };
+
+    let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
+    revwalk.push(head_oid).map_err(|e| e.to_string())?;
+    let mut entries = Vec::new();
+
+    for oid in revwalk {
+        let oid = oid.map_err(|e| e.to_string())?;
+        let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+        if !commit_touches_path(&repo, &commit, &relative_path)? {
+            continue;
+        }
+        let (reason, summary) = parse_commit_summary(commit.summary().unwrap_or_default());
+        entries.push(VaultGitHistoryEntry {
+            commit_id: oid.to_string(),
+            reason,
+            summary,
+            author: commit.author().name().unwrap_or_default().to_string(),
+            created_at: commit_timestamp(&commit),
+        });
+    }
+
+    Ok(entries)
+}
+
+pub fn get_vault_git_diff(input: VaultGitDiffInput) -> Result<VaultGitDiff, String> {
+    let (repo, relative_path) = open_note_repo(&input.vault_root, &input.relative_note_path)?
+        .ok_or_else(|| "Vault has no history.".to_string())?;
+    let oid = Oid::from_str(input.commit_id.trim()).map_err(|e| e.to_string())?;
+    let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+    let parent_commit = commit.parent(0).ok();
+    let before = parent_commit
+        .as_ref()
+        .map(|parent| blob_content_at_commit(&repo, parent.id(), &relative_path))
+        .transpose()?
+        .flatten()
+        .unwrap_or_default();
+    let after = blob_content_at_commit(&repo, commit.id(), &relative_path)?;
+    let current = note_on_disk(&input.vault_root, &input.relative_note_path)?;
+    let blocked_reason = match (&after, &current) {
+        (None, _) => Some("The note was deleted in this version.".to_string()),
+        (Some(after), Some(current)) if after == current => {
+            Some("Already using this version.".to_string())
+        }
+        _ => None,
+    };
+
+    Ok(VaultGitDiff {
+        commit_id: commit.id().to_string(),
+        parent_commit_id: parent_commit.map(|parent| parent.id().to_string()),
+        unified_diff: build_unified_diff(&before, after.as_deref().unwrap_or_default()),
+        can_restore: blocked_reason.is_none(),
+        blocked_reason,
+    })
+}
+
+pub fn restore_vault_git_revision(
+    input: RestoreVaultGitRevisionInput,
+) -> Result<VaultGitRestoreResult, String> {
+    let (repo, relative_path) = open_note_repo(&input.vault_root, &input.relative_note_path)?
+        .ok_or_else(|| "Vault has no history.".to_string())?;
+    let oid = Oid::from_str(input.commit_id.trim()).map_err(|e| e.to_string())?;
+    let snapshot = blob_content_at_commit(&repo, oid, &relative_path)?
+        .ok_or_else(|| "Snapshot not found for note revision.".to_string())?;
+
+    Ok(VaultGitRestoreResult {
+        commit_id: oid.to_string(),
+        snapshot,
+    })
+}
+
+fn vault_path(vault_root: &str) -> Result<PathBuf, String> {
+    let trimmed = vault_root.trim();
+    if trimmed.is_empty() {
+        return Err("Vault root is required.".to_string());
+    }
+    let path = Path::new(trimmed);
+    Ok(fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()))
+}
+
+fn normalize_relative_note_path(value: &str) -> Result<PathBuf, String> {
+    let trimmed = value.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        return Err("Note path is required.".to_string());
+    }
+    let candidate = Path::new(trimmed);
+    if candidate.is_absolute() {
+        return Err("Note path must be relative.".to_string());
+    }
+    if !candidate
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("md"))
+        .unwrap_or(false)
+    {
+        return Err("Note path must end with .md.".to_string());
+    }
+
+    let mut normalized = PathBuf::new();
+    for component in candidate.components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => continue,
+            Component::ParentDir | Component::Prefix(_) | Component::RootDir => {
+                return Err("Note path is invalid.".to_string())
+            }
+        }
+    }
+
+    Ok(normalized)
+}
+
+fn is_git_internal_path(relative_path: &str) -> bool {
+    relative_path == ".git" || relative_path.starts_with(".git/")
+}
+
+fn repo_mode(repo: &Repository, vault_path: &Path) -> Result<VaultGitMode, String> {
+    let at_vault_root = repo
+        .workdir()
+        .and_then(|workdir| fs::canonicalize(workdir).ok())
+        .is_some_and(|workdir| workdir == vault_path);
+    if at_vault_root && config_flag(repo, MANAGED_CONFIG_KEY)? {
+        Ok(VaultGitMode::Managed)
+    } else {
+        Ok(VaultGitMode::User)
+    }
+}
+
+fn config_flag(repo: &Repository, key: &str) -> Result<bool, String> {
+    let config = repo.config().map_err(|e| e.to_string())?;
+    match config.get_bool(key) {
+        Ok(value) => Ok(value),
+        Err(err) if err.code() == ErrorCode::NotFound => Ok(false),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+fn set_config_flag(repo: &Repository, key: &str, value: bool) -> Result<(), String> {
+    let mut config = repo
+        .config()
+        .and_then(|config| config.open_level(ConfigLevel::Local))
+        .map_err(|e| e.to_string())?;
+    config.set_bool(key, value).map_err(|e| e.to_string())
+}
+
+/// Opens whichever repository holds the vault and maps the note path to a path inside it, so
+/// a user repository rooted above the vault still resolves.
+fn open_note_repo(
+    vault_root: &str,
+    relative_note_path: &str,
+) -> Result<Option<(Repository, PathBuf)>, String> {
+    let vault_path = vault_path(vault_root)?;
+    let relative_path = normalize_relative_note_path(relative_note_path)?;
+    let repo = match Repository::discover(&vault_path) {
+        Ok(repo) => repo,
+        Err(err) if err.code() == ErrorCode::NotFound => return Ok(None),
+        Err(err) => return Err(err.to_string()),
+    };
+    let workdir = repo
+        .workdir()
+        .and_then(|workdir| fs::canonicalize(workdir).ok())
+        .ok_or_else(|| "Vault repository is bare.".to_string())?;
+    let prefix = vault_path
+        .strip_prefix(&workdir)
+        .map_err(|_| "Vault is outside its repository.".to_string())?;
+
+    Ok(Some((repo, prefix.join(relative_path))))
+}
+
+fn note_on_disk(vault_root: &str, relative_note_path: &str) -> Result<Option<String>, String> {
+    let path = vault_path(vault_root)?.join(normalize_relative_note_path(relative_note_path)?);
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+fn head_oid(repo: &Repository) -> Result<Option<Oid>, String> {
+    match repo.head() {
+        Ok(head) => Ok(head.target()),
+        Err(err) if err.code() == ErrorCode::UnbornBranch => Ok(None),
+        Err(err) if err.code() == ErrorCode::NotFound => Ok(None),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+fn commit_vault(
+    repo: &Repository,
+    reason: &str,
+    summary: &str,
+    author_name: &str,
+    allow_empty: bool,
+) -> Result<Option<String>, String> {
+    let mut index = repo.index().map_err(|e| e.to_string())?;
+    index
+        .add_all(["*"].iter(), IndexAddOption::DEFAULT, None)
+        .map_err(|e| e.to_string())?;
+    index
+        .update_all(["*"].iter(), None)
+        .map_err(|e| e.to_string())?;
+    index.write().map_err(|e| e.to_string())?;
+    let tree_id = index.write_tree().map_err(|e| e.to_string())?;
+    let tree = repo.find_tree(tree_id).map_err(|e| e.to_string())?;
+
+    let parent = head_oid(repo)?
+        .map(|oid| repo.find_commit(oid))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    if !allow_empty
+        && parent
+            .as_ref()
+            .is_some_and(|parent| parent.tree_id() == tree_id)
+    {
+        return Ok(None);
+    }
+
+    let author = Signature::now(author_name, HISTORY_AUTHOR_EMAIL).map_err(|e| e.to_string())?;
+    let committer =
+        Signature::now(HISTORY_AUTHOR_NAME, HISTORY_AUTHOR_EMAIL).map_err(|e| e.to_string())?;
+    let message = format!("vault:{reason} {summary}");
+    let parents: Vec<&git2::Commit<'_>> = parent.iter().collect();
+    let oid = repo
+        .commit(Some("HEAD"), &author, &committer, &message, &tree, &parents)
+        .map_err(|e| e.to_string())?;
+
+    Ok(Some(oid.to_string()))
+}
+
+fn parse_commit_summary(summary: &str) -> (String, String) {
+    let trimmed = summary.trim();
+    if let Some(rest) = trimmed.strip_prefix("vault:") {
+        let mut parts = rest.splitn(2, ' ');
+        let reason = parts.next().unwrap_or_default().trim();
+        let summary = parts.next().unwrap_or_default().trim();
+        return (
+            if reason.is_empty() { "update" } else { reason }.to_string(),
+            summary.to_string(),
+        );
+    }
+    // Commits made outside Philo, e.g. in a user-managed repository.
+    ("commit".to_string(), trimmed.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_vault(label: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        let nonce = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        path.push(format!("philo-vault-git-{label}-{nonce}"));
+        fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    fn history(vault: &Path, note: &str) -> Vec<VaultGitHistoryEntry> {
+        list_vault_git_history(VaultGitHistoryInput {
+            vault_root: vault.to_string_lossy().to_string(),
+            relative_note_path: note.to_string(),
+        })
+        .unwrap()
+    }
+
+    fn enable(vault: &Path) -> VaultGitStatus {
+        set_vault_git_enabled(SetVaultGitEnabledInput {
+            vault_root: vault.to_string_lossy().to_string(),
+            enabled: true,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn enabling_creates_managed_repo_with_baseline() {
+        let vault = temp_vault("enable");
+        fs::create_dir_all(vault.join("notes")).unwrap();
+        fs::write(vault.join("notes/a.md"), "# A\n").unwrap();
+
+        let status = enable(&vault);
+        assert_eq!(status.mode, VaultGitMode::Managed);
+        assert!(status.auto_commit);
+
+        let entries = history(&vault, "notes/a.md");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].reason, "import");
+
+        let _ = fs::remove_dir_all(&vault);
+    }
+
+    #[test]
+    fn auto_commits_attribute_own_and_external_writes() {
+        let vault = temp_vault("attribution");
+        fs::write(vault.join("a.md"), "one\n").unwrap();
+        enable(&vault);
+
+        fs::write(vault.join("a.md"), "two\n").unwrap();
+        let own = PendingVaultChanges {
+            own: BTreeSet::from(["a.md".to_string()]),
+            external: BTreeSet::new(),
+        };
+        assert!(commit_vault_changes(&vault, &own).unwrap().is_some());
+        assert!(commit_vault_changes(&vault, &own).unwrap().is_none());
+
+        fs::write(vault.join("a.md"), "three\n").unwrap();
+        commit_vault_changes(&vault, &PendingVaultChanges::default()).unwrap();
+
+        let entries = history(&vault, "a.md");
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].reason, "external");
+        assert_eq!(entries[0].author, EXTERNAL_AUTHOR_NAME);
+        assert_eq!(entries[1].reason, "edit");
+        assert_eq!(entries[1].author, HISTORY_AUTHOR_NAME);
+
+        let vault_root = vault.to_string_lossy().to_string();
+        let diff = get_vault_git_diff(VaultGitDiffInput {
+            vault_root: vault_root.clone(),
+            relative_note_path: "a.md".to_string(),
+            commit_id: entries[1].commit_id.clone(),
+        })
+        .unwrap();
+        assert!(diff.unified_diff.contains("-one"));
+        assert!(diff.unified_diff.contains("+two"));
+        assert!(diff.can_restore);
+
+        let restored = restore_vault_git_revision(RestoreVaultGitRevisionInput {
+            vault_root,
+            relative_note_path: "a.md".to_string(),
+            commit_id: entries[2].commit_id.clone(),
+        })
+        .unwrap();
+        assert_eq!(restored.snapshot, "one\n");
+
+        let _ = fs::remove_dir_all(&vault);
+    }
+
+    #[test]
+    fn leaves_user_managed_repo_alone() {
+        let root = temp_vault("user");
+        let repo = Repository::init(&root).unwrap();
+        let vault = root.join("vault");
+        fs::create_dir_all(&vault).unwrap();
+        fs::write(vault.join("a.md"), "mine\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("vault/a.md")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("Me", "me@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Add a", &tree, &[])
+            .unwrap();
+
+        let status = vault_git_status(&vault.to_string_lossy()).unwrap();
+        assert_eq!(status.mode, VaultGitMode::User);
+        assert!(set_vault_git_enabled(SetVaultGitEnabledInput {
+            vault_root: vault.to_string_lossy().to_string(),
+            enabled: true,
+        })
+        .is_err());
+
+        fs::write(vault.join("a.md"), "changed\n").unwrap();
+        assert!(
+            commit_vault_changes(&vault, &PendingVaultChanges::default())
+                .unwrap()
+                .is_none()
+        );
+
+        let entries = history(&vault, "a.md");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].summary, "Add a");
+        assert!(!vault.join(".git").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn queues_changes_until_taken() {
+        let state = VaultGitState::default();
+        assert!(!state.record("a.md", false));
+
+        state.set_vault_root(Some(PathBuf::from("/vault")));
+        assert!(state.record("a.md", true));
+        assert!(state.record("b.md", false));
+        assert!(!state.record(".git/index", false));
+
+        let (root, changes) = state.take_pending().unwrap();
+        assert_eq!(root, PathBuf::from("/vault"));
+        assert!(changes.own.contains("a.md"));
+        assert!(changes.external.contains("b.md"));
+        assert!(state.take_pending().is_none());
+
+        state.record("c.md", false);
+        state.record("d.md", false);
+        state.claim_own_writes(&["c.md".to_string()]);
+        let (_, changes) = state.take_pending().unwrap();
+        assert_eq!(changes.own, BTreeSet::from(["c.md".to_string()]));
+        assert_eq!(changes.external, BTreeSet::from(["d.md".to_string()]));
+
+        state.record("c.md", false);
+        state.set_vault_root(None);
+        assert!(state.take_pending().is_none());
+    }
+}
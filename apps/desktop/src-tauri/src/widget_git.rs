@@ -320,7 +320,7 @@ fn tracked_blob_content(repo: &Repository, relative_path: &Path) -> Result<Optio
     blob_content_at_commit(repo, head_oid, relative_path)
 }
 
-fn blob_content_at_commit(
+pub(crate) fn blob_content_at_commit(
     repo: &Repository,
     commit_id: Oid,
     relative_path: &Path,
@@ -412,7 +412,7 @@ fn parse_commit_summary(summary: &str) -> (String, String) {
     ("update".to_string(), normalize_title(trimmed))
 }
 
-fn commit_touches_path(
+pub(crate) fn commit_touches_path(
     repo: &Repository,
     commit: &git2::Commit<'_>,
     relative_path: &Path,
@@ -450,7 +450,7 @@ fn commit_touches_path(
     Ok(false)
 }
 
-fn commit_timestamp(commit: &git2::Commit<'_>) -> String {
+pub(crate) fn commit_timestamp(commit: &git2::Commit<'_>) -> String {
     let seconds = commit.time().seconds();
     let system_time = if seconds >= 0 {
         UNIX_EPOCH + Duration::from_secs(seconds as u64)
@@ -463,7 +463,7 @@ fn commit_timestamp(commit: &git2::Commit<'_>) -> String {
     datetime.to_rfc3339()
 }
 
-fn build_unified_diff(before: &str, after: &str) -> String {
+pub(crate) fn build_unified_diff(before: &str, after: &str) -> String {
     TextDiff::from_lines(before, after)
         .unified_diff()
         .context_radius(3)
@@ -99,6 +99,7 @@ import {
   type UpdateInfo,
 } from "../../services/updater";
 import { isUrlSummaryPage, } from "../../services/url-summary";
+import { openVaultGitHistory, } from "../../services/vault-git-history";
 import { createWidgetFile, } from "../../services/widget-files";
 import { recordWidgetGitRevision, } from "../../services/widget-git-history";
 import { stringifyStorageSchema, } from "../../services/widget-storage";
@@ -2712,6 +2713,7 @@ export default function AppLayout() {
         const hasJournalConfig = !!settings.journalDir || !!settings.vaultDir;
         if (settings.hasCompletedOnboarding || hasJournalConfig) {
           await initJournalScope();
+          void openVaultGitHistory().catch(console.error,);
           setIsConfigured(true,);
         } else {
           setOnboardingOpen(true,);
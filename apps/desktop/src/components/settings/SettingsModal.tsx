@@ -1,10 +1,15 @@
-import { invoke, } from "@tauri-apps/api/core";
 import { join, } from "@tauri-apps/api/path";
 import { getCurrentWindow, } from "@tauri-apps/api/window";
 import { open as openDialog, } from "@tauri-apps/plugin-dialog";
 import { AlertTriangle, ArrowUpDown, Check, RefreshCw, X, } from "lucide-react";
 import { useEffect, useRef, useState, } from "react";
-import { connectGoogleAccount, disconnectGoogleAccount, isGoogleAccountConnected, } from "../../services/google";
+import { connectCaldavAccount, disconnectCaldavAccount, } from "../../services/caldav";
+import {
+  connectGoogleAccount,
+  disconnectGoogleAccount,
+  isGoogleAccountConnected,
+  listGoogleSessionAccounts,
+} from "../../services/google";
 import { detectObsidianFolders, isWithinObsidianVault, } from "../../services/obsidian";
 import { applyFilenamePattern, getJournalDir, initJournalScope, resetJournalDir, } from "../../services/paths";
 import {
@@ -39,6 +44,8 @@ import {
   requestDesktopSyncMagicLink,
   syncDesktopNow,
 } from "../../services/sync";
+import { listNoteTemplates, } from "../../services/templates";
+import { openVaultGitHistory, } from "../../services/vault-git-history";
 import { getToday, } from "../../types/note";
 import { VaultPathMarquee, } from "../shared/VaultPathMarquee";
 import { SpokenLanguagesField, } from "./SpokenLanguagesField";
@@ -448,7 +455,11 @@ export function SettingsModal({ open, initialTab = "ai", onClose, }: SettingsMod
   >(null,);
   const [googleError, setGoogleError,] = useState("",);
   const [googleSessionAccounts, setGoogleSessionAccounts,] = useState<null | string[]>(null,);
+  const [caldavForm, setCaldavForm,] = useState({ serverUrl: "", username: "", password: "", },);
+  const [caldavAction, setCaldavAction,] = useState<null | "connecting" | { disconnecting: string; }>(null,);
+  const [caldavError, setCaldavError,] = useState("",);
   const [isObsidianVault, setIsObsidianVault,] = useState(false,);
+  const [noteTemplates, setNoteTemplates,] = useState<string[]>([],);
   const [syncAction, setSyncAction,] = useState<null | "sending_link" | "syncing" | "disconnecting">(null,);
   const [syncNotice, setSyncNotice,] = useState("",);
   const inputRef = useRef<HTMLInputElement>(null,);
@@ -480,6 +491,7 @@ export function SettingsModal({ open, initialTab = "ai", onClose, }: SettingsMod
       },);
       // Resolve the default journal dir for display
       getJournalDir().then(setDefaultJournalDir,);
+      listNoteTemplates().then(setNoteTemplates,);
       setTimeout(() => inputRef.current?.focus(), 100,);
     }
   }, [open, initialTab,],);
@@ -520,7 +532,7 @@ export function SettingsModal({ open, initialTab = "ai", onClose, }: SettingsMod
     if (!open || !settings) return;
 
     let cancelled = false;
-    invoke<string[]>("list_google_oauth_session_accounts",)
+    listGoogleSessionAccounts()
       .then((accounts,) => {
         if (!cancelled) {
           setGoogleSessionAccounts(accounts.map((account,) => account.trim().toLowerCase()),);
@@ -600,6 +612,7 @@ export function SettingsModal({ open, initialTab = "ai", onClose, }: SettingsMod
       try {
         await resetJournalDir(next.journalDir || undefined,);
         await initJournalScope();
+        await openVaultGitHistory();
       } catch (err) {
         console.error("Failed to refresh journal scope:", err,);
       }
@@ -796,6 +809,46 @@ export function SettingsModal({ open, initialTab = "ai", onClose, }: SettingsMod
     }
   };
 
+  const applyCaldavAccounts = (caldavAccounts: Settings["caldavAccounts"],) => {
+    const currentDraft = settingsRef.current;
+    if (!currentDraft) return;
+    const nextDraft: Settings = { ...currentDraft, caldavAccounts, };
+    settingsRef.current = nextDraft;
+    if (lastSavedSettingsRef.current) {
+      lastSavedSettingsRef.current = { ...lastSavedSettingsRef.current, caldavAccounts, };
+    }
+    setSettings(nextDraft,);
+  };
+
+  const handleConnectCaldav = async () => {
+    setCaldavAction("connecting",);
+    setCaldavError("",);
+    try {
+      const account = await connectCaldavAccount(caldavForm,);
+      const current = settingsRef.current?.caldavAccounts ?? [];
+      applyCaldavAccounts([...current.filter((entry,) => entry.id !== account.id), account,],);
+      setCaldavForm({ serverUrl: "", username: "", password: "", },);
+    } catch (err) {
+      setCaldavError(getErrorMessage(err, "Failed to connect CalDAV account.",),);
+    } finally {
+      setCaldavAction(null,);
+    }
+  };
+
+  const handleDisconnectCaldav = async (accountId: string,) => {
+    setCaldavAction({ disconnecting: accountId, },);
+    setCaldavError("",);
+    try {
+      const persisted = await loadSettings();
+      await disconnectCaldavAccount(persisted, accountId,);
+      applyCaldavAccounts((settingsRef.current?.caldavAccounts ?? []).filter((entry,) => entry.id !== accountId),);
+    } catch (err) {
+      setCaldavError(getErrorMessage(err, "Failed to disconnect CalDAV account.",),);
+    } finally {
+      setCaldavAction(null,);
+    }
+  };
+
   const cancelGoogleConnect = () => {
     const controller = googleAbortControllerRef.current;
     if (!controller) return;
@@ -1471,6 +1524,82 @@ export function SettingsModal({ open, initialTab = "ai", onClose, }: SettingsMod
 
             <div className="my-5 border-t border-gray-100" />
 
+            <div className="space-y-3">
+              <label className="block text-sm text-gray-600" style={mono}>
+                CalDAV calendars
+              </label>
+              {settings.caldavAccounts.length > 0
+                ? (
+                  <div className="space-y-2">
+                    {settings.caldavAccounts.map((account,) => (
+                      <div
+                        key={account.id}
+                        className="flex items-center justify-between gap-2 border border-gray-200 px-3 py-2 text-xs text-gray-600"
+                        style={mono}
+                      >
+                        <span className="truncate">{account.id}</span>
+                        <button
+                          onClick={() => void handleDisconnectCaldav(account.id,)}
+                          disabled={caldavAction !== null}
+                          className="inline-flex h-7 w-7 shrink-0 items-center justify-center rounded-none text-gray-400 transition-colors cursor-pointer hover:bg-gray-100 hover:text-gray-600 focus:outline-none focus:ring-2 focus:ring-gray-300/40 disabled:cursor-default disabled:opacity-60"
+                          title={`Disconnect ${account.id}`}
+                          aria-label={`Disconnect ${account.id}`}
+                        >
+                          <X className="h-3.5 w-3.5" strokeWidth={2} />
+                        </button>
+                      </div>
+                    ))}
+                  </div>
+                )
+                : (
+                  <p className="text-xs text-gray-500" style={mono}>
+                    No CalDAV account connected yet. iCloud, Fastmail, and Nextcloud work with an app password.
+                  </p>
+                )}
+              <div className="grid gap-2 md:grid-cols-3">
+                <input
+                  type="text"
+                  value={caldavForm.serverUrl}
+                  onChange={(e,) => setCaldavForm((form,) => ({ ...form, serverUrl: e.target.value, }))}
+                  placeholder="caldav.icloud.com"
+                  className="w-full border border-gray-200 bg-white px-3 py-2 text-sm transition-all focus:border-violet-400 focus:outline-none focus:ring-2 focus:ring-violet-500/30"
+                  style={mono}
+                />
+                <input
+                  type="text"
+                  value={caldavForm.username}
+                  onChange={(e,) => setCaldavForm((form,) => ({ ...form, username: e.target.value, }))}
+                  placeholder="Username"
+                  className="w-full border border-gray-200 bg-white px-3 py-2 text-sm transition-all focus:border-violet-400 focus:outline-none focus:ring-2 focus:ring-violet-500/30"
+                  style={mono}
+                />
+                <input
+                  type="password"
+                  value={caldavForm.password}
+                  onChange={(e,) => setCaldavForm((form,) => ({ ...form, password: e.target.value, }))}
+                  placeholder="App password"
+                  className="w-full border border-gray-200 bg-white px-3 py-2 text-sm transition-all focus:border-violet-400 focus:outline-none focus:ring-2 focus:ring-violet-500/30"
+                  style={mono}
+                />
+              </div>
+              {caldavError && (
+                <p className="text-xs text-red-600" style={mono}>
+                  {caldavError}
+                </p>
+              )}
+              <button
+                onClick={() => void handleConnectCaldav()}
+                disabled={caldavAction !== null || !caldavForm.serverUrl.trim() || !caldavForm.username.trim()
+                  || !caldavForm.password}
+                className="rounded-none border border-gray-200 px-3 py-2 text-sm text-gray-700 transition-colors cursor-pointer hover:bg-gray-50 disabled:cursor-default disabled:opacity-60"
+                style={mono}
+              >
+                {caldavAction === "connecting" ? "Connecting..." : "Connect CalDAV"}
+              </button>
+            </div>
+
+            <div className="my-5 border-t border-gray-100" />
+
             <div className="space-y-3">
               <label className="block text-sm text-gray-600" style={mono}>
                 Local-cloud sync
@@ -1643,6 +1772,49 @@ export function SettingsModal({ open, initialTab = "ai", onClose, }: SettingsMod
                   />
                 </div>
               </div>
+              <div className="grid gap-3 lg:grid-cols-3">
+                <div className="space-y-2 lg:col-span-1">
+                  <label className="block text-sm text-gray-600 pt-2" style={mono}>
+                    Templates folder (optional)
+                  </label>
+                  <input
+                    type="text"
+                    value={settings.templatesFolder}
+                    onChange={(e,) => update({ templatesFolder: e.target.value, },)}
+                    placeholder="templates"
+                    className="w-full px-3 py-2 border border-gray-200 rounded-none text-sm focus:outline-none focus:ring-2 focus:ring-violet-500/30 focus:border-violet-400 transition-all"
+                    style={mono}
+                  />
+                </div>
+                <div className="space-y-2 lg:col-span-1">
+                  <label className="block text-sm text-gray-600 pt-2" style={mono}>
+                    Daily note template
+                  </label>
+                  <select
+                    value={settings.dailyNoteTemplate}
+                    onChange={(e,) => update({ dailyNoteTemplate: e.target.value, },)}
+                    className="w-full px-3 py-2 border border-gray-200 rounded-none text-sm bg-white focus:outline-none focus:ring-2 focus:ring-violet-500/30 focus:border-violet-400 transition-all"
+                    style={mono}
+                  >
+                    <option value="">None</option>
+                    {noteTemplates.map((name,) => <option key={name} value={name}>{name}</option>)}
+                  </select>
+                </div>
+                <div className="space-y-2 lg:col-span-1">
+                  <label className="block text-sm text-gray-600 pt-2" style={mono}>
+                    Page template
+                  </label>
+                  <select
+                    value={settings.pageTemplate}
+                    onChange={(e,) => update({ pageTemplate: e.target.value, },)}
+                    className="w-full px-3 py-2 border border-gray-200 rounded-none text-sm bg-white focus:outline-none focus:ring-2 focus:ring-violet-500/30 focus:border-violet-400 transition-all"
+                    style={mono}
+                  >
+                    <option value="">None</option>
+                    {noteTemplates.map((name,) => <option key={name} value={name}>{name}</option>)}
+                  </select>
+                </div>
+              </div>
               <div className="space-y-2 pt-2">
                 <label className="block text-sm text-gray-600" style={mono}>
                   Widget Git history
@@ -193,6 +193,12 @@ Rules:
 - If \`conversationHistory\` is present in the request, treat it as the current chat thread and answer follow-up questions in that context.
 - For information requests, search first and only read the most relevant notes.
 - For week-based or contiguous date-range questions, use \`run_philo\` with \`note read-range --from YYYY-MM-DD --to YYYY-MM-DD --json\`.
+- To review a whole week, month or quarter, \`note rollup --week YYYY-Www --dry-run --json\` (or \`--month YYYY-MM\`, \`--quarter YYYY-Qn\`) summarizes completed tasks, meetings and open items from its daily notes.
+- New notes and pages can start from a template in the templates folder: \`template list --json\` lists them and \`note create --date YYYY-MM-DD --template NAME\` or \`page create --title TITLE --template NAME\` applies one.
+- Page frontmatter is checked against its type (meeting, person, project, link or a user-defined type). \`page read\` reports problems under \`issues\`, \`page lint --json\` (optionally \`--type TYPE\`) checks every page, and \`page update\` rejects frontmatter that breaks the schema. A frontmatter block in \`page update\` input only sets the keys it lists; add \`--replace-frontmatter\` to also drop the keys it leaves out.
+- \`lint --json\` checks every note and page for skipped heading levels, mixed bullet markers, trailing whitespace, broken wiki links, duplicate tasks and non-ISO dates (\`--rule NAME\` narrows it). \`lint --fix --dry-run --json\` previews the fixes as unified diffs; never run \`lint --fix --apply\` unless the user asks for it.
+- To bring in notes from Notion, Logseq or Bear, run \`import --from notion|logseq|bear --path EXPORT --dry-run --json\` and show the user the summary and conflicts first. Only run it with \`--apply\` once they confirm; existing notes are never overwritten.
+- To publish notes as a static website, run \`site export --out DIR --dry-run --json\` with any of \`--folder F\`, \`--tag T\` or \`--type T\` to pick notes (\`--private-field KEY\` hides extra frontmatter fields). Notes marked \`private: true\` or \`publish: false\` are never exported. Only use \`--apply\` once the user confirms the page list.
 - Interpret "last week" as ${temporal.lastWeekStart} through ${temporal.lastWeekEnd}, and "this week" as ${temporal.thisWeekStart} through ${temporal.thisWeekEnd}, unless the user gives a different date range.
 - Read at most 5 notes unless the user explicitly names dates or asks about a specific contiguous range like last week.
 - Cite note dates in your final answer when making claims.
@@ -391,7 +397,7 @@ async function executePhiloTool(
   },);
 
   if (output.code === 0) {
-    if (input.argv[0] === "note" && input.argv[1] === "read") {
+    if (input.argv[0] === "note" && input.argv[1] === "read" && input.argv.includes("--date",)) {
       addCitation(citations, noteEnvelopeSchema.parse(JSON.parse(output.stdout,),).note,);
     } else if (input.argv[0] === "note" && input.argv[1] === "read-range") {
       const parsed = noteRangeEnvelopeSchema.parse(JSON.parse(output.stdout,),);
@@ -0,0 +1,99 @@
+import { invoke, } from "@tauri-apps/api/core";
+import { type CaldavAccount, loadSettings, saveSettings, type Settings, } from "./settings";
+
+export interface CaldavCalendar {
+  href: string;
+  displayName: string;
+  color: string | null;
+  ctag: string | null;
+}
+
+export interface CaldavEventParticipant {
+  name: string | null;
+  email: string | null;
+}
+
+export interface CaldavEvent {
+  id: string;
+  trackingId: string;
+  calendarId: string;
+  uid: string;
+  name: string;
+  note: string;
+  location: string | null;
+  startDate: string;
+  endDate: string;
+  allDay: boolean;
+  eventUrl: string | null;
+  participants: CaldavEventParticipant[];
+  isRecurring: boolean;
+  cancelled: boolean;
+  etag: string;
+}
+
+export interface CaldavSyncResult {
+  events: CaldavEvent[];
+  added: number;
+  updated: number;
+  removed: number;
+  unchanged: number;
+}
+
+interface CaldavAccountResponse {
+  id: string;
+  serverUrl: string;
+  username: string;
+  calendars: CaldavCalendar[];
+}
+
+export function isCaldavAccountConnected(settings: Settings,) {
+  return settings.caldavAccounts.length > 0;
+}
+
+export async function connectCaldavAccount(input: {
+  serverUrl: string;
+  username: string;
+  password: string;
+},): Promise<CaldavAccount> {
+  const response = await invoke<CaldavAccountResponse>("connect_caldav_account", { input, },);
+  const account: CaldavAccount = {
+    id: response.id,
+    serverUrl: response.serverUrl,
+    username: response.username,
+    calendarHrefs: response.calendars.map((calendar,) => calendar.href),
+  };
+
+  const settings = await loadSettings();
+  await saveSettings({
+    ...settings,
+    caldavAccounts: [
+      ...settings.caldavAccounts.filter((entry,) => entry.id !== account.id),
+      account,
+    ],
+  },);
+  return account;
+}
+
+export async function disconnectCaldavAccount(settings: Settings, accountId: string,) {
+  await invoke("disconnect_caldav_account", { accountId, },);
+  await saveSettings({
+    ...settings,
+    caldavAccounts: settings.caldavAccounts.filter((entry,) => entry.id !== accountId),
+  },);
+}
+
+export async function listCaldavCalendars(accountId: string,) {
+  const accounts = await invoke<CaldavAccountResponse[]>("list_caldav_accounts",);
+  return accounts.find((account,) => account.id === accountId)?.calendars ?? [];
+}
+
+export async function syncCaldavEvents(account: CaldavAccount, from: string, to: string,) {
+  return await invoke<CaldavSyncResult>("sync_caldav_events", {
+    input: {
+      accountId: account.id,
+      from,
+      to,
+      calendarHrefs: account.calendarHrefs,
+    },
+  },);
+}
@@ -5,6 +5,7 @@ import { z, } from "zod";
 import { json2md, md2json, parseJsonContent, } from "../lib/markdown";
 import { getToday, } from "../types/note";
 import { getAiSdkModel, tauriStreamFetch, } from "./ai-sdk";
+import { type CaldavEvent, syncCaldavEvents, } from "./caldav";
 import { getGoogleAccessToken, } from "./google";
 import {
   createDateMention,
@@ -16,7 +17,7 @@ import {
   renderMentionMarkdown,
 } from "./mentions";
 import { getBaseDir, } from "./paths";
-import { type ActiveAiConfig, loadSettings, resolveActiveAiConfig, } from "./settings";
+import { type ActiveAiConfig, type CaldavAccount, loadSettings, resolveActiveAiConfig, } from "./settings";
 import { getOrCreateDailyNote, saveDailyNote, } from "./storage";
 
 const GOOGLE_IMPORT_STATE_FILE = "google-import-state.json";
@@ -868,6 +869,51 @@ async function syncCalendarAccount(
 ) {
   const accessToken = await getGoogleAccessToken(accountEmail,);
   const response = await fetchCalendarEvents(accessToken,);
+  await applyCalendarEvents(accountEmail, response.items ?? [], state, aiConfig, "Google Calendar",);
+}
+
+function caldavEventToCalendarEvent(account: CaldavAccount, event: CaldavEvent,): GoogleCalendarEvent {
+  const organizer = event.participants[0];
+  return {
+    etag: event.etag || undefined,
+    htmlLink: event.eventUrl ?? new URL(event.calendarId, account.serverUrl,).toString(),
+    iCalUID: event.uid,
+    id: event.trackingId,
+    location: event.location ?? undefined,
+    organizer: organizer
+      ? { displayName: organizer.name ?? undefined, email: organizer.email ?? undefined, }
+      : undefined,
+    start: event.allDay ? { date: event.startDate.slice(0, 10,), } : { dateTime: event.startDate, },
+    status: event.cancelled ? "cancelled" : "confirmed",
+    summary: event.name,
+  };
+}
+
+async function syncCaldavCalendarAccount(
+  account: CaldavAccount,
+  state: GoogleImportState,
+  aiConfig: ActiveAiConfig | null,
+) {
+  const now = new Date();
+  const result = await syncCaldavEvents(
+    account,
+    toLocalIsoDate(now,),
+    toLocalIsoDate(addDays(now, GOOGLE_CALENDAR_LOOKAHEAD_DAYS,),),
+  );
+  const nowMs = now.getTime();
+  const events = result.events
+    .filter((event,) => new Date(event.endDate,).getTime() >= nowMs)
+    .map((event,) => caldavEventToCalendarEvent(account, event,));
+  await applyCalendarEvents(account.id, events, state, aiConfig, "CalDAV",);
+}
+
+async function applyCalendarEvents(
+  accountEmail: string,
+  items: GoogleCalendarEvent[],
+  state: GoogleImportState,
+  aiConfig: ActiveAiConfig | null,
+  sourceChipLabel: string,
+) {
   const calendarCandidates: CalendarCandidate[] = [];
   const seenKeys = new Set<string>();
 
@@ -877,7 +923,7 @@ async function syncCalendarAccount(
     }
   },);
 
-  (response.items ?? []).forEach((event,) => {
+  items.forEach((event,) => {
     const eventId = event.id?.trim();
     const href = event.htmlLink?.trim();
     if (!eventId || !href) return;
@@ -927,7 +973,7 @@ async function syncCalendarAccount(
         renderAsTask: false,
         revision,
         sortKey: `${dueDate}:${timeText ?? "all-day"}:${eventId}:overview`,
-        sourceChipLabel: "Google Calendar",
+        sourceChipLabel,
         timeText,
       },
       dismissedRevision: state.records[overviewKey]?.dismissedRevision ?? null,
@@ -958,7 +1004,7 @@ async function syncCalendarAccount(
         renderAsTask: true,
         revision,
         sortKey: `${dueDate}:${timeText ?? "all-day"}:${eventId}:action`,
-        sourceChipLabel: "Google Calendar",
+        sourceChipLabel,
         timeText,
       },
       dismissedRevision: state.records[actionKey]?.dismissedRevision ?? null,
@@ -1323,7 +1369,8 @@ export async function syncGoogleImports(): Promise<boolean> {
   reconcileLocalActions(state, today, originalMarkdown,);
 
   const googleAccounts = [...settings.googleAccounts,];
-  if (googleAccounts.length === 0) {
+  const caldavAccounts = [...settings.caldavAccounts,];
+  if (googleAccounts.length === 0 && caldavAccounts.length === 0) {
     delete state.lastRenderedByDate[today];
     const nextMarkdown = replaceGoogleBlock(originalMarkdown, "",);
     if (nextMarkdown.trim() !== originalMarkdown.trim()) {
@@ -1362,7 +1409,20 @@ export async function syncGoogleImports(): Promise<boolean> {
     },),
   );
 
-  const { markdown: nextGoogleBlock, snapshot, } = buildGoogleBlock(state, googleAccounts.length,);
+  await Promise.all(
+    caldavAccounts.map(async (account,) => {
+      try {
+        await syncCaldavCalendarAccount(account, state, aiConfig,);
+      } catch (error) {
+        console.error(`CalDAV calendar sync failed for ${account.id}:`, error,);
+      }
+    },),
+  );
+
+  const { markdown: nextGoogleBlock, snapshot, } = buildGoogleBlock(
+    state,
+    googleAccounts.length + caldavAccounts.length,
+  );
   state.lastRenderedByDate[today] = snapshot;
 
   const nextMarkdown = replaceGoogleBlock(originalMarkdown, nextGoogleBlock,);
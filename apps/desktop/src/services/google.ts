@@ -1,9 +1,13 @@
-import { invoke, } from "@tauri-apps/api/core";
-import { openUrl, } from "@tauri-apps/plugin-opener";
+import {
+  authorizeOAuthAccount,
+  disconnectOAuthAccount,
+  ensureOAuthAccessToken,
+  listOAuthAccounts,
+  type OAuthLegacySessionInput,
+} from "./oauth";
 import { type GoogleAccount, loadSettings, saveSettings, type Settings, } from "./settings";
 
-const GOOGLE_AUTH_URL = "https://accounts.google.com/o/oauth2/v2/auth";
-const GOOGLE_CONNECT_TIMEOUT_MS = 180_000;
+const GOOGLE_OAUTH_PROVIDER = { providerId: "google", } as const;
 
 export const GOOGLE_CALENDAR_SCOPE = "https://www.googleapis.com/auth/calendar.events.readonly";
 export const GOOGLE_GMAIL_SCOPE = "https://www.googleapis.com/auth/gmail.readonly";
@@ -16,101 +20,10 @@ export const GOOGLE_ACCOUNT_SCOPES = [
   GOOGLE_GMAIL_SCOPE,
 ] as const;
 
-interface GoogleOAuthSession {
-  sessionId: string;
-  redirectUri: string;
-}
-
-interface GoogleOAuthCallback {
-  code?: string;
-  state?: string;
-  error?: string;
-}
-
-interface GoogleLegacySessionInput {
-  accessToken: string;
-  accessTokenExpiresAtMs: number;
-  refreshToken: string;
-}
-
-interface GoogleOAuthConnectionResult {
-  accountEmail: string;
-  accessTokenExpiresAtMs: number;
-  grantedScopes: string[];
-}
-
-interface GoogleAccessTokenResult {
-  accountEmail: string;
-  accessToken: string;
-  accessTokenExpiresAtMs: number;
-  grantedScopes: string[];
-}
-
 export function isGoogleAccountConnected(settings: Settings,) {
   return settings.googleAccounts.length > 0;
 }
 
-function createAbortError() {
-  return new DOMException("Google authorization cancelled.", "AbortError",);
-}
-
-function throwIfAborted(signal?: AbortSignal,) {
-  if (signal?.aborted) {
-    throw createAbortError();
-  }
-}
-
-async function cancelGoogleOAuthCallback(sessionId: string,) {
-  await invoke("cancel_google_oauth_callback", { sessionId, },);
-}
-
-async function waitForGoogleOAuthCallback(sessionId: string, timeoutMs: number, signal?: AbortSignal,) {
-  throwIfAborted(signal,);
-
-  if (!signal) {
-    return await invoke<GoogleOAuthCallback>("wait_for_google_oauth_callback", {
-      sessionId,
-      timeoutMs,
-    },);
-  }
-
-  return await new Promise<GoogleOAuthCallback>((resolve, reject,) => {
-    let settled = false;
-
-    const cleanup = () => {
-      signal.removeEventListener("abort", handleAbort,);
-    };
-
-    const settle = (callback: () => void,) => {
-      if (settled) return;
-      settled = true;
-      cleanup();
-      callback();
-    };
-
-    const handleAbort = () => {
-      void cancelGoogleOAuthCallback(sessionId,)
-        .catch(() => undefined)
-        .finally(() => {
-          settle(() => reject(createAbortError(),));
-        },);
-    };
-
-    signal.addEventListener("abort", handleAbort, { once: true, },);
-
-    invoke<GoogleOAuthCallback>("wait_for_google_oauth_callback", {
-      sessionId,
-      timeoutMs,
-    },)
-      .then((callback,) => {
-        settle(() => resolve(callback,));
-      },)
-      .catch((error,) => {
-        settle(() => reject(error,));
-      },);
-  },);
-}
-
 export function hasGoogleAccess(settings: Settings, scope: string, accountEmail?: string,) {
   const normalizedScope = scope.trim();
   if (!normalizedScope) return false;
@@ -123,29 +36,6 @@ export function hasGoogleAccess(settings: Settings, scope: string, accountEmail?
   },);
 }
 
-function toBase64Url(bytes: Uint8Array,) {
-  let binary = "";
-  for (const byte of bytes) {
-    binary += String.fromCharCode(byte,);
-  }
-  return btoa(binary,)
-    .replace(/\+/g, "-",)
-    .replace(/\//g, "_",)
-    .replace(/=+$/g, "",);
-}
-
-function createRandomVerifier(byteLength: number = 32,) {
-  const bytes = new Uint8Array(byteLength,);
-  crypto.getRandomValues(bytes,);
-  return toBase64Url(bytes,);
-}
-
-async function createCodeChallenge(verifier: string,) {
-  const bytes = new TextEncoder().encode(verifier,);
-  const digest = await crypto.subtle.digest("SHA-256", bytes,);
-  return toBase64Url(new Uint8Array(digest,),);
-}
-
 function buildGrantedScopes(scopes: string[] | undefined,) {
   if (!scopes || scopes.length === 0) return [...GOOGLE_ACCOUNT_SCOPES,];
   return Array.from(
@@ -162,7 +52,7 @@ function toExpiryTimestamp(expiresAtMs: number,) {
   return new Date(expiresAtMs,).toISOString();
 }
 
-function buildLegacySessionInput(settings: Settings, accountEmail?: string,): GoogleLegacySessionInput | null {
+function buildLegacySessionInput(settings: Settings, accountEmail?: string,): OAuthLegacySessionInput | null {
   const legacyEmail = settings.googleAccountEmail.trim();
   if (accountEmail && legacyEmail.toLowerCase() !== accountEmail.trim().toLowerCase()) {
     return null;
@@ -234,69 +124,21 @@ export async function connectGoogleAccount(
     throw new Error("Google sign-in is not configured yet.",);
   }
 
-  const verifier = createRandomVerifier(64,);
-  const challenge = await createCodeChallenge(verifier,);
-  const state = createRandomVerifier(32,);
-
-  const session = await invoke<GoogleOAuthSession>("start_google_oauth_callback",);
-  const authUrl = new URL(GOOGLE_AUTH_URL,);
-  authUrl.searchParams.set("access_type", "offline",);
-  authUrl.searchParams.set("client_id", clientId,);
-  authUrl.searchParams.set("code_challenge", challenge,);
-  authUrl.searchParams.set("code_challenge_method", "S256",);
-  authUrl.searchParams.set("include_granted_scopes", "true",);
-  authUrl.searchParams.set("prompt", options?.expectedAccountEmail ? "consent select_account" : "consent",);
-  authUrl.searchParams.set("redirect_uri", session.redirectUri,);
-  authUrl.searchParams.set("response_type", "code",);
-  authUrl.searchParams.set("scope", GOOGLE_ACCOUNT_SCOPES.join(" ",),);
-  authUrl.searchParams.set("state", state,);
-  if (options?.expectedAccountEmail) {
-    authUrl.searchParams.set("login_hint", options.expectedAccountEmail,);
-  }
-
-  try {
-    throwIfAborted(options?.signal,);
-    await openUrl(authUrl.toString(),);
-  } catch (error) {
-    await cancelGoogleOAuthCallback(session.sessionId,).catch(() => undefined);
-    throw error;
-  }
-
-  const callback = await waitForGoogleOAuthCallback(
-    session.sessionId,
-    GOOGLE_CONNECT_TIMEOUT_MS,
-    options?.signal,
-  );
-
-  if (callback.error) {
-    throw new Error(callback.error,);
-  }
-  if (!callback.code) {
-    throw new Error("Google did not return an authorization code.",);
-  }
-  if (callback.state !== state) {
-    throw new Error("Google OAuth state mismatch. Please try again.",);
-  }
-
-  throwIfAborted(options?.signal,);
-
-  const result = await invoke<GoogleOAuthConnectionResult>("complete_google_oauth", {
-    input: {
-      clientId,
-      code: callback.code,
-      codeVerifier: verifier,
-      expectedAccountEmail: options?.expectedAccountEmail,
-      legacyAccountEmail: settings.googleAccountEmail.trim(),
-      legacySession: buildLegacySessionInput(settings, options?.expectedAccountEmail,),
-      redirectUri: session.redirectUri,
-    },
+  const result = await authorizeOAuthAccount(GOOGLE_OAUTH_PROVIDER, {
+    clientId,
+    providerName: "Google",
+    loginHint: options?.expectedAccountEmail,
+    prompt: options?.expectedAccountEmail ? "consent select_account" : undefined,
+    scopes: [...GOOGLE_ACCOUNT_SCOPES,],
+    expectedAccount: options?.expectedAccountEmail,
+    legacyAccount: settings.googleAccountEmail.trim(),
+    legacySession: buildLegacySessionInput(settings, options?.expectedAccountEmail,),
+    signal: options?.signal,
   },);
 
-  throwIfAborted(options?.signal,);
-
   const account = buildGoogleAccount(
     result.accessTokenExpiresAtMs,
-    result.accountEmail,
+    result.account,
     result.grantedScopes,
   );
 
@@ -307,7 +149,7 @@ export async function connectGoogleAccount(
 }
 
 export async function disconnectGoogleAccount(settings: Settings, accountEmail: string,) {
-  await invoke("clear_google_oauth_session", { accountEmail, },);
+  await disconnectOAuthAccount(GOOGLE_OAUTH_PROVIDER, accountEmail,);
   const next = {
     ...settings,
     googleAccounts: removeGoogleAccount(settings.googleAccounts, accountEmail,),
@@ -317,6 +159,10 @@ export async function disconnectGoogleAccount(settings: Settings, accountEmail:
   return next;
 }
 
+export async function listGoogleSessionAccounts() {
+  return await listOAuthAccounts(GOOGLE_OAUTH_PROVIDER,);
+}
+
 export async function getGoogleAccessToken(accountEmail?: string,) {
   const settings = await loadSettings();
   const clientId = settings.googleOAuthClientId.trim();
@@ -333,18 +179,16 @@ export async function getGoogleAccessToken(accountEmail?: string,) {
     throw new Error("Select a Google account before fetching Google data.",);
   }
 
-  const result = await invoke<GoogleAccessTokenResult>("ensure_google_access_token", {
-    input: {
-      accountEmail: account.email,
-      clientId,
-      grantedScopes: [...account.grantedScopes,],
-      legacyAccountEmail: settings.googleAccountEmail.trim(),
-      legacySession: buildLegacySessionInput(settings, account.email,),
-    },
+  const result = await ensureOAuthAccessToken(GOOGLE_OAUTH_PROVIDER, {
+    account: account.email,
+    clientId,
+    grantedScopes: [...account.grantedScopes,],
+    legacyAccount: settings.googleAccountEmail.trim(),
+    legacySession: buildLegacySessionInput(settings, account.email,),
   },);
   const updatedAccount = buildGoogleAccount(
     result.accessTokenExpiresAtMs,
-    result.accountEmail,
+    result.account,
     result.grantedScopes,
   );
   await saveGoogleFields(
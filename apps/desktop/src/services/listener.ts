@@ -12,6 +12,27 @@ export interface ListenerSessionParams {
   base_url: string;
   api_key: string;
   keywords: string[];
+  pipeline?: ListenerPipelineConfig;
+  recording_format?: ListenerRecordingFormat;
+}
+
+export type ListenerRecordingFormat = "wav" | "opus_segments";
+
+export interface ListenerPipelineConfig {
+  aec: boolean;
+  high_pass_hz: number | null;
+  noise_gate_db: number | null;
+  vad_mask: { hangover_frames: number; amplitude_floor: number; speech_threshold?: number; } | null;
+  normalize: boolean;
+  output_sample_rate: number;
+  language_id?: ListenerLanguageIdConfig | null;
+}
+
+export interface ListenerLanguageIdConfig {
+  initial_secs: number;
+  interval_secs: number;
+  window_secs: number;
+  min_probability: number;
 }
 
 export type ListenerStreamWord = {
@@ -100,6 +121,13 @@ export type ListenerSessionDataEvent =
     type: "stream_response";
     session_id: string;
     response: ListenerStreamResponse;
+  }
+  | {
+    type: "language_detected";
+    session_id: string;
+    language: string;
+    probability: number;
+    previous: string | null;
   };
 
 export type ListenerSessionErrorEvent =
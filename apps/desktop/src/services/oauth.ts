@@ -0,0 +1,185 @@
+import { invoke, } from "@tauri-apps/api/core";
+import { openUrl, } from "@tauri-apps/plugin-opener";
+
+export const OAUTH_CONNECT_TIMEOUT_MS = 180_000;
+
+export type OAuthProviderId = "google" | "microsoft" | "github" | (string & {});
+
+/** Endpoints for a provider that isn't built in, e.g. a self-hosted IdP. */
+export interface OAuthProviderConfig {
+  id: string;
+  displayName: string;
+  authorizationUrl: string;
+  tokenUrl: string;
+  userinfoUrl: string;
+  revocationUrl?: string;
+  scopes?: string[];
+  authorizationParams?: Record<string, string>;
+  accountClaim?: string;
+}
+
+export interface OAuthLegacySessionInput {
+  accessToken: string;
+  accessTokenExpiresAtMs: number;
+  refreshToken: string;
+}
+
+export interface OAuthConnectionResult {
+  account: string;
+  accessTokenExpiresAtMs: number;
+  grantedScopes: string[];
+}
+
+export interface OAuthAccessTokenResult {
+  account: string;
+  accessToken: string;
+  accessTokenExpiresAtMs: number;
+  grantedScopes: string[];
+}
+
+interface OAuthAuthorizationSession {
+  sessionId: string;
+  authorizationUrl: string;
+  redirectUri: string;
+}
+
+interface OAuthProviderSelector {
+  providerId: OAuthProviderId;
+  provider?: OAuthProviderConfig;
+}
+
+export function createOAuthAbortError(providerName: string,) {
+  return new DOMException(`${providerName} authorization cancelled.`, "AbortError",);
+}
+
+async function cancelOAuthAuthorization(sessionId: string,) {
+  await invoke("cancel_oauth_authorization", { sessionId, },);
+}
+
+async function finishOAuthAuthorization(
+  sessionId: string,
+  input: {
+    expectedAccount?: string;
+    legacyAccount?: string;
+    legacySession?: OAuthLegacySessionInput | null;
+  },
+  providerName: string,
+  signal?: AbortSignal,
+) {
+  const finish = () =>
+    invoke<OAuthConnectionResult>("finish_oauth_authorization", {
+      input: { sessionId, timeoutMs: OAUTH_CONNECT_TIMEOUT_MS, ...input, },
+    },);
+
+  if (!signal) return await finish();
+  if (signal.aborted) {
+    await cancelOAuthAuthorization(sessionId,).catch(() => undefined);
+    throw createOAuthAbortError(providerName,);
+  }
+
+  return await new Promise<OAuthConnectionResult>((resolve, reject,) => {
+    let settled = false;
+
+    const settle = (callback: () => void,) => {
+      if (settled) return;
+      settled = true;
+      signal.removeEventListener("abort", handleAbort,);
+      callback();
+    };
+
+    const handleAbort = () => {
+      void cancelOAuthAuthorization(sessionId,)
+        .catch(() => undefined)
+        .finally(() => {
+          settle(() => reject(createOAuthAbortError(providerName,),));
+        },);
+    };
+
+    signal.addEventListener("abort", handleAbort, { once: true, },);
+
+    finish()
+      .then((result,) => {
+        settle(() => resolve(result,));
+      },)
+      .catch((error,) => {
+        settle(() => reject(error,));
+      },);
+  },);
+}
+
+/**
+ * Runs the authorization-code + PKCE flow in the user's browser and stores the resulting session
+ * in the OS keyring. Resolves with the connected account once the loopback redirect comes back.
+ */
+export async function authorizeOAuthAccount(
+  selector: OAuthProviderSelector,
+  options: {
+    clientId: string;
+    providerName: string;
+    loginHint?: string;
+    prompt?: string;
+    scopes?: string[];
+    expectedAccount?: string;
+    legacyAccount?: string;
+    legacySession?: OAuthLegacySessionInput | null;
+    signal?: AbortSignal;
+  },
+) {
+  if (options.signal?.aborted) {
+    throw createOAuthAbortError(options.providerName,);
+  }
+
+  const session = await invoke<OAuthAuthorizationSession>("start_oauth_authorization", {
+    input: {
+      ...selector,
+      clientId: options.clientId,
+      loginHint: options.loginHint,
+      prompt: options.prompt,
+      scopes: options.scopes,
+    },
+  },);
+
+  try {
+    if (options.signal?.aborted) {
+      throw createOAuthAbortError(options.providerName,);
+    }
+    await openUrl(session.authorizationUrl,);
+  } catch (error) {
+    await cancelOAuthAuthorization(session.sessionId,).catch(() => undefined);
+    throw error;
+  }
+
+  return await finishOAuthAuthorization(
+    session.sessionId,
+    {
+      expectedAccount: options.expectedAccount,
+      legacyAccount: options.legacyAccount,
+      legacySession: options.legacySession,
+    },
+    options.providerName,
+    options.signal,
+  );
+}
+
+export async function ensureOAuthAccessToken(
+  selector: OAuthProviderSelector,
+  input: {
+    clientId: string;
+    account: string;
+    grantedScopes: string[];
+    legacyAccount?: string;
+    legacySession?: OAuthLegacySessionInput | null;
+  },
+) {
+  return await invoke<OAuthAccessTokenResult>("ensure_oauth_access_token", {
+    input: { ...selector, ...input, },
+  },);
+}
+
+export async function disconnectOAuthAccount(selector: OAuthProviderSelector, account: string,) {
+  await invoke("disconnect_oauth_account", { input: { ...selector, account, }, },);
+}
+
+export async function listOAuthAccounts(selector: OAuthProviderSelector,) {
+  return await invoke<string[]>("list_oauth_accounts", { input: selector, },);
+}
@@ -371,7 +371,7 @@ export function parsePageTitleFromLinkTarget(target: string,): string | null {
   if (!trimmed) return null;
   if (/^(?:[a-z]+:)?\/\//i.test(trimmed,) || /^[a-z]+:/i.test(trimmed,)) return null;
 
-  const [pathOnly,] = trimmed.split(/[?#]/, 1,);
+  const [pathOnly,] = trimmed.split(/[?#^]/, 1,);
   const decoded = decodePathTarget(pathOnly,).replace(/^\/+/, "",).replace(/^\.?\/+/, "",);
   if (!decoded) return null;
 
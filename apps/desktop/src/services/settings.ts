@@ -24,10 +24,23 @@ export interface Settings {
   googleRefreshToken: string;
   googleAccessTokenExpiresAt: string;
   googleGrantedScopes: string[];
+  caldavAccounts: CaldavAccount[];
   journalDir: string;
   filenamePattern: string;
   vaultDir: string;
   dailyLogsFolder: string;
+  weeklyNotesFolder: string;
+  weeklyFilenamePattern: string;
+  monthlyNotesFolder: string;
+  monthlyFilenamePattern: string;
+  quarterlyNotesFolder: string;
+  quarterlyFilenamePattern: string;
+  templatesFolder: string;
+  dailyNoteTemplate: string;
+  pageTemplate: string;
+  weeklyNoteTemplate: string;
+  monthlyNoteTemplate: string;
+  quarterlyNoteTemplate: string;
   excalidrawFolder: string;
   assetsFolder: string;
   widgetGitHistoryEnabled: boolean;
@@ -167,6 +180,13 @@ export interface GoogleAccount {
   grantedScopes: string[];
 }
 
+export interface CaldavAccount {
+  id: string;
+  serverUrl: string;
+  username: string;
+  calendarHrefs: string[];
+}
+
 export const DEFAULT_FILENAME_PATTERN = "{YYYY}-{MM}-{DD}";
 const BUNDLED_GOOGLE_OAUTH_CLIENT_ID = "426453142223-dnbr4440defc5ms857fhmd715v4fe68n.apps.googleusercontent.com";
 export const DEFAULT_GOOGLE_OAUTH_CLIENT_ID = import.meta.env.VITE_GOOGLE_OAUTH_CLIENT_ID?.trim()
@@ -194,10 +214,23 @@ const DEFAULT_SETTINGS: Settings = {
   googleRefreshToken: "",
   googleAccessTokenExpiresAt: "",
   googleGrantedScopes: [],
+  caldavAccounts: [],
   journalDir: "",
   filenamePattern: "",
   vaultDir: "",
   dailyLogsFolder: "",
+  weeklyNotesFolder: "",
+  weeklyFilenamePattern: "",
+  monthlyNotesFolder: "",
+  monthlyFilenamePattern: "",
+  quarterlyNotesFolder: "",
+  quarterlyFilenamePattern: "",
+  templatesFolder: "",
+  dailyNoteTemplate: "",
+  pageTemplate: "",
+  weeklyNoteTemplate: "",
+  monthlyNoteTemplate: "",
+  quarterlyNoteTemplate: "",
   excalidrawFolder: "",
   assetsFolder: "",
   widgetGitHistoryEnabled: true,
@@ -296,6 +329,29 @@ function normalizeGoogleAccounts(value: unknown, legacy?: {
   },];
 }
 
+function normalizeCaldavAccounts(value: unknown,) {
+  if (!Array.isArray(value,)) return [];
+  return value
+    .map((entry,) => {
+      if (!entry || typeof entry !== "object") return null;
+      const id = typeof entry.id === "string" ? entry.id.trim() : "";
+      const serverUrl = typeof entry.serverUrl === "string" ? entry.serverUrl.trim() : "";
+      const username = typeof entry.username === "string" ? entry.username.trim() : "";
+      if (!id || !serverUrl || !username) return null;
+      return {
+        id,
+        serverUrl,
+        username,
+        calendarHrefs: Array.isArray(entry.calendarHrefs,)
+          ? entry.calendarHrefs.filter((href: unknown,): href is string =>
+            typeof href === "string" && href.trim().length > 0
+          )
+          : [],
+      } satisfies CaldavAccount;
+    },)
+    .filter((entry,): entry is CaldavAccount => entry !== null);
+}
+
 function normalizeGoogleOAuthClientId(_value: unknown,) {
   return DEFAULT_GOOGLE_OAUTH_CLIENT_ID;
 }
@@ -503,6 +559,7 @@ export async function loadSettings(): Promise<Settings> {
       },),
       googleOAuthClientId: normalizeGoogleOAuthClientId(parsed.googleOAuthClientId,),
       googleGrantedScopes: normalizeGoogleGrantedScopes(parsed.googleGrantedScopes,),
+      caldavAccounts: normalizeCaldavAccounts(parsed.caldavAccounts,),
       syncEnabled: parsed.syncEnabled === true,
       syncEmail: typeof parsed.syncEmail === "string" ? parsed.syncEmail.trim() : "",
       syncAccessToken: typeof parsed.syncAccessToken === "string" ? parsed.syncAccessToken.trim() : "",
@@ -537,6 +594,7 @@ export async function saveSettings(settings: Settings,): Promise<void> {
         saveRecordings: settings.saveRecordings !== false,
         googleAccounts: normalizeGoogleAccounts(settings.googleAccounts,),
         googleOAuthClientId: DEFAULT_GOOGLE_OAUTH_CLIENT_ID,
+        caldavAccounts: normalizeCaldavAccounts(settings.caldavAccounts,),
         syncEnabled: settings.syncEnabled === true,
         syncEmail: settings.syncEmail.trim(),
         syncAccessToken: settings.syncAccessToken.trim(),
@@ -46,6 +46,7 @@ import {
   getFilenamePattern,
   getVaultDirSetting,
 } from "./settings";
+import { renderNoteTemplate, } from "./templates";
 import { resolveWidgetEmbeds, } from "./widget-files";
 
 const FRONTMATTER_RE = /^---\n([\s\S]*?)\n---\n?/;
@@ -896,6 +897,13 @@ export async function createAttachedPage({
     return page;
   }
 
+  const template = await renderNoteTemplate({ kind: "page", title: normalizedTitle, },).catch(() => null);
+  if (template !== null) {
+    await invoke("write_markdown_file", { path, content: template, },);
+    const created = await loadPage(normalizedTitle,);
+    if (created) return created;
+  }
+
   const page = buildPageNote(
     normalizedTitle,
     path,
@@ -948,6 +956,13 @@ export async function listPagesAttachedTo(date: string,): Promise<AttachedPage[]
 }
 
 export async function createEmptyDailyNote(date: string,): Promise<DailyNote> {
+  const template = await renderNoteTemplate({ kind: "daily", date, },).catch(() => null);
+  if (template !== null) {
+    await invoke("write_markdown_file", { path: await getNotePath(date,), content: template, },);
+    const created = await loadDailyNote(date,);
+    if (created) return created;
+  }
+
   const note: DailyNote = {
     date,
     content: JSON.stringify(EMPTY_DOC,),
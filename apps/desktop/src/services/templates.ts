@@ -0,0 +1,25 @@
+import { invoke, } from "@tauri-apps/api/core";
+
+export type NoteTemplateKind = "daily" | "page" | "weekly" | "monthly" | "quarterly";
+
+export interface NoteTemplateRequest {
+  kind: NoteTemplateKind;
+  /** Overrides the template selected in settings for this kind. */
+  template?: string;
+  date?: string;
+  title?: string;
+  period?: string;
+  city?: string;
+}
+
+/**
+ * Renders the file contents for a new note from its template. Returns null when no template
+ * applies, so callers can fall back to an empty note.
+ */
+export async function renderNoteTemplate(input: NoteTemplateRequest,): Promise<string | null> {
+  return await invoke<string | null>("render_note_template", { input, },);
+}
+
+export async function listNoteTemplates(): Promise<string[]> {
+  return await invoke<string[]>("list_note_templates",).catch(() => []);
+}
@@ -0,0 +1,103 @@
+import { invoke, } from "@tauri-apps/api/core";
+import { join, } from "@tauri-apps/api/path";
+import { getVaultDirSetting, } from "./settings";
+
+export type VaultGitMode = "none" | "managed" | "user";
+
+export type VaultGitReason = "import" | "edit" | "external" | "update" | "commit";
+
+export interface VaultGitStatus {
+  mode: VaultGitMode;
+  autoCommit: boolean;
+  repoPath: string | null;
+}
+
+export interface VaultGitHistoryEntry {
+  commitId: string;
+  reason: VaultGitReason;
+  summary: string;
+  author: string;
+  createdAt: string;
+}
+
+export interface VaultGitDiff {
+  commitId: string;
+  parentCommitId: string | null;
+  unifiedDiff: string;
+  canRestore: boolean;
+  blockedReason: string | null;
+}
+
+interface VaultGitRestoreResult {
+  commitId: string;
+  snapshot: string;
+}
+
+async function resolveVaultNotePath(notePath: string,) {
+  const vaultRoot = (await getVaultDirSetting()).trim();
+  if (!vaultRoot) {
+    throw new Error("Vault history needs a vault folder.",);
+  }
+
+  const absolutePath = notePath.trim();
+  const relativeNotePath = absolutePath.startsWith(`${vaultRoot}/`,)
+    ? absolutePath.slice(vaultRoot.length + 1,)
+    : absolutePath.replace(/^\/+/, "",);
+  return { vaultRoot, relativeNotePath, };
+}
+
+/**
+ * Report the vault's versioning status and resume auto-commits if they are enabled.
+ * Call from the app root and whenever the vault folder changes.
+ */
+export async function openVaultGitHistory(): Promise<VaultGitStatus | null> {
+  const vaultRoot = (await getVaultDirSetting()).trim();
+  if (!vaultRoot) return null;
+  return await invoke<VaultGitStatus>("open_vault_git", { input: { vaultRoot, }, },);
+}
+
+export async function setVaultGitHistoryEnabled(enabled: boolean,): Promise<VaultGitStatus> {
+  const vaultRoot = (await getVaultDirSetting()).trim();
+  if (!vaultRoot) {
+    throw new Error("Vault history needs a vault folder.",);
+  }
+  return await invoke<VaultGitStatus>("set_vault_git_enabled", {
+    input: { vaultRoot, enabled, },
+  },);
+}
+
+export async function listVaultGitHistory(notePath: string,): Promise<VaultGitHistoryEntry[]> {
+  const { vaultRoot, relativeNotePath, } = await resolveVaultNotePath(notePath,);
+  return await invoke<VaultGitHistoryEntry[]>("list_vault_git_history", {
+    input: { vaultRoot, relativeNotePath, },
+  },);
+}
+
+export async function getVaultGitDiff(notePath: string, commitId: string,): Promise<VaultGitDiff> {
+  const { vaultRoot, relativeNotePath, } = await resolveVaultNotePath(notePath,);
+  return await invoke<VaultGitDiff>("get_vault_git_diff", {
+    input: {
+      vaultRoot,
+      relativeNotePath,
+      commitId,
+    },
+  },);
+}
+
+export async function restoreVaultGitRevision(
+  notePath: string,
+  commitId: string,
+): Promise<{ commitId: string; snapshot: string; }> {
+  const { vaultRoot, relativeNotePath, } = await resolveVaultNotePath(notePath,);
+  const result = await invoke<VaultGitRestoreResult>("restore_vault_git_revision", {
+    input: {
+      vaultRoot,
+      relativeNotePath,
+      commitId,
+    },
+  },);
+  const path = await join(vaultRoot, relativeNotePath,);
+  await invoke("write_markdown_file", { path, content: result.snapshot, },);
+
+  return result;
+}
@@ -0,0 +1,224 @@
+mod manifest;
+mod metrics;
+
+pub use manifest::*;
+pub use metrics::*;
+
+use std::path::Path;
+use std::time::Instant;
+
+use futures_util::StreamExt;
+use hypr_aec::AEC;
+use hypr_audio_utils::{
+    ResamplerDynamicNew, ResamplerDynamicOld, ResamplerStaticNew, chunk_size_for_stt,
+};
+use hypr_vad_ext::{StreamingVad, VadConfig};
+
+/// Rate the AEC and VAD run at inside the listener pipeline.
+const PIPELINE_SAMPLE_RATE: u32 = 16_000;
+const REPORT_VERSION: u32 = 1;
+
+/// Everything the eval measured. Fields and fixtures keep a fixed order and values are rounded,
+/// so reports from two commits diff cleanly; `rtf` is wall-clock and will always drift a bit.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Report {
+    pub version: u32,
+    pub aec: Vec<AecResult>,
+    pub vad: Vec<VadResult>,
+    pub resampler: Vec<ResamplerResult>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AecResult {
+    pub fixture: String,
+    pub erle_db: f64,
+    pub rtf: f64,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct VadResult {
+    pub fixture: String,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+    pub rtf: f64,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ResamplerResult {
+    pub resampler: String,
+    pub from_rate: u32,
+    pub to_rate: u32,
+    pub snr_db: f64,
+    pub rtf: f64,
+}
+
+pub async fn evaluate(manifest: &Manifest) -> anyhow::Result<Report> {
+    let mut aec = Vec::new();
+    for fixture in &manifest.aec {
+        aec.push(eval_aec(fixture)?);
+    }
+
+    let mut vad = Vec::new();
+    for fixture in &manifest.vad {
+        vad.push(eval_vad(fixture)?);
+    }
+
+    let mut resampler = Vec::new();
+    if let Some(sweep) = &manifest.resampler {
+        for &from_rate in &sweep.from_rates {
+            resampler.extend(eval_resamplers(from_rate, sweep.to_rate, sweep.duration_secs).await?);
+        }
+    }
+
+    Ok(Report {
+        version: REPORT_VERSION,
+        aec,
+        vad,
+        resampler,
+    })
+}
+
+fn eval_aec(fixture: &AecFixture) -> anyhow::Result<AecResult> {
+    let mic = read_mono_wav(&fixture.mic)?;
+    let speaker = read_mono_wav(&fixture.speaker)?;
+    let len = mic.len().min(speaker.len());
+    let chunk_size = chunk_size_for_stt(PIPELINE_SAMPLE_RATE);
+
+    let mut aec = AEC::new()?;
+    let started = Instant::now();
+    let mut processed = Vec::with_capacity(len);
+    for (mic, speaker) in mic[..len]
+        .chunks(chunk_size)
+        .zip(speaker[..len].chunks(chunk_size))
+    {
+        processed.extend(aec.process_streaming(mic, speaker)?);
+    }
+    let elapsed = started.elapsed().as_secs_f64();
+
+    Ok(AecResult {
+        fixture: fixture.name.clone(),
+        erle_db: round(erle_db(&mic[..len], &processed)),
+        rtf: round(elapsed / duration_secs(len, PIPELINE_SAMPLE_RATE)),
+    })
+}
+
+fn eval_vad(fixture: &VadFixture) -> anyhow::Result<VadResult> {
+    let mut audio = read_mono_wav(&fixture.audio)?;
+    let labels: Vec<Segment> = serde_json::from_str(&std::fs::read_to_string(&fixture.labels)?)?;
+    let segments = merge_segments(labels, fixture.merge_gap_ms);
+    let chunk_size = chunk_size_for_stt(PIPELINE_SAMPLE_RATE);
+
+    let mut vad = StreamingVad::with_config(chunk_size, VadConfig::default());
+    let mut confusion = Confusion::default();
+    let started = Instant::now();
+    let mut offset = 0;
+    for chunk in audio.chunks_mut(chunk_size) {
+        vad.process_in_place(chunk, |frame, is_speech| {
+            let midpoint = offset + frame.len() / 2;
+            let at_ms = midpoint as u64 * 1000 / PIPELINE_SAMPLE_RATE as u64;
+            confusion.record(is_speech, is_labelled_speech(&segments, at_ms));
+            offset += frame.len();
+        });
+    }
+    let elapsed = started.elapsed().as_secs_f64();
+
+    Ok(VadResult {
+        fixture: fixture.name.clone(),
+        precision: round(confusion.precision()),
+        recall: round(confusion.recall()),
+        f1: round(confusion.f1()),
+        rtf: round(elapsed / duration_secs(audio.len(), PIPELINE_SAMPLE_RATE)),
+    })
+}
+
+async fn eval_resamplers(
+    from_rate: u32,
+    to_rate: u32,
+    duration: f64,
+) -> anyhow::Result<Vec<ResamplerResult>> {
+    let input = multitone(from_rate, duration);
+    let reference = multitone(to_rate, duration);
+    let chunk_size = chunk_size_for_stt(to_rate);
+    let source = || rodio::buffer::SamplesBuffer::new(1, from_rate, input.clone());
+
+    let mut results = Vec::new();
+    for name in ["dynamic_new", "dynamic_old", "static_new"] {
+        let started = Instant::now();
+        let output: Vec<f32> = match name {
+            "dynamic_new" => {
+                collect_chunks(ResamplerDynamicNew::new(source(), to_rate, chunk_size)?).await?
+            }
+            "dynamic_old" => ResamplerDynamicOld::new(source(), to_rate).collect().await,
+            _ => collect_chunks(ResamplerStaticNew::new(source(), to_rate, chunk_size)?).await?,
+        };
+        let elapsed = started.elapsed().as_secs_f64();
+
+        results.push(ResamplerResult {
+            resampler: name.to_string(),
+            from_rate,
+            to_rate,
+            snr_db: round(aligned_snr_db(
+                &reference,
+                &output,
+                256,
+                to_rate as usize / 10,
+            )),
+            rtf: round(elapsed / duration),
+        });
+    }
+    Ok(results)
+}
+
+async fn collect_chunks<S, E>(stream: S) -> anyhow::Result<Vec<f32>>
+where
+    S: futures_util::Stream<Item = Result<Vec<f32>, E>>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut stream = std::pin::pin!(stream);
+    let mut output = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        output.extend(chunk?);
+    }
+    Ok(output)
+}
+
+fn read_mono_wav(path: &Path) -> anyhow::Result<Vec<f32>> {
+    let mut reader = hound::WavReader::open(path)?;
+    let spec = reader.spec();
+    anyhow::ensure!(
+        spec.sample_rate == PIPELINE_SAMPLE_RATE,
+        "{} is {} Hz, expected {} Hz",
+        path.display(),
+        spec.sample_rate,
+        PIPELINE_SAMPLE_RATE
+    );
+
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = (1_i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    Ok(samples
+        .into_iter()
+        .step_by(spec.channels.max(1) as usize)
+        .collect())
+}
+
+fn duration_secs(samples: usize, sample_rate: u32) -> f64 {
+    (samples as f64 / sample_rate as f64).max(f64::MIN_POSITIVE)
+}
+
+fn round(value: f64) -> f64 {
+    if value.is_finite() {
+        (value * 1000.0).round() / 1000.0
+    } else {
+        value
+    }
+}
@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+/// Runs the audio fixtures through AEC, VAD and the resamplers and prints a JSON report.
+///
+/// Save the report from two commits and diff them to see whether a change helped.
+#[derive(Parser)]
+struct Args {
+    /// Fixture manifest; defaults to the one checked in next to this crate.
+    #[arg(long)]
+    manifest: Option<PathBuf>,
+    /// Write the report here instead of stdout.
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let manifest_path = args
+        .manifest
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures.json"));
+
+    let manifest = audio_eval::Manifest::load(&manifest_path)?;
+    let report = audio_eval::evaluate(&manifest).await?;
+    let json = serde_json::to_string_pretty(&report)?;
+
+    match args.output {
+        Some(path) => std::fs::write(path, format!("{json}\n"))?,
+        None => println!("{json}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Manifest {
+    #[serde(default)]
+    pub aec: Vec<AecFixture>,
+    #[serde(default)]
+    pub vad: Vec<VadFixture>,
+    pub resampler: Option<ResamplerSweep>,
+}
+
+/// A recorded mic track and the speaker (loopback) signal that leaked into it.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct AecFixture {
+    pub name: String,
+    pub mic: PathBuf,
+    pub speaker: PathBuf,
+}
+
+/// Audio plus a JSON array of `{ "start", "end" }` speech spans in milliseconds. Word-level
+/// transcripts work as labels; neighbouring words are merged into segments.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct VadFixture {
+    pub name: String,
+    pub audio: PathBuf,
+    pub labels: PathBuf,
+    #[serde(default = "default_merge_gap_ms")]
+    pub merge_gap_ms: u64,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ResamplerSweep {
+    pub from_rates: Vec<u32>,
+    pub to_rate: u32,
+    pub duration_secs: f64,
+}
+
+fn default_merge_gap_ms() -> u64 {
+    300
+}
+
+impl Manifest {
+    /// Reads a manifest, resolving fixture paths relative to the manifest's directory.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let mut manifest: Manifest = serde_json::from_str(&content)?;
+        let base = path.parent().unwrap_or(Path::new("."));
+
+        for fixture in &mut manifest.aec {
+            fixture.mic = base.join(&fixture.mic);
+            fixture.speaker = base.join(&fixture.speaker);
+        }
+        for fixture in &mut manifest.vad {
+            fixture.audio = base.join(&fixture.audio);
+            fixture.labels = base.join(&fixture.labels);
+        }
+
+        Ok(manifest)
+    }
+}
@@ -0,0 +1,232 @@
+/// Echo return loss enhancement: how much quieter the processed mic is than the raw mic, in dB.
+pub fn erle_db(mic: &[f32], processed: &[f32]) -> f64 {
+    let len = mic.len().min(processed.len());
+    let mic_energy = energy(&mic[..len]);
+    let out_energy = energy(&processed[..len]);
+
+    if mic_energy == 0.0 {
+        return 0.0;
+    }
+    10.0 * (mic_energy / out_energy.max(f64::MIN_POSITIVE)).log10()
+}
+
+fn energy(samples: &[f32]) -> f64 {
+    samples.iter().map(|&s| (s as f64) * (s as f64)).sum()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+pub struct Segment {
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Sorts spans and merges those separated by at most `gap_ms`.
+pub fn merge_segments(mut segments: Vec<Segment>, gap_ms: u64) -> Vec<Segment> {
+    segments.sort_by_key(|segment| segment.start);
+
+    let mut merged: Vec<Segment> = Vec::with_capacity(segments.len());
+    for segment in segments {
+        match merged.last_mut() {
+            Some(last) if segment.start <= last.end + gap_ms => {
+                last.end = last.end.max(segment.end);
+            }
+            _ => merged.push(segment),
+        }
+    }
+    merged
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Confusion {
+    pub true_positive: u64,
+    pub false_positive: u64,
+    pub false_negative: u64,
+    pub true_negative: u64,
+}
+
+impl Confusion {
+    pub fn record(&mut self, predicted: bool, actual: bool) {
+        match (predicted, actual) {
+            (true, true) => self.true_positive += 1,
+            (true, false) => self.false_positive += 1,
+            (false, true) => self.false_negative += 1,
+            (false, false) => self.true_negative += 1,
+        }
+    }
+
+    pub fn precision(&self) -> f64 {
+        ratio(self.true_positive, self.true_positive + self.false_positive)
+    }
+
+    pub fn recall(&self) -> f64 {
+        ratio(self.true_positive, self.true_positive + self.false_negative)
+    }
+
+    pub fn f1(&self) -> f64 {
+        let (p, r) = (self.precision(), self.recall());
+        if p + r == 0.0 {
+            0.0
+        } else {
+            2.0 * p * r / (p + r)
+        }
+    }
+}
+
+fn ratio(numerator: u64, denominator: u64) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+pub fn is_labelled_speech(segments: &[Segment], at_ms: u64) -> bool {
+    segments
+        .iter()
+        .any(|segment| segment.start <= at_ms && at_ms < segment.end)
+}
+
+/// Sum of sines well below the Nyquist frequency of every rate we sweep, so the ideal signal can
+/// be synthesized directly at the target rate.
+pub fn multitone(sample_rate: u32, duration_secs: f64) -> Vec<f32> {
+    const TONES: [(f64, f64); 3] = [(300.0, 0.2), (1000.0, 0.2), (2500.0, 0.2)];
+
+    let len = (sample_rate as f64 * duration_secs) as usize;
+    (0..len)
+        .map(|i| {
+            let t = i as f64 / sample_rate as f64;
+            TONES
+                .iter()
+                .map(|(freq, amp)| amp * (2.0 * std::f64::consts::PI * freq * t).sin())
+                .sum::<f64>() as f32
+        })
+        .collect()
+}
+
+/// SNR of `actual` against `reference` after compensating for the resampler's group delay.
+/// `edge` samples are ignored at both ends to skip filter warm-up and flush.
+pub fn aligned_snr_db(reference: &[f32], actual: &[f32], max_lag: usize, edge: usize) -> f64 {
+    let lag = best_lag(reference, actual, max_lag, edge);
+
+    let mut signal = 0.0;
+    let mut noise = 0.0;
+    for (i, &expected) in reference.iter().enumerate().skip(edge) {
+        let j = i as isize + lag;
+        if j < 0 {
+            continue;
+        }
+        let Some(&got) = actual.get(j as usize) else {
+            break;
+        };
+        if i + edge >= reference.len() {
+            break;
+        }
+        signal += (expected as f64) * (expected as f64);
+        noise += (expected as f64 - got as f64).powi(2);
+    }
+
+    if signal == 0.0 {
+        return 0.0;
+    }
+    10.0 * (signal / noise.max(f64::MIN_POSITIVE)).log10()
+}
+
+fn best_lag(reference: &[f32], actual: &[f32], max_lag: usize, edge: usize) -> isize {
+    let window_end = reference.len().saturating_sub(edge).min(edge + 8_000);
+    let max_lag = max_lag as isize;
+
+    (-max_lag..=max_lag)
+        .max_by(|&a, &b| {
+            correlation(reference, actual, a, edge, window_end)
+                .total_cmp(&correlation(reference, actual, b, edge, window_end))
+        })
+        .unwrap_or(0)
+}
+
+fn correlation(reference: &[f32], actual: &[f32], lag: isize, from: usize, to: usize) -> f64 {
+    (from..to)
+        .filter_map(|i| {
+            let j = i as isize + lag;
+            (j >= 0)
+                .then(|| actual.get(j as usize))
+                .flatten()
+                .map(|&got| reference[i] as f64 * got as f64)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn erle_measures_attenuation() {
+        let mic = multitone(16_000, 0.5);
+        assert!(erle_db(&mic, &mic).abs() < 1e-9);
+
+        let halved: Vec<f32> = mic.iter().map(|s| s * 0.5).collect();
+        assert!((erle_db(&mic, &halved) - 6.0206).abs() < 1e-3);
+    }
+
+    #[test]
+    fn segments_merge_and_score_frames() {
+        let merged = merge_segments(
+            vec![
+                Segment {
+                    start: 500,
+                    end: 900,
+                },
+                Segment {
+                    start: 100,
+                    end: 300,
+                },
+                Segment {
+                    start: 2000,
+                    end: 2500,
+                },
+            ],
+            300,
+        );
+        assert_eq!(
+            merged,
+            vec![
+                Segment {
+                    start: 100,
+                    end: 900
+                },
+                Segment {
+                    start: 2000,
+                    end: 2500,
+                },
+            ]
+        );
+        assert!(is_labelled_speech(&merged, 400));
+        assert!(!is_labelled_speech(&merged, 1500));
+
+        let mut confusion = Confusion::default();
+        confusion.record(true, true);
+        confusion.record(true, false);
+        confusion.record(false, true);
+        confusion.record(true, true);
+        assert!((confusion.precision() - 2.0 / 3.0).abs() < 1e-9);
+        assert!((confusion.recall() - 2.0 / 3.0).abs() < 1e-9);
+        assert!((confusion.f1() - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(Confusion::default().f1(), 0.0);
+    }
+
+    #[test]
+    fn snr_compensates_for_delay() {
+        let reference = multitone(16_000, 1.0);
+        let mut delayed = vec![0.0; 37];
+        delayed.extend_from_slice(&reference);
+        assert!(aligned_snr_db(&reference, &delayed, 64, 100) > 100.0);
+
+        let noisy: Vec<f32> = reference
+            .iter()
+            .enumerate()
+            .map(|(i, s)| s + if i % 2 == 0 { 0.01 } else { -0.01 })
+            .collect();
+        let snr = aligned_snr_db(&reference, &noisy, 64, 100);
+        assert!((20.0..40.0).contains(&snr), "snr {snr}");
+    }
+}
@@ -0,0 +1,507 @@
+use std::collections::VecDeque;
+use std::path::Path;
+
+use rubato::{
+    Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction,
+};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CODEC_TYPE_NULL, CODEC_TYPE_OPUS, CodecType, DecoderOptions};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::{FormatOptions, FormatReader, Track};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::AudioMetadata;
+
+/// Opus always decodes at 48 kHz, whatever rate the container advertises.
+pub(crate) const OPUS_SAMPLE_RATE: u32 = 48_000;
+/// Longest Opus packet is 120 ms.
+#[cfg(feature = "opus")]
+const OPUS_MAX_FRAMES: usize = OPUS_SAMPLE_RATE as usize * 120 / 1000;
+const RESAMPLER_CHUNK_FRAMES: usize = 1024;
+
+/// Reads container metadata (rate, channels, duration) without decoding any audio.
+pub fn probe_file(path: impl AsRef<Path>) -> Result<AudioMetadata, crate::Error> {
+    let (_, track) = open(path.as_ref())?;
+    metadata_from_track(&track)
+}
+
+/// Decodes the first playable audio track of any container symphonia can demux (mp4/m4a, webm/mkv,
+/// ogg, wav, flac, mp3, aac, aiff, caf), packet by packet.
+pub fn decode_file(path: impl AsRef<Path>) -> Result<DecodedAudio, crate::Error> {
+    let (format, track) = open(path.as_ref())?;
+    let metadata = metadata_from_track(&track)?;
+    let decoder = PacketDecoder::new(&track)?;
+
+    Ok(DecodedAudio {
+        format,
+        decoder,
+        track_id: track.id,
+        metadata,
+    })
+}
+
+/// Decodes `path`, mixes it down to mono and resamples it to `to_rate` while decoding, yielding
+/// chunks of `chunk_frames` samples (the last one may be shorter).
+pub fn decode_resampled(
+    path: impl AsRef<Path>,
+    to_rate: u32,
+    chunk_frames: usize,
+) -> Result<ResampledMono, crate::Error> {
+    ResampledMono::new(decode_file(path)?, to_rate, chunk_frames)
+}
+
+fn open(path: &Path) -> Result<(Box<dyn FormatReader>, Track), crate::Error> {
+    let file = std::fs::File::open(path)?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let format_options = FormatOptions {
+        enable_gapless: true,
+        ..Default::default()
+    };
+    let probed = symphonia::default::get_probe()
+        .format(&hint, stream, &format_options, &MetadataOptions::default())
+        .map_err(|error| match error {
+            SymphoniaError::Unsupported(_) => crate::Error::UnsupportedContainer {
+                extension: path.extension().map(|e| e.to_string_lossy().into_owned()),
+            },
+            other => other.into(),
+        })?;
+
+    let format = probed.format;
+    let track = select_track(format.tracks())?.clone();
+    Ok((format, track))
+}
+
+/// Prefers a track we can decode; otherwise reports the codec of the first audio track found.
+fn select_track(tracks: &[Track]) -> Result<&Track, crate::Error> {
+    let audio_tracks = || {
+        tracks
+            .iter()
+            .filter(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+    };
+
+    if let Some(track) = audio_tracks().find(|track| is_decodable(track.codec_params.codec)) {
+        return Ok(track);
+    }
+
+    match audio_tracks().next() {
+        Some(track) => Err(crate::Error::UnsupportedCodec {
+            codec: codec_name(track.codec_params.codec),
+        }),
+        None => Err(crate::Error::NoAudioTrack),
+    }
+}
+
+fn is_decodable(codec: CodecType) -> bool {
+    if codec == CODEC_TYPE_OPUS {
+        return cfg!(feature = "opus");
+    }
+    symphonia::default::get_codecs().get_codec(codec).is_some()
+}
+
+fn codec_name(codec: CodecType) -> String {
+    if codec == CODEC_TYPE_OPUS {
+        return "opus".to_string();
+    }
+    symphonia::default::get_codecs()
+        .get_codec(codec)
+        .map(|descriptor| descriptor.short_name.to_string())
+        .unwrap_or_else(|| format!("{codec:?}"))
+}
+
+fn metadata_from_track(track: &Track) -> Result<AudioMetadata, crate::Error> {
+    let params = &track.codec_params;
+
+    let sample_rate = if params.codec == CODEC_TYPE_OPUS {
+        OPUS_SAMPLE_RATE
+    } else {
+        params.sample_rate.unwrap_or(0)
+    };
+    if sample_rate == 0 {
+        return Err(crate::Error::InvalidSampleRate(sample_rate));
+    }
+
+    let channel_count = params.channels.map(|c| c.count()).unwrap_or(0);
+    let channels = u8::try_from(channel_count).ok().filter(|&c| c > 0).ok_or(
+        crate::Error::UnsupportedChannelCount {
+            count: channel_count as u16,
+        },
+    )?;
+
+    let duration_secs = match (params.n_frames, params.time_base) {
+        (Some(frames), Some(time_base)) => {
+            let time = time_base.calc_time(frames);
+            Some(time.seconds as f64 + time.frac)
+        }
+        (Some(frames), None) => Some(frames as f64 / sample_rate as f64),
+        _ => None,
+    };
+
+    Ok(AudioMetadata {
+        sample_rate,
+        channels,
+        duration_secs,
+    })
+}
+
+enum PacketDecoder {
+    Symphonia(Box<dyn symphonia::core::codecs::Decoder>),
+    #[cfg(feature = "opus")]
+    Opus {
+        decoder: opus::Decoder,
+        channels: usize,
+        buffer: Vec<f32>,
+    },
+}
+
+impl PacketDecoder {
+    fn new(track: &Track) -> Result<Self, crate::Error> {
+        #[cfg(feature = "opus")]
+        if track.codec_params.codec == CODEC_TYPE_OPUS {
+            let count = track.codec_params.channels.map(|c| c.count()).unwrap_or(0);
+            let channels = match count {
+                1 => opus::Channels::Mono,
+                2 => opus::Channels::Stereo,
+                _ => {
+                    return Err(crate::Error::UnsupportedCodec {
+                        codec: format!("opus ({count} channel multistream)"),
+                    });
+                }
+            };
+            return Ok(Self::Opus {
+                decoder: opus::Decoder::new(OPUS_SAMPLE_RATE, channels)?,
+                channels: count,
+                buffer: vec![0.0; OPUS_MAX_FRAMES * count],
+            });
+        }
+
+        let decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())
+            .map_err(|error| match error {
+                SymphoniaError::Unsupported(_) => crate::Error::UnsupportedCodec {
+                    codec: codec_name(track.codec_params.codec),
+                },
+                other => other.into(),
+            })?;
+        Ok(Self::Symphonia(decoder))
+    }
+
+    /// Interleaved samples of one packet, with gapless padding already trimmed.
+    fn decode(
+        &mut self,
+        packet: &symphonia::core::formats::Packet,
+    ) -> Result<Vec<f32>, crate::Error> {
+        match self {
+            Self::Symphonia(decoder) => match decoder.decode(packet) {
+                Ok(decoded) => {
+                    let mut buffer =
+                        SampleBuffer::<f32>::new(decoded.capacity() as u64, *decoded.spec());
+                    buffer.copy_interleaved_ref(decoded);
+                    Ok(buffer.samples().to_vec())
+                }
+                // A corrupt packet is skipped rather than failing the whole import.
+                Err(SymphoniaError::DecodeError(reason)) => {
+                    tracing::warn!(reason, "audio_packet_decode_failed");
+                    Ok(Vec::new())
+                }
+                Err(error) => Err(error.into()),
+            },
+            #[cfg(feature = "opus")]
+            Self::Opus {
+                decoder,
+                channels,
+                buffer,
+            } => {
+                let frames = decoder.decode_float(packet.buf(), buffer, false)?;
+                let start = (packet.trim_start() as usize).min(frames);
+                let end = frames.saturating_sub(packet.trim_end() as usize).max(start);
+                Ok(buffer[start * *channels..end * *channels].to_vec())
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        match self {
+            Self::Symphonia(decoder) => decoder.reset(),
+            #[cfg(feature = "opus")]
+            Self::Opus { decoder, .. } => {
+                let _ = decoder.reset_state();
+            }
+        }
+    }
+}
+
+/// Interleaved f32 packets from one audio track.
+pub struct DecodedAudio {
+    format: Box<dyn FormatReader>,
+    decoder: PacketDecoder,
+    track_id: u32,
+    metadata: AudioMetadata,
+}
+
+impl DecodedAudio {
+    pub fn metadata(&self) -> AudioMetadata {
+        self.metadata
+    }
+}
+
+impl Iterator for DecodedAudio {
+    type Item = Result<Vec<f32>, crate::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let packet = match self.format.next_packet() {
+                Ok(packet) => packet,
+                Err(SymphoniaError::IoError(error))
+                    if error.kind() == std::io::ErrorKind::UnexpectedEof =>
+                {
+                    return None;
+                }
+                Err(SymphoniaError::ResetRequired) => {
+                    self.decoder.reset();
+                    continue;
+                }
+                Err(error) => return Some(Err(error.into())),
+            };
+
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+
+            match self.decoder.decode(&packet) {
+                Ok(samples) if samples.is_empty() => continue,
+                result => return Some(result),
+            }
+        }
+    }
+}
+
+/// Mono audio at a fixed rate, resampled incrementally as packets are decoded.
+pub struct ResampledMono {
+    source: DecodedAudio,
+    channels: usize,
+    resampler: Option<SincFixedIn<f32>>,
+    ratio: f64,
+    input: Vec<f32>,
+    output: VecDeque<f32>,
+    chunk_frames: usize,
+    delay_to_skip: usize,
+    consumed_frames: usize,
+    produced_frames: usize,
+    finished: bool,
+}
+
+impl ResampledMono {
+    fn new(source: DecodedAudio, to_rate: u32, chunk_frames: usize) -> Result<Self, crate::Error> {
+        let from_rate = source.metadata().sample_rate;
+        let ratio = to_rate as f64 / from_rate as f64;
+
+        let resampler = if from_rate == to_rate {
+            None
+        } else {
+            let params = SincInterpolationParameters {
+                sinc_len: 256,
+                f_cutoff: 0.95,
+                interpolation: SincInterpolationType::Linear,
+                oversampling_factor: 256,
+                window: WindowFunction::BlackmanHarris2,
+            };
+            Some(SincFixedIn::<f32>::new(
+                ratio,
+                1.0,
+                params,
+                RESAMPLER_CHUNK_FRAMES,
+                1,
+            )?)
+        };
+        let delay_to_skip = resampler.as_ref().map_or(0, |r| r.output_delay());
+
+        Ok(Self {
+            channels: source.metadata().channels.max(1) as usize,
+            source,
+            resampler,
+            ratio,
+            input: Vec::new(),
+            output: VecDeque::new(),
+            chunk_frames: chunk_frames.max(1),
+            delay_to_skip,
+            consumed_frames: 0,
+            produced_frames: 0,
+            finished: false,
+        })
+    }
+
+    pub fn metadata(&self) -> AudioMetadata {
+        self.source.metadata()
+    }
+
+    fn push_output(&mut self, frames: Vec<f32>) {
+        let skip = self.delay_to_skip.min(frames.len());
+        self.delay_to_skip -= skip;
+        self.produced_frames += frames.len() - skip;
+        self.output.extend(&frames[skip..]);
+    }
+
+    fn fill(&mut self) -> Result<(), crate::Error> {
+        while self.output.len() < self.chunk_frames && !self.finished {
+            match self.source.next() {
+                Some(samples) => {
+                    let samples = samples?;
+                    let channels = self.channels;
+                    self.input.extend(
+                        samples
+                            .chunks_exact(channels)
+                            .map(|frame| frame.iter().sum::<f32>() / channels as f32),
+                    );
+                }
+                None => self.finished = true,
+            }
+
+            let mut resampled = Vec::new();
+            match self.resampler.as_mut() {
+                Some(resampler) => {
+                    while self.input.len() >= resampler.input_frames_next() {
+                        let needed = resampler.input_frames_next();
+                        let out = resampler.process(&[&self.input[..needed]], None)?;
+                        self.input.drain(..needed);
+                        self.consumed_frames += needed;
+                        resampled.extend(out.into_iter().next().unwrap_or_default());
+                    }
+                }
+                None => {
+                    self.consumed_frames += self.input.len();
+                    resampled = std::mem::take(&mut self.input);
+                }
+            }
+            self.push_output(resampled);
+
+            if self.finished {
+                self.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Drains the partial last block and the resampler's delay line, stopping at the length the
+    /// input implies.
+    fn flush(&mut self) -> Result<(), crate::Error> {
+        let Some(resampler) = self.resampler.as_mut() else {
+            return Ok(());
+        };
+
+        self.consumed_frames += self.input.len();
+        let expected = (self.consumed_frames as f64 * self.ratio).round() as usize;
+
+        let remaining = [std::mem::take(&mut self.input)];
+        let mut tail = resampler
+            .process_partial(Some(&remaining[..]), None)?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        for _ in 0..4 {
+            if self.produced_frames + tail.len().saturating_sub(self.delay_to_skip) >= expected {
+                break;
+            }
+            tail.extend(
+                resampler
+                    .process_partial(None::<&[Vec<f32>]>, None)?
+                    .into_iter()
+                    .next()
+                    .unwrap_or_default(),
+            );
+        }
+
+        let skip = self.delay_to_skip.min(tail.len());
+        let keep = expected
+            .saturating_sub(self.produced_frames)
+            .min(tail.len() - skip);
+        tail.truncate(skip + keep);
+        self.push_output(tail);
+        Ok(())
+    }
+}
+
+impl Iterator for ResampledMono {
+    type Item = Result<Vec<f32>, crate::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(error) = self.fill() {
+            self.finished = true;
+            self.output.clear();
+            return Some(Err(error));
+        }
+
+        if self.output.is_empty() {
+            return None;
+        }
+
+        let take = self.chunk_frames.min(self.output.len());
+        Some(Ok(self.output.drain(..take).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! test_decode_resampled {
+        ($($name:ident: $path:expr),* $(,)?) => {
+            $(
+                #[test]
+                fn $name() {
+                    let metadata = probe_file($path).unwrap();
+                    let samples: Vec<f32> = decode_resampled($path, 16_000, 1920)
+                        .unwrap()
+                        .collect::<Result<Vec<_>, _>>()
+                        .unwrap()
+                        .concat();
+
+                    assert!(samples.iter().any(|s| *s != 0.0));
+                    if let Some(duration) = metadata.duration_secs {
+                        let decoded = samples.len() as f64 / 16_000.0;
+                        assert!((decoded - duration).abs() < 0.25, "{decoded} vs {duration}");
+                    }
+                }
+            )*
+        };
+    }
+
+    test_decode_resampled! {
+        test_decode_wav: hypr_data::english_1::AUDIO_PATH,
+        test_decode_mp3: hypr_data::english_1::AUDIO_MP3_PATH,
+        test_decode_mp4: hypr_data::english_1::AUDIO_MP4_PATH,
+        test_decode_m4a: hypr_data::english_1::AUDIO_M4A_PATH,
+        test_decode_ogg: hypr_data::english_1::AUDIO_OGG_PATH,
+        test_decode_flac: hypr_data::english_1::AUDIO_FLAC_PATH,
+    }
+
+    #[cfg(feature = "opus")]
+    test_decode_resampled! {
+        test_decode_opus: hypr_data::english_1::AUDIO_OPUS_PATH,
+        test_decode_webm: hypr_data::english_1::AUDIO_WEBM_PATH,
+    }
+
+    #[cfg(not(feature = "opus"))]
+    #[test]
+    fn test_opus_reports_unsupported_codec() {
+        let error = decode_file(hypr_data::english_1::AUDIO_OPUS_PATH)
+            .err()
+            .unwrap();
+        assert!(matches!(error, crate::Error::UnsupportedCodec { ref codec } if codec == "opus"));
+    }
+
+    #[test]
+    fn test_unknown_container_is_reported() {
+        let error = probe_file(hypr_data::english_1::TRANSCRIPTION_PATH)
+            .err()
+            .unwrap();
+        assert!(matches!(error, crate::Error::UnsupportedContainer { .. }));
+    }
+}
@@ -22,4 +22,22 @@ pub enum Error {
     EmptyChannelSet,
     #[error("too many channels: {count}")]
     TooManyChannels { count: usize },
+    #[error(transparent)]
+    Symphonia(#[from] symphonia::core::errors::Error),
+    #[cfg(feature = "opus")]
+    #[error(transparent)]
+    Opus(#[from] opus::Error),
+    #[cfg(feature = "opus")]
+    #[error(transparent)]
+    Ogg(#[from] ogg::OggReadError),
+    #[error("invalid ogg opus stream: {0}")]
+    InvalidOggOpus(&'static str),
+    #[error("segment {path} has a different channel layout or sample rate")]
+    SegmentFormatMismatch { path: String },
+    #[error("unsupported container {}", .extension.as_deref().unwrap_or("(no extension)"))]
+    UnsupportedContainer { extension: Option<String> },
+    #[error("unsupported codec {codec}")]
+    UnsupportedCodec { codec: String },
+    #[error("no audio track")]
+    NoAudioTrack,
 }
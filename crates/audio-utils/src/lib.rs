@@ -4,12 +4,18 @@ use bytes::{BufMut, Bytes, BytesMut};
 use futures_util::{Stream, StreamExt};
 use hypr_audio_interface::AsyncSource;
 
+mod decode;
 mod error;
+#[cfg(feature = "opus")]
+mod ogg_opus;
 mod pcm;
 mod resampler;
 mod vorbis;
 
+pub use decode::*;
 pub use error::*;
+#[cfg(feature = "opus")]
+pub use ogg_opus::*;
 pub use pcm::*;
 pub use resampler::*;
 pub use vorbis::*;
@@ -22,6 +28,8 @@ const I16_SCALE: f32 = 32768.0;
 pub struct AudioMetadata {
     pub sample_rate: u32,
     pub channels: u8,
+    /// `None` when the container doesn't record a length (e.g. some streamed mp3/webm files).
+    pub duration_secs: Option<f64>,
 }
 
 impl<T: AsyncSource> AudioFormatExt for T {}
@@ -172,14 +180,21 @@ where
     Ok(AudioMetadata {
         sample_rate,
         channels,
+        duration_secs: source.total_duration().map(|d| d.as_secs_f64()),
     })
 }
 
 pub fn audio_file_metadata(
     path: impl AsRef<std::path::Path>,
 ) -> Result<AudioMetadata, crate::Error> {
-    let source = source_from_path(path)?;
-    metadata_from_source(&source)
+    match probe_file(path.as_ref()) {
+        Ok(metadata) => Ok(metadata),
+        Err(error) => {
+            tracing::debug!(?error, "symphonia_probe_failed_falling_back_to_rodio");
+            let source = source_from_path(path)?;
+            metadata_from_source(&source)
+        }
+    }
 }
 
 pub fn resample_audio<S>(source: S, to_rate: u32) -> Result<Vec<f32>, crate::Error>
@@ -0,0 +1,529 @@
+use std::fs::File;
+use std::io::{BufReader, Write};
+use std::path::Path;
+
+use ogg::reading::PacketReader;
+use ogg::writing::{PacketWriteEndInfo, PacketWriter};
+
+use crate::{Error, OPUS_SAMPLE_RATE};
+
+/// Encoder lookahead libopus adds at 48 kHz; written as pre-skip so players trim it.
+const OPUS_PRE_SKIP: u16 = 312;
+const FRAME_MS: u32 = 20;
+const MAX_PACKET_BYTES: usize = 4000;
+const BITRATE_PER_CHANNEL: i32 = 24_000;
+const FIRST_SERIAL: u32 = 0x6879_7072;
+const OPUS_HEAD_MAGIC: &[u8] = b"OpusHead";
+const OPUS_TAGS_MAGIC: &[u8] = b"OpusTags";
+const VENDOR: &str = concat!("hypr-audio-utils ", env!("CARGO_PKG_VERSION"));
+
+/// What an Ogg Opus file holds, read up to its last complete page.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OggOpusInfo {
+    /// Rate the audio was captured at; decoders still output 48 kHz.
+    pub sample_rate: u32,
+    pub channels: u8,
+    pub pre_skip: u16,
+    pub duration_secs: f64,
+    /// `false` when the file ends without an end-of-stream page, e.g. after a crash.
+    pub complete: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct OpusHead {
+    channels: u8,
+    pre_skip: u16,
+    sample_rate: u32,
+}
+
+impl OpusHead {
+    fn to_bytes(self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(19);
+        bytes.extend_from_slice(OPUS_HEAD_MAGIC);
+        bytes.push(1);
+        bytes.push(self.channels);
+        bytes.extend_from_slice(&self.pre_skip.to_le_bytes());
+        bytes.extend_from_slice(&self.sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&0i16.to_le_bytes());
+        bytes.push(0);
+        bytes
+    }
+
+    fn parse(packet: &[u8]) -> Result<Self, Error> {
+        if packet.len() < 19 || !packet.starts_with(OPUS_HEAD_MAGIC) {
+            return Err(Error::InvalidOggOpus("missing OpusHead"));
+        }
+        if packet[18] != 0 {
+            return Err(Error::UnsupportedCodec {
+                codec: format!("opus (channel mapping family {})", packet[18]),
+            });
+        }
+
+        Ok(Self {
+            channels: packet[9],
+            pre_skip: u16::from_le_bytes([packet[10], packet[11]]),
+            sample_rate: u32::from_le_bytes([packet[12], packet[13], packet[14], packet[15]]),
+        })
+    }
+}
+
+fn opus_tags() -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(16 + VENDOR.len());
+    bytes.extend_from_slice(OPUS_TAGS_MAGIC);
+    bytes.extend_from_slice(&(VENDOR.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(VENDOR.as_bytes());
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    bytes
+}
+
+/// Number of 48 kHz frames an Opus packet decodes to, from its TOC byte (RFC 6716 §3.1).
+fn packet_frames(packet: &[u8]) -> u64 {
+    let Some(&toc) = packet.first() else {
+        return 0;
+    };
+
+    let config = toc >> 3;
+    let frame_size: u64 = match config {
+        0..=11 => [480, 960, 1920, 2880][usize::from(config % 4)],
+        12..=15 => [480, 960][usize::from(config % 2)],
+        _ => [120, 240, 480, 960][usize::from(config % 4)],
+    };
+    let frame_count: u64 = match toc & 0b11 {
+        0 => 1,
+        1 | 2 => 2,
+        _ => packet.get(1).map_or(0, |b| u64::from(b & 0x3f)),
+    };
+
+    frame_size * frame_count
+}
+
+struct Segment<W: Write> {
+    packets: PacketWriter<'static, W>,
+    serial: u32,
+    /// Stream granule at which this segment starts.
+    base_granule: u64,
+    /// Last encoded packet, kept back so the segment can always end on an end-of-stream packet.
+    held: Option<Vec<u8>>,
+    cut_page: bool,
+}
+
+impl<W: Write> Segment<W> {
+    fn start(writer: W, serial: u32, head: OpusHead, base_granule: u64) -> Result<Self, Error> {
+        let mut packets = PacketWriter::new(writer);
+        packets.write_packet(head.to_bytes(), serial, PacketWriteEndInfo::EndPage, 0)?;
+        packets.write_packet(opus_tags(), serial, PacketWriteEndInfo::EndPage, 0)?;
+        packets.inner_mut().flush()?;
+
+        Ok(Self {
+            packets,
+            serial,
+            base_granule,
+            held: None,
+            cut_page: false,
+        })
+    }
+
+    fn push(&mut self, packet: Vec<u8>, stream_granule: u64) -> Result<(), Error> {
+        // The held packet ends where the one just encoded starts.
+        let granule = stream_granule - packet_frames(&packet) - self.base_granule;
+        if let Some(previous) = self.held.replace(packet) {
+            let end = if std::mem::take(&mut self.cut_page) {
+                PacketWriteEndInfo::EndPage
+            } else {
+                PacketWriteEndInfo::NormalPacket
+            };
+            self.packets
+                .write_packet(previous, self.serial, end, granule)?;
+            if matches!(end, PacketWriteEndInfo::EndPage) {
+                self.packets.inner_mut().flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the held packet as the end of the stream; a segment without audio stays header-only.
+    fn close(mut self, end_granule: u64) -> Result<W, Error> {
+        if let Some(last) = self.held.take() {
+            self.packets.write_packet(
+                last,
+                self.serial,
+                PacketWriteEndInfo::EndStream,
+                end_granule - self.base_granule,
+            )?;
+        }
+        let mut writer = self.packets.into_inner();
+        writer.flush()?;
+        Ok(writer)
+    }
+}
+
+/// Streams interleaved f32 audio into Ogg Opus, optionally rolling over into new files.
+///
+/// One encoder runs across all segments, so concatenating them with [`concat_ogg_opus`]
+/// is gapless; each segment is still a playable file on its own.
+pub struct OggOpusWriter<W: Write> {
+    encoder: opus::Encoder,
+    sample_rate: u32,
+    channels: u8,
+    frame_len: usize,
+    granule_scale: u64,
+    pending: Vec<f32>,
+    scratch: Vec<u8>,
+    input_frames: u64,
+    encoded_granule: u64,
+    segment_start_frames: u64,
+    segment: Segment<W>,
+    segments_started: u32,
+}
+
+impl<W: Write> OggOpusWriter<W> {
+    pub fn new(writer: W, sample_rate: u32, channels: u8) -> Result<Self, Error> {
+        if !matches!(sample_rate, 8_000 | 12_000 | 16_000 | 24_000 | 48_000) {
+            return Err(Error::InvalidSampleRate(sample_rate));
+        }
+        let opus_channels = match channels {
+            1 => opus::Channels::Mono,
+            2 => opus::Channels::Stereo,
+            count => {
+                return Err(Error::UnsupportedChannelCount {
+                    count: u16::from(count),
+                });
+            }
+        };
+
+        let mut encoder = opus::Encoder::new(sample_rate, opus_channels, opus::Application::Voip)?;
+        encoder.set_bitrate(opus::Bitrate::Bits(
+            BITRATE_PER_CHANNEL * i32::from(channels),
+        ))?;
+
+        let head = OpusHead {
+            channels,
+            pre_skip: OPUS_PRE_SKIP,
+            sample_rate,
+        };
+        let frame_len = (sample_rate * FRAME_MS / 1000) as usize * usize::from(channels);
+
+        Ok(Self {
+            encoder,
+            sample_rate,
+            channels,
+            frame_len,
+            granule_scale: u64::from(OPUS_SAMPLE_RATE / sample_rate),
+            pending: Vec::with_capacity(frame_len),
+            scratch: vec![0; MAX_PACKET_BYTES],
+            input_frames: 0,
+            encoded_granule: 0,
+            segment_start_frames: 0,
+            segment: Segment::start(writer, FIRST_SERIAL, head, 0)?,
+            segments_started: 1,
+        })
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn channels(&self) -> u8 {
+        self.channels
+    }
+
+    /// Frames written to the current segment so far, at the input rate.
+    pub fn segment_frames(&self) -> u64 {
+        self.input_frames - self.segment_start_frames
+    }
+
+    pub fn write_interleaved(&mut self, samples: &[f32]) -> Result<(), Error> {
+        self.input_frames += (samples.len() / usize::from(self.channels)) as u64;
+
+        let mut rest = samples;
+        while !rest.is_empty() {
+            let take = (self.frame_len - self.pending.len()).min(rest.len());
+            self.pending.extend_from_slice(&rest[..take]);
+            rest = &rest[take..];
+
+            if self.pending.len() == self.frame_len {
+                self.encode_pending()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Ends the current Ogg page at the next packet so everything before it reaches disk.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.segment.cut_page = true;
+        self.segment.packets.inner_mut().flush()?;
+        Ok(())
+    }
+
+    /// Closes the current segment and continues the stream in `next`, returning the old writer.
+    pub fn roll(&mut self, next: W) -> Result<W, Error> {
+        let head = OpusHead {
+            channels: self.channels,
+            pre_skip: 0,
+            sample_rate: self.sample_rate,
+        };
+        let serial = FIRST_SERIAL.wrapping_add(self.segments_started);
+        let next = Segment::start(next, serial, head, self.encoded_granule)?;
+        self.segments_started += 1;
+        self.segment_start_frames = self.input_frames;
+
+        let previous = std::mem::replace(&mut self.segment, next);
+        previous.close(self.encoded_granule)
+    }
+
+    /// Pads out the encoder delay and closes the last segment, trimming the padding on playback.
+    pub fn finish(mut self) -> Result<W, Error> {
+        let end_granule = u64::from(OPUS_PRE_SKIP) + self.input_frames * self.granule_scale;
+        while self.encoded_granule < end_granule {
+            self.pending.resize(self.frame_len, 0.0);
+            self.encode_pending()?;
+        }
+
+        let end_granule = end_granule.max(self.segment.base_granule);
+        self.segment.close(end_granule)
+    }
+
+    fn encode_pending(&mut self) -> Result<(), Error> {
+        let len = self
+            .encoder
+            .encode_float(&self.pending, &mut self.scratch)?;
+        self.pending.clear();
+
+        let packet = self.scratch[..len].to_vec();
+        self.encoded_granule += packet_frames(&packet);
+        self.segment.push(packet, self.encoded_granule)
+    }
+}
+
+/// Reads the Opus header and the last complete page of a (possibly truncated) Ogg Opus file.
+pub fn read_ogg_opus_info(path: impl AsRef<Path>) -> Result<OggOpusInfo, Error> {
+    let path = path.as_ref();
+    let mut reader = PacketReader::new(BufReader::new(File::open(path)?));
+
+    let head = match reader.read_packet()? {
+        Some(packet) => OpusHead::parse(&packet.data)?,
+        None => return Err(Error::InvalidOggOpus("empty stream")),
+    };
+
+    let mut last_granule = 0u64;
+    let mut complete = false;
+    loop {
+        match reader.read_packet() {
+            Ok(Some(packet)) => {
+                if packet.last_in_page() && packet.absgp_page() != u64::MAX {
+                    last_granule = packet.absgp_page();
+                }
+                if packet.last_in_stream() {
+                    complete = true;
+                    break;
+                }
+            }
+            Ok(None) => break,
+            Err(error) => {
+                tracing::warn!(?error, path = %path.display(), "ogg_opus_truncated_tail");
+                break;
+            }
+        }
+    }
+
+    let frames = last_granule.saturating_sub(u64::from(head.pre_skip));
+    Ok(OggOpusInfo {
+        sample_rate: head.sample_rate,
+        channels: head.channels,
+        pre_skip: head.pre_skip,
+        duration_secs: frames as f64 / f64::from(OPUS_SAMPLE_RATE),
+        complete,
+    })
+}
+
+/// Remuxes Ogg Opus segments into one logical stream without re-encoding.
+///
+/// Segments from [`OggOpusWriter::roll`] join seamlessly. A segment started by a fresh encoder
+/// (e.g. after a crash) keeps its ~6.5 ms encoder delay, since pre-skip only applies at the start.
+pub fn concat_ogg_opus<W: Write>(
+    segments: &[impl AsRef<Path>],
+    output: W,
+) -> Result<OggOpusInfo, Error> {
+    let mut out = PacketWriter::new(output);
+    let mut first: Option<OpusHead> = None;
+    let mut held: Option<Vec<u8>> = None;
+    let mut granule = 0u64;
+    let mut end_granule = 0u64;
+
+    for path in segments {
+        let path = path.as_ref();
+        let mut reader = PacketReader::new(BufReader::new(File::open(path)?));
+
+        let head = match reader.read_packet()? {
+            Some(packet) => OpusHead::parse(&packet.data)?,
+            None => continue,
+        };
+        match first {
+            None => {
+                out.write_packet(
+                    head.to_bytes(),
+                    FIRST_SERIAL,
+                    PacketWriteEndInfo::EndPage,
+                    0,
+                )?;
+                out.write_packet(opus_tags(), FIRST_SERIAL, PacketWriteEndInfo::EndPage, 0)?;
+                first = Some(head);
+            }
+            Some(expected)
+                if expected.channels != head.channels
+                    || expected.sample_rate != head.sample_rate =>
+            {
+                return Err(Error::SegmentFormatMismatch {
+                    path: path.display().to_string(),
+                });
+            }
+            Some(_) => {}
+        }
+
+        let segment_start = granule;
+        let mut seen_tags = false;
+        loop {
+            let packet = match reader.read_packet() {
+                Ok(Some(packet)) => packet,
+                Ok(None) => break,
+                Err(error) => {
+                    tracing::warn!(?error, path = %path.display(), "ogg_opus_truncated_tail");
+                    break;
+                }
+            };
+            if !seen_tags {
+                seen_tags = true;
+                if packet.data.starts_with(OPUS_TAGS_MAGIC) {
+                    continue;
+                }
+            }
+
+            if let Some(previous) = held.replace(packet.data) {
+                out.write_packet(
+                    previous,
+                    FIRST_SERIAL,
+                    PacketWriteEndInfo::NormalPacket,
+                    granule,
+                )?;
+            }
+            granule += held.as_deref().map_or(0, packet_frames);
+            end_granule = granule;
+
+            if packet.last_in_page() && packet.absgp_page() != u64::MAX {
+                // The writer's end trimming only survives on the final page of the final segment.
+                end_granule = (segment_start + packet.absgp_page()).min(granule);
+            }
+        }
+    }
+
+    let Some(head) = first else {
+        return Err(Error::InvalidOggOpus("no segments"));
+    };
+    if let Some(last) = held {
+        out.write_packet(
+            last,
+            FIRST_SERIAL,
+            PacketWriteEndInfo::EndStream,
+            end_granule,
+        )?;
+    }
+    out.into_inner().flush()?;
+
+    let frames = end_granule.saturating_sub(u64::from(head.pre_skip));
+    Ok(OggOpusInfo {
+        sample_rate: head.sample_rate,
+        channels: head.channels,
+        pre_skip: head.pre_skip,
+        duration_secs: frames as f64 / f64::from(OPUS_SAMPLE_RATE),
+        complete: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(frames: usize, sample_rate: u32) -> Vec<f32> {
+        (0..frames)
+            .map(|i| (i as f32 * 440.0 * std::f32::consts::TAU / sample_rate as f32).sin() * 0.3)
+            .collect()
+    }
+
+    #[test]
+    fn test_packet_frames_from_toc() {
+        // CELT 20 ms, one frame.
+        assert_eq!(packet_frames(&[0b1111_1000]), 960);
+        // SILK 10 ms, two frames.
+        assert_eq!(packet_frames(&[0b0000_0001]), 960);
+        // Code 3 with 3 frames of hybrid 20 ms.
+        assert_eq!(packet_frames(&[0b0110_1011, 3]), 2880);
+    }
+
+    #[test]
+    fn test_segments_roundtrip_and_concat() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths: Vec<_> = (0..3)
+            .map(|i| dir.path().join(format!("{i}.opus")))
+            .collect();
+
+        let audio = tone(16_000 * 3, 16_000);
+        let mut writer = OggOpusWriter::new(File::create(&paths[0]).unwrap(), 16_000, 1).unwrap();
+        for (index, second) in audio.chunks(16_000).enumerate() {
+            for chunk in second.chunks(512) {
+                writer.write_interleaved(chunk).unwrap();
+            }
+            writer.flush().unwrap();
+            if let Some(next) = paths.get(index + 1) {
+                writer.roll(File::create(next).unwrap()).unwrap();
+            }
+        }
+        writer.finish().unwrap();
+
+        for path in &paths {
+            let info = read_ogg_opus_info(path).unwrap();
+            assert!(info.complete);
+            assert_eq!(info.sample_rate, 16_000);
+            assert!((info.duration_secs - 1.0).abs() < 0.05, "{info:?}");
+        }
+
+        let joined = dir.path().join("joined.opus");
+        let info = concat_ogg_opus(&paths, File::create(&joined).unwrap()).unwrap();
+        assert!((info.duration_secs - 3.0).abs() < 0.001, "{info:?}");
+        assert_eq!(read_ogg_opus_info(&joined).unwrap(), info);
+
+        let decoded: usize = crate::decode_file(&joined)
+            .unwrap()
+            .map(|chunk| chunk.unwrap().len())
+            .sum();
+        assert!((decoded as f64 / 48_000.0 - 3.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_truncated_segment_keeps_complete_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("0.opus");
+
+        let mut writer = OggOpusWriter::new(File::create(&path).unwrap(), 16_000, 2).unwrap();
+        let stereo: Vec<f32> = tone(16_000, 16_000)
+            .iter()
+            .flat_map(|s| [*s, -*s])
+            .collect();
+        writer.write_interleaved(&stereo).unwrap();
+        writer.flush().unwrap();
+        writer.write_interleaved(&stereo[..3200]).unwrap();
+        let file = writer.finish().unwrap();
+        let full_len = file.metadata().unwrap().len();
+        drop(file);
+
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..full_len as usize - 20]).unwrap();
+
+        let info = read_ogg_opus_info(&path).unwrap();
+        assert!(!info.complete);
+        assert_eq!(info.channels, 2);
+        assert!(
+            info.duration_secs > 0.9 && info.duration_secs < 1.1,
+            "{info:?}"
+        );
+    }
+}
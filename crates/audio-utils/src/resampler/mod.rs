@@ -1,10 +1,12 @@
 mod driver;
 mod dynamic_new;
 mod dynamic_old;
+mod push;
 mod static_new;
 
 pub use dynamic_new::*;
 pub use dynamic_old::*;
+pub use push::*;
 pub use static_new::*;
 
 #[cfg(test)]
@@ -215,6 +217,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_push_resampler() {
+        let mut resampler = ResamplerPush::new(16000, 8000, 320).unwrap();
+
+        let mut output = Vec::new();
+        for chunk in vec![0.25; 3200].chunks(200) {
+            output.extend(resampler.process(chunk).unwrap());
+        }
+
+        assert!((1500..=1600).contains(&output.len()), "{}", output.len());
+        assert!(output[output.len() - 100..].iter().all(|s| (s - 0.25).abs() < 1e-3));
+        assert!(matches!(
+            ResamplerPush::new(16000, 0, 320),
+            Err(crate::Error::InvalidSampleRate(0))
+        ));
+    }
+
     #[tokio::test]
     async fn test_dynamic_new_rate_change_boundary() {
         let segments = vec![
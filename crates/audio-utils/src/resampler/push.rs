@@ -0,0 +1,47 @@
+use rubato::{FastFixedIn, PolynomialDegree};
+
+use super::driver::RubatoChunkResampler;
+
+/// Fixed-rate resampler for callers that already hold chunks of samples, such as a processing
+/// stage fed by a capture loop. Input that doesn't fill a block is carried over to the next call.
+pub struct ResamplerPush {
+    driver: RubatoChunkResampler<FastFixedIn<f32>, 1>,
+}
+
+impl ResamplerPush {
+    pub fn new(
+        source_rate: u32,
+        target_rate: u32,
+        input_block_size: usize,
+    ) -> Result<Self, crate::Error> {
+        if source_rate == 0 {
+            return Err(crate::Error::InvalidSampleRate(source_rate));
+        }
+        if target_rate == 0 {
+            return Err(crate::Error::InvalidSampleRate(target_rate));
+        }
+
+        let input_block_size = input_block_size.max(1);
+        let ratio = target_rate as f64 / source_rate as f64;
+        let resampler = FastFixedIn::<f32>::new(
+            ratio,
+            1.0,
+            PolynomialDegree::Quintic,
+            input_block_size,
+            1,
+        )?;
+
+        Ok(Self {
+            driver: RubatoChunkResampler::new(resampler, input_block_size, input_block_size),
+        })
+    }
+
+    /// Resamples `samples` and returns whatever output the completed blocks produced.
+    pub fn process(&mut self, samples: &[f32]) -> Result<Vec<f32>, crate::Error> {
+        for &sample in samples {
+            self.driver.push_sample(sample);
+        }
+        self.driver.process_all_ready_blocks()?;
+        Ok(self.driver.take_all_output().unwrap_or_default())
+    }
+}
@@ -14,6 +14,12 @@ const ANALYZE_CHUNK_SIZE: usize = 512;
 #[pin_project]
 pub struct NormalizedSource<S: hypr_audio_interface::AsyncSource> {
     source: S,
+    normalizer: LoudnessNormalizer,
+}
+
+/// Sample-by-sample loudness normalization towards -23 LUFS with a true-peak limiter, usable
+/// on chunks outside of a stream.
+pub struct LoudnessNormalizer {
     gain_linear: f32,
     ebur128: EbuR128,
     loudness_buffer: Vec<f32>,
@@ -21,6 +27,47 @@ pub struct NormalizedSource<S: hypr_audio_interface::AsyncSource> {
     true_peak_limit: f32,
 }
 
+impl LoudnessNormalizer {
+    pub fn new(sample_rate: u32) -> Self {
+        let ebur128 = EbuR128::new(CHANNELS, sample_rate, Mode::I | Mode::TRUE_PEAK)
+            .expect("Failed to create EBU R128 analyzer");
+
+        Self {
+            gain_linear: 1.0,
+            ebur128,
+            loudness_buffer: Vec::with_capacity(ANALYZE_CHUNK_SIZE),
+            limiter: TruePeakLimiter::new(sample_rate),
+            true_peak_limit: 10_f32.powf(TRUE_PEAK_LIMIT as f32 / 20.0),
+        }
+    }
+
+    pub fn process(&mut self, sample: f32) -> f32 {
+        self.loudness_buffer.push(sample);
+
+        if self.loudness_buffer.len() >= ANALYZE_CHUNK_SIZE {
+            let _ = self.ebur128.add_frames_f32(&self.loudness_buffer);
+            self.loudness_buffer.clear();
+
+            if let Ok(current_lufs) = self.ebur128.loudness_global()
+                && current_lufs.is_finite()
+                && current_lufs < 0.0
+            {
+                let gain_db = TARGET_LUFS - current_lufs;
+                self.gain_linear = 10_f32.powf(gain_db as f32 / 20.0);
+            }
+        }
+
+        let amplified = sample * self.gain_linear;
+        self.limiter.process(amplified, self.true_peak_limit)
+    }
+
+    pub fn process_chunk(&mut self, samples: &mut [f32]) {
+        for sample in samples.iter_mut() {
+            *sample = self.process(*sample);
+        }
+    }
+}
+
 struct TruePeakLimiter {
     lookahead_samples: usize,
     buffer: Vec<f32>,
@@ -65,19 +112,11 @@ pub trait NormalizeExt<S: hypr_audio_interface::AsyncSource> {
 
 impl<S: hypr_audio_interface::AsyncSource> NormalizeExt<S> for S {
     fn normalize(self) -> NormalizedSource<S> {
-        let sample_rate = self.sample_rate();
-        let ebur128 = EbuR128::new(CHANNELS, sample_rate, Mode::I | Mode::TRUE_PEAK)
-            .expect("Failed to create EBU R128 analyzer");
-
-        let true_peak_limit = 10_f32.powf(TRUE_PEAK_LIMIT as f32 / 20.0);
+        let normalizer = LoudnessNormalizer::new(self.sample_rate());
 
         NormalizedSource {
             source: self,
-            gain_linear: 1.0,
-            ebur128,
-            loudness_buffer: Vec::with_capacity(ANALYZE_CHUNK_SIZE),
-            limiter: TruePeakLimiter::new(sample_rate),
-            true_peak_limit,
+            normalizer,
         }
     }
 }
@@ -90,27 +129,7 @@ impl<S: hypr_audio_interface::AsyncSource> Stream for NormalizedSource<S> {
         let mut inner = std::pin::pin!(this.source.as_stream());
 
         match inner.as_mut().poll_next(cx) {
-            Poll::Ready(Some(sample)) => {
-                this.loudness_buffer.push(sample);
-
-                if this.loudness_buffer.len() >= ANALYZE_CHUNK_SIZE {
-                    let _ = this.ebur128.add_frames_f32(&this.loudness_buffer);
-                    this.loudness_buffer.clear();
-
-                    if let Ok(current_lufs) = this.ebur128.loudness_global()
-                        && current_lufs.is_finite()
-                        && current_lufs < 0.0
-                    {
-                        let gain_db = TARGET_LUFS - current_lufs;
-                        this.gain_linear = 10_f32.powf(gain_db as f32 / 20.0);
-                    }
-                }
-
-                let amplified = sample * this.gain_linear;
-                let limited = this.limiter.process(amplified, this.true_peak_limit);
-
-                Poll::Ready(Some(limited))
-            }
+            Poll::Ready(Some(sample)) => Poll::Ready(Some(this.normalizer.process(sample))),
             Poll::Pending => Poll::Pending,
             Poll::Ready(None) => Poll::Ready(None),
         }
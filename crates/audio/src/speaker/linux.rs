@@ -1,16 +1,19 @@
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, mpsc};
 use std::task::{Poll, Waker};
 use std::thread;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use futures_util::Stream;
+use hypr_device_monitor::{DeviceSwitch, DeviceSwitchMonitor};
 use libpulse_binding as pulse;
 use pin_project::pin_project;
+use pulse::callbacks::ListResult;
 use pulse::context::{Context as PaContext, FlagSet as ContextFlagSet};
 use pulse::mainloop::threaded::Mainloop;
 use pulse::sample::{Format, Spec};
-use pulse::stream::{FlagSet as StreamFlagSet, Stream as PaStream};
+use pulse::stream::{FlagSet as StreamFlagSet, PeekResult, Stream as PaStream};
 use ringbuf::{
     HeapCons, HeapProd, HeapRb,
     traits::{Consumer, Producer, Split},
@@ -18,7 +21,10 @@ use ringbuf::{
 
 use super::{BUFFER_SIZE, CHUNK_SIZE};
 
+/// Requested when the default sink's own rate can't be read; PulseAudio resamples for us.
 const SAMPLE_RATE: u32 = 48000;
+const RECONNECT_DELAY: Duration = Duration::from_millis(500);
+const QUERY_TIMEOUT: Duration = Duration::from_secs(2);
 
 pub struct SpeakerInput {
     sample_rate: u32,
@@ -29,6 +35,9 @@ struct WakerState {
     has_data: bool,
 }
 
+/// Records the default sink's monitor source, which PulseAudio and PipeWire (via pipewire-pulse)
+/// both expose. When the default output changes, capture reconnects to the new sink's monitor
+/// and the rate handle is updated before any samples at the new rate are produced.
 #[pin_project(PinnedDrop)]
 pub struct SpeakerStream {
     consumer: HeapCons<f32>,
@@ -40,10 +49,17 @@ pub struct SpeakerStream {
 }
 
 impl SpeakerInput {
+    /// Briefly opens the default monitor source so `sample_rate` reports the rate the server
+    /// actually grants, which is what the capture stream will start at.
     pub fn new() -> Result<Self> {
-        Ok(Self {
-            sample_rate: SAMPLE_RATE,
-        })
+        let (mut mainloop, mut context) = connect()?;
+        let target = default_monitor_target(&mut mainloop, &context);
+        let mut stream = open_capture(&mut mainloop, &mut context, target.as_ref())?;
+        let sample_rate = negotiated_rate(&mut mainloop, &stream);
+        disconnect(&mut mainloop, &mut stream);
+        mainloop.stop();
+
+        Ok(Self { sample_rate })
     }
 
     pub fn sample_rate(&self) -> u32 {
@@ -87,12 +103,83 @@ impl SpeakerInput {
     }
 }
 
+/// The monitor source of the default sink, and the rate that sink runs at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MonitorTarget {
+    source: String,
+    sample_rate: u32,
+}
+
+enum CaptureEnd {
+    Stopped,
+    DeviceChanged(Option<MonitorTarget>),
+    StreamLost,
+}
+
 fn capture_loop(
     mut producer: HeapProd<f32>,
     waker_state: Arc<Mutex<WakerState>>,
     current_sample_rate: Arc<AtomicU32>,
     stop_signal: Arc<AtomicBool>,
 ) -> Result<()> {
+    let (mut mainloop, mut context) = connect()?;
+
+    let (switch_tx, switch_rx) = mpsc::channel();
+    let _device_monitor = DeviceSwitchMonitor::spawn_debounced(switch_tx);
+
+    let mut target = default_monitor_target(&mut mainloop, &context);
+
+    while !stop_signal.load(Ordering::Acquire) {
+        let mut stream = match open_capture(&mut mainloop, &mut context, target.as_ref()) {
+            Ok(stream) => stream,
+            Err(e) => {
+                tracing::warn!(error = ?e, target = ?target, "monitor_capture_open_failed");
+                thread::sleep(RECONNECT_DELAY);
+                target = default_monitor_target(&mut mainloop, &context);
+                continue;
+            }
+        };
+
+        let actual_rate = negotiated_rate(&mut mainloop, &stream);
+        current_sample_rate.store(actual_rate, Ordering::Release);
+        tracing::info!(
+            monitor_source = ?target.as_ref().map(|t| &t.source),
+            sample_rate = actual_rate,
+            "PulseAudio capture initialized"
+        );
+
+        let end = pump(
+            &mut mainloop,
+            &context,
+            &mut stream,
+            &mut producer,
+            &waker_state,
+            &stop_signal,
+            &switch_rx,
+            target.as_ref(),
+        );
+        disconnect(&mut mainloop, &mut stream);
+
+        match end {
+            CaptureEnd::Stopped => break,
+            CaptureEnd::DeviceChanged(next) => {
+                tracing::info!(from = ?target, to = ?next, "default_output_changed_reconnecting");
+                target = next;
+            }
+            CaptureEnd::StreamLost => {
+                tracing::warn!(target = ?target, "monitor_stream_lost_reconnecting");
+                thread::sleep(RECONNECT_DELAY);
+                target = default_monitor_target(&mut mainloop, &context);
+            }
+        }
+    }
+
+    mainloop.stop();
+
+    Ok(())
+}
+
+fn connect() -> Result<(Mainloop, PaContext)> {
     let mut mainloop = Mainloop::new().context("Failed to create PulseAudio mainloop")?;
     let mut context = PaContext::new(&mainloop, "hyprnote-speaker-capture")
         .context("Failed to create PulseAudio context")?;
@@ -107,105 +194,156 @@ fn capture_loop(
 
     wait_for_context_ready(&mut mainloop, &context)?;
 
+    Ok((mainloop, context))
+}
+
+/// The rate the server settled on for a ready stream, which can differ from the one requested.
+fn negotiated_rate(mainloop: &mut Mainloop, stream: &PaStream) -> u32 {
+    mainloop.lock();
+    let rate = stream
+        .get_sample_spec()
+        .map(|s| s.rate)
+        .unwrap_or(SAMPLE_RATE);
+    mainloop.unlock();
+    rate
+}
+
+fn disconnect(mainloop: &mut Mainloop, stream: &mut PaStream) {
+    mainloop.lock();
+    let _ = stream.disconnect();
+    mainloop.unlock();
+}
+
+fn open_capture(
+    mainloop: &mut Mainloop,
+    context: &mut PaContext,
+    target: Option<&MonitorTarget>,
+) -> Result<PaStream> {
     let spec = Spec {
         format: Format::F32le,
         channels: 1,
-        rate: SAMPLE_RATE,
+        rate: target.map(|t| t.sample_rate).unwrap_or(SAMPLE_RATE),
     };
 
     if !spec.is_valid() {
         anyhow::bail!("Invalid sample spec");
     }
 
-    let monitor_device = get_default_monitor_device(&mut mainloop, &context);
-    tracing::info!(monitor_device = ?monitor_device, "Connecting to monitor device");
-
     mainloop.lock();
 
-    let mut stream = PaStream::new(&mut context, "hyprnote-capture", &spec, None)
-        .context("Failed to create PulseAudio stream")?;
+    let Some(mut stream) = PaStream::new(context, "hyprnote-capture", &spec, None) else {
+        mainloop.unlock();
+        anyhow::bail!("Failed to create PulseAudio stream");
+    };
 
-    stream
-        .connect_record(
-            monitor_device.as_deref(),
-            None,
-            StreamFlagSet::ADJUST_LATENCY | StreamFlagSet::AUTO_TIMING_UPDATE,
-        )
-        .map_err(|_| anyhow::anyhow!("Failed to connect stream for recording"))?;
+    // DONT_MOVE: if the sink disappears the stream fails instead of being silently moved, and we
+    // pick the new default ourselves.
+    let connected = stream.connect_record(
+        target.map(|t| t.source.as_str()),
+        None,
+        StreamFlagSet::ADJUST_LATENCY
+            | StreamFlagSet::AUTO_TIMING_UPDATE
+            | StreamFlagSet::DONT_MOVE,
+    );
 
     mainloop.unlock();
 
-    wait_for_stream_ready(&mut mainloop, &stream)?;
+    connected.map_err(|_| anyhow::anyhow!("Failed to connect stream for recording"))?;
+    wait_for_stream_ready(mainloop, &stream)?;
 
-    mainloop.lock();
-    let actual_rate = stream
-        .get_sample_spec()
-        .map(|s| s.rate)
-        .unwrap_or(SAMPLE_RATE);
-    mainloop.unlock();
+    Ok(stream)
+}
 
-    current_sample_rate.store(actual_rate, Ordering::Release);
-    tracing::info!(sample_rate = actual_rate, "PulseAudio capture initialized");
+#[allow(clippy::too_many_arguments)]
+fn pump(
+    mainloop: &mut Mainloop,
+    context: &PaContext,
+    stream: &mut PaStream,
+    producer: &mut HeapProd<f32>,
+    waker_state: &Arc<Mutex<WakerState>>,
+    stop_signal: &AtomicBool,
+    switch_rx: &mpsc::Receiver<DeviceSwitch>,
+    target: Option<&MonitorTarget>,
+) -> CaptureEnd {
+    let mut samples: Vec<f32> = Vec::with_capacity(CHUNK_SIZE);
 
-    let mut buffer = vec![0u8; CHUNK_SIZE * 4];
+    loop {
+        if stop_signal.load(Ordering::Acquire) {
+            return CaptureEnd::Stopped;
+        }
+
+        if output_switch_pending(switch_rx) {
+            let next = default_monitor_target(mainloop, context);
+            if next.as_ref() != target {
+                return CaptureEnd::DeviceChanged(next);
+            }
+        }
 
-    while !stop_signal.load(Ordering::Acquire) {
         mainloop.lock();
 
-        let readable = stream.readable_size().unwrap_or(0);
-        if readable == 0 {
+        if matches!(
+            stream.get_state(),
+            pulse::stream::State::Failed | pulse::stream::State::Terminated
+        ) {
             mainloop.unlock();
-            thread::sleep(std::time::Duration::from_millis(5));
-            continue;
+            return CaptureEnd::StreamLost;
         }
 
-        match stream.peek() {
-            Ok(pulse::stream::PeekResult::Data(data)) => {
-                let bytes_to_copy = data.len().min(buffer.len());
-                buffer[..bytes_to_copy].copy_from_slice(&data[..bytes_to_copy]);
+        if stream.readable_size().unwrap_or(0) == 0 {
+            mainloop.unlock();
+            thread::sleep(Duration::from_millis(5));
+            continue;
+        }
 
+        // Copy the whole fragment out while locked; it can be larger than one chunk.
+        let peeked = match stream.peek() {
+            Ok(PeekResult::Data(data)) => {
+                samples.clear();
+                samples.extend(f32_samples(data));
                 let _ = stream.discard();
-                mainloop.unlock();
-
-                let samples: Vec<f32> = buffer[..bytes_to_copy]
-                    .chunks_exact(4)
-                    .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
-                    .collect();
-
-                if !samples.is_empty() {
-                    let pushed = producer.push_slice(&samples);
-
-                    if pushed < samples.len() {
-                        tracing::warn!(dropped = samples.len() - pushed, "samples_dropped");
-                    }
-
-                    if pushed > 0 {
-                        wake_consumer(&waker_state);
-                    }
-                }
+                true
             }
-            Ok(pulse::stream::PeekResult::Hole(_)) => {
+            Ok(PeekResult::Hole(_)) => {
                 let _ = stream.discard();
-                mainloop.unlock();
-            }
-            Ok(pulse::stream::PeekResult::Empty) => {
-                mainloop.unlock();
-                thread::sleep(std::time::Duration::from_millis(5));
-            }
-            Err(_) => {
-                mainloop.unlock();
-                thread::sleep(std::time::Duration::from_millis(10));
+                false
             }
+            Ok(PeekResult::Empty) | Err(_) => false,
+        };
+
+        mainloop.unlock();
+
+        if !peeked || samples.is_empty() {
+            continue;
         }
-    }
 
-    mainloop.lock();
-    let _ = stream.disconnect();
-    mainloop.unlock();
+        let pushed = producer.push_slice(&samples);
+        if pushed < samples.len() {
+            tracing::warn!(dropped = samples.len() - pushed, "samples_dropped");
+        }
+        if pushed > 0 {
+            wake_consumer(waker_state);
+        }
+    }
+}
 
-    mainloop.stop();
+/// Drains pending device events; true if any could mean the default sink moved.
+fn output_switch_pending(switch_rx: &mpsc::Receiver<DeviceSwitch>) -> bool {
+    let mut pending = false;
+    while let Ok(event) = switch_rx.try_recv() {
+        if matches!(
+            event,
+            DeviceSwitch::DefaultOutputChanged { .. } | DeviceSwitch::DeviceListChanged
+        ) {
+            pending = true;
+        }
+    }
+    pending
+}
 
-    Ok(())
+fn f32_samples(bytes: &[u8]) -> impl Iterator<Item = f32> + '_ {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
 }
 
 fn wait_for_context_ready(mainloop: &mut Mainloop, context: &PaContext) -> Result<()> {
@@ -254,36 +392,57 @@ fn wait_for_stream_ready(mainloop: &mut Mainloop, stream: &PaStream) -> Result<(
     }
 }
 
-fn get_default_monitor_device(mainloop: &mut Mainloop, context: &PaContext) -> Option<String> {
-    use std::sync::mpsc;
+/// Resolves the default sink, then its monitor source and native rate. Falls back to the
+/// `<sink>.monitor` naming convention if the sink can't be introspected.
+fn default_monitor_target(mainloop: &mut Mainloop, context: &PaContext) -> Option<MonitorTarget> {
+    let sink_name = introspect(mainloop, |tx| {
+        context.introspect().get_server_info(move |info| {
+            let _ = tx.send(info.default_sink_name.as_ref().map(|s| s.to_string()));
+        });
+    })
+    .flatten()?;
+
+    let from_sink = {
+        let sink_name = sink_name.clone();
+        introspect(mainloop, move |tx| {
+            context
+                .introspect()
+                .get_sink_info_by_name(&sink_name, move |result| {
+                    if let ListResult::Item(info) = result {
+                        let _ = tx.send(info.monitor_source_name.as_ref().map(|source| {
+                            MonitorTarget {
+                                source: source.to_string(),
+                                sample_rate: info.sample_spec.rate,
+                            }
+                        }));
+                    }
+                });
+        })
+        .flatten()
+    };
+
+    Some(from_sink.unwrap_or_else(|| MonitorTarget {
+        source: monitor_source_name(&sink_name),
+        sample_rate: SAMPLE_RATE,
+    }))
+}
 
+/// Runs one introspection request under the mainloop lock and waits for its first reply.
+fn introspect<T: Send + 'static>(
+    mainloop: &mut Mainloop,
+    request: impl FnOnce(mpsc::Sender<T>),
+) -> Option<T> {
     let (tx, rx) = mpsc::channel();
-    let done = Arc::new(AtomicBool::new(false));
-    let done_clone = done.clone();
 
     mainloop.lock();
-    let introspector = context.introspect();
-    introspector.get_server_info(move |info| {
-        if let Some(sink_name) = &info.default_sink_name {
-            let monitor_name = format!("{}.monitor", sink_name);
-            let _ = tx.send(Some(monitor_name));
-        } else {
-            let _ = tx.send(None);
-        }
-        done_clone.store(true, Ordering::Release);
-    });
+    request(tx);
     mainloop.unlock();
 
-    let timeout = std::time::Duration::from_secs(2);
-    let start = std::time::Instant::now();
-
-    while !done.load(Ordering::Acquire) && start.elapsed() < timeout {
-        thread::sleep(std::time::Duration::from_millis(10));
-    }
+    rx.recv_timeout(QUERY_TIMEOUT).ok()
+}
 
-    rx.recv_timeout(std::time::Duration::from_millis(100))
-        .ok()
-        .flatten()
+fn monitor_source_name(sink_name: &str) -> String {
+    format!("{}.monitor", sink_name)
 }
 
 fn wake_consumer(waker_state: &Arc<Mutex<WakerState>>) {
@@ -306,6 +465,11 @@ impl SpeakerStream {
     pub fn sample_rate(&self) -> u32 {
         self.current_sample_rate.load(Ordering::Acquire)
     }
+
+    /// Shared with the capture thread, which stores the new rate whenever it reconnects.
+    pub fn rate_handle(&self) -> Arc<AtomicU32> {
+        self.current_sample_rate.clone()
+    }
 }
 
 impl Stream for SpeakerStream {
@@ -344,3 +508,35 @@ impl PinnedDrop for SpeakerStream {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_little_endian_f32_and_ignores_partial_frames() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&0.5f32.to_le_bytes());
+        bytes.extend_from_slice(&(-1.0f32).to_le_bytes());
+        bytes.extend_from_slice(&[0, 0]);
+
+        assert_eq!(f32_samples(&bytes).collect::<Vec<_>>(), vec![0.5, -1.0]);
+        assert_eq!(
+            monitor_source_name("alsa_output.pci-0000_00_1f.3.analog-stereo"),
+            "alsa_output.pci-0000_00_1f.3.analog-stereo.monitor"
+        );
+    }
+
+    #[test]
+    fn only_output_events_trigger_a_monitor_lookup() {
+        let (tx, rx) = mpsc::channel();
+        tx.send(DeviceSwitch::DefaultInputChanged).unwrap();
+        assert!(!output_switch_pending(&rx));
+
+        tx.send(DeviceSwitch::DefaultInputChanged).unwrap();
+        tx.send(DeviceSwitch::DefaultOutputChanged { headphone: None })
+            .unwrap();
+        assert!(output_switch_pending(&rx));
+        assert!(!output_switch_pending(&rx));
+    }
+}
@@ -26,8 +26,8 @@ type PlatformSpeakerInput = linux::SpeakerInput;
 #[cfg(all(target_os = "linux", not(test)))]
 type PlatformSpeakerStream = linux::SpeakerStream;
 
-#[cfg(test)]
-mod mock;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
 
 #[cfg(all(
     any(target_os = "macos", target_os = "windows", target_os = "linux"),
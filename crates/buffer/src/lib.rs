@@ -1,3 +1,10 @@
+mod lint;
+
+pub use lint::{
+    DateOrder, Diagnostic, Fix, LintConfig, LintRule, ListMarkerStyle, Span, apply_fixes,
+    fix_markdown, lint_markdown,
+};
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("failed to parse markdown")]
@@ -8,6 +15,8 @@ pub enum Error {
     HTMLRenderError(String),
     #[error("failed to parse html")]
     HTMLParseError(String),
+    #[error("unknown lint rule: {0}")]
+    UnknownLintRule(String),
 }
 
 type TextTransformation = Box<dyn Fn(&mut String)>;
@@ -0,0 +1,325 @@
+mod rules;
+
+use std::collections::{BTreeSet, HashSet};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintRule {
+    HeadingIncrement,
+    ListMarker,
+    TrailingWhitespace,
+    BrokenWikiLink,
+    DuplicateTask,
+    DateFormat,
+}
+
+impl LintRule {
+    pub const ALL: [LintRule; 6] = [
+        LintRule::HeadingIncrement,
+        LintRule::ListMarker,
+        LintRule::TrailingWhitespace,
+        LintRule::BrokenWikiLink,
+        LintRule::DuplicateTask,
+        LintRule::DateFormat,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintRule::HeadingIncrement => "heading-increment",
+            LintRule::ListMarker => "list-marker",
+            LintRule::TrailingWhitespace => "trailing-whitespace",
+            LintRule::BrokenWikiLink => "broken-wiki-link",
+            LintRule::DuplicateTask => "duplicate-task",
+            LintRule::DateFormat => "date-format",
+        }
+    }
+}
+
+impl FromStr for LintRule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LintRule::ALL
+            .into_iter()
+            .find(|rule| rule.as_str() == s)
+            .ok_or_else(|| Error::UnknownLintRule(s.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListMarkerStyle {
+    /// Whatever marker the first bullet list in the document uses.
+    #[default]
+    Consistent,
+    Dash,
+    Asterisk,
+    Plus,
+}
+
+/// How to read `03/04/2026`. Without one, only dates where the day is above 12 are fixed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DateOrder {
+    MonthFirst,
+    DayFirst,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LintConfig {
+    pub rules: BTreeSet<LintRule>,
+    pub list_marker: ListMarkerStyle,
+    pub date_order: Option<DateOrder>,
+    /// Lowercased titles `[[wiki links]]` may point at. Links are only checked when this is set.
+    #[serde(skip)]
+    pub known_pages: Option<HashSet<String>>,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            rules: LintRule::ALL.into_iter().collect(),
+            list_marker: ListMarkerStyle::default(),
+            date_order: None,
+            known_pages: None,
+        }
+    }
+}
+
+/// Byte offsets into the linted text, plus the 1-based line and column of `start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Replaces `start..end` of the linted text with `replacement`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Fix {
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub rule: LintRule,
+    pub message: String,
+    pub span: Span,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<Fix>,
+}
+
+pub(crate) struct Document<'a> {
+    source: &'a str,
+    ast: markdown::mdast::Node,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> Document<'a> {
+    fn parse(source: &'a str) -> Result<Self, Error> {
+        let options = markdown::ParseOptions {
+            constructs: markdown::Constructs {
+                frontmatter: true,
+                ..markdown::Constructs::gfm()
+            },
+            ..markdown::ParseOptions::gfm()
+        };
+        let ast = markdown::to_mdast(source, &options)
+            .map_err(|e| Error::MarkdownParseError(e.to_string()))?;
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Ok(Self {
+            source,
+            ast,
+            line_starts,
+        })
+    }
+
+    fn span(&self, start: usize, end: usize) -> Span {
+        let line = self.line_starts.partition_point(|&offset| offset <= start);
+        let line_start = self.line_starts[line - 1];
+        Span {
+            start,
+            end,
+            line,
+            column: self.source[line_start..start].chars().count() + 1,
+        }
+    }
+
+    fn line_start(&self, offset: usize) -> usize {
+        let line = self.line_starts.partition_point(|&o| o <= offset);
+        self.line_starts[line - 1]
+    }
+}
+
+pub fn lint_markdown(text: &str, config: &LintConfig) -> Result<Vec<Diagnostic>, Error> {
+    let doc = Document::parse(text)?;
+    let mut diagnostics = Vec::new();
+
+    for rule in &config.rules {
+        match rule {
+            LintRule::HeadingIncrement => rules::heading_increment(&doc, &mut diagnostics),
+            LintRule::ListMarker => rules::list_marker(&doc, config, &mut diagnostics),
+            LintRule::TrailingWhitespace => rules::trailing_whitespace(&doc, &mut diagnostics),
+            LintRule::BrokenWikiLink => rules::broken_wiki_link(&doc, config, &mut diagnostics),
+            LintRule::DuplicateTask => rules::duplicate_task(&doc, &mut diagnostics),
+            LintRule::DateFormat => rules::date_format(&doc, config, &mut diagnostics),
+        }
+    }
+
+    diagnostics.sort_by_key(|d| (d.span.start, d.rule));
+    Ok(diagnostics)
+}
+
+/// Applies every fix that doesn't overlap an earlier one. Skipped fixes show up again on the next
+/// lint, which is what `fix_markdown` relies on.
+pub fn apply_fixes(text: &str, diagnostics: &[Diagnostic]) -> String {
+    let mut fixes: Vec<&Fix> = diagnostics.iter().filter_map(|d| d.fix.as_ref()).collect();
+    fixes.sort_by_key(|fix| (fix.start, fix.end));
+
+    let mut out = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for fix in fixes {
+        if fix.start < cursor {
+            continue;
+        }
+        out.push_str(&text[cursor..fix.start]);
+        out.push_str(&fix.replacement);
+        cursor = fix.end;
+    }
+    out.push_str(&text[cursor..]);
+    out
+}
+
+/// Lints and fixes until nothing fixable is left, returning the fixed text and what remains.
+pub fn fix_markdown(text: &str, config: &LintConfig) -> Result<(String, Vec<Diagnostic>), Error> {
+    const MAX_PASSES: usize = 8;
+
+    let mut text = text.to_string();
+    let mut diagnostics = lint_markdown(&text, config)?;
+    for _ in 0..MAX_PASSES {
+        if diagnostics.iter().all(|d| d.fix.is_none()) {
+            break;
+        }
+        let fixed = apply_fixes(&text, &diagnostics);
+        if fixed == text {
+            break;
+        }
+        text = fixed;
+        diagnostics = lint_markdown(&text, config)?;
+    }
+    Ok((text, diagnostics))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(diagnostics: &[Diagnostic]) -> Vec<(usize, &'static str)> {
+        diagnostics
+            .iter()
+            .map(|d| (d.span.line, d.rule.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_reports_spans() {
+        let input = "---\ntitle: Plan  \n---\n# Plan\n\n### Tasks \n\n- [ ] Ship it\n* [x] Review\n\nMet on 3/14/2026 and 2026/3/15.\n";
+
+        let diagnostics = lint_markdown(input, &LintConfig::default()).unwrap();
+        assert_eq!(
+            rules(&diagnostics),
+            vec![
+                (2, "trailing-whitespace"),
+                (6, "heading-increment"),
+                (6, "trailing-whitespace"),
+                (9, "list-marker"),
+                (11, "date-format"),
+                (11, "date-format"),
+            ]
+        );
+
+        let heading = &diagnostics[1];
+        assert_eq!(heading.span.column, 1);
+        assert_eq!(&input[heading.span.start..heading.span.end], "###");
+    }
+
+    #[test]
+    fn test_fix_markdown() {
+        let input = "# Plan\n\n### Tasks\n\n#### Today\n\n* [ ] Ship it   \n- [ ] Call Sam\n* [ ] ship  it\n\nDue 2026-3-5, moved from 05/03/2026.\n";
+
+        let config = LintConfig {
+            list_marker: ListMarkerStyle::Dash,
+            ..LintConfig::default()
+        };
+        let (fixed, remaining) = fix_markdown(input, &config).unwrap();
+
+        insta::assert_snapshot!(fixed, @"
+        # Plan
+
+        ## Tasks
+
+        ### Today
+
+        - [ ] Ship it
+        - [ ] Call Sam
+
+        Due 2026-03-05, moved from 05/03/2026.
+        ");
+        assert_eq!(rules(&remaining), vec![(10, "date-format")]);
+        assert!(remaining[0].fix.is_none());
+    }
+
+    #[test]
+    fn test_broken_wiki_links() {
+        let input = "See [[Launch plan#Goals]], [[launch plan|the plan]], [[Missing]] and ![[diagram.png]].\n\n`[[Code]]`\n";
+
+        let config = LintConfig {
+            known_pages: Some(HashSet::from(["launch plan".to_string()])),
+            ..LintConfig::default()
+        };
+        let diagnostics = lint_markdown(input, &config).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            &input[diagnostics[0].span.start..diagnostics[0].span.end],
+            "[[Missing]]"
+        );
+
+        let unchecked = lint_markdown(input, &LintConfig::default()).unwrap();
+        assert!(unchecked.is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_tasks_keep_completion() {
+        let input = "- [ ] Email Alex\n- [x] Email Alex\n- [ ] Email  alex\n";
+
+        let (fixed, remaining) = fix_markdown(input, &LintConfig::default()).unwrap();
+        assert_eq!(fixed, "- [ ] Email Alex\n- [x] Email Alex\n");
+        assert_eq!(rules(&remaining), vec![(2, "duplicate-task")]);
+    }
+
+    #[test]
+    fn test_rule_names_roundtrip() {
+        for rule in LintRule::ALL {
+            assert_eq!(rule.as_str().parse::<LintRule>().unwrap(), rule);
+        }
+        assert!("nope".parse::<LintRule>().is_err());
+
+        let config: LintConfig =
+            serde_json::from_str(r#"{ "rules": ["date-format"], "dateOrder": "day-first" }"#)
+                .unwrap();
+        assert_eq!(config.rules, BTreeSet::from([LintRule::DateFormat]));
+        assert_eq!(config.date_order, Some(DateOrder::DayFirst));
+    }
+}
@@ -0,0 +1,349 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+
+use markdown::mdast::Node;
+
+use super::{DateOrder, Diagnostic, Document, Fix, LintConfig, LintRule, ListMarkerStyle};
+
+static WIKI_LINK: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"(!?)\[\[([^\[\]\n]+?)\]\]").unwrap());
+static YEAR_FIRST_DATE: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"\b(\d{4})([-/.])(\d{1,2})[-/.](\d{1,2})\b").unwrap());
+static YEAR_LAST_DATE: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"\b(\d{1,2})[/.](\d{1,2})[/.](\d{4})\b").unwrap());
+
+fn visit<'a>(node: &'a Node, f: &mut impl FnMut(&'a Node)) {
+    f(node);
+    if let Some(children) = node.children() {
+        for child in children {
+            visit(child, f);
+        }
+    }
+}
+
+fn offsets(node: &Node) -> Option<(usize, usize)> {
+    node.position()
+        .map(|position| (position.start.offset, position.end.offset))
+}
+
+/// Offset of the first non-blank character at or after `offset`; list items and headings may be
+/// indented, and their position starts before the indent.
+fn skip_indent(source: &str, offset: usize) -> usize {
+    offset + source[offset..].len() - source[offset..].trim_start_matches([' ', '\t']).len()
+}
+
+/// Source ranges of the plain text in the document, leaving out code, HTML and frontmatter.
+fn text_ranges(doc: &Document) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    visit(&doc.ast, &mut |node| {
+        if let Node::Text(_) = node
+            && let Some(range) = offsets(node)
+        {
+            ranges.push(range);
+        }
+    });
+    ranges
+}
+
+pub(super) fn heading_increment(doc: &Document, out: &mut Vec<Diagnostic>) {
+    let mut previous: Option<u8> = None;
+    visit(&doc.ast, &mut |node| {
+        let Node::Heading(heading) = node else {
+            return;
+        };
+        let Some((start, _)) = offsets(node) else {
+            return;
+        };
+
+        let depth = heading.depth;
+        let Some(prev) = previous.filter(|prev| depth > prev + 1) else {
+            previous = Some(depth);
+            return;
+        };
+
+        let fixed = prev + 1;
+        let start = skip_indent(doc.source, start);
+        let hashes = doc.source[start..]
+            .bytes()
+            .take_while(|b| *b == b'#')
+            .count();
+        // Setext headings have no `#` run to rewrite.
+        let fix = (hashes == depth as usize).then(|| Fix {
+            start,
+            end: start + hashes,
+            replacement: "#".repeat(fixed as usize),
+        });
+        out.push(Diagnostic {
+            rule: LintRule::HeadingIncrement,
+            message: format!("Heading level {depth} follows level {prev}; use level {fixed}."),
+            span: doc.span(start, start + hashes.max(1)),
+            fix,
+        });
+        previous = Some(fixed);
+    });
+}
+
+pub(super) fn list_marker(doc: &Document, config: &LintConfig, out: &mut Vec<Diagnostic>) {
+    let mut expected = match config.list_marker {
+        ListMarkerStyle::Consistent => None,
+        ListMarkerStyle::Dash => Some('-'),
+        ListMarkerStyle::Asterisk => Some('*'),
+        ListMarkerStyle::Plus => Some('+'),
+    };
+
+    visit(&doc.ast, &mut |node| {
+        let Node::List(list) = node else {
+            return;
+        };
+        if list.ordered {
+            return;
+        }
+
+        for item in &list.children {
+            let Some((start, _)) = offsets(item) else {
+                continue;
+            };
+            let start = skip_indent(doc.source, start);
+            let Some(marker) = doc.source[start..]
+                .chars()
+                .next()
+                .filter(|c| matches!(c, '-' | '*' | '+'))
+            else {
+                continue;
+            };
+
+            let expected = *expected.get_or_insert(marker);
+            if marker != expected {
+                out.push(Diagnostic {
+                    rule: LintRule::ListMarker,
+                    message: format!("Use `{expected}` for bullets instead of `{marker}`."),
+                    span: doc.span(start, start + 1),
+                    fix: Some(Fix {
+                        start,
+                        end: start + 1,
+                        replacement: expected.to_string(),
+                    }),
+                });
+            }
+        }
+    });
+}
+
+pub(super) fn trailing_whitespace(doc: &Document, out: &mut Vec<Diagnostic>) {
+    // Code keeps its whitespace, and two trailing spaces before a line ending are a hard break.
+    let mut skipped_lines = HashSet::new();
+    visit(&doc.ast, &mut |node| match node {
+        Node::Code(_) | Node::Html(_) => {
+            if let Some(position) = node.position() {
+                skipped_lines.extend(position.start.line..=position.end.line);
+            }
+        }
+        Node::Break(_) => {
+            if let Some(position) = node.position() {
+                skipped_lines.insert(position.start.line);
+            }
+        }
+        _ => {}
+    });
+
+    for (index, &line_start) in doc.line_starts.iter().enumerate() {
+        let line_end = doc
+            .line_starts
+            .get(index + 1)
+            .map_or(doc.source.len(), |next| next - 1);
+        let line = doc.source[line_start..line_end].trim_end_matches('\r');
+        let content = line.trim_end_matches([' ', '\t']);
+        if content.len() == line.len() || skipped_lines.contains(&(index + 1)) {
+            continue;
+        }
+
+        let start = line_start + content.len();
+        let end = line_start + line.len();
+        out.push(Diagnostic {
+            rule: LintRule::TrailingWhitespace,
+            message: "Trailing whitespace.".to_string(),
+            span: doc.span(start, end),
+            fix: Some(Fix {
+                start,
+                end,
+                replacement: String::new(),
+            }),
+        });
+    }
+}
+
+pub(super) fn broken_wiki_link(doc: &Document, config: &LintConfig, out: &mut Vec<Diagnostic>) {
+    let Some(known_pages) = &config.known_pages else {
+        return;
+    };
+
+    for (start, end) in text_ranges(doc) {
+        for captures in WIKI_LINK.captures_iter(&doc.source[start..end]) {
+            let whole = captures.get(0).unwrap();
+            let target = captures[2].split('|').next().unwrap_or_default();
+            let target = target.split('#').next().unwrap_or_default().trim();
+            // `[[#Heading]]` points into the same note.
+            if target.is_empty() {
+                continue;
+            }
+
+            let is_embed = !captures[1].is_empty();
+            let extension = target.rsplit_once('.').map(|(_, ext)| ext.to_lowercase());
+            if is_embed && extension.as_deref().is_some_and(|ext| ext != "md") {
+                continue;
+            }
+
+            let title = target.strip_suffix(".md").unwrap_or(target).to_lowercase();
+            let basename = title.rsplit('/').next().unwrap_or(&title);
+            if known_pages.contains(&title) || known_pages.contains(basename) {
+                continue;
+            }
+
+            out.push(Diagnostic {
+                rule: LintRule::BrokenWikiLink,
+                message: format!("No page named \"{target}\"."),
+                span: doc.span(start + whole.start(), start + whole.end()),
+                fix: None,
+            });
+        }
+    }
+}
+
+pub(super) fn duplicate_task(doc: &Document, out: &mut Vec<Diagnostic>) {
+    let mut seen: HashMap<String, bool> = HashMap::new();
+    visit(&doc.ast, &mut |node| {
+        let Node::ListItem(item) = node else {
+            return;
+        };
+        let Some(checked) = item.checked else {
+            return;
+        };
+        let Some((start, end)) = offsets(node) else {
+            return;
+        };
+
+        let key = node
+            .to_string()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+        if key.is_empty() {
+            return;
+        }
+        let Some(&first_checked) = seen.get(&key) else {
+            seen.insert(key, checked);
+            return;
+        };
+
+        // Removing a copy is only safe when it carries nothing the first one doesn't.
+        let removable = first_checked == checked
+            && item.children.len() == 1
+            && matches!(item.children[0], Node::Paragraph(_));
+        // The last item of a list may end past the blank line that follows it.
+        let end = start + doc.source[start..end].trim_end().len();
+        let fix = removable.then(|| {
+            let end = if doc.source[end..].starts_with("\r\n") {
+                end + 2
+            } else if doc.source[end..].starts_with('\n') {
+                end + 1
+            } else {
+                end
+            };
+            Fix {
+                start: doc.line_start(start),
+                end,
+                replacement: String::new(),
+            }
+        });
+        let start = skip_indent(doc.source, start);
+        out.push(Diagnostic {
+            rule: LintRule::DuplicateTask,
+            message: if first_checked == checked {
+                "Duplicate task.".to_string()
+            } else {
+                "Duplicate task with a different completion state.".to_string()
+            },
+            span: doc.span(start, end),
+            fix,
+        });
+    });
+}
+
+pub(super) fn date_format(doc: &Document, config: &LintConfig, out: &mut Vec<Diagnostic>) {
+    for (start, end) in text_ranges(doc) {
+        let text = &doc.source[start..end];
+
+        for captures in YEAR_FIRST_DATE.captures_iter(text) {
+            let whole = captures.get(0).unwrap();
+            let (year, month, day) = (&captures[1], &captures[3], &captures[4]);
+            let is_iso = &captures[2] == "-" && month.len() == 2 && day.len() == 2;
+            if is_iso {
+                continue;
+            }
+            push_date(doc, out, start, whole, iso_date(year, month, day));
+        }
+
+        for captures in YEAR_LAST_DATE.captures_iter(text) {
+            let whole = captures.get(0).unwrap();
+            let (first, second, year) = (&captures[1], &captures[2], &captures[3]);
+            let (a, b) = (
+                first.parse::<u32>().unwrap_or(0),
+                second.parse::<u32>().unwrap_or(0),
+            );
+            let order = match config.date_order {
+                Some(order) => Some(order),
+                None if a > 12 => Some(DateOrder::DayFirst),
+                None if b > 12 => Some(DateOrder::MonthFirst),
+                None => None,
+            };
+            let iso = match order {
+                Some(DateOrder::MonthFirst) => iso_date(year, first, second),
+                Some(DateOrder::DayFirst) => iso_date(year, second, first),
+                None => None,
+            };
+            push_date(doc, out, start, whole, iso);
+        }
+    }
+}
+
+fn push_date(
+    doc: &Document,
+    out: &mut Vec<Diagnostic>,
+    offset: usize,
+    found: regex::Match,
+    iso: Option<String>,
+) {
+    let (start, end) = (offset + found.start(), offset + found.end());
+    out.push(Diagnostic {
+        rule: LintRule::DateFormat,
+        message: match &iso {
+            Some(iso) => format!("Write {} as {iso}.", found.as_str()),
+            None => format!("Write {} as YYYY-MM-DD.", found.as_str()),
+        },
+        span: doc.span(start, end),
+        fix: iso.map(|replacement| Fix {
+            start,
+            end,
+            replacement,
+        }),
+    });
+}
+
+fn iso_date(year: &str, month: &str, day: &str) -> Option<String> {
+    let year: u32 = year.parse().ok()?;
+    let month: u32 = month.parse().ok()?;
+    let day: u32 = day.parse().ok()?;
+
+    let leap = year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return None,
+    };
+    (1..=days)
+        .contains(&day)
+        .then(|| format!("{year:04}-{month:02}-{day:02}"))
+}
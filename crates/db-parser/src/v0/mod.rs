@@ -208,5 +208,6 @@ pub async fn parse_from_sqlite(path: &Path) -> Result<Collection> {
         enhanced_notes,
         tags,
         tag_mappings,
+        ..Default::default()
     })
 }
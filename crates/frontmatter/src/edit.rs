@@ -0,0 +1,507 @@
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+
+use crate::Error;
+
+/// Frontmatter YAML kept as text, so an edit only rewrites the lines of the key it touches.
+///
+/// Key order, comments, blank lines, quoting and unrelated keys survive untouched. Only a
+/// top-level block mapping can be edited; every edit is re-parsed and rolled back if it
+/// would change anything but the edited key.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Frontmatter {
+    lines: Vec<String>,
+    crlf: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    key: String,
+    colon: usize,
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Quote {
+    Plain,
+    Single,
+    Double,
+}
+
+impl Frontmatter {
+    /// Parses the YAML between the `---` delimiters.
+    pub fn parse(yaml: &str) -> Result<Self, Error> {
+        let frontmatter = Self {
+            lines: yaml.lines().map(str::to_string).collect(),
+            crlf: yaml.contains("\r\n"),
+        };
+        frontmatter.validate()?;
+        Ok(frontmatter)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.iter().all(|line| line.trim().is_empty())
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        self.entries().into_iter().map(|entry| entry.key).collect()
+    }
+
+    pub fn get(&self, key: &str) -> Option<Value> {
+        self.to_mapping()
+            .ok()?
+            .into_iter()
+            .find(|(k, _)| key_string(k).as_deref() == Some(key))
+            .map(|(_, value)| value)
+    }
+
+    pub fn to_mapping(&self) -> Result<Mapping, Error> {
+        if self.is_empty() {
+            return Ok(Mapping::new());
+        }
+        match serde_yaml::from_str(&self.lines.join("\n"))? {
+            Value::Mapping(mapping) => Ok(mapping),
+            Value::Null => Ok(Mapping::new()),
+            _ => Err(Error::UnsupportedLayout),
+        }
+    }
+
+    /// Sets `key`, keeping its position, trailing comment and quoting style. New keys go last.
+    pub fn set<V: Serialize + ?Sized>(&mut self, key: &str, value: &V) -> Result<(), Error> {
+        let value = serde_yaml::to_value(value)?;
+        let previous = self.lines.clone();
+
+        for styled in [true, false] {
+            match self.entry(key) {
+                Some(entry) => {
+                    let lines = self.render_entry(&entry, &value, styled)?;
+                    self.lines.splice(entry.start..entry.end, lines);
+                }
+                None => {
+                    let head = format!("{}:", plain_text(&Value::String(key.to_string()))?);
+                    let lines = self.render_value(&head, "", &value, Layout::New, styled)?;
+                    let at = self.entries().last().map_or(self.lines.len(), |e| e.end);
+                    self.lines.splice(at..at, lines);
+                }
+            }
+            if self.check(key, Some(&value)) {
+                return Ok(());
+            }
+            self.lines = previous.clone();
+        }
+        Err(Error::InvalidEdit(key.to_string()))
+    }
+
+    /// Removes `key` and its value lines. Returns whether the key was there.
+    pub fn remove(&mut self, key: &str) -> Result<bool, Error> {
+        let Some(entry) = self.entry(key) else {
+            return Ok(false);
+        };
+        let previous = self.lines.clone();
+        self.lines.drain(entry.start..entry.end);
+        if !self.check(key, None) {
+            self.lines = previous;
+            return Err(Error::InvalidEdit(key.to_string()));
+        }
+        Ok(true)
+    }
+
+    /// Renames `from` to `to` in place. Returns whether `from` was there.
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<bool, Error> {
+        let Some(entry) = self.entry(from) else {
+            return Ok(false);
+        };
+        if from == to {
+            return Ok(true);
+        }
+        if self.entry(to).is_some() {
+            return Err(Error::DuplicateKey(to.to_string()));
+        }
+
+        let value = self.get(from);
+        let previous = self.lines.clone();
+        let line = &self.lines[entry.start];
+        let renamed = format!(
+            "{}{}",
+            plain_text(&Value::String(to.to_string()))?,
+            &line[entry.colon..]
+        );
+        self.lines[entry.start] = renamed;
+
+        if !self.check(to, value.as_ref()) || self.get(from).is_some() {
+            self.lines = previous;
+            return Err(Error::InvalidEdit(to.to_string()));
+        }
+        Ok(true)
+    }
+
+    /// Appends `item` to the list under `key`, creating the list (or wrapping a scalar) as needed.
+    pub fn append<V: Serialize + ?Sized>(&mut self, key: &str, item: &V) -> Result<(), Error> {
+        let item = serde_yaml::to_value(item)?;
+        let Some(entry) = self.entry(key) else {
+            return self.set(key, &Value::Sequence(vec![item]));
+        };
+
+        let mut items = match self.get(key) {
+            Some(Value::Sequence(items)) => items,
+            Some(Value::Null) | None => vec![],
+            Some(Value::Mapping(_)) => return Err(Error::InvalidEdit(key.to_string())),
+            Some(other) => vec![other],
+        };
+        items.push(item.clone());
+
+        // Block lists get one more `- ` line so comments between items stay put.
+        let first_item = self.lines[entry.start + 1..entry.end]
+            .iter()
+            .find(|line| line.trim_start().starts_with('-'));
+        if let Some(first_item) = first_item {
+            let indent = first_item.len() - first_item.trim_start().len();
+            let quote = quote_style(first_item.trim_start().trim_start_matches('-').trim());
+            let previous = self.lines.clone();
+            let text = match scalar_or_block(&item, quote)? {
+                text if !text.contains('\n') => format!("- {text}"),
+                _ => plain_text(&Value::Sequence(vec![item.clone()]))?,
+            };
+            let lines = indent_lines(&text, indent);
+            self.lines.splice(entry.end..entry.end, lines);
+            if self.check(key, Some(&Value::Sequence(items.clone()))) {
+                return Ok(());
+            }
+            self.lines = previous;
+        }
+        self.set(key, &Value::Sequence(items))
+    }
+
+    /// Sets every key of `target` whose value differs, with the fewest edits: unchanged keys
+    /// keep their text and new keys are appended in `target` order. Keys `target` doesn't
+    /// mention are left alone.
+    pub fn merge(&mut self, target: &Mapping) -> Result<(), Error> {
+        let current = self.to_mapping()?;
+        for (key, value) in target {
+            let Some(key) = key_string(key) else {
+                return Err(Error::UnsupportedLayout);
+            };
+            let unchanged = current
+                .iter()
+                .any(|(k, v)| key_string(k).as_deref() == Some(key.as_str()) && v == value);
+            if !unchanged {
+                self.set(&key, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Like [`Frontmatter::merge`], but also removes the keys `target` doesn't have, so the
+    /// keys and values end up matching `target` exactly.
+    pub fn merge_exact(&mut self, target: &Mapping) -> Result<(), Error> {
+        let target_keys: Vec<String> = target.keys().filter_map(key_string).collect();
+        for key in self.keys() {
+            if !target_keys.contains(&key) {
+                self.remove(&key)?;
+            }
+        }
+        self.merge(target)
+    }
+
+    /// The YAML between the delimiters, one terminated line per line.
+    pub fn render(&self) -> String {
+        let newline = if self.crlf { "\r\n" } else { "\n" };
+        self.lines
+            .iter()
+            .map(|line| format!("{line}{newline}"))
+            .collect()
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        let mapping = self.to_mapping()?;
+        let keys = self.keys();
+        let matches = keys.len() == mapping.len()
+            && mapping
+                .keys()
+                .all(|k| key_string(k).is_some_and(|k| keys.contains(&k)));
+        if matches {
+            Ok(())
+        } else {
+            Err(Error::UnsupportedLayout)
+        }
+    }
+
+    fn check(&self, key: &str, expected: Option<&Value>) -> bool {
+        self.validate().is_ok() && self.get(key).as_ref() == expected
+    }
+
+    fn entry(&self, key: &str) -> Option<Entry> {
+        self.entries().into_iter().find(|entry| entry.key == key)
+    }
+
+    fn entries(&self) -> Vec<Entry> {
+        let mut entries: Vec<Entry> = Vec::new();
+        for (index, line) in self.lines.iter().enumerate() {
+            if let Some((key, colon)) = top_level_key(line) {
+                entries.push(Entry {
+                    key,
+                    colon,
+                    start: index,
+                    end: index + 1,
+                });
+            } else if is_continuation(line)
+                && let Some(entry) = entries.last_mut()
+                && self.lines[entry.end..index]
+                    .iter()
+                    .all(|line| line.trim().is_empty() || line.trim_start().starts_with('#'))
+            {
+                entry.end = index + 1;
+            }
+        }
+        entries
+    }
+
+    /// Indent of the first block list in the document, so new lists look like the old ones.
+    fn sequence_indent(&self) -> usize {
+        self.entries()
+            .iter()
+            .filter_map(|entry| self.lines.get(entry.start + 1))
+            .find(|line| line.trim_start().starts_with("- "))
+            .map_or(2, |line| line.len() - line.trim_start().len())
+    }
+
+    fn render_entry(
+        &self,
+        entry: &Entry,
+        value: &Value,
+        styled: bool,
+    ) -> Result<Vec<String>, Error> {
+        let line = &self.lines[entry.start];
+        let head = &line[..=entry.colon];
+        let (current, comment) = split_comment(&line[entry.colon + 1..]);
+
+        let layout = if current.starts_with('[') || current.starts_with('{') {
+            Layout::Flow
+        } else if current.is_empty() {
+            let indent = self.lines[entry.start + 1..entry.end]
+                .iter()
+                .find(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+                .map(|line| line.len() - line.trim_start().len());
+            match indent {
+                Some(indent) => Layout::Block(indent),
+                None => Layout::New,
+            }
+        } else {
+            Layout::Scalar(quote_style(current))
+        };
+
+        self.render_value(head, comment, value, layout, styled)
+    }
+
+    fn render_value(
+        &self,
+        head: &str,
+        comment: &str,
+        value: &Value,
+        layout: Layout,
+        styled: bool,
+    ) -> Result<Vec<String>, Error> {
+        let quote = match layout {
+            Layout::Scalar(quote) if styled => quote,
+            _ => Quote::Plain,
+        };
+
+        match value {
+            Value::Sequence(items) if !items.is_empty() => {
+                if styled
+                    && layout == Layout::Flow
+                    && let Some(flow) = flow_sequence(items)?
+                {
+                    return Ok(vec![format!("{head} {flow}{comment}")]);
+                }
+                let indent = match layout {
+                    Layout::Block(indent) if styled => indent,
+                    _ => self.sequence_indent(),
+                };
+                let mut lines = vec![format!("{head}{comment}")];
+                lines.extend(indent_lines(&plain_text(value)?, indent));
+                Ok(lines)
+            }
+            Value::Mapping(mapping) if !mapping.is_empty() => {
+                let indent = match layout {
+                    Layout::Block(indent) if styled && indent > 0 => indent,
+                    _ => 2,
+                };
+                let mut lines = vec![format!("{head}{comment}")];
+                lines.extend(indent_lines(&plain_text(value)?, indent));
+                Ok(lines)
+            }
+            _ => {
+                let text = scalar_or_block(value, quote)?;
+                let mut text_lines = text.lines();
+                let first = text_lines.next().unwrap_or_default();
+                let mut lines = vec![format!("{head} {first}{comment}")];
+                lines.extend(text_lines.map(str::to_string));
+                Ok(lines)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Layout {
+    New,
+    Scalar(Quote),
+    Flow,
+    Block(usize),
+}
+
+fn top_level_key(line: &str) -> Option<(String, usize)> {
+    let first = line.chars().next()?;
+    if first.is_whitespace()
+        || matches!(
+            first,
+            '#' | '-' | '{' | '[' | '?' | '%' | '&' | '*' | '!' | '|' | '>'
+        )
+        || line.starts_with("...")
+    {
+        return None;
+    }
+
+    if first == '"' || first == '\'' {
+        let end = closing_quote(line, first)?;
+        let after = &line[end + 1..];
+        let colon = end + 1 + after.find(|c: char| c != ' ')?;
+        if !line[colon..].starts_with(':') || !ends_key(&line[colon + 1..]) {
+            return None;
+        }
+        let key: String = serde_yaml::from_str(&line[..=end]).ok()?;
+        return Some((key, colon));
+    }
+
+    let colon = line
+        .match_indices(':')
+        .map(|(index, _)| index)
+        .find(|index| ends_key(&line[index + 1..]))?;
+    Some((line[..colon].trim_end().to_string(), colon))
+}
+
+fn ends_key(rest: &str) -> bool {
+    rest.is_empty() || rest.starts_with(' ') || rest.starts_with('\t')
+}
+
+fn closing_quote(line: &str, quote: char) -> Option<usize> {
+    let mut chars = line.char_indices().skip(1).peekable();
+    while let Some((index, c)) = chars.next() {
+        if quote == '"' && c == '\\' {
+            chars.next();
+        } else if c == quote {
+            if quote == '\'' && chars.peek().is_some_and(|(_, next)| *next == '\'') {
+                chars.next();
+                continue;
+            }
+            return Some(index);
+        }
+    }
+    None
+}
+
+fn is_continuation(line: &str) -> bool {
+    line.starts_with(' ') || line.starts_with('\t') || line == "-" || line.starts_with("- ")
+}
+
+/// Splits `value # comment` into the value and the comment with its leading whitespace.
+fn split_comment(rest: &str) -> (&str, &str) {
+    let mut quote = None;
+    let mut escaped = false;
+    let mut previous_space = true;
+    for (index, c) in rest.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if q == '"' && c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+        } else if (c == '"' || c == '\'') && previous_space {
+            quote = Some(c);
+        } else if c == '#' && previous_space {
+            let value = rest[..index].trim_end();
+            return (value.trim_start(), &rest[value.len()..]);
+        }
+        previous_space = c.is_whitespace();
+    }
+    (rest.trim(), "")
+}
+
+fn quote_style(text: &str) -> Quote {
+    match text.chars().next() {
+        Some('"') => Quote::Double,
+        Some('\'') => Quote::Single,
+        _ => Quote::Plain,
+    }
+}
+
+fn scalar_or_block(value: &Value, quote: Quote) -> Result<String, Error> {
+    match (value, quote) {
+        (Value::String(s), Quote::Double) if !s.contains('\n') => Ok(double_quoted(s)),
+        (Value::String(s), Quote::Single) if !s.contains('\n') => {
+            Ok(format!("'{}'", s.replace('\'', "''")))
+        }
+        _ => plain_text(value),
+    }
+}
+
+fn flow_sequence(items: &[Value]) -> Result<Option<String>, Error> {
+    let mut rendered = Vec::with_capacity(items.len());
+    for item in items {
+        let text = plain_text(item)?;
+        if matches!(item, Value::Sequence(_) | Value::Mapping(_)) || text.contains('\n') {
+            return Ok(None);
+        }
+        rendered.push(text);
+    }
+    Ok(Some(format!("[{}]", rendered.join(", "))))
+}
+
+fn plain_text(value: &Value) -> Result<String, Error> {
+    Ok(serde_yaml::to_string(value)?
+        .trim_end_matches('\n')
+        .to_string())
+}
+
+fn double_quoted(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04X}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn indent_lines(text: &str, indent: usize) -> Vec<String> {
+    let pad = " ".repeat(indent);
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{pad}{line}")
+            }
+        })
+        .collect()
+}
+
+fn key_string(key: &Value) -> Option<String> {
+    match key {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
@@ -8,4 +8,10 @@ pub enum Error {
     MissingClosingDelimiter,
     #[error("failed to parse YAML frontmatter: {0}")]
     YamlParse(#[from] serde_yaml::Error),
+    #[error("frontmatter must be a mapping with one plain key per line to be edited")]
+    UnsupportedLayout,
+    #[error("key {0:?} already exists")]
+    DuplicateKey(String),
+    #[error("editing {0:?} would change other keys or break the YAML")]
+    InvalidEdit(String),
 }
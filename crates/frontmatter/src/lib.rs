@@ -1,7 +1,9 @@
 mod document;
+mod edit;
 mod error;
 
 pub use document::Document;
+pub use edit::Frontmatter;
 pub use error::Error;
 
 #[cfg(test)]
@@ -310,4 +312,181 @@ And another --- line."#;
             assert_eq!(doc.content, parsed.content);
         }
     }
+
+    mod edit {
+        use super::*;
+
+        const YAML: &str = r#"# Managed by hand
+type: meeting
+title: "Launch review" # shown in the sidebar
+participants:
+  - Alex
+  - 'Priya'
+
+aliases: [Launch, Review]
+status: draft
+"#;
+
+        #[test]
+        fn untouched_roundtrip() {
+            let fm = Frontmatter::parse(YAML).unwrap();
+            assert_eq!(fm.render(), YAML);
+            assert_eq!(
+                fm.keys(),
+                ["type", "title", "participants", "aliases", "status"]
+            );
+        }
+
+        #[test]
+        fn set_keeps_quoting_and_comments() {
+            let mut fm = Frontmatter::parse(YAML).unwrap();
+            fm.set("title", "Launch \"v2\" review").unwrap();
+            fm.set("status", &true).unwrap();
+            fm.set("location", "HQ").unwrap();
+
+            insta::assert_snapshot!(fm.render(), @r#"
+            # Managed by hand
+            type: meeting
+            title: "Launch \"v2\" review" # shown in the sidebar
+            participants:
+              - Alex
+              - 'Priya'
+
+            aliases: [Launch, Review]
+            status: true
+            location: HQ
+            "#);
+        }
+
+        #[test]
+        fn set_collections() {
+            let mut fm = Frontmatter::parse(YAML).unwrap();
+            fm.set("aliases", &["Launch", "Kickoff"]).unwrap();
+            fm.set("participants", &["Sam"]).unwrap();
+            fm.set("tags", &["work", "q2"]).unwrap();
+
+            insta::assert_snapshot!(fm.render(), @r#"
+            # Managed by hand
+            type: meeting
+            title: "Launch review" # shown in the sidebar
+            participants:
+              - Sam
+
+            aliases: [Launch, Kickoff]
+            status: draft
+            tags:
+              - work
+              - q2
+            "#);
+        }
+
+        #[test]
+        fn append_to_lists() {
+            let mut fm = Frontmatter::parse(YAML).unwrap();
+            fm.append("participants", "Sam").unwrap();
+            fm.append("aliases", "Kickoff").unwrap();
+            fm.append("type", "project").unwrap();
+            fm.append("tags", "work").unwrap();
+
+            insta::assert_snapshot!(fm.render(), @r#"
+            # Managed by hand
+            type:
+              - meeting
+              - project
+            title: "Launch review" # shown in the sidebar
+            participants:
+              - Alex
+              - 'Priya'
+              - Sam
+
+            aliases: [Launch, Review, Kickoff]
+            status: draft
+            tags:
+              - work
+            "#);
+        }
+
+        #[test]
+        fn remove_and_rename() {
+            let mut fm = Frontmatter::parse(YAML).unwrap();
+            assert!(fm.remove("participants").unwrap());
+            assert!(!fm.remove("missing").unwrap());
+            assert!(fm.rename("status", "state").unwrap());
+            assert!(matches!(
+                fm.rename("state", "type"),
+                Err(Error::DuplicateKey(key)) if key == "type"
+            ));
+
+            insta::assert_snapshot!(fm.render(), @r#"
+            # Managed by hand
+            type: meeting
+            title: "Launch review" # shown in the sidebar
+
+            aliases: [Launch, Review]
+            state: draft
+            "#);
+        }
+
+        #[test]
+        fn merge_keeps_omitted_keys() {
+            let mut fm = Frontmatter::parse(YAML).unwrap();
+            let target: serde_yaml::Mapping =
+                serde_yaml::from_str("status: done\nrating: 4").unwrap();
+            fm.merge(&target).unwrap();
+
+            insta::assert_snapshot!(fm.render(), @r#"
+            # Managed by hand
+            type: meeting
+            title: "Launch review" # shown in the sidebar
+            participants:
+              - Alex
+              - 'Priya'
+
+            aliases: [Launch, Review]
+            status: done
+            rating: 4
+            "#);
+        }
+
+        #[test]
+        fn merge_exact_touches_only_changed_keys() {
+            let mut fm = Frontmatter::parse(YAML).unwrap();
+            let target: serde_yaml::Mapping = serde_yaml::from_str(
+                "type: meeting\ntitle: Launch review\naliases: [Launch, Review]\nstatus: done\nrating: 4",
+            )
+            .unwrap();
+            fm.merge_exact(&target).unwrap();
+
+            insta::assert_snapshot!(fm.render(), @r#"
+            # Managed by hand
+            type: meeting
+            title: "Launch review" # shown in the sidebar
+
+            aliases: [Launch, Review]
+            status: done
+            rating: 4
+            "#);
+        }
+
+        #[test]
+        fn unsupported_layouts() {
+            assert!(matches!(
+                Frontmatter::parse("{a: 1, b: 2}"),
+                Err(Error::UnsupportedLayout)
+            ));
+            assert!(matches!(
+                Frontmatter::parse("- a\n- b"),
+                Err(Error::UnsupportedLayout)
+            ));
+            assert!(Frontmatter::parse("").unwrap().is_empty());
+        }
+
+        #[test]
+        fn windows_line_endings() {
+            let mut fm = Frontmatter::parse("a: 1\r\nb: 2\r\n").unwrap();
+            fm.set("b", &3).unwrap();
+            assert_eq!(fm.render(), "a: 1\r\nb: 3\r\n");
+        }
+    }
 }
+
@@ -16,6 +16,8 @@ pub struct Collection {
     pub enhanced_notes: Vec<EnhancedNote>,
     pub tags: Vec<Tag>,
     pub tag_mappings: Vec<TagMapping>,
+    pub notes: Vec<Note>,
+    pub attachments: Vec<Attachment>,
 }
 
 impl std::fmt::Display for Collection {
@@ -29,6 +31,21 @@ impl std::fmt::Display for Collection {
         writeln!(f, "enhanced_notes: {}", self.enhanced_notes.len())?;
         writeln!(f, "tags: {}", self.tags.len())?;
         writeln!(f, "tag_mappings: {}", self.tag_mappings.len())?;
+        if !self.notes.is_empty() || !self.attachments.is_empty() {
+            let daily = self
+                .notes
+                .iter()
+                .filter(|n| n.kind == NoteKind::Daily)
+                .count();
+            writeln!(
+                f,
+                "notes: {} ({} daily, {} pages)",
+                self.notes.len(),
+                daily,
+                self.notes.len() - daily
+            )?;
+            writeln!(f, "attachments: {}", self.attachments.len())?;
+        }
 
         if let Some(s) = self.sessions.first() {
             writeln!(f, "\n[First Session]")?;
@@ -75,6 +92,21 @@ impl std::fmt::Display for Collection {
             }
         }
 
+        if let Some(n) = self.notes.first() {
+            writeln!(f, "\n[First Note]")?;
+            writeln!(f, "  id: {}", n.id)?;
+            writeln!(f, "  kind: {:?}", n.kind)?;
+            writeln!(f, "  title: {}", n.title)?;
+            if let Some(ref date) = n.date {
+                writeln!(f, "  date: {}", date)?;
+            }
+            if !n.tags.is_empty() {
+                writeln!(f, "  tags: {}", n.tags.join(", "))?;
+            }
+            let preview: String = n.body_md.chars().take(100).collect();
+            writeln!(f, "  body_md: {}...", preview)?;
+        }
+
         if let Some(h) = self.humans.first() {
             writeln!(f, "\n[First Human]")?;
             writeln!(f, "  id: {}", h.id)?;
@@ -274,3 +306,55 @@ common_derives! {
         pub session_id: String,
     }
 }
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteKind {
+    Daily,
+    #[default]
+    Page,
+}
+
+common_derives! {
+    /// A markdown document from a notes app, as opposed to a recorded session.
+    pub struct Note {
+        pub id: String,
+        #[serde(default)]
+        pub kind: NoteKind,
+        #[serde(default)]
+        pub title: String,
+        /// `YYYY-MM-DD`, for daily notes.
+        #[serde(default)]
+        pub date: Option<String>,
+        #[serde(default)]
+        pub created_at: String,
+        #[serde(default)]
+        pub updated_at: String,
+        #[serde(default)]
+        pub body_md: String,
+        /// Written out as frontmatter.
+        #[serde(default)]
+        pub properties: serde_json::Map<String, serde_json::Value>,
+        #[serde(default)]
+        pub tags: Vec<String>,
+        #[serde(default)]
+        pub aliases: Vec<String>,
+        /// Where the note came from inside the export, for reports.
+        #[serde(default)]
+        pub source_path: String,
+    }
+}
+
+common_derives! {
+    pub struct Attachment {
+        pub id: String,
+        #[serde(default)]
+        pub note_id: String,
+        #[serde(default)]
+        pub file_name: String,
+        #[serde(default)]
+        pub source_path: String,
+        #[serde(skip)]
+        pub data: Vec<u8>,
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+
+use crate::{Error, Result};
+
+trait ReadSeek: Read + Seek {}
+impl<T: Read + Seek> ReadSeek for T {}
+
+enum Part {
+    Dir(PathBuf),
+    Zip(zip::ZipArchive<Box<dyn ReadSeek>>),
+}
+
+/// The files of an export, whether it was handed over as a folder or a zip. Paths are relative,
+/// `/`-separated, and skip hidden files and macOS resource forks.
+pub(crate) struct Archive {
+    parts: Vec<Part>,
+    /// Normalized path to the part holding it and its name there.
+    entries: BTreeMap<String, (usize, String)>,
+}
+
+impl Archive {
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut archive = Self {
+            parts: Vec::new(),
+            entries: BTreeMap::new(),
+        };
+
+        if path.is_dir() {
+            archive.parts.push(Part::Dir(path.to_path_buf()));
+            let mut files = Vec::new();
+            walk(path, path, &mut files)?;
+            for file in files {
+                archive.entries.insert(file.clone(), (0, file));
+            }
+        } else {
+            let file: Box<dyn ReadSeek> = Box::new(File::open(path)?);
+            archive.add_zip(file, true)?;
+        }
+
+        if archive.entries.is_empty() {
+            return Err(Error::InvalidExport(format!(
+                "{} contains no files",
+                path.display()
+            )));
+        }
+        Ok(archive)
+    }
+
+    /// Notion splits large exports into `Export-…-Part-N.zip` files inside the outer zip, so zips
+    /// found at the top level are opened as well.
+    fn add_zip(&mut self, reader: Box<dyn ReadSeek>, expand_nested: bool) -> Result<()> {
+        let mut zip = zip::ZipArchive::new(reader)?;
+        let mut nested = Vec::new();
+        let index = self.parts.len();
+
+        for i in 0..zip.len() {
+            let mut entry = zip.by_index(i)?;
+            if entry.is_dir() {
+                continue;
+            }
+            let name = normalize(entry.name());
+            if is_hidden(&name) {
+                continue;
+            }
+            if expand_nested && name.ends_with(".zip") && !name.contains('/') {
+                let mut data = Vec::new();
+                entry.read_to_end(&mut data)?;
+                nested.push(data);
+                continue;
+            }
+            self.entries.insert(name, (index, entry.name().to_string()));
+        }
+        self.parts.push(Part::Zip(zip));
+
+        for data in nested {
+            self.add_zip(Box::new(Cursor::new(data)), false)?;
+        }
+        Ok(())
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.entries.contains_key(path)
+    }
+
+    pub fn read(&mut self, path: &str) -> Result<Vec<u8>> {
+        let Some((index, name)) = self.entries.get(path) else {
+            return Err(Error::InvalidExport(format!("{path} is not in the export")));
+        };
+
+        match &mut self.parts[*index] {
+            Part::Dir(root) => Ok(std::fs::read(root.join(name))?),
+            Part::Zip(zip) => {
+                let mut entry = zip.by_name(name)?;
+                let mut data = Vec::new();
+                entry.read_to_end(&mut data)?;
+                Ok(data)
+            }
+        }
+    }
+
+    pub fn read_string(&mut self, path: &str) -> Result<String> {
+        let data = self.read(path)?;
+        Ok(match String::from_utf8(data) {
+            Ok(text) => text,
+            Err(err) => String::from_utf8_lossy(err.as_bytes()).into_owned(),
+        })
+    }
+}
+
+fn walk(root: &Path, dir: &Path, files: &mut Vec<String>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        let relative = normalize(&relative.to_string_lossy());
+        if is_hidden(&relative) {
+            continue;
+        }
+
+        if entry.file_type()?.is_dir() {
+            walk(root, &path, files)?;
+        } else {
+            files.push(relative);
+        }
+    }
+    Ok(())
+}
+
+fn normalize(name: &str) -> String {
+    name.replace('\\', "/").trim_start_matches('/').to_string()
+}
+
+fn is_hidden(path: &str) -> bool {
+    path.split('/')
+        .any(|part| part.starts_with('.') || part == "__MACOSX")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn zip_of(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, data) in files {
+            writer
+                .start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn reads_nested_zip_parts() {
+        let part = zip_of(&[("Page abc.md", b"# Page\n"), ("__MACOSX/._Page", b"x")]);
+        let outer = zip_of(&[("Export-Part-1.zip", &part), (".DS_Store", b"x")]);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.zip");
+        std::fs::write(&path, outer).unwrap();
+
+        let mut archive = Archive::open(&path).unwrap();
+        assert_eq!(archive.paths().collect::<Vec<_>>(), vec!["Page abc.md"]);
+        assert_eq!(archive.read_string("Page abc.md").unwrap(), "# Page\n");
+    }
+}
@@ -0,0 +1,208 @@
+//! Bear's backup (`.bear2bk`) and TextBundle/TextPack exports: one `Note.textbundle/` per note
+//! with `text.markdown`, `info.json` and `assets/`. A folder of plain markdown files, Bear's other
+//! export format, is read as well.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use hypr_importer_core::ir::{Note, NoteKind};
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::archive::Archive;
+use crate::common::{
+    date_from_title, decode, extract_tags, file_stem, is_external, note_id, parent_dir,
+    resolve_relative, rewrite_links, Attachments,
+};
+use crate::{Error, Import, Result, Source};
+
+/// Bear's `#multi word tags#`.
+static MULTI_WORD_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(^|\s)#([^#\s][^#\n]*?[^#\s])#").unwrap());
+
+const TEXT_FILES: [&str; 3] = ["text.markdown", "text.md", "text.txt"];
+
+struct BearInfo {
+    created_at: String,
+    updated_at: String,
+    trashed: bool,
+    archived: bool,
+    pinned: bool,
+}
+
+pub fn parse_from_backup(path: &Path) -> Result<Import> {
+    let mut archive = Archive::open(path)?;
+    let paths: Vec<String> = archive.paths().map(str::to_string).collect();
+
+    // Bundle root → its text file.
+    let mut bundles: BTreeMap<String, String> = BTreeMap::new();
+    for path in &paths {
+        let Some((root, file)) = path.split_once(".textbundle/") else {
+            continue;
+        };
+        if TEXT_FILES.contains(&file) {
+            bundles
+                .entry(format!("{root}.textbundle"))
+                .or_insert_with(|| path.clone());
+        }
+    }
+    let loose: Vec<String> = paths
+        .iter()
+        .filter(|path| !path.contains(".textbundle/"))
+        .filter(|path| path.ends_with(".md") || path.ends_with(".markdown"))
+        .cloned()
+        .collect();
+    if bundles.is_empty() && loose.is_empty() {
+        return Err(Error::InvalidExport(
+            "no notes found; export from Bear as a backup, TextBundle or Markdown".to_string(),
+        ));
+    }
+
+    let mut import = Import::default();
+    let mut attachments = Attachments::default();
+    let notes = bundles
+        .iter()
+        .map(|(root, text)| (root.clone(), text.clone(), true))
+        .chain(loose.into_iter().map(|path| {
+            let root = parent_dir(&path).to_string();
+            (root, path, false)
+        }));
+
+    for (root, text_path, is_bundle) in notes {
+        let info = if is_bundle {
+            read_info(&mut archive, &format!("{root}/info.json"))
+        } else {
+            None
+        };
+        if info.as_ref().is_some_and(|info| info.trashed) {
+            import
+                .warnings
+                .push(format!("{root}: skipped a note from Bear's trash"));
+            continue;
+        }
+
+        let raw = archive.read_string(&text_path)?;
+        let source_path = if is_bundle {
+            root.clone()
+        } else {
+            text_path.clone()
+        };
+        let id = note_id(Source::Bear, &source_path);
+
+        let mut lines = raw
+            .lines()
+            .skip_while(|line| line.trim().is_empty())
+            .peekable();
+        let title = match lines.peek() {
+            Some(line) if line.starts_with("# ") => {
+                let title = line[2..].trim().to_string();
+                lines.next();
+                title
+            }
+            Some(line) => line.trim().trim_start_matches('#').trim().to_string(),
+            None => file_stem(&source_path).to_string(),
+        };
+        let body = lines
+            .skip_while(|line| line.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let body = MULTI_WORD_TAG
+            .replace_all(&body, |captures: &regex::Captures| {
+                format!("{}#{}", &captures[1], captures[2].replace(' ', "-"))
+            })
+            .into_owned();
+
+        let mut warnings = Vec::new();
+        let base = if is_bundle {
+            root.as_str()
+        } else {
+            parent_dir(&text_path)
+        };
+        let body = rewrite_links(&body, |is_image, text, target| {
+            if is_external(target) {
+                return None;
+            }
+            let resolved = resolve_relative(base, &decode(target));
+            if !archive.contains(&resolved) {
+                warnings.push(format!("{source_path}: broken link to {target}"));
+                return None;
+            }
+            match attachments.add(&mut archive, &mut import.collection, &id, &resolved) {
+                Ok(link) => {
+                    let bang = if is_image { "!" } else { "" };
+                    Some(format!("{bang}[{text}]({link})"))
+                }
+                Err(err) => {
+                    warnings.push(format!("{source_path}: could not read {resolved}: {err}"));
+                    None
+                }
+            }
+        });
+        import.warnings.extend(warnings);
+
+        let mut properties = Map::new();
+        if let Some(info) = &info {
+            if info.archived {
+                properties.insert("archived".to_string(), Value::Bool(true));
+            }
+            if info.pinned {
+                properties.insert("pinned".to_string(), Value::Bool(true));
+            }
+        }
+
+        let date = date_from_title(&title);
+        import.collection.notes.push(Note {
+            id,
+            kind: if date.is_some() {
+                NoteKind::Daily
+            } else {
+                NoteKind::Page
+            },
+            tags: extract_tags(&body),
+            title,
+            date,
+            created_at: info
+                .as_ref()
+                .map(|info| info.created_at.clone())
+                .unwrap_or_default(),
+            updated_at: info
+                .as_ref()
+                .map(|info| info.updated_at.clone())
+                .unwrap_or_default(),
+            body_md: body.trim_end().to_string() + "\n",
+            properties,
+            aliases: Vec::new(),
+            source_path,
+        });
+    }
+
+    Ok(import)
+}
+
+fn read_info(archive: &mut Archive, path: &str) -> Option<BearInfo> {
+    if !archive.contains(path) {
+        return None;
+    }
+    let info: Value = serde_json::from_slice(&archive.read(path).ok()?).ok()?;
+    let bear = info.get("net.shinyfrog.bear")?;
+    let flag = |key: &str| match bear.get(key) {
+        Some(Value::Bool(value)) => *value,
+        Some(Value::Number(value)) => value.as_i64().is_some_and(|value| value != 0),
+        _ => false,
+    };
+    let text = |key: &str| {
+        bear.get(key)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    Some(BearInfo {
+        created_at: text("creationDate"),
+        updated_at: text("modificationDate"),
+        trashed: flag("trashed"),
+        archived: flag("archived"),
+        pinned: flag("pinned"),
+    })
+}
@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use chrono::NaiveDate;
+use hypr_importer_core::ir::{Attachment, Collection};
+use regex::Regex;
+use serde_json::Value;
+
+use crate::archive::Archive;
+use crate::{Result, Source};
+
+/// Bodies link to attachments as `attachment:<id>` until the vault writer knows their final path.
+pub(crate) const ATTACHMENT_SCHEME: &str = "attachment:";
+
+static MARKDOWN_LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(!?)\[([^\]\n]*)\]\(([^)\n]+)\)").unwrap());
+static ORDINAL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(\d{1,2})(?:st|nd|rd|th)\b").unwrap());
+static TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|[\s(])#([\p{L}\p{N}_][\p{L}\p{N}_/-]*)").unwrap());
+static INLINE_CODE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"`[^`\n]*`").unwrap());
+
+pub(crate) fn note_id(source: Source, path: &str) -> String {
+    format!("{}:{}", source.as_str(), path)
+}
+
+/// The `YYYY-MM-DD` a title names, for titles like `2024-03-05`, `2024_03_05`, `March 5, 2024`
+/// or `Mar 5th, 2024`.
+pub(crate) fn date_from_title(title: &str) -> Option<String> {
+    const FORMATS: [&str; 8] = [
+        "%Y-%m-%d",
+        "%Y_%m_%d",
+        "%Y/%m/%d",
+        "%Y%m%d",
+        "%B %d, %Y",
+        "%b %d, %Y",
+        "%A, %B %d, %Y",
+        "%d %B %Y",
+    ];
+
+    let title = ORDINAL.replace_all(title.trim(), "$1");
+    FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(&title, format).ok())
+        .map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+/// `#tags` in the body, outside code, in order of first use.
+pub(crate) fn extract_tags(body: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    let mut in_fence = false;
+    for line in body.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let line = INLINE_CODE.replace_all(line, "");
+        for captures in TAG.captures_iter(&line) {
+            let tag = captures[1].trim_end_matches('/').to_string();
+            if tag.chars().all(|c| c.is_ascii_digit()) || tags.contains(&tag) {
+                continue;
+            }
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Calls `rewrite(is_image, text, target)` for every inline markdown link and replaces the link
+/// with its result, when there is one.
+pub(crate) fn rewrite_links(
+    body: &str,
+    mut rewrite: impl FnMut(bool, &str, &str) -> Option<String>,
+) -> String {
+    MARKDOWN_LINK
+        .replace_all(body, |captures: &regex::Captures| {
+            let target = captures[3].trim();
+            let target = target
+                .strip_prefix('<')
+                .and_then(|rest| rest.strip_suffix('>'))
+                .unwrap_or_else(|| target.split(" \"").next().unwrap_or(target));
+            rewrite(!captures[1].is_empty(), &captures[2], target)
+                .unwrap_or_else(|| captures[0].to_string())
+        })
+        .into_owned()
+}
+
+pub(crate) fn is_external(target: &str) -> bool {
+    target.contains("://")
+        || target.starts_with('#')
+        || target.starts_with("mailto:")
+        || target.starts_with("data:")
+}
+
+pub(crate) fn decode(target: &str) -> String {
+    urlencoding::decode(target)
+        .map(|decoded| decoded.into_owned())
+        .unwrap_or_else(|_| target.to_string())
+}
+
+/// Joins `target` onto the directory `base` (both archive paths) and folds `.` and `..`.
+pub(crate) fn resolve_relative(base: &str, target: &str) -> String {
+    let mut parts: Vec<&str> = if target.starts_with('/') {
+        Vec::new()
+    } else {
+        base.split('/').filter(|part| !part.is_empty()).collect()
+    };
+    for part in target.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+pub(crate) fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+pub(crate) fn file_stem(path: &str) -> &str {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name.rsplit_once('.').map_or(name, |(stem, _)| stem)
+}
+
+pub(crate) fn property_key(name: &str) -> String {
+    name.trim()
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// A property value as text from an export: dates become `YYYY-MM-DD`, booleans and numbers keep
+/// their type.
+pub(crate) fn property_value(raw: &str) -> Value {
+    let raw = raw.trim();
+    match raw {
+        "true" | "Yes" => return Value::Bool(true),
+        "false" | "No" => return Value::Bool(false),
+        _ => {}
+    }
+    if let Ok(number) = raw.parse::<i64>() {
+        return Value::from(number);
+    }
+    if let Some(date) = date_from_title(raw) {
+        return Value::String(date);
+    }
+    Value::String(raw.to_string())
+}
+
+/// Splits `a, b` and `[[a]], [[b]]` lists.
+pub(crate) fn split_list(raw: &str) -> Vec<String> {
+    raw.split(',')
+        .map(|item| {
+            item.trim()
+                .trim_start_matches("[[")
+                .trim_end_matches("]]")
+                .trim_start_matches('#')
+                .trim()
+                .to_string()
+        })
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// RFC 4180 rows, as Notion writes them.
+pub(crate) fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => row.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (c, _) => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+/// Attachments read out of the export so far, so a file linked twice is stored once.
+#[derive(Default)]
+pub(crate) struct Attachments {
+    by_path: HashMap<String, String>,
+}
+
+impl Attachments {
+    /// Copies `path` out of the archive and returns the link target that stands in for it.
+    pub fn add(
+        &mut self,
+        archive: &mut Archive,
+        collection: &mut Collection,
+        note_id: &str,
+        path: &str,
+    ) -> Result<String> {
+        if let Some(id) = self.by_path.get(path) {
+            return Ok(format!("{ATTACHMENT_SCHEME}{id}"));
+        }
+
+        let id = format!("a{}", collection.attachments.len() + 1);
+        collection.attachments.push(Attachment {
+            id: id.clone(),
+            note_id: note_id.to_string(),
+            file_name: path.rsplit('/').next().unwrap_or(path).to_string(),
+            source_path: path.to_string(),
+            data: archive.read(path)?,
+        });
+        self.by_path.insert(path.to_string(), id.clone());
+        Ok(format!("{ATTACHMENT_SCHEME}{id}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_titles_as_dates() {
+        assert_eq!(date_from_title("2024_03_05").as_deref(), Some("2024-03-05"));
+        assert_eq!(
+            date_from_title("Mar 5th, 2024").as_deref(),
+            Some("2024-03-05")
+        );
+        assert_eq!(
+            date_from_title("March 15, 2024").as_deref(),
+            Some("2024-03-15")
+        );
+        assert_eq!(date_from_title("Roadmap 2024"), None);
+    }
+
+    #[test]
+    fn extracts_tags_outside_code() {
+        let body = "Notes #work and #work/1on1\n`#not` #123\n```\n#skip\n```\n(#last)";
+        assert_eq!(extract_tags(body), vec!["work", "work/1on1", "last"]);
+    }
+
+    #[test]
+    fn parses_quoted_csv() {
+        let rows = parse_csv("\u{feff}Name,Tags\n\"Plan, v2\",\"a, \"\"b\"\"\"\r\nSolo,\n");
+        assert_eq!(
+            rows,
+            vec![
+                vec!["Name".to_string(), "Tags".to_string()],
+                vec!["Plan, v2".to_string(), "a, \"b\"".to_string()],
+                vec!["Solo".to_string(), String::new()],
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_relative_paths() {
+        assert_eq!(
+            resolve_relative("pages/sub", "../assets/a.png"),
+            "pages/assets/a.png"
+        );
+        assert_eq!(resolve_relative("", "./b.md"), "b.md");
+    }
+}
@@ -0,0 +1,243 @@
+//! Importers for notes exported from other apps. Each source is parsed into the shared
+//! `hypr_importer_core::ir::Collection`, then planned against the vault layout so the caller can
+//! preview what would be written before anything touches disk.
+
+mod archive;
+mod common;
+mod error;
+mod vault;
+
+pub mod bear;
+pub mod logseq;
+pub mod notion;
+
+pub use error::{Error, Result};
+pub use vault::*;
+
+use std::path::Path;
+
+use hypr_importer_core::ir::Collection;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    Notion,
+    Logseq,
+    Bear,
+}
+
+impl Source {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Source::Notion => "notion",
+            Source::Logseq => "logseq",
+            Source::Bear => "bear",
+        }
+    }
+}
+
+impl std::str::FromStr for Source {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "notion" => Ok(Source::Notion),
+            "logseq" => Ok(Source::Logseq),
+            "bear" => Ok(Source::Bear),
+            other => Err(Error::InvalidExport(format!(
+                "unknown source \"{other}\"; expected notion, logseq or bear"
+            ))),
+        }
+    }
+}
+
+/// A parsed export, plus what couldn't be carried over.
+#[derive(Debug, Default)]
+pub struct Import {
+    pub collection: Collection,
+    pub warnings: Vec<String>,
+}
+
+/// Reads an export folder or zip. Attachments are read into memory; notes link to them as
+/// `attachment:<id>` until `plan_import` gives them vault paths.
+pub fn parse(source: Source, path: &Path) -> Result<Import> {
+    match source {
+        Source::Notion => notion::parse_from_export(path),
+        Source::Logseq => logseq::parse_from_graph(path),
+        Source::Bear => bear::parse_from_backup(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hypr_importer_core::ir::NoteKind;
+    use std::fs;
+
+    fn write(root: &Path, path: &str, contents: &[u8]) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn layout(root: &Path) -> VaultLayout {
+        VaultLayout {
+            journal_dir: root.join("journal"),
+            pages_dir: root.join("pages"),
+            assets_dir: root.join("journal/assets"),
+            assets_link_root: "assets".to_string(),
+            filename_pattern: "{YYYY}/{MM}/{DD}".to_string(),
+        }
+    }
+
+    fn planned(plan: &ImportPlan, path: &Path) -> String {
+        plan.files
+            .iter()
+            .find(|file| file.path == path)
+            .map(|file| String::from_utf8(file.contents().to_vec()).unwrap())
+            .unwrap_or_else(|| panic!("{} was not planned", path.display()))
+    }
+
+    #[test]
+    fn imports_logseq_graph() {
+        let export = tempfile::tempdir().unwrap();
+        let vault = tempfile::tempdir().unwrap();
+        write(
+            export.path(),
+            "graph/journals/2024_03_05.md",
+            b"- TODO call [[Project/Alpha]]\n- DONE ship #[[big release]]\n- see ((6600a1b2-0000-4000-8000-000000000001))\n- ![chart](../assets/chart.png)\n",
+        );
+        write(
+            export.path(),
+            "graph/pages/Project___Alpha.md",
+            b"alias:: Alpha\nstatus:: active\n\n- Kickoff notes\n  id:: 6600a1b2-0000-4000-8000-000000000001\n  collapsed:: true\n",
+        );
+        write(export.path(), "graph/assets/chart.png", b"png");
+
+        let import = parse(Source::Logseq, export.path()).unwrap();
+        assert_eq!(import.collection.notes.len(), 2);
+        let daily = &import.collection.notes[0];
+        assert_eq!(daily.kind, NoteKind::Daily);
+        assert_eq!(daily.date.as_deref(), Some("2024-03-05"));
+        assert_eq!(daily.tags, vec!["big-release"]);
+
+        let plan = plan_import(&import, &layout(vault.path())).unwrap();
+        assert!(plan.conflicts.is_empty());
+        assert_eq!(
+            planned(&plan, &vault.path().join("journal/2024/03/05.md")),
+            "---\ntags:\n- big-release\n---\n\n- [ ] call [[Project Alpha]]\n- [x] ship #big-release\n- see [[Project Alpha#^6600a1b2-0000-4000-8000-000000000001]]\n- ![chart](assets/chart.png)\n"
+        );
+        assert_eq!(
+            planned(&plan, &vault.path().join("pages/Project Alpha.md")),
+            "---\naliases:\n- Alpha\nstatus: active\n---\n\n- Kickoff notes ^6600a1b2-0000-4000-8000-000000000001\n"
+        );
+
+        assert_eq!(apply_import(&plan).unwrap(), 3);
+        assert_eq!(
+            fs::read(vault.path().join("journal/assets/chart.png")).unwrap(),
+            b"png"
+        );
+    }
+
+    #[test]
+    fn imports_notion_export() {
+        let export = tempfile::tempdir().unwrap();
+        let vault = tempfile::tempdir().unwrap();
+        write(
+            export.path(),
+            "Home 0123456789abcdef0123456789abcdef.md",
+            b"# Home\n\nSee [Tasks](Tasks%200123456789abcdef0123456789abcdef.csv) and [Plan](Home%200123456789abcdef0123456789abcdef/Plan%20fedcba9876543210fedcba9876543210.md).\n",
+        );
+        write(
+            export.path(),
+            "Home 0123456789abcdef0123456789abcdef/Plan fedcba9876543210fedcba9876543210.md",
+            b"# Plan\n\n![diagram](diagram.png)\n",
+        );
+        write(
+            export.path(),
+            "Home 0123456789abcdef0123456789abcdef/diagram.png",
+            b"img",
+        );
+        write(
+            export.path(),
+            "Tasks 0123456789abcdef0123456789abcdef.csv",
+            b"Name,Status,Tags\nWrite docs,Done,\"docs, q1\"\n",
+        );
+
+        let import = parse(Source::Notion, export.path()).unwrap();
+        let plan = plan_import(&import, &layout(vault.path())).unwrap();
+        assert_eq!(
+            planned(&plan, &vault.path().join("pages/Home.md")),
+            "See [[Tasks]] and [[Plan]].\n"
+        );
+        assert_eq!(
+            planned(&plan, &vault.path().join("pages/Plan.md")),
+            "![diagram](assets/diagram.png)\n"
+        );
+        assert_eq!(
+            planned(&plan, &vault.path().join("pages/Write docs.md")),
+            "---\ntags:\n- docs\n- q1\nstatus: Done\n---\n\n"
+        );
+        assert_eq!(
+            planned(&plan, &vault.path().join("pages/Tasks.md")),
+            "- [[Write docs]]\n"
+        );
+    }
+
+    #[test]
+    fn reports_bear_conflicts() {
+        let export = tempfile::tempdir().unwrap();
+        let vault = tempfile::tempdir().unwrap();
+        write(
+            export.path(),
+            "Ideas.textbundle/text.markdown",
+            b"# Ideas\n\nTry #deep work# today\n![](assets/photo%201.jpg)\n",
+        );
+        write(export.path(), "Ideas.textbundle/assets/photo 1.jpg", b"new");
+        write(
+            export.path(),
+            "Ideas.textbundle/info.json",
+            br#"{"net.shinyfrog.bear": {"creationDate": "2024-01-02T10:00:00Z", "pinned": 1}}"#,
+        );
+        write(
+            export.path(),
+            "Old.textbundle/text.markdown",
+            b"# Ideas\n\nAn older note\n",
+        );
+        write(export.path(), "Gone.textbundle/text.markdown", b"# Gone\n");
+        write(
+            export.path(),
+            "Gone.textbundle/info.json",
+            br#"{"net.shinyfrog.bear": {"trashed": true}}"#,
+        );
+        write(vault.path(), "journal/assets/photo-1.jpg", b"old");
+        write(vault.path(), "pages/Ideas 2.md", b"mine");
+
+        let import = parse(Source::Bear, export.path()).unwrap();
+        assert_eq!(import.warnings.len(), 1);
+        let plan = plan_import(&import, &layout(vault.path())).unwrap();
+        let kinds: Vec<_> = plan.conflicts.iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![ConflictKind::DuplicateTitle, ConflictKind::Exists]
+        );
+        assert_eq!(
+            planned(&plan, &vault.path().join("pages/Ideas.md")),
+            "---\ntags:\n- deep-work\ncreated: 2024-01-02T10:00:00Z\npinned: true\n---\n\nTry #deep-work today\n![](assets/photo-1-1.jpg)\n"
+        );
+
+        let summary = plan.to_string();
+        assert!(summary.starts_with("daily_notes: 0\npages: 1\nattachments: 1\nconflicts: 2\n"));
+
+        apply_import(&plan).unwrap();
+        assert_eq!(
+            fs::read_to_string(vault.path().join("pages/Ideas 2.md")).unwrap(),
+            "mine"
+        );
+        assert_eq!(
+            fs::read(vault.path().join("journal/assets/photo-1.jpg")).unwrap(),
+            b"old"
+        );
+    }
+}
@@ -0,0 +1,283 @@
+//! A Logseq graph folder: `journals/YYYY_MM_DD.md`, `pages/*.md` (namespaces written as `a___b`)
+//! and `assets/`. Pages are outlines whose blocks can carry `key:: value` properties, and blocks
+//! with an `id::` can be referenced from anywhere as `((uuid))`.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use hypr_importer_core::ir::{Note, NoteKind};
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::archive::Archive;
+use crate::common::{
+    date_from_title, decode, extract_tags, file_stem, is_external, note_id, parent_dir,
+    property_key, property_value, resolve_relative, rewrite_links, split_list, Attachments,
+};
+use crate::{Error, Import, Result, Source};
+
+static PROPERTY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:- )?([A-Za-z0-9_-]+):: ?(.*)$").unwrap());
+static TASK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\s*- )(TODO|LATER|NOW|DOING|WAIT|WAITING|DONE|CANCELED|CANCELLED) (.*)$")
+        .unwrap()
+});
+static BLOCK_EMBED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{embed \(\(([0-9a-f-]{36})\)\)\}\}").unwrap());
+static PAGE_EMBED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{embed \[\[([^\]]+)\]\]\}\}").unwrap());
+static BLOCK_REF: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\(\(([0-9a-f-]{36})\)\)").unwrap());
+static MULTI_WORD_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"#\[\[([^\]]+)\]\]").unwrap());
+
+struct Page {
+    path: String,
+    raw: String,
+    title: String,
+    date: Option<String>,
+}
+
+pub fn parse_from_graph(path: &Path) -> Result<Import> {
+    let mut archive = Archive::open(path)?;
+    let paths: Vec<String> = archive.paths().map(str::to_string).collect();
+
+    // The graph may sit inside a folder of the zip or directory we were given.
+    let root = paths
+        .iter()
+        .find_map(|path| {
+            ["journals/", "pages/"].iter().find_map(|dir| {
+                path.find(dir)
+                    .filter(|&i| i == 0 || path[..i].ends_with('/'))
+                    .map(|i| path[..i].to_string())
+            })
+        })
+        .ok_or_else(|| {
+            Error::InvalidExport("no journals/ or pages/ folder; pick the graph folder".to_string())
+        })?;
+
+    let mut import = Import::default();
+    let mut pages = Vec::new();
+    for path in &paths {
+        let Some(relative) = path.strip_prefix(&root) else {
+            continue;
+        };
+        let is_journal = relative.starts_with("journals/");
+        if !is_journal && !relative.starts_with("pages/") {
+            continue;
+        }
+        if relative.ends_with(".org") {
+            import
+                .warnings
+                .push(format!("{path}: org-mode pages are not imported"));
+            continue;
+        }
+        if !relative.ends_with(".md") {
+            continue;
+        }
+
+        let raw = archive.read_string(path)?;
+        let stem = file_stem(path);
+        let (title, date) = if is_journal {
+            match date_from_title(stem) {
+                Some(date) => (date.clone(), Some(date)),
+                None => {
+                    import
+                        .warnings
+                        .push(format!("{path}: journal file name is not a date"));
+                    (stem.to_string(), None)
+                }
+            }
+        } else {
+            (decode(stem).replace("___", "/"), None)
+        };
+        pages.push(Page {
+            path: path.clone(),
+            raw,
+            title,
+            date,
+        });
+    }
+
+    // `title::` renames a page, and block ids resolve to the page their block lives on.
+    for page in &mut pages {
+        if let Some(title) = page_properties(&page.raw)
+            .into_iter()
+            .find(|(key, _)| key == "title")
+            .map(|(_, value)| value)
+        {
+            page.title = title;
+        }
+    }
+    let mut blocks: HashMap<String, String> = HashMap::new();
+    for page in &pages {
+        for line in page.raw.lines() {
+            if let Some(captures) = PROPERTY.captures(line.trim_start()) {
+                if &captures[1] == "id" {
+                    blocks.insert(captures[2].trim().to_string(), page.title.clone());
+                }
+            }
+        }
+    }
+
+    let mut attachments = Attachments::default();
+    for page in &pages {
+        let id = note_id(Source::Logseq, &page.path);
+        let mut properties = Map::new();
+        let mut tags = Vec::new();
+        let mut aliases = Vec::new();
+        for (key, value) in page_properties(&page.raw) {
+            match key.as_str() {
+                "title" => {}
+                "tags" => tags.extend(split_list(&value)),
+                "alias" => aliases.extend(split_list(&value)),
+                _ => {
+                    let values = split_list(&value);
+                    let value = if value.contains("[[") && values.len() > 1 {
+                        Value::from(values)
+                    } else {
+                        property_value(value.trim_start_matches("[[").trim_end_matches("]]"))
+                    };
+                    properties.insert(property_key(&key), value);
+                }
+            }
+        }
+
+        let mut warnings = Vec::new();
+        let body = convert_body(&page.raw, &blocks, &mut |uuid| {
+            warnings.push(format!("{}: block (({uuid})) was not found", page.path));
+        });
+
+        let dir = parent_dir(&page.path);
+        let body = rewrite_links(&body, |is_image, text, target| {
+            if is_external(target) {
+                return None;
+            }
+            let resolved = resolve_relative(dir, &decode(target));
+            if !archive.contains(&resolved) {
+                warnings.push(format!("{}: broken link to {target}", page.path));
+                return None;
+            }
+            match attachments.add(&mut archive, &mut import.collection, &id, &resolved) {
+                Ok(link) => {
+                    let bang = if is_image { "!" } else { "" };
+                    Some(format!("{bang}[{text}]({link})"))
+                }
+                Err(err) => {
+                    warnings.push(format!("{}: could not read {resolved}: {err}", page.path));
+                    None
+                }
+            }
+        });
+        import.warnings.extend(warnings);
+
+        for tag in extract_tags(&body) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+
+        import.collection.notes.push(Note {
+            id,
+            kind: if page.date.is_some() {
+                NoteKind::Daily
+            } else {
+                NoteKind::Page
+            },
+            title: page.title.clone(),
+            date: page.date.clone(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            body_md: body,
+            properties,
+            tags,
+            aliases,
+            source_path: page.path.clone(),
+        });
+    }
+
+    Ok(import)
+}
+
+/// The `key:: value` lines a page starts with.
+fn page_properties(raw: &str) -> Vec<(String, String)> {
+    raw.lines()
+        .skip_while(|line| line.trim().is_empty())
+        .map_while(|line| PROPERTY.captures(line.trim_end()))
+        .map(|captures| (captures[1].to_lowercase(), captures[2].trim().to_string()))
+        .collect()
+}
+
+/// Drops page properties and Logseq-only block properties, turns block ids into `^id` anchors,
+/// block references and embeds into wiki links to those anchors, and task keywords into
+/// checkboxes.
+fn convert_body(
+    raw: &str,
+    blocks: &HashMap<String, String>,
+    on_missing_block: &mut dyn FnMut(&str),
+) -> String {
+    let mut out: Vec<String> = Vec::new();
+    let mut lines = raw
+        .lines()
+        .skip_while(|line| line.trim().is_empty())
+        .peekable();
+    while lines
+        .peek()
+        .is_some_and(|line| PROPERTY.is_match(line.trim_end()))
+    {
+        lines.next();
+    }
+
+    for line in lines.skip_while(|line| line.trim().is_empty()) {
+        if let Some(captures) = PROPERTY.captures(line.trim()) {
+            match &captures[1] {
+                "id" => {
+                    if let Some(last) = out.iter_mut().rev().find(|l| !l.trim().is_empty()) {
+                        last.push_str(&format!(" ^{}", captures[2].trim()));
+                    }
+                    continue;
+                }
+                "collapsed" => continue,
+                _ => {}
+            }
+        }
+
+        let line = match TASK.captures(line) {
+            Some(captures) => match &captures[2] {
+                "DONE" => format!("{}[x] {}", &captures[1], &captures[3]),
+                "CANCELED" | "CANCELLED" => format!("{}[x] ~~{}~~", &captures[1], &captures[3]),
+                _ => format!("{}[ ] {}", &captures[1], &captures[3]),
+            },
+            None => line.to_string(),
+        };
+
+        let mut resolve = |captures: &regex::Captures, embed: bool| {
+            let uuid = &captures[1];
+            match blocks.get(uuid) {
+                Some(title) => format!("{}[[{title}#^{uuid}]]", if embed { "!" } else { "" }),
+                None => {
+                    on_missing_block(uuid);
+                    captures[0].to_string()
+                }
+            }
+        };
+        let line = BLOCK_EMBED
+            .replace_all(&line, |captures: &regex::Captures| resolve(captures, true))
+            .into_owned();
+        let line = PAGE_EMBED.replace_all(&line, "![[$1]]").into_owned();
+        let line = BLOCK_REF
+            .replace_all(&line, |captures: &regex::Captures| resolve(captures, false))
+            .into_owned();
+        let line = MULTI_WORD_TAG
+            .replace_all(&line, |captures: &regex::Captures| {
+                format!("#{}", captures[1].trim().replace(' ', "-"))
+            })
+            .into_owned();
+        out.push(line);
+    }
+
+    let mut body = out.join("\n");
+    body.push('\n');
+    body
+}
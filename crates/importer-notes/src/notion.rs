@@ -0,0 +1,292 @@
+//! Notion's "Markdown & CSV" export: one `.md` per page named `Title <32 hex id>.md`, child pages
+//! and files in a folder of the same name, and each database as a `.csv` next to a folder holding
+//! one page per row.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::LazyLock;
+
+use hypr_importer_core::ir::{Note, NoteKind};
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::archive::Archive;
+use crate::common::{
+    date_from_title, decode, extract_tags, file_stem, is_external, note_id, parent_dir, parse_csv,
+    property_key, property_value, resolve_relative, rewrite_links, split_list, Attachments,
+};
+use crate::{Error, Import, Result, Source};
+
+static NOTION_ID: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s+[0-9a-f]{32}$").unwrap());
+static PROPERTY_LINE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([^:\n]{1,60}): (.*)$").unwrap());
+
+/// `Tasks 3f2a…` → `Tasks`.
+fn clean_name(name: &str) -> String {
+    NOTION_ID.replace(name.trim(), "").trim().to_string()
+}
+
+struct Database {
+    title: String,
+    csv_path: String,
+}
+
+pub fn parse_from_export(path: &Path) -> Result<Import> {
+    let mut archive = Archive::open(path)?;
+    let paths: Vec<String> = archive.paths().map(str::to_string).collect();
+
+    let mut pages = Vec::new();
+    for path in paths.iter().filter(|p| p.ends_with(".md")) {
+        pages.push((path.clone(), archive.read_string(path)?));
+    }
+    if pages.is_empty() {
+        return Err(Error::InvalidExport(
+            "no markdown pages found; export from Notion as \"Markdown & CSV\"".to_string(),
+        ));
+    }
+
+    // Notion writes both `DB.csv` and `DB_all.csv` (which ignores view filters); prefer the latter.
+    let mut databases: BTreeMap<String, Database> = BTreeMap::new();
+    for csv in paths.iter().filter(|p| p.ends_with(".csv")) {
+        let stem = file_stem(csv);
+        let (stem, is_all) = match stem.strip_suffix("_all") {
+            Some(stem) => (stem, true),
+            None => (stem, false),
+        };
+        let folder = match parent_dir(csv) {
+            "" => stem.to_string(),
+            dir => format!("{dir}/{stem}"),
+        };
+        if is_all || !databases.contains_key(&folder) {
+            databases.insert(
+                folder,
+                Database {
+                    title: clean_name(stem),
+                    csv_path: csv.clone(),
+                },
+            );
+        }
+    }
+
+    let titles: HashMap<String, String> = pages
+        .iter()
+        .map(|(path, raw)| {
+            let title = raw
+                .lines()
+                .find(|line| !line.trim().is_empty())
+                .and_then(|line| line.strip_prefix("# "))
+                .map(|title| title.trim().to_string())
+                .unwrap_or_else(|| clean_name(file_stem(path)));
+            (path.clone(), title)
+        })
+        .collect();
+
+    let mut import = Import::default();
+    let mut attachments = Attachments::default();
+    for (path, raw) in &pages {
+        let id = note_id(Source::Notion, path);
+        let title = titles[path].clone();
+        let dir = parent_dir(path);
+
+        let mut lines = raw
+            .lines()
+            .skip_while(|line| line.trim().is_empty())
+            .peekable();
+        if lines.peek().is_some_and(|line| line.starts_with("# ")) {
+            lines.next();
+        }
+        let mut lines = lines.skip_while(|line| line.trim().is_empty()).peekable();
+
+        // Database rows list their properties as `Name: value` lines under the title.
+        let mut properties = Map::new();
+        let mut tags = Vec::new();
+        if databases.contains_key(dir) {
+            while let Some(captures) = lines.peek().and_then(|line| PROPERTY_LINE.captures(line)) {
+                add_property(&mut properties, &mut tags, &captures[1], &captures[2]);
+                lines.next();
+            }
+        }
+        let body = lines.collect::<Vec<_>>().join("\n");
+
+        let mut warnings = Vec::new();
+        let body = rewrite_links(&body, |is_image, text, target| {
+            if is_external(target) {
+                return None;
+            }
+            let decoded = decode(target);
+            let resolved = resolve_relative(dir, decoded.split('#').next().unwrap_or_default());
+
+            if let Some(linked) = titles.get(&resolved) {
+                return Some(if text.is_empty() || text == linked {
+                    format!("[[{linked}]]")
+                } else {
+                    format!("[[{linked}|{text}]]")
+                });
+            }
+            if resolved.ends_with(".csv") {
+                let folder = resolved.trim_end_matches(".csv").trim_end_matches("_all");
+                if let Some(database) = databases.get(folder) {
+                    return Some(format!("[[{}]]", database.title));
+                }
+            }
+            if archive.contains(&resolved) {
+                match attachments.add(&mut archive, &mut import.collection, &id, &resolved) {
+                    Ok(link) => {
+                        let bang = if is_image { "!" } else { "" };
+                        return Some(format!("{bang}[{text}]({link})"));
+                    }
+                    Err(err) => warnings.push(format!("{path}: could not read {resolved}: {err}")),
+                }
+            } else {
+                warnings.push(format!("{path}: broken link to {decoded}"));
+            }
+            None
+        });
+        import.warnings.extend(warnings);
+
+        for tag in extract_tags(&body) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+
+        let date = date_from_title(&title);
+        let created_at = take_string(&mut properties, &["created", "created_time"]);
+        let updated_at = take_string(&mut properties, &["last_edited_time", "updated"]);
+        import.collection.notes.push(Note {
+            id,
+            kind: if date.is_some() {
+                NoteKind::Daily
+            } else {
+                NoteKind::Page
+            },
+            title,
+            date,
+            created_at,
+            updated_at,
+            body_md: body.trim_end().to_string() + "\n",
+            properties,
+            tags,
+            aliases: Vec::new(),
+            source_path: path.clone(),
+        });
+    }
+
+    for (folder, database) in &databases {
+        add_database(&mut archive, &mut import, folder, database)?;
+    }
+
+    Ok(import)
+}
+
+fn add_property(
+    properties: &mut Map<String, Value>,
+    tags: &mut Vec<String>,
+    name: &str,
+    raw: &str,
+) {
+    let key = property_key(name);
+    if key == "tags" {
+        tags.extend(split_list(raw));
+    } else if !raw.trim().is_empty() {
+        properties.insert(key, property_value(raw));
+    }
+}
+
+fn take_string(properties: &mut Map<String, Value>, keys: &[&str]) -> String {
+    keys.iter()
+        .find_map(|key| match properties.remove(*key) {
+            Some(Value::String(value)) => Some(value),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// Fills row pages with the CSV columns their markdown left out, adds pages for rows that have no
+/// markdown, and gives the database itself a page listing its rows.
+fn add_database(
+    archive: &mut Archive,
+    import: &mut Import,
+    folder: &str,
+    database: &Database,
+) -> Result<()> {
+    let rows = parse_csv(&archive.read_string(&database.csv_path)?);
+    let Some((header, rows)) = rows.split_first() else {
+        return Ok(());
+    };
+
+    let mut row_titles = Vec::new();
+    for row in rows {
+        let Some(title) = row
+            .first()
+            .map(|title| title.trim())
+            .filter(|t| !t.is_empty())
+        else {
+            continue;
+        };
+        row_titles.push(title.to_string());
+
+        let existing = import
+            .collection
+            .notes
+            .iter_mut()
+            .find(|note| note.title == title && parent_dir(&note.source_path) == folder);
+        let note = match existing {
+            Some(note) => note,
+            None => {
+                let source_path = format!("{}#{}", database.csv_path, title);
+                import.collection.notes.push(Note {
+                    id: note_id(Source::Notion, &source_path),
+                    kind: NoteKind::Page,
+                    title: title.to_string(),
+                    date: None,
+                    created_at: String::new(),
+                    updated_at: String::new(),
+                    body_md: String::new(),
+                    properties: Map::new(),
+                    tags: Vec::new(),
+                    aliases: Vec::new(),
+                    source_path,
+                });
+                import.collection.notes.last_mut().unwrap()
+            }
+        };
+
+        for (name, value) in header.iter().zip(row).skip(1) {
+            let key = property_key(name);
+            if !note.properties.contains_key(&key) && (key != "tags" || note.tags.is_empty()) {
+                add_property(&mut note.properties, &mut note.tags, name, value);
+            }
+        }
+    }
+
+    let listing: String = row_titles
+        .iter()
+        .map(|title| format!("- [[{title}]]\n"))
+        .collect();
+    match import
+        .collection
+        .notes
+        .iter_mut()
+        .find(|note| note.title == database.title && note.kind == NoteKind::Page)
+    {
+        Some(note) => {
+            note.body_md.push('\n');
+            note.body_md.push_str(&listing);
+        }
+        None => import.collection.notes.push(Note {
+            id: note_id(Source::Notion, &database.csv_path),
+            kind: NoteKind::Page,
+            title: database.title.clone(),
+            date: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+            body_md: listing,
+            properties: Map::new(),
+            tags: Vec::new(),
+            aliases: Vec::new(),
+            source_path: database.csv_path.clone(),
+        }),
+    }
+    Ok(())
+}
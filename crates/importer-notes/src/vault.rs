@@ -0,0 +1,425 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use hypr_importer_core::ir::{Note, NoteKind};
+use regex::Regex;
+use serde::Serialize;
+
+use crate::common::ATTACHMENT_SCHEME;
+use crate::{Import, Result};
+
+static ATTACHMENT_LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"{}([A-Za-z0-9_-]+)",
+        regex::escape(ATTACHMENT_SCHEME)
+    ))
+    .unwrap()
+});
+static WIKI_LINK_TARGET: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[\[([^\]|#\n]+)").unwrap());
+
+/// Where imported notes go, matching the app's journal and pages settings.
+#[derive(Debug, Clone)]
+pub struct VaultLayout {
+    pub journal_dir: PathBuf,
+    pub pages_dir: PathBuf,
+    pub assets_dir: PathBuf,
+    /// How notes link into `assets_dir`, e.g. `assets`.
+    pub assets_link_root: String,
+    /// Daily note path under `journal_dir`, e.g. `{YYYY}/{MM}/{DD}`.
+    pub filename_pattern: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlannedKind {
+    DailyNote,
+    Page,
+    Attachment,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedFile {
+    pub path: PathBuf,
+    pub kind: PlannedKind,
+    pub source_path: String,
+    #[serde(skip)]
+    contents: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    /// The vault already has a file at the target path. It is kept and the note is skipped.
+    Exists,
+    /// Two imported pages share a title. Later ones get a numbered title.
+    DuplicateTitle,
+    /// Two imported daily notes fall on the same date. They are merged into one note.
+    SameDate,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Conflict {
+    pub kind: ConflictKind,
+    pub path: PathBuf,
+    pub source_path: String,
+    pub resolution: String,
+}
+
+/// What an import would write. Nothing touches the vault until `apply_import`.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPlan {
+    pub files: Vec<PlannedFile>,
+    pub conflicts: Vec<Conflict>,
+    pub warnings: Vec<String>,
+}
+
+impl PlannedFile {
+    pub fn contents(&self) -> &[u8] {
+        &self.contents
+    }
+}
+
+impl ImportPlan {
+    fn count(&self, kind: PlannedKind) -> usize {
+        self.files.iter().filter(|file| file.kind == kind).count()
+    }
+}
+
+impl std::fmt::Display for ImportPlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "daily_notes: {}", self.count(PlannedKind::DailyNote))?;
+        writeln!(f, "pages: {}", self.count(PlannedKind::Page))?;
+        writeln!(f, "attachments: {}", self.count(PlannedKind::Attachment))?;
+        writeln!(f, "conflicts: {}", self.conflicts.len())?;
+        writeln!(f, "warnings: {}", self.warnings.len())?;
+
+        for (kind, heading) in [
+            (PlannedKind::DailyNote, "First Daily Note"),
+            (PlannedKind::Page, "First Page"),
+        ] {
+            if let Some(file) = self.files.iter().find(|file| file.kind == kind) {
+                writeln!(f, "\n[{}]", heading)?;
+                writeln!(f, "  path: {}", file.path.display())?;
+                writeln!(f, "  source: {}", file.source_path)?;
+                let preview: String = String::from_utf8_lossy(&file.contents)
+                    .chars()
+                    .take(100)
+                    .collect();
+                writeln!(f, "  content: {}...", preview.replace('\n', " "))?;
+            }
+        }
+
+        if !self.conflicts.is_empty() {
+            writeln!(f, "\n[Conflicts]")?;
+            for conflict in &self.conflicts {
+                writeln!(
+                    f,
+                    "  {:?}: {} ({}) -> {}",
+                    conflict.kind,
+                    conflict.path.display(),
+                    conflict.source_path,
+                    conflict.resolution
+                )?;
+            }
+        }
+
+        if !self.warnings.is_empty() {
+            writeln!(f, "\n[Warnings]")?;
+            for warning in &self.warnings {
+                writeln!(f, "  {}", warning)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn plan_import(import: &Import, layout: &VaultLayout) -> Result<ImportPlan> {
+    let mut plan = ImportPlan {
+        warnings: import.warnings.clone(),
+        ..Default::default()
+    };
+
+    let notes = merge_daily_notes(&import.collection.notes, layout, &mut plan);
+
+    // Titles the vault can't hold as file names are renamed, and links follow them.
+    let mut renamed: HashMap<String, String> = HashMap::new();
+    let mut taken: HashSet<String> = HashSet::new();
+    let mut targets = Vec::new();
+    for note in &notes {
+        let (path, kind) = match (note.kind, note.date.as_deref()) {
+            (NoteKind::Daily, Some(date)) => (
+                layout.journal_dir.join(format!(
+                    "{}.md",
+                    daily_note_path(&layout.filename_pattern, date)
+                )),
+                PlannedKind::DailyNote,
+            ),
+            _ => {
+                let base = sanitize_title(&note.title);
+                let base = if base.is_empty() {
+                    "Untitled".to_string()
+                } else {
+                    base
+                };
+                let mut title = base.clone();
+                let mut counter = 1;
+                while !taken.insert(title.to_lowercase()) {
+                    counter += 1;
+                    title = format!("{base} {counter}");
+                }
+                if counter > 1 {
+                    plan.conflicts.push(Conflict {
+                        kind: ConflictKind::DuplicateTitle,
+                        path: layout.pages_dir.join(format!("{title}.md")),
+                        source_path: note.source_path.clone(),
+                        resolution: format!("imported as \"{title}\""),
+                    });
+                } else if title != note.title {
+                    renamed.insert(note.title.clone(), title.clone());
+                }
+                (
+                    layout.pages_dir.join(format!("{title}.md")),
+                    PlannedKind::Page,
+                )
+            }
+        };
+
+        if path.exists() {
+            plan.conflicts.push(Conflict {
+                kind: ConflictKind::Exists,
+                path,
+                source_path: note.source_path.clone(),
+                resolution: "skipped; the existing file is kept".to_string(),
+            });
+            continue;
+        }
+        targets.push((note, path, kind));
+    }
+
+    let used: HashSet<&str> = targets
+        .iter()
+        .flat_map(|(note, _, _)| ATTACHMENT_LINK.captures_iter(&note.body_md))
+        .filter_map(|captures| captures.get(1).map(|id| id.as_str()))
+        .collect();
+    let mut links = HashMap::new();
+    let mut names = HashSet::new();
+    for attachment in &import.collection.attachments {
+        if !used.contains(attachment.id.as_str()) {
+            continue;
+        }
+        let (name, reuse) = attachment_name(
+            &layout.assets_dir,
+            &sanitize_title(&attachment.file_name).replace(' ', "-"),
+            &attachment.data,
+            &mut names,
+        );
+        let root = layout.assets_link_root.trim_matches('/');
+        let link = if root.is_empty() {
+            name.clone()
+        } else {
+            format!("{root}/{name}")
+        };
+        links.insert(attachment.id.clone(), link);
+        if !reuse {
+            plan.files.push(PlannedFile {
+                path: layout.assets_dir.join(&name),
+                kind: PlannedKind::Attachment,
+                source_path: attachment.source_path.clone(),
+                contents: attachment.data.clone(),
+            });
+        }
+    }
+
+    for (note, path, kind) in targets {
+        let body = ATTACHMENT_LINK.replace_all(&note.body_md, |captures: &regex::Captures| {
+            links
+                .get(&captures[1])
+                .cloned()
+                .unwrap_or_else(|| captures[0].to_string())
+        });
+        let body = WIKI_LINK_TARGET.replace_all(&body, |captures: &regex::Captures| match renamed
+            .get(captures[1].trim())
+        {
+            Some(title) => format!("[[{title}"),
+            None => captures[0].to_string(),
+        });
+
+        plan.files.push(PlannedFile {
+            path,
+            kind,
+            source_path: note.source_path.clone(),
+            contents: render_note(note, &body)?.into_bytes(),
+        });
+    }
+
+    Ok(plan)
+}
+
+/// Writes the planned files, never replacing one that appeared since planning. Returns how many
+/// were written.
+pub fn apply_import(plan: &ImportPlan) -> Result<usize> {
+    let mut written = 0;
+    for file in &plan.files {
+        if let Some(parent) = file.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&file.path)
+        {
+            Ok(mut handle) => {
+                handle.write_all(&file.contents)?;
+                written += 1;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(written)
+}
+
+fn merge_daily_notes(notes: &[Note], layout: &VaultLayout, plan: &mut ImportPlan) -> Vec<Note> {
+    let mut merged: Vec<Note> = Vec::new();
+    let mut by_date: HashMap<String, usize> = HashMap::new();
+    for note in notes {
+        let Some(date) = note.date.as_ref().filter(|_| note.kind == NoteKind::Daily) else {
+            merged.push(note.clone());
+            continue;
+        };
+        let Some(&index) = by_date.get(date) else {
+            by_date.insert(date.clone(), merged.len());
+            merged.push(note.clone());
+            continue;
+        };
+
+        let first = &mut merged[index];
+        first.body_md = format!("{}\n{}", first.body_md.trim_end(), note.body_md);
+        for tag in &note.tags {
+            if !first.tags.contains(tag) {
+                first.tags.push(tag.clone());
+            }
+        }
+        for (key, value) in &note.properties {
+            first
+                .properties
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+        }
+        plan.conflicts.push(Conflict {
+            kind: ConflictKind::SameDate,
+            path: layout.journal_dir.join(format!(
+                "{}.md",
+                daily_note_path(&layout.filename_pattern, date)
+            )),
+            source_path: note.source_path.clone(),
+            resolution: format!("merged into {}", first.source_path),
+        });
+    }
+    merged
+}
+
+fn daily_note_path(pattern: &str, date: &str) -> String {
+    let mut parts = date.splitn(3, '-');
+    let (year, month, day) = (
+        parts.next().unwrap_or_default(),
+        parts.next().unwrap_or_default(),
+        parts.next().unwrap_or_default(),
+    );
+    let pattern = if pattern.trim().is_empty() {
+        "{YYYY}-{MM}-{DD}"
+    } else {
+        pattern
+    };
+    pattern
+        .replace("{YYYY}", year)
+        .replace("{MM}", month)
+        .replace("{DD}", day)
+}
+
+/// The app's page title rules: path separators and control characters become spaces, and runs of
+/// whitespace collapse.
+fn sanitize_title(title: &str) -> String {
+    title
+        .split(|c: char| matches!(c, '/' | '\\') || c.is_control() || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A free file name in `dir` for `data`. Names never contain spaces, so they can be linked without
+/// escaping. A file that already holds the same bytes is reused instead.
+fn attachment_name(
+    dir: &Path,
+    file_name: &str,
+    data: &[u8],
+    names: &mut HashSet<String>,
+) -> (String, bool) {
+    let file_name = if file_name.is_empty() {
+        "attachment"
+    } else {
+        file_name
+    };
+    let (stem, extension) = match file_name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, Some(extension)),
+        _ => (file_name, None),
+    };
+
+    let mut counter = 0;
+    loop {
+        let candidate = match (counter, extension) {
+            (0, _) => file_name.to_string(),
+            (n, Some(extension)) => format!("{stem}-{n}.{extension}"),
+            (n, None) => format!("{stem}-{n}"),
+        };
+        counter += 1;
+
+        if names.contains(&candidate.to_lowercase()) {
+            continue;
+        }
+        let path = dir.join(&candidate);
+        if path.exists() {
+            if std::fs::read(&path).is_ok_and(|existing| existing == data) {
+                names.insert(candidate.to_lowercase());
+                return (candidate, true);
+            }
+            continue;
+        }
+        names.insert(candidate.to_lowercase());
+        return (candidate, false);
+    }
+}
+
+fn render_note(note: &Note, body: &str) -> Result<String> {
+    // A YAML mapping keeps insertion order, so the well-known keys lead.
+    let mut frontmatter = serde_yaml::Mapping::new();
+    if !note.aliases.is_empty() {
+        frontmatter.insert("aliases".into(), serde_yaml::to_value(&note.aliases)?);
+    }
+    if !note.tags.is_empty() {
+        frontmatter.insert("tags".into(), serde_yaml::to_value(&note.tags)?);
+    }
+    if !note.created_at.is_empty() {
+        frontmatter.insert("created".into(), note.created_at.clone().into());
+    }
+    for (key, value) in &note.properties {
+        let key = serde_yaml::Value::from(key.clone());
+        if !frontmatter.contains_key(&key) {
+            frontmatter.insert(key, serde_yaml::to_value(value)?);
+        }
+    }
+
+    if frontmatter.is_empty() {
+        return Ok(body.to_string());
+    }
+    let yaml = serde_yaml::to_string(&frontmatter)?;
+    Ok(format!("---\n{yaml}---\n\n{body}"))
+}
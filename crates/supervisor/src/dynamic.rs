@@ -9,6 +9,10 @@ use ractor::{
     SupervisionEvent, concurrency::JoinHandle,
 };
 
+use crate::introspect::{
+    ChildCounts, ChildHealth, ChildInfo, EventBus, EventSubscription, SupervisorEvent,
+};
+
 // ---------------------------------------------------------------------------
 // Public types
 // ---------------------------------------------------------------------------
@@ -104,6 +108,15 @@ pub enum DynamicSupervisorMsg {
     ScheduledRestart {
         spec: DynChildSpec,
     },
+    WhichChildren {
+        reply: RpcReplyPort<Vec<ChildInfo>>,
+    },
+    CountChildren {
+        reply: RpcReplyPort<ChildCounts>,
+    },
+    SubscribeEvents {
+        reply: RpcReplyPort<EventSubscription>,
+    },
 }
 
 impl std::fmt::Debug for DynamicSupervisorMsg {
@@ -120,6 +133,9 @@ impl std::fmt::Debug for DynamicSupervisorMsg {
                 .debug_struct("ScheduledRestart")
                 .field("id", &spec.id)
                 .finish(),
+            Self::WhichChildren { .. } => f.write_str("WhichChildren"),
+            Self::CountChildren { .. } => f.write_str("CountChildren"),
+            Self::SubscribeEvents { .. } => f.write_str("SubscribeEvents"),
         }
     }
 }
@@ -148,6 +164,9 @@ pub struct DynamicSupervisorState {
     active_children: HashMap<String, ActiveChild>,
     child_failure_state: HashMap<String, ChildFailureState>,
     restart_log: Vec<RestartLogEntry>,
+    /// Every child ever spawned, including stopped ones, for introspection.
+    child_health: HashMap<String, (crate::RestartPolicy, ChildHealth)>,
+    events: EventBus,
 }
 
 // ---------------------------------------------------------------------------
@@ -197,6 +216,30 @@ impl DynamicSupervisor {
         })?;
         Ok(())
     }
+
+    pub async fn which_children(
+        sup_ref: ActorRef<DynamicSupervisorMsg>,
+    ) -> Result<Vec<ChildInfo>, ActorProcessingErr> {
+        Ok(ractor::call!(sup_ref, |reply| {
+            DynamicSupervisorMsg::WhichChildren { reply }
+        })?)
+    }
+
+    pub async fn count_children(
+        sup_ref: ActorRef<DynamicSupervisorMsg>,
+    ) -> Result<ChildCounts, ActorProcessingErr> {
+        Ok(ractor::call!(sup_ref, |reply| {
+            DynamicSupervisorMsg::CountChildren { reply }
+        })?)
+    }
+
+    pub async fn subscribe_events(
+        sup_ref: ActorRef<DynamicSupervisorMsg>,
+    ) -> Result<EventSubscription, ActorProcessingErr> {
+        Ok(ractor::call!(sup_ref, |reply| {
+            DynamicSupervisorMsg::SubscribeEvents { reply }
+        })?)
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -251,6 +294,33 @@ impl DynamicSupervisorState {
         entry.restart_count += 1;
         entry.last_fail = now;
     }
+
+    fn child_infos(&self) -> Vec<ChildInfo> {
+        let mut children: Vec<_> = self
+            .child_health
+            .iter()
+            .map(|(id, (policy, health))| health.info(id, *policy))
+            .collect();
+        children.sort_by(|a, b| a.id.cmp(&b.id));
+        children
+    }
+
+    fn record_child_exit(
+        &mut self,
+        child_id: &str,
+        reason: Option<&str>,
+        abnormal: bool,
+        restarting: bool,
+    ) {
+        if let Some((_, health)) = self.child_health.get_mut(child_id) {
+            health.record_exit(reason.map(ToOwned::to_owned), restarting);
+        }
+        self.events.emit(SupervisorEvent::ChildExited {
+            child_id: child_id.to_string(),
+            reason: reason.map(ToOwned::to_owned),
+            abnormal,
+        });
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -273,6 +343,8 @@ impl Actor for DynamicSupervisor {
             active_children: HashMap::new(),
             child_failure_state: HashMap::new(),
             restart_log: Vec::new(),
+            child_health: HashMap::new(),
+            events: EventBus::new(),
         })
     }
 
@@ -301,7 +373,28 @@ impl Actor for DynamicSupervisor {
                 Ok(())
             }
             DynamicSupervisorMsg::ScheduledRestart { spec } => {
-                handle_spawn_child(&spec, false, state, myself).await
+                let result = handle_spawn_child(&spec, false, state, myself).await;
+                if let Err(e) = &result {
+                    if let Some((_, health)) = state.child_health.get_mut(&spec.id) {
+                        health.record_exit(None, false);
+                    }
+                    state.events.emit(SupervisorEvent::Meltdown {
+                        reason: e.to_string(),
+                    });
+                }
+                result
+            }
+            DynamicSupervisorMsg::WhichChildren { reply } => {
+                reply.send(state.child_infos())?;
+                Ok(())
+            }
+            DynamicSupervisorMsg::CountChildren { reply } => {
+                reply.send(ChildCounts::from_children(&state.child_infos()))?;
+                Ok(())
+            }
+            DynamicSupervisorMsg::SubscribeEvents { reply } => {
+                reply.send(state.events.subscribe(&state.child_infos()))?;
+                Ok(())
             }
         }
     }
@@ -353,6 +446,22 @@ async fn handle_spawn_child(
 
     match result {
         Ok(child_cell) => {
+            let (_, health) = state
+                .child_health
+                .entry(spec.id.clone())
+                .or_insert_with(|| (spec.restart, ChildHealth::new()));
+            if first_start {
+                health.record_start();
+                state.events.emit(SupervisorEvent::ChildStarted {
+                    child_id: spec.id.clone(),
+                });
+            } else {
+                let restart_count = health.record_restart();
+                state.events.emit(SupervisorEvent::ChildRestarted {
+                    child_id: spec.id.clone(),
+                    restart_count,
+                });
+            }
             state.active_children.insert(
                 spec.id.clone(),
                 ActiveChild {
@@ -384,6 +493,7 @@ fn handle_terminate_child(
     if let Some(child) = state.active_children.remove(child_id) {
         child.cell.unlink(myself.get_cell());
         child.cell.kill();
+        state.record_child_exit(child_id, Some("terminated"), false, false);
     }
 }
 
@@ -392,7 +502,7 @@ fn handle_child_restart(
     abnormal: bool,
     state: &mut DynamicSupervisorState,
     myself: &ActorRef<DynamicSupervisorMsg>,
-    _reason: Option<&str>,
+    reason: Option<&str>,
 ) -> Result<(), ActorProcessingErr> {
     let child_id = cell
         .get_name()
@@ -408,6 +518,7 @@ fn handle_child_restart(
         crate::RestartPolicy::Transient => abnormal,
         crate::RestartPolicy::Temporary => false,
     };
+    state.record_child_exit(&child_id, reason, abnormal, should_restart);
 
     if !should_restart {
         return Ok(());
@@ -802,4 +913,74 @@ mod tests {
         );
         assert_eq!(counter.load(Ordering::SeqCst), 2);
     }
+
+    #[tokio::test]
+    async fn which_children_reports_restarts_and_terminations() {
+        let sup_name = unique_name("dyn_introspect_sup");
+        let child_name = unique_name("dyn_introspect_child");
+        let counter = Arc::new(AtomicU32::new(0));
+
+        let (sup_ref, sup_handle) = DynamicSupervisor::spawn(sup_name, options(5))
+            .await
+            .expect("failed to spawn dynamic supervisor");
+        let mut events = DynamicSupervisor::subscribe_events(sup_ref.clone())
+            .await
+            .expect("failed to subscribe");
+
+        DynamicSupervisor::spawn_child(
+            sup_ref.clone(),
+            make_spec(
+                &child_name,
+                RestartPolicy::Permanent,
+                ChildBehavior::DelayedFail { ms: 50 },
+                counter.clone(),
+            ),
+        )
+        .await
+        .expect("failed to spawn child");
+
+        assert_eq!(
+            events.recv().await.unwrap(),
+            SupervisorEvent::ChildStarted {
+                child_id: child_name.clone(),
+            }
+        );
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            SupervisorEvent::ChildExited { abnormal: true, .. }
+        ));
+        assert_eq!(
+            events.recv().await.unwrap(),
+            SupervisorEvent::ChildRestarted {
+                child_id: child_name.clone(),
+                restart_count: 1,
+            }
+        );
+
+        DynamicSupervisor::terminate_child(sup_ref.clone(), child_name.clone())
+            .await
+            .expect("failed to terminate child");
+
+        let children = DynamicSupervisor::which_children(sup_ref.clone())
+            .await
+            .expect("failed to list children");
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].id, child_name);
+        assert_eq!(children[0].status, crate::ChildStatus::Stopped);
+        assert!(children[0].restart_count >= 1);
+        assert_eq!(children[0].last_exit_reason.as_deref(), Some("terminated"));
+        assert_eq!(
+            DynamicSupervisor::count_children(sup_ref.clone())
+                .await
+                .expect("failed to count children"),
+            ChildCounts {
+                specs: 1,
+                active: 0,
+                restarting: 0,
+            }
+        );
+
+        sup_ref.stop(None);
+        let _ = sup_handle.await;
+    }
 }
@@ -0,0 +1,278 @@
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use tokio::sync::broadcast;
+
+use crate::RestartPolicy;
+
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ChildStatus {
+    Running,
+    /// Exited and waiting to be spawned again.
+    Restarting,
+    Stopped,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, specta::Type)]
+pub struct ChildInfo {
+    pub id: String,
+    pub policy: RestartPolicy,
+    pub status: ChildStatus,
+    /// Restarts since the supervisor started, not just within the current budget window.
+    pub restart_count: u32,
+    pub last_exit_reason: Option<String>,
+    pub uptime_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, specta::Type)]
+pub struct ChildCounts {
+    pub specs: usize,
+    pub active: usize,
+    pub restarting: usize,
+}
+
+impl ChildCounts {
+    pub fn from_children(children: &[ChildInfo]) -> Self {
+        Self {
+            specs: children.len(),
+            active: children
+                .iter()
+                .filter(|child| child.status == ChildStatus::Running)
+                .count(),
+            restarting: children
+                .iter()
+                .filter(|child| child.status == ChildStatus::Restarting)
+                .count(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, specta::Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SupervisorEvent {
+    ChildStarted {
+        child_id: String,
+    },
+    ChildExited {
+        child_id: String,
+        reason: Option<String>,
+        abnormal: bool,
+    },
+    ChildRestarted {
+        child_id: String,
+        restart_count: u32,
+    },
+    Meltdown {
+        reason: String,
+    },
+}
+
+/// A supervisor and its children, as shown in the diagnostics view.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, specta::Type)]
+pub struct SupervisorTree {
+    pub name: String,
+    pub children: Vec<ChildInfo>,
+}
+
+/// Lifetime bookkeeping for one child, kept next to the restart budget tracking.
+#[derive(Debug, Clone)]
+pub struct ChildHealth {
+    status: ChildStatus,
+    started_at: Option<Instant>,
+    restart_count: u32,
+    last_exit_reason: Option<String>,
+}
+
+impl Default for ChildHealth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChildHealth {
+    pub fn new() -> Self {
+        Self {
+            status: ChildStatus::Stopped,
+            started_at: None,
+            restart_count: 0,
+            last_exit_reason: None,
+        }
+    }
+
+    pub fn record_start(&mut self) {
+        self.status = ChildStatus::Running;
+        self.started_at = Some(Instant::now());
+    }
+
+    pub fn record_exit(&mut self, reason: Option<String>, restarting: bool) {
+        self.status = if restarting {
+            ChildStatus::Restarting
+        } else {
+            ChildStatus::Stopped
+        };
+        self.started_at = None;
+        if reason.is_some() {
+            self.last_exit_reason = reason;
+        }
+    }
+
+    /// Marks a successful respawn and returns the new total restart count.
+    pub fn record_restart(&mut self) -> u32 {
+        self.restart_count += 1;
+        self.record_start();
+        self.restart_count
+    }
+
+    pub fn status(&self) -> ChildStatus {
+        self.status
+    }
+
+    pub fn info(&self, id: &str, policy: RestartPolicy) -> ChildInfo {
+        ChildInfo {
+            id: id.to_string(),
+            policy,
+            status: self.status,
+            restart_count: self.restart_count,
+            last_exit_reason: self.last_exit_reason.clone(),
+            uptime_ms: self
+                .started_at
+                .map(|started_at| started_at.elapsed().as_millis() as u64),
+        }
+    }
+}
+
+/// Fan-out of supervision events. Sending never fails, so a supervisor without subscribers pays
+/// nothing but the clone.
+pub struct EventBus(broadcast::Sender<SupervisorEvent>);
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self(broadcast::channel(EVENT_CHANNEL_CAPACITY).0)
+    }
+
+    /// Subscribes to live events. A `ChildStarted` is replayed first for every child that is
+    /// already running, since children started before anyone could subscribe.
+    pub fn subscribe(&self, children: &[ChildInfo]) -> EventSubscription {
+        let replay = children
+            .iter()
+            .filter(|child| child.status == ChildStatus::Running)
+            .map(|child| SupervisorEvent::ChildStarted {
+                child_id: child.id.clone(),
+            })
+            .collect();
+        EventSubscription {
+            replay,
+            live: self.0.subscribe(),
+        }
+    }
+
+    pub fn emit(&self, event: SupervisorEvent) {
+        let _ = self.0.send(event);
+    }
+}
+
+/// One subscriber's view of a supervisor's events: the replayed current state, then live events.
+pub struct EventSubscription {
+    replay: VecDeque<SupervisorEvent>,
+    live: broadcast::Receiver<SupervisorEvent>,
+}
+
+impl EventSubscription {
+    pub async fn recv(&mut self) -> Result<SupervisorEvent, broadcast::error::RecvError> {
+        match self.replay.pop_front() {
+            Some(event) => Ok(event),
+            None => self.live.recv().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn health_tracks_exits_and_restarts() {
+        let mut health = ChildHealth::new();
+        health.record_start();
+        assert_eq!(health.status(), ChildStatus::Running);
+
+        health.record_exit(Some("boom".to_string()), true);
+        let info = health.info("child", RestartPolicy::Permanent);
+        assert_eq!(info.status, ChildStatus::Restarting);
+        assert_eq!(info.uptime_ms, None);
+        assert_eq!(info.last_exit_reason.as_deref(), Some("boom"));
+
+        assert_eq!(health.record_restart(), 1);
+        let info = health.info("child", RestartPolicy::Permanent);
+        assert_eq!(info.status, ChildStatus::Running);
+        assert!(info.uptime_ms.is_some());
+        assert_eq!(info.last_exit_reason.as_deref(), Some("boom"));
+    }
+
+    #[test]
+    fn counts_summarize_children() {
+        let mut running = ChildHealth::new();
+        running.record_start();
+        let mut restarting = ChildHealth::new();
+        restarting.record_exit(None, true);
+
+        let children = [
+            running.info("a", RestartPolicy::Permanent),
+            restarting.info("b", RestartPolicy::Transient),
+            ChildHealth::new().info("c", RestartPolicy::Temporary),
+        ];
+        assert_eq!(
+            ChildCounts::from_children(&children),
+            ChildCounts {
+                specs: 3,
+                active: 1,
+                restarting: 1,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn event_bus_replays_running_children_then_delivers_live_events() {
+        let bus = EventBus::new();
+        bus.emit(SupervisorEvent::Meltdown {
+            reason: "ignored".to_string(),
+        });
+
+        let mut running = ChildHealth::new();
+        running.record_start();
+        let children = [
+            running.info("a", RestartPolicy::Permanent),
+            ChildHealth::new().info("b", RestartPolicy::Permanent),
+        ];
+        let mut subscription = bus.subscribe(&children);
+        bus.emit(SupervisorEvent::ChildExited {
+            child_id: "a".to_string(),
+            reason: None,
+            abnormal: false,
+        });
+
+        assert_eq!(
+            subscription.recv().await.unwrap(),
+            SupervisorEvent::ChildStarted {
+                child_id: "a".to_string(),
+            }
+        );
+        assert_eq!(
+            subscription.recv().await.unwrap(),
+            SupervisorEvent::ChildExited {
+                child_id: "a".to_string(),
+                reason: None,
+                abnormal: false,
+            }
+        );
+    }
+}
@@ -1,10 +1,16 @@
 pub mod dynamic;
+mod introspect;
 mod restart;
 mod retry;
 mod supervisor;
 
+pub use introspect::{
+    ChildCounts, ChildHealth, ChildInfo, ChildStatus, EventBus, EventSubscription, SupervisorEvent,
+    SupervisorTree,
+};
 pub use restart::{RestartBudget, RestartTracker};
 pub use retry::{RetryStrategy, spawn_with_retry};
 pub use supervisor::{
-    ChildSpec, RestartPolicy, SpawnFn, Supervisor, SupervisorConfig, SupervisorMsg,
+    ChildSpec, RestartPolicy, SpawnFn, SupervisionStrategy, Supervisor, SupervisorConfig,
+    SupervisorMsg,
 };
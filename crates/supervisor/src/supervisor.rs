@@ -1,19 +1,52 @@
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
-use ractor::{Actor, ActorCell, ActorProcessingErr, ActorRef, SpawnErr, SupervisionEvent};
+use ractor::{
+    Actor, ActorCell, ActorProcessingErr, ActorRef, ActorStatus, RpcReplyPort, SpawnErr,
+    SupervisionEvent,
+};
 
+use crate::introspect::{
+    ChildCounts, ChildHealth, ChildInfo, ChildStatus, EventBus, EventSubscription, SupervisorEvent,
+};
 use crate::restart::{RestartBudget, RestartTracker};
 use crate::retry::{RetryStrategy, spawn_with_retry};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+const SIBLING_STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
 pub enum RestartPolicy {
     Permanent,
     Transient,
     Temporary,
 }
 
+/// Which children are restarted when one of them exits, following Erlang/OTP.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SupervisionStrategy {
+    /// Only the exited child is restarted.
+    #[default]
+    OneForOne,
+    /// Every running child is stopped and the whole set is restarted in start order.
+    OneForAll,
+    /// The exited child and every running child started after it are restarted in order.
+    RestForOne,
+}
+
+impl SupervisionStrategy {
+    /// Indices of the children affected when the child at `idx` exits, in start order.
+    fn affected(self, idx: usize, len: usize) -> std::ops::Range<usize> {
+        match self {
+            SupervisionStrategy::OneForOne => idx..idx + 1,
+            SupervisionStrategy::OneForAll => 0..len,
+            SupervisionStrategy::RestForOne => idx..len,
+        }
+    }
+}
+
 pub type SpawnFuture = Pin<Box<dyn Future<Output = Result<ActorCell, SpawnErr>> + Send>>;
 
 #[derive(Clone)]
@@ -43,6 +76,7 @@ pub struct SupervisorConfig {
     pub children: Vec<ChildSpec>,
     pub restart_budget: RestartBudget,
     pub retry_strategy: RetryStrategy,
+    pub strategy: SupervisionStrategy,
 }
 
 struct ChildEntry {
@@ -51,20 +85,56 @@ struct ChildEntry {
     policy: RestartPolicy,
     spawn_fn: SpawnFn,
     tracker: RestartTracker,
+    health: ChildHealth,
 }
 
 pub struct SupervisorState {
     children: Vec<ChildEntry>,
     budget: RestartBudget,
     retry_strategy: RetryStrategy,
+    strategy: SupervisionStrategy,
+    events: EventBus,
     shutting_down: bool,
 }
 
 pub struct Supervisor;
 
-#[derive(Debug)]
 pub enum SupervisorMsg {
     Shutdown,
+    WhichChildren(RpcReplyPort<Vec<ChildInfo>>),
+    CountChildren(RpcReplyPort<ChildCounts>),
+    SubscribeEvents(RpcReplyPort<EventSubscription>),
+}
+
+impl std::fmt::Debug for SupervisorMsg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Shutdown => f.write_str("Shutdown"),
+            Self::WhichChildren(_) => f.write_str("WhichChildren"),
+            Self::CountChildren(_) => f.write_str("CountChildren"),
+            Self::SubscribeEvents(_) => f.write_str("SubscribeEvents"),
+        }
+    }
+}
+
+impl Supervisor {
+    pub async fn which_children(
+        sup_ref: &ActorRef<SupervisorMsg>,
+    ) -> Result<Vec<ChildInfo>, ActorProcessingErr> {
+        Ok(ractor::call!(sup_ref, SupervisorMsg::WhichChildren)?)
+    }
+
+    pub async fn count_children(
+        sup_ref: &ActorRef<SupervisorMsg>,
+    ) -> Result<ChildCounts, ActorProcessingErr> {
+        Ok(ractor::call!(sup_ref, SupervisorMsg::CountChildren)?)
+    }
+
+    pub async fn subscribe_events(
+        sup_ref: &ActorRef<SupervisorMsg>,
+    ) -> Result<EventSubscription, ActorProcessingErr> {
+        Ok(ractor::call!(sup_ref, SupervisorMsg::SubscribeEvents)?)
+    }
 }
 
 impl SupervisorState {
@@ -79,8 +149,19 @@ impl SupervisorState {
             if let Some(cell) = entry.cell.take() {
                 cell.stop(Some("supervisor_shutdown".to_string()));
             }
+            // Children waiting on a restart have no cell but must not stay `Restarting`.
+            if entry.health.status() != ChildStatus::Stopped {
+                entry.health.record_exit(None, false);
+            }
         }
     }
+
+    fn child_infos(&self) -> Vec<ChildInfo> {
+        self.children
+            .iter()
+            .map(|entry| entry.health.info(&entry.id, entry.policy))
+            .collect()
+    }
 }
 
 fn should_restart(policy: RestartPolicy, abnormal: bool) -> bool {
@@ -96,21 +177,70 @@ async fn handle_child_exit(
     state: &mut SupervisorState,
     idx: usize,
     abnormal: bool,
+    reason: Option<String>,
 ) {
-    state.children[idx].cell = None;
-
-    if !should_restart(state.children[idx].policy, abnormal) {
+    let restarting = should_restart(state.children[idx].policy, abnormal);
+    let entry = &mut state.children[idx];
+    entry.cell = None;
+    entry.health.record_exit(reason.clone(), restarting);
+    state.events.emit(SupervisorEvent::ChildExited {
+        child_id: entry.id.clone(),
+        reason,
+        abnormal,
+    });
+
+    if !restarting {
         return;
     }
 
     if !state.children[idx].tracker.record_restart(&state.budget) {
         tracing::error!(child = %state.children[idx].id, "restart_limit_exceeded");
-        state.shutting_down = true;
-        state.stop_all_children();
-        myself.stop(Some("meltdown".to_string()));
+        meltdown(myself, state, "meltdown");
         return;
     }
 
+    // Siblings are stopped in reverse start order, then everything is restarted in start order.
+    // Temporary siblings stay down, like the exit of a temporary child itself.
+    let mut restart = vec![idx];
+    for sibling in state
+        .strategy
+        .affected(idx, state.children.len())
+        .rev()
+        .filter(|&sibling| sibling != idx)
+    {
+        let entry = &mut state.children[sibling];
+        if let Some(cell) = entry.cell.take() {
+            tracing::info!(child = %entry.id, "stopping_sibling");
+            if let Err(error) = stop_child_and_wait(cell).await {
+                tracing::error!(child = %entry.id, %error, "sibling_stop_timeout");
+                meltdown(myself, state, "sibling_stop_timeout");
+                return;
+            }
+            let restarting = entry.policy != RestartPolicy::Temporary;
+            entry
+                .health
+                .record_exit(Some("sibling_restart".to_string()), restarting);
+            if restarting {
+                restart.push(sibling);
+            }
+        }
+    }
+    restart.sort_unstable();
+
+    for child in restart {
+        if !respawn_child(myself, state, child).await {
+            tracing::error!(child = %state.children[child].id, "spawn_retry_exhausted");
+            meltdown(myself, state, "spawn_retry_exhausted");
+            return;
+        }
+    }
+}
+
+async fn respawn_child(
+    myself: &ActorRef<SupervisorMsg>,
+    state: &mut SupervisorState,
+    idx: usize,
+) -> bool {
     let spawn_fn = state.children[idx].spawn_fn.clone();
     let sup_cell = myself.get_cell();
     let retry = state.retry_strategy.clone();
@@ -122,17 +252,57 @@ async fn handle_child_exit(
     })
     .await;
 
-    match new_cell {
-        Some(cell) => {
-            state.children[idx].cell = Some(cell);
-        }
-        None => {
-            tracing::error!(child = %state.children[idx].id, "spawn_retry_exhausted");
-            state.shutting_down = true;
-            state.stop_all_children();
-            myself.stop(Some("spawn_retry_exhausted".to_string()));
-        }
+    let entry = &mut state.children[idx];
+    entry.cell = new_cell;
+    if entry.cell.is_none() {
+        entry.health.record_exit(None, false);
+        return false;
     }
+
+    let restart_count = entry.health.record_restart();
+    state.events.emit(SupervisorEvent::ChildRestarted {
+        child_id: entry.id.clone(),
+        restart_count,
+    });
+    true
+}
+
+/// Stops a child and waits until it has left the registry, so its replacement can reuse the name.
+/// Its termination event arrives later and is ignored, since the entry no longer holds its cell.
+/// Fails if the child is still around after `SIBLING_STOP_TIMEOUT`.
+async fn stop_child_and_wait(cell: ActorCell) -> Result<(), String> {
+    let name = cell.get_name();
+    cell.stop(Some("sibling_restart".to_string()));
+
+    let stopped = async {
+        loop {
+            let unregistered = name
+                .as_ref()
+                .is_none_or(|name| ractor::registry::where_is(name.clone()).is_none());
+            if cell.get_status() == ActorStatus::Stopped && unregistered {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    };
+    tokio::time::timeout(SIBLING_STOP_TIMEOUT, stopped)
+        .await
+        .map_err(|_| {
+            format!(
+                "actor {} did not stop within {:?}",
+                name.unwrap_or_else(|| cell.get_id().to_string()),
+                SIBLING_STOP_TIMEOUT
+            )
+        })
+}
+
+fn meltdown(myself: &ActorRef<SupervisorMsg>, state: &mut SupervisorState, reason: &str) {
+    state.events.emit(SupervisorEvent::Meltdown {
+        reason: reason.to_string(),
+    });
+    state.shutting_down = true;
+    state.stop_all_children();
+    myself.stop(Some(reason.to_string()));
 }
 
 #[ractor::async_trait]
@@ -147,6 +317,7 @@ impl Actor for Supervisor {
         config: Self::Arguments,
     ) -> Result<Self::State, ActorProcessingErr> {
         let mut children = Vec::new();
+        let events = EventBus::new();
 
         for spec in config.children {
             let cell =
@@ -157,12 +328,16 @@ impl Actor for Supervisor {
                         format!("failed to spawn child '{}': {}", spec.id, e).into()
                     })?;
 
+            let mut health = ChildHealth::new();
+            health.record_start();
+
             children.push(ChildEntry {
                 id: spec.id,
                 cell: Some(cell),
                 policy: spec.restart_policy,
                 spawn_fn: spec.spawn_fn,
                 tracker: RestartTracker::new(),
+                health,
             });
         }
 
@@ -170,6 +345,8 @@ impl Actor for Supervisor {
             children,
             budget: config.restart_budget,
             retry_strategy: config.retry_strategy,
+            strategy: config.strategy,
+            events,
             shutting_down: false,
         })
     }
@@ -186,6 +363,15 @@ impl Actor for Supervisor {
                 state.stop_all_children();
                 myself.stop(None);
             }
+            SupervisorMsg::WhichChildren(reply) => {
+                let _ = reply.send(state.child_infos());
+            }
+            SupervisorMsg::CountChildren(reply) => {
+                let _ = reply.send(ChildCounts::from_children(&state.child_infos()));
+            }
+            SupervisorMsg::SubscribeEvents(reply) => {
+                let _ = reply.send(state.events.subscribe(&state.child_infos()));
+            }
         }
         Ok(())
     }
@@ -207,15 +393,16 @@ impl Actor for Supervisor {
         match message {
             SupervisionEvent::ActorStarted(_) | SupervisionEvent::ProcessGroupChanged(_) => {}
 
-            SupervisionEvent::ActorTerminated(cell, _, _reason) => {
+            SupervisionEvent::ActorTerminated(cell, _, reason) => {
                 if let Some(idx) = state.find_child_index(&cell) {
-                    handle_child_exit(&myself, state, idx, false).await;
+                    handle_child_exit(&myself, state, idx, false, reason).await;
                 }
             }
 
-            SupervisionEvent::ActorFailed(cell, _error) => {
+            SupervisionEvent::ActorFailed(cell, error) => {
                 if let Some(idx) = state.find_child_index(&cell) {
-                    handle_child_exit(&myself, state, idx, true).await;
+                    let reason = format!("{:?}", error);
+                    handle_child_exit(&myself, state, idx, true, Some(reason)).await;
                 }
             }
         }
@@ -227,8 +414,8 @@ impl Actor for Supervisor {
 mod tests {
     use super::*;
     use ractor::{Actor, ActorRef, ActorStatus};
-    use std::sync::Arc;
     use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::{Arc, Mutex};
     use std::time::Duration;
 
     // ---- Test child actor with configurable behaviors ----
@@ -242,6 +429,7 @@ mod tests {
 
     struct TestChild {
         counter: Arc<AtomicU32>,
+        starts: Option<Arc<Mutex<Vec<String>>>>,
     }
 
     #[ractor::async_trait]
@@ -256,6 +444,9 @@ mod tests {
             behavior: Self::Arguments,
         ) -> Result<Self::State, ActorProcessingErr> {
             self.counter.fetch_add(1, Ordering::SeqCst);
+            if let (Some(starts), Some(name)) = (&self.starts, myself.get_name()) {
+                starts.lock().unwrap().push(name);
+            }
 
             match &behavior {
                 ChildBehavior::Healthy => {}
@@ -291,6 +482,16 @@ mod tests {
         policy: RestartPolicy,
         behavior: ChildBehavior,
         counter: Arc<AtomicU32>,
+    ) -> ChildSpec {
+        make_logged_child_spec(name, policy, behavior, counter, None)
+    }
+
+    fn make_logged_child_spec(
+        name: &str,
+        policy: RestartPolicy,
+        behavior: ChildBehavior,
+        counter: Arc<AtomicU32>,
+        starts: Option<Arc<Mutex<Vec<String>>>>,
     ) -> ChildSpec {
         let name = name.to_string();
         ChildSpec {
@@ -299,11 +500,16 @@ mod tests {
             spawn_fn: SpawnFn::new(move |sup_cell| {
                 let behavior = behavior.clone();
                 let counter = counter.clone();
+                let starts = starts.clone();
                 let name = name.clone();
                 async move {
-                    let (actor_ref, _) =
-                        Actor::spawn_linked(Some(name), TestChild { counter }, behavior, sup_cell)
-                            .await?;
+                    let (actor_ref, _) = Actor::spawn_linked(
+                        Some(name),
+                        TestChild { counter, starts },
+                        behavior,
+                        sup_cell,
+                    )
+                    .await?;
                     Ok(actor_ref.get_cell())
                 }
             }),
@@ -339,6 +545,7 @@ mod tests {
             )],
             restart_budget: test_budget(1),
             retry_strategy: fast_retry(),
+            strategy: SupervisionStrategy::OneForOne,
         };
 
         let (sup_ref, sup_handle) =
@@ -365,6 +572,7 @@ mod tests {
             )],
             restart_budget: test_budget(5),
             retry_strategy: fast_retry(),
+            strategy: SupervisionStrategy::OneForOne,
         };
 
         let (sup_ref, sup_handle) =
@@ -392,6 +600,7 @@ mod tests {
             )],
             restart_budget: test_budget(1),
             retry_strategy: fast_retry(),
+            strategy: SupervisionStrategy::OneForOne,
         };
 
         let (sup_ref, sup_handle) =
@@ -416,6 +625,7 @@ mod tests {
             )],
             restart_budget: test_budget(5),
             retry_strategy: fast_retry(),
+            strategy: SupervisionStrategy::OneForOne,
         };
 
         let (sup_ref, sup_handle) =
@@ -443,6 +653,7 @@ mod tests {
             )],
             restart_budget: test_budget(2),
             retry_strategy: fast_retry(),
+            strategy: SupervisionStrategy::OneForOne,
         };
 
         let (sup_ref, sup_handle) =
@@ -476,6 +687,7 @@ mod tests {
             )],
             restart_budget: budget,
             retry_strategy: fast_retry(),
+            strategy: SupervisionStrategy::OneForOne,
         };
 
         let (sup_ref, sup_handle) =
@@ -506,6 +718,7 @@ mod tests {
             )],
             restart_budget: test_budget(5),
             retry_strategy: fast_retry(),
+            strategy: SupervisionStrategy::OneForOne,
         };
 
         let (sup_ref, sup_handle) = Actor::spawn(
@@ -549,6 +762,7 @@ mod tests {
             ],
             restart_budget: test_budget(5),
             retry_strategy: fast_retry(),
+            strategy: SupervisionStrategy::OneForOne,
         };
 
         let (sup_ref, sup_handle) =
@@ -596,6 +810,7 @@ mod tests {
             )],
             restart_budget: budget,
             retry_strategy: fast_retry(),
+            strategy: SupervisionStrategy::OneForOne,
         };
 
         let (sup_ref, sup_handle) =
@@ -612,4 +827,298 @@ mod tests {
         sup_ref.stop(None);
         let _ = sup_handle.await;
     }
+
+    #[tokio::test]
+    async fn one_for_all_restarts_every_child_in_order() {
+        let counters: Vec<_> = (0..3).map(|_| Arc::new(AtomicU32::new(0))).collect();
+        let starts = Arc::new(Mutex::new(Vec::new()));
+        let config = SupervisorConfig {
+            children: vec![
+                make_logged_child_spec(
+                    "ofa_source",
+                    RestartPolicy::Permanent,
+                    ChildBehavior::DelayedFail { ms: 100 },
+                    counters[0].clone(),
+                    Some(starts.clone()),
+                ),
+                make_logged_child_spec(
+                    "ofa_listener",
+                    RestartPolicy::Permanent,
+                    ChildBehavior::Healthy,
+                    counters[1].clone(),
+                    Some(starts.clone()),
+                ),
+                make_logged_child_spec(
+                    "ofa_temporary",
+                    RestartPolicy::Temporary,
+                    ChildBehavior::Healthy,
+                    counters[2].clone(),
+                    Some(starts.clone()),
+                ),
+            ],
+            restart_budget: test_budget(1),
+            retry_strategy: fast_retry(),
+            strategy: SupervisionStrategy::OneForAll,
+        };
+
+        let (sup_ref, sup_handle) =
+            Actor::spawn(Some("test_one_for_all".to_string()), Supervisor, config)
+                .await
+                .unwrap();
+
+        // Source fails -> all restarted (temporary stays down) -> source fails again -> meltdown
+        let _ = sup_handle.await;
+        assert_eq!(sup_ref.get_status(), ActorStatus::Stopped);
+        assert_eq!(counters[0].load(Ordering::SeqCst), 2);
+        assert_eq!(counters[1].load(Ordering::SeqCst), 2);
+        assert_eq!(counters[2].load(Ordering::SeqCst), 1);
+        assert_eq!(
+            *starts.lock().unwrap(),
+            [
+                "ofa_source",
+                "ofa_listener",
+                "ofa_temporary",
+                "ofa_source",
+                "ofa_listener"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn rest_for_one_restarts_later_children_in_order() {
+        let counters: Vec<_> = (0..3).map(|_| Arc::new(AtomicU32::new(0))).collect();
+        let starts = Arc::new(Mutex::new(Vec::new()));
+        let config = SupervisorConfig {
+            children: vec![
+                make_logged_child_spec(
+                    "rfo_first",
+                    RestartPolicy::Permanent,
+                    ChildBehavior::Healthy,
+                    counters[0].clone(),
+                    Some(starts.clone()),
+                ),
+                make_logged_child_spec(
+                    "rfo_source",
+                    RestartPolicy::Permanent,
+                    ChildBehavior::DelayedFail { ms: 100 },
+                    counters[1].clone(),
+                    Some(starts.clone()),
+                ),
+                make_logged_child_spec(
+                    "rfo_recorder",
+                    RestartPolicy::Transient,
+                    ChildBehavior::Healthy,
+                    counters[2].clone(),
+                    Some(starts.clone()),
+                ),
+            ],
+            restart_budget: test_budget(1),
+            retry_strategy: fast_retry(),
+            strategy: SupervisionStrategy::RestForOne,
+        };
+
+        let (sup_ref, sup_handle) =
+            Actor::spawn(Some("test_rest_for_one".to_string()), Supervisor, config)
+                .await
+                .unwrap();
+
+        let _ = sup_handle.await;
+        assert_eq!(sup_ref.get_status(), ActorStatus::Stopped);
+        // Children started before the failing one are left alone
+        assert_eq!(counters[0].load(Ordering::SeqCst), 1);
+        assert_eq!(counters[1].load(Ordering::SeqCst), 2);
+        assert_eq!(counters[2].load(Ordering::SeqCst), 2);
+        assert_eq!(
+            *starts.lock().unwrap(),
+            [
+                "rfo_first",
+                "rfo_source",
+                "rfo_recorder",
+                "rfo_source",
+                "rfo_recorder"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn one_for_all_ignores_normal_exit_of_transient_child() {
+        let healthy_counter = Arc::new(AtomicU32::new(0));
+        let transient_counter = Arc::new(AtomicU32::new(0));
+        let config = SupervisorConfig {
+            children: vec![
+                make_child_spec(
+                    "ofa_normal_healthy",
+                    RestartPolicy::Permanent,
+                    ChildBehavior::Healthy,
+                    healthy_counter.clone(),
+                ),
+                make_child_spec(
+                    "ofa_normal_transient",
+                    RestartPolicy::Transient,
+                    ChildBehavior::DelayedNormal { ms: 100 },
+                    transient_counter.clone(),
+                ),
+            ],
+            restart_budget: test_budget(5),
+            retry_strategy: fast_retry(),
+            strategy: SupervisionStrategy::OneForAll,
+        };
+
+        let (sup_ref, sup_handle) =
+            Actor::spawn(Some("test_ofa_normal".to_string()), Supervisor, config)
+                .await
+                .unwrap();
+        let mut events = Supervisor::subscribe_events(&sup_ref).await.unwrap();
+
+        for child_id in ["ofa_normal_healthy", "ofa_normal_transient"] {
+            assert_eq!(
+                events.recv().await.unwrap(),
+                SupervisorEvent::ChildStarted {
+                    child_id: child_id.to_string(),
+                }
+            );
+        }
+        assert_eq!(
+            events.recv().await.unwrap(),
+            SupervisorEvent::ChildExited {
+                child_id: "ofa_normal_transient".to_string(),
+                reason: None,
+                abnormal: false,
+            }
+        );
+
+        // The exit is fully handled before the supervisor answers the next call.
+        let children = Supervisor::which_children(&sup_ref).await.unwrap();
+        assert_eq!(children[0].status, ChildStatus::Running);
+        assert_eq!(children[0].restart_count, 0);
+        assert_eq!(children[1].status, ChildStatus::Stopped);
+        assert_eq!(healthy_counter.load(Ordering::SeqCst), 1);
+        assert_eq!(transient_counter.load(Ordering::SeqCst), 1);
+
+        sup_ref.stop(None);
+        let _ = sup_handle.await;
+    }
+
+    #[test]
+    fn strategy_selects_affected_children() {
+        assert_eq!(SupervisionStrategy::OneForOne.affected(1, 3), 1..2);
+        assert_eq!(SupervisionStrategy::OneForAll.affected(1, 3), 0..3);
+        assert_eq!(SupervisionStrategy::RestForOne.affected(1, 3), 1..3);
+    }
+
+    #[tokio::test]
+    async fn reports_children_and_streams_events() {
+        let healthy_counter = Arc::new(AtomicU32::new(0));
+        let failing_counter = Arc::new(AtomicU32::new(0));
+        let config = SupervisorConfig {
+            children: vec![
+                make_child_spec(
+                    "introspect_healthy",
+                    RestartPolicy::Permanent,
+                    ChildBehavior::Healthy,
+                    healthy_counter.clone(),
+                ),
+                make_child_spec(
+                    "introspect_failing",
+                    RestartPolicy::Permanent,
+                    ChildBehavior::DelayedFail { ms: 100 },
+                    failing_counter.clone(),
+                ),
+            ],
+            restart_budget: test_budget(5),
+            retry_strategy: fast_retry(),
+            strategy: SupervisionStrategy::OneForOne,
+        };
+
+        let (sup_ref, sup_handle) =
+            Actor::spawn(Some("test_introspect".to_string()), Supervisor, config)
+                .await
+                .unwrap();
+        let mut events = Supervisor::subscribe_events(&sup_ref).await.unwrap();
+
+        let children = Supervisor::which_children(&sup_ref).await.unwrap();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].id, "introspect_healthy");
+        assert_eq!(children[0].policy, RestartPolicy::Permanent);
+        assert_eq!(children[0].status, ChildStatus::Running);
+        assert_eq!(children[1].restart_count, 0);
+
+        // Both children started before the subscription, so their starts are replayed.
+        for child_id in ["introspect_healthy", "introspect_failing"] {
+            assert_eq!(
+                events.recv().await.unwrap(),
+                SupervisorEvent::ChildStarted {
+                    child_id: child_id.to_string(),
+                }
+            );
+        }
+        match events.recv().await.unwrap() {
+            SupervisorEvent::ChildExited {
+                child_id, abnormal, ..
+            } => {
+                assert_eq!(child_id, "introspect_failing");
+                assert!(abnormal);
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+        assert_eq!(
+            events.recv().await.unwrap(),
+            SupervisorEvent::ChildRestarted {
+                child_id: "introspect_failing".to_string(),
+                restart_count: 1,
+            }
+        );
+
+        let children = Supervisor::which_children(&sup_ref).await.unwrap();
+        assert_eq!(children[1].status, ChildStatus::Running);
+        assert_eq!(children[1].restart_count, 1);
+        assert!(children[1].last_exit_reason.is_some());
+        assert_eq!(
+            Supervisor::count_children(&sup_ref).await.unwrap(),
+            ChildCounts {
+                specs: 2,
+                active: 2,
+                restarting: 0,
+            }
+        );
+
+        sup_ref.stop(None);
+        let _ = sup_handle.await;
+    }
+
+    #[test]
+    fn stopping_children_moves_restarting_children_to_stopped() {
+        let mut health = ChildHealth::new();
+        health.record_start();
+        health.record_exit(Some("boom".to_string()), true);
+        assert_eq!(health.status(), ChildStatus::Restarting);
+
+        let spec = make_child_spec(
+            "meltdown_restarting",
+            RestartPolicy::Permanent,
+            ChildBehavior::Healthy,
+            Arc::new(AtomicU32::new(0)),
+        );
+        let mut state = SupervisorState {
+            children: vec![ChildEntry {
+                id: spec.id,
+                cell: None,
+                policy: spec.restart_policy,
+                spawn_fn: spec.spawn_fn,
+                tracker: RestartTracker::new(),
+                health,
+            }],
+            budget: test_budget(0),
+            retry_strategy: fast_retry(),
+            strategy: SupervisionStrategy::OneForOne,
+            events: EventBus::new(),
+            shutting_down: false,
+        };
+
+        state.stop_all_children();
+
+        let children = state.child_infos();
+        assert_eq!(children[0].status, ChildStatus::Stopped);
+        assert_eq!(children[0].last_exit_reason.as_deref(), Some("boom"));
+    }
 }
@@ -1,10 +1,14 @@
 use markdown::mdast;
 
 pub fn mdast_to_markdown(node: &mdast::Node) -> Result<String, String> {
-    let task_items = collect_task_items(node);
+    let mut node = node.clone();
+    let mut raw = Raw::new(&node);
+    extract_raw(&mut node, false, &mut raw)?;
+
+    let task_items = collect_task_items(&node);
 
     let md = mdast_util_to_markdown::to_markdown_with_options(
-        node,
+        &node,
         &mdast_util_to_markdown::Options {
             bullet: '-',
             ..Default::default()
@@ -13,7 +17,312 @@ pub fn mdast_to_markdown(node: &mdast::Node) -> Result<String, String> {
     .map_err(|e| e.to_string())?;
 
     let md = inject_task_checkboxes(&md, &task_items);
-    Ok(unescape_markdown(&md))
+    Ok(restore_raw(&unescape_markdown(&md), &raw))
+}
+
+/// Markup that is spliced back in after serializing, keyed by placeholders of the form
+/// `{tag}{index}{tag}`.
+///
+/// Placeholders are plain ASCII: `mdast_util_to_markdown` looks characters up by byte offset
+/// when escaping, which mangles text after any multi-byte character, so non-ASCII text is
+/// swapped out the same way.
+struct Raw {
+    tag: String,
+    values: Vec<String>,
+}
+
+impl Raw {
+    /// Picks a tag that occurs nowhere in the document.
+    fn new(node: &mdast::Node) -> Self {
+        let source = format!("{node:?}");
+        let mut tag = String::from("Rawx");
+        while source.contains(&tag) {
+            tag.push('x');
+        }
+        Self {
+            tag,
+            values: Vec::new(),
+        }
+    }
+
+    fn placeholder(&mut self, value: String) -> String {
+        let placeholder = format!("{}{}{}", self.tag, self.values.len(), self.tag);
+        self.values.push(value);
+        placeholder
+    }
+
+    /// The value behind a placeholder at the start of `text`, and the text after it.
+    fn take<'a>(&self, text: &'a str) -> Option<(&String, &'a str)> {
+        let after = text.strip_prefix(self.tag.as_str())?;
+        let digits = after
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(after.len());
+        let tail = after[digits..].strip_prefix(self.tag.as_str())?;
+        let value = self.values.get(after[..digits].parse::<usize>().ok()?)?;
+        Some((value, tail))
+    }
+}
+
+/// Swaps nodes that `mdast_util_to_markdown` can't print (or would escape) for placeholders.
+///
+/// Tables, footnotes, math, frontmatter and raw HTML (which also carries wiki links) are
+/// rendered here instead, and spliced back in after escaping so their markup stays verbatim.
+fn extract_raw(node: &mut mdast::Node, inline: bool, raw: &mut Raw) -> Result<(), String> {
+    match node {
+        mdast::Node::Text(text) => {
+            text.value = protect_non_ascii(&text.value, raw);
+            return Ok(());
+        }
+        mdast::Node::InlineCode(code) => {
+            // Line endings in a code span read back as spaces, and the serializer panics on them
+            // inside containers.
+            code.value = protect_non_ascii(&code.value.replace('\n', " "), raw);
+            return Ok(());
+        }
+        mdast::Node::Image(image) => {
+            image.alt = protect_non_ascii(&image.alt, raw);
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let rendered = match node {
+        mdast::Node::Table(table) => Some(render_table(table)?),
+        mdast::Node::FootnoteDefinition(def) => Some(render_footnote_definition(def)?),
+        mdast::Node::FootnoteReference(reference) => Some(format!(
+            "[^{}]",
+            reference.label.as_deref().unwrap_or(&reference.identifier)
+        )),
+        mdast::Node::Math(math) => {
+            let fence = fence_for(&math.value, "$$");
+            let meta = math.meta.as_deref().unwrap_or_default();
+            Some(format!("{fence}{meta}\n{}\n{fence}", math.value))
+        }
+        mdast::Node::InlineMath(math) => {
+            let fence = fence_for(&math.value, "$");
+            Some(format!("{fence}{}{fence}", math.value))
+        }
+        mdast::Node::Yaml(yaml) => Some(render_frontmatter("---", &yaml.value)),
+        mdast::Node::Toml(toml) => Some(render_frontmatter("+++", &toml.value)),
+        mdast::Node::Html(html) => Some(html.value.clone()),
+        mdast::Node::Paragraph(paragraph) => match paragraph.children.as_slice() {
+            [mdast::Node::Text(text)] if reads_back_raw(&text.value) => Some(text.value.clone()),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    if let Some(rendered) = rendered {
+        let placeholder = mdast::Node::Text(mdast::Text {
+            value: raw.placeholder(rendered),
+            position: None,
+        });
+        let inline = inline
+            || matches!(
+                node,
+                mdast::Node::InlineMath(_) | mdast::Node::FootnoteReference(_)
+            );
+        *node = if inline {
+            placeholder
+        } else {
+            mdast::Node::Paragraph(mdast::Paragraph {
+                children: vec![placeholder],
+                position: None,
+            })
+        };
+        return Ok(());
+    }
+
+    let children_inline = matches!(
+        node,
+        mdast::Node::Paragraph(_)
+            | mdast::Node::Heading(_)
+            | mdast::Node::Strong(_)
+            | mdast::Node::Emphasis(_)
+            | mdast::Node::Delete(_)
+            | mdast::Node::Link(_)
+    );
+    if let Some(children) = node.children_mut() {
+        for child in children {
+            extract_raw(child, children_inline, raw)?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether a paragraph's text would be read back as raw HTML or frontmatter, where escapes are
+/// kept literally. Editors without those node types hold them as text.
+fn reads_back_raw(text: &str) -> bool {
+    if ["---", "+++"].iter().any(|fence| {
+        text.starts_with(&format!("{fence}\n")) && text.ends_with(&format!("\n{fence}"))
+    }) {
+        return true;
+    }
+    text.starts_with('<')
+        && matches!(
+            markdown::to_mdast(text, &markdown::ParseOptions::gfm()),
+            Ok(mdast::Node::Root(root)) if matches!(root.children.as_slice(), [mdast::Node::Html(_)])
+        )
+}
+
+fn protect_non_ascii(text: &str, raw: &mut Raw) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(|c: char| !c.is_ascii()) {
+        result.push_str(&rest[..start]);
+        let run = &rest[start..];
+        let end = run.find(|c: char| c.is_ascii()).unwrap_or(run.len());
+        result.push_str(&raw.placeholder(run[..end].to_string()));
+        rest = &run[end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+fn render_table(table: &mdast::Table) -> Result<String, String> {
+    let mut rows = Vec::with_capacity(table.children.len());
+    for row in &table.children {
+        let mut cells = Vec::new();
+        for cell in row.children().map(Vec::as_slice).unwrap_or_default() {
+            let children = cell.children().cloned().unwrap_or_default();
+            cells.push(render_table_cell(children)?);
+        }
+        rows.push(cells);
+    }
+
+    let columns = rows
+        .iter()
+        .map(Vec::len)
+        .chain(std::iter::once(table.align.len()))
+        .max()
+        .unwrap_or(0);
+    if rows.is_empty() || columns == 0 {
+        return Ok(String::new());
+    }
+
+    let delimiter: Vec<String> = (0..columns)
+        .map(|column| {
+            match table.align.get(column).unwrap_or(&mdast::AlignKind::None) {
+                mdast::AlignKind::Left => ":---",
+                mdast::AlignKind::Right => "---:",
+                mdast::AlignKind::Center => ":---:",
+                mdast::AlignKind::None => "---",
+            }
+            .to_string()
+        })
+        .collect();
+
+    let format_row = |cells: &[String]| {
+        let mut line = String::from("|");
+        for column in 0..columns {
+            line.push(' ');
+            line.push_str(cells.get(column).map(String::as_str).unwrap_or_default());
+            line.push_str(" |");
+        }
+        line
+    };
+
+    let mut lines = vec![format_row(&rows[0]), format_row(&delimiter)];
+    lines.extend(rows[1..].iter().map(|cells| format_row(cells)));
+    Ok(lines.join("\n"))
+}
+
+fn render_table_cell(children: Vec<mdast::Node>) -> Result<String, String> {
+    let paragraph = mdast::Node::Root(mdast::Root {
+        children: vec![mdast::Node::Paragraph(mdast::Paragraph {
+            children,
+            position: None,
+        })],
+        position: None,
+    });
+    let md = mdast_to_markdown(&paragraph)?;
+    Ok(md.trim().replace('\n', " ").replace('|', "\\|"))
+}
+
+fn render_footnote_definition(def: &mdast::FootnoteDefinition) -> Result<String, String> {
+    let body = mdast_to_markdown(&mdast::Node::Root(mdast::Root {
+        children: def.children.clone(),
+        position: None,
+    }))?;
+
+    let label = def.label.as_deref().unwrap_or(&def.identifier);
+    let mut result = format!("[^{label}]:");
+    for (index, line) in body.trim_end().lines().enumerate() {
+        if index == 0 {
+            result.push(' ');
+        } else {
+            result.push('\n');
+            if !line.is_empty() {
+                result.push_str("    ");
+            }
+        }
+        result.push_str(line);
+    }
+    Ok(result)
+}
+
+fn render_frontmatter(fence: &str, value: &str) -> String {
+    if value.is_empty() {
+        format!("{fence}\n{fence}")
+    } else {
+        format!("{fence}\n{value}\n{fence}")
+    }
+}
+
+/// The shortest run of `$` (at least `min`) that doesn't occur in `value`.
+fn fence_for(value: &str, min: &str) -> String {
+    let mut fence = min.to_string();
+    while value.contains(&fence) {
+        fence.push('$');
+    }
+    fence
+}
+
+/// Puts the hand-rendered markup back, repeating the container prefix (`> `, list
+/// indentation) on continuation lines of multi-line blocks.
+fn restore_raw(md: &str, raw: &Raw) -> String {
+    if raw.values.is_empty() {
+        return md.to_string();
+    }
+
+    let mut result = String::with_capacity(md.len());
+    for (index, line) in md.split('\n').enumerate() {
+        if index > 0 {
+            result.push('\n');
+        }
+
+        let continuation: String = line
+            .chars()
+            .take_while(|c| matches!(c, ' ' | '>' | '-' | '*' | '+' | '.' | ')' | '0'..='9'))
+            .map(|c| if c == '>' { '>' } else { ' ' })
+            .collect();
+
+        let mut rest = line;
+        while let Some(start) = rest.find(raw.tag.as_str()) {
+            result.push_str(&rest[..start]);
+            let Some((value, tail)) = raw.take(&rest[start..]) else {
+                // Not a placeholder after all, e.g. text that ends in part of the tag.
+                result.push_str(&rest[start..start + 1]);
+                rest = &rest[start + 1..];
+                continue;
+            };
+
+            for (line_index, raw_line) in value.split('\n').enumerate() {
+                if line_index > 0 {
+                    result.push('\n');
+                    if raw_line.is_empty() {
+                        result.push_str(continuation.trim_end());
+                    } else {
+                        result.push_str(&continuation);
+                    }
+                }
+                result.push_str(raw_line);
+            }
+            rest = tail;
+        }
+        result.push_str(rest);
+    }
+    result
 }
 
 fn unescape_markdown(md: &str) -> String {
@@ -93,45 +402,29 @@ fn collect_task_items_recursive(node: &mdast::Node, items: &mut Vec<Option<bool>
     }
 }
 
+/// Adds `[ ]`/`[x]` after the list markers of task items, walking the markers in document
+/// order. A line can open several items at once, e.g. `- - [ ] nested`.
 fn inject_task_checkboxes(md: &str, task_items: &[Option<bool>]) -> String {
     let mut result = String::with_capacity(md.len() + task_items.len() * 6);
-    let mut task_index = 0;
+    let mut items = task_items.iter();
 
     for line in md.lines() {
-        let trimmed = line.trim_start();
-        if trimmed.starts_with("- ") || trimmed.starts_with("* ") {
-            if task_index < task_items.len() {
-                if let Some(checked) = task_items[task_index] {
-                    let prefix_len = line.len() - trimmed.len();
-                    let bullet = &trimmed[..2];
-                    let rest = &trimmed[2..];
-                    let checkbox = if checked { "[x] " } else { "[ ] " };
-                    result.push_str(&line[..prefix_len]);
-                    result.push_str(bullet);
-                    result.push_str(checkbox);
-                    result.push_str(rest);
-                } else {
-                    result.push_str(line);
-                }
-                task_index += 1;
-            } else {
-                result.push_str(line);
-            }
-        } else if trimmed.starts_with(|c: char| c.is_ascii_digit()) {
-            let mut chars = trimmed.chars().peekable();
-            while chars.peek().is_some_and(|c| c.is_ascii_digit()) {
-                chars.next();
-            }
-            if chars.next() == Some('.')
-                && chars.next() == Some(' ')
-                && task_index < task_items.len()
-            {
-                task_index += 1;
+        let mut rest = line;
+        loop {
+            let indent = rest.len() - rest.trim_start_matches([' ', '>']).len();
+            result.push_str(&rest[..indent]);
+            rest = &rest[indent..];
+
+            let Some(marker_len) = list_marker_len(rest) else {
+                break;
+            };
+            result.push_str(&rest[..marker_len]);
+            rest = &rest[marker_len..];
+            if let Some(Some(checked)) = items.next() {
+                result.push_str(if *checked { "[x] " } else { "[ ] " });
             }
-            result.push_str(line);
-        } else {
-            result.push_str(line);
         }
+        result.push_str(rest);
         result.push('\n');
     }
 
@@ -141,3 +434,12 @@ fn inject_task_checkboxes(md: &str, task_items: &[Option<bool>]) -> String {
 
     result
 }
+
+/// Length of the bullet (`- `, `* `) or ordered (`1. `) list marker at the start of `line`.
+fn list_marker_len(line: &str) -> Option<usize> {
+    if line.starts_with("- ") || line.starts_with("* ") {
+        return Some(2);
+    }
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    (digits > 0 && line[digits..].starts_with(". ")).then_some(digits + 2)
+}
@@ -1,19 +1,122 @@
 use markdown::mdast;
 use serde_json::{Value, json};
 
+/// Node types the converter may emit beyond what the desktop editor renders.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MdToTiptapOptions {
+    /// Emit `frontmatter`, `wikiLink`, `embed`, `inlineMath`, `blockMath`, `footnoteReference`,
+    /// `footnoteDefinition`, `htmlBlock` and `htmlInline` nodes. Off, that markdown is parsed as
+    /// plain GFM, so wiki links and math stay text, and frontmatter, footnotes and raw HTML are
+    /// kept as their markdown in text nodes.
+    pub obsidian_nodes: bool,
+}
+
+/// Converts markdown to JSON the editor can load, i.e. without [`MdToTiptapOptions::obsidian_nodes`].
 pub fn md_to_tiptap_json(md: &str) -> Result<Value, String> {
-    let mdast = markdown::to_mdast(md, &markdown::ParseOptions::gfm())
+    md_to_tiptap_json_with(md, MdToTiptapOptions::default())
+}
+
+pub fn md_to_tiptap_json_with(md: &str, options: MdToTiptapOptions) -> Result<Value, String> {
+    let mut mdast = markdown::to_mdast(md, &parse_options(md, options))
         .map_err(|e| format!("Failed to parse markdown: {}", e))?;
-    Ok(mdast_to_tiptap(&mdast))
+    if !options.obsidian_nodes {
+        keep_as_text(&mut mdast);
+    }
+    Ok(mdast_to_tiptap(&mdast, options))
+}
+
+/// GFM with frontmatter, plus `$` math when Obsidian nodes are enabled.
+fn parse_options(md: &str, options: MdToTiptapOptions) -> markdown::ParseOptions {
+    markdown::ParseOptions {
+        constructs: markdown::Constructs {
+            frontmatter: has_frontmatter(md),
+            math_flow: options.obsidian_nodes,
+            math_text: options.obsidian_nodes,
+            ..markdown::Constructs::gfm()
+        },
+        ..markdown::ParseOptions::gfm()
+    }
+}
+
+/// Whether `md` opens with a closed frontmatter block. `markdown` mis-parses (or panics on) the
+/// rest of a document whose leading `---` rule never gets a closing fence.
+fn has_frontmatter(md: &str) -> bool {
+    let mut lines = md.lines().map(str::trim_end);
+    match lines.next() {
+        Some(fence @ ("---" | "+++")) => lines.any(|line| line == fence),
+        _ => false,
+    }
+}
+
+/// Rewrites nodes the editor has no type for into text holding their markdown, in a paragraph
+/// of its own at block level. A footnote definition keeps its content, behind a `[^label]: `
+/// prefix.
+fn keep_as_text(node: &mut mdast::Node) {
+    let block = matches!(
+        node,
+        mdast::Node::Root(_) | mdast::Node::Blockquote(_) | mdast::Node::ListItem(_)
+    );
+    let Some(children) = node.children_mut() else {
+        return;
+    };
+    for mut child in std::mem::take(children) {
+        if let mdast::Node::FootnoteDefinition(def) = child {
+            let prefix = format!("[^{}]: ", footnote_label(&def.identifier, &def.label));
+            let mut blocks = def.children.into_iter().peekable();
+            let mut first = match blocks.next_if(|b| matches!(b, mdast::Node::Paragraph(_))) {
+                Some(mdast::Node::Paragraph(p)) => p,
+                _ => mdast::Paragraph {
+                    children: Vec::new(),
+                    position: None,
+                },
+            };
+            first.children.insert(0, text_node(prefix));
+            for mut block in std::iter::once(mdast::Node::Paragraph(first)).chain(blocks) {
+                keep_as_text(&mut block);
+                children.push(block);
+            }
+            continue;
+        }
+
+        let markdown = match &child {
+            mdast::Node::FootnoteReference(f) => {
+                Some(format!("[^{}]", footnote_label(&f.identifier, &f.label)))
+            }
+            mdast::Node::Html(h) => Some(h.value.clone()),
+            mdast::Node::Math(m) => Some(format!("$$\n{}\n$$", m.value)),
+            mdast::Node::InlineMath(m) => Some(format!("${}$", m.value)),
+            mdast::Node::Yaml(y) => Some(format!("---\n{}\n---", y.value)),
+            mdast::Node::Toml(t) => Some(format!("+++\n{}\n+++", t.value)),
+            _ => None,
+        };
+        match markdown {
+            Some(markdown) if block => children.push(mdast::Node::Paragraph(mdast::Paragraph {
+                children: vec![text_node(markdown)],
+                position: None,
+            })),
+            Some(markdown) => children.push(text_node(markdown)),
+            None => {
+                keep_as_text(&mut child);
+                children.push(child);
+            }
+        }
+    }
 }
 
-fn mdast_to_tiptap(node: &mdast::Node) -> Value {
+fn text_node(value: String) -> mdast::Node {
+    mdast::Node::Text(mdast::Text {
+        value,
+        position: None,
+    })
+}
+
+fn mdast_to_tiptap(node: &mdast::Node, options: MdToTiptapOptions) -> Value {
     match node {
         mdast::Node::Root(root) => {
             let content: Vec<Value> = root
                 .children
                 .iter()
-                .filter_map(convert_block_node)
+                .filter_map(|child| convert_block_node(child, options))
                 .collect();
             json!({
                 "type": "doc",
@@ -27,21 +130,33 @@ fn mdast_to_tiptap(node: &mdast::Node) -> Value {
     }
 }
 
-fn convert_block_node(node: &mdast::Node) -> Option<Value> {
+fn convert_block_node(node: &mdast::Node, options: MdToTiptapOptions) -> Option<Value> {
+    let obsidian = options.obsidian_nodes;
     match node {
-        mdast::Node::Paragraph(p) => Some(convert_paragraph(p)),
-        mdast::Node::Heading(h) => Some(convert_heading(h)),
-        mdast::Node::List(l) => Some(convert_list(l)),
+        mdast::Node::Paragraph(p) => Some(convert_paragraph(p, options)),
+        mdast::Node::Heading(h) => Some(convert_heading(h, options)),
+        mdast::Node::List(l) => Some(convert_list(l, options)),
         mdast::Node::Code(c) => Some(convert_code_block(c)),
-        mdast::Node::Blockquote(b) => Some(convert_blockquote(b)),
+        mdast::Node::Blockquote(b) => Some(convert_blockquote(b, options)),
         mdast::Node::ThematicBreak(_) => Some(json!({ "type": "horizontalRule" })),
         mdast::Node::Image(img) => Some(convert_image(img)),
+        mdast::Node::Table(t) => Some(convert_table(t, options)),
+        mdast::Node::FootnoteDefinition(f) if obsidian => {
+            Some(convert_footnote_definition(f, options))
+        }
+        mdast::Node::Math(m) if obsidian => Some(convert_math(m)),
+        mdast::Node::Html(h) if obsidian => Some(json!({
+            "type": "htmlBlock",
+            "attrs": { "html": h.value }
+        })),
+        mdast::Node::Yaml(y) if obsidian => Some(convert_frontmatter("yaml", &y.value)),
+        mdast::Node::Toml(t) if obsidian => Some(convert_frontmatter("toml", &t.value)),
         _ => None,
     }
 }
 
-fn convert_paragraph(p: &mdast::Paragraph) -> Value {
-    let content = convert_inline_nodes(&p.children);
+fn convert_paragraph(p: &mdast::Paragraph, options: MdToTiptapOptions) -> Value {
+    let content = convert_inline_nodes(&p.children, options);
     if content.is_empty() {
         json!({ "type": "paragraph" })
     } else {
@@ -52,8 +167,8 @@ fn convert_paragraph(p: &mdast::Paragraph) -> Value {
     }
 }
 
-fn convert_heading(h: &mdast::Heading) -> Value {
-    let content = convert_inline_nodes(&h.children);
+fn convert_heading(h: &mdast::Heading, options: MdToTiptapOptions) -> Value {
+    let content = convert_inline_nodes(&h.children, options);
     let mut result = json!({
         "type": "heading",
         "attrs": { "level": h.depth }
@@ -64,7 +179,7 @@ fn convert_heading(h: &mdast::Heading) -> Value {
     result
 }
 
-fn convert_list(l: &mdast::List) -> Value {
+fn convert_list(l: &mdast::List, options: MdToTiptapOptions) -> Value {
     let has_checked = l
         .children
         .iter()
@@ -76,7 +191,7 @@ fn convert_list(l: &mdast::List) -> Value {
             .iter()
             .filter_map(|child| {
                 if let mdast::Node::ListItem(item) = child {
-                    Some(convert_task_item(item))
+                    Some(convert_task_item(item, options))
                 } else {
                     None
                 }
@@ -92,7 +207,7 @@ fn convert_list(l: &mdast::List) -> Value {
             .iter()
             .filter_map(|child| {
                 if let mdast::Node::ListItem(item) = child {
-                    Some(convert_list_item(item))
+                    Some(convert_list_item(item, options))
                 } else {
                     None
                 }
@@ -112,7 +227,7 @@ fn convert_list(l: &mdast::List) -> Value {
             .iter()
             .filter_map(|child| {
                 if let mdast::Node::ListItem(item) = child {
-                    Some(convert_list_item(item))
+                    Some(convert_list_item(item, options))
                 } else {
                     None
                 }
@@ -125,11 +240,11 @@ fn convert_list(l: &mdast::List) -> Value {
     }
 }
 
-fn convert_list_item(item: &mdast::ListItem) -> Value {
+fn convert_list_item(item: &mdast::ListItem, options: MdToTiptapOptions) -> Value {
     let content: Vec<Value> = item
         .children
         .iter()
-        .filter_map(convert_block_node)
+        .filter_map(|child| convert_block_node(child, options))
         .collect();
     json!({
         "type": "listItem",
@@ -137,11 +252,11 @@ fn convert_list_item(item: &mdast::ListItem) -> Value {
     })
 }
 
-fn convert_task_item(item: &mdast::ListItem) -> Value {
+fn convert_task_item(item: &mdast::ListItem, options: MdToTiptapOptions) -> Value {
     let content: Vec<Value> = item
         .children
         .iter()
-        .filter_map(convert_block_node)
+        .filter_map(|child| convert_block_node(child, options))
         .collect();
     json!({
         "type": "taskItem",
@@ -163,8 +278,12 @@ fn convert_code_block(c: &mdast::Code) -> Value {
     result
 }
 
-fn convert_blockquote(b: &mdast::Blockquote) -> Value {
-    let content: Vec<Value> = b.children.iter().filter_map(convert_block_node).collect();
+fn convert_blockquote(b: &mdast::Blockquote, options: MdToTiptapOptions) -> Value {
+    let content: Vec<Value> = b
+        .children
+        .iter()
+        .filter_map(|child| convert_block_node(child, options))
+        .collect();
     json!({
         "type": "blockquote",
         "content": content
@@ -185,90 +304,204 @@ fn convert_image(img: &mdast::Image) -> Value {
     })
 }
 
-fn convert_inline_nodes(nodes: &[mdast::Node]) -> Vec<Value> {
-    nodes.iter().filter_map(convert_inline_node).collect()
+fn convert_table(t: &mdast::Table, options: MdToTiptapOptions) -> Value {
+    let content: Vec<Value> = t
+        .children
+        .iter()
+        .enumerate()
+        .filter_map(|(index, row)| {
+            let mdast::Node::TableRow(row) = row else {
+                return None;
+            };
+            let cell_type = if index == 0 {
+                "tableHeader"
+            } else {
+                "tableCell"
+            };
+            let cells: Vec<Value> = row
+                .children
+                .iter()
+                .enumerate()
+                .filter_map(|(column, cell)| {
+                    let mdast::Node::TableCell(cell) = cell else {
+                        return None;
+                    };
+                    let paragraph = convert_paragraph(
+                        &mdast::Paragraph {
+                            children: cell.children.clone(),
+                            position: None,
+                        },
+                        options,
+                    );
+                    let mut result = json!({
+                        "type": cell_type,
+                        "content": [paragraph]
+                    });
+                    if let Some(align) = t.align.get(column).and_then(align_name) {
+                        result["attrs"] = json!({ "align": align });
+                    }
+                    Some(result)
+                })
+                .collect();
+            Some(json!({
+                "type": "tableRow",
+                "content": cells
+            }))
+        })
+        .collect();
+    json!({
+        "type": "table",
+        "content": content
+    })
 }
 
-fn convert_inline_node(node: &mdast::Node) -> Option<Value> {
-    match node {
-        mdast::Node::Text(t) => Some(json!({ "type": "text", "text": t.value })),
-        mdast::Node::Strong(s) => Some(convert_marked_text(&s.children, "bold")),
-        mdast::Node::Emphasis(e) => Some(convert_marked_text(&e.children, "italic")),
-        mdast::Node::InlineCode(c) => Some(json!({
-            "type": "text",
-            "text": c.value,
-            "marks": [{ "type": "code" }]
-        })),
-        mdast::Node::Link(l) => Some(convert_link(l)),
-        mdast::Node::Delete(d) => Some(convert_marked_text(&d.children, "strike")),
-        mdast::Node::Break(_) => Some(json!({ "type": "hardBreak" })),
-        mdast::Node::Image(img) => Some(convert_image(img)),
-        _ => None,
+fn align_name(align: &mdast::AlignKind) -> Option<&'static str> {
+    match align {
+        mdast::AlignKind::Left => Some("left"),
+        mdast::AlignKind::Right => Some("right"),
+        mdast::AlignKind::Center => Some("center"),
+        mdast::AlignKind::None => None,
     }
 }
 
-fn convert_marked_text(children: &[mdast::Node], mark_type: &str) -> Value {
-    let text = extract_text(children);
-    let mut existing_marks = extract_marks(children);
-    existing_marks.push(json!({ "type": mark_type }));
-
+fn convert_footnote_definition(f: &mdast::FootnoteDefinition, options: MdToTiptapOptions) -> Value {
+    let content: Vec<Value> = f
+        .children
+        .iter()
+        .filter_map(|child| convert_block_node(child, options))
+        .collect();
     json!({
-        "type": "text",
-        "text": text,
-        "marks": existing_marks
+        "type": "footnoteDefinition",
+        "attrs": { "label": footnote_label(&f.identifier, &f.label) },
+        "content": content
     })
 }
 
-fn convert_link(l: &mdast::Link) -> Value {
-    let text = extract_text(&l.children);
-    let mut existing_marks = extract_marks(&l.children);
+fn footnote_label(identifier: &str, label: &Option<String>) -> String {
+    label.clone().unwrap_or_else(|| identifier.to_string())
+}
 
-    let mut link_attrs = json!({ "href": l.url });
-    if let Some(title) = &l.title {
-        link_attrs["title"] = json!(title);
+fn convert_math(m: &mdast::Math) -> Value {
+    let mut attrs = json!({ "latex": m.value });
+    if let Some(meta) = &m.meta {
+        attrs["meta"] = json!(meta);
     }
-    existing_marks.push(json!({ "type": "link", "attrs": link_attrs }));
+    json!({
+        "type": "blockMath",
+        "attrs": attrs
+    })
+}
 
+fn convert_frontmatter(format: &str, value: &str) -> Value {
     json!({
-        "type": "text",
-        "text": text,
-        "marks": existing_marks
+        "type": "frontmatter",
+        "attrs": { "format": format, "value": value }
     })
 }
 
-fn extract_text(nodes: &[mdast::Node]) -> String {
+fn convert_inline_nodes(nodes: &[mdast::Node], options: MdToTiptapOptions) -> Vec<Value> {
     nodes
         .iter()
-        .map(|n| match n {
-            mdast::Node::Text(t) => t.value.clone(),
-            mdast::Node::Strong(s) => extract_text(&s.children),
-            mdast::Node::Emphasis(e) => extract_text(&e.children),
-            mdast::Node::InlineCode(c) => c.value.clone(),
-            mdast::Node::Link(l) => extract_text(&l.children),
-            mdast::Node::Delete(d) => extract_text(&d.children),
-            _ => String::new(),
-        })
-        .collect()
-}
-
-fn extract_marks(nodes: &[mdast::Node]) -> Vec<Value> {
-    let mut marks = Vec::new();
-    for node in nodes {
-        match node {
+        .flat_map(|node| match node {
+            mdast::Node::Text(t) if options.obsidian_nodes => split_wiki_links(&t.value),
             mdast::Node::Strong(s) => {
-                marks.push(json!({ "type": "bold" }));
-                marks.extend(extract_marks(&s.children));
+                convert_marked(&s.children, json!({ "type": "bold" }), options)
             }
             mdast::Node::Emphasis(e) => {
-                marks.push(json!({ "type": "italic" }));
-                marks.extend(extract_marks(&e.children));
+                convert_marked(&e.children, json!({ "type": "italic" }), options)
             }
             mdast::Node::Delete(d) => {
-                marks.push(json!({ "type": "strike" }));
-                marks.extend(extract_marks(&d.children));
+                convert_marked(&d.children, json!({ "type": "strike" }), options)
             }
-            _ => {}
+            mdast::Node::Link(l) => convert_marked(&l.children, link_mark(l), options),
+            _ => convert_inline_node(node, options).into_iter().collect(),
+        })
+        .collect()
+}
+
+/// Splits Obsidian `[[target|alias]]` links and `![[target]]` embeds out of plain text.
+fn split_wiki_links(text: &str) -> Vec<Value> {
+    fn push_text(text: &str, result: &mut Vec<Value>) {
+        if !text.is_empty() {
+            result.push(json!({ "type": "text", "text": text }));
         }
     }
-    marks
+
+    let mut result = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("[[") {
+        let after = &rest[start + 2..];
+        let Some(len) = after.find("]]") else {
+            break;
+        };
+        let inner = &after[..len];
+        if inner.is_empty() || inner.contains("[[") || inner.contains('\n') {
+            push_text(&rest[..start + 2], &mut result);
+            rest = after;
+            continue;
+        }
+
+        let embed = rest[..start].ends_with('!');
+        let text_end = if embed { start - 1 } else { start };
+        push_text(&rest[..text_end], &mut result);
+
+        let (target, alias) = match inner.split_once('|') {
+            Some((target, alias)) => (target, Some(alias)),
+            None => (inner, None),
+        };
+        result.push(json!({
+            "type": if embed { "embed" } else { "wikiLink" },
+            "attrs": { "target": target, "alias": alias }
+        }));
+        rest = &after[len + 2..];
+    }
+    push_text(rest, &mut result);
+    result
+}
+
+fn convert_inline_node(node: &mdast::Node, options: MdToTiptapOptions) -> Option<Value> {
+    let obsidian = options.obsidian_nodes;
+    match node {
+        mdast::Node::Text(t) => Some(json!({ "type": "text", "text": t.value })),
+        mdast::Node::InlineCode(c) => Some(json!({
+            "type": "text",
+            "text": c.value,
+            "marks": [{ "type": "code" }]
+        })),
+        mdast::Node::Break(_) => Some(json!({ "type": "hardBreak" })),
+        mdast::Node::Image(img) => Some(convert_image(img)),
+        mdast::Node::InlineMath(m) if obsidian => Some(json!({
+            "type": "inlineMath",
+            "attrs": { "latex": m.value }
+        })),
+        mdast::Node::Html(h) if obsidian => Some(json!({
+            "type": "htmlInline",
+            "attrs": { "html": h.value }
+        })),
+        mdast::Node::FootnoteReference(f) if obsidian => Some(json!({
+            "type": "footnoteReference",
+            "attrs": { "label": footnote_label(&f.identifier, &f.label) }
+        })),
+        _ => None,
+    }
+}
+
+/// Converts the children of a mark and adds the mark to each resulting node, outermost last.
+fn convert_marked(children: &[mdast::Node], mark: Value, options: MdToTiptapOptions) -> Vec<Value> {
+    let mut nodes = convert_inline_nodes(children, options);
+    for node in &mut nodes {
+        match node.get_mut("marks").and_then(Value::as_array_mut) {
+            Some(marks) => marks.push(mark.clone()),
+            None => node["marks"] = json!([mark.clone()]),
+        }
+    }
+    nodes
+}
+
+fn link_mark(l: &mdast::Link) -> Value {
+    let mut link_attrs = json!({ "href": l.url });
+    if let Some(title) = &l.title {
+        link_attrs["title"] = json!(title);
+    }
+    json!({ "type": "link", "attrs": link_attrs })
 }
@@ -5,7 +5,7 @@ mod from_md;
 mod to_ast;
 
 pub use from_ast::mdast_to_markdown;
-pub use from_md::md_to_tiptap_json;
+pub use from_md::{MdToTiptapOptions, md_to_tiptap_json, md_to_tiptap_json_with};
 pub use to_ast::tiptap_json_to_mdast;
 
 pub fn tiptap_json_to_md(json: &serde_json::Value) -> Result<String, String> {
@@ -16,6 +16,27 @@ pub fn tiptap_json_to_md(json: &serde_json::Value) -> Result<String, String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use quickcheck::{Arbitrary, Gen};
+    use quickcheck_macros::quickcheck;
+
+    const OBSIDIAN_NOTES: &[&str] = &[
+        include_str!("./testdata/obsidian/daily-note.md"),
+        include_str!("./testdata/obsidian/meeting.md"),
+        include_str!("./testdata/obsidian/research.md"),
+        include_str!("./testdata/obsidian/reading-list.md"),
+    ];
+
+    const OBSIDIAN: MdToTiptapOptions = MdToTiptapOptions {
+        obsidian_nodes: true,
+    };
+
+    fn roundtrip(md: &str) -> String {
+        tiptap_json_to_md(&md_to_tiptap_json_with(md, OBSIDIAN).unwrap()).unwrap()
+    }
+
+    fn default_roundtrip(md: &str) -> String {
+        tiptap_json_to_md(&md_to_tiptap_json(md).unwrap()).unwrap()
+    }
 
     fn to_md(json: serde_json::Value) -> String {
         let mdast = tiptap_json_to_mdast(&json);
@@ -152,7 +173,7 @@ mod tests {
 
         ***
 
-        [A ](https://example.com)[link](https://example.com)
+        [A link](https://example.com)
         ");
     }
 
@@ -469,7 +490,7 @@ mod tests {
 
         **Bold with *****nested italic***** inside** normal text.
 
-        **[Bold link](https://example.com)** and *`italic code`*
+        [**Bold link**](https://example.com) and *`italic code`*
         ");
     }
 
@@ -847,4 +868,361 @@ mod tests {
         let result = tiptap_json_to_md(&json);
         assert!(result.is_ok(), "should not panic on multi-byte chars");
     }
+
+    #[test]
+    fn test_md_to_tiptap_obsidian_nodes() {
+        let json = md_to_tiptap_json_with(
+            "---\ntitle: Note\n---\n\nSee [[Page|alias]] and ![[img.png]], $x^2$[^1].\n\n[^1]: Footnote.",
+            OBSIDIAN,
+        )
+        .unwrap();
+        let content = json["content"].as_array().unwrap();
+
+        assert_eq!(content[0]["type"], "frontmatter");
+        assert_eq!(content[0]["attrs"]["value"], "title: Note");
+
+        let inline = content[1]["content"].as_array().unwrap();
+        let types: Vec<&str> = inline.iter().map(|n| n["type"].as_str().unwrap()).collect();
+        assert_eq!(
+            types,
+            [
+                "text",
+                "wikiLink",
+                "text",
+                "embed",
+                "text",
+                "inlineMath",
+                "footnoteReference",
+                "text"
+            ]
+        );
+        assert_eq!(inline[1]["attrs"]["target"], "Page");
+        assert_eq!(inline[1]["attrs"]["alias"], "alias");
+        assert_eq!(inline[3]["attrs"]["target"], "img.png");
+        assert_eq!(inline[5]["attrs"]["latex"], "x^2");
+
+        assert_eq!(content[2]["type"], "footnoteDefinition");
+        assert_eq!(content[2]["attrs"]["label"], "1");
+    }
+
+    #[test]
+    fn test_md_to_tiptap_default_has_no_obsidian_nodes() {
+        let json = md_to_tiptap_json(
+            "---\ntitle: Note\n---\n\nSee [[Page]], $x^2$[^1] and <mark>a</mark>.\n\n[^1]: Footnote.\n\n$$\nx\n$$",
+        )
+        .unwrap();
+
+        fn collect_types(node: &serde_json::Value, types: &mut Vec<String>) {
+            if let Some(ty) = node["type"].as_str() {
+                types.push(ty.to_string());
+            }
+            for child in node["content"].as_array().into_iter().flatten() {
+                collect_types(child, types);
+            }
+        }
+        let mut types = Vec::new();
+        collect_types(&json, &mut types);
+
+        for ty in [
+            "frontmatter",
+            "wikiLink",
+            "embed",
+            "inlineMath",
+            "blockMath",
+            "footnoteReference",
+            "footnoteDefinition",
+            "htmlBlock",
+            "htmlInline",
+        ] {
+            assert!(!types.iter().any(|t| t == ty), "unexpected {ty} node");
+        }
+        assert!(
+            json.to_string().contains("See [[Page]], $x^2$"),
+            "wiki links and math should stay text"
+        );
+    }
+
+    #[test]
+    fn test_default_roundtrip_keeps_unsupported_markdown_as_text() {
+        let md = "---\ntitle: Q&A\n---\n\nSee [[Page]][^1] and <mark>a</mark>.\n\n<p>a&nbsp;b</p>\n\n[^1]: The **note**.";
+        let json = md_to_tiptap_json(md).unwrap();
+        let content = json["content"].as_array().unwrap();
+
+        assert_eq!(content[0]["content"][0]["text"], "---\ntitle: Q&A\n---");
+        assert_eq!(content[2]["content"][0]["text"], "<p>a&nbsp;b</p>");
+        assert_eq!(content[3]["content"][0]["text"], "[^1]: ");
+        assert_eq!(content[3]["content"][2]["marks"][0]["type"], "bold");
+
+        let once = default_roundtrip(md);
+        assert_eq!(once.trim(), md);
+        assert_eq!(default_roundtrip(&once), once);
+    }
+
+    #[test]
+    fn test_roundtrip_frontmatter() {
+        let md = "---\ntitle: Note\ntags:\n  - a\n---\n\n# Heading";
+        assert_eq!(roundtrip(md).trim(), md);
+    }
+
+    #[test]
+    fn test_roundtrip_leading_rule_is_not_frontmatter() {
+        let md = "---\n\n> quote\n\n```\ncode\n```";
+        assert_eq!(roundtrip(md).trim(), "***\n\n> quote\n\n```\ncode\n```");
+    }
+
+    #[test]
+    fn test_roundtrip_table() {
+        let md = "| Name | Link | Count |\n| :--- | :---: | ---: |\n| **a** | [[Page\\|alias]] | 1 |\n| b |  | 2 |";
+        assert_eq!(roundtrip(md).trim(), md);
+    }
+
+    #[test]
+    fn test_roundtrip_footnotes() {
+        let md = "Text with a note[^1].\n\n[^1]: The note.\n\n    Second paragraph.";
+        assert_eq!(roundtrip(md).trim(), md);
+    }
+
+    #[test]
+    fn test_roundtrip_math() {
+        let md = "Inline $a_1 + b^2$ math.\n\n$$\n\\int_0^1 x \\, dx\n$$";
+        assert_eq!(roundtrip(md).trim(), md);
+    }
+
+    #[test]
+    fn test_roundtrip_html_and_wiki_links() {
+        let md = "<div class=\"callout\">\n\n- [[Note]] with <mark>highlight</mark>\n- ![[diagram.png|400]]\n\n</div>";
+        assert_eq!(roundtrip(md).trim(), md);
+    }
+
+    #[test]
+    fn test_roundtrip_obsidian_notes() {
+        for note in OBSIDIAN_NOTES {
+            let once = roundtrip(note);
+            assert_eq!(roundtrip(&once), once);
+            assert_eq!(
+                md_to_tiptap_json_with(&once, OBSIDIAN),
+                md_to_tiptap_json_with(note, OBSIDIAN)
+            );
+        }
+    }
+
+    /// A random document built from the block and inline nodes the converter understands.
+    #[derive(Debug, Clone)]
+    struct Doc {
+        frontmatter: bool,
+        blocks: Vec<Block>,
+    }
+
+    #[derive(Debug, Clone)]
+    enum Block {
+        Paragraph(Vec<Inline>),
+        Heading(u8, Vec<Inline>),
+        BulletList(Vec<Vec<Block>>),
+        TaskList(Vec<(bool, Vec<Inline>)>),
+        Blockquote(Vec<Block>),
+        Code(String),
+        Math(String),
+        Table(Vec<Vec<Vec<Inline>>>),
+        Footnote(u8, Vec<Inline>),
+        Rule,
+    }
+
+    #[derive(Debug, Clone)]
+    enum Inline {
+        Text(String),
+        Strong(Vec<Inline>),
+        Emphasis(Vec<Inline>),
+        Code(String),
+        Link(String, Vec<Inline>),
+        WikiLink(String, Option<String>),
+        Embed(String),
+        Math(String),
+        FootnoteReference(u8),
+    }
+
+    const WORDS: &[&str] = &[
+        "note", "Daily", "x^2", "café", "42", "to-do", "a.b", "über", "Q&A", "(draft)",
+    ];
+
+    fn word(g: &mut Gen) -> String {
+        g.choose(WORDS).unwrap().to_string()
+    }
+
+    fn words(g: &mut Gen) -> String {
+        let count = 1 + usize::arbitrary(g) % 3;
+        (0..count).map(|_| word(g)).collect::<Vec<_>>().join(" ")
+    }
+
+    fn list<T>(g: &mut Gen, max: usize, mut item: impl FnMut(&mut Gen) -> T) -> Vec<T> {
+        let count = 1 + usize::arbitrary(g) % max;
+        (0..count).map(|_| item(g)).collect()
+    }
+
+    fn inline(g: &mut Gen, depth: usize) -> Inline {
+        let choice = u8::arbitrary(g) % if depth == 0 { 6 } else { 9 };
+        match choice {
+            0 => Inline::Code(words(g)),
+            1 => Inline::WikiLink(words(g), bool::arbitrary(g).then(|| word(g))),
+            2 => Inline::Embed(format!("{}.png", word(g))),
+            3 => Inline::Math(word(g)),
+            4 => Inline::FootnoteReference(u8::arbitrary(g) % 3 + 1),
+            5 => Inline::Text(words(g)),
+            6 => Inline::Strong(inlines(g, depth - 1)),
+            7 => Inline::Emphasis(inlines(g, depth - 1)),
+            _ => Inline::Link(
+                format!("https://example.com/{}", u8::arbitrary(g)),
+                inlines(g, 0),
+            ),
+        }
+    }
+
+    fn inlines(g: &mut Gen, depth: usize) -> Vec<Inline> {
+        list(g, 4, |g| inline(g, depth))
+    }
+
+    /// Footnote definitions are only generated at the top level, where notes put them.
+    fn block(g: &mut Gen, depth: usize, top_level: bool) -> Block {
+        let choice = u8::arbitrary(g) % if depth == 0 { 8 } else { 10 };
+        match choice {
+            0 => Block::Heading(u8::arbitrary(g) % 6 + 1, inlines(g, 1)),
+            1 => Block::TaskList(list(g, 3, |g| (bool::arbitrary(g), inlines(g, 1)))),
+            2 => Block::Code(list(g, 3, words).join("\n")),
+            3 => Block::Math(words(g)),
+            4 => {
+                let columns = 1 + usize::arbitrary(g) % 3;
+                Block::Table(list(g, 3, |g| {
+                    (0..columns).map(|_| inlines(g, 0)).collect()
+                }))
+            }
+            5 if top_level => Block::Footnote(u8::arbitrary(g) % 3 + 1, inlines(g, 1)),
+            6 => Block::Rule,
+            5 | 7 => Block::Paragraph(inlines(g, 2)),
+            8 => Block::BulletList(list(g, 3, |g| {
+                // An item has to open with text, `- ---` would be a thematic break instead.
+                let mut item = vec![Block::Paragraph(inlines(g, 2))];
+                if bool::arbitrary(g) {
+                    item.extend(blocks(g, depth - 1));
+                }
+                item
+            })),
+            _ => Block::Blockquote(blocks(g, depth - 1)),
+        }
+    }
+
+    fn blocks(g: &mut Gen, depth: usize) -> Vec<Block> {
+        list(g, 3, |g| block(g, depth, false))
+    }
+
+    impl Arbitrary for Doc {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Doc {
+                frontmatter: bool::arbitrary(g),
+                blocks: list(g, 6, |g| block(g, 2, true)),
+            }
+        }
+    }
+
+    fn render_inlines(inlines: &[Inline]) -> String {
+        // Adjacent runs are separated so that delimiters never merge into a different construct.
+        inlines
+            .iter()
+            .map(render_inline)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn render_inline(inline: &Inline) -> String {
+        match inline {
+            Inline::Text(text) => text.clone(),
+            Inline::Strong(children) => format!("**{}**", render_inlines(children)),
+            Inline::Emphasis(children) => format!("*{}*", render_inlines(children)),
+            Inline::Code(code) => format!("`{code}`"),
+            Inline::Link(url, children) => format!("[{}]({url})", render_inlines(children)),
+            Inline::WikiLink(target, None) => format!("[[{target}]]"),
+            Inline::WikiLink(target, Some(alias)) => format!("[[{target}|{alias}]]"),
+            Inline::Embed(target) => format!("![[{target}]]"),
+            Inline::Math(latex) => format!("${latex}$"),
+            Inline::FootnoteReference(label) => format!("[^{label}]"),
+        }
+    }
+
+    fn render_blocks(blocks: &[Block], indent: &str) -> String {
+        blocks
+            .iter()
+            .map(|block| render_block(block, indent))
+            .collect::<Vec<_>>()
+            .join(&format!("\n{indent}\n{indent}"))
+    }
+
+    fn render_block(block: &Block, indent: &str) -> String {
+        match block {
+            Block::Paragraph(children) => render_inlines(children),
+            Block::Heading(level, children) => {
+                format!(
+                    "{} {}",
+                    "#".repeat(*level as usize),
+                    render_inlines(children)
+                )
+            }
+            Block::BulletList(items) => items
+                .iter()
+                .map(|item| format!("- {}", render_blocks(item, &format!("{indent}  "))))
+                .collect::<Vec<_>>()
+                .join(&format!("\n{indent}")),
+            Block::TaskList(items) => items
+                .iter()
+                .map(|(checked, children)| {
+                    let mark = if *checked { "x" } else { " " };
+                    format!("- [{mark}] {}", render_inlines(children))
+                })
+                .collect::<Vec<_>>()
+                .join(&format!("\n{indent}")),
+            Block::Blockquote(children) => {
+                format!("> {}", render_blocks(children, &format!("{indent}> ")))
+            }
+            Block::Code(code) => {
+                let code = code.replace('\n', &format!("\n{indent}"));
+                format!("```\n{indent}{code}\n{indent}```")
+            }
+            Block::Math(latex) => format!("$$\n{indent}{latex}\n{indent}$$"),
+            Block::Table(rows) => {
+                let render_row = |row: &Vec<Vec<Inline>>| {
+                    let cells: Vec<String> = row
+                        .iter()
+                        .map(|cell| render_inlines(cell).replace('|', "\\|"))
+                        .collect();
+                    format!("| {} |", cells.join(" | "))
+                };
+                let delimiter = format!("|{}", " --- |".repeat(rows[0].len()));
+                let mut lines = vec![render_row(&rows[0]), delimiter];
+                lines.extend(rows[1..].iter().map(render_row));
+                lines.join(&format!("\n{indent}"))
+            }
+            Block::Footnote(label, children) => {
+                format!("[^{label}]: {}", render_inlines(children))
+            }
+            Block::Rule => "---".to_string(),
+        }
+    }
+
+    fn render_doc(doc: &Doc) -> String {
+        let body = render_blocks(&doc.blocks, "");
+        if doc.frontmatter {
+            format!("---\ntitle: Note\n---\n\n{body}\n")
+        } else {
+            format!("{body}\n")
+        }
+    }
+
+    #[quickcheck]
+    fn prop_md_roundtrip_is_idempotent(doc: Doc) -> bool {
+        let once = roundtrip(&render_doc(&doc));
+        roundtrip(&once) == once
+    }
+
+    #[quickcheck]
+    fn prop_default_md_roundtrip_is_idempotent(doc: Doc) -> bool {
+        let once = default_roundtrip(&render_doc(&doc));
+        default_roundtrip(&once) == once
+    }
 }
@@ -32,6 +32,11 @@ fn convert_node(node: &serde_json::Value) -> Option<mdast::Node> {
         "horizontalRule" => Some(convert_horizontal_rule()),
         "hardBreak" => Some(convert_hard_break()),
         "image" => Some(convert_image(node)),
+        "table" => Some(convert_table(node)),
+        "footnoteDefinition" => Some(convert_footnote_definition(node)),
+        "blockMath" => Some(convert_block_math(node)),
+        "htmlBlock" => Some(convert_html(node)),
+        "frontmatter" => convert_frontmatter(node),
         "text" => convert_text(node),
         _ => None,
     }
@@ -147,12 +152,20 @@ fn convert_list_item(node: &serde_json::Value, checked: Option<bool>) -> mdast::
     let children = convert_content(node);
     mdast::Node::ListItem(mdast::ListItem {
         checked,
-        spread: false,
+        spread: needs_spread(&children),
         children,
         position: None,
     })
 }
 
+/// A tight item joins its blocks with a bare newline, so only a nested list can follow another
+/// block without being read back as a lazy continuation of it.
+fn needs_spread(children: &[mdast::Node]) -> bool {
+    children
+        .windows(2)
+        .any(|pair| !matches!(pair[1], mdast::Node::List(_)))
+}
+
 fn convert_code_block(node: &serde_json::Value) -> mdast::Node {
     let lang = node
         .get("attrs")
@@ -220,96 +233,256 @@ fn convert_image(node: &serde_json::Value) -> mdast::Node {
     })
 }
 
-fn convert_text(node: &serde_json::Value) -> Option<mdast::Node> {
-    let text = node.get("text")?.as_str()?;
-    Some(mdast::Node::Text(mdast::Text {
-        value: text.to_string(),
+fn convert_table(node: &serde_json::Value) -> mdast::Node {
+    let rows: Vec<&serde_json::Value> = node
+        .get("content")
+        .and_then(|c| c.as_array())
+        .map(|rows| rows.iter().collect())
+        .unwrap_or_default();
+
+    let align = rows
+        .first()
+        .and_then(|row| row.get("content"))
+        .and_then(|c| c.as_array())
+        .map(|cells| {
+            cells
+                .iter()
+                .map(|cell| match attr_str(cell, "align") {
+                    Some("left") => mdast::AlignKind::Left,
+                    Some("right") => mdast::AlignKind::Right,
+                    Some("center") => mdast::AlignKind::Center,
+                    _ => mdast::AlignKind::None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let children = rows
+        .into_iter()
+        .map(|row| {
+            let cells = row
+                .get("content")
+                .and_then(|c| c.as_array())
+                .map(|cells| {
+                    cells
+                        .iter()
+                        .map(|cell| {
+                            // Cells hold paragraphs in tiptap but only inline content in markdown.
+                            let children = convert_content(cell)
+                                .into_iter()
+                                .flat_map(|block| match block {
+                                    mdast::Node::Paragraph(p) => p.children,
+                                    other => vec![other],
+                                })
+                                .collect();
+                            mdast::Node::TableCell(mdast::TableCell {
+                                children,
+                                position: None,
+                            })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            mdast::Node::TableRow(mdast::TableRow {
+                children: cells,
+                position: None,
+            })
+        })
+        .collect();
+
+    mdast::Node::Table(mdast::Table {
+        children,
+        align,
         position: None,
-    }))
+    })
 }
 
-fn convert_inline_content(node: &serde_json::Value) -> Vec<mdast::Node> {
-    let Some(content) = node.get("content").and_then(|c| c.as_array()) else {
-        return vec![];
-    };
+fn convert_footnote_definition(node: &serde_json::Value) -> mdast::Node {
+    let label = attr_str(node, "label").unwrap_or_default().to_string();
+    mdast::Node::FootnoteDefinition(mdast::FootnoteDefinition {
+        children: convert_content(node),
+        identifier: label.to_lowercase(),
+        label: Some(label),
+        position: None,
+    })
+}
 
-    content.iter().filter_map(convert_inline_node).collect()
+fn convert_block_math(node: &serde_json::Value) -> mdast::Node {
+    mdast::Node::Math(mdast::Math {
+        value: attr_str(node, "latex").unwrap_or_default().to_string(),
+        meta: attr_str(node, "meta").map(|s| s.to_string()),
+        position: None,
+    })
 }
 
-fn convert_inline_node(node: &serde_json::Value) -> Option<mdast::Node> {
-    let node_type = node.get("type")?.as_str()?;
+fn convert_html(node: &serde_json::Value) -> mdast::Node {
+    mdast::Node::Html(mdast::Html {
+        value: attr_str(node, "html").unwrap_or_default().to_string(),
+        position: None,
+    })
+}
 
-    match node_type {
-        "text" => convert_text_with_marks(node),
-        "hardBreak" => Some(convert_hard_break()),
-        "image" => Some(convert_image(node)),
-        _ => None,
+fn convert_frontmatter(node: &serde_json::Value) -> Option<mdast::Node> {
+    let value = attr_str(node, "value").unwrap_or_default().to_string();
+    match attr_str(node, "format") {
+        Some("toml") => Some(mdast::Node::Toml(mdast::Toml {
+            value,
+            position: None,
+        })),
+        Some("yaml") | None => Some(mdast::Node::Yaml(mdast::Yaml {
+            value,
+            position: None,
+        })),
+        Some(_) => None,
     }
 }
 
-fn convert_text_with_marks(node: &serde_json::Value) -> Option<mdast::Node> {
-    let text = node.get("text")?.as_str()?;
-    let marks = node.get("marks").and_then(|m| m.as_array());
+/// mdast has no wiki link node, so the Obsidian syntax goes out as raw markup.
+fn convert_wiki_link(node: &serde_json::Value, embed: bool) -> mdast::Node {
+    let target = attr_str(node, "target").unwrap_or_default();
+    let mut value = if embed { "![[" } else { "[[" }.to_string();
+    value.push_str(target);
+    if let Some(alias) = attr_str(node, "alias") {
+        value.push('|');
+        value.push_str(alias);
+    }
+    value.push_str("]]");
 
-    let text_node = mdast::Node::Text(mdast::Text {
+    mdast::Node::Html(mdast::Html {
+        value,
+        position: None,
+    })
+}
+
+fn attr_str<'a>(node: &'a serde_json::Value, key: &str) -> Option<&'a str> {
+    node.get("attrs")
+        .and_then(|a| a.get(key))
+        .and_then(|v| v.as_str())
+}
+
+fn convert_text(node: &serde_json::Value) -> Option<mdast::Node> {
+    let text = node.get("text")?.as_str()?;
+    Some(mdast::Node::Text(mdast::Text {
         value: text.to_string(),
         position: None,
-    });
+    }))
+}
 
-    let Some(marks) = marks else {
-        return Some(text_node);
+fn convert_inline_content(node: &serde_json::Value) -> Vec<mdast::Node> {
+    let Some(content) = node.get("content").and_then(|c| c.as_array()) else {
+        return vec![];
     };
 
-    let mut result = text_node;
+    let marked: Vec<(Vec<&serde_json::Value>, mdast::Node)> = content
+        .iter()
+        .filter_map(|node| {
+            let marks = node
+                .get("marks")
+                .and_then(|m| m.as_array())
+                .map(|marks| marks.iter().filter(|mark| is_wrapping_mark(mark)).collect())
+                .unwrap_or_default();
+            Some((marks, convert_inline_node(node)?))
+        })
+        .collect();
+    wrap_marks(marked)
+}
 
-    for mark in marks.iter().rev() {
-        let mark_type = mark.get("type").and_then(|t| t.as_str());
-        result = match mark_type {
+/// Wraps each run of adjacent nodes that share their outermost (last) mark in one node for that
+/// mark, so `**a $x$ b**` stays a single strong span.
+fn wrap_marks(nodes: Vec<(Vec<&serde_json::Value>, mdast::Node)>) -> Vec<mdast::Node> {
+    let mut result = Vec::new();
+    let mut nodes = nodes.into_iter().peekable();
+    while let Some((mut marks, node)) = nodes.next() {
+        let Some(outer) = marks.pop() else {
+            result.push(node);
+            continue;
+        };
+
+        let mut run = vec![(marks, node)];
+        while let Some((mut marks, node)) = nodes.next_if(|(marks, _)| marks.last() == Some(&outer))
+        {
+            marks.pop();
+            run.push((marks, node));
+        }
+
+        let children = wrap_marks(run);
+        result.push(match outer.get("type").and_then(|t| t.as_str()) {
             Some("bold") | Some("strong") => mdast::Node::Strong(mdast::Strong {
-                children: vec![result],
+                children,
                 position: None,
             }),
             Some("italic") | Some("em") => mdast::Node::Emphasis(mdast::Emphasis {
-                children: vec![result],
+                children,
                 position: None,
             }),
-            Some("code") => {
-                if let mdast::Node::Text(t) = result {
-                    mdast::Node::InlineCode(mdast::InlineCode {
-                        value: t.value,
-                        position: None,
-                    })
-                } else {
-                    result
-                }
-            }
-            Some("link") => {
-                let url = mark
-                    .get("attrs")
-                    .and_then(|a| a.get("href"))
-                    .and_then(|h| h.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                let title = mark
-                    .get("attrs")
-                    .and_then(|a| a.get("title"))
-                    .and_then(|t| t.as_str())
-                    .map(|s| s.to_string());
-
-                mdast::Node::Link(mdast::Link {
-                    url,
-                    title,
-                    children: vec![result],
-                    position: None,
-                })
-            }
-            Some("strike") => mdast::Node::Delete(mdast::Delete {
-                children: vec![result],
+            Some("link") => mdast::Node::Link(mdast::Link {
+                url: attr_str(outer, "href").unwrap_or_default().to_string(),
+                title: attr_str(outer, "title").map(str::to_string),
+                children,
                 position: None,
             }),
-            _ => result,
-        };
+            _ => mdast::Node::Delete(mdast::Delete {
+                children,
+                position: None,
+            }),
+        });
     }
+    result
+}
 
-    Some(result)
+/// Marks that become a wrapping node; `code` instead turns the text itself into inline code.
+fn is_wrapping_mark(mark: &serde_json::Value) -> bool {
+    matches!(
+        mark.get("type").and_then(|t| t.as_str()),
+        Some("bold" | "strong" | "italic" | "em" | "link" | "strike")
+    )
+}
+
+fn convert_inline_node(node: &serde_json::Value) -> Option<mdast::Node> {
+    let node_type = node.get("type")?.as_str()?;
+
+    match node_type {
+        "text" => convert_text_with_code_mark(node),
+        "hardBreak" => Some(convert_hard_break()),
+        "image" => Some(convert_image(node)),
+        "inlineMath" => Some(mdast::Node::InlineMath(mdast::InlineMath {
+            value: attr_str(node, "latex").unwrap_or_default().to_string(),
+            position: None,
+        })),
+        "htmlInline" => Some(convert_html(node)),
+        "footnoteReference" => {
+            let label = attr_str(node, "label").unwrap_or_default().to_string();
+            Some(mdast::Node::FootnoteReference(mdast::FootnoteReference {
+                identifier: label.to_lowercase(),
+                label: Some(label),
+                position: None,
+            }))
+        }
+        "wikiLink" | "embed" => Some(convert_wiki_link(node, node_type == "embed")),
+        _ => None,
+    }
+}
+
+fn convert_text_with_code_mark(node: &serde_json::Value) -> Option<mdast::Node> {
+    let value = node.get("text")?.as_str()?.to_string();
+    let code = node
+        .get("marks")
+        .and_then(|m| m.as_array())
+        .is_some_and(|marks| {
+            marks
+                .iter()
+                .any(|mark| mark.get("type").and_then(|t| t.as_str()) == Some("code"))
+        });
+
+    Some(if code {
+        mdast::Node::InlineCode(mdast::InlineCode {
+            value,
+            position: None,
+        })
+    } else {
+        mdast::Node::Text(mdast::Text {
+            value,
+            position: None,
+        })
+    })
 }
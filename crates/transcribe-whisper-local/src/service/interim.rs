@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use owhisper_interface::stream::Word;
+
+/// Partial results while someone is still speaking, read from the `custom_query`
+/// (`interim_results=true`, `interim_interval_ms`, `interim_window_ms`, `stability_window_ms`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InterimConfig {
+    /// Audio that has to accumulate between two interim passes.
+    pub interval: Duration,
+    /// Most recent speech each interim pass transcribes; the final pass covers the rest.
+    pub window: Duration,
+    /// Trailing audio whose words are only published once two passes agree on them.
+    pub stability_window: Duration,
+}
+
+impl Default for InterimConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(1000),
+            window: Duration::from_secs(10),
+            stability_window: Duration::from_millis(1500),
+        }
+    }
+}
+
+impl InterimConfig {
+    pub fn from_query(query: Option<&HashMap<String, String>>) -> Option<Self> {
+        let query = query?;
+        let enabled = query
+            .get("interim_results")
+            .is_some_and(|v| v == "true" || v == "1");
+        if !enabled {
+            return None;
+        }
+
+        let millis = |key: &str| {
+            query
+                .get(key)
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(Duration::from_millis)
+        };
+        let default = Self::default();
+
+        Some(Self {
+            interval: millis("interim_interval_ms").unwrap_or(default.interval),
+            window: millis("interim_window_ms").unwrap_or(default.window),
+            stability_window: millis("stability_window_ms").unwrap_or(default.stability_window),
+        })
+    }
+}
+
+/// Keeps interim hypotheses from flickering: a word is published once it ends before the
+/// stability window or the previous pass over the same speech produced it at the same position.
+#[derive(Debug, Default)]
+pub struct StabilityFilter {
+    previous: HashMap<String, Vec<String>>,
+}
+
+impl StabilityFilter {
+    /// `words` are relative to the start of the speech, which is `audio_secs` long so far.
+    pub fn stable_prefix(
+        &mut self,
+        source: &str,
+        words: Vec<Word>,
+        audio_secs: f64,
+        window: Duration,
+    ) -> Vec<Word> {
+        let cutoff = audio_secs - window.as_secs_f64();
+        let current: Vec<String> = words.iter().map(|w| normalize(&w.word)).collect();
+        let previous = self
+            .previous
+            .insert(source.to_string(), current.clone())
+            .unwrap_or_default();
+
+        let stable = words
+            .iter()
+            .enumerate()
+            .take_while(|(i, word)| word.end <= cutoff || previous.get(*i) == Some(&current[*i]))
+            .count();
+
+        words.into_iter().take(stable).collect()
+    }
+
+    /// The speech ended; the next interim pass for `source` starts a new hypothesis.
+    pub fn reset(&mut self, source: &str) {
+        self.previous.remove(source);
+    }
+}
+
+fn normalize(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(spec: &[(&str, f64)]) -> Vec<Word> {
+        spec.iter()
+            .map(|(word, end)| Word {
+                word: word.to_string(),
+                start: end - 0.3,
+                end: *end,
+                confidence: 1.0,
+                speaker: None,
+                punctuated_word: None,
+                language: None,
+            })
+            .collect()
+    }
+
+    fn texts(words: &[Word]) -> Vec<&str> {
+        words.iter().map(|w| w.word.as_str()).collect()
+    }
+
+    #[test]
+    fn test_trailing_words_wait_for_agreement() {
+        let window = Duration::from_millis(1000);
+        let mut filter = StabilityFilter::default();
+
+        let first = filter.stable_prefix(
+            "mic",
+            words(&[("hello", 0.5), ("word", 1.4), ("is", 1.8)]),
+            2.0,
+            window,
+        );
+        assert_eq!(texts(&first), ["hello"]);
+
+        let second = filter.stable_prefix(
+            "mic",
+            words(&[("hello", 0.5), ("world,", 1.4), ("this", 2.2), ("is", 2.6)]),
+            3.0,
+            window,
+        );
+        assert_eq!(texts(&second), ["hello", "world,"]);
+
+        let third = filter.stable_prefix(
+            "mic",
+            words(&[("Hello", 0.5), ("world", 1.4), ("this", 2.2), ("was", 3.0)]),
+            3.5,
+            window,
+        );
+        assert_eq!(texts(&third), ["Hello", "world", "this"]);
+
+        filter.reset("mic");
+        let fresh = filter.stable_prefix("mic", words(&[("next", 0.4)]), 0.8, window);
+        assert!(fresh.is_empty());
+    }
+
+    #[test]
+    fn test_interim_config_from_query() {
+        assert_eq!(InterimConfig::from_query(None), None);
+
+        let mut query = HashMap::new();
+        query.insert("interim_results".to_string(), "true".to_string());
+        query.insert("stability_window_ms".to_string(), "800".to_string());
+        query.insert("interim_window_ms".to_string(), "5000".to_string());
+
+        let config = InterimConfig::from_query(Some(&query)).unwrap();
+        assert_eq!(config.interval, Duration::from_millis(1000));
+        assert_eq!(config.window, Duration::from_secs(5));
+        assert_eq!(config.stability_window, Duration::from_millis(800));
+    }
+}
@@ -1,3 +1,6 @@
+mod interim;
+pub use interim::*;
+
 mod streaming;
 pub use streaming::*;
 
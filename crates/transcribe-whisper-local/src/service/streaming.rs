@@ -1,4 +1,5 @@
 use std::{
+    collections::VecDeque,
     future::Future,
     path::PathBuf,
     pin::Pin,
@@ -17,12 +18,14 @@ use axum::{
 use futures_util::{SinkExt, StreamExt};
 use tower::Service;
 
-use hypr_vad_ext::VadExt;
+use hypr_vad_ext::{SpeechEvent, VadExt};
 use hypr_ws_utils::{ConnectionGuard, ConnectionManager};
 use owhisper_interface::ListenParams;
 use owhisper_interface::stream::{Alternatives, Channel, Metadata, StreamResponse, Word};
 
-use crate::GlobalTimer;
+use crate::{GlobalTimer, InterimConfig, StabilityFilter};
+
+const SAMPLE_RATE: u32 = 16 * 1000;
 
 #[derive(Clone)]
 pub struct TranscribeService {
@@ -140,6 +143,7 @@ async fn handle_websocket_connection(
         .map(Duration::from_millis)
         .unwrap_or(Duration::from_millis(400));
 
+    let interim = InterimConfig::from_query(params.custom_query.as_ref());
     let global_timer = GlobalTimer::new();
 
     match params.channels {
@@ -150,6 +154,7 @@ async fn handle_websocket_connection(
                 model,
                 guard,
                 redemption_time,
+                interim,
                 global_timer,
             )
             .await;
@@ -161,6 +166,7 @@ async fn handle_websocket_connection(
                 model,
                 guard,
                 redemption_time,
+                interim,
                 global_timer,
             )
             .await;
@@ -174,15 +180,17 @@ async fn handle_single_channel(
     model: hypr_whisper_local::Whisper,
     guard: ConnectionGuard,
     redemption_time: Duration,
+    interim: Option<InterimConfig>,
     global_timer: GlobalTimer,
 ) {
-    let audio_source = hypr_ws_utils::WebSocketAudioSource::new(ws_receiver, 16 * 1000);
-    let vad_chunks = audio_source.speech_chunks(redemption_time);
+    let audio_source = hypr_ws_utils::WebSocketAudioSource::new(ws_receiver, SAMPLE_RATE);
+    let speech = audio_source.speech_events(redemption_time);
 
-    let chunked = hypr_whisper_local::AudioChunkStream(process_vad_stream(vad_chunks, "mixed"));
+    let chunked =
+        hypr_whisper_local::AudioChunkStream(process_vad_stream(speech, "mixed", interim));
 
     let stream = hypr_whisper_local::TranscribeMetadataAudioStreamExt::transcribe(chunked, model);
-    process_transcription_stream(ws_sender, stream, guard, 1, global_timer).await;
+    process_transcription_stream(ws_sender, stream, guard, 1, interim, global_timer).await;
 }
 
 async fn handle_dual_channel(
@@ -191,19 +199,20 @@ async fn handle_dual_channel(
     model: hypr_whisper_local::Whisper,
     guard: ConnectionGuard,
     redemption_time: Duration,
+    interim: Option<InterimConfig>,
     global_timer: GlobalTimer,
 ) {
     let (mic_source, speaker_source) =
-        hypr_ws_utils::split_dual_audio_sources(ws_receiver, 16 * 1000);
+        hypr_ws_utils::split_dual_audio_sources(ws_receiver, SAMPLE_RATE);
 
     let mic_chunked = {
-        let mic_vad_chunks = mic_source.speech_chunks(redemption_time);
-        hypr_whisper_local::AudioChunkStream(process_vad_stream(mic_vad_chunks, "mic"))
+        let mic_speech = mic_source.speech_events(redemption_time);
+        hypr_whisper_local::AudioChunkStream(process_vad_stream(mic_speech, "mic", interim))
     };
 
     let speaker_chunked = {
-        let speaker_vad_chunks = speaker_source.speech_chunks(redemption_time);
-        hypr_whisper_local::AudioChunkStream(process_vad_stream(speaker_vad_chunks, "speaker"))
+        let speaker_speech = speaker_source.speech_events(redemption_time);
+        hypr_whisper_local::AudioChunkStream(process_vad_stream(speaker_speech, "speaker", interim))
     };
 
     let merged_stream = hypr_whisper_local::AudioChunkStream(futures_util::stream::select(
@@ -214,7 +223,7 @@ async fn handle_dual_channel(
     let stream =
         hypr_whisper_local::TranscribeMetadataAudioStreamExt::transcribe(merged_stream, model);
 
-    process_transcription_stream(ws_sender, stream, guard, 2, global_timer).await;
+    process_transcription_stream(ws_sender, stream, guard, 2, interim, global_timer).await;
 }
 
 async fn process_transcription_stream(
@@ -222,8 +231,11 @@ async fn process_transcription_stream(
     mut stream: impl futures_util::Stream<Item = hypr_whisper_local::Segment> + Unpin,
     guard: ConnectionGuard,
     channels: i32,
+    interim: Option<InterimConfig>,
     global_timer: GlobalTimer,
 ) {
+    let mut stability = StabilityFilter::default();
+
     loop {
         tokio::select! {
             _ = guard.cancelled() => {
@@ -234,22 +246,21 @@ async fn process_transcription_stream(
                 let Some(chunk) = chunk_opt else { break };
 
                 let meta = chunk.meta();
-                let text = chunk.text().to_string();
                 let language = chunk.language().map(|s| s.to_string()).map(|s| vec![s]).unwrap_or_default();
                 let duration_f64 = chunk.duration();
                 let confidence = chunk.confidence() as f64;
+                let is_final = !chunk.is_interim();
 
-                let global_offset = global_timer.add_audio_duration(duration_f64);
-
-                let adjusted_start_f64 = global_offset;
-                let adjusted_end_f64 = global_offset + duration_f64;
-
+                let field = |key: &str| meta.as_ref().and_then(|meta| meta.get(key).cloned());
+                let source = field("source").and_then(|v| v.as_str().map(|s| s.to_string()));
 
-                let source = meta.and_then(|meta|
-                    meta.get("source")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string())
-                );
+                // VAD timestamps place each chunk on the channel's own timeline; chunks without
+                // them are laid end to end.
+                let offset = match field("start_ms").and_then(|v| v.as_u64()) {
+                    Some(start_ms) => start_ms as f64 / 1000.0,
+                    None if is_final => global_timer.add_audio_duration(duration_f64),
+                    None => global_timer.current_duration(),
+                };
 
                 let (speaker, channel_index) = match source.as_deref() {
                     Some("mic") => (Some(0), vec![0, channels]),
@@ -257,29 +268,54 @@ async fn process_transcription_stream(
                     _ => (None, vec![0, 1]),
                 };
 
-                let words: Vec<Word> = text
-                    .split_whitespace()
-                    .filter(|w| !w.is_empty())
-                    .map(|w| Word {
-                        word: w.trim().to_string(),
-                        start: adjusted_start_f64,
-                        end: adjusted_end_f64,
-                        confidence,
-                        speaker,
-                        punctuated_word: None,
-                        language: None,
-                    })
-                    .collect();
+                let mut words = to_stream_words(&chunk, speaker);
+                let source_key = source.as_deref().unwrap_or("mixed");
+                if is_final {
+                    stability.reset(source_key);
+                } else if let Some(config) = interim {
+                    let audio_secs = field("audio_secs")
+                        .and_then(|v| v.as_f64())
+                        .unwrap_or(chunk.end());
+                    words = stability.stable_prefix(
+                        source_key,
+                        words,
+                        audio_secs,
+                        config.stability_window,
+                    );
+                    if words.is_empty() {
+                        continue;
+                    }
+                }
+                for word in &mut words {
+                    word.start += offset;
+                    word.end += offset;
+                }
+
+                let text = if is_final {
+                    chunk.text().to_string()
+                } else {
+                    words
+                        .iter()
+                        .map(|w| w.word.as_str())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                };
+                let (start, duration) = match (words.first(), words.last()) {
+                    (Some(first), Some(last)) if !is_final => {
+                        (first.start, last.end - first.start)
+                    }
+                    _ => (offset + chunk.start(), duration_f64),
+                };
 
                 let response = StreamResponse::TranscriptResponse {
-                    start: adjusted_start_f64,
-                    duration: duration_f64,
-                    is_final: true,
-                    speech_final: true,
+                    start,
+                    duration,
+                    is_final,
+                    speech_final: is_final,
                     from_finalize: false,
                     channel: Channel{
                         alternatives: vec![Alternatives{
-                            transcript: text.clone(),
+                            transcript: text,
                             languages: language.clone(),
                             words,
                             confidence,
@@ -301,19 +337,86 @@ async fn process_transcription_stream(
     let _ = ws_sender.close().await;
 }
 
+/// Per-word timings from whisper's token timestamps, relative to the segment's audio. Falls back
+/// to spreading the segment's span over its words when whisper returned no token timings.
+fn to_stream_words(segment: &hypr_whisper_local::Segment, speaker: Option<i32>) -> Vec<Word> {
+    let word = |text: &str, start: f64, end: f64, confidence: f64| Word {
+        word: text.to_string(),
+        start,
+        end,
+        confidence,
+        speaker,
+        punctuated_word: Some(text.to_string()),
+        language: segment.language().map(|s| s.to_string()),
+    };
+
+    if !segment.words().is_empty() {
+        return segment
+            .words()
+            .iter()
+            .map(|w| word(&w.text, w.start, w.end, w.confidence as f64))
+            .collect();
+    }
+
+    let texts: Vec<&str> = segment.text().split_whitespace().collect();
+    let step = segment.duration() / texts.len().max(1) as f64;
+    texts
+        .iter()
+        .enumerate()
+        .map(|(i, text)| {
+            let start = segment.start() + step * i as f64;
+            word(text, start, start + step, segment.confidence() as f64)
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct SpeechBuffer {
+    /// The trailing `interim.window` of the segment so far.
+    samples: VecDeque<f32>,
+    start_ms: usize,
+    /// Samples of the segment that already fell out of the window.
+    dropped: usize,
+    since_interim: usize,
+}
+
+impl SpeechBuffer {
+    fn push(&mut self, samples: &[f32], window: usize) {
+        self.samples.extend(samples);
+        let excess = self.samples.len().saturating_sub(window);
+        self.samples.drain(..excess);
+        self.dropped += excess;
+        self.since_interim += samples.len();
+    }
+
+    fn window_start_ms(&self) -> usize {
+        self.start_ms + self.dropped * 1000 / SAMPLE_RATE as usize
+    }
+}
+
+/// Final chunks for each finished speech segment and, with `interim`, chunks of the trailing
+/// `interim.window` of the segment still in progress every `interim.interval` of new audio.
 fn process_vad_stream<S, E>(
     stream: S,
     source_name: &str,
+    interim: Option<InterimConfig>,
 ) -> impl futures_util::Stream<Item = hypr_whisper_local::SimpleAudioChunk>
 where
-    S: futures_util::Stream<Item = Result<hypr_vad_ext::AudioChunk, E>>,
+    S: futures_util::Stream<Item = Result<SpeechEvent, E>>,
     E: std::fmt::Display,
 {
     let source_name = source_name.to_string();
-
-    stream
-        .take_while(move |chunk_result| {
-            futures_util::future::ready(match chunk_result {
+    let samples_for = |duration: Duration| (duration.as_secs_f64() * SAMPLE_RATE as f64) as usize;
+    let interval_samples = interim
+        .map(|config| samples_for(config.interval))
+        .unwrap_or(usize::MAX);
+    let window_samples = interim
+        .map(|config| samples_for(config.window).max(1))
+        .unwrap_or(usize::MAX);
+
+    let chunks = stream
+        .take_while(move |event| {
+            futures_util::future::ready(match event {
                 Ok(_) => true,
                 Err(e) => {
                     tracing::error!("vad_error_disconnecting: {}", e);
@@ -321,13 +424,152 @@ where
                 }
             })
         })
-        .filter_map(move |chunk_result| {
-            futures_util::future::ready(match chunk_result {
+        .scan(SpeechBuffer::default(), move |buffer, event| {
+            let chunk = match event {
                 Err(_) => None,
-                Ok(chunk) => Some(hypr_whisper_local::SimpleAudioChunk {
-                    samples: chunk.samples,
-                    meta: Some(serde_json::json!({ "source": source_name })),
-                }),
-            })
+                Ok(SpeechEvent::Started { timestamp_ms }) => {
+                    *buffer = SpeechBuffer {
+                        start_ms: timestamp_ms,
+                        ..Default::default()
+                    };
+                    None
+                }
+                Ok(SpeechEvent::Audio(samples)) if interim.is_some() => {
+                    buffer.push(&samples, window_samples);
+                    (buffer.since_interim >= interval_samples).then(|| {
+                        buffer.since_interim = 0;
+                        hypr_whisper_local::SimpleAudioChunk {
+                            samples: buffer.samples.iter().copied().collect(),
+                            meta: Some(serde_json::json!({
+                                "source": source_name,
+                                "start_ms": buffer.window_start_ms(),
+                                "audio_secs": buffer.samples.len() as f64 / SAMPLE_RATE as f64,
+                            })),
+                            interim: true,
+                        }
+                    })
+                }
+                Ok(SpeechEvent::Audio(_)) => None,
+                Ok(SpeechEvent::Ended(chunk)) => {
+                    *buffer = SpeechBuffer::default();
+                    Some(hypr_whisper_local::SimpleAudioChunk {
+                        samples: chunk.samples,
+                        meta: Some(serde_json::json!({
+                            "source": source_name,
+                            "start_ms": chunk.start_timestamp_ms,
+                        })),
+                        interim: false,
+                    })
+                }
+            };
+            futures_util::future::ready(Some(chunk))
         })
+        .filter_map(futures_util::future::ready);
+
+    latest_interim(chunks)
+}
+
+/// Whisper runs inline while the transcription stream is polled, so audio that arrives during a
+/// pass is only segmented afterwards. Of the interim chunks that piled up meanwhile only the
+/// newest is kept, and it is dropped once the segment's final chunk is also ready.
+fn latest_interim<S>(
+    stream: S,
+) -> impl futures_util::Stream<Item = hypr_whisper_local::SimpleAudioChunk>
+where
+    S: futures_util::Stream<Item = hypr_whisper_local::SimpleAudioChunk>,
+{
+    let mut stream = Box::pin(stream);
+    let mut finals = VecDeque::new();
+    let mut interim = None;
+    let mut done = false;
+
+    futures_util::stream::poll_fn(move |cx| {
+        while !done {
+            match stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(chunk)) if chunk.interim => interim = Some(chunk),
+                Poll::Ready(Some(chunk)) => {
+                    interim = None;
+                    finals.push_back(chunk);
+                }
+                Poll::Ready(None) => done = true,
+                Poll::Pending => break,
+            }
+        }
+
+        match finals.pop_front().or_else(|| interim.take()) {
+            Some(chunk) => Poll::Ready(Some(chunk)),
+            None if done => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use hypr_vad_ext::AudioChunk;
+
+    fn interim_config() -> InterimConfig {
+        InterimConfig {
+            interval: Duration::from_millis(500),
+            window: Duration::from_secs(1),
+            ..Default::default()
+        }
+    }
+
+    fn speech(seconds: usize) -> Vec<Result<SpeechEvent, String>> {
+        let chunk = SAMPLE_RATE as usize / 4;
+        let mut events = vec![Ok(SpeechEvent::Started { timestamp_ms: 2000 })];
+        events.extend((0..seconds * 4).map(|_| Ok(SpeechEvent::Audio(vec![0.0; chunk]))));
+        events
+    }
+
+    fn ended(seconds: usize) -> Result<SpeechEvent, String> {
+        Ok(SpeechEvent::Ended(AudioChunk {
+            samples: vec![0.0; seconds * SAMPLE_RATE as usize],
+            start_timestamp_ms: 2000,
+            end_timestamp_ms: 2000 + seconds * 1000,
+        }))
+    }
+
+    #[tokio::test]
+    async fn interim_passes_cover_the_trailing_window() {
+        // The source stays open, so the interim isn't superseded by a final.
+        let events = futures_util::stream::iter(speech(3)).chain(futures_util::stream::pending());
+        let mut chunks = Box::pin(process_vad_stream(events, "mic", Some(interim_config())));
+
+        let chunk = chunks.next().await.unwrap();
+        assert!(chunk.interim);
+        assert_eq!(chunk.samples.len(), SAMPLE_RATE as usize);
+        let meta = chunk.meta.unwrap();
+        assert_eq!(meta["start_ms"], 4000);
+        assert_eq!(meta["audio_secs"], 1.0);
+    }
+
+    #[tokio::test]
+    async fn pending_interims_are_coalesced_and_dropped_by_the_final() {
+        let mut events = speech(3);
+        let chunks: Vec<_> = process_vad_stream(
+            futures_util::stream::iter(events.clone()),
+            "mic",
+            Some(interim_config()),
+        )
+        .collect()
+        .await;
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].interim);
+
+        events.push(ended(3));
+        let chunks: Vec<_> = process_vad_stream(
+            futures_util::stream::iter(events),
+            "mic",
+            Some(interim_config()),
+        )
+        .collect()
+        .await;
+        assert_eq!(chunks.len(), 1);
+        assert!(!chunks[0].interim);
+        assert_eq!(chunks[0].samples.len(), 3 * SAMPLE_RATE as usize);
+    }
 }
@@ -7,15 +7,16 @@ use std::{
 
 use futures_util::{Stream, StreamExt, future};
 use hypr_audio_interface::AsyncSource;
+use hypr_vad::VadBackend;
 use pin_project::pin_project;
 use silero_rs::{VadConfig, VadSession, VadTransition};
 
 #[derive(Debug, Clone)]
 pub(crate) enum VadStreamItem {
-    #[allow(dead_code)]
     AudioSamples(Vec<f32>),
-    #[allow(dead_code)]
-    SpeechStart { timestamp_ms: usize },
+    SpeechStart {
+        timestamp_ms: usize,
+    },
     SpeechEnd {
         start_timestamp_ms: usize,
         end_timestamp_ms: usize,
@@ -30,35 +31,287 @@ pub struct AudioChunk {
     pub end_timestamp_ms: usize,
 }
 
+/// Speech segmentation with the audio of a segment that is still in progress.
+#[derive(Debug, Clone)]
+pub enum SpeechEvent {
+    Started {
+        timestamp_ms: usize,
+    },
+    /// Audio captured since the last event while speech is ongoing.
+    Audio(Vec<f32>),
+    Ended(AudioChunk),
+}
+
+/// Turns fixed-size chunks of audio into speech start/end transitions.
+pub(crate) trait Segmenter {
+    fn process(&mut self, chunk: &[f32]) -> Result<Vec<VadStreamItem>, crate::Error>;
+
+    /// Called once the source is exhausted, to close any open speech segment.
+    fn finish(&mut self) -> Vec<VadStreamItem> {
+        Vec::new()
+    }
+}
+
+impl Segmenter for VadSession {
+    fn process(&mut self, chunk: &[f32]) -> Result<Vec<VadStreamItem>, crate::Error> {
+        let transitions = VadSession::process(self, chunk)
+            .map_err(|e| crate::Error::VadProcessingFailed(e.to_string()))?;
+
+        Ok(transitions
+            .into_iter()
+            .map(|transition| match transition {
+                VadTransition::SpeechStart { timestamp_ms } => {
+                    VadStreamItem::SpeechStart { timestamp_ms }
+                }
+                VadTransition::SpeechEnd {
+                    start_timestamp_ms,
+                    end_timestamp_ms,
+                    samples,
+                } => VadStreamItem::SpeechEnd {
+                    start_timestamp_ms,
+                    end_timestamp_ms,
+                    samples,
+                },
+            })
+            .collect())
+    }
+}
+
+/// Segmentation settings for [`VadExt::speech_chunks_with_backend`].
+#[derive(Debug, Clone)]
+pub struct SegmenterConfig {
+    /// Probability at which a frame starts (or keeps) a speech segment.
+    pub speech_threshold: f32,
+    /// Probability below which a frame counts towards ending the segment.
+    pub silence_threshold: f32,
+    /// How long speech may pause before the segment is closed.
+    pub redemption_time: Duration,
+    pub pre_speech_pad: Duration,
+    pub post_speech_pad: Duration,
+    /// Shorter segments are dropped.
+    pub min_speech_time: Duration,
+}
+
+impl SegmenterConfig {
+    pub fn new(redemption_time: Duration) -> Self {
+        Self {
+            speech_threshold: 0.5,
+            silence_threshold: 0.35,
+            redemption_time,
+            pre_speech_pad: redemption_time,
+            post_speech_pad: Duration::from_millis(0),
+            min_speech_time: Duration::from_millis(50),
+        }
+    }
+}
+
+struct ActiveSpeech {
+    start: usize,
+    samples: Vec<f32>,
+    silence_run: usize,
+}
+
+/// Speech segmentation driven by any [`VadBackend`]'s per-frame probabilities.
+pub(crate) struct BackendSegmenter<B: VadBackend> {
+    backend: B,
+    config: SegmenterConfig,
+    sample_rate: usize,
+    frame_size: usize,
+    pending: Vec<f32>,
+    history: VecDeque<f32>,
+    processed: usize,
+    speech: Option<ActiveSpeech>,
+}
+
+impl<B: VadBackend> BackendSegmenter<B> {
+    pub(crate) fn new(
+        backend: B,
+        config: SegmenterConfig,
+        sample_rate: usize,
+        chunk_hint: usize,
+    ) -> Self {
+        let frame_size = backend.frame_size(chunk_hint).max(1);
+        Self {
+            backend,
+            config,
+            sample_rate,
+            frame_size,
+            pending: Vec::with_capacity(frame_size),
+            history: VecDeque::new(),
+            processed: 0,
+            speech: None,
+        }
+    }
+
+    fn samples(&self, duration: Duration) -> usize {
+        (duration.as_secs_f64() * self.sample_rate as f64) as usize
+    }
+
+    fn ms(&self, samples: usize) -> usize {
+        samples * 1000 / self.sample_rate.max(1)
+    }
+
+    fn step(&mut self, frame: &[f32], probability: f32, items: &mut Vec<VadStreamItem>) {
+        let frame_start = self.processed;
+        self.processed += frame.len();
+        let redemption = self.samples(self.config.redemption_time);
+        let pre_pad = self.samples(self.config.pre_speech_pad);
+
+        let Some(active) = self.speech.as_mut() else {
+            if probability >= self.config.speech_threshold {
+                let start = frame_start - self.history.len();
+                let mut samples: Vec<f32> = self.history.drain(..).collect();
+                samples.extend_from_slice(frame);
+
+                items.push(VadStreamItem::SpeechStart {
+                    timestamp_ms: self.ms(start),
+                });
+                self.speech = Some(ActiveSpeech {
+                    start,
+                    samples,
+                    silence_run: 0,
+                });
+            } else {
+                self.history.extend(frame.iter().copied());
+                while self.history.len() > pre_pad {
+                    self.history.pop_front();
+                }
+            }
+            return;
+        };
+
+        active.samples.extend_from_slice(frame);
+        if probability < self.config.silence_threshold {
+            active.silence_run += frame.len();
+        } else if probability >= self.config.speech_threshold {
+            active.silence_run = 0;
+        }
+
+        if active.silence_run >= redemption {
+            self.end_speech(items);
+        }
+    }
+
+    fn end_speech(&mut self, items: &mut Vec<VadStreamItem>) {
+        let Some(mut active) = self.speech.take() else {
+            return;
+        };
+
+        let post_pad = self.samples(self.config.post_speech_pad);
+        let keep = (active.samples.len() - active.silence_run + post_pad).min(active.samples.len());
+        let trailing = active.samples.split_off(keep);
+
+        // Trailing silence becomes the pre-speech padding of the next segment.
+        let pre_pad = self.samples(self.config.pre_speech_pad);
+        self.history.clear();
+        self.history.extend(
+            trailing[trailing.len().saturating_sub(pre_pad)..]
+                .iter()
+                .copied(),
+        );
+
+        if active.samples.len() >= self.samples(self.config.min_speech_time) {
+            items.push(VadStreamItem::SpeechEnd {
+                start_timestamp_ms: self.ms(active.start),
+                end_timestamp_ms: self.ms(active.start + active.samples.len()),
+                samples: active.samples,
+            });
+        }
+    }
+}
+
+impl<B: VadBackend> Segmenter for BackendSegmenter<B> {
+    fn process(&mut self, chunk: &[f32]) -> Result<Vec<VadStreamItem>, crate::Error> {
+        let mut items = Vec::new();
+        self.pending.extend_from_slice(chunk);
+
+        while self.pending.len() >= self.frame_size {
+            let frame: Vec<f32> = self.pending.drain(..self.frame_size).collect();
+            let probability = self
+                .backend
+                .speech_probability(&frame)
+                .map_err(|e| crate::Error::VadProcessingFailed(e.to_string()))?;
+            self.step(&frame, probability, &mut items);
+        }
+
+        Ok(items)
+    }
+
+    fn finish(&mut self) -> Vec<VadStreamItem> {
+        let mut items = Vec::new();
+        if let Some(active) = self.speech.as_mut() {
+            active.samples.append(&mut self.pending);
+            self.end_speech(&mut items);
+        }
+        items
+    }
+}
+
 #[pin_project]
-pub(crate) struct ContinuousVadStream<S: AsyncSource> {
+pub(crate) struct ContinuousVadStream<S: AsyncSource, G: Segmenter = VadSession> {
     source: S,
-    vad_session: VadSession,
+    segmenter: G,
     chunk_samples: usize,
     buffer: Vec<f32>,
     pending_items: VecDeque<VadStreamItem>,
+    finished: bool,
+}
+
+// https://github.com/emotechlab/silero-rs/blob/26a6460/src/lib.rs#L775
+const CHUNK_DURATION: Duration = Duration::from_millis(30);
+
+fn chunk_samples(sample_rate: u32) -> usize {
+    (CHUNK_DURATION.as_secs_f64() * sample_rate as f64) as usize
 }
 
 impl<S: AsyncSource> ContinuousVadStream<S> {
     pub(crate) fn new(source: S, mut config: VadConfig) -> Result<Self, crate::Error> {
         config.sample_rate = source.sample_rate() as usize;
+        let segmenter =
+            VadSession::new(config).map_err(|_| crate::Error::VadSessionCreationFailed)?;
 
-        // https://github.com/emotechlab/silero-rs/blob/26a6460/src/lib.rs#L775
-        let chunk_duration = Duration::from_millis(30);
-        let chunk_samples = (chunk_duration.as_secs_f64() * config.sample_rate as f64) as usize;
+        Ok(Self::with_segmenter(source, segmenter))
+    }
+}
 
-        Ok(Self {
+impl<S: AsyncSource, B: VadBackend> ContinuousVadStream<S, BackendSegmenter<B>> {
+    pub(crate) fn with_backend(source: S, backend: B, config: SegmenterConfig) -> Self {
+        let sample_rate = source.sample_rate();
+        let segmenter = BackendSegmenter::new(
+            backend,
+            config,
+            sample_rate as usize,
+            chunk_samples(sample_rate),
+        );
+        Self::with_segmenter(source, segmenter)
+    }
+}
+
+impl<S: AsyncSource, G: Segmenter> ContinuousVadStream<S, G> {
+    fn with_segmenter(source: S, segmenter: G) -> Self {
+        let chunk_samples = chunk_samples(source.sample_rate());
+        Self {
             source,
-            vad_session: VadSession::new(config)
-                .map_err(|_| crate::Error::VadSessionCreationFailed)?,
+            segmenter,
             chunk_samples,
             buffer: Vec::with_capacity(chunk_samples),
             pending_items: VecDeque::new(),
-        })
+            finished: false,
+        }
     }
 }
 
-impl<S: AsyncSource> Stream for ContinuousVadStream<S> {
+impl<S: AsyncSource, G: Segmenter> ContinuousVadStream<S, G> {
+    fn process_chunk(&mut self, chunk: Vec<f32>) -> Result<(), crate::Error> {
+        let items = self.segmenter.process(&chunk)?;
+        self.pending_items
+            .push_back(VadStreamItem::AudioSamples(chunk));
+        self.pending_items.extend(items);
+        Ok(())
+    }
+}
+
+impl<S: AsyncSource, G: Segmenter> Stream for ContinuousVadStream<S, G> {
     type Item = Result<VadStreamItem, crate::Error>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
@@ -67,96 +320,124 @@ impl<S: AsyncSource> Stream for ContinuousVadStream<S> {
         if let Some(item) = this.pending_items.pop_front() {
             return Poll::Ready(Some(Ok(item)));
         }
+        if this.finished {
+            return Poll::Ready(None);
+        }
 
-        let stream = this.source.as_stream();
-        let mut stream = std::pin::pin!(stream);
+        let mut source_done = false;
+        {
+            let stream = this.source.as_stream();
+            let mut stream = std::pin::pin!(stream);
 
-        while this.buffer.len() < this.chunk_samples {
-            match stream.as_mut().poll_next(cx) {
-                Poll::Pending => {
-                    return Poll::Pending;
-                }
-                Poll::Ready(Some(sample)) => {
-                    this.buffer.push(sample);
-                }
-                Poll::Ready(None) => {
-                    if !this.buffer.is_empty() {
-                        let chunk = std::mem::take(&mut this.buffer);
-
-                        match this.vad_session.process(&chunk) {
-                            Ok(transitions) => {
-                                this.pending_items
-                                    .push_back(VadStreamItem::AudioSamples(chunk));
-
-                                for transition in transitions {
-                                    let item = match transition {
-                                        VadTransition::SpeechStart { timestamp_ms } => {
-                                            VadStreamItem::SpeechStart { timestamp_ms }
-                                        }
-                                        VadTransition::SpeechEnd {
-                                            start_timestamp_ms,
-                                            end_timestamp_ms,
-                                            samples,
-                                        } => VadStreamItem::SpeechEnd {
-                                            start_timestamp_ms,
-                                            end_timestamp_ms,
-                                            samples,
-                                        },
-                                    };
-                                    this.pending_items.push_back(item);
-                                }
-
-                                if let Some(item) = this.pending_items.pop_front() {
-                                    return Poll::Ready(Some(Ok(item)));
-                                }
-                            }
-                            Err(e) => {
-                                return Poll::Ready(Some(Err(crate::Error::VadProcessingFailed(
-                                    e.to_string(),
-                                ))));
-                            }
-                        }
+            while this.buffer.len() < this.chunk_samples {
+                match stream.as_mut().poll_next(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(Some(sample)) => this.buffer.push(sample),
+                    Poll::Ready(None) => {
+                        source_done = true;
+                        break;
                     }
-                    return Poll::Ready(None);
                 }
             }
         }
 
-        let mut chunk = Vec::with_capacity(this.chunk_samples);
-        chunk.extend(this.buffer.drain(..this.chunk_samples));
+        let result = if source_done {
+            this.finished = true;
+            let chunk = std::mem::take(&mut this.buffer);
+            let result = if chunk.is_empty() {
+                Ok(())
+            } else {
+                this.process_chunk(chunk)
+            };
+            this.pending_items.extend(this.segmenter.finish());
+            result
+        } else {
+            let chunk = this.buffer.drain(..this.chunk_samples).collect();
+            this.process_chunk(chunk)
+        };
+
+        match result {
+            Err(e) => Poll::Ready(Some(Err(e))),
+            Ok(()) => Poll::Ready(this.pending_items.pop_front().map(Ok)),
+        }
+    }
+}
 
-        match this.vad_session.process(&chunk) {
-            Ok(transitions) => {
-                this.pending_items
-                    .push_back(VadStreamItem::AudioSamples(chunk));
+fn speech_end_chunks<St>(stream: St) -> impl Stream<Item = Result<AudioChunk, crate::Error>>
+where
+    St: Stream<Item = Result<VadStreamItem, crate::Error>>,
+{
+    stream.filter_map(|item| {
+        future::ready(match item {
+            Ok(VadStreamItem::SpeechEnd {
+                samples,
+                start_timestamp_ms,
+                end_timestamp_ms,
+            }) => Some(Ok(AudioChunk {
+                samples,
+                start_timestamp_ms,
+                end_timestamp_ms,
+            })),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        })
+    })
+}
 
-                for transition in transitions {
-                    let item = match transition {
-                        VadTransition::SpeechStart { timestamp_ms } => {
-                            VadStreamItem::SpeechStart { timestamp_ms }
+fn speech_events_from<St>(stream: St) -> impl Stream<Item = Result<SpeechEvent, crate::Error>>
+where
+    St: Stream<Item = Result<VadStreamItem, crate::Error>>,
+{
+    // Each chunk's audio is yielded before the transitions it caused, so the chunk that
+    // started speech is held back and replayed after `Started`.
+    stream
+        .scan(
+            (false, Vec::new()),
+            |(in_speech, last_chunk): &mut (bool, Vec<f32>), item| {
+                let events = match item {
+                    Ok(VadStreamItem::AudioSamples(samples)) => {
+                        if *in_speech {
+                            vec![Ok(SpeechEvent::Audio(samples))]
+                        } else {
+                            *last_chunk = samples;
+                            vec![]
                         }
-                        VadTransition::SpeechEnd {
-                            start_timestamp_ms,
-                            end_timestamp_ms,
+                    }
+                    Ok(VadStreamItem::SpeechStart { timestamp_ms }) => {
+                        *in_speech = true;
+                        vec![
+                            Ok(SpeechEvent::Started { timestamp_ms }),
+                            Ok(SpeechEvent::Audio(std::mem::take(last_chunk))),
+                        ]
+                    }
+                    Ok(VadStreamItem::SpeechEnd {
+                        start_timestamp_ms,
+                        end_timestamp_ms,
+                        samples,
+                    }) => {
+                        *in_speech = false;
+                        vec![Ok(SpeechEvent::Ended(AudioChunk {
                             samples,
-                        } => VadStreamItem::SpeechEnd {
                             start_timestamp_ms,
                             end_timestamp_ms,
-                            samples,
-                        },
-                    };
-                    this.pending_items.push_back(item);
-                }
+                        }))]
+                    }
+                    Err(e) => vec![Err(e)],
+                };
+                future::ready(Some(futures_util::stream::iter(events)))
+            },
+        )
+        .flatten()
+}
 
-                if let Some(item) = this.pending_items.pop_front() {
-                    Poll::Ready(Some(Ok(item)))
-                } else {
-                    // Should never happen since we always push audio
-                    Poll::Pending
-                }
-            }
-            Err(e) => Poll::Ready(Some(Err(crate::Error::VadProcessingFailed(e.to_string())))),
-        }
+/// Silero settings shared by [`VadExt::speech_chunks`] and [`VadExt::speech_events`].
+fn vad_config(redemption_time: Duration) -> VadConfig {
+    VadConfig {
+        redemption_time,
+        pre_speech_pad: redemption_time,
+        post_speech_pad: Duration::from_millis(0),
+        min_speech_time: Duration::from_millis(50),
+        ..Default::default()
     }
 }
 
@@ -168,32 +449,160 @@ pub trait VadExt: AsyncSource + Sized {
     where
         Self: 'static,
     {
-        let config = VadConfig {
-            redemption_time,
-            pre_speech_pad: redemption_time,
-            post_speech_pad: Duration::from_millis(0),
-            min_speech_time: Duration::from_millis(50),
-            ..Default::default()
-        };
+        speech_end_chunks(ContinuousVadStream::new(self, vad_config(redemption_time)).unwrap())
+    }
 
-        ContinuousVadStream::new(self, config)
-            .unwrap()
-            .filter_map(|item| {
-                future::ready(match item {
-                    Ok(VadStreamItem::SpeechEnd {
-                        samples,
-                        start_timestamp_ms,
-                        end_timestamp_ms,
-                    }) => Some(Ok(AudioChunk {
-                        samples,
-                        start_timestamp_ms,
-                        end_timestamp_ms,
-                    })),
-                    Ok(_) => None,
-                    Err(e) => Some(Err(e)),
-                })
-            })
+    /// Same as [`VadExt::speech_chunks`], with speech detected by `backend`. The source must be
+    /// 16 kHz mono; any other rate yields a single [`crate::Error::UnsupportedSampleRate`].
+    fn speech_chunks_with_backend<B: VadBackend + 'static>(
+        self,
+        backend: B,
+        config: SegmenterConfig,
+    ) -> impl Stream<Item = Result<AudioChunk, crate::Error>>
+    where
+        Self: 'static,
+    {
+        let sample_rate = self.sample_rate();
+        if sample_rate != hypr_vad::SAMPLE_RATE {
+            let error = crate::Error::UnsupportedSampleRate(sample_rate);
+            return futures_util::stream::once(future::ready(Err(error))).left_stream();
+        }
+        speech_end_chunks(ContinuousVadStream::with_backend(self, backend, config)).right_stream()
+    }
+
+    /// Like [`VadExt::speech_chunks`], but also yields the audio of speech as it happens, for
+    /// consumers that want partial results before the segment ends.
+    fn speech_events(
+        self,
+        redemption_time: Duration,
+    ) -> impl Stream<Item = Result<SpeechEvent, crate::Error>>
+    where
+        Self: 'static,
+    {
+        speech_events_from(ContinuousVadStream::new(self, vad_config(redemption_time)).unwrap())
     }
 }
 
 impl<T: AsyncSource> VadExt for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Scripted(VecDeque<f32>);
+
+    impl VadBackend for Scripted {
+        fn frame_size(&self, _hint: usize) -> usize {
+            160
+        }
+
+        fn speech_probability(&mut self, _frame: &[f32]) -> Result<f32, hypr_vad::VadError> {
+            Ok(self.0.pop_front().unwrap_or(0.0))
+        }
+    }
+
+    fn segmenter(script: &[f32]) -> BackendSegmenter<Scripted> {
+        let config = SegmenterConfig {
+            pre_speech_pad: Duration::from_millis(10),
+            min_speech_time: Duration::from_millis(20),
+            ..SegmenterConfig::new(Duration::from_millis(30))
+        };
+        BackendSegmenter::new(
+            Scripted(script.iter().copied().collect()),
+            config,
+            16_000,
+            480,
+        )
+    }
+
+    #[test]
+    fn backend_segmenter_emits_padded_segments() {
+        // 10ms frames: silence, 3 frames of speech, a short dip, speech, then silence.
+        let script = [0.1, 0.1, 0.9, 0.8, 0.9, 0.2, 0.6, 0.9, 0.1, 0.1, 0.1, 0.1];
+        let mut seg = segmenter(&script);
+
+        let items = seg.process(&vec![0.0; 160 * script.len()]).unwrap();
+        let summary: Vec<_> = items
+            .iter()
+            .map(|item| match item {
+                VadStreamItem::SpeechStart { timestamp_ms } => (*timestamp_ms, 0, 0),
+                VadStreamItem::SpeechEnd {
+                    start_timestamp_ms,
+                    end_timestamp_ms,
+                    samples,
+                } => (*start_timestamp_ms, *end_timestamp_ms, samples.len()),
+                VadStreamItem::AudioSamples(_) => unreachable!(),
+            })
+            .collect();
+
+        assert_eq!(summary, vec![(10, 0, 0), (10, 80, 1120)]);
+        assert!(seg.finish().is_empty());
+    }
+
+    #[tokio::test]
+    async fn speech_events_replay_the_chunk_that_started_speech() {
+        let items: Vec<Result<VadStreamItem, crate::Error>> = vec![
+            Ok(VadStreamItem::AudioSamples(vec![0.0; 2])),
+            Ok(VadStreamItem::AudioSamples(vec![1.0; 2])),
+            Ok(VadStreamItem::SpeechStart { timestamp_ms: 5 }),
+            Ok(VadStreamItem::AudioSamples(vec![2.0; 2])),
+            Ok(VadStreamItem::SpeechEnd {
+                start_timestamp_ms: 5,
+                end_timestamp_ms: 9,
+                samples: vec![1.0, 1.0, 2.0, 2.0],
+            }),
+            Ok(VadStreamItem::AudioSamples(vec![3.0; 2])),
+        ];
+
+        let events: Vec<SpeechEvent> = speech_events_from(futures_util::stream::iter(items))
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert!(matches!(
+            events.as_slice(),
+            [
+                SpeechEvent::Started { timestamp_ms: 5 },
+                SpeechEvent::Audio(first),
+                SpeechEvent::Audio(second),
+                SpeechEvent::Ended(AudioChunk { start_timestamp_ms: 5, .. }),
+            ] if first == &[1.0, 1.0] && second == &[2.0, 2.0]
+        ));
+    }
+
+    #[tokio::test]
+    async fn speech_chunks_with_backend_rejects_other_rates() {
+        let source = rodio::buffer::SamplesBuffer::new(1, 8_000, vec![0.0; 800]);
+        let items: Vec<_> = source
+            .speech_chunks_with_backend(
+                Scripted(VecDeque::new()),
+                SegmenterConfig::new(Duration::from_millis(30)),
+            )
+            .collect()
+            .await;
+
+        assert!(matches!(
+            items.as_slice(),
+            [Err(crate::Error::UnsupportedSampleRate(8_000))]
+        ));
+    }
+
+    #[test]
+    fn backend_segmenter_drops_short_blips_and_flushes_on_finish() {
+        let mut seg = segmenter(&[0.9, 0.1, 0.1, 0.1]);
+        let items = seg.process(&vec![0.0; 640]).unwrap();
+        assert!(
+            !items
+                .iter()
+                .any(|item| matches!(item, VadStreamItem::SpeechEnd { .. }))
+        );
+
+        let mut seg = segmenter(&[0.9, 0.9, 0.9]);
+        seg.process(&vec![0.0; 480 + 100]).unwrap();
+        let items = seg.finish();
+        assert!(matches!(
+            items.as_slice(),
+            [VadStreamItem::SpeechEnd { samples, .. }] if samples.len() == 580
+        ));
+    }
+}
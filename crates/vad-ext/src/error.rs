@@ -4,4 +4,6 @@ pub enum Error {
     VadSessionCreationFailed,
     #[error("Failed to process audio")]
     VadProcessingFailed(String),
+    #[error("unsupported sample rate {0}, expected 16 kHz")]
+    UnsupportedSampleRate(u32),
 }
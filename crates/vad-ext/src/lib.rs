@@ -3,7 +3,7 @@ mod error;
 mod masking;
 mod streaming;
 
-pub use continuous::*;
+pub use continuous::{AudioChunk, SegmenterConfig, SpeechEvent, VadExt};
 pub use error::*;
 pub use masking::*;
 pub use streaming::*;
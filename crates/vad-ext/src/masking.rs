@@ -1,14 +1,26 @@
+use hypr_vad::VadBackend;
+use hypr_vad::earshot::VoiceActivityDetector;
+
 use crate::{StreamingVad, VadConfig};
 
-pub struct VadMask {
-    vad: Option<StreamingVad>,
+/// Zeroes out frames the VAD does not consider speech.
+pub struct VadMask<B: VadBackend = VoiceActivityDetector> {
+    vad: Option<StreamingVad<B>>,
+    backend: Option<B>,
     vad_cfg: VadConfig,
 }
 
 impl VadMask {
     pub fn new() -> Self {
+        Self::with_backend(VoiceActivityDetector::new())
+    }
+}
+
+impl<B: VadBackend> VadMask<B> {
+    pub fn with_backend(backend: B) -> Self {
         Self {
             vad: None,
+            backend: Some(backend),
             vad_cfg: VadConfig::default(),
         }
     }
@@ -23,15 +35,25 @@ impl VadMask {
             return;
         }
 
-        let vad = self
-            .vad
-            .get_or_insert_with(|| StreamingVad::with_config(samples.len(), self.vad_cfg.clone()));
+        // The frame size is picked from the first chunk, so the VAD is built lazily.
+        if self.vad.is_none() {
+            let Some(backend) = self.backend.take() else {
+                return;
+            };
+            self.vad = Some(StreamingVad::with_backend(
+                backend,
+                samples.len(),
+                self.vad_cfg.clone(),
+            ));
+        }
 
-        vad.process_in_place(samples, |frame, is_speech| {
-            if !is_speech {
-                frame.fill(0.0);
-            }
-        });
+        if let Some(vad) = self.vad.as_mut() {
+            vad.process_in_place(samples, |frame, is_speech| {
+                if !is_speech {
+                    frame.fill(0.0);
+                }
+            });
+        }
     }
 }
 
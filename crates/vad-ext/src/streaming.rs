@@ -1,4 +1,4 @@
-use hypr_audio_utils::f32_to_i16_samples;
+use hypr_vad::VadBackend;
 use hypr_vad::earshot::VoiceActivityDetector;
 
 #[derive(Clone, Debug)]
@@ -6,6 +6,8 @@ pub struct VadConfig {
     pub hangover_frames: usize,
     pub amplitude_floor: f32,
     pub start_in_speech: bool,
+    /// Backend probability at or above which a frame counts as speech.
+    pub speech_threshold: f32,
 }
 
 impl Default for VadConfig {
@@ -14,12 +16,13 @@ impl Default for VadConfig {
             hangover_frames: 6,
             amplitude_floor: 0.0005,
             start_in_speech: true,
+            speech_threshold: 0.5,
         }
     }
 }
 
-pub struct StreamingVad {
-    vad: VoiceActivityDetector,
+pub struct StreamingVad<B: VadBackend = VoiceActivityDetector> {
+    backend: B,
     cfg: VadConfig,
     frame_size: usize,
     in_speech: bool,
@@ -33,11 +36,17 @@ impl StreamingVad {
     }
 
     pub fn with_config(frame_hint: usize, cfg: VadConfig) -> Self {
-        let frame_size = hypr_vad::earshot::choose_optimal_frame_size(frame_hint);
+        Self::with_backend(VoiceActivityDetector::new(), frame_hint, cfg)
+    }
+}
+
+impl<B: VadBackend> StreamingVad<B> {
+    pub fn with_backend(backend: B, frame_hint: usize, cfg: VadConfig) -> Self {
+        let frame_size = backend.frame_size(frame_hint);
         debug_assert!(frame_size > 0, "VAD frame size must be > 0");
 
         Self {
-            vad: VoiceActivityDetector::new(),
+            backend,
             frame_size,
             in_speech: cfg.start_in_speech,
             trailing_non_speech: 0,
@@ -73,41 +82,51 @@ impl StreamingVad {
         }
     }
 
-    fn classify_frame(&mut self, frame: &[f32]) -> bool {
+    /// The backend's speech probability for `frame` and the smoothed decision. Frames below
+    /// the amplitude floor are never sent to the backend and report a probability of 0.
+    fn classify_frame(&mut self, frame: &[f32]) -> (f32, bool) {
         if frame.is_empty() {
-            return false;
+            return (0.0, false);
         }
 
         let rms = Self::calculate_rms(frame);
         if rms < self.cfg.amplitude_floor {
-            return self.smooth_decision(false);
+            return (0.0, self.smooth_decision(false));
         }
 
-        let raw_is_speech = if frame.len() == self.frame_size {
-            let i16_samples = f32_to_i16_samples(frame);
-            self.vad.predict_16khz(&i16_samples).unwrap_or(true)
+        let probability = if frame.len() == self.frame_size {
+            self.backend.speech_probability(frame)
         } else {
             self.scratch_frame.clear();
             self.scratch_frame.extend_from_slice(frame);
             self.scratch_frame.resize(self.frame_size, 0.0);
-            let i16_samples = f32_to_i16_samples(&self.scratch_frame);
-            self.vad.predict_16khz(&i16_samples).unwrap_or(true)
-        };
+            self.backend.speech_probability(&self.scratch_frame)
+        }
+        .unwrap_or(1.0);
 
-        self.smooth_decision(raw_is_speech)
+        let is_speech = self.smooth_decision(probability >= self.cfg.speech_threshold);
+        (probability, is_speech)
     }
 
     pub fn process_in_place<F>(&mut self, samples: &mut [f32], mut f: F)
     where
         F: FnMut(&mut [f32], bool),
+    {
+        self.process_in_place_with_probability(samples, |frame, _, is_speech| f(frame, is_speech));
+    }
+
+    /// Like [`Self::process_in_place`], also passing the backend's raw speech probability.
+    pub fn process_in_place_with_probability<F>(&mut self, samples: &mut [f32], mut f: F)
+    where
+        F: FnMut(&mut [f32], f32, bool),
     {
         if samples.is_empty() {
             return;
         }
 
         for frame in samples.chunks_mut(self.frame_size) {
-            let is_speech = self.classify_frame(frame);
-            f(frame, is_speech);
+            let (probability, is_speech) = self.classify_frame(frame);
+            f(frame, probability, is_speech);
         }
     }
 }
@@ -163,4 +182,51 @@ mod tests {
         assert_eq!(StreamingVad::new(640).frame_size(), 320);
         assert_eq!(StreamingVad::new(960).frame_size(), 480);
     }
+
+    struct FixedProbability(f32);
+
+    impl VadBackend for FixedProbability {
+        fn frame_size(&self, _hint: usize) -> usize {
+            256
+        }
+
+        fn speech_probability(&mut self, frame: &[f32]) -> Result<f32, hypr_vad::VadError> {
+            assert_eq!(frame.len(), 256);
+            Ok(self.0)
+        }
+    }
+
+    #[test]
+    fn test_custom_backend_probability_threshold() {
+        let cfg = VadConfig {
+            hangover_frames: 0,
+            start_in_speech: false,
+            ..Default::default()
+        };
+
+        let mut vad = StreamingVad::with_backend(FixedProbability(0.7), 320, cfg.clone());
+        assert_eq!(vad.frame_size(), 256);
+        let mut samples = vec![0.1; 600];
+        let mut seen = Vec::new();
+        vad.process_in_place_with_probability(&mut samples, |frame, probability, is_speech| {
+            seen.push((frame.len(), probability, is_speech));
+        });
+        assert_eq!(
+            seen,
+            vec![(256, 0.7, true), (256, 0.7, true), (88, 0.7, true)]
+        );
+
+        let mut vad = StreamingVad::with_backend(
+            FixedProbability(0.3),
+            320,
+            VadConfig {
+                speech_threshold: 0.25,
+                ..cfg.clone()
+            },
+        );
+        vad.process_in_place(&mut samples, |_, is_speech| assert!(is_speech));
+
+        let mut vad = StreamingVad::with_backend(FixedProbability(0.3), 320, cfg);
+        vad.process_in_place(&mut samples, |_, is_speech| assert!(!is_speech));
+    }
 }
@@ -0,0 +1,35 @@
+use crate::VadError;
+
+/// The only rate backends accept.
+pub const SAMPLE_RATE: u32 = 16_000;
+
+/// A voice activity model over [`SAMPLE_RATE`] mono audio.
+///
+/// Backends report a speech probability per frame; callers decide the threshold and any
+/// smoothing, so backends with very different confidence profiles can be swapped freely.
+pub trait VadBackend: Send {
+    /// Frame length in samples to feed `speech_probability`, given the caller's typical chunk
+    /// length. Backends with a fixed window ignore the hint.
+    fn frame_size(&self, hint: usize) -> usize;
+
+    /// Probability in `[0, 1]` that `frame` contains speech. `frame` is exactly
+    /// `frame_size(..)` samples long.
+    fn speech_probability(&mut self, frame: &[f32]) -> Result<f32, VadError>;
+
+    /// Drops any recurrent state, e.g. when the audio source changes.
+    fn reset(&mut self) {}
+}
+
+impl<B: VadBackend + ?Sized> VadBackend for Box<B> {
+    fn frame_size(&self, hint: usize) -> usize {
+        (**self).frame_size(hint)
+    }
+
+    fn speech_probability(&mut self, frame: &[f32]) -> Result<f32, VadError> {
+        (**self).speech_probability(frame)
+    }
+
+    fn reset(&mut self) {
+        (**self).reset()
+    }
+}
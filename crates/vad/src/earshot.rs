@@ -1,14 +1,12 @@
 pub use ::earshot;
 use ::earshot::VoiceActivityProfile;
 
+use crate::{VadBackend, VadError};
+
 pub const FRAME_10MS: usize = 160;
 pub const FRAME_20MS: usize = 320;
 pub const FRAME_30MS: usize = 480;
 
-#[derive(Debug, thiserror::Error)]
-#[error("voice activity detection failed")]
-pub struct VadError;
-
 pub struct VoiceActivityDetector {
     inner: ::earshot::VoiceActivityDetector,
 }
@@ -21,7 +19,29 @@ impl VoiceActivityDetector {
     }
 
     pub fn predict_16khz(&mut self, samples: &[i16]) -> Result<bool, VadError> {
-        self.inner.predict_16khz(samples).map_err(|_| VadError)
+        self.inner
+            .predict_16khz(samples)
+            .map_err(|_| VadError::PredictionFailed)
+    }
+}
+
+/// Earshot only gives a yes/no answer, reported as a probability of 0 or 1.
+impl VadBackend for VoiceActivityDetector {
+    fn frame_size(&self, hint: usize) -> usize {
+        choose_optimal_frame_size(hint)
+    }
+
+    fn speech_probability(&mut self, frame: &[f32]) -> Result<f32, VadError> {
+        let samples: Vec<i16> = frame
+            .iter()
+            .map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+            .collect();
+        let is_speech = self.predict_16khz(&samples)?;
+        Ok(if is_speech { 1.0 } else { 0.0 })
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
     }
 }
 
@@ -0,0 +1,16 @@
+#[derive(Debug, thiserror::Error)]
+pub enum VadError {
+    #[error("voice activity detection failed")]
+    PredictionFailed,
+    #[error("unexpected frame length {actual}, backend expects {expected}")]
+    FrameSize { expected: usize, actual: usize },
+    #[cfg(feature = "silero")]
+    #[error(transparent)]
+    Onnx(#[from] hypr_onnx::Error),
+    #[cfg(feature = "silero")]
+    #[error(transparent)]
+    Ort(#[from] hypr_onnx::ort::Error),
+    #[cfg(feature = "silero")]
+    #[error("missing model output: {0}")]
+    MissingOutput(String),
+}
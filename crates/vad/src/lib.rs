@@ -1,2 +1,10 @@
+mod backend;
+mod error;
+
+pub use backend::*;
+pub use error::*;
+
 #[cfg(feature = "earshot")]
 pub mod earshot;
+#[cfg(feature = "silero")]
+pub mod silero;
@@ -0,0 +1,249 @@
+use std::path::Path;
+
+use hypr_onnx::{
+    ndarray::{Array1, Array2, Array3, ArrayViewD},
+    ort::{session::Session, value::TensorRef},
+};
+
+use crate::{SAMPLE_RATE, VadBackend, VadError};
+
+/// Silero v5 consumes 512-sample windows at 16 kHz.
+pub const FRAME_SIZE: usize = 512;
+/// Tail of the previous window the model expects in front of each new one.
+const CONTEXT_SIZE: usize = 64;
+const STATE_SHAPE: (usize, usize, usize) = (2, 1, 128);
+
+/// Silero-style recurrent VAD running through ONNX Runtime.
+pub struct SileroVad {
+    session: Session,
+    window: Window,
+}
+
+impl SileroVad {
+    pub fn from_bytes(model: &[u8]) -> Result<Self, VadError> {
+        Ok(Self::with_session(hypr_onnx::load_model_from_bytes(model)?))
+    }
+
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, VadError> {
+        Ok(Self::with_session(hypr_onnx::load_model_from_path(path)?))
+    }
+
+    fn with_session(session: Session) -> Self {
+        Self {
+            session,
+            window: Window::new(),
+        }
+    }
+}
+
+impl VadBackend for SileroVad {
+    fn frame_size(&self, _hint: usize) -> usize {
+        FRAME_SIZE
+    }
+
+    fn speech_probability(&mut self, frame: &[f32]) -> Result<f32, VadError> {
+        let input = self.window.input(frame)?;
+        let sr = Array1::from_elem(1, i64::from(SAMPLE_RATE));
+
+        let mut outputs = self.session.run(hypr_onnx::ort::inputs![
+            "input" => TensorRef::from_array_view(input.view())?,
+            "state" => TensorRef::from_array_view(self.window.state.view())?,
+            "sr" => TensorRef::from_array_view(sr.view())?,
+        ])?;
+
+        let probability = outputs
+            .remove("output")
+            .ok_or_else(|| VadError::MissingOutput("output".to_string()))?
+            .try_extract_array::<f32>()?
+            .iter()
+            .copied()
+            .next()
+            .ok_or_else(|| VadError::MissingOutput("output".to_string()))?;
+
+        let state = outputs
+            .remove("stateN")
+            .ok_or_else(|| VadError::MissingOutput("stateN".to_string()))?;
+        self.window
+            .advance(frame, state.try_extract_array::<f32>()?.view())?;
+
+        Ok(probability.clamp(0.0, 1.0))
+    }
+
+    fn reset(&mut self) {
+        self.window.reset();
+    }
+}
+
+/// Everything the model carries between frames: its recurrent state and the tail of the last
+/// frame, which is prepended to the next one.
+struct Window {
+    state: Array3<f32>,
+    context: Vec<f32>,
+}
+
+impl Window {
+    fn new() -> Self {
+        Self {
+            state: Array3::zeros(STATE_SHAPE),
+            context: vec![0.0; CONTEXT_SIZE],
+        }
+    }
+
+    /// The `[1, CONTEXT_SIZE + FRAME_SIZE]` model input for `frame`.
+    fn input(&self, frame: &[f32]) -> Result<Array2<f32>, VadError> {
+        if frame.len() != FRAME_SIZE {
+            return Err(VadError::FrameSize {
+                expected: FRAME_SIZE,
+                actual: frame.len(),
+            });
+        }
+
+        let mut input = Vec::with_capacity(CONTEXT_SIZE + FRAME_SIZE);
+        input.extend_from_slice(&self.context);
+        input.extend_from_slice(frame);
+        Array2::from_shape_vec((1, input.len()), input).map_err(|_| VadError::PredictionFailed)
+    }
+
+    /// Keeps the model's new state and the tail of `frame` for the next call.
+    fn advance(&mut self, frame: &[f32], state: ArrayViewD<'_, f32>) -> Result<(), VadError> {
+        self.state = state
+            .to_owned()
+            .into_shape_with_order(STATE_SHAPE)
+            .map_err(|_| VadError::MissingOutput("stateN".to_string()))?;
+        self.context
+            .copy_from_slice(&frame[FRAME_SIZE - CONTEXT_SIZE..]);
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        self.state.fill(0.0);
+        self.context.fill(0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hypr_onnx::ndarray::{ArrayD, IxDyn};
+
+    /// The model isn't checked in. Point `SILERO_VAD_MODEL` at a Silero v5 `silero_vad.onnx`,
+    /// or drop it next to Cargo.toml, to run the tests that need it.
+    fn model() -> Option<SileroVad> {
+        let path = std::env::var("SILERO_VAD_MODEL").unwrap_or_else(|_| {
+            concat!(env!("CARGO_MANIFEST_DIR"), "/silero_vad.onnx").to_string()
+        });
+        if !Path::new(&path).exists() {
+            eprintln!("skipping: no Silero model at {path}");
+            return None;
+        }
+        Some(SileroVad::from_path(path).unwrap())
+    }
+
+    /// One second of silence followed by the first two seconds of speech from `english_1`.
+    fn fixture() -> (Vec<f32>, usize) {
+        let silence = SAMPLE_RATE as usize;
+        let mut samples = vec![0.0; silence];
+        samples.extend(
+            hypr_data::english_1::AUDIO
+                .chunks_exact(2)
+                .take(2 * SAMPLE_RATE as usize)
+                .map(|chunk| i16::from_le_bytes([chunk[0], chunk[1]]) as f32 / 32768.0),
+        );
+        (samples, silence)
+    }
+
+    fn probabilities(vad: &mut SileroVad, samples: &[f32]) -> Vec<f32> {
+        samples
+            .chunks_exact(FRAME_SIZE)
+            .map(|frame| vad.speech_probability(frame).unwrap())
+            .collect()
+    }
+
+    fn ramp(start: f32) -> Vec<f32> {
+        (0..FRAME_SIZE).map(|i| start + i as f32).collect()
+    }
+
+    fn state(value: f32, shape: &[usize]) -> ArrayD<f32> {
+        ArrayD::from_elem(IxDyn(shape), value)
+    }
+
+    #[test]
+    fn test_window_prepends_previous_tail() {
+        let mut window = Window::new();
+        let first = ramp(0.0);
+        let input = window.input(&first).unwrap();
+        assert_eq!(input.shape(), &[1, CONTEXT_SIZE + FRAME_SIZE]);
+        assert!(input.iter().take(CONTEXT_SIZE).all(|&s| s == 0.0));
+        assert_eq!(input.as_slice().unwrap()[CONTEXT_SIZE..], first[..]);
+
+        window
+            .advance(&first, state(0.5, &[2, 1, 128]).view())
+            .unwrap();
+        let second = ramp(1000.0);
+        let input = window.input(&second).unwrap();
+        let input = input.as_slice().unwrap();
+        assert_eq!(input[..CONTEXT_SIZE], first[FRAME_SIZE - CONTEXT_SIZE..]);
+        assert_eq!(input[CONTEXT_SIZE..], second[..]);
+    }
+
+    #[test]
+    fn test_window_keeps_state_in_model_shape() {
+        let mut window = Window::new();
+        assert_eq!(window.state.dim(), STATE_SHAPE);
+
+        // Runtimes may hand the state back flattened; only the element count has to match.
+        window
+            .advance(&ramp(0.0), state(0.25, &[256]).view())
+            .unwrap();
+        assert_eq!(window.state.dim(), STATE_SHAPE);
+        assert!(window.state.iter().all(|&s| s == 0.25));
+
+        assert!(matches!(
+            window.advance(&ramp(0.0), state(0.25, &[2, 1, 64]).view()),
+            Err(VadError::MissingOutput(_))
+        ));
+
+        window.reset();
+        assert!(window.state.iter().all(|&s| s == 0.0));
+        assert!(window.context.iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_window_rejects_wrong_frame_size() {
+        assert!(matches!(
+            Window::new().input(&[0.0; FRAME_SIZE - 1]),
+            Err(VadError::FrameSize {
+                expected: FRAME_SIZE,
+                actual: 511,
+            })
+        ));
+    }
+
+    #[test]
+    fn test_separates_speech_from_silence() {
+        let Some(mut vad) = model() else {
+            return;
+        };
+        let (samples, silence) = fixture();
+        let probs = probabilities(&mut vad, &samples);
+        let (quiet, speech) = probs.split_at(silence / FRAME_SIZE);
+
+        assert!(quiet.iter().all(|&p| p < 0.2), "{quiet:?}");
+        assert!(
+            speech.iter().filter(|&&p| p > 0.5).count() > speech.len() / 2,
+            "{speech:?}"
+        );
+    }
+
+    #[test]
+    fn test_reset_restores_initial_state() {
+        let Some(mut vad) = model() else {
+            return;
+        };
+        let (samples, _) = fixture();
+
+        let first = probabilities(&mut vad, &samples);
+        vad.reset();
+        assert_eq!(probabilities(&mut vad, &samples), first);
+    }
+}
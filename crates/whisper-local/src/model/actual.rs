@@ -10,7 +10,7 @@ use whisper_rs::{
 
 use hypr_whisper::Language;
 
-use crate::Segment;
+use crate::{Segment, Word};
 
 lazy_static! {
     static ref TRAILING_DOTS: Regex = Regex::new(r"\.{2,}$").unwrap();
@@ -55,6 +55,7 @@ impl WhisperBuilder {
         let ctx = WhisperContext::new_with_params(&model_path, context_param)?;
         let state = ctx.create_state()?;
         let token_beg = ctx.token_beg();
+        let token_eot = ctx.token_eot();
 
         Ok(Whisper {
             id: uuid::Uuid::new_v4().to_string(),
@@ -63,6 +64,7 @@ impl WhisperBuilder {
             dynamic_prompt: "".to_string(),
             state,
             token_beg,
+            token_eot,
         })
     }
 
@@ -86,6 +88,17 @@ pub struct Whisper {
     dynamic_prompt: String,
     state: WhisperState,
     token_beg: WhisperTokenId,
+    /// Ids at or above this are special tokens (timestamps, language, task).
+    token_eot: WhisperTokenId,
+}
+
+/// One text token with whisper's token-level timestamps, in seconds.
+#[derive(Debug, Clone)]
+struct TokenTiming {
+    text: String,
+    start: f64,
+    end: f64,
+    probability: f32,
 }
 
 impl Whisper {
@@ -94,6 +107,20 @@ impl Whisper {
     }
 
     pub fn transcribe(&mut self, audio: &[f32]) -> Result<Vec<Segment>, crate::Error> {
+        self.run(audio, true)
+    }
+
+    /// Transcribes audio that is still being spoken. The result doesn't feed the prompt used
+    /// for later chunks, since the final pass over the same audio will.
+    pub fn transcribe_interim(&mut self, audio: &[f32]) -> Result<Vec<Segment>, crate::Error> {
+        let mut segments = self.run(audio, false)?;
+        for segment in &mut segments {
+            segment.interim = true;
+        }
+        Ok(segments)
+    }
+
+    fn run(&mut self, audio: &[f32], update_prompt: bool) -> Result<Vec<Segment>, crate::Error> {
         #[cfg(debug_assertions)]
         self.debug(audio);
 
@@ -126,7 +153,7 @@ impl Whisper {
             }
 
             p.set_no_timestamps(true);
-            p.set_token_timestamps(false);
+            p.set_token_timestamps(true);
             p.set_split_on_word(true);
 
             p.set_temperature(0.0);
@@ -163,14 +190,37 @@ impl Whisper {
                 TRAILING_DOTS.replace(&segment_text, "").to_string()
             };
 
+            let mut tokens = Vec::new();
+            for t in 0..segment.n_tokens() {
+                let Some(token) = segment.get_token(t) else {
+                    continue;
+                };
+                if token.token_id() >= self.token_eot {
+                    continue;
+                }
+
+                let data = token.token_data();
+                tokens.push(TokenTiming {
+                    text: token.to_str_lossy()?.into_owned(),
+                    start: data.t0 as f64 / 100.0,
+                    end: data.t1 as f64 / 100.0,
+                    probability: data.p,
+                });
+            }
+
+            let confidence = if tokens.is_empty() {
+                0.0
+            } else {
+                tokens.iter().map(|t| t.probability).sum::<f32>() / tokens.len() as f32
+            };
+
             segments.push(Segment {
                 text,
                 language: language.clone(),
                 start,
                 end,
-                // https://github.com/ggml-org/whisper.cpp/pull/971/files#diff-2d3599a9fad195f2c3c60bd06691bc1815325b3560b5feda41a91fa71194e805R310-R327
-                // We previously implemented it based on above, but after updating to v1.7.6, the API has changed, and we're still unable to figure it out. We're not using it anyway.
-                confidence: 1.0,
+                confidence,
+                words: group_words(&tokens),
                 ..Default::default()
             });
         }
@@ -185,7 +235,9 @@ impl Whisper {
 
         if !full_text.is_empty() {
             tracing::info!(text_length = full_text.len(), "transcribe_completed");
-            self.dynamic_prompt = full_text;
+            if update_prompt {
+                self.dynamic_prompt = full_text;
+            }
         }
 
         Ok(segments)
@@ -204,8 +256,7 @@ impl Whisper {
         }
 
         let lang_str = {
-            self.state.pcm_to_mel(audio, 1)?;
-            let (_lang_id, lang_probs) = self.state.lang_detect(0, 1)?;
+            let lang_probs = self.lang_probs(audio)?;
 
             let mut best_lang = None;
             let mut best_prob = f32::NEG_INFINITY;
@@ -228,6 +279,27 @@ impl Whisper {
         Ok(lang_str)
     }
 
+    /// Runs only whisper's language-detection pass over `audio` (16 kHz mono; the model looks at
+    /// the first 30 seconds) and returns every language it knows, most probable first.
+    pub fn detect_language(&mut self, audio: &[f32]) -> Result<Vec<(Language, f32)>, crate::Error> {
+        let lang_probs = self.lang_probs(audio)?;
+
+        let mut ranked: Vec<(Language, f32)> = lang_probs
+            .into_iter()
+            .enumerate()
+            .filter_map(|(id, prob)| Language::from_whisper_index(id).map(|lang| (lang, prob)))
+            .collect();
+        ranked.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+        Ok(ranked)
+    }
+
+    fn lang_probs(&mut self, audio: &[f32]) -> Result<Vec<f32>, crate::Error> {
+        self.state.pcm_to_mel(audio, 1)?;
+        let (_lang_id, lang_probs) = self.state.lang_detect(0, 1)?;
+        Ok(lang_probs)
+    }
+
     fn filter_segments(segments: Vec<Segment>) -> Vec<Segment> {
         segments
             .into_iter()
@@ -295,10 +367,77 @@ impl Whisper {
     }
 }
 
+/// Whisper's BPE tokens carry the leading space of the word they start, so a word runs from
+/// one space-prefixed token to the next. Punctuation tokens stay attached to their word.
+fn group_words(tokens: &[TokenTiming]) -> Vec<Word> {
+    let mut words: Vec<(Word, usize)> = Vec::new();
+
+    for token in tokens {
+        let starts_word = token.text.starts_with(char::is_whitespace) || words.is_empty();
+        let text = token.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        match words.last_mut() {
+            Some((word, count)) if !starts_word => {
+                word.text.push_str(text);
+                word.end = word.end.max(token.end);
+                word.confidence += token.probability;
+                *count += 1;
+            }
+            _ => words.push((
+                Word {
+                    text: text.to_string(),
+                    start: token.start,
+                    end: token.end.max(token.start),
+                    confidence: token.probability,
+                },
+                1,
+            )),
+        }
+    }
+
+    words
+        .into_iter()
+        .map(|(mut word, count)| {
+            word.confidence /= count as f32;
+            word
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn token(text: &str, start: f64, end: f64, probability: f32) -> TokenTiming {
+        TokenTiming {
+            text: text.to_string(),
+            start,
+            end,
+            probability,
+        }
+    }
+
+    #[test]
+    fn test_group_words_from_tokens() {
+        let words = group_words(&[
+            token(" Hel", 0.0, 0.2, 0.8),
+            token("lo", 0.2, 0.4, 0.6),
+            token(",", 0.4, 0.4, 1.0),
+            token(" world", 0.5, 0.9, 0.9),
+            token(" ", 0.9, 0.9, 0.1),
+        ]);
+
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].text, "Hello,");
+        assert_eq!((words[0].start, words[0].end), (0.0, 0.4));
+        assert!((words[0].confidence - 0.8).abs() < 1e-6);
+        assert_eq!(words[1].text, "world");
+        assert_eq!((words[1].start, words[1].end), (0.5, 0.9));
+    }
+
     #[test]
     fn test_whisper() {
         let mut whisper = Whisper::builder()
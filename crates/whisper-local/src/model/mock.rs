@@ -1,4 +1,4 @@
-use crate::Segment;
+use crate::{Segment, Word};
 use hypr_whisper::Language;
 
 #[derive(Default)]
@@ -33,7 +33,29 @@ impl Whisper {
             start: 0.0,
             end: 1.0,
             confidence: 1.0,
+            words: vec![Word {
+                text: "mock".to_string(),
+                start: 0.0,
+                end: 1.0,
+                confidence: 1.0,
+            }],
+            interim: false,
             meta: None,
         }])
     }
+
+    pub fn transcribe_interim(&mut self, samples: &[f32]) -> Result<Vec<Segment>, crate::Error> {
+        let mut segments = self.transcribe(samples)?;
+        for segment in &mut segments {
+            segment.interim = true;
+        }
+        Ok(segments)
+    }
+
+    pub fn detect_language(
+        &mut self,
+        _samples: &[f32],
+    ) -> Result<Vec<(Language, f32)>, crate::Error> {
+        Ok(vec![(Language::En, 1.0)])
+    }
 }
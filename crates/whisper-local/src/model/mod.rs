@@ -8,6 +8,16 @@ mod mock;
 #[cfg(not(feature = "actual"))]
 pub use mock::*;
 
+/// A word with timings (seconds, relative to the transcribed audio) from whisper's token timestamps.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Word {
+    pub text: String,
+    pub start: f64,
+    pub end: f64,
+    /// Mean probability of the word's tokens.
+    pub confidence: f32,
+}
+
 #[derive(Debug, Default)]
 pub struct Segment {
     pub text: String,
@@ -15,6 +25,9 @@ pub struct Segment {
     pub start: f64,
     pub end: f64,
     pub confidence: f32,
+    pub words: Vec<Word>,
+    /// Hypothesis over audio that is still being spoken; a final segment will replace it.
+    pub interim: bool,
     pub meta: Option<serde_json::Value>,
 }
 
@@ -43,6 +56,14 @@ impl Segment {
         self.confidence
     }
 
+    pub fn words(&self) -> &[Word] {
+        &self.words
+    }
+
+    pub fn is_interim(&self) -> bool {
+        self.interim
+    }
+
     pub fn meta(&self) -> Option<serde_json::Value> {
         self.meta.clone()
     }
@@ -19,12 +19,18 @@ pub struct TranscriptionTask<S, T> {
 pub trait AudioChunk: Send + 'static {
     fn samples(&self) -> &[f32];
     fn meta(&self) -> Option<serde_json::Value>;
+
+    /// Audio of speech that hasn't ended yet; transcribed with [`Whisper::transcribe_interim`].
+    fn is_interim(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Default)]
 pub struct SimpleAudioChunk {
     pub samples: Vec<f32>,
     pub meta: Option<serde_json::Value>,
+    pub interim: bool,
 }
 
 impl AudioChunk for SimpleAudioChunk {
@@ -35,6 +41,10 @@ impl AudioChunk for SimpleAudioChunk {
     fn meta(&self) -> Option<serde_json::Value> {
         self.meta.clone()
     }
+
+    fn is_interim(&self) -> bool {
+        self.interim
+    }
 }
 
 pub struct AudioChunkStream<S>(pub S);
@@ -111,6 +121,7 @@ where
                         &samples,
                         &mut this.current_segment_task,
                         None,
+                        false,
                     ) {
                         Poll::Ready(result) => return Poll::Ready(result),
                         Poll::Pending => continue,
@@ -156,6 +167,7 @@ where
                         samples,
                         &mut this.current_segment_task,
                         meta,
+                        chunk.is_interim(),
                     ) {
                         Poll::Ready(result) => return Poll::Ready(result),
                         Poll::Pending => continue,
@@ -173,9 +185,16 @@ fn process_transcription<'a>(
     samples: &'a [f32],
     current_segment_task: &'a mut Option<Pin<Box<dyn Stream<Item = Segment> + Send>>>,
     meta: Option<serde_json::Value>,
+    interim: bool,
 ) -> Poll<Option<Segment>> {
     if !samples.is_empty() {
-        match whisper.transcribe(samples) {
+        let result = if interim {
+            whisper.transcribe_interim(samples)
+        } else {
+            whisper.transcribe(samples)
+        };
+
+        match result {
             Err(e) => {
                 tracing::error!("process_transcription: {:?}", e);
                 // Return Ready(None) to terminate the stream on error
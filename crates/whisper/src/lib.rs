@@ -1,6 +1,8 @@
 // https://github.com/openai/whisper/blob/ba3f3cd/whisper/tokenizer.py#L10-L128
 #[repr(u8)]
-#[derive(Debug, Copy, Clone, strum::EnumString, strum::Display, strum::AsRefStr)]
+#[derive(
+    Debug, Copy, Clone, strum::EnumString, strum::Display, strum::AsRefStr, strum::FromRepr,
+)]
 pub enum Language {
     #[strum(serialize = "en")]
     En,
@@ -208,4 +210,8 @@ impl Language {
     pub fn whisper_index(self) -> usize {
         self as usize
     }
+
+    pub fn from_whisper_index(index: usize) -> Option<Self> {
+        u8::try_from(index).ok().and_then(Self::from_repr)
+    }
 }
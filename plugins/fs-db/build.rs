@@ -5,6 +5,7 @@ const COMMANDS: &[&str] = &[
     "save_session_content",
     "save_session_transcript",
     "save_session_enhanced_note",
+    "plan_migrations",
 ];
 
 fn main() {
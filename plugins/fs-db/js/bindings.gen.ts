@@ -63,6 +63,14 @@ export const commands = {
       else return { status: "error", error: e as any, };
     }
   },
+  async planMigrations(): Promise<Result<PlannedMigration[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("plugin:fs-db|plan_migrations",), };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any, };
+    }
+  },
 };
 
 /** user-defined events **/
@@ -80,6 +88,10 @@ export type EnhancedNoteData = {
   content: string;
 };
 export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue; }>;
+/**
+ * The operations a pending migration would perform, relative to the vault base.
+ */
+export type PlannedMigration = { version: string; operations: string[]; };
 export type SessionContent = { rawMd: string | null; };
 export type SessionEnhancedNotes = { notes: EnhancedNoteData[]; };
 export type SessionTranscript = { transcripts: TranscriptData[]; };
@@ -1,4 +1,5 @@
 use crate::FsDbPluginExt;
+use crate::migrations::PlannedMigration;
 use crate::types::{
     EnhancedNoteData, SessionContent, SessionEnhancedNotes, SessionTranscript, TranscriptData,
 };
@@ -78,3 +79,14 @@ pub(crate) async fn save_session_enhanced_note<R: tauri::Runtime>(
         .await
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn plan_migrations<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<Vec<PlannedMigration>, String> {
+    app.fs_db()
+        .plan_migrations()
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -5,6 +5,7 @@ use hypr_frontmatter::Document;
 use tauri_plugin_settings::SettingsPluginExt;
 
 use crate::Error;
+use crate::migrations::PlannedMigration;
 use crate::types::{
     self, EnhancedNoteData, EnhancedNoteFrontmatterWrite, MemoFrontmatter, MemoFrontmatterWrite,
     SessionContent, SessionEnhancedNotes, SessionTranscript, TranscriptData, TranscriptEntryWrite,
@@ -24,8 +25,18 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> FsDb<'a, R, M> {
             return Ok(());
         }
 
-        let app_version = self
-            .manager
+        crate::version::write_version(&base_dir, &self.app_version())?;
+        Ok(())
+    }
+
+    /// The migrations the next start would run on the vault, without applying any of them.
+    pub async fn plan_migrations(&self) -> crate::Result<Vec<PlannedMigration>> {
+        let base_dir = self.manager.app_handle().settings().fresh_vault_base()?;
+        crate::migrations::dry_run(&base_dir, &self.app_version()).await
+    }
+
+    fn app_version(&self) -> hypr_version::Version {
+        self.manager
             .app_handle()
             .config()
             .version
@@ -36,10 +47,7 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> FsDb<'a, R, M> {
                     v.parse::<hypr_version::Version>()
                         .expect("version must be semver")
                 },
-            );
-
-        crate::version::write_version(&base_dir, &app_version)?;
-        Ok(())
+            )
     }
 
     fn resolve_session_dir(&self, session_id: &str) -> crate::Result<PathBuf> {
@@ -25,6 +25,7 @@ fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
             commands::save_session_content::<tauri::Wry>,
             commands::save_session_transcript::<tauri::Wry>,
             commands::save_session_enhanced_note::<tauri::Wry>,
+            commands::plan_migrations::<tauri::Wry>,
         ])
         .error_handling(tauri_specta::ErrorHandlingMode::Result)
 }
@@ -1,4 +1,5 @@
 mod runner;
+mod snapshot;
 mod utils;
 
 use std::future::Future;
@@ -10,7 +11,8 @@ use hypr_version::Version;
 use crate::Result;
 use crate::version::{DetectedVersion, version_from_name};
 
-pub use runner::run;
+pub use runner::{PlannedMigration, dry_run, run};
+pub use utils::FileOp;
 
 pub trait Migration: Send + Sync {
     fn introduced_in(&self) -> &'static Version;
@@ -19,7 +21,12 @@ pub trait Migration: Send + Sync {
         true
     }
 
-    fn run<'a>(&self, base_dir: &'a Path) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+    /// Lists the file operations the migration would perform against `base_dir`, without
+    /// touching it. The runner snapshots every path they touch before applying them.
+    fn plan<'a>(
+        &self,
+        base_dir: &'a Path,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<FileOp>>> + Send + 'a>>;
 }
 
 macro_rules! migrations {
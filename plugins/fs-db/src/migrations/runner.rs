@@ -1,7 +1,10 @@
+use std::io::Write;
 use std::path::Path;
 
 use hypr_version::Version;
 
+use super::snapshot::{self, Snapshot};
+use super::utils::apply_ops;
 use super::{Migration, all_migrations};
 use crate::Result;
 use crate::version::{DetectedVersion, InferredVersion, detect_version, write_version};
@@ -32,7 +35,22 @@ fn migrations_to_apply(detected: &DetectedVersion, to: &Version) -> Vec<&'static
         .collect()
 }
 
+const LOG_FILE: &str = ".hyprnote/migrations.log";
+
+/// The operations a pending migration would perform, relative to the vault base.
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub struct PlannedMigration {
+    pub version: String,
+    pub operations: Vec<String>,
+}
+
 pub async fn run(base_dir: &Path, app_version: &Version) -> Result<()> {
+    for leftover in snapshot::leftover(base_dir)? {
+        let version = leftover.version().to_string();
+        leftover.restore()?;
+        append_log(base_dir, &version, "restored after an interrupted run", &[])?;
+    }
+
     let detected = detect_version(base_dir).await;
 
     if matches!(detected, DetectedVersion::Fresh) {
@@ -41,7 +59,7 @@ pub async fn run(base_dir: &Path, app_version: &Version) -> Result<()> {
     }
 
     for migration in migrations_to_apply(&detected, app_version) {
-        migration.run(base_dir).await?;
+        apply(base_dir, migration).await?;
         write_version(base_dir, migration.introduced_in())?;
     }
 
@@ -49,6 +67,77 @@ pub async fn run(base_dir: &Path, app_version: &Version) -> Result<()> {
     Ok(())
 }
 
+/// Reports what `run` would do without touching the vault. Each migration is planned against
+/// the vault as it is now, so operations that depend on an earlier pending migration (e.g.
+/// renaming transcripts inside folders that have yet to be moved) show their current paths.
+pub async fn dry_run(base_dir: &Path, app_version: &Version) -> Result<Vec<PlannedMigration>> {
+    let detected = detect_version(base_dir).await;
+    let mut planned = vec![];
+
+    for migration in migrations_to_apply(&detected, app_version) {
+        let ops = migration.plan(base_dir).await?;
+        planned.push(PlannedMigration {
+            version: migration.introduced_in().to_string(),
+            operations: ops.iter().map(|op| op.describe(base_dir)).collect(),
+        });
+    }
+
+    Ok(planned)
+}
+
+async fn apply(base_dir: &Path, migration: &dyn Migration) -> Result<()> {
+    let version = migration.introduced_in().to_string();
+    let ops = migration.plan(base_dir).await?;
+    let operations: Vec<String> = ops.iter().map(|op| op.describe(base_dir)).collect();
+
+    if ops.is_empty() {
+        return append_log(base_dir, &version, "nothing to change", &[]);
+    }
+
+    let snapshot = Snapshot::create(base_dir, &version, &ops)?;
+
+    match apply_ops(ops) {
+        Ok(()) => {
+            snapshot.discard()?;
+            append_log(base_dir, &version, "applied", &operations)
+        }
+        Err(error) => {
+            let status = match snapshot.restore() {
+                Ok(()) => format!("failed and was rolled back: {error}"),
+                Err(restore_error) => {
+                    format!("failed: {error}; rollback failed: {restore_error}")
+                }
+            };
+            append_log(base_dir, &version, &status, &operations)?;
+            Err(error)
+        }
+    }
+}
+
+fn append_log(base_dir: &Path, version: &str, status: &str, operations: &[String]) -> Result<()> {
+    let path = base_dir.join(LOG_FILE);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut entry = format!(
+        "{} {version} {status}\n",
+        chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+    );
+    for operation in operations {
+        entry.push_str("  ");
+        entry.push_str(operation);
+        entry.push('\n');
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    file.write_all(entry.as_bytes())?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -173,4 +262,66 @@ mod tests {
             assert_eq!(result, *expected, "from {from:?} to {to}");
         }
     }
+
+    struct Failing;
+
+    impl Migration for Failing {
+        fn introduced_in(&self) -> &'static Version {
+            static VERSION: std::sync::LazyLock<Version> =
+                std::sync::LazyLock::new(|| v("1.0.4-nightly.2"));
+            &VERSION
+        }
+
+        fn plan<'a>(
+            &self,
+            base_dir: &'a Path,
+        ) -> std::pin::Pin<
+            Box<dyn std::future::Future<Output = Result<Vec<super::super::FileOp>>> + Send + 'a>,
+        > {
+            Box::pin(async move {
+                Ok(vec![
+                    super::super::FileOp::Write {
+                        path: base_dir.join("sessions/s1/transcript.json"),
+                        content: "half migrated".to_string(),
+                        force: true,
+                    },
+                    super::super::FileOp::Rename {
+                        from: base_dir.join("sessions/s1/missing.json"),
+                        to: base_dir.join("sessions/s1/other.json"),
+                    },
+                ])
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_apply_rolls_back_failed_migration() {
+        let temp = tempfile::tempdir().unwrap();
+        let base = temp.path();
+        std::fs::create_dir_all(base.join("sessions/s1")).unwrap();
+        std::fs::write(base.join("sessions/s1/transcript.json"), "original").unwrap();
+
+        let planned = Failing.plan(base).await.unwrap();
+        assert_eq!(
+            planned
+                .iter()
+                .map(|op| op.describe(base))
+                .collect::<Vec<_>>(),
+            vec![
+                "write sessions/s1/transcript.json",
+                "rename sessions/s1/missing.json -> sessions/s1/other.json",
+            ]
+        );
+
+        assert!(apply(base, &Failing).await.is_err());
+        assert_eq!(
+            std::fs::read_to_string(base.join("sessions/s1/transcript.json")).unwrap(),
+            "original"
+        );
+        assert!(snapshot::leftover(base).unwrap().is_empty());
+
+        let log = std::fs::read_to_string(base.join(LOG_FILE)).unwrap();
+        assert!(log.contains(" 1.0.4-nightly.2 failed and was rolled back: "));
+        assert!(log.contains("\n  write sessions/s1/transcript.json\n"));
+    }
 }
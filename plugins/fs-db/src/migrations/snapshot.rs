@@ -0,0 +1,325 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::utils::FileOp;
+use crate::Result;
+
+const MIGRATIONS_DIR: &str = ".hyprnote/migrations";
+const MANIFEST: &str = "manifest.json";
+const FILES_DIR: &str = "files";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    version: String,
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    /// Relative to the vault base.
+    path: PathBuf,
+    existed: bool,
+}
+
+/// The state of every path a migration is about to touch, kept under
+/// `.hyprnote/migrations/<version>` until the migration either finishes or is rolled back.
+/// The manifest is written last, so a snapshot without one never had its migration started.
+pub struct Snapshot {
+    base_dir: PathBuf,
+    dir: PathBuf,
+    manifest: Manifest,
+}
+
+impl Snapshot {
+    pub fn create(base_dir: &Path, version: &str, ops: &[FileOp]) -> Result<Self> {
+        let dir = base_dir.join(MIGRATIONS_DIR).join(version);
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)?;
+        }
+        std::fs::create_dir_all(dir.join(FILES_DIR))?;
+
+        let mut paths = BTreeSet::new();
+        for op in ops {
+            for path in op.touched() {
+                let rel = path.strip_prefix(base_dir).map_err(|_| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!("{} is outside the vault", path.display()),
+                    )
+                })?;
+                paths.insert(rel.to_path_buf());
+            }
+        }
+
+        // A directory entry already restores everything beneath it.
+        let covered: Vec<PathBuf> = paths
+            .iter()
+            .filter(|path| path.ancestors().skip(1).any(|a| paths.contains(a)))
+            .cloned()
+            .collect();
+        for path in covered {
+            paths.remove(&path);
+        }
+
+        let mut entries = vec![];
+        for rel in paths {
+            let source = base_dir.join(&rel);
+            let existed = source.symlink_metadata().is_ok();
+            if existed {
+                let in_place = ops.iter().any(|op| op.writes_in_place(&source));
+                preserve(&source, &dir.join(FILES_DIR).join(&rel), in_place)?;
+            }
+            entries.push(Entry { path: rel, existed });
+        }
+
+        let manifest = Manifest {
+            version: version.to_string(),
+            entries,
+        };
+        std::fs::write(dir.join(MANIFEST), serde_json::to_string_pretty(&manifest)?)?;
+
+        Ok(Self {
+            base_dir: base_dir.to_path_buf(),
+            dir,
+            manifest,
+        })
+    }
+
+    pub fn version(&self) -> &str {
+        &self.manifest.version
+    }
+
+    /// Puts every snapshotted path back the way it was and drops the snapshot. An entry whose
+    /// preserved copy is gone was already moved back by an interrupted restore, so running it
+    /// again picks up where that one stopped.
+    pub fn restore(self) -> Result<()> {
+        for entry in self.manifest.entries.iter().rev() {
+            let target = self.base_dir.join(&entry.path);
+            let preserved = self.dir.join(FILES_DIR).join(&entry.path);
+            if entry.existed && preserved.symlink_metadata().is_err() {
+                continue;
+            }
+
+            remove(&target)?;
+
+            if entry.existed {
+                if let Some(parent) = target.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::rename(&preserved, &target)?;
+            }
+        }
+
+        self.discard()
+    }
+
+    pub fn discard(self) -> Result<()> {
+        std::fs::remove_dir_all(&self.dir)?;
+        Ok(())
+    }
+}
+
+/// Finds snapshots left behind by a migration that never finished, e.g. because the app was
+/// killed mid-way. Snapshots without a manifest are dropped since nothing was changed yet.
+pub fn leftover(base_dir: &Path) -> Result<Vec<Snapshot>> {
+    let root = base_dir.join(MIGRATIONS_DIR);
+    if !root.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut snapshots = vec![];
+    for entry in std::fs::read_dir(&root)? {
+        let dir = entry?.path();
+        if !dir.is_dir() {
+            continue;
+        }
+
+        let manifest = match std::fs::read_to_string(dir.join(MANIFEST)) {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(_) => {
+                std::fs::remove_dir_all(&dir)?;
+                continue;
+            }
+        };
+
+        snapshots.push(Snapshot {
+            base_dir: base_dir.to_path_buf(),
+            dir,
+            manifest,
+        });
+    }
+
+    Ok(snapshots)
+}
+
+/// Hard-links `source` into the snapshot, falling back to a copy across devices. Anything the
+/// migration rewrites in place, or a directory holding such a file, is copied instead, since a
+/// link would see the new contents.
+fn preserve(source: &Path, target: &Path, copy: bool) -> Result<()> {
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    if source.is_dir() {
+        std::fs::create_dir_all(target)?;
+        for entry in std::fs::read_dir(source)? {
+            let entry = entry?;
+            preserve(&entry.path(), &target.join(entry.file_name()), copy)?;
+        }
+        return Ok(());
+    }
+
+    if copy || std::fs::hard_link(source, target).is_err() {
+        std::fs::copy(source, target)?;
+    }
+    Ok(())
+}
+
+fn remove(path: &Path) -> Result<()> {
+    match path.symlink_metadata() {
+        Ok(meta) if meta.is_dir() => std::fs::remove_dir_all(path)?,
+        Ok(_) => std::fs::remove_file(path)?,
+        Err(_) => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_restore_reverts_writes_renames_and_removes() {
+        let temp = tempdir().unwrap();
+        let base = temp.path();
+        std::fs::create_dir_all(base.join("a")).unwrap();
+        std::fs::write(base.join("a/_transcript.json"), "legacy").unwrap();
+        std::fs::write(base.join("a/_meta.json"), "meta").unwrap();
+        std::fs::write(base.join("old.json"), "old").unwrap();
+
+        let ops = vec![
+            FileOp::Rename {
+                from: base.join("a/_transcript.json"),
+                to: base.join("a/transcript.json"),
+            },
+            FileOp::Write {
+                path: base.join("a/_meta.json"),
+                content: "new".to_string(),
+                force: true,
+            },
+            FileOp::Remove {
+                path: base.join("old.json"),
+            },
+        ];
+
+        let snapshot = Snapshot::create(base, "1.0.0", &ops).unwrap();
+        super::super::utils::apply_ops(ops).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(base.join("a/_meta.json")).unwrap(),
+            "new"
+        );
+
+        snapshot.restore().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(base.join("a/_transcript.json")).unwrap(),
+            "legacy"
+        );
+        assert_eq!(
+            std::fs::read_to_string(base.join("a/_meta.json")).unwrap(),
+            "meta"
+        );
+        assert_eq!(
+            std::fs::read_to_string(base.join("old.json")).unwrap(),
+            "old"
+        );
+        assert!(!base.join("a/transcript.json").exists());
+        assert!(!base.join(MIGRATIONS_DIR).join("1.0.0").exists());
+    }
+
+    #[test]
+    fn test_restore_reverts_in_place_writes_under_a_covered_directory() {
+        let temp = tempdir().unwrap();
+        let base = temp.path();
+        std::fs::create_dir_all(base.join("a")).unwrap();
+        std::fs::write(base.join("a/_meta.json"), "meta").unwrap();
+
+        let ops = vec![
+            FileOp::Write {
+                path: base.join("a/_meta.json"),
+                content: "new".to_string(),
+                force: true,
+            },
+            FileOp::Rename {
+                from: base.join("a"),
+                to: base.join("b"),
+            },
+        ];
+
+        let snapshot = Snapshot::create(base, "1.0.0", &ops).unwrap();
+        super::super::utils::apply_ops(ops).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(base.join("b/_meta.json")).unwrap(),
+            "new"
+        );
+
+        snapshot.restore().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(base.join("a/_meta.json")).unwrap(),
+            "meta"
+        );
+        assert!(!base.join("b").exists());
+    }
+
+    #[test]
+    fn test_restore_resumes_after_an_interrupted_restore() {
+        let temp = tempdir().unwrap();
+        let base = temp.path();
+        std::fs::write(base.join("kept.json"), "kept").unwrap();
+        std::fs::write(base.join("old.json"), "old").unwrap();
+
+        let ops = vec![
+            FileOp::Write {
+                path: base.join("kept.json"),
+                content: "new".to_string(),
+                force: true,
+            },
+            FileOp::Remove {
+                path: base.join("old.json"),
+            },
+        ];
+
+        Snapshot::create(base, "1.0.0", &ops).unwrap();
+        super::super::utils::apply_ops(ops).unwrap();
+
+        // The first restore moved `old.json` back, then the app was killed.
+        let files = base.join(MIGRATIONS_DIR).join("1.0.0").join(FILES_DIR);
+        std::fs::rename(files.join("old.json"), base.join("old.json")).unwrap();
+
+        let mut snapshots = leftover(base).unwrap();
+        assert_eq!(snapshots.len(), 1);
+        snapshots.pop().unwrap().restore().unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(base.join("old.json")).unwrap(),
+            "old"
+        );
+        assert_eq!(
+            std::fs::read_to_string(base.join("kept.json")).unwrap(),
+            "kept"
+        );
+        assert!(leftover(base).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_leftover_skips_snapshots_without_manifest() {
+        let temp = tempdir().unwrap();
+        let base = temp.path();
+        std::fs::create_dir_all(base.join(MIGRATIONS_DIR).join("1.0.0").join(FILES_DIR)).unwrap();
+
+        assert!(leftover(base).unwrap().is_empty());
+        assert!(!base.join(MIGRATIONS_DIR).join("1.0.0").exists());
+    }
+}
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use hypr_db_parser::{SpeakerHint, Transcript, Word};
 use serde_json::Value;
@@ -12,6 +12,47 @@ pub enum FileOp {
         content: String,
         force: bool,
     },
+    Rename {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    Remove {
+        path: PathBuf,
+    },
+}
+
+impl FileOp {
+    /// Paths whose current contents the op may replace or delete.
+    pub fn touched(&self) -> Vec<&Path> {
+        match self {
+            FileOp::Write { path, .. } | FileOp::Remove { path } => vec![path],
+            FileOp::Rename { from, to } => vec![from, to],
+        }
+    }
+
+    /// Whether the op rewrites `path`, or a file beneath it, in place, so a hard link would not
+    /// preserve it.
+    pub fn writes_in_place(&self, path: &Path) -> bool {
+        matches!(self, FileOp::Write { path: target, .. } if target.starts_with(path))
+    }
+
+    pub fn describe(&self, base_dir: &Path) -> String {
+        let rel = |path: &Path| {
+            path.strip_prefix(base_dir)
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        };
+
+        match self {
+            FileOp::Write {
+                path, force: true, ..
+            } => format!("write {}", rel(path)),
+            FileOp::Write { path, .. } => format!("create {}", rel(path)),
+            FileOp::Rename { from, to } => format!("rename {} -> {}", rel(from), rel(to)),
+            FileOp::Remove { path } => format!("remove {}", rel(path)),
+        }
+    }
 }
 
 pub fn group_by_session_id<T, F>(items: &[T], get_id: F) -> HashMap<&str, Vec<&T>>
@@ -99,6 +140,19 @@ pub fn apply_ops(ops: Vec<FileOp>) -> Result<()> {
                 }
                 std::fs::write(&path, &content)?;
             }
+            FileOp::Rename { from, to } => {
+                if let Some(parent) = to.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::rename(&from, &to)?;
+            }
+            FileOp::Remove { path } => {
+                if path.is_dir() {
+                    std::fs::remove_dir_all(&path)?;
+                } else if path.exists() {
+                    std::fs::remove_file(&path)?;
+                }
+            }
         }
     }
     Ok(())
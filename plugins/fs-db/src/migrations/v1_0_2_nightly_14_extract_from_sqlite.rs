@@ -9,7 +9,7 @@ use hypr_db_parser::{
 use hypr_frontmatter::Document;
 use hypr_version::Version;
 
-use super::utils::{FileOp, apply_ops, build_transcript_json_multi, group_by_session_id};
+use super::utils::{FileOp, build_transcript_json_multi, group_by_session_id};
 use super::version_from_name;
 use crate::Result;
 use crate::version::{DetectedVersion, InferredVersion};
@@ -77,26 +77,26 @@ impl super::Migration for Migrate {
         )
     }
 
-    fn run<'a>(&self, base_dir: &'a Path) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
-        Box::pin(run_inner(base_dir))
+    fn plan<'a>(
+        &self,
+        base_dir: &'a Path,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<FileOp>>> + Send + 'a>> {
+        Box::pin(plan_inner(base_dir))
     }
 }
 
-async fn run_inner(base_dir: &Path) -> Result<()> {
+async fn plan_inner(base_dir: &Path) -> Result<Vec<FileOp>> {
     let sqlite_path = base_dir.join("db.sqlite");
     if !sqlite_path.exists() {
-        return Ok(());
+        return Ok(vec![]);
     }
 
     if hypr_db_parser::v1::validate(&sqlite_path).await.is_err() {
-        return Ok(());
+        return Ok(vec![]);
     }
 
     let data = hypr_db_parser::v1::parse_from_sqlite(&sqlite_path).await?;
-    let ops = collect_ops(base_dir, &data)?;
-    apply_ops(ops)?;
-
-    Ok(())
+    collect_ops(base_dir, &data)
 }
 
 fn collect_ops(base_dir: &Path, data: &Collection) -> Result<Vec<FileOp>> {
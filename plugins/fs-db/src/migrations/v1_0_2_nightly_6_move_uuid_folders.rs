@@ -5,6 +5,7 @@ use std::pin::Pin;
 use hypr_version::Version;
 use uuid::Uuid;
 
+use super::utils::FileOp;
 use super::version_from_name;
 use crate::Result;
 use crate::version::{DetectedVersion, InferredVersion};
@@ -23,20 +24,22 @@ impl super::Migration for Migrate {
         )
     }
 
-    fn run<'a>(&self, base_dir: &'a Path) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
-        Box::pin(run_inner(base_dir))
+    fn plan<'a>(
+        &self,
+        base_dir: &'a Path,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<FileOp>>> + Send + 'a>> {
+        Box::pin(plan_inner(base_dir))
     }
 }
 
-async fn run_inner(base_dir: &Path) -> Result<()> {
+async fn plan_inner(base_dir: &Path) -> Result<Vec<FileOp>> {
     if !base_dir.exists() {
-        return Ok(());
+        return Ok(vec![]);
     }
 
     let sessions_dir = base_dir.join("sessions");
-    std::fs::create_dir_all(&sessions_dir)?;
-
     let entries = std::fs::read_dir(base_dir)?;
+    let mut ops = vec![];
 
     for entry in entries {
         let entry = entry?;
@@ -60,8 +63,11 @@ async fn run_inner(base_dir: &Path) -> Result<()> {
             continue;
         }
 
-        std::fs::rename(&path, &target)?;
+        ops.push(FileOp::Rename {
+            from: path,
+            to: target,
+        });
     }
 
-    Ok(())
+    Ok(ops)
 }
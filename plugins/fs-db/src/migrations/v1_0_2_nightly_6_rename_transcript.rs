@@ -4,6 +4,7 @@ use std::pin::Pin;
 
 use hypr_version::Version;
 
+use super::utils::FileOp;
 use super::version_from_name;
 use crate::Result;
 use crate::version::{DetectedVersion, InferredVersion};
@@ -22,21 +23,23 @@ impl super::Migration for Migrate {
         )
     }
 
-    fn run<'a>(&self, base_dir: &'a Path) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
-        Box::pin(run_inner(base_dir))
+    fn plan<'a>(
+        &self,
+        base_dir: &'a Path,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<FileOp>>> + Send + 'a>> {
+        Box::pin(plan_inner(base_dir))
     }
 }
 
-async fn run_inner(base_dir: &Path) -> Result<()> {
-    if !base_dir.exists() {
-        return Ok(());
+async fn plan_inner(base_dir: &Path) -> Result<Vec<FileOp>> {
+    let mut ops = vec![];
+    if base_dir.exists() {
+        rename_recursively(base_dir, &mut ops)?;
     }
-
-    rename_recursively(base_dir)?;
-    Ok(())
+    Ok(ops)
 }
 
-fn rename_recursively(dir: &Path) -> Result<()> {
+fn rename_recursively(dir: &Path, ops: &mut Vec<FileOp>) -> Result<()> {
     let entries = std::fs::read_dir(dir)?;
 
     for entry in entries {
@@ -44,13 +47,16 @@ fn rename_recursively(dir: &Path) -> Result<()> {
         let path = entry.path();
 
         if path.is_dir() {
-            rename_recursively(&path)?;
+            rename_recursively(&path, ops)?;
         } else if path.file_name().and_then(|n| n.to_str()) == Some("_transcript.json") {
             let target = path.with_file_name("transcript.json");
             if target.exists() {
-                std::fs::remove_file(&path)?;
+                ops.push(FileOp::Remove { path });
             } else {
-                std::fs::rename(&path, &target)?;
+                ops.push(FileOp::Rename {
+                    from: path,
+                    to: target,
+                });
             }
         }
     }
@@ -7,7 +7,7 @@ use hypr_db_parser::{Collection, Transcript};
 use hypr_version::Version;
 use serde_json::Value;
 
-use super::utils::{FileOp, apply_ops, group_by_session_id, hints_to_json, transcript_to_json};
+use super::utils::{FileOp, group_by_session_id, hints_to_json, transcript_to_json};
 use super::version_from_name;
 use crate::Result;
 
@@ -24,26 +24,26 @@ impl super::Migration for Migrate {
         version_from_name!()
     }
 
-    fn run<'a>(&self, base_dir: &'a Path) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
-        Box::pin(run_inner(base_dir))
+    fn plan<'a>(
+        &self,
+        base_dir: &'a Path,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<FileOp>>> + Send + 'a>> {
+        Box::pin(plan_inner(base_dir))
     }
 }
 
-async fn run_inner(base_dir: &Path) -> Result<()> {
+async fn plan_inner(base_dir: &Path) -> Result<Vec<FileOp>> {
     let sqlite_path = base_dir.join("db.sqlite");
     if !sqlite_path.exists() {
-        return Ok(());
+        return Ok(vec![]);
     }
 
     let data = match try_parse_sqlite(&sqlite_path).await {
         Some(data) => data,
-        None => return Ok(()),
+        None => return Ok(vec![]),
     };
 
-    let ops = collect_repair_ops(base_dir, &data)?;
-    apply_ops(ops)?;
-
-    Ok(())
+    collect_repair_ops(base_dir, &data)
 }
 
 async fn try_parse_sqlite(path: &Path) -> Option<Collection> {
@@ -444,6 +444,7 @@ mod tests {
                 assert!(path.ends_with("transcript.json"));
                 assert!(*force);
             }
+            _ => panic!("expected a write"),
         }
     }
 }
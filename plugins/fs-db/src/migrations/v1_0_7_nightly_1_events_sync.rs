@@ -6,7 +6,7 @@ use std::pin::Pin;
 use hypr_version::Version;
 use serde_json::Value;
 
-use super::utils::{FileOp, apply_ops};
+use super::utils::FileOp;
 use super::version_from_name;
 use crate::Result;
 
@@ -17,12 +17,15 @@ impl super::Migration for Migrate {
         version_from_name!()
     }
 
-    fn run<'a>(&self, base_dir: &'a Path) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
-        Box::pin(run_inner(base_dir))
+    fn plan<'a>(
+        &self,
+        base_dir: &'a Path,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<FileOp>>> + Send + 'a>> {
+        Box::pin(plan_inner(base_dir))
     }
 }
 
-async fn run_inner(base_dir: &Path) -> Result<()> {
+async fn plan_inner(base_dir: &Path) -> Result<Vec<FileOp>> {
     let events = load_events(base_dir);
     let timezone = load_timezone(base_dir);
     let mut ops = Vec::new();
@@ -33,8 +36,7 @@ async fn run_inner(base_dir: &Path) -> Result<()> {
     clean_events_json(base_dir, &mut ops);
     migrate_store_values(base_dir, &ignored, &mut ops);
 
-    apply_ops(ops)?;
-    Ok(())
+    Ok(ops)
 }
 
 // events.json
@@ -494,6 +496,7 @@ fn migrate_ignored_recurring_series(tb_obj: &mut serde_json::Map<String, Value>)
 
 #[cfg(test)]
 mod tests {
+    use super::super::utils::apply_ops;
     use super::*;
     use tempfile::tempdir;
 
@@ -22,6 +22,9 @@ const COMMANDS: &[&str] = &[
     "attachment_save",
     "attachment_list",
     "attachment_remove",
+    "attachment_gc",
+    "check_vault",
+    "repair_vault",
 ];
 
 fn main() {
@@ -210,6 +210,30 @@ export const commands = {
       else return { status: "error", error: e as any, };
     }
   },
+  async attachmentGc(): Promise<Result<number, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("plugin:fs-sync|attachment_gc"), };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any, };
+    }
+  },
+  async checkVault(): Promise<Result<VaultIssue[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("plugin:fs-sync|check_vault"), };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any, };
+    }
+  },
+  async repairVault(repairs: VaultRepair[],): Promise<Result<VaultRepairResult, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("plugin:fs-sync|repair_vault", { repairs, },), };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any, };
+    }
+  },
 };
 
 /** user-defined events **/
@@ -218,7 +242,17 @@ export const commands = {
 
 /** user-defined types **/
 
-export type AttachmentInfo = { attachmentId: string; path: string; extension: string; modifiedAt: string; };
+export type AttachmentInfo = {
+  attachmentId: string;
+  filename: string;
+  path: string;
+  extension: string;
+  modifiedAt: string;
+  size: number;
+  mimeType: string | null;
+  width: number | null;
+  height: number | null;
+};
 export type AttachmentSaveResult = { path: string; attachmentId: string; };
 export type CleanupTarget = { type: "files"; subdir: string; extension: string; } | {
   type: "dirs";
@@ -283,6 +317,28 @@ export type TranscriptSpeakerHint = {
   endWordId: string;
 };
 export type TranscriptWord = { id: string | null; text: string; startMs: number; endMs: number; channel: number; };
+export type VaultIssue = {
+  kind: VaultIssueKind;
+  path: string;
+  sessionId: string | null;
+  message: string;
+  repair: VaultRepair | null;
+};
+export type VaultIssueKind =
+  | "orphanedAttachment"
+  | "speakerHintMismatch"
+  | "duplicateSession"
+  | "unreadableJson"
+  | "danglingEventRef"
+  | "missingAttachmentBlob"
+  | "unreferencedBlob";
+export type VaultRepair = { type: "quarantine"; path: string; } | {
+  type: "dropSpeakerHints";
+  path: string;
+  transcript: string;
+  hints: number[];
+} | { type: "clearEventId"; path: string; };
+export type VaultRepairResult = { applied: VaultRepair[]; stale: VaultRepair[]; };
 
 /** tauri-specta globals **/
 
@@ -0,0 +1,503 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::cleanup::remove_orphan_files;
+use crate::types::{AttachmentInfo, AttachmentSaveResult};
+
+/// Vault-level directory holding one blob per distinct attachment, named by its SHA-256.
+pub const BLOBS_DIR: &str = "attachment-blobs";
+/// Per-session list of the blobs the session has attached.
+pub(crate) const MANIFEST_FILE: &str = "attachments.json";
+/// Sessions from before the blob store keep their files here.
+const LEGACY_DIR: &str = "attachments";
+/// Blobs this fresh are never collected, so a save racing a collection keeps its blob.
+const GC_GRACE_PERIOD: Duration = Duration::from_secs(10 * 60);
+const SNIFF_LEN: u64 = 8192;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ManifestEntry {
+    filename: String,
+    added_at: String,
+}
+
+type Manifest = BTreeMap<String, ManifestEntry>;
+
+pub fn save(
+    base_dir: &Path,
+    session_dir: &Path,
+    data: &[u8],
+    filename: &str,
+) -> Result<AttachmentSaveResult, crate::Error> {
+    let filename = sanitize_filename(filename)?;
+    let hash = format!("{:x}", Sha256::digest(data));
+    let extension = infer::get(data)
+        .map(|kind| kind.extension().to_string())
+        .or_else(|| {
+            Path::new(&filename)
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(|e| e.to_ascii_lowercase())
+        });
+    let attachment_id = match extension {
+        Some(ext) if !ext.is_empty() => format!("{hash}.{ext}"),
+        _ => hash.clone(),
+    };
+
+    let blobs_dir = base_dir.join(BLOBS_DIR);
+    std::fs::create_dir_all(&blobs_dir)?;
+    let path = blobs_dir.join(&attachment_id);
+
+    if holds_blob(&path, data.len(), &hash) {
+        // Same contents are already stored; refresh the mtime so a concurrent
+        // collection treats the blob as freshly saved.
+        std::fs::File::options()
+            .write(true)
+            .open(&path)?
+            .set_modified(SystemTime::now())?;
+    } else {
+        if path.exists() {
+            tracing::warn!(attachment_id = %attachment_id, "attachment_blob_corrupt_rewriting");
+        }
+        write_blob(&blobs_dir, &path, data)?;
+    }
+
+    let mut manifest = read_manifest(session_dir);
+    manifest
+        .entry(attachment_id.clone())
+        .or_insert_with(|| ManifestEntry {
+            filename,
+            added_at: now(),
+        });
+    write_manifest(session_dir, &manifest)?;
+
+    Ok(AttachmentSaveResult {
+        path: path.to_string_lossy().to_string(),
+        attachment_id,
+    })
+}
+
+/// Whether `path` already holds exactly these contents. A blob cut short by a crash in an
+/// older version fails the length check, and anything else that differs fails the hash.
+fn holds_blob(path: &Path, len: usize, hash: &str) -> bool {
+    std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.len() == len as u64)
+        && std::fs::read(path)
+            .is_ok_and(|existing| format!("{:x}", Sha256::digest(existing)) == hash)
+}
+
+/// Writes and syncs the blob under a temporary name, then renames it into place, so the final
+/// name never points at a partial file.
+fn write_blob(blobs_dir: &Path, path: &Path, data: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let tmp_path = blobs_dir.join(format!(".{}.tmp", uuid::Uuid::new_v4()));
+    let result = std::fs::File::create_new(&tmp_path)
+        .and_then(|mut file| {
+            file.write_all(data)?;
+            file.sync_all()
+        })
+        .and_then(|()| std::fs::rename(&tmp_path, path));
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+pub fn list(base_dir: &Path, session_dir: &Path) -> Result<Vec<AttachmentInfo>, crate::Error> {
+    let mut attachments = Vec::new();
+    for (attachment_id, entry) in read_manifest(session_dir) {
+        let path = base_dir.join(BLOBS_DIR).join(&attachment_id);
+        let Ok(metadata) = std::fs::metadata(&path) else {
+            tracing::warn!(attachment_id = %attachment_id, "attachment_blob_missing");
+            continue;
+        };
+        attachments.push(describe(
+            attachment_id,
+            entry.filename,
+            path,
+            metadata.len(),
+            entry.added_at,
+        ));
+    }
+
+    let entries = match std::fs::read_dir(session_dir.join(LEGACY_DIR)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(attachments),
+        Err(e) => return Err(e.into()),
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        let filename = match path.file_name().and_then(|s| s.to_str()) {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let modified_at = metadata
+            .modified()
+            .map(|t| {
+                chrono::DateTime::<chrono::Utc>::from(t)
+                    .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+            })
+            .unwrap_or_default();
+
+        attachments.push(describe(
+            filename.clone(),
+            filename,
+            path,
+            metadata.len(),
+            modified_at,
+        ));
+    }
+
+    Ok(attachments)
+}
+
+/// Detaches an attachment from the session. Blobs stay on disk until `collect_garbage` finds
+/// nothing referencing them; legacy per-session files are deleted right away.
+pub fn remove(session_dir: &Path, attachment_id: &str) -> Result<(), crate::Error> {
+    let mut manifest = read_manifest(session_dir);
+    if manifest.remove(attachment_id).is_some() {
+        return write_manifest(session_dir, &manifest);
+    }
+
+    let entries = match std::fs::read_dir(session_dir.join(LEGACY_DIR)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_file() && path.file_name().and_then(|s| s.to_str()) == Some(attachment_id) {
+            std::fs::remove_file(&path)?;
+            return Ok(());
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes blobs that no session manifest and no markdown file references.
+pub fn collect_garbage(base_dir: &Path) -> std::io::Result<u32> {
+    remove_orphan_files(&base_dir.join(BLOBS_DIR), garbage_filter(base_dir))
+}
+
+/// The blobs `collect_garbage` would remove.
+pub(crate) fn unreferenced_blobs(base_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(base_dir.join(BLOBS_DIR)) else {
+        return Vec::new();
+    };
+    let is_garbage = garbage_filter(base_dir);
+
+    let mut blobs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && is_garbage(path))
+        .collect();
+    blobs.sort();
+    blobs
+}
+
+/// Ids in the session's manifest whose blob is not in the blob store.
+pub(crate) fn missing_blobs(base_dir: &Path, session_dir: &Path) -> Vec<String> {
+    read_manifest(session_dir)
+        .into_keys()
+        .filter(|id| !base_dir.join(BLOBS_DIR).join(id).is_file())
+        .collect()
+}
+
+/// Matches blobs that nothing references and that are older than the grace period.
+fn garbage_filter(base_dir: &Path) -> impl Fn(&Path) -> bool {
+    let ref_counts = reference_counts(base_dir);
+    let cutoff = SystemTime::now() - GC_GRACE_PERIOD;
+
+    move |path| {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            return false;
+        };
+        if !is_blob_name(name) || ref_counts.contains_key(blob_hash(name)) {
+            return false;
+        }
+        std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .is_ok_and(|modified| modified < cutoff)
+    }
+}
+
+/// How many session manifests and markdown files reference each blob, keyed by hash. This walks
+/// the whole vault, so only garbage collection and the vault check do it.
+fn reference_counts(base_dir: &Path) -> HashMap<String, u32> {
+    let mut counts = HashMap::new();
+    count_references(base_dir, base_dir, &mut counts);
+    counts
+}
+
+fn count_references(base_dir: &Path, dir: &Path, counts: &mut HashMap<String, u32>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+
+        if path.is_dir() {
+            if !name.starts_with('.') && path != base_dir.join(BLOBS_DIR) {
+                count_references(base_dir, &path, counts);
+            }
+            continue;
+        }
+
+        let hashes: HashSet<String> = if name == MANIFEST_FILE {
+            read_manifest(dir)
+                .keys()
+                .map(|id| blob_hash(id).to_string())
+                .collect()
+        } else if name.ends_with(".md") {
+            match std::fs::read_to_string(&path) {
+                Ok(content) => linked_hashes(&content),
+                Err(_) => continue,
+            }
+        } else {
+            continue;
+        };
+
+        for hash in hashes {
+            *counts.entry(hash).or_default() += 1;
+        }
+    }
+}
+
+/// Blob hashes linked from markdown, whether the link is relative or an absolute path.
+fn linked_hashes(content: &str) -> HashSet<String> {
+    let prefix = format!("{BLOBS_DIR}/");
+    content
+        .match_indices(&prefix)
+        .filter_map(|(i, _)| {
+            let rest = &content[i + prefix.len()..];
+            let end = rest
+                .find(|c: char| !c.is_ascii_hexdigit())
+                .unwrap_or(rest.len());
+            let hash = &rest[..end];
+            is_blob_name(hash).then(|| hash.to_string())
+        })
+        .collect()
+}
+
+fn blob_hash(name: &str) -> &str {
+    name.split_once('.').map_or(name, |(hash, _)| hash)
+}
+
+fn is_blob_name(name: &str) -> bool {
+    let hash = blob_hash(name);
+    hash.len() == 64 && hash.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+fn describe(
+    attachment_id: String,
+    filename: String,
+    path: PathBuf,
+    size: u64,
+    modified_at: String,
+) -> AttachmentInfo {
+    let mut header = Vec::new();
+    if let Ok(file) = std::fs::File::open(&path) {
+        let _ = file.take(SNIFF_LEN).read_to_end(&mut header);
+    }
+
+    let mime_type = infer::get(&header).map(|kind| kind.mime_type().to_string());
+    let dimensions = mime_type
+        .as_deref()
+        .filter(|mime| mime.starts_with("image/"))
+        .and_then(|_| imagesize::blob_size(&header).ok());
+
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_string();
+
+    AttachmentInfo {
+        attachment_id,
+        filename,
+        path: path.to_string_lossy().to_string(),
+        extension,
+        modified_at,
+        size,
+        mime_type,
+        width: dimensions.as_ref().map(|d| d.width as u32),
+        height: dimensions.as_ref().map(|d| d.height as u32),
+    }
+}
+
+fn read_manifest(session_dir: &Path) -> Manifest {
+    std::fs::read_to_string(session_dir.join(MANIFEST_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_manifest(session_dir: &Path, manifest: &Manifest) -> Result<(), crate::Error> {
+    std::fs::create_dir_all(session_dir)?;
+    let content = serde_json::to_string_pretty(manifest).map_err(std::io::Error::other)?;
+    std::fs::write(session_dir.join(MANIFEST_FILE), content)?;
+    Ok(())
+}
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+fn sanitize_filename(filename: &str) -> Result<String, crate::Error> {
+    let path = std::path::Path::new(filename);
+
+    let clean_name = path.file_name().and_then(|n| n.to_str()).ok_or_else(|| {
+        crate::Error::from(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Invalid filename",
+        ))
+    })?;
+
+    if clean_name.is_empty() || clean_name.contains(['/', '\\', '\0']) {
+        return Err(crate::Error::from(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Invalid filename characters",
+        )));
+    }
+
+    Ok(clean_name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{TestEnv, UUID_1, UUID_2};
+    use assert_fs::prelude::*;
+    use predicates::prelude::*;
+
+    // 2x3 transparent PNG.
+    const PNG: &[u8] = &[
+        0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44,
+        0x52, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x03, 0x08, 0x06, 0x00, 0x00, 0x00, 0x9d,
+        0x74, 0x66, 0x1a, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+    ];
+
+    fn age(path: &Path) {
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::now() - GC_GRACE_PERIOD * 2)
+            .unwrap();
+    }
+
+    #[test]
+    fn save_deduplicates_across_sessions() {
+        let env = TestEnv::new()
+            .session(UUID_1)
+            .done()
+            .session(UUID_2)
+            .done()
+            .build();
+
+        let first = save(env.path(), &env.session_path(UUID_1), PNG, "shot.png").unwrap();
+        let second = save(env.path(), &env.session_path(UUID_2), PNG, "copy.PNG").unwrap();
+        assert_eq!(first.attachment_id, second.attachment_id);
+        assert_eq!(first.path, second.path);
+        assert!(is_blob_name(&first.attachment_id));
+        assert!(first.attachment_id.ends_with(".png"));
+        assert_eq!(
+            std::fs::read_dir(env.path().join(BLOBS_DIR))
+                .unwrap()
+                .count(),
+            1
+        );
+
+        let listed = list(env.path(), &env.session_path(UUID_2)).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].filename, "copy.PNG");
+        assert_eq!(listed[0].mime_type.as_deref(), Some("image/png"));
+        assert_eq!((listed[0].width, listed[0].height), (Some(2), Some(3)));
+        assert_eq!(
+            reference_counts(env.path()).get(blob_hash(&first.attachment_id)),
+            Some(&2)
+        );
+    }
+
+    #[test]
+    fn save_rewrites_a_truncated_blob() {
+        let env = TestEnv::new().session(UUID_1).done().build();
+
+        let saved = save(env.path(), &env.session_path(UUID_1), PNG, "shot.png").unwrap();
+        std::fs::write(&saved.path, &PNG[..8]).unwrap();
+
+        let again = save(env.path(), &env.session_path(UUID_1), PNG, "shot.png").unwrap();
+        assert_eq!(again.path, saved.path);
+        assert_eq!(std::fs::read(&saved.path).unwrap(), PNG);
+        assert_eq!(
+            std::fs::read_dir(env.path().join(BLOBS_DIR))
+                .unwrap()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn list_includes_legacy_files() {
+        let env = TestEnv::new().session(UUID_1).done().build();
+        env.child(&format!("{UUID_1}/attachments/notes.txt"))
+            .write_str("hello")
+            .unwrap();
+
+        let listed = list(env.path(), &env.session_path(UUID_1)).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].attachment_id, "notes.txt");
+        assert_eq!(listed[0].size, 5);
+        assert_eq!(listed[0].mime_type, None);
+
+        remove(&env.session_path(UUID_1), "notes.txt").unwrap();
+        env.child(&format!("{UUID_1}/attachments/notes.txt"))
+            .assert(predicate::path::missing());
+    }
+
+    #[test]
+    fn garbage_collection_keeps_referenced_blobs() {
+        let env = TestEnv::new()
+            .session(UUID_1)
+            .done()
+            .session(UUID_2)
+            .done()
+            .build();
+
+        let linked = save(env.path(), &env.session_path(UUID_1), PNG, "a.png").unwrap();
+        let detached = save(env.path(), &env.session_path(UUID_2), b"bytes", "b.bin").unwrap();
+        env.child(&format!("{UUID_2}/_memo.md"))
+            .write_str(&format!("![a]({})\n", linked.path))
+            .unwrap();
+        remove(&env.session_path(UUID_1), &linked.attachment_id).unwrap();
+        remove(&env.session_path(UUID_2), &detached.attachment_id).unwrap();
+        age(Path::new(&linked.path));
+
+        assert_eq!(collect_garbage(env.path()).unwrap(), 0);
+
+        age(Path::new(&detached.path));
+        assert_eq!(collect_garbage(env.path()).unwrap(), 1);
+        assert!(Path::new(&linked.path).exists());
+        assert!(!Path::new(&detached.path).exists());
+    }
+}
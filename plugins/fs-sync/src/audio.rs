@@ -1,16 +1,18 @@
-use std::fs::{File, copy, remove_file, rename, write};
+use std::fs::{copy, remove_file, rename, write};
 use std::io::ErrorKind;
-use std::num::{NonZeroU8, NonZeroU32};
+use std::num::NonZeroU32;
 use std::path::{Path, PathBuf};
 
 use hypr_audio_utils::{
-    Source, VorbisEncodeSettings, encode_vorbis_mono, mix_down_to_mono, resample_audio,
+    VorbisEncodeSettings, chunk_size_for_stt, decode_resampled, encode_vorbis_mono,
 };
 
 use crate::error::{AudioImportError, AudioProcessingError};
 
 const TARGET_SAMPLE_RATE_HZ: u32 = 16_000;
-const AUDIO_FORMATS: [&str; 2] = ["audio.wav", "audio.ogg"];
+const AUDIO_FORMATS: [&str; 3] = ["audio.wav", "audio.ogg", "audio.opus"];
+/// In-progress Opus recording segments, written by the listener until the session finalizes.
+const AUDIO_SEGMENTS_DIR: &str = "audio.segments";
 
 pub fn exists(session_dir: &Path) -> std::io::Result<bool> {
     AUDIO_FORMATS
@@ -28,6 +30,10 @@ pub fn delete(session_dir: &Path) -> std::io::Result<()> {
             std::fs::remove_file(&path)?;
         }
     }
+    let segments = session_dir.join(AUDIO_SEGMENTS_DIR);
+    if std::fs::exists(&segments).unwrap_or(false) {
+        std::fs::remove_dir_all(&segments)?;
+    }
     Ok(())
 }
 
@@ -67,51 +73,39 @@ pub fn import_audio(
     tmp_path: &Path,
     target_path: &Path,
 ) -> Result<PathBuf, AudioProcessingError> {
-    let file = File::open(source_path)?;
-    match rodio::Decoder::try_from(file) {
-        Ok(decoder) => import_audio_from_decoder(decoder, tmp_path, target_path),
-        Err(_original_err) => {
-            #[cfg(target_os = "macos")]
-            {
-                let wav_path = hypr_afconvert::to_wav(source_path)
-                    .map_err(|e| AudioProcessingError::AfconvertFailed(e.to_string()))?;
-                let result = (|| {
-                    let file = File::open(&wav_path)?;
-                    let decoder = rodio::Decoder::try_from(file)?;
-                    import_audio_from_decoder(decoder, tmp_path, target_path)
-                })();
-                let _ = std::fs::remove_file(&wav_path);
-                result
-            }
-            #[cfg(not(target_os = "macos"))]
-            {
-                Err(_original_err.into())
-            }
+    match decode_mono(source_path) {
+        Ok(samples) => write_session_audio(samples, tmp_path, target_path),
+        #[cfg(target_os = "macos")]
+        Err(AudioProcessingError::AudioUtils(
+            hypr_audio_utils::Error::UnsupportedCodec { .. }
+            | hypr_audio_utils::Error::UnsupportedContainer { .. },
+        )) => {
+            let wav_path = hypr_afconvert::to_wav(source_path)
+                .map_err(|e| AudioProcessingError::AfconvertFailed(e.to_string()))?;
+            let result = decode_mono(&wav_path)
+                .and_then(|samples| write_session_audio(samples, tmp_path, target_path));
+            let _ = std::fs::remove_file(&wav_path);
+            result
         }
+        Err(error) => Err(error),
+    }
+}
+
+/// Demuxes any supported container and resamples to the session rate while decoding.
+fn decode_mono(source_path: &Path) -> Result<Vec<f32>, AudioProcessingError> {
+    let chunk_frames = chunk_size_for_stt(TARGET_SAMPLE_RATE_HZ);
+    let mut samples = Vec::new();
+    for chunk in decode_resampled(source_path, TARGET_SAMPLE_RATE_HZ, chunk_frames)? {
+        samples.extend(chunk?);
     }
+    Ok(samples)
 }
 
-fn import_audio_from_decoder<R: std::io::Read + std::io::Seek>(
-    decoder: rodio::Decoder<R>,
+fn write_session_audio(
+    mono_samples: Vec<f32>,
     tmp_path: &Path,
     target_path: &Path,
 ) -> Result<PathBuf, AudioProcessingError> {
-    let channel_count_raw = decoder.channels().max(1);
-    let channel_count_u8 = u8::try_from(channel_count_raw).map_err(|_| {
-        AudioProcessingError::UnsupportedChannelCount {
-            count: channel_count_raw,
-        }
-    })?;
-    let channel_count =
-        NonZeroU8::new(channel_count_u8).ok_or(AudioProcessingError::InvalidChannelCount)?;
-
-    let samples = resample_audio(decoder, TARGET_SAMPLE_RATE_HZ)?;
-    let mono_samples = if channel_count.get() > 1 {
-        mix_down_to_mono(&samples, channel_count)
-    } else {
-        samples
-    };
-
     if mono_samples.is_empty() {
         return Err(AudioProcessingError::EmptyInput);
     }
@@ -201,5 +195,21 @@ mod tests {
         test_import_aac: hypr_data::english_1::AUDIO_AAC_PATH,
         test_import_aiff: hypr_data::english_1::AUDIO_AIFF_PATH,
         test_import_caf: hypr_data::english_1::AUDIO_CAF_PATH,
+        test_import_opus: hypr_data::english_1::AUDIO_OPUS_PATH,
+        test_import_webm: hypr_data::english_1::AUDIO_WEBM_PATH,
+    }
+
+    #[test]
+    fn test_import_unsupported_container() {
+        let temp = TempDir::new().unwrap();
+        let error = import_to_session(
+            temp.path(),
+            std::path::Path::new(hypr_data::english_1::TRANSCRIPTION_PATH),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            AudioImportError::UnsupportedContainer { .. }
+        ));
     }
 }
@@ -0,0 +1,628 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::attachment;
+use crate::path::{is_uuid, to_relative_path};
+use crate::session::find_session_dir;
+use crate::types::{VaultIssue, VaultIssueKind, VaultRepair, VaultRepairResult};
+
+const SESSION_META_FILE: &str = "_meta.json";
+const SESSION_TRANSCRIPT_FILE: &str = "transcript.json";
+const ATTACHMENTS_DIR: &str = "attachments";
+const EVENTS_FILE: &str = "events.json";
+const QUARANTINE_DIR: &str = ".hyprnote/quarantine";
+
+/// Looks for problems in the vault without changing anything. Issues that can be fixed carry
+/// the repair `repair_vault` would apply, so the caller can preview them first.
+pub fn check_vault(base_dir: &Path) -> Vec<VaultIssue> {
+    let mut issues = Vec::new();
+    let events = load_event_ids(base_dir, &mut issues);
+
+    let sessions_dir = base_dir.join("sessions");
+    let mut locations: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    collect_session_dirs(&sessions_dir, &mut locations);
+
+    for (session_id, dirs) in &locations {
+        let loaded = find_session_dir(&sessions_dir, session_id);
+
+        for dir in dirs.iter().filter(|dir| **dir != loaded) {
+            issues.push(VaultIssue {
+                kind: VaultIssueKind::DuplicateSession,
+                path: to_relative_path(dir, base_dir),
+                session_id: Some(session_id.clone()),
+                message: format!(
+                    "session is also stored at {}, which is the copy that gets loaded",
+                    to_relative_path(&loaded, base_dir)
+                ),
+                repair: Some(VaultRepair::Quarantine {
+                    path: to_relative_path(dir, base_dir),
+                }),
+            });
+        }
+
+        check_session(base_dir, session_id, &loaded, events.as_ref(), &mut issues);
+    }
+
+    for blob in attachment::unreferenced_blobs(base_dir) {
+        let relative_path = to_relative_path(&blob, base_dir);
+        issues.push(VaultIssue {
+            kind: VaultIssueKind::UnreferencedBlob,
+            path: relative_path.clone(),
+            session_id: None,
+            message: "no session or note references this attachment".to_string(),
+            repair: Some(VaultRepair::Quarantine {
+                path: relative_path,
+            }),
+        });
+    }
+
+    issues
+}
+
+/// Applies the previewed repairs that a fresh check still proposes. Anything that no longer
+/// matches (the file changed or the issue went away since the preview) is returned as stale
+/// and left alone.
+pub fn repair_vault(
+    base_dir: &Path,
+    repairs: &[VaultRepair],
+) -> Result<VaultRepairResult, crate::Error> {
+    let current: Vec<VaultRepair> = check_vault(base_dir)
+        .into_iter()
+        .filter_map(|issue| issue.repair)
+        .collect();
+
+    let quarantine = base_dir
+        .join(QUARANTINE_DIR)
+        .join(chrono::Utc::now().format("%Y%m%dT%H%M%S").to_string());
+
+    let mut result = VaultRepairResult {
+        applied: Vec::new(),
+        stale: Vec::new(),
+    };
+
+    for repair in repairs {
+        if !current.contains(repair) {
+            result.stale.push(repair.clone());
+            continue;
+        }
+
+        match repair {
+            VaultRepair::Quarantine { path } => {
+                let target = quarantine.join(path);
+                if let Some(parent) = target.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::rename(base_dir.join(path), &target)?;
+            }
+            VaultRepair::DropSpeakerHints {
+                path,
+                transcript,
+                hints,
+            } => {
+                update_json(&base_dir.join(path), |json| {
+                    drop_speaker_hints(json, transcript, hints)
+                })?;
+            }
+            VaultRepair::ClearEventId { path } => {
+                update_json(&base_dir.join(path), |json| {
+                    if let Some(meta) = json.as_object_mut() {
+                        meta.remove("event_id");
+                    }
+                })?;
+            }
+        }
+
+        tracing::info!(repair = ?repair, "vault_repair_applied");
+        result.applied.push(repair.clone());
+    }
+
+    Ok(result)
+}
+
+fn collect_session_dirs(dir: &Path, locations: &mut BTreeMap<String, Vec<PathBuf>>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+
+        if is_uuid(name) {
+            locations.entry(name.to_string()).or_default().push(path);
+        } else {
+            collect_session_dirs(&path, locations);
+        }
+    }
+}
+
+/// Ids from `events.json`, or `None` when there is nothing to check event references against.
+fn load_event_ids(base_dir: &Path, issues: &mut Vec<VaultIssue>) -> Option<HashSet<String>> {
+    let path = base_dir.join(EVENTS_FILE);
+    let content = std::fs::read_to_string(&path).ok()?;
+
+    match serde_json::from_str::<HashMap<String, Value>>(&content) {
+        Ok(events) => Some(events.into_keys().collect()),
+        Err(e) => {
+            issues.push(VaultIssue {
+                kind: VaultIssueKind::UnreadableJson,
+                path: EVENTS_FILE.to_string(),
+                session_id: None,
+                message: e.to_string(),
+                repair: None,
+            });
+            None
+        }
+    }
+}
+
+fn check_session(
+    base_dir: &Path,
+    session_id: &str,
+    session_dir: &Path,
+    events: Option<&HashSet<String>>,
+    issues: &mut Vec<VaultIssue>,
+) {
+    let Ok(entries) = std::fs::read_dir(session_dir) else {
+        return;
+    };
+
+    let mut notes = Vec::new();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+
+        if name.ends_with(".md") {
+            if let Ok(content) = std::fs::read_to_string(&path) {
+                notes.push(content);
+            }
+            continue;
+        }
+
+        if !name.ends_with(".json") {
+            continue;
+        }
+
+        let relative_path = to_relative_path(&path, base_dir);
+        let json = match std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str::<Value>(&content).map_err(|e| e.to_string()))
+        {
+            Ok(json) => json,
+            Err(message) => {
+                // Without `_meta.json` the session disappears, so that one is only reported.
+                let repair = (name != SESSION_META_FILE).then(|| VaultRepair::Quarantine {
+                    path: relative_path.clone(),
+                });
+                issues.push(VaultIssue {
+                    kind: VaultIssueKind::UnreadableJson,
+                    path: relative_path,
+                    session_id: Some(session_id.to_string()),
+                    message,
+                    repair,
+                });
+                continue;
+            }
+        };
+
+        if name == SESSION_META_FILE {
+            check_event_ref(&json, events, session_id, relative_path, issues);
+        } else if name == SESSION_TRANSCRIPT_FILE {
+            check_speaker_hints(&json, session_id, relative_path, issues);
+        }
+    }
+
+    check_attachments(base_dir, session_id, session_dir, &notes, issues);
+}
+
+fn check_event_ref(
+    meta: &Value,
+    events: Option<&HashSet<String>>,
+    session_id: &str,
+    path: String,
+    issues: &mut Vec<VaultIssue>,
+) {
+    let Some(events) = events else {
+        return;
+    };
+    let Some(event_id) = meta.get("event_id").and_then(|v| v.as_str()) else {
+        return;
+    };
+    if event_id.is_empty() || events.contains(event_id) {
+        return;
+    }
+
+    issues.push(VaultIssue {
+        kind: VaultIssueKind::DanglingEventRef,
+        path: path.clone(),
+        session_id: Some(session_id.to_string()),
+        message: format!("event {event_id} is not in {EVENTS_FILE}"),
+        repair: Some(VaultRepair::ClearEventId { path }),
+    });
+}
+
+fn check_speaker_hints(json: &Value, session_id: &str, path: String, issues: &mut Vec<VaultIssue>) {
+    let Some(transcripts) = json.get("transcripts").and_then(|v| v.as_array()) else {
+        return;
+    };
+
+    for transcript in transcripts {
+        let transcript_id = transcript.get("id").and_then(|v| v.as_str()).unwrap_or("");
+        let bad = mismatched_hints(transcript);
+        if bad.is_empty() {
+            continue;
+        }
+
+        issues.push(VaultIssue {
+            kind: VaultIssueKind::SpeakerHintMismatch,
+            path: path.clone(),
+            session_id: Some(session_id.to_string()),
+            message: format!(
+                "{} speaker hint(s) in transcript {transcript_id} point at words it doesn't have",
+                bad.len()
+            ),
+            repair: Some(VaultRepair::DropSpeakerHints {
+                path: path.clone(),
+                transcript: transcript_id.to_string(),
+                hints: bad,
+            }),
+        });
+    }
+}
+
+/// Indexes of hints whose word range doesn't resolve to words of the same transcript. Hints
+/// written by older versions point at a single `word_id` instead of a range.
+fn mismatched_hints(transcript: &Value) -> Vec<u32> {
+    let positions: HashMap<&str, usize> = transcript
+        .get("words")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .filter_map(|(i, word)| Some((word.get("id")?.as_str()?, i)))
+        .collect();
+
+    speaker_hints(transcript)
+        .iter()
+        .enumerate()
+        .filter(|(_, hint)| {
+            let field = |snake: &str, camel: &str| {
+                hint.get(snake)
+                    .or_else(|| hint.get(camel))
+                    .and_then(|v| v.as_str())
+            };
+
+            let range = match (
+                field("start_word_id", "startWordId"),
+                field("end_word_id", "endWordId"),
+            ) {
+                (Some(start), Some(end)) => Some((start, end)),
+                _ => field("word_id", "wordId").map(|id| (id, id)),
+            };
+
+            match range.map(|(start, end)| (positions.get(start), positions.get(end))) {
+                Some((Some(start), Some(end))) => start > end,
+                _ => true,
+            }
+        })
+        .map(|(i, _)| i as u32)
+        .collect()
+}
+
+fn speaker_hints(transcript: &Value) -> &[Value] {
+    transcript
+        .get("speaker_hints")
+        .or_else(|| transcript.get("speakerHints"))
+        .and_then(|v| v.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+}
+
+fn drop_speaker_hints(json: &mut Value, transcript_id: &str, hints: &[u32]) {
+    let Some(transcripts) = json.get_mut("transcripts").and_then(|v| v.as_array_mut()) else {
+        return;
+    };
+
+    for transcript in transcripts {
+        if transcript.get("id").and_then(|v| v.as_str()) != Some(transcript_id) {
+            continue;
+        }
+
+        let key = if transcript.get("speaker_hints").is_some() {
+            "speaker_hints"
+        } else {
+            "speakerHints"
+        };
+        if let Some(list) = transcript.get_mut(key).and_then(|v| v.as_array_mut()) {
+            let mut index = 0;
+            list.retain(|_| {
+                let keep = !hints.contains(&index);
+                index += 1;
+                keep
+            });
+        }
+    }
+}
+
+fn check_attachments(
+    base_dir: &Path,
+    session_id: &str,
+    session_dir: &Path,
+    notes: &[String],
+    issues: &mut Vec<VaultIssue>,
+) {
+    // A missing blob can't be recovered from the vault, so there is nothing to repair.
+    for attachment_id in attachment::missing_blobs(base_dir, session_dir) {
+        issues.push(VaultIssue {
+            kind: VaultIssueKind::MissingAttachmentBlob,
+            path: to_relative_path(&session_dir.join(attachment::MANIFEST_FILE), base_dir),
+            session_id: Some(session_id.to_string()),
+            message: format!(
+                "attachment {attachment_id} is listed but missing from {}",
+                attachment::BLOBS_DIR
+            ),
+            repair: None,
+        });
+    }
+
+    let Ok(entries) = std::fs::read_dir(session_dir.join(ATTACHMENTS_DIR)) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+
+        let encoded = name.replace(' ', "%20");
+        if notes
+            .iter()
+            .any(|note| note.contains(name) || note.contains(&encoded))
+        {
+            continue;
+        }
+
+        let relative_path = to_relative_path(&path, base_dir);
+        issues.push(VaultIssue {
+            kind: VaultIssueKind::OrphanedAttachment,
+            path: relative_path.clone(),
+            session_id: Some(session_id.to_string()),
+            message: "no note in the session links to this attachment".to_string(),
+            repair: Some(VaultRepair::Quarantine {
+                path: relative_path,
+            }),
+        });
+    }
+}
+
+fn update_json(path: &Path, update: impl FnOnce(&mut Value)) -> Result<(), crate::Error> {
+    let content = std::fs::read_to_string(path)?;
+    let mut json: Value = serde_json::from_str(&content).map_err(std::io::Error::other)?;
+    update(&mut json);
+    let content = crate::json::serialize(json).map_err(std::io::Error::other)?;
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{TestEnv, UUID_1, UUID_2};
+    use assert_fs::prelude::*;
+    use predicates::prelude::*;
+
+    fn kinds(issues: &[VaultIssue]) -> Vec<VaultIssueKind> {
+        let mut kinds: Vec<_> = issues.iter().map(|issue| issue.kind).collect();
+        kinds.sort_by_key(|kind| format!("{kind:?}"));
+        kinds
+    }
+
+    fn transcript(hints: Value) -> String {
+        serde_json::json!({
+            "transcripts": [{
+                "id": "t1",
+                "session_id": UUID_1,
+                "words": [{"id": "w1", "text": "hi"}, {"id": "w2", "text": "there"}],
+                "speaker_hints": hints,
+            }]
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn check_clean_vault_reports_nothing() {
+        let env = TestEnv::new()
+            .folder("sessions")
+            .session(UUID_1)
+            .memo("![](attachments/photo.png)")
+            .done_folder()
+            .done()
+            .build();
+        env.child(&format!("sessions/{UUID_1}/attachments/photo.png"))
+            .write_str("png")
+            .unwrap();
+        env.child(&format!("sessions/{UUID_1}/transcript.json"))
+            .write_str(&transcript(serde_json::json!([
+                {"id": "h1", "word_id": "w2", "type": "manual", "value": "A"},
+                {"start_word_id": "w1", "end_word_id": "w2"},
+            ])))
+            .unwrap();
+
+        assert!(check_vault(env.path()).is_empty());
+    }
+
+    #[test]
+    fn check_reports_each_kind_of_issue() {
+        let env = TestEnv::new()
+            .folder("sessions")
+            .session(UUID_1)
+            .done_folder()
+            .done()
+            .folder("sessions/work")
+            .session(UUID_1)
+            .done_folder()
+            .session(UUID_2)
+            .done_folder()
+            .done()
+            .file("events.json", r#"{"e1": {}}"#)
+            .build();
+        let loaded = find_session_dir(&env.path().join("sessions"), UUID_1);
+        std::fs::create_dir_all(loaded.join("attachments")).unwrap();
+        std::fs::write(loaded.join("attachments/stray.png"), "png").unwrap();
+        std::fs::write(
+            loaded.join("transcript.json"),
+            transcript(serde_json::json!([{"start_word_id": "w2", "end_word_id": "w9"}])),
+        )
+        .unwrap();
+        env.child(&format!("sessions/work/{UUID_2}/_meta.json"))
+            .write_str(r#"{"event_id": "gone"}"#)
+            .unwrap();
+        env.child(&format!("sessions/work/{UUID_2}/transcript.json"))
+            .write_str("{")
+            .unwrap();
+
+        let issues = check_vault(env.path());
+        assert_eq!(
+            kinds(&issues),
+            vec![
+                VaultIssueKind::DanglingEventRef,
+                VaultIssueKind::DuplicateSession,
+                VaultIssueKind::OrphanedAttachment,
+                VaultIssueKind::SpeakerHintMismatch,
+                VaultIssueKind::UnreadableJson,
+            ]
+        );
+    }
+
+    #[test]
+    fn check_reports_missing_and_unreferenced_blobs() {
+        let env = TestEnv::new()
+            .folder("sessions")
+            .session(UUID_1)
+            .done_folder()
+            .session(UUID_2)
+            .done_folder()
+            .done()
+            .build();
+        let sessions = env.path().join("sessions");
+        let missing =
+            attachment::save(env.path(), &sessions.join(UUID_1), b"gone", "gone.bin").unwrap();
+        let stray =
+            attachment::save(env.path(), &sessions.join(UUID_2), b"stray", "stray.bin").unwrap();
+        std::fs::remove_file(&missing.path).unwrap();
+        attachment::remove(&sessions.join(UUID_2), &stray.attachment_id).unwrap();
+
+        // Blobs inside the grace period may belong to a save that isn't referenced yet.
+        assert!(
+            check_vault(env.path())
+                .iter()
+                .all(|issue| issue.kind != VaultIssueKind::UnreferencedBlob)
+        );
+
+        std::fs::File::options()
+            .write(true)
+            .open(&stray.path)
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(86400))
+            .unwrap();
+
+        let issues = check_vault(env.path());
+        assert_eq!(
+            kinds(&issues),
+            vec![
+                VaultIssueKind::MissingAttachmentBlob,
+                VaultIssueKind::UnreferencedBlob,
+            ]
+        );
+        let missing_issue = issues
+            .iter()
+            .find(|issue| issue.kind == VaultIssueKind::MissingAttachmentBlob)
+            .unwrap();
+        assert_eq!(
+            missing_issue.path,
+            format!("sessions/{UUID_1}/attachments.json")
+        );
+        assert!(missing_issue.message.contains(&missing.attachment_id));
+        assert!(missing_issue.repair.is_none());
+
+        let plan: Vec<VaultRepair> = issues
+            .into_iter()
+            .filter_map(|issue| issue.repair)
+            .collect();
+        assert_eq!(
+            plan,
+            vec![VaultRepair::Quarantine {
+                path: format!("{}/{}", attachment::BLOBS_DIR, stray.attachment_id),
+            }]
+        );
+        repair_vault(env.path(), &plan).unwrap();
+        assert!(!Path::new(&stray.path).exists());
+    }
+
+    #[test]
+    fn repair_applies_previewed_plan() {
+        let env = TestEnv::new()
+            .folder("sessions")
+            .session(UUID_1)
+            .done_folder()
+            .done()
+            .file("events.json", "{}")
+            .build();
+        env.child(&format!("sessions/{UUID_1}/_meta.json"))
+            .write_str(r#"{"event_id": "gone", "title": "Standup"}"#)
+            .unwrap();
+        env.child(&format!("sessions/{UUID_1}/attachments/stray.png"))
+            .write_str("png")
+            .unwrap();
+        env.child(&format!("sessions/{UUID_1}/transcript.json"))
+            .write_str(&transcript(serde_json::json!([
+                {"word_id": "w1"},
+                {"word_id": "missing"},
+            ])))
+            .unwrap();
+
+        let plan: Vec<VaultRepair> = check_vault(env.path())
+            .into_iter()
+            .filter_map(|issue| issue.repair)
+            .collect();
+        assert_eq!(plan.len(), 3);
+
+        let result = repair_vault(env.path(), &plan).unwrap();
+        assert_eq!(result.applied.len(), 3);
+        assert!(result.stale.is_empty());
+        assert!(check_vault(env.path()).is_empty());
+
+        env.child(&format!("sessions/{UUID_1}/attachments/stray.png"))
+            .assert(predicate::path::missing());
+        let meta: Value = serde_json::from_str(
+            &std::fs::read_to_string(env.path().join(format!("sessions/{UUID_1}/_meta.json")))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(meta, serde_json::json!({"title": "Standup"}));
+
+        let again = repair_vault(env.path(), &plan).unwrap();
+        assert!(again.applied.is_empty());
+        assert_eq!(again.stale.len(), 3);
+    }
+}
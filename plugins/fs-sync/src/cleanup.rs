@@ -8,6 +8,18 @@ pub fn cleanup_files_in_dir(
     extension: &str,
     valid_ids: &HashSet<String>,
 ) -> std::io::Result<u32> {
+    remove_orphan_files(dir, |path| {
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            return false;
+        };
+        path.extension().and_then(|e| e.to_str()) == Some(extension)
+            && is_uuid(stem)
+            && !valid_ids.contains(stem)
+    })
+}
+
+/// Removes the files directly inside `dir` that `is_orphan` picks, returning how many went.
+pub fn remove_orphan_files(dir: &Path, is_orphan: impl Fn(&Path) -> bool) -> std::io::Result<u32> {
     if !dir.exists() {
         return Ok(0);
     }
@@ -16,17 +28,8 @@ pub fn cleanup_files_in_dir(
 
     let orphans: Vec<_> = std::fs::read_dir(dir)?
         .flatten()
-        .filter_map(|entry| {
-            let path = entry.path();
-            if !path.is_file() {
-                return None;
-            }
-            let stem = path.file_stem()?.to_str()?;
-            if path.extension()?.to_str()? != extension || !is_uuid(stem) {
-                return None;
-            }
-            (!valid_ids.contains(stem)).then_some(path)
-        })
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && is_orphan(path))
         .collect();
 
     let mut removed = 0;
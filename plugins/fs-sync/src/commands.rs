@@ -363,3 +363,32 @@ pub(crate) async fn attachment_remove<R: tauri::Runtime>(
             .map_err(|e| e.to_string())
     })
 }
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn attachment_gc<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<u32, String> {
+    spawn_blocking!({ app.fs_sync().attachment_gc().map_err(|e| e.to_string()) })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn check_vault<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<Vec<crate::VaultIssue>, String> {
+    spawn_blocking!({ app.fs_sync().check_vault().map_err(|e| e.to_string()) })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn repair_vault<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    repairs: Vec<crate::VaultRepair>,
+) -> Result<crate::VaultRepairResult, String> {
+    spawn_blocking!({
+        app.fs_sync()
+            .repair_vault(&repairs)
+            .map_err(|e| e.to_string())
+    })
+}
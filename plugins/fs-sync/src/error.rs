@@ -30,13 +30,7 @@ pub enum AudioProcessingError {
     #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error(transparent)]
-    Decoder(#[from] rodio::decoder::DecoderError),
-    #[error(transparent)]
     AudioUtils(#[from] hypr_audio_utils::Error),
-    #[error("audio_import_unsupported_channel_count")]
-    UnsupportedChannelCount { count: u16 },
-    #[error("audio_import_invalid_channel_count")]
-    InvalidChannelCount,
     #[error("audio_import_empty_input")]
     EmptyInput,
     #[error("audio_import_invalid_target_rate")]
@@ -50,6 +44,29 @@ pub enum AudioProcessingError {
 pub enum AudioImportError {
     #[error(transparent)]
     Io(#[from] std::io::Error),
+    #[error("audio_import_unsupported_container: {}", .extension.as_deref().unwrap_or("unknown"))]
+    UnsupportedContainer { extension: Option<String> },
+    #[error("audio_import_unsupported_codec: {codec}")]
+    UnsupportedCodec { codec: String },
+    #[error("audio_import_no_audio_track")]
+    NoAudioTrack,
     #[error(transparent)]
-    Processing(#[from] AudioProcessingError),
+    Processing(AudioProcessingError),
+}
+
+impl From<AudioProcessingError> for AudioImportError {
+    fn from(error: AudioProcessingError) -> Self {
+        use hypr_audio_utils::Error as AudioError;
+
+        match error {
+            AudioProcessingError::AudioUtils(AudioError::UnsupportedContainer { extension }) => {
+                Self::UnsupportedContainer { extension }
+            }
+            AudioProcessingError::AudioUtils(AudioError::UnsupportedCodec { codec }) => {
+                Self::UnsupportedCodec { codec }
+            }
+            AudioProcessingError::AudioUtils(AudioError::NoAudioTrack) => Self::NoAudioTrack,
+            other => Self::Processing(other),
+        }
+    }
 }
@@ -1,7 +1,9 @@
 #[cfg(test)]
 mod test_fixtures;
 
+mod attachment;
 mod audio;
+mod check;
 mod cleanup;
 mod commands;
 mod error;
@@ -51,6 +53,9 @@ fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
             commands::attachment_save::<tauri::Wry>,
             commands::attachment_list::<tauri::Wry>,
             commands::attachment_remove::<tauri::Wry>,
+            commands::attachment_gc::<tauri::Wry>,
+            commands::check_vault::<tauri::Wry>,
+            commands::repair_vault::<tauri::Wry>,
         ])
         .error_handling(tauri_specta::ErrorHandlingMode::Result)
 }
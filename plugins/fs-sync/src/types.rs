@@ -50,9 +50,59 @@ pub struct AttachmentSaveResult {
 #[serde(rename_all = "camelCase")]
 pub struct AttachmentInfo {
     pub attachment_id: String,
+    pub filename: String,
     pub path: String,
     pub extension: String,
     pub modified_at: String,
+    pub size: u64,
+    pub mime_type: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum VaultIssueKind {
+    OrphanedAttachment,
+    SpeakerHintMismatch,
+    DuplicateSession,
+    UnreadableJson,
+    DanglingEventRef,
+    MissingAttachmentBlob,
+    UnreferencedBlob,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum VaultRepair {
+    Quarantine {
+        path: String,
+    },
+    DropSpeakerHints {
+        path: String,
+        transcript: String,
+        hints: Vec<u32>,
+    },
+    ClearEventId {
+        path: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultIssue {
+    pub kind: VaultIssueKind,
+    pub path: String,
+    pub session_id: Option<String>,
+    pub message: String,
+    pub repair: Option<VaultRepair>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultRepairResult {
+    pub applied: Vec<VaultRepair>,
+    pub stale: Vec<VaultRepair>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
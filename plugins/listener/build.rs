@@ -7,6 +7,8 @@ const COMMANDS: &[&str] = &[
     "start_session",
     "stop_session",
     "get_state",
+    "get_supervision_tree",
+    "get_pipeline_latencies",
     "run_batch",
     "is_supported_languages_live",
     "suggest_providers_for_languages_live",
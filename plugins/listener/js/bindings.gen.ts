@@ -61,6 +61,22 @@ export const commands = {
       else return { status: "error", error: e as any, };
     }
   },
+  async getSupervisionTree(): Promise<Result<SupervisorTree | null, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("plugin:listener|get_supervision_tree",), };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any, };
+    }
+  },
+  async getPipelineLatencies(): Promise<Result<StageLatency[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("plugin:listener|get_pipeline_latencies",), };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any, };
+    }
+  },
   async isSupportedLanguagesLive(
     provider: string,
     model: string | null,
@@ -115,16 +131,95 @@ export const events = __makeEvents__<{
 
 /** user-defined types **/
 
+export type ChildInfo = {
+  id: string;
+  policy: RestartPolicy;
+  status: ChildStatus;
+  /**
+   * Restarts since the supervisor started, not just within the current budget window.
+   */
+  restart_count: number;
+  last_exit_reason: string | null;
+  uptime_ms: number | null;
+};
+export type ChildStatus =
+  | "running"
+  /**
+   * Exited and waiting to be spawned again.
+   */
+  | "restarting"
+  | "stopped";
 export type DegradedError =
   | { type: "authentication_failed"; provider: string; }
   | { type: "upstream_unavailable"; message: string; }
   | { type: "connection_timeout"; }
   | { type: "stream_error"; message: string; };
+/**
+ * Periodic spoken-language identification on the captured audio, using a local whisper model.
+ */
+export type LanguageIdConfig = {
+  /**
+   * Seconds of audio the first detection pass looks at.
+   */
+  initial_secs: number;
+  /**
+   * Seconds between later passes.
+   */
+  interval_secs: number;
+  /**
+   * Seconds of the most recent audio each later pass looks at.
+   */
+  window_secs: number;
+  /**
+   * Probability the top language needs before it replaces the current one.
+   */
+  min_probability: number;
+};
+/**
+ * Which processing stages run between capture and the listener/recorder, chosen per session.
+ */
+export type PipelineConfig = {
+  aec: boolean;
+  high_pass_hz: number | null;
+  /**
+   * Chunks quieter than this (dBFS) are silenced on both channels.
+   */
+  noise_gate_db: number | null;
+  /**
+   * Mic masking outside of detected speech; `None` passes the mic through untouched.
+   */
+  vad_mask: VadMaskConfig | null;
+  normalize: boolean;
+  output_sample_rate: number;
+  /**
+   * Spoken-language identification on the raw capture; `None` disables it.
+   */
+  language_id: LanguageIdConfig | null;
+};
+/**
+ * How the session recording is stored on disk.
+ */
+export type RecordingFormat =
+  /**
+   * One float WAV per session (`audio.wav`).
+   */
+  | "wav"
+  /**
+   * Rolling Ogg Opus segments, joined into `audio.opus` when the session ends.
+   */
+  | "opus_segments";
+export type RestartPolicy = "permanent" | "transient" | "temporary";
 export type SessionDataEvent = { type: "audio_amplitude"; session_id: string; mic: number; speaker: number; } | {
   type: "mic_muted";
   session_id: string;
   value: boolean;
-} | { type: "stream_response"; session_id: string; response: StreamResponse; };
+} | { type: "stream_response"; session_id: string; response: StreamResponse; } | {
+  type: "language_detected";
+  session_id: string;
+  language: string;
+  probability: number;
+  previous: string | null;
+};
 export type SessionErrorEvent = {
   type: "audio_error";
   session_id: string;
@@ -146,12 +241,15 @@ export type SessionParams = {
   base_url: string;
   api_key: string;
   keywords: string[];
+  pipeline?: PipelineConfig;
+  recording_format?: RecordingFormat;
 };
 export type SessionProgressEvent =
   | { type: "audio_initializing"; session_id: string; }
   | { type: "audio_ready"; session_id: string; device: string | null; }
   | { type: "connecting"; session_id: string; }
   | { type: "connected"; session_id: string; adapter: string; };
+export type StageLatency = { stage: string; last_us: number; avg_us: number; max_us: number; };
 export type State = "active" | "inactive" | "finalizing";
 export type StreamAlternatives = { transcript: string; words: StreamWord[]; confidence: number; languages?: string[]; };
 export type StreamChannel = { alternatives: StreamAlternatives[]; };
@@ -188,6 +286,18 @@ export type StreamWord = {
   punctuated_word: string | null;
   language: string | null;
 };
+/**
+ * A supervisor and its children, as shown in the diagnostics view.
+ */
+export type SupervisorTree = { name: string; children: ChildInfo[]; };
+export type VadMaskConfig = {
+  hangover_frames: number;
+  amplitude_floor: number;
+  /**
+   * Speech probability at or above which a frame is kept.
+   */
+  speech_threshold: number;
+};
 
 /** tauri-specta globals **/
 
@@ -118,7 +118,7 @@ fn build_listen_params(args: &ListenerArgs) -> owhisper_interface::ListenParams
     owhisper_interface::ListenParams {
         model: Some(args.model.clone()),
         languages: args.languages.clone(),
-        sample_rate: super::super::SAMPLE_RATE,
+        sample_rate: args.sample_rate,
         keywords: args.keywords.clone(),
         custom_query: Some(std::collections::HashMap::from([(
             "redemption_time_ms".to_string(),
@@ -1,3 +1,5 @@
+mod segments;
+
 use std::fs::File;
 use std::io::BufWriter;
 use std::path::PathBuf;
@@ -11,8 +13,24 @@ use hypr_audio_utils::{
 use ractor::{Actor, ActorName, ActorProcessingErr, ActorRef};
 use tauri_plugin_fs_sync::find_session_dir;
 
+use segments::SegmentedRecording;
+pub use segments::{OPUS_FILENAME, SEGMENTS_DIR};
+
 const FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(1000);
 
+/// How the session recording is stored on disk.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, specta::Type,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingFormat {
+    /// One float WAV per session (`audio.wav`).
+    #[default]
+    Wav,
+    /// Rolling Ogg Opus segments, joined into `audio.opus` when the session ends.
+    OpusSegments,
+}
+
 pub enum RecMsg {
     AudioSingle(Arc<[f32]>),
     AudioDual(Arc<[f32]>, Arc<[f32]>),
@@ -21,10 +39,13 @@ pub enum RecMsg {
 pub struct RecArgs {
     pub app_dir: PathBuf,
     pub session_id: String,
+    pub sample_rate: u32,
+    pub format: RecordingFormat,
 }
 
 pub struct RecState {
     writer: Option<hound::WavWriter<BufWriter<File>>>,
+    opus: Option<SegmentedRecording>,
     writer_mic: Option<hound::WavWriter<BufWriter<File>>>,
     writer_spk: Option<hound::WavWriter<BufWriter<File>>>,
     wav_path: PathBuf,
@@ -58,44 +79,29 @@ impl Actor for RecorderActor {
         let wav_path = dir.join(format!("{}.wav", filename_base));
         let ogg_path = dir.join(format!("{}.ogg", filename_base));
 
-        let is_stereo = if ogg_path.exists() {
-            let has_identical = ogg_has_identical_channels(&ogg_path).map_err(into_actor_err)?;
-            if has_identical {
-                decode_vorbis_to_mono_wav_file(&ogg_path, &wav_path).map_err(into_actor_err)?;
-            } else {
-                decode_vorbis_to_wav_file(&ogg_path, &wav_path).map_err(into_actor_err)?;
-            }
-            std::fs::remove_file(&ogg_path)?;
-            !has_identical
-        } else if wav_path.exists() {
-            let reader = hound::WavReader::open(&wav_path)?;
-            reader.spec().channels == 2
+        let (writer, opus, is_stereo) = if args.format == RecordingFormat::OpusSegments
+            && !wav_path.exists()
+            && !ogg_path.exists()
+        {
+            let opus =
+                SegmentedRecording::open(&dir, args.sample_rate, true).map_err(into_actor_err)?;
+            let is_stereo = opus.is_stereo();
+            (None, Some(opus), is_stereo)
         } else {
-            true
-        };
-
-        let stereo_spec = hound::WavSpec {
-            channels: 2,
-            sample_rate: super::SAMPLE_RATE,
-            bits_per_sample: 32,
-            sample_format: hound::SampleFormat::Float,
+            if args.format == RecordingFormat::OpusSegments {
+                tracing::info!("session_has_wav_recording_keeping_wav_format");
+            }
+            let (writer, is_stereo) = open_wav_writer(&wav_path, &ogg_path, args.sample_rate)?;
+            (Some(writer), None, is_stereo)
         };
 
         let mono_spec = hound::WavSpec {
             channels: 1,
-            sample_rate: super::SAMPLE_RATE,
+            sample_rate: args.sample_rate,
             bits_per_sample: 32,
             sample_format: hound::SampleFormat::Float,
         };
 
-        let writer = if wav_path.exists() {
-            hound::WavWriter::append(&wav_path)?
-        } else if is_stereo {
-            hound::WavWriter::create(&wav_path, stereo_spec)?
-        } else {
-            hound::WavWriter::create(&wav_path, mono_spec)?
-        };
-
         let (writer_mic, writer_spk) = if is_debug_mode() {
             let mic_path = dir.join(format!("{}_mic.wav", filename_base));
             let spk_path = dir.join(format!("{}_spk.wav", filename_base));
@@ -118,7 +124,8 @@ impl Actor for RecorderActor {
         };
 
         Ok(RecState {
-            writer: Some(writer),
+            writer,
+            opus,
             writer_mic,
             writer_spk,
             wav_path,
@@ -135,6 +142,14 @@ impl Actor for RecorderActor {
     ) -> Result<(), ActorProcessingErr> {
         match msg {
             RecMsg::AudioSingle(samples) => {
+                if let Some(ref mut opus) = st.opus {
+                    if st.is_stereo {
+                        let stereo: Vec<f32> = samples.iter().flat_map(|s| [*s, *s]).collect();
+                        opus.write(&stereo).map_err(into_actor_err)?;
+                    } else {
+                        opus.write(&samples).map_err(into_actor_err)?;
+                    }
+                }
                 if let Some(ref mut writer) = st.writer {
                     if st.is_stereo {
                         write_mono_as_stereo(writer, &samples)?;
@@ -144,6 +159,15 @@ impl Actor for RecorderActor {
                 }
             }
             RecMsg::AudioDual(mic, spk) => {
+                if let Some(ref mut opus) = st.opus {
+                    if st.is_stereo {
+                        opus.write(&interleave_stereo(&mic, &spk))
+                            .map_err(into_actor_err)?;
+                    } else {
+                        opus.write(&mix_audio_f32(&mic, &spk))
+                            .map_err(into_actor_err)?;
+                    }
+                }
                 if let Some(ref mut writer) = st.writer {
                     if st.is_stereo {
                         write_interleaved_stereo(writer, &mic, &spk)?;
@@ -173,6 +197,9 @@ impl Actor for RecorderActor {
         st: &mut Self::State,
     ) -> Result<(), ActorProcessingErr> {
         finalize_writer(&mut st.writer, Some(&st.wav_path))?;
+        if let Some(opus) = st.opus.take() {
+            opus.finalize().map_err(into_actor_err)?;
+        }
         finalize_writer(&mut st.writer_mic, None)?;
         finalize_writer(&mut st.writer_spk, None)?;
 
@@ -189,6 +216,53 @@ fn into_actor_err(err: hypr_audio_utils::Error) -> ActorProcessingErr {
     Box::new(err)
 }
 
+/// Opens `audio.wav` for appending, first unpacking an `audio.ogg` left by an earlier finalize.
+fn open_wav_writer(
+    wav_path: &std::path::Path,
+    ogg_path: &std::path::Path,
+    sample_rate: u32,
+) -> Result<(hound::WavWriter<BufWriter<File>>, bool), ActorProcessingErr> {
+    let is_stereo = if ogg_path.exists() {
+        let has_identical = ogg_has_identical_channels(ogg_path).map_err(into_actor_err)?;
+        if has_identical {
+            decode_vorbis_to_mono_wav_file(ogg_path, wav_path).map_err(into_actor_err)?;
+        } else {
+            decode_vorbis_to_wav_file(ogg_path, wav_path).map_err(into_actor_err)?;
+        }
+        std::fs::remove_file(ogg_path)?;
+        !has_identical
+    } else if wav_path.exists() {
+        let reader = hound::WavReader::open(wav_path)?;
+        reader.spec().channels == 2
+    } else {
+        true
+    };
+
+    let stereo_spec = hound::WavSpec {
+        channels: 2,
+        sample_rate,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+
+    let mono_spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+
+    let writer = if wav_path.exists() {
+        hound::WavWriter::append(wav_path)?
+    } else if is_stereo {
+        hound::WavWriter::create(wav_path, stereo_spec)?
+    } else {
+        hound::WavWriter::create(wav_path, mono_spec)?
+    };
+
+    Ok((writer, is_stereo))
+}
+
 fn is_debug_mode() -> bool {
     cfg!(debug_assertions)
         || std::env::var("HYPRNOTE_DEBUG")
@@ -196,17 +270,20 @@ fn is_debug_mode() -> bool {
             .unwrap_or(false)
 }
 
-fn flush_if_due(state: &mut RecState) -> Result<(), hound::Error> {
+fn flush_if_due(state: &mut RecState) -> Result<(), ActorProcessingErr> {
     if state.last_flush.elapsed() < FLUSH_INTERVAL {
         return Ok(());
     }
     flush_all(state)
 }
 
-fn flush_all(state: &mut RecState) -> Result<(), hound::Error> {
+fn flush_all(state: &mut RecState) -> Result<(), ActorProcessingErr> {
     if let Some(writer) = state.writer.as_mut() {
         writer.flush()?;
     }
+    if let Some(opus) = state.opus.as_mut() {
+        opus.flush().map_err(into_actor_err)?;
+    }
     if let Some(writer_mic) = state.writer_mic.as_mut() {
         writer_mic.flush()?;
     }
@@ -238,6 +315,18 @@ fn write_mono_as_stereo(
     Ok(())
 }
 
+fn interleave_stereo(mic: &[f32], spk: &[f32]) -> Vec<f32> {
+    let max_len = mic.len().max(spk.len());
+    (0..max_len)
+        .flat_map(|i| {
+            [
+                mic.get(i).copied().unwrap_or(0.0),
+                spk.get(i).copied().unwrap_or(0.0),
+            ]
+        })
+        .collect()
+}
+
 fn write_interleaved_stereo(
     writer: &mut hound::WavWriter<BufWriter<File>>,
     mic: &[f32],
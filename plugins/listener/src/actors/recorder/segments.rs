@@ -0,0 +1,318 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use hypr_audio_utils::{OggOpusWriter, concat_ogg_opus, read_ogg_opus_info};
+
+use super::{sync_dir, sync_file};
+
+pub const SEGMENTS_DIR: &str = "audio.segments";
+pub const OPUS_FILENAME: &str = "audio.opus";
+const MANIFEST_FILENAME: &str = "manifest.json";
+const SEGMENT_EXTENSION: &str = "opus";
+const SEGMENT_SECS: u64 = 300;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct Manifest {
+    sample_rate: u32,
+    channels: u8,
+    segments: Vec<ManifestSegment>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct ManifestSegment {
+    file: String,
+    duration_secs: f64,
+    /// `false` for a segment cut short by a crash and picked up on restart.
+    complete: bool,
+}
+
+/// Opus recording split into rolling segment files, so a crash loses at most the last page.
+///
+/// Layout inside the session dir:
+/// - `audio.segments/000001.opus`, `000002.opus`, ...: closed and in-progress segments
+/// - `audio.segments/manifest.json`: closed segments, rewritten whenever one closes
+/// - `audio.opus`: the concatenated recording, written on finalize
+pub(super) struct SegmentedRecording {
+    session_dir: PathBuf,
+    dir: PathBuf,
+    writer: OggOpusWriter<BufWriter<File>>,
+    manifest: Manifest,
+    current: String,
+    next_index: u32,
+    segment_frames: u64,
+}
+
+impl SegmentedRecording {
+    /// Recovers segments left by an earlier run before starting a new one.
+    ///
+    /// Recovered segments decide the channel layout, like the WAV recorder's resume path.
+    pub(super) fn open(
+        session_dir: &Path,
+        sample_rate: u32,
+        stereo: bool,
+    ) -> Result<Self, hypr_audio_utils::Error> {
+        let dir = session_dir.join(SEGMENTS_DIR);
+        std::fs::create_dir_all(&dir)?;
+        recover_finalized(session_dir, &dir)?;
+
+        let recovered = scan_segments(&dir)?;
+        let channels = recovered
+            .first()
+            .map(|(_, info)| info.channels)
+            .unwrap_or(if stereo { 2 } else { 1 });
+        let next_index = recovered
+            .last()
+            .and_then(|(file, _)| segment_index(file))
+            .map_or(1, |index| index + 1);
+
+        let manifest = Manifest {
+            sample_rate,
+            channels,
+            segments: recovered
+                .into_iter()
+                .map(|(file, info)| ManifestSegment {
+                    file,
+                    duration_secs: info.duration_secs,
+                    complete: info.complete,
+                })
+                .collect(),
+        };
+        if !manifest.segments.is_empty() {
+            tracing::info!(
+                segments = manifest.segments.len(),
+                "recovered_opus_segments"
+            );
+        }
+
+        let current = segment_name(next_index);
+        let file = BufWriter::new(File::create(dir.join(&current))?);
+        let writer = OggOpusWriter::new(file, sample_rate, channels)?;
+
+        let recording = Self {
+            session_dir: session_dir.to_path_buf(),
+            dir,
+            writer,
+            manifest,
+            current,
+            next_index: next_index + 1,
+            segment_frames: SEGMENT_SECS * u64::from(sample_rate),
+        };
+        recording.write_manifest()?;
+        Ok(recording)
+    }
+
+    pub(super) fn is_stereo(&self) -> bool {
+        self.writer.channels() == 2
+    }
+
+    pub(super) fn write(&mut self, interleaved: &[f32]) -> Result<(), hypr_audio_utils::Error> {
+        self.writer.write_interleaved(interleaved)?;
+        if self.writer.segment_frames() >= self.segment_frames {
+            self.roll()?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn flush(&mut self) -> Result<(), hypr_audio_utils::Error> {
+        self.writer.flush()
+    }
+
+    /// Closes the last segment and concatenates everything into `audio.opus`.
+    pub(super) fn finalize(self) -> Result<PathBuf, hypr_audio_utils::Error> {
+        let Self {
+            session_dir,
+            dir,
+            writer,
+            mut manifest,
+            current,
+            ..
+        } = self;
+
+        let duration_secs = writer.segment_frames() as f64 / f64::from(manifest.sample_rate);
+        writer.finish()?.into_inner().map_err(|e| e.into_error())?;
+        sync_file(&dir.join(&current));
+        manifest.segments.push(ManifestSegment {
+            file: current,
+            duration_secs,
+            complete: true,
+        });
+        write_manifest(&dir, &manifest)?;
+
+        let paths: Vec<PathBuf> = manifest
+            .segments
+            .iter()
+            .map(|segment| dir.join(&segment.file))
+            .collect();
+        let target = session_dir.join(OPUS_FILENAME);
+        let tmp = session_dir.join(format!("{OPUS_FILENAME}.tmp"));
+
+        let info = concat_ogg_opus(&paths, BufWriter::new(File::create(&tmp)?))?;
+        sync_file(&tmp);
+        // Segments go before the rename; a leftover `.tmp` without segments is the finished file.
+        std::fs::remove_dir_all(&dir)?;
+        std::fs::rename(&tmp, &target)?;
+        sync_dir(&target);
+
+        tracing::info!(
+            segments = paths.len(),
+            duration_secs = info.duration_secs,
+            "opus_recording_finalized"
+        );
+        Ok(target)
+    }
+
+    fn roll(&mut self) -> Result<(), hypr_audio_utils::Error> {
+        let next = segment_name(self.next_index);
+        let file = BufWriter::new(File::create(self.dir.join(&next))?);
+        let duration_secs =
+            self.writer.segment_frames() as f64 / f64::from(self.manifest.sample_rate);
+
+        self.writer
+            .roll(file)?
+            .into_inner()
+            .map_err(|e| e.into_error())?;
+        sync_file(&self.dir.join(&self.current));
+
+        let closed = std::mem::replace(&mut self.current, next);
+        self.next_index += 1;
+        self.manifest.segments.push(ManifestSegment {
+            file: closed,
+            duration_secs,
+            complete: true,
+        });
+        self.write_manifest()
+    }
+
+    fn write_manifest(&self) -> Result<(), hypr_audio_utils::Error> {
+        write_manifest(&self.dir, &self.manifest)
+    }
+}
+
+fn write_manifest(dir: &Path, manifest: &Manifest) -> Result<(), hypr_audio_utils::Error> {
+    let path = dir.join(MANIFEST_FILENAME);
+    let tmp = dir.join(format!("{MANIFEST_FILENAME}.tmp"));
+    let json = serde_json::to_vec_pretty(manifest).map_err(std::io::Error::other)?;
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// Puts an earlier finalized recording back in front so the session keeps appending to it.
+fn recover_finalized(session_dir: &Path, dir: &Path) -> Result<(), std::io::Error> {
+    let target = session_dir.join(OPUS_FILENAME);
+    let tmp = session_dir.join(format!("{OPUS_FILENAME}.tmp"));
+
+    let has_segments = std::fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .any(|entry| segment_index(&entry.file_name().to_string_lossy()).is_some());
+
+    if tmp.exists() {
+        if has_segments {
+            std::fs::remove_file(&tmp)?;
+        } else {
+            std::fs::rename(&tmp, &target)?;
+        }
+    }
+    if target.exists() && !has_segments {
+        std::fs::rename(&target, dir.join(segment_name(0)))?;
+    }
+    Ok(())
+}
+
+/// Segments in order, skipping (and deleting) files too damaged to hold any audio.
+fn scan_segments(
+    dir: &Path,
+) -> Result<Vec<(String, hypr_audio_utils::OggOpusInfo)>, std::io::Error> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| segment_index(name).is_some())
+        .collect();
+    names.sort();
+
+    let mut segments = Vec::with_capacity(names.len());
+    for name in names {
+        let path = dir.join(&name);
+        match read_ogg_opus_info(&path) {
+            Ok(info) if info.duration_secs > 0.0 => segments.push((name, info)),
+            Ok(_) => {
+                tracing::info!(segment = %name, "removing_empty_opus_segment");
+                std::fs::remove_file(&path)?;
+            }
+            Err(error) => {
+                tracing::warn!(segment = %name, %error, "removing_unreadable_opus_segment");
+                std::fs::remove_file(&path)?;
+            }
+        }
+    }
+    Ok(segments)
+}
+
+fn segment_name(index: u32) -> String {
+    format!("{index:06}.{SEGMENT_EXTENSION}")
+}
+
+fn segment_index(name: &str) -> Option<u32> {
+    name.strip_suffix(SEGMENT_EXTENSION)?
+        .strip_suffix('.')?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(secs: f32) -> Vec<f32> {
+        (0..(secs * 16_000.0) as usize)
+            .map(|i| (i as f32 * 0.05).sin() * 0.3)
+            .collect()
+    }
+
+    #[test]
+    fn test_recovers_crashed_segments_and_appends() {
+        let temp = tempfile::tempdir().unwrap();
+        let session_dir = temp.path();
+
+        let mut first = SegmentedRecording::open(session_dir, 16_000, false).unwrap();
+        first.write(&tone(2.0)).unwrap();
+        first.flush().unwrap();
+        first.write(&tone(0.1)).unwrap();
+        // Simulate a crash: the writer is never finished.
+        std::mem::forget(first);
+
+        let mut second = SegmentedRecording::open(session_dir, 16_000, true).unwrap();
+        assert!(!second.is_stereo());
+        assert_eq!(second.manifest.segments.len(), 1);
+        assert!(!second.manifest.segments[0].complete);
+
+        second.write(&tone(1.0)).unwrap();
+        let path = second.finalize().unwrap();
+
+        assert_eq!(path, session_dir.join(OPUS_FILENAME));
+        assert!(!session_dir.join(SEGMENTS_DIR).exists());
+        let info = read_ogg_opus_info(&path).unwrap();
+        assert!(info.complete);
+        assert!(
+            info.duration_secs > 2.8 && info.duration_secs < 3.2,
+            "{info:?}"
+        );
+
+        let mut third = SegmentedRecording::open(session_dir, 16_000, false).unwrap();
+        assert_eq!(third.manifest.segments[0].file, segment_name(0));
+        third.write(&tone(1.0)).unwrap();
+        let info = read_ogg_opus_info(third.finalize().unwrap()).unwrap();
+        assert!(
+            info.duration_secs > 3.8 && info.duration_secs < 4.2,
+            "{info:?}"
+        );
+    }
+
+    #[test]
+    fn test_segment_names_sort_by_index() {
+        assert_eq!(segment_index(&segment_name(42)), Some(42));
+        assert_eq!(segment_index(MANIFEST_FILENAME), None);
+        assert!(segment_name(9) < segment_name(10));
+    }
+}
@@ -17,6 +17,7 @@ pub enum RootMsg {
     StartSession(SessionParams, RpcReplyPort<bool>),
     StopSession(RpcReplyPort<()>),
     GetState(RpcReplyPort<crate::State>),
+    GetSessionSupervisor(RpcReplyPort<Option<ActorCell>>),
 }
 
 pub struct RootArgs {
@@ -82,6 +83,9 @@ impl Actor for RootActor {
                 };
                 let _ = reply.send(fsm_state);
             }
+            RootMsg::GetSessionSupervisor(reply) => {
+                let _ = reply.send(state.supervisor.clone());
+            }
         }
         Ok(())
     }
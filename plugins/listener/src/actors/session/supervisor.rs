@@ -1,6 +1,9 @@
-use hypr_supervisor::{RestartBudget, RestartTracker, RetryStrategy, spawn_with_retry};
+use hypr_supervisor::{
+    ChildHealth, ChildInfo, ChildSpec, RestartBudget, RestartPolicy, RetryStrategy, SpawnFn,
+    SupervisionStrategy, Supervisor, SupervisorConfig, SupervisorMsg,
+};
 use ractor::concurrency::Duration;
-use ractor::{Actor, ActorCell, ActorProcessingErr, ActorRef, SupervisionEvent};
+use ractor::{Actor, ActorCell, ActorProcessingErr, ActorRef, RpcReplyPort, SupervisionEvent};
 use tauri_specta::Event;
 use tracing::Instrument;
 
@@ -12,12 +15,7 @@ use crate::actors::{
     ChannelMode, ListenerActor, ListenerArgs, RecArgs, RecorderActor, SourceActor, SourceArgs,
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum ChildKind {
-    Source,
-    Listener,
-    Recorder,
-}
+const LISTENER_ID: &str = "listener";
 
 const RESTART_BUDGET: RestartBudget = RestartBudget {
     max_restarts: 3,
@@ -32,19 +30,108 @@ const RETRY_STRATEGY: RetryStrategy = RetryStrategy {
 
 pub struct SessionState {
     ctx: SessionContext,
-    source_cell: Option<ActorCell>,
+    // Source and recorder run under a rest-for-one supervisor, so a source restart also
+    // restarts the recorder fed by it. A meltdown there ends the session.
+    capture: ActorRef<SupervisorMsg>,
+    // The listener is never restarted; losing it puts the session in degraded mode.
     listener_cell: Option<ActorCell>,
-    recorder_cell: Option<ActorCell>,
-    source_restarts: RestartTracker,
-    recorder_restarts: RestartTracker,
+    listener_health: ChildHealth,
     shutting_down: bool,
 }
 
+impl SessionState {
+    async fn child_infos(&self) -> Vec<ChildInfo> {
+        let mut infos = Supervisor::which_children(&self.capture)
+            .await
+            .unwrap_or_default();
+        let listener = self
+            .listener_health
+            .info(LISTENER_ID, RestartPolicy::Temporary);
+        infos.insert(infos.len().min(1), listener);
+        infos
+    }
+
+    fn enter_degraded_mode(&mut self, reason: Option<String>, degraded: DegradedError) {
+        self.listener_cell = None;
+        self.listener_health.record_exit(reason, false);
+
+        let _ = (SessionLifecycleEvent::Active {
+            session_id: self.ctx.params.session_id.clone(),
+            error: Some(degraded),
+        })
+        .emit(&self.ctx.app);
+    }
+}
+
+fn capture_children(ctx: &SessionContext) -> Vec<ChildSpec> {
+    let mut children = vec![ChildSpec {
+        id: "source".to_string(),
+        restart_policy: RestartPolicy::Permanent,
+        spawn_fn: SpawnFn::new({
+            let ctx = ctx.clone();
+            move |sup| {
+                let args = SourceArgs {
+                    mic_device: None,
+                    onboarding: ctx.params.onboarding,
+                    app: ctx.app.clone(),
+                    session_id: ctx.params.session_id.clone(),
+                    pipeline: ctx.params.pipeline.clone(),
+                };
+                async move {
+                    let (r, _) =
+                        Actor::spawn_linked(Some(SourceActor::name()), SourceActor, args, sup)
+                            .await?;
+                    Ok(r.get_cell())
+                }
+            }
+        }),
+    }];
+
+    if ctx.params.record_enabled {
+        children.push(ChildSpec {
+            id: "recorder".to_string(),
+            restart_policy: RestartPolicy::Permanent,
+            spawn_fn: SpawnFn::new({
+                let ctx = ctx.clone();
+                move |sup| {
+                    let args = RecArgs {
+                        app_dir: ctx.app_dir.clone(),
+                        session_id: ctx.params.session_id.clone(),
+                        sample_rate: ctx.params.pipeline.output_sample_rate,
+                        format: ctx.params.recording_format,
+                    };
+                    async move {
+                        let (r, _) = Actor::spawn_linked(
+                            Some(RecorderActor::name()),
+                            RecorderActor,
+                            args,
+                            sup,
+                        )
+                        .await?;
+                        Ok(r.get_cell())
+                    }
+                }
+            }),
+        });
+    }
+
+    children
+}
+
 pub struct SessionActor;
 
-#[derive(Debug)]
 pub enum SessionMsg {
     Shutdown,
+    WhichChildren(RpcReplyPort<Vec<ChildInfo>>),
+}
+
+impl std::fmt::Debug for SessionMsg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Shutdown => f.write_str("Shutdown"),
+            Self::WhichChildren(_) => f.write_str("WhichChildren"),
+        }
+    }
 }
 
 #[ractor::async_trait]
@@ -62,42 +149,24 @@ impl Actor for SessionActor {
         let span = session_span(&session_id);
 
         async {
-            let (source_ref, _) = Actor::spawn_linked(
-                Some(SourceActor::name()),
-                SourceActor,
-                SourceArgs {
-                    mic_device: None,
-                    onboarding: ctx.params.onboarding,
-                    app: ctx.app.clone(),
-                    session_id: ctx.params.session_id.clone(),
+            let (capture, _) = Actor::spawn_linked(
+                None,
+                Supervisor,
+                SupervisorConfig {
+                    children: capture_children(&ctx),
+                    restart_budget: RESTART_BUDGET,
+                    retry_strategy: RETRY_STRATEGY,
+                    strategy: SupervisionStrategy::RestForOne,
                 },
                 myself.get_cell(),
             )
             .await?;
 
-            let recorder_cell = if ctx.params.record_enabled {
-                let (recorder_ref, _) = Actor::spawn_linked(
-                    Some(RecorderActor::name()),
-                    RecorderActor,
-                    RecArgs {
-                        app_dir: ctx.app_dir.clone(),
-                        session_id: ctx.params.session_id.clone(),
-                    },
-                    myself.get_cell(),
-                )
-                .await?;
-                Some(recorder_ref.get_cell())
-            } else {
-                None
-            };
-
             Ok(SessionState {
                 ctx,
-                source_cell: Some(source_ref.get_cell()),
+                capture,
                 listener_cell: None,
-                recorder_cell,
-                source_restarts: RestartTracker::new(),
-                recorder_restarts: RestartTracker::new(),
+                listener_health: ChildHealth::new(),
                 shutting_down: false,
             })
         }
@@ -127,6 +196,7 @@ impl Actor for SessionActor {
                     base_url: state.ctx.params.base_url.clone(),
                     api_key: state.ctx.params.api_key.clone(),
                     keywords: state.ctx.params.keywords.clone(),
+                    sample_rate: state.ctx.params.pipeline.output_sample_rate,
                     mode,
                     session_started_at: state.ctx.started_at_instant,
                     session_started_at_unix: state.ctx.started_at_system,
@@ -138,18 +208,14 @@ impl Actor for SessionActor {
             {
                 Ok((listener_ref, _)) => {
                     state.listener_cell = Some(listener_ref.get_cell());
+                    state.listener_health.record_start();
                 }
                 Err(e) => {
                     tracing::warn!(?e, "listener_spawn_failed_entering_degraded_mode");
-                    let base_url = &state.ctx.params.base_url;
                     let degraded = DegradedError::UpstreamUnavailable {
-                        message: classify_connection_failure(base_url),
+                        message: classify_connection_failure(&state.ctx.params.base_url),
                     };
-                    let _ = (SessionLifecycleEvent::Active {
-                        session_id: state.ctx.params.session_id.clone(),
-                        error: Some(degraded),
-                    })
-                    .emit(&state.ctx.app);
+                    state.enter_degraded_mode(Some(e.to_string()), degraded);
                 }
             }
             Ok(())
@@ -168,20 +234,18 @@ impl Actor for SessionActor {
             SessionMsg::Shutdown => {
                 state.shutting_down = true;
 
-                if let Some(cell) = state.recorder_cell.take() {
-                    cell.stop(Some("session_stop".to_string()));
-                    lifecycle::wait_for_actor_shutdown(RecorderActor::name()).await;
-                }
+                let _ = state.capture.cast(SupervisorMsg::Shutdown);
+                lifecycle::wait_for_actor_shutdown(RecorderActor::name()).await;
 
-                if let Some(cell) = state.source_cell.take() {
-                    cell.stop(Some("session_stop".to_string()));
-                }
                 if let Some(cell) = state.listener_cell.take() {
                     cell.stop(Some("session_stop".to_string()));
                 }
-
+                state.listener_health.record_exit(None, false);
                 myself.stop(None);
             }
+            SessionMsg::WhichChildren(reply) => {
+                let _ = reply.send(state.child_infos().await);
+            }
         }
         Ok(())
     }
@@ -195,9 +259,6 @@ impl Actor for SessionActor {
         let span = session_span(&state.ctx.params.session_id);
         let _guard = span.enter();
 
-        state.source_restarts.maybe_reset(&RESTART_BUDGET);
-        state.recorder_restarts.maybe_reset(&RESTART_BUDGET);
-
         if state.shutting_down {
             return Ok(());
         }
@@ -206,200 +267,53 @@ impl Actor for SessionActor {
             SupervisionEvent::ActorStarted(_) | SupervisionEvent::ProcessGroupChanged(_) => {}
 
             SupervisionEvent::ActorTerminated(cell, _, reason) => {
-                match identify_child(state, &cell) {
-                    Some(ChildKind::Listener) => {
-                        tracing::info!(?reason, "listener_terminated_entering_degraded_mode");
-                        let degraded = parse_degraded_reason(reason.as_ref());
-                        state.listener_cell = None;
-
-                        let _ = (SessionLifecycleEvent::Active {
-                            session_id: state.ctx.params.session_id.clone(),
-                            error: Some(degraded),
-                        })
-                        .emit(&state.ctx.app);
-                    }
-                    Some(ChildKind::Source) => {
-                        tracing::info!(?reason, "source_terminated_attempting_restart");
-                        state.source_cell = None;
-                        if !try_restart_source(myself.get_cell(), state).await {
-                            tracing::error!("source_restart_limit_exceeded_meltdown");
-                            meltdown(myself, state).await;
-                        }
-                    }
-                    Some(ChildKind::Recorder) => {
-                        tracing::info!(?reason, "recorder_terminated_attempting_restart");
-                        state.recorder_cell = None;
-                        if !try_restart_recorder(myself.get_cell(), state).await {
-                            tracing::error!("recorder_restart_limit_exceeded_meltdown");
-                            meltdown(myself, state).await;
-                        }
-                    }
-                    None => {
-                        tracing::warn!("unknown_child_terminated");
-                    }
+                if is_listener(state, &cell) {
+                    tracing::info!(?reason, "listener_terminated_entering_degraded_mode");
+                    let degraded = parse_degraded_reason(reason.as_ref());
+                    state.enter_degraded_mode(reason, degraded);
+                } else if cell.get_id() == state.capture.get_id() {
+                    tracing::error!(?reason, "capture_supervisor_stopped_meltdown");
+                    meltdown(myself, state).await;
+                } else {
+                    tracing::warn!("unknown_child_terminated");
                 }
             }
 
-            SupervisionEvent::ActorFailed(cell, error) => match identify_child(state, &cell) {
-                Some(ChildKind::Listener) => {
+            SupervisionEvent::ActorFailed(cell, error) => {
+                if is_listener(state, &cell) {
                     tracing::info!(?error, "listener_failed_entering_degraded_mode");
                     let degraded = DegradedError::StreamError {
                         message: format!("{:?}", error),
                     };
-                    state.listener_cell = None;
-
-                    let _ = (SessionLifecycleEvent::Active {
-                        session_id: state.ctx.params.session_id.clone(),
-                        error: Some(degraded),
-                    })
-                    .emit(&state.ctx.app);
-                }
-                Some(ChildKind::Source) => {
-                    tracing::warn!(?error, "source_failed_attempting_restart");
-                    state.source_cell = None;
-                    if !try_restart_source(myself.get_cell(), state).await {
-                        tracing::error!("source_restart_limit_exceeded_meltdown");
-                        meltdown(myself, state).await;
-                    }
-                }
-                Some(ChildKind::Recorder) => {
-                    tracing::warn!(?error, "recorder_failed_attempting_restart");
-                    state.recorder_cell = None;
-                    if !try_restart_recorder(myself.get_cell(), state).await {
-                        tracing::error!("recorder_restart_limit_exceeded_meltdown");
-                        meltdown(myself, state).await;
-                    }
-                }
-                None => {
+                    state.enter_degraded_mode(Some(format!("{:?}", error)), degraded);
+                } else if cell.get_id() == state.capture.get_id() {
+                    tracing::error!(?error, "capture_supervisor_failed_meltdown");
+                    meltdown(myself, state).await;
+                } else {
                     tracing::warn!("unknown_child_failed");
                 }
-            },
+            }
         }
         Ok(())
     }
 }
 
-fn identify_child(state: &SessionState, cell: &ActorCell) -> Option<ChildKind> {
-    if state
-        .source_cell
-        .as_ref()
-        .is_some_and(|c| c.get_id() == cell.get_id())
-    {
-        return Some(ChildKind::Source);
-    }
-    if state
+fn is_listener(state: &SessionState, cell: &ActorCell) -> bool {
+    state
         .listener_cell
         .as_ref()
         .is_some_and(|c| c.get_id() == cell.get_id())
-    {
-        return Some(ChildKind::Listener);
-    }
-    if state
-        .recorder_cell
-        .as_ref()
-        .is_some_and(|c| c.get_id() == cell.get_id())
-    {
-        return Some(ChildKind::Recorder);
-    }
-    None
-}
-
-async fn try_restart_source(supervisor_cell: ActorCell, state: &mut SessionState) -> bool {
-    if !state.source_restarts.record_restart(&RESTART_BUDGET) {
-        return false;
-    }
-
-    let sup = supervisor_cell;
-    let onboarding = state.ctx.params.onboarding;
-    let app = state.ctx.app.clone();
-    let session_id = state.ctx.params.session_id.clone();
-
-    let cell = spawn_with_retry(&RETRY_STRATEGY, || {
-        let sup = sup.clone();
-        let app = app.clone();
-        let session_id = session_id.clone();
-        async move {
-            let (r, _) = Actor::spawn_linked(
-                Some(SourceActor::name()),
-                SourceActor,
-                SourceArgs {
-                    mic_device: None,
-                    onboarding,
-                    app,
-                    session_id,
-                },
-                sup,
-            )
-            .await?;
-            Ok(r.get_cell())
-        }
-    })
-    .await;
-
-    match cell {
-        Some(c) => {
-            state.source_cell = Some(c);
-            true
-        }
-        None => false,
-    }
-}
-
-async fn try_restart_recorder(supervisor_cell: ActorCell, state: &mut SessionState) -> bool {
-    if !state.ctx.params.record_enabled {
-        return true;
-    }
-
-    if !state.recorder_restarts.record_restart(&RESTART_BUDGET) {
-        return false;
-    }
-
-    let sup = supervisor_cell;
-    let app_dir = state.ctx.app_dir.clone();
-    let session_id = state.ctx.params.session_id.clone();
-
-    let cell = spawn_with_retry(&RETRY_STRATEGY, || {
-        let sup = sup.clone();
-        let app_dir = app_dir.clone();
-        let session_id = session_id.clone();
-        async move {
-            let (r, _) = Actor::spawn_linked(
-                Some(RecorderActor::name()),
-                RecorderActor,
-                RecArgs {
-                    app_dir,
-                    session_id,
-                },
-                sup,
-            )
-            .await?;
-            Ok(r.get_cell())
-        }
-    })
-    .await;
-
-    match cell {
-        Some(c) => {
-            state.recorder_cell = Some(c);
-            true
-        }
-        None => false,
-    }
 }
 
+// The capture supervisor has already stopped its children by the time it exits.
 async fn meltdown(myself: ActorRef<SessionMsg>, state: &mut SessionState) {
     state.shutting_down = true;
 
-    if let Some(cell) = state.source_cell.take() {
-        cell.stop(Some("meltdown".to_string()));
-    }
     if let Some(cell) = state.listener_cell.take() {
         cell.stop(Some("meltdown".to_string()));
     }
-    if let Some(cell) = state.recorder_cell.take() {
-        cell.stop(Some("meltdown".to_string()));
-        lifecycle::wait_for_actor_shutdown(RecorderActor::name()).await;
-    }
+    state.listener_health.record_exit(None, false);
+    lifecycle::wait_for_actor_shutdown(RecorderActor::name()).await;
     myself.stop(Some("restart_limit_exceeded".to_string()));
 }
 
@@ -17,6 +17,10 @@ pub struct SessionParams {
     pub base_url: String,
     pub api_key: String,
     pub keywords: Vec<String>,
+    #[serde(default)]
+    pub pipeline: crate::actors::PipelineConfig,
+    #[serde(default)]
+    pub recording_format: crate::actors::RecordingFormat,
 }
 
 #[derive(Clone)]
@@ -0,0 +1,272 @@
+use std::{
+    collections::VecDeque,
+    path::PathBuf,
+    sync::{
+        Arc, Mutex,
+        mpsc::{Receiver, SyncSender, TrySendError, sync_channel},
+    },
+};
+
+use tauri_plugin_local_stt::{LocalSttPluginExt, SpokenLanguageDetector};
+use tauri_specta::Event;
+
+use crate::{SessionDataEvent, SessionErrorEvent, actors::SAMPLE_RATE};
+
+/// Periodic spoken-language identification on the captured audio, using a local whisper model.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
+#[serde(default)]
+pub struct LanguageIdConfig {
+    /// Seconds of audio the first detection pass looks at.
+    pub initial_secs: u32,
+    /// Seconds between later passes.
+    pub interval_secs: u32,
+    /// Seconds of the most recent audio each later pass looks at.
+    pub window_secs: u32,
+    /// Probability the top language needs before it replaces the current one.
+    pub min_probability: f32,
+}
+
+impl Default for LanguageIdConfig {
+    fn default() -> Self {
+        Self {
+            initial_secs: 10,
+            interval_secs: 60,
+            window_secs: 10,
+            min_probability: 0.6,
+        }
+    }
+}
+
+/// Decides when a detection pass is due and which audio it gets.
+struct DetectionWindow {
+    buffer: VecDeque<f32>,
+    capacity: usize,
+    window: usize,
+    first_pass: usize,
+    interval: usize,
+    since_last_pass: usize,
+    passes: usize,
+}
+
+impl DetectionWindow {
+    fn new(config: &LanguageIdConfig, sample_rate: u32) -> Self {
+        let secs = |s: u32| (s.max(1) * sample_rate) as usize;
+        let first_pass = secs(config.initial_secs);
+        let window = secs(config.window_secs);
+
+        Self {
+            buffer: VecDeque::with_capacity(first_pass.max(window)),
+            capacity: first_pass.max(window),
+            window,
+            first_pass,
+            interval: secs(config.interval_secs),
+            since_last_pass: 0,
+            passes: 0,
+        }
+    }
+
+    /// Feeds mono samples; returns the audio to analyse once a pass is due.
+    fn push(&mut self, samples: impl IntoIterator<Item = f32>) -> Option<Vec<f32>> {
+        for sample in samples {
+            if self.buffer.len() == self.capacity {
+                self.buffer.pop_front();
+            }
+            self.buffer.push_back(sample);
+            self.since_last_pass += 1;
+        }
+
+        let due = if self.passes == 0 {
+            self.first_pass
+        } else {
+            self.interval
+        };
+        if self.since_last_pass < due {
+            return None;
+        }
+
+        let take = if self.passes == 0 {
+            self.first_pass
+        } else {
+            self.window
+        };
+        self.since_last_pass = 0;
+        self.passes += 1;
+
+        let skip = self.buffer.len().saturating_sub(take);
+        Some(self.buffer.iter().skip(skip).copied().collect())
+    }
+
+    fn reset(&mut self) {
+        self.buffer.clear();
+        self.since_last_pass = 0;
+    }
+}
+
+pub(super) struct LanguageMonitor {
+    window: DetectionWindow,
+    passes: SyncSender<Vec<f32>>,
+    current: Arc<Mutex<Option<hypr_language::Language>>>,
+}
+
+impl LanguageMonitor {
+    /// `None` when no multilingual whisper model is downloaded; a non-fatal warning is emitted.
+    pub(super) fn new(
+        app: tauri::AppHandle,
+        session_id: String,
+        config: &LanguageIdConfig,
+    ) -> Option<Self> {
+        let Some(model_path) = app.local_stt().language_id_model_path() else {
+            if let Err(error) = (SessionErrorEvent::AudioError {
+                session_id,
+                error: "language identification needs a downloaded multilingual whisper model"
+                    .to_string(),
+                device: None,
+                is_fatal: false,
+            })
+            .emit(&app)
+            {
+                tracing::error!(?error, "failed_to_emit_language_id_warning");
+            }
+            return None;
+        };
+
+        // Rendezvous channel: a pass is only handed over while the worker is idle.
+        let (passes, rx) = sync_channel(0);
+        let current = Arc::new(Mutex::new(None));
+        let worker = DetectionWorker {
+            app,
+            session_id,
+            model_path,
+            min_probability: config.min_probability,
+            current: Arc::clone(&current),
+        };
+        tauri::async_runtime::spawn_blocking(move || worker.run(rx));
+
+        Some(Self {
+            window: DetectionWindow::new(config, SAMPLE_RATE),
+            passes,
+            current,
+        })
+    }
+
+    pub(super) fn reset(&mut self) {
+        self.window.reset();
+    }
+
+    pub(super) fn current(&self) -> Option<hypr_language::Language> {
+        self.current.lock().ok()?.clone()
+    }
+
+    /// Takes the raw (pre-stage) frame so detection runs at the capture rate and hears both sides.
+    pub(super) fn observe(&mut self, mic: &[f32], spk: &[f32]) {
+        let mixed = mic.iter().zip(spk).map(|(m, s)| (m + s) * 0.5);
+        let Some(audio) = self.window.push(mixed) else {
+            return;
+        };
+
+        match self.passes.try_send(audio) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                tracing::debug!("language_id_pass_skipped_previous_running");
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                tracing::warn!("language_id_worker_stopped");
+            }
+        }
+    }
+}
+
+/// Owns the whisper model for the whole session, so loading and inference never block the
+/// capture loop reading [`LanguageMonitor::current`]. Exits once the monitor is dropped.
+struct DetectionWorker {
+    app: tauri::AppHandle,
+    session_id: String,
+    model_path: PathBuf,
+    min_probability: f32,
+    current: Arc<Mutex<Option<hypr_language::Language>>>,
+}
+
+impl DetectionWorker {
+    fn run(self, passes: Receiver<Vec<f32>>) {
+        let mut detector = None;
+
+        for audio in passes {
+            let (language, probability) = match detect(&mut detector, &self.model_path, &audio) {
+                Ok(Some(top)) => top,
+                Ok(None) => continue,
+                Err(error) => {
+                    tracing::warn!(%error, "language_id_failed");
+                    continue;
+                }
+            };
+            if probability < self.min_probability {
+                tracing::debug!(language = %language.bcp47_code(), probability, "language_id_unsure");
+                continue;
+            }
+
+            let previous = {
+                let Ok(mut current) = self.current.lock() else {
+                    return;
+                };
+                if current.as_ref().map(|l| l.iso639()) == Some(language.iso639()) {
+                    continue;
+                }
+                current.replace(language.clone())
+            };
+
+            tracing::info!(
+                language = %language.bcp47_code(),
+                previous = ?previous.as_ref().map(|l| l.bcp47_code()),
+                probability,
+                "spoken_language_changed"
+            );
+            if let Err(error) = (SessionDataEvent::LanguageDetected {
+                session_id: self.session_id.clone(),
+                language: language.bcp47_code(),
+                probability,
+                previous: previous.map(|l| l.bcp47_code()),
+            })
+            .emit(&self.app)
+            {
+                tracing::error!(?error, "session_data_event_emit_failed");
+            }
+        }
+    }
+}
+
+fn detect(
+    detector: &mut Option<SpokenLanguageDetector>,
+    model_path: &std::path::Path,
+    audio: &[f32],
+) -> Result<Option<(hypr_language::Language, f32)>, tauri_plugin_local_stt::Error> {
+    let detector = match detector {
+        Some(detector) => detector,
+        None => detector.insert(SpokenLanguageDetector::load(model_path)?),
+    };
+    Ok(detector.detect(audio)?.into_iter().next())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_pass_uses_initial_audio_then_recent_window() {
+        let config = LanguageIdConfig {
+            initial_secs: 3,
+            interval_secs: 4,
+            window_secs: 2,
+            min_probability: 0.5,
+        };
+        let mut window = DetectionWindow::new(&config, 10);
+
+        assert!(window.push((0..29).map(|i| i as f32)).is_none());
+        let first = window.push([29.0]).unwrap();
+        assert_eq!(first.len(), 30);
+        assert_eq!(first[0], 0.0);
+
+        assert!(window.push((30..69).map(|i| i as f32)).is_none());
+        let second = window.push([69.0]).unwrap();
+        assert_eq!(second, (50..70).map(|i| i as f32).collect::<Vec<_>>());
+    }
+}
@@ -1,4 +1,6 @@
+mod language;
 mod pipeline;
+mod stages;
 mod stream;
 
 use std::sync::{
@@ -19,7 +21,9 @@ use crate::{
 use hypr_audio::AudioInput;
 use tauri_specta::Event;
 
+pub use language::LanguageIdConfig;
 use pipeline::Pipeline;
+pub use stages::{PipelineConfig, StageLatency, VadMaskConfig};
 use stream::start_source_loop;
 
 use hypr_device_monitor::{DeviceMonitorHandle, DeviceSwitch, DeviceSwitchMonitor};
@@ -28,6 +32,8 @@ pub enum SourceMsg {
     SetMicMute(bool),
     GetMicMute(RpcReplyPort<bool>),
     GetMicDevice(RpcReplyPort<Option<String>>),
+    GetStageLatencies(RpcReplyPort<Vec<StageLatency>>),
+    GetDetectedLanguage(RpcReplyPort<Option<hypr_language::Language>>),
     MicChunk(AudioChunk),
     SpeakerChunk(AudioChunk),
     StreamFailed(String),
@@ -38,6 +44,7 @@ pub struct SourceArgs {
     pub onboarding: bool,
     pub app: tauri::AppHandle,
     pub session_id: String,
+    pub pipeline: PipelineConfig,
 }
 
 pub struct SourceState {
@@ -124,7 +131,7 @@ impl Actor for SourceActor {
                 .or_else(|| Some(AudioInput::get_default_device_name()));
             tracing::info!(mic_device = ?mic_device);
 
-            let pipeline = Pipeline::new(args.app.clone(), args.session_id.clone());
+            let pipeline = Pipeline::new(args.app.clone(), args.session_id.clone(), &args.pipeline);
 
             let mut st = SourceState {
                 app: args.app,
@@ -170,6 +177,16 @@ impl Actor for SourceActor {
                     let _ = reply.send(st.mic_device.clone());
                 }
             }
+            SourceMsg::GetStageLatencies(reply) => {
+                if !reply.is_closed() {
+                    let _ = reply.send(st.pipeline.stage_latencies());
+                }
+            }
+            SourceMsg::GetDetectedLanguage(reply) => {
+                if !reply.is_closed() {
+                    let _ = reply.send(st.pipeline.detected_language());
+                }
+            }
             SourceMsg::MicChunk(chunk) => {
                 st.pipeline.ingest_mic(chunk);
                 st.pipeline.flush(st.current_mode);
@@ -7,13 +7,13 @@ use std::{
 use ractor::{ActorRef, registry};
 use tauri_specta::Event;
 
+use super::language::LanguageMonitor;
+use super::stages::{Frame, LatencyStats, PipelineConfig, Stage, StageLatency, build_stages};
 use crate::{
-    SessionDataEvent,
+    SessionDataEvent, SessionErrorEvent,
     actors::{AudioChunk, ChannelMode, ListenerActor, ListenerMsg, RecMsg, RecorderActor},
 };
-use hypr_aec::AEC;
 use hypr_audio_utils::f32_to_i16_bytes;
-use hypr_vad_ext::VadMask;
 
 const AUDIO_AMPLITUDE_THROTTLE: Duration = Duration::from_millis(100);
 const MAX_BUFFER_CHUNKS: usize = 150;
@@ -22,8 +22,8 @@ type AudioPair = (Vec<f32>, Vec<f32>);
 type BufferedAudio = (Arc<[f32]>, Arc<[f32]>, ChannelMode);
 
 pub(in crate::actors) struct Pipeline {
-    vad_mask: VadMask,
-    aec: Option<AEC>,
+    stages: Vec<(Box<dyn Stage>, LatencyStats)>,
+    language: Option<LanguageMonitor>,
     joiner: Joiner,
     amplitude: AmplitudeEmitter,
     audio_buffer: AudioBuffer,
@@ -34,28 +34,67 @@ impl Pipeline {
     const BACKLOG_QUOTA_INCREMENT: f32 = 0.25;
     const MAX_BACKLOG_QUOTA: f32 = 2.0;
 
-    pub(super) fn new(app: tauri::AppHandle, session_id: String) -> Self {
+    pub(super) fn new(app: tauri::AppHandle, session_id: String, config: &PipelineConfig) -> Self {
+        let (stages, warnings) = build_stages(config);
+        for warning in warnings {
+            if let Err(error) = (SessionErrorEvent::AudioError {
+                session_id: session_id.clone(),
+                error: warning,
+                device: None,
+                is_fatal: false,
+            })
+            .emit(&app)
+            {
+                tracing::error!(?error, "failed_to_emit_pipeline_warning");
+            }
+        }
+        tracing::info!(
+            stages = ?stages.iter().map(|s| s.name()).collect::<Vec<_>>(),
+            "pipeline_configured"
+        );
+
+        let language = config
+            .language_id
+            .as_ref()
+            .and_then(|cfg| LanguageMonitor::new(app.clone(), session_id.clone(), cfg));
+
         Self {
-            aec: AEC::new()
-                .map_err(|e| tracing::warn!(error = ?e, "aec_init_failed"))
-                .ok(),
+            stages: stages
+                .into_iter()
+                .map(|stage| (stage, LatencyStats::default()))
+                .collect(),
+            language,
             joiner: Joiner::new(),
             amplitude: AmplitudeEmitter::new(app, session_id),
             audio_buffer: AudioBuffer::new(MAX_BUFFER_CHUNKS),
             backlog_quota: 0.0,
-            vad_mask: VadMask::default(),
         }
     }
 
     pub(super) fn reset(&mut self) {
         self.joiner.reset();
-        if let Some(aec) = &mut self.aec {
-            aec.reset();
+        for (stage, _) in &mut self.stages {
+            stage.reset();
+        }
+        if let Some(language) = &mut self.language {
+            language.reset();
         }
         self.amplitude.reset();
         self.audio_buffer.clear();
         self.backlog_quota = 0.0;
-        self.vad_mask = VadMask::default();
+    }
+
+    pub(super) fn stage_latencies(&self) -> Vec<StageLatency> {
+        self.stages
+            .iter()
+            .map(|(stage, stats)| stats.report(stage.name()))
+            .collect()
+    }
+
+    pub(super) fn detected_language(&self) -> Option<hypr_language::Language> {
+        self.language
+            .as_ref()
+            .and_then(|language| language.current())
     }
 
     pub(super) fn ingest_mic(&mut self, chunk: AudioChunk) {
@@ -73,21 +112,17 @@ impl Pipeline {
     }
 
     fn dispatch(&mut self, mic: Vec<f32>, spk: Vec<f32>, mode: ChannelMode) {
-        let mut processed_mic = if let Some(aec) = &mut self.aec {
-            match aec.process_streaming(&mic, &spk) {
-                Ok(processed) => processed,
-                Err(e) => {
-                    tracing::warn!(error = ?e, "aec_failed");
-                    mic
-                }
-            }
-        } else {
-            mic
-        };
+        if let Some(language) = &mut self.language {
+            language.observe(&mic, &spk);
+        }
+
+        let mut frame = Frame { mic, spk };
+        for (stage, stats) in &mut self.stages {
+            stats.time(|| stage.process(&mut frame));
+        }
 
-        self.vad_mask.process(&mut processed_mic);
-        let processed_mic = Arc::<[f32]>::from(processed_mic);
-        let processed_spk = Arc::<[f32]>::from(spk);
+        let processed_mic = Arc::<[f32]>::from(frame.mic);
+        let processed_spk = Arc::<[f32]>::from(frame.spk);
 
         self.amplitude.observe_mic(&processed_mic);
         self.amplitude.observe_spk(&processed_spk);
@@ -0,0 +1,559 @@
+use std::time::{Duration, Instant};
+
+use hypr_aec::AEC;
+use hypr_audio::LoudnessNormalizer;
+use hypr_audio_utils::{ResamplerPush, chunk_size_for_stt};
+use hypr_vad_ext::{VadConfig, VadMask};
+
+use super::language::LanguageIdConfig;
+use crate::actors::SAMPLE_RATE;
+
+/// Which processing stages run between capture and the listener/recorder, chosen per session.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
+#[serde(default)]
+pub struct PipelineConfig {
+    pub aec: bool,
+    pub high_pass_hz: Option<f32>,
+    /// Chunks quieter than this (dBFS) are silenced on both channels.
+    pub noise_gate_db: Option<f32>,
+    /// Mic masking outside of detected speech; `None` passes the mic through untouched.
+    pub vad_mask: Option<VadMaskConfig>,
+    pub normalize: bool,
+    /// Rate handed to the listener and recorder; one the Opus recorder accepts.
+    #[serde(deserialize_with = "deserialize_output_sample_rate")]
+    pub output_sample_rate: u32,
+    /// Spoken-language identification on the raw capture; `None` disables it.
+    pub language_id: Option<LanguageIdConfig>,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            aec: true,
+            high_pass_hz: None,
+            noise_gate_db: None,
+            vad_mask: Some(VadMaskConfig::default()),
+            normalize: false,
+            output_sample_rate: SAMPLE_RATE,
+            language_id: None,
+        }
+    }
+}
+
+/// Output rates the recorder can encode, matching `OggOpusWriter::new`.
+const OUTPUT_SAMPLE_RATES: [u32; 5] = [8_000, 12_000, 16_000, 24_000, 48_000];
+
+fn deserialize_output_sample_rate<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let rate = <u32 as serde::Deserialize>::deserialize(deserializer)?;
+    if OUTPUT_SAMPLE_RATES.contains(&rate) {
+        Ok(rate)
+    } else {
+        Err(serde::de::Error::custom(format!(
+            "unsupported output sample rate {rate}, expected one of {OUTPUT_SAMPLE_RATES:?}"
+        )))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
+#[serde(default)]
+pub struct VadMaskConfig {
+    pub hangover_frames: u32,
+    pub amplitude_floor: f32,
+    /// Speech probability at or above which a frame is kept.
+    pub speech_threshold: f32,
+}
+
+impl Default for VadMaskConfig {
+    fn default() -> Self {
+        let cfg = VadConfig::default();
+        Self {
+            hangover_frames: cfg.hangover_frames as u32,
+            amplitude_floor: cfg.amplitude_floor,
+            speech_threshold: cfg.speech_threshold,
+        }
+    }
+}
+
+impl From<&VadMaskConfig> for VadConfig {
+    fn from(value: &VadMaskConfig) -> Self {
+        VadConfig {
+            hangover_frames: value.hangover_frames as usize,
+            amplitude_floor: value.amplitude_floor,
+            speech_threshold: value.speech_threshold,
+            ..VadConfig::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, specta::Type)]
+pub struct StageLatency {
+    pub stage: String,
+    pub last_us: u32,
+    pub avg_us: u32,
+    pub max_us: u32,
+}
+
+/// One joined mic/speaker frame travelling through the stage chain.
+pub(super) struct Frame {
+    pub mic: Vec<f32>,
+    pub spk: Vec<f32>,
+}
+
+pub(super) trait Stage: Send {
+    fn name(&self) -> &'static str;
+    fn process(&mut self, frame: &mut Frame);
+    fn reset(&mut self);
+}
+
+/// Builds the chain for `config`. Stages that fail to initialize are left out and reported
+/// through the returned warnings instead of being dropped silently.
+pub(super) fn build_stages(config: &PipelineConfig) -> (Vec<Box<dyn Stage>>, Vec<String>) {
+    let mut stages: Vec<Box<dyn Stage>> = Vec::new();
+    let mut warnings = Vec::new();
+
+    if config.aec {
+        match AecStage::new() {
+            Ok(stage) => stages.push(Box::new(stage)),
+            Err(e) => {
+                tracing::warn!(error = ?e, "aec_init_failed");
+                warnings.push(format!("echo cancellation unavailable: {e}"));
+            }
+        }
+    }
+    if let Some(cutoff_hz) = config.high_pass_hz {
+        stages.push(Box::new(HighPassStage::new(cutoff_hz, SAMPLE_RATE)));
+    }
+    if let Some(threshold_db) = config.noise_gate_db {
+        stages.push(Box::new(NoiseGateStage::new(threshold_db)));
+    }
+    if let Some(vad) = &config.vad_mask {
+        stages.push(Box::new(VadMaskStage::new(vad)));
+    }
+    if config.normalize {
+        stages.push(Box::new(NormalizeStage::new(SAMPLE_RATE)));
+    }
+    if config.output_sample_rate != SAMPLE_RATE {
+        match ResampleStage::new(SAMPLE_RATE, config.output_sample_rate) {
+            Ok(stage) => stages.push(Box::new(stage)),
+            Err(e) => {
+                tracing::warn!(error = ?e, "resample_init_failed");
+                warnings.push(format!("resampling unavailable: {e}"));
+            }
+        }
+    }
+
+    (stages, warnings)
+}
+
+#[derive(Default)]
+pub(super) struct LatencyStats {
+    last: Duration,
+    total: Duration,
+    max: Duration,
+    count: u32,
+}
+
+impl LatencyStats {
+    pub(super) fn time<T>(&mut self, f: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let out = f();
+        let elapsed = started.elapsed();
+
+        self.last = elapsed;
+        self.max = self.max.max(elapsed);
+        self.total += elapsed;
+        self.count = self.count.saturating_add(1);
+        out
+    }
+
+    pub(super) fn report(&self, stage: &str) -> StageLatency {
+        let avg = if self.count == 0 {
+            Duration::ZERO
+        } else {
+            self.total / self.count
+        };
+
+        StageLatency {
+            stage: stage.to_string(),
+            last_us: self.last.as_micros() as u32,
+            avg_us: avg.as_micros() as u32,
+            max_us: self.max.as_micros() as u32,
+        }
+    }
+}
+
+struct AecStage {
+    aec: AEC,
+}
+
+impl AecStage {
+    fn new() -> Result<Self, hypr_aec::Error> {
+        Ok(Self { aec: AEC::new()? })
+    }
+}
+
+impl Stage for AecStage {
+    fn name(&self) -> &'static str {
+        "aec"
+    }
+
+    fn process(&mut self, frame: &mut Frame) {
+        match self.aec.process_streaming(&frame.mic, &frame.spk) {
+            Ok(processed) => frame.mic = processed,
+            Err(e) => tracing::warn!(error = ?e, "aec_failed"),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.aec.reset();
+    }
+}
+
+struct VadMaskStage {
+    config: VadConfig,
+    mask: VadMask,
+}
+
+impl VadMaskStage {
+    fn new(config: &VadMaskConfig) -> Self {
+        let config = VadConfig::from(config);
+        Self {
+            mask: VadMask::new().with_vad_config(config.clone()),
+            config,
+        }
+    }
+}
+
+impl Stage for VadMaskStage {
+    fn name(&self) -> &'static str {
+        "vad_mask"
+    }
+
+    fn process(&mut self, frame: &mut Frame) {
+        self.mask.process(&mut frame.mic);
+    }
+
+    fn reset(&mut self) {
+        self.mask = VadMask::new().with_vad_config(self.config.clone());
+    }
+}
+
+/// First-order high-pass, run independently on each channel.
+struct HighPassStage {
+    alpha: f32,
+    mic: HighPassState,
+    spk: HighPassState,
+}
+
+#[derive(Default)]
+struct HighPassState {
+    prev_in: f32,
+    prev_out: f32,
+}
+
+impl HighPassState {
+    fn process(&mut self, samples: &mut [f32], alpha: f32) {
+        for sample in samples.iter_mut() {
+            let out = alpha * (self.prev_out + *sample - self.prev_in);
+            self.prev_in = *sample;
+            self.prev_out = out;
+            *sample = out;
+        }
+    }
+}
+
+impl HighPassStage {
+    fn new(cutoff_hz: f32, sample_rate: u32) -> Self {
+        let rc = 1.0 / (2.0 * std::f32::consts::PI * cutoff_hz.max(1.0));
+        let dt = 1.0 / sample_rate as f32;
+        Self {
+            alpha: rc / (rc + dt),
+            mic: HighPassState::default(),
+            spk: HighPassState::default(),
+        }
+    }
+}
+
+impl Stage for HighPassStage {
+    fn name(&self) -> &'static str {
+        "high_pass"
+    }
+
+    fn process(&mut self, frame: &mut Frame) {
+        self.mic.process(&mut frame.mic, self.alpha);
+        self.spk.process(&mut frame.spk, self.alpha);
+    }
+
+    fn reset(&mut self) {
+        self.mic = HighPassState::default();
+        self.spk = HighPassState::default();
+    }
+}
+
+struct NoiseGateStage {
+    threshold_rms: f32,
+}
+
+impl NoiseGateStage {
+    fn new(threshold_db: f32) -> Self {
+        Self {
+            threshold_rms: 10_f32.powf(threshold_db / 20.0),
+        }
+    }
+
+    fn gate(&self, samples: &mut [f32]) {
+        if samples.is_empty() {
+            return;
+        }
+        let rms = (samples.iter().map(|&s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+        if rms < self.threshold_rms {
+            samples.fill(0.0);
+        }
+    }
+}
+
+impl Stage for NoiseGateStage {
+    fn name(&self) -> &'static str {
+        "noise_gate"
+    }
+
+    fn process(&mut self, frame: &mut Frame) {
+        self.gate(&mut frame.mic);
+        self.gate(&mut frame.spk);
+    }
+
+    fn reset(&mut self) {}
+}
+
+struct NormalizeStage {
+    sample_rate: u32,
+    mic: LoudnessNormalizer,
+    spk: LoudnessNormalizer,
+}
+
+impl NormalizeStage {
+    fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            mic: LoudnessNormalizer::new(sample_rate),
+            spk: LoudnessNormalizer::new(sample_rate),
+        }
+    }
+}
+
+impl Stage for NormalizeStage {
+    fn name(&self) -> &'static str {
+        "normalize"
+    }
+
+    fn process(&mut self, frame: &mut Frame) {
+        self.mic.process_chunk(&mut frame.mic);
+        self.spk.process_chunk(&mut frame.spk);
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.sample_rate);
+    }
+}
+
+/// Rubato resampler per channel. Both see the same chunk sizes, so joined frames keep equal
+/// lengths.
+struct ResampleStage {
+    from_rate: u32,
+    to_rate: u32,
+    mic: ResamplerPush,
+    spk: ResamplerPush,
+}
+
+impl ResampleStage {
+    fn new(from_rate: u32, to_rate: u32) -> Result<Self, hypr_audio_utils::Error> {
+        let block = chunk_size_for_stt(from_rate);
+        Ok(Self {
+            from_rate,
+            to_rate,
+            mic: ResamplerPush::new(from_rate, to_rate, block)?,
+            spk: ResamplerPush::new(from_rate, to_rate, block)?,
+        })
+    }
+}
+
+impl Stage for ResampleStage {
+    fn name(&self) -> &'static str {
+        "resample"
+    }
+
+    fn process(&mut self, frame: &mut Frame) {
+        match (self.mic.process(&frame.mic), self.spk.process(&frame.spk)) {
+            (Ok(mic), Ok(spk)) => {
+                frame.mic = mic;
+                frame.spk = spk;
+            }
+            (Err(e), _) | (_, Err(e)) => {
+                // Passing the frame through would mix rates downstream, so drop it instead.
+                tracing::warn!(error = ?e, "resample_failed");
+                frame.mic.clear();
+                frame.spk.clear();
+                self.reset();
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        if let Ok(stage) = Self::new(self.from_rate, self.to_rate) {
+            *self = stage;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use hypr_audio::mock::MockInnerStream;
+
+    use super::*;
+
+    fn sine(freq: f32, amplitude: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| {
+                amplitude
+                    * (2.0 * std::f32::consts::PI * freq * i as f32 / SAMPLE_RATE as f32).sin()
+            })
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|&s| s * s).sum::<f32>() / samples.len().max(1) as f32).sqrt()
+    }
+
+    async fn run(stage: &mut dyn Stage, speaker: Vec<Vec<f32>>) -> Vec<Frame> {
+        let mut frames = Vec::new();
+        let mut stream = MockInnerStream::new(speaker, SAMPLE_RATE);
+        while let Some(spk) = stream.next().await {
+            let mut frame = Frame {
+                mic: spk.clone(),
+                spk,
+            };
+            stage.process(&mut frame);
+            frames.push(frame);
+        }
+        frames
+    }
+
+    #[test]
+    fn config_defaults_match_the_previous_fixed_pipeline() {
+        let config: PipelineConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config, PipelineConfig::default());
+        assert!(config.aec);
+        assert!(config.vad_mask.is_some());
+        assert_eq!(config.output_sample_rate, SAMPLE_RATE);
+
+        let (stages, _) = build_stages(&PipelineConfig {
+            aec: false,
+            vad_mask: None,
+            ..Default::default()
+        });
+        assert!(stages.is_empty());
+    }
+
+    #[test]
+    fn vad_mask_threshold_reaches_the_vad() {
+        let config: VadMaskConfig = serde_json::from_str(r#"{"speech_threshold":0.8}"#).unwrap();
+        let vad = VadConfig::from(&config);
+        assert_eq!(vad.speech_threshold, 0.8);
+        assert_eq!(vad.hangover_frames, VadConfig::default().hangover_frames);
+    }
+
+    #[tokio::test]
+    async fn high_pass_removes_low_frequencies() {
+        let mut stage = HighPassStage::new(300.0, SAMPLE_RATE);
+        let chunks = sine(20.0, 0.5, 16_000)
+            .chunks(320)
+            .map(<[f32]>::to_vec)
+            .collect();
+        let frames = run(&mut stage, chunks).await;
+        let tail: Vec<f32> = frames[25..].iter().flat_map(|f| f.spk.clone()).collect();
+        assert!(rms(&tail) < 0.05, "rms {}", rms(&tail));
+
+        let mut stage = HighPassStage::new(300.0, SAMPLE_RATE);
+        let frames = run(&mut stage, vec![sine(3000.0, 0.5, 3200)]).await;
+        assert!(rms(&frames[0].mic[1600..]) > 0.3);
+    }
+
+    #[tokio::test]
+    async fn noise_gate_silences_quiet_chunks_only() {
+        let mut stage = NoiseGateStage::new(-40.0);
+        let frames = run(
+            &mut stage,
+            vec![sine(440.0, 0.001, 320), sine(440.0, 0.5, 320)],
+        )
+        .await;
+        assert!(frames[0].mic.iter().all(|&s| s == 0.0));
+        assert!(frames[0].spk.iter().all(|&s| s == 0.0));
+        assert!(rms(&frames[1].mic) > 0.3);
+    }
+
+    #[tokio::test]
+    async fn normalize_raises_quiet_audio() {
+        let mut stage = NormalizeStage::new(SAMPLE_RATE);
+        let chunks = sine(440.0, 0.01, 32_000)
+            .chunks(320)
+            .map(<[f32]>::to_vec)
+            .collect();
+        let frames = run(&mut stage, chunks).await;
+        let last = frames.last().unwrap();
+        assert!(rms(&last.spk) > 0.01 * 2.0, "rms {}", rms(&last.spk));
+        assert!(last.spk.iter().all(|s| s.abs() <= 1.0));
+    }
+
+    #[tokio::test]
+    async fn resample_changes_rate_and_keeps_channels_aligned() {
+        let block = chunk_size_for_stt(SAMPLE_RATE);
+        let mut stage = ResampleStage::new(SAMPLE_RATE, 8_000).unwrap();
+        let frames = run(&mut stage, vec![vec![0.25; block]; 10]).await;
+        let total: usize = frames.iter().map(|f| f.mic.len()).sum();
+        assert!(
+            (block * 5 - 16..=block * 5).contains(&total),
+            "total {total}"
+        );
+        assert!(frames.iter().all(|f| f.mic.len() == f.spk.len()));
+        let last = &frames.last().unwrap().mic;
+        assert!(last.iter().all(|&s| (s - 0.25).abs() < 1e-3));
+
+        let mut stage = ResampleStage::new(SAMPLE_RATE, 48_000).unwrap();
+        let frames = run(&mut stage, vec![vec![0.0; block]; 4]).await;
+        let total: usize = frames.iter().map(|f| f.spk.len()).sum();
+        assert!(
+            (block * 12 - 48..=block * 12).contains(&total),
+            "total {total}"
+        );
+    }
+
+    #[test]
+    fn output_sample_rate_is_validated_on_deserialize() {
+        let config: PipelineConfig =
+            serde_json::from_str(r#"{"output_sample_rate": 48000}"#).unwrap();
+        assert_eq!(config.output_sample_rate, 48_000);
+
+        for rate in [0, 44_100] {
+            let json = format!(r#"{{"output_sample_rate": {rate}}}"#);
+            let err = serde_json::from_str::<PipelineConfig>(&json).unwrap_err();
+            assert!(err.to_string().contains("unsupported output sample rate"));
+        }
+    }
+
+    #[test]
+    fn latency_stats_track_last_average_and_max() {
+        let mut stats = LatencyStats::default();
+        assert_eq!(stats.report("x").avg_us, 0);
+        stats.time(|| std::thread::sleep(Duration::from_millis(2)));
+        stats.time(|| ());
+        let report = stats.report("x");
+        assert_eq!(report.stage, "x");
+        assert!(report.max_us >= 2_000);
+        assert!(report.last_us <= report.max_us);
+        assert!(report.avg_us <= report.max_us);
+    }
+}
@@ -66,6 +66,22 @@ pub async fn get_state<R: tauri::Runtime>(
     Ok(app.listener().get_state().await)
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn get_supervision_tree<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<Option<hypr_supervisor::SupervisorTree>, String> {
+    Ok(app.listener().get_supervision_tree().await)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_pipeline_latencies<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<Vec<crate::actors::StageLatency>, String> {
+    Ok(app.listener().get_pipeline_latencies().await)
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn is_supported_languages_live<R: tauri::Runtime>(
@@ -92,15 +108,25 @@ pub async fn is_supported_languages_live<R: tauri::Runtime>(
 #[tauri::command]
 #[specta::specta]
 pub async fn suggest_providers_for_languages_live<R: tauri::Runtime>(
-    _app: tauri::AppHandle<R>,
+    app: tauri::AppHandle<R>,
     languages: Vec<String>,
 ) -> Result<Vec<String>, String> {
-    let languages_parsed = languages
+    let mut languages_parsed = languages
         .iter()
         .map(|s| hypr_language::Language::from_str(s))
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| format!("unknown_language: {}", e))?;
 
+    // A language heard in the running session counts as requested, so suggestions re-route to
+    // adapters that can transcribe it.
+    if let Some(detected) = app.listener().get_detected_language().await
+        && !languages_parsed
+            .iter()
+            .any(|language| language.iso639() == detected.iso639())
+    {
+        languages_parsed.push(detected);
+    }
+
     let all_providers = [
         AdapterKind::Argmax,
         AdapterKind::Soniox,
@@ -80,5 +80,12 @@ common_event_derives! {
             session_id: String,
             response: Box<StreamResponse>,
         },
+        #[serde(rename = "language_detected")]
+        LanguageDetected {
+            session_id: String,
+            language: String,
+            probability: f32,
+            previous: Option<String>,
+        },
     }
 }
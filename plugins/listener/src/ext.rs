@@ -1,6 +1,6 @@
 use ractor::{ActorRef, call_t, registry};
 
-use crate::actors::{RootActor, RootMsg, SessionParams, SourceActor, SourceMsg};
+use crate::actors::{RootActor, RootMsg, SessionMsg, SessionParams, SourceActor, SourceMsg};
 
 pub struct Listener<'a, R: tauri::Runtime, M: tauri::Manager<R>> {
     #[allow(unused)]
@@ -40,6 +40,20 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Listener<'a, R, M> {
         }
     }
 
+    /// The running session's supervisor and its children, or `None` when no session is active.
+    #[tracing::instrument(skip_all)]
+    pub async fn get_supervision_tree(&self) -> Option<hypr_supervisor::SupervisorTree> {
+        let root: ActorRef<RootMsg> = registry::where_is(RootActor::name())?.into();
+        let supervisor = call_t!(root, RootMsg::GetSessionSupervisor, 100).ok()??;
+        let session: ActorRef<SessionMsg> = supervisor.clone().into();
+        let children = call_t!(session, SessionMsg::WhichChildren, 500).ok()?;
+
+        Some(hypr_supervisor::SupervisorTree {
+            name: supervisor.get_name().unwrap_or_default(),
+            children,
+        })
+    }
+
     #[tracing::instrument(skip_all)]
     pub async fn get_mic_muted(&self) -> bool {
         if let Some(cell) = registry::where_is(SourceActor::name()) {
@@ -50,6 +64,26 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Listener<'a, R, M> {
         }
     }
 
+    /// Per-stage processing time of the live audio pipeline; empty when no session is capturing.
+    #[tracing::instrument(skip_all)]
+    pub async fn get_pipeline_latencies(&self) -> Vec<crate::actors::StageLatency> {
+        if let Some(cell) = registry::where_is(SourceActor::name()) {
+            let actor: ActorRef<SourceMsg> = cell.into();
+            call_t!(actor, SourceMsg::GetStageLatencies, 100).unwrap_or_default()
+        } else {
+            vec![]
+        }
+    }
+
+    /// Spoken language most recently identified from the session audio, if language ID is on.
+    #[tracing::instrument(skip_all)]
+    pub async fn get_detected_language(&self) -> Option<hypr_language::Language> {
+        let actor: ActorRef<SourceMsg> = registry::where_is(SourceActor::name())?.into();
+        call_t!(actor, SourceMsg::GetDetectedLanguage, 100)
+            .ok()
+            .flatten()
+    }
+
     #[tracing::instrument(skip_all)]
     pub async fn set_mic_muted(&self, muted: bool) {
         if let Some(cell) = registry::where_is(SourceActor::name()) {
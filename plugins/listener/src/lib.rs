@@ -35,6 +35,8 @@ fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
             commands::start_session::<tauri::Wry>,
             commands::stop_session::<tauri::Wry>,
             commands::get_state::<tauri::Wry>,
+            commands::get_supervision_tree::<tauri::Wry>,
+            commands::get_pipeline_latencies::<tauri::Wry>,
             commands::is_supported_languages_live::<tauri::Wry>,
             commands::suggest_providers_for_languages_live::<tauri::Wry>,
             commands::list_documented_language_codes_live::<tauri::Wry>,
@@ -10,6 +10,7 @@ const COMMANDS: &[&str] = &[
     "stop_server",
     "get_server_for_model",
     "get_servers",
+    "get_supervision_tree",
     "list_supported_models",
     "list_supported_languages",
 ];
@@ -72,6 +72,14 @@ export const commands = {
       else return { status: "error", error: e as any, };
     }
   },
+  async getSupervisionTree(): Promise<Result<SupervisorTree, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("plugin:local-stt|get_supervision_tree",), };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any, };
+    }
+  },
   async startServer(model: SupportedSttModel,): Promise<Result<string, string>> {
     try {
       return { status: "ok", data: await TAURI_INVOKE("plugin:local-stt|start_server", { model, },), };
@@ -119,7 +127,26 @@ export type CactusSttModel =
   | "cactus-whisper-medium-int4-apple"
   | "cactus-whisper-medium-int8"
   | "cactus-whisper-medium-int8-apple";
+export type ChildInfo = {
+  id: string;
+  policy: RestartPolicy;
+  status: ChildStatus;
+  /**
+   * Restarts since the supervisor started, not just within the current budget window.
+   */
+  restart_count: number;
+  last_exit_reason: string | null;
+  uptime_ms: number | null;
+};
+export type ChildStatus =
+  | "running"
+  /**
+   * Exited and waiting to be spawned again.
+   */
+  | "restarting"
+  | "stopped";
 export type DownloadProgressPayload = { model: SupportedSttModel; progress: number; };
+export type RestartPolicy = "permanent" | "transient" | "temporary";
 export type ServerInfo = { url: string | null; status: ServerStatus; model: SupportedSttModel | null; };
 export type ServerStatus = "unreachable" | "loading" | "ready";
 export type ServerType = "internal" | "external";
@@ -131,6 +158,10 @@ export type SttModelInfo = {
   model_type: SttModelType;
 };
 export type SttModelType = "cactus" | "whispercpp" | "argmax";
+/**
+ * A supervisor and its children, as shown in the diagnostics view.
+ */
+export type SupervisorTree = { name: string; children: ChildInfo[]; };
 export type SupportedSttModel = CactusSttModel | WhisperModel | AmModel;
 export type WhisperModel =
   | "QuantizedTiny"
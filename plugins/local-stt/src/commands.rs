@@ -133,3 +133,14 @@ pub async fn get_servers<R: tauri::Runtime>(
         .await
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_supervision_tree<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<hypr_supervisor::SupervisorTree, String> {
+    app.local_stt()
+        .get_supervision_tree()
+        .await
+        .map_err(|e| e.to_string())
+}
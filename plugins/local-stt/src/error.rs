@@ -20,6 +20,8 @@ pub enum Error {
     ServerStopFailed(String),
     #[error("Supervisor not found")]
     SupervisorNotFound,
+    #[error("Supervisor call failed: {0}")]
+    SupervisorCallFailed(String),
     #[error("AM API key not set")]
     AmApiKeyNotSet,
     #[error("Internal server only supports Whisper models")]
@@ -28,6 +30,11 @@ pub enum Error {
     ModelDeleteFailed(String),
     #[error("Model unpack failed: {0}")]
     ModelUnpackFailed(String),
+    #[error("Spoken language detection requires the whisper-cpp feature")]
+    LanguageIdUnavailable,
+    #[cfg(feature = "whisper-cpp")]
+    #[error(transparent)]
+    WhisperError(#[from] hypr_whisper_local::Error),
 }
 
 impl Serialize for Error {
@@ -54,6 +54,11 @@ impl<'a, R: Runtime, M: Manager<R>> LocalStt<'a, R, M> {
             })
     }
 
+    /// Model used for spoken-language identification: the cheapest downloaded multilingual whisper.
+    pub fn language_id_model_path(&self) -> Option<PathBuf> {
+        crate::language_id_model_path(&self.models_dir())
+    }
+
     pub async fn get_supervisor(&self) -> Result<supervisor::SupervisorRef, crate::Error> {
         let state = self.manager.state::<crate::SharedState>();
         let guard = state.lock().await;
@@ -202,6 +207,21 @@ impl<'a, R: Runtime, M: Manager<R>> LocalStt<'a, R, M> {
         Ok(info)
     }
 
+    #[tracing::instrument(skip_all)]
+    pub async fn get_supervision_tree(
+        &self,
+    ) -> Result<hypr_supervisor::SupervisorTree, crate::Error> {
+        let supervisor = self.get_supervisor().await?;
+        let children = hypr_supervisor::dynamic::DynamicSupervisor::which_children(supervisor)
+            .await
+            .map_err(|e| crate::Error::SupervisorCallFailed(e.to_string()))?;
+
+        Ok(hypr_supervisor::SupervisorTree {
+            name: supervisor::SUPERVISOR_NAME.to_string(),
+            children,
+        })
+    }
+
     #[tracing::instrument(skip_all)]
     pub async fn get_servers(&self) -> Result<HashMap<ServerType, ServerInfo>, crate::Error> {
         #[cfg(target_arch = "aarch64")]
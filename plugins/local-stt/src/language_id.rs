@@ -0,0 +1,63 @@
+use std::path::{Path, PathBuf};
+
+use hypr_whisper_local_model::WhisperModel;
+
+/// Multilingual whisper models that can run the language-detection pass, cheapest first.
+const LANGUAGE_ID_MODELS: [WhisperModel; 4] = [
+    WhisperModel::QuantizedTiny,
+    WhisperModel::QuantizedBase,
+    WhisperModel::QuantizedSmall,
+    WhisperModel::QuantizedLargeTurbo,
+];
+
+/// The cheapest downloaded multilingual whisper model in `models_dir`, if any.
+pub fn language_id_model_path(models_dir: &Path) -> Option<PathBuf> {
+    LANGUAGE_ID_MODELS
+        .iter()
+        .map(|model| models_dir.join(model.file_name()))
+        .find(|path| path.exists())
+}
+
+/// Identifies the spoken language of raw 16 kHz mono audio with a local whisper model.
+pub struct SpokenLanguageDetector {
+    #[cfg(feature = "whisper-cpp")]
+    whisper: hypr_whisper_local::Whisper,
+}
+
+impl SpokenLanguageDetector {
+    #[cfg(feature = "whisper-cpp")]
+    pub fn load(model_path: &Path) -> Result<Self, crate::Error> {
+        let whisper = hypr_whisper_local::Whisper::builder()
+            .model_path(model_path.to_string_lossy())
+            .build()?;
+        Ok(Self { whisper })
+    }
+
+    #[cfg(not(feature = "whisper-cpp"))]
+    pub fn load(_model_path: &Path) -> Result<Self, crate::Error> {
+        Err(crate::Error::LanguageIdUnavailable)
+    }
+
+    /// Languages ranked by probability, most likely first. Whisper languages without an ISO 639-1
+    /// code are dropped.
+    #[cfg(feature = "whisper-cpp")]
+    pub fn detect(
+        &mut self,
+        audio: &[f32],
+    ) -> Result<Vec<(hypr_language::Language, f32)>, crate::Error> {
+        Ok(self
+            .whisper
+            .detect_language(audio)?
+            .into_iter()
+            .filter_map(|(lang, prob)| lang.try_into().ok().map(|lang| (lang, prob)))
+            .collect())
+    }
+
+    #[cfg(not(feature = "whisper-cpp"))]
+    pub fn detect(
+        &mut self,
+        _audio: &[f32],
+    ) -> Result<Vec<(hypr_language::Language, f32)>, crate::Error> {
+        Err(crate::Error::LanguageIdUnavailable)
+    }
+}
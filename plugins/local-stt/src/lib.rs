@@ -9,12 +9,14 @@ use tokio_util::sync::CancellationToken;
 mod commands;
 mod error;
 mod ext;
+mod language_id;
 mod model;
 mod server;
 mod types;
 
 pub use error::*;
 pub use ext::*;
+pub use language_id::*;
 pub use model::*;
 pub use server::supervisor::{SUPERVISOR_NAME, SupervisorRef};
 pub use server::*;
@@ -50,6 +52,7 @@ fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
             commands::delete_model::<Wry>,
             commands::get_server_for_model::<Wry>,
             commands::get_servers::<Wry>,
+            commands::get_supervision_tree::<Wry>,
             commands::start_server::<Wry>,
             commands::stop_server::<Wry>,
             commands::list_supported_models,
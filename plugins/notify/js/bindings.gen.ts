@@ -10,8 +10,10 @@ export const commands = {};
 
 export const events = __makeEvents__<{
   fileChanged: FileChanged;
+  ownFileChanged: OwnFileChanged;
 }>({
   fileChanged: "plugin:notify:file-changed",
+  ownFileChanged: "plugin:notify:own-file-changed",
 },);
 
 /** user-defined constants **/
@@ -19,6 +21,7 @@ export const events = __makeEvents__<{
 /** user-defined types **/
 
 export type FileChanged = { path: string; };
+export type OwnFileChanged = { path: string; };
 
 /** tauri-specta globals **/
 
@@ -8,6 +8,8 @@ pub enum Error {
     Tauri(#[from] tauri::Error),
     #[error(transparent)]
     Settings(#[from] tauri_plugin_settings::Error),
+    #[error("already watching {}", .0.display())]
+    AlreadyWatching(std::path::PathBuf),
 }
 
 impl Serialize for Error {
@@ -13,3 +13,11 @@ common_event_derives! {
         pub path: String,
     }
 }
+
+common_event_derives! {
+    /// A change the app made itself via `mark_own_writes`. Kept apart from `FileChanged` so
+    /// listeners that reload on external edits don't see their own echoes.
+    pub struct OwnFileChanged {
+        pub path: String,
+    }
+}
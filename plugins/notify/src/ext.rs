@@ -1,12 +1,13 @@
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use notify::RecursiveMode;
-use notify_debouncer_full::{DebouncedEvent, new_debouncer};
+use notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_full::{DebouncedEvent, Debouncer, RecommendedCache, new_debouncer};
 use tauri_plugin_settings::SettingsPluginExt;
 use tauri_specta::Event;
 
 use crate::path::{should_skip_path, to_relative_path};
-use crate::{FileChanged, WatcherState};
+use crate::{FileChanged, OwnFileChanged, WatcherState};
 
 const DEBOUNCE_DELAY_MS: u64 = 900;
 const OWN_WRITES_TTL_MS: u128 = (DEBOUNCE_DELAY_MS as u128) * 2 + 200;
@@ -31,6 +32,44 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Notify<'a, R, M> {
             .settings()
             .cached_vault_base()?
             .into_std_path_buf();
+        *guard = Some((base.clone(), self.watch(base)?));
+
+        Ok(())
+    }
+
+    /// Watches `base`, for apps whose vault isn't the settings vault base. Event paths are
+    /// relative to `base`. A watcher already running on another root is left alone and this
+    /// fails, since paths passed to `mark_own_writes` are relative to that root.
+    pub fn start_at(&self, base: PathBuf) -> Result<(), crate::Error> {
+        let state = self.manager.state::<WatcherState>();
+        let mut guard = state.debouncer.lock().unwrap();
+
+        match guard.as_ref() {
+            Some((root, _)) if *root == base => return Ok(()),
+            Some((root, _)) => return Err(crate::Error::AlreadyWatching(root.clone())),
+            None => {}
+        }
+
+        *guard = Some((base.clone(), self.watch(base)?));
+
+        Ok(())
+    }
+
+    /// Stops the watcher if it is the one `start_at(base)` started.
+    pub fn stop_at(&self, base: &Path) -> Result<(), crate::Error> {
+        let state = self.manager.state::<WatcherState>();
+        let mut guard = state.debouncer.lock().unwrap();
+        if guard.as_ref().is_some_and(|(root, _)| root == base) {
+            *guard = None;
+        }
+        Ok(())
+    }
+
+    fn watch(
+        &self,
+        base: PathBuf,
+    ) -> Result<Debouncer<RecommendedWatcher, RecommendedCache>, crate::Error> {
+        let state = self.manager.state::<WatcherState>();
         let app_handle = self.manager.app_handle().clone();
         let base_for_closure = base.clone();
         let own_writes = state.own_writes.clone();
@@ -87,6 +126,7 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Notify<'a, R, M> {
                             own.contains_key(&path)
                         };
                         if skip {
+                            let _ = OwnFileChanged { path }.emit(&app_handle);
                             continue;
                         }
                         tracing::info!("file_changed: {:?}", path);
@@ -97,9 +137,8 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Notify<'a, R, M> {
         )?;
 
         debouncer.watch(&base, RecursiveMode::Recursive)?;
-        *guard = Some(debouncer);
 
-        Ok(())
+        Ok(debouncer)
     }
 
     pub fn stop(&self) -> Result<(), crate::Error> {
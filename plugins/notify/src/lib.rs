@@ -4,6 +4,7 @@ mod ext;
 mod path;
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
@@ -17,18 +18,39 @@ pub use ext::*;
 
 const PLUGIN_NAME: &str = "notify";
 
+/// A running watcher and the root its event paths are relative to.
+pub(crate) type RootedDebouncer = (PathBuf, Debouncer<RecommendedWatcher, RecommendedCache>);
+
 pub struct WatcherState {
-    pub(crate) debouncer: Mutex<Option<Debouncer<RecommendedWatcher, RecommendedCache>>>,
+    pub(crate) debouncer: Mutex<Option<RootedDebouncer>>,
     pub(crate) own_writes: Arc<Mutex<HashMap<String, Instant>>>,
 }
 
 fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
     tauri_specta::Builder::<R>::new()
         .plugin_name(PLUGIN_NAME)
-        .events(tauri_specta::collect_events![FileChanged,])
+        .events(tauri_specta::collect_events![FileChanged, OwnFileChanged,])
+}
+
+pub struct InitOptions {
+    /// Start watching the settings vault base once the webview is ready. Apps that only watch
+    /// a root of their own through `start_at` turn this off.
+    pub watch_vault_base: bool,
+}
+
+impl Default for InitOptions {
+    fn default() -> Self {
+        Self {
+            watch_vault_base: true,
+        }
+    }
 }
 
 pub fn init() -> tauri::plugin::TauriPlugin<tauri::Wry> {
+    init_with(InitOptions::default())
+}
+
+pub fn init_with(options: InitOptions) -> tauri::plugin::TauriPlugin<tauri::Wry> {
     let specta_builder = make_specta_builder();
 
     tauri::plugin::Builder::new(PLUGIN_NAME)
@@ -43,7 +65,10 @@ pub fn init() -> tauri::plugin::TauriPlugin<tauri::Wry> {
 
             Ok(())
         })
-        .on_webview_ready(|webview| {
+        .on_webview_ready(move |webview| {
+            if !options.watch_vault_base {
+                return;
+            }
             if let Err(e) = webview.app_handle().notify().start() {
                 tracing::error!("failed_to_start_watcher: {}", e);
             }
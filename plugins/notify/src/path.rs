@@ -30,7 +30,14 @@ pub fn should_skip_path(relative_path: &str, path: &Path) -> bool {
 
     if path
         .extension()
-        .is_some_and(|ext| ext == "wav" || ext == "ogg" || ext == "tmp")
+        .is_some_and(|ext| ext == "wav" || ext == "ogg" || ext == "opus" || ext == "tmp")
+    {
+        return true;
+    }
+
+    if path
+        .components()
+        .any(|component| component.as_os_str() == "audio.segments")
     {
         return true;
     }
@@ -96,6 +103,18 @@ mod tests {
         assert!(should_skip_path("audio/recording.ogg", &path));
     }
 
+    #[test]
+    fn test_skip_opus_recording_segments() {
+        let path = PathBuf::from("/vault/sessions/abc/audio.opus");
+        assert!(should_skip_path("sessions/abc/audio.opus", &path));
+
+        let path = PathBuf::from("/vault/sessions/abc/audio.segments/manifest.json");
+        assert!(should_skip_path(
+            "sessions/abc/audio.segments/manifest.json",
+            &path
+        ));
+    }
+
     #[test]
     fn test_skip_tmp_extension() {
         let path = PathBuf::from("/vault/temp/file.tmp");